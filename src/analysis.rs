@@ -3,14 +3,24 @@
 
 #![deny(missing_docs)]
 
+use crate::dynamic_analysis::common::{BoundCandidate, OwnedBoundCandidate};
 use crate::error::TraitError;
+use crate::target::TargetType;
+use crate::type_path::{self, TypePath, TypeRoot};
 use syn::{
-    Ident, ImplItemFn, Item, ItemEnum, ItemFn, ItemImpl, ItemStruct, ItemTrait, Path as SynPath,
-    TraitItemFn, Type, TypeParamBound, punctuated::Punctuated, token::Plus, visit::Visit,
+    FnArg, Ident, ImplItemFn, Item, ItemEnum, ItemFn, ItemImpl, ItemStruct, ItemTrait, Pat,
+    Path as SynPath, TraitItemFn, Type, TypeParamBound,
+    punctuated::Punctuated,
+    token::{Comma, Plus},
+    visit::Visit,
 };
 
 use paste::paste;
 use proc_macro2::Span;
+use quote::ToTokens;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
 
 /// Reference to a Rust item in the AST.
 pub enum ItemRef<'ast> {
@@ -42,38 +52,169 @@ pub enum ItemRef<'ast> {
     },
 }
 
-/// A lightweight identity/label for an inspected item.
-pub struct ItemKey<'ast> {
-    item: ItemRef<'ast>,
-    label: String,
-    span: Span,
-}
+impl<'ast> ItemRef<'ast> {
+    /// The generics (parameters and where clause) declared on the item.
+    pub fn generics(&self) -> &'ast syn::Generics {
+        match self {
+            ItemRef::Func(f) => &f.sig.generics,
+            ItemRef::Struct(s) => &s.generics,
+            ItemRef::Enum(e) => &e.generics,
+            ItemRef::Trait(t) => &t.generics,
+            ItemRef::Impl(i) => &i.generics,
+            ItemRef::ImplMethod { method, .. } => &method.sig.generics,
+            ItemRef::TraitMethod { method, .. } => &method.sig.generics,
+        }
+    }
 
-/// Generate label-formatting helpers on `ItemKey`.
-/// Each arm is `$fn_name: (args...) => "fmt";` and returns `String`.
-macro_rules! define_item_labels {
-    ( $( $fn_name:ident ( $($arg:ident),* ) => $fmt:expr ; )* ) => {
-        impl<'ast> ItemKey<'ast> {
-            $(
-                #[allow(missing_docs, reason = "macro-generated code")]
-                #[inline]
-                pub fn $fn_name ( $( $arg: &str ),* ) -> String {
-                    format!($fmt, $( $arg ),*)
+    /// The arguments of functions and methods, where `impl Trait` bounds live; `None` for
+    /// other items.
+    pub fn inputs(&self) -> Option<&'ast Punctuated<FnArg, Comma>> {
+        self.sig().map(|sig| &sig.inputs)
+    }
+
+    /// The declared type at `root` (see [`TypePath`]): the return type or an argument's of
+    /// functions and methods, a field's of structs.
+    pub fn type_root(&self, root: TypeRoot) -> Option<&'ast Type> {
+        match (root, self) {
+            (TypeRoot::Field(i), ItemRef::Struct(s)) => s.fields.iter().nth(i).map(|f| &f.ty),
+            (TypeRoot::Return, _) => type_path::return_type(&self.sig()?.output),
+            (TypeRoot::Arg(i), _) => match self.inputs()?.iter().nth(i)? {
+                FnArg::Typed(pt) => Some(&pt.ty),
+                FnArg::Receiver(_) => None,
+            },
+            _ => None,
+        }
+    }
+
+    /// The signature of functions and methods; `None` for other items.
+    pub fn sig(&self) -> Option<&'ast syn::Signature> {
+        match self {
+            ItemRef::Func(f) => Some(&f.sig),
+            ItemRef::ImplMethod { method, .. } => Some(&method.sig),
+            ItemRef::TraitMethod { method, .. } => Some(&method.sig),
+            _ => None,
+        }
+    }
+
+    /// Token rendering of the item's signature: the full `fn` signature for functions and
+    /// methods, the generics and where clause for everything else.
+    pub fn signature(&self) -> String {
+        match self {
+            ItemRef::Func(f) => f.sig.to_token_stream().to_string(),
+            ItemRef::ImplMethod { method, .. } => method.sig.to_token_stream().to_string(),
+            ItemRef::TraitMethod { method, .. } => method.sig.to_token_stream().to_string(),
+            _ => render_generics(self.generics()),
+        }
+    }
+
+    /// The outer attributes written on the item itself.
+    pub fn attrs(&self) -> &'ast [syn::Attribute] {
+        match self {
+            ItemRef::Func(f) => &f.attrs,
+            ItemRef::Struct(s) => &s.attrs,
+            ItemRef::Enum(e) => &e.attrs,
+            ItemRef::Trait(t) => &t.attrs,
+            ItemRef::Impl(i) => &i.attrs,
+            ItemRef::ImplMethod { method, .. } => &method.attrs,
+            ItemRef::TraitMethod { method, .. } => &method.attrs,
+        }
+    }
+
+    /// Traits a struct or enum derives, by the last segment of their path (`serde::Serialize`
+    /// is `Serialize`), in attribute order. Derived impls copy the type's where clause, so
+    /// its bounds may be load-bearing for code `cargo check` only sees when it is used.
+    /// Empty for other items.
+    pub fn derives(&self) -> Vec<String> {
+        if !matches!(self, ItemRef::Struct(_) | ItemRef::Enum(_)) {
+            return Vec::new();
+        }
+        let mut out = Vec::new();
+        for attr in self.attrs().iter().filter(|a| a.path().is_ident("derive")) {
+            let Ok(paths) =
+                attr.parse_args_with(Punctuated::<SynPath, syn::Token![,]>::parse_terminated)
+            else {
+                continue;
+            };
+            out.extend(
+                paths
+                    .iter()
+                    .filter_map(|p| p.segments.last().map(|s| s.ident.to_string())),
+            );
+        }
+        out
+    }
+
+    /// Whether the item is a blanket impl: its self type is one of its own type parameters,
+    /// bare or behind `&`, `&mut` or `Box` (`impl<T: Display> Tr for &T`). Its bounds
+    /// decide which types it covers, so loosening them can make it overlap impls elsewhere.
+    pub fn is_blanket_impl(&self) -> bool {
+        let ItemRef::Impl(im) = self else {
+            return false;
+        };
+        let mut ty = &*im.self_ty;
+        loop {
+            ty = match ty {
+                Type::Reference(r) => &r.elem,
+                Type::Paren(p) => &p.elem,
+                Type::Path(p) if p.qself.is_none() => {
+                    if let Some(ident) = p.path.get_ident() {
+                        return im.generics.type_params().any(|tp| tp.ident == *ident);
+                    }
+                    let Some(last) = p.path.segments.last().filter(|s| s.ident == "Box") else {
+                        return false;
+                    };
+                    match &last.arguments {
+                        syn::PathArguments::AngleBracketed(a) if a.args.len() == 1 => {
+                            match &a.args[0] {
+                                syn::GenericArgument::Type(inner) => inner,
+                                _ => return false,
+                            }
+                        }
+                        _ => return false,
+                    }
                 }
-            )*
+                _ => return false,
+            };
         }
-    };
+    }
+
+    /// Whether the item may be part of the crate's public API, judging by its own
+    /// declaration: `pub` items and methods, and every trait method and impl (their trait
+    /// or type may be public). Enclosing modules are not looked at, so this errs towards
+    /// `true`.
+    pub fn is_public(&self) -> bool {
+        let public = |vis: &syn::Visibility| matches!(vis, syn::Visibility::Public(_));
+        match self {
+            ItemRef::Func(f) => public(&f.vis),
+            ItemRef::Struct(s) => public(&s.vis),
+            ItemRef::Enum(e) => public(&e.vis),
+            ItemRef::Trait(t) => public(&t.vis),
+            ItemRef::Impl(_) | ItemRef::TraitMethod { .. } => true,
+            ItemRef::ImplMethod {
+                trait_path, method, ..
+            } => trait_path.is_some() || public(&method.vis),
+        }
+    }
+}
+
+/// Render generic parameters followed by the where clause, if any.
+pub(crate) fn render_generics(g: &syn::Generics) -> String {
+    let params = g.to_token_stream().to_string();
+    match &g.where_clause {
+        Some(wc) => format!("{params} {}", wc.to_token_stream()),
+        None => params,
+    }
 }
 
-define_item_labels! {
-    fn_label            (name)                => "// fn {}";
-    struct_label        (name)                => "// struct {}";
-    enum_label          (name)                => "// enum {}";
-    trait_label         (name)                => "// trait {}";
-    impl_inherent_label (self_ty)             => "// impl {}";
-    impl_trait_label    (trait_path, self_ty) => "// impl {} for {}";
-    impl_method_label   (owner, method)       => "// {}::{}";
-    trait_method_label  (trait_name, method)  => "// trait {}::{}";
+/// A lightweight identity/label for an inspected item.
+pub struct ItemKey<'ast> {
+    item: ItemRef<'ast>,
+    span: Span,
+    path: Vec<usize>,
+    module: String,
+    ordinal: Option<usize>,
+    visibility: ItemVisibility,
+    cfg: Vec<syn::Meta>,
 }
 
 impl<'ast> ItemKey<'ast> {
@@ -95,6 +236,131 @@ impl<'ast> ItemKey<'ast> {
         self.span
     }
 
+    /// Where the item sits in its file's tree: its index among the items, impl items or
+    /// trait items around it, after those of the items enclosing it, e.g. `[2, 0]` for the
+    /// first method of the third item. Unlike its span, the same in any parse of the text.
+    #[inline]
+    pub fn path(&self) -> &[usize] {
+        &self.path
+    }
+
+    /// Path of the inline modules enclosing the item within its file, e.g. `outer::inner`;
+    /// empty at file level.
+    #[inline]
+    pub fn module_path(&self) -> &str {
+        &self.module
+    }
+
+    /// How many items of the same kind and ident (none for impls) come before the item in
+    /// its [`module_path`](Self::module_path), whether they have bounds or not: what finds
+    /// it again when its [`path`](Self::path) and span no longer do. `None` within a
+    /// function body, which edits do not reach.
+    #[inline]
+    pub fn ordinal(&self) -> Option<usize> {
+        self.ordinal
+    }
+
+    /// How far the item is visible, capped by the inline modules enclosing it within its file
+    /// (see [`ItemVisibility`]).
+    #[inline]
+    pub fn visibility(&self) -> ItemVisibility {
+        self.visibility
+    }
+
+    /// The outer attributes written on the item itself, doc comments included.
+    #[inline]
+    pub fn attrs(&self) -> &'ast [syn::Attribute] {
+        self.item.attrs()
+    }
+
+    /// The `#[cfg]` predicates the item is compiled under, outermost first: the file's, its
+    /// enclosing modules', its impl block's or trait's, and its own. Empty when it always is.
+    #[inline]
+    pub fn cfg(&self) -> &[syn::Meta] {
+        &self.cfg
+    }
+
+    /// The impl block the item is or is in, if it is an impl or an impl method.
+    pub fn impl_header(&self) -> Option<ImplHeader> {
+        let (self_ty, trait_path) = match self.item {
+            ItemRef::Impl(im) => (&*im.self_ty, im.trait_.as_ref().map(|(_, p, _)| p)),
+            ItemRef::ImplMethod {
+                self_ty,
+                trait_path,
+                ..
+            } => (self_ty, trait_path),
+            _ => return None,
+        };
+        Some(ImplHeader {
+            self_ty: self_ty.to_token_stream().to_string(),
+            trait_path: trait_path.map(|p| p.to_token_stream().to_string()),
+        })
+    }
+
+    /// The item's name as [`ItemLocator::name`] has it: its ident, or for impls and methods
+    /// the impl or trait it is in, e.g. `Display for Wrapper < T >::fmt`.
+    pub fn name(&self) -> String {
+        let owner = || self.impl_header().map(|h| h.owner()).unwrap_or_default();
+        match self.item {
+            ItemRef::Impl(_) => owner(),
+            ItemRef::ImplMethod { method, .. } => format!("{}::{}", owner(), method.sig.ident),
+            ItemRef::TraitMethod {
+                trait_ident,
+                method,
+            } => format!("{trait_ident}::{}", method.sig.ident),
+            _ => self
+                .ident_opt()
+                .map(ToString::to_string)
+                .unwrap_or_default(),
+        }
+    }
+
+    /// The item's label, e.g. `// fn foo`: its [`kind`](Self::kind) and [`name`](Self::name)
+    /// through [`ItemKind::label`], without the module path.
+    pub fn label(&self) -> String {
+        self.kind().label(&self.name())
+    }
+
+    /// Where a bound of the item is taken to behave alike (see
+    /// [`RejectionMemo`](crate::dynamic_analysis::edit::RejectionMemo)): the impl or trait of
+    /// a method, else the item itself, as its label within [`module_path`](Self::module_path).
+    pub fn scope(&self) -> String {
+        let label = match self.item {
+            ItemRef::ImplMethod { .. } => {
+                ItemKind::Impl.label(&self.impl_header().map(|h| h.owner()).unwrap_or_default())
+            }
+            ItemRef::TraitMethod { trait_ident, .. } => {
+                ItemKind::Trait.label(&trait_ident.to_string())
+            }
+            _ => self.label(),
+        };
+        self.in_module(&label)
+    }
+
+    /// `label` without its leading `// `, after the module path if there is one, e.g.
+    /// `outer::inner fn foo`.
+    fn in_module(&self, label: &str) -> String {
+        let label = label.trim_start_matches("// ");
+        if self.module.is_empty() {
+            label.to_owned()
+        } else {
+            format!("{} {label}", self.module)
+        }
+    }
+
+    /// Kind of the item.
+    pub fn kind(&self) -> ItemKind {
+        match self.item {
+            ItemRef::Func(_) => ItemKind::Fn,
+            ItemRef::Struct(_) => ItemKind::Struct,
+            ItemRef::Enum(_) => ItemKind::Enum,
+            ItemRef::Trait(_) => ItemKind::Trait,
+            ItemRef::Impl(_) => ItemKind::Impl,
+            ItemRef::ImplMethod { .. } => ItemKind::ImplMethod,
+            ItemRef::TraitMethod { .. } => ItemKind::TraitMethod,
+        }
+    }
+
     #[inline]
     fn ident_opt(&self) -> Option<&'ast syn::Ident> {
         match self.item {
@@ -109,9 +375,223 @@ impl<'ast> ItemKey<'ast> {
     }
 }
 
+/// Kind of an inspected item.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ItemKind {
+    /// A free-standing function.
+    #[default]
+    Fn,
+    /// A struct definition.
+    Struct,
+    /// An enum definition.
+    Enum,
+    /// A trait definition.
+    Trait,
+    /// An impl block.
+    Impl,
+    /// A method in an impl block.
+    ImplMethod,
+    /// A method in a trait definition.
+    TraitMethod,
+    /// A type alias. Not collected yet: the compiler does not enforce its bounds.
+    TypeAlias,
+    /// A union definition. Not collected yet.
+    Union,
+    /// An associated type of a trait. Not collected yet.
+    AssocType,
+}
+
+impl ItemKind {
+    /// The `--target-type` selecting items of this kind, if there is one.
+    pub fn target_type(self) -> Option<TargetType> {
+        Some(match self {
+            ItemKind::Fn => TargetType::Function,
+            ItemKind::Struct => TargetType::Struct,
+            ItemKind::Enum => TargetType::Enum,
+            ItemKind::Trait => TargetType::Trait,
+            ItemKind::Impl => TargetType::Impl,
+            ItemKind::ImplMethod => TargetType::ImplMethod,
+            ItemKind::TraitMethod => TargetType::TraitMethod,
+            ItemKind::TypeAlias | ItemKind::Union | ItemKind::AssocType => return None,
+        })
+    }
+
+    /// The kind as serialized, e.g. `impl_method`.
+    pub fn id(self) -> &'static str {
+        match self {
+            ItemKind::Fn => "fn",
+            ItemKind::Struct => "struct",
+            ItemKind::Enum => "enum",
+            ItemKind::Trait => "trait",
+            ItemKind::Impl => "impl",
+            ItemKind::ImplMethod => "impl_method",
+            ItemKind::TraitMethod => "trait_method",
+            ItemKind::TypeAlias => "type_alias",
+            ItemKind::Union => "union",
+            ItemKind::AssocType => "assoc_type",
+        }
+    }
+
+    /// The label of the item of this kind called `name` (see [`ItemLocator::name`]), e.g.
+    /// `// fn foo`, `// impl Display for Wrapper < T >`, `// Wrapper < T >::id`.
+    pub fn label(self, name: &str) -> String {
+        let keyword = match self {
+            ItemKind::Fn => "fn ",
+            ItemKind::Struct => "struct ",
+            ItemKind::Enum => "enum ",
+            ItemKind::Trait | ItemKind::TraitMethod => "trait ",
+            ItemKind::Impl => "impl ",
+            ItemKind::ImplMethod => "",
+            ItemKind::TypeAlias | ItemKind::AssocType => "type ",
+            ItemKind::Union => "union ",
+        };
+        format!("// {keyword}{name}")
+    }
+}
+
+/// The impl block an impl or impl method is in.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ImplHeader {
+    /// The implementing type, as tokens, e.g. `Wrapper < T >`.
+    pub self_ty: String,
+    /// The implemented trait, as tokens, e.g. `Display`; `None` for an inherent impl.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub trait_path: Option<String>,
+}
+
+impl ImplHeader {
+    /// How names and labels call the impl: `Display for Wrapper < T >`, or the type alone.
+    pub fn owner(&self) -> String {
+        match &self.trait_path {
+            Some(tp) => format!("{tp} for {}", self.self_ty),
+            None => self.self_ty.clone(),
+        }
+    }
+}
+
+/// How far an item is visible. Ordered from least to most visible, so the visibility of an
+/// item in a module is the lesser of its own and the module's.
+#[derive(
+    Debug, Clone, Copy, Default, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize,
+)]
+#[serde(rename_all = "snake_case")]
+pub enum ItemVisibility {
+    /// Private to its module.
+    Private,
+    /// Visible within the crate only: `pub(crate)`, `pub(super)`, `pub(in path)`.
+    Crate,
+    /// `pub`; also impl blocks and the methods of trait impls, which have no visibility of
+    /// their own. What [`ItemLocator`]s written without one read as.
+    #[default]
+    Public,
+}
+
+impl ItemVisibility {
+    /// The visibility `vis` declares.
+    pub fn of(vis: &syn::Visibility) -> Self {
+        match vis {
+            syn::Visibility::Public(_) => ItemVisibility::Public,
+            syn::Visibility::Restricted(_) => ItemVisibility::Crate,
+            syn::Visibility::Inherited => ItemVisibility::Private,
+        }
+    }
+}
+
+impl std::fmt::Display for ItemVisibility {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(match self {
+            ItemVisibility::Private => "private",
+            ItemVisibility::Crate => "pub(crate)",
+            ItemVisibility::Public => "pub",
+        })
+    }
+}
+
+/// Owned, serializable identity of an item: where it is and what it is called.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ItemLocator {
+    /// File containing the item; empty until set with [`ItemLocator::in_file`].
+    pub file: PathBuf,
+    /// Module path of the item, e.g. `a::inner`; empty at the crate root.
+    pub module_path: String,
+    /// Kind of item.
+    pub kind: ItemKind,
+    /// Name as labeled, without the kind keyword, e.g. `foo`, `Wrapper < T >::id`.
+    pub name: String,
+    /// The impl block of an impl or impl method, which `name` spells out.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub impl_header: Option<ImplHeader>,
+    /// 1-based line of the item's anchor (its name, or `impl`).
+    pub line: usize,
+    /// 1-based column (in chars) of the item's anchor.
+    pub column: usize,
+    /// How far the item is visible (see [`ItemKey::visibility`]).
+    #[serde(default)]
+    pub visibility: ItemVisibility,
+    /// The item's own outer attributes other than doc comments, as tokens, e.g.
+    /// `# [inline]`.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub attrs: Vec<String>,
+}
+
+impl ItemLocator {
+    /// The item's label, e.g. `// fn foo` (see [`ItemKind::label`]).
+    pub fn label(&self) -> String {
+        self.kind.label(&self.name)
+    }
+
+    /// Place the item in `file`, prefixing the module path implied by the file's
+    /// location under `src/` (`src/a/mod.rs` is `a`, `src/lib.rs` the crate root).
+    pub fn in_file(mut self, file: &Path) -> Self {
+        let mut parts: Vec<String> = file
+            .with_extension("")
+            .components()
+            .map(|c| c.as_os_str().to_string_lossy().into_owned())
+            .collect();
+        if let Some(i) = parts.iter().position(|p| p == "src") {
+            parts.drain(..=i);
+        }
+        if matches!(
+            parts.last().map(String::as_str),
+            Some("lib" | "main" | "mod")
+        ) {
+            parts.pop();
+        }
+        if !self.module_path.is_empty() {
+            parts.push(std::mem::take(&mut self.module_path));
+        }
+        self.module_path = parts.join("::");
+        self.file = file.to_path_buf();
+        self
+    }
+}
+
+impl From<&ItemKey<'_>> for ItemLocator {
+    fn from(key: &ItemKey<'_>) -> Self {
+        let start = key.span.start();
+        Self {
+            file: PathBuf::new(),
+            module_path: key.module.clone(),
+            kind: key.kind(),
+            name: key.name(),
+            impl_header: key.impl_header(),
+            line: start.line,
+            column: start.column + 1,
+            visibility: key.visibility,
+            attrs: key
+                .attrs()
+                .iter()
+                .filter(|a| !a.path().is_ident("doc"))
+                .map(|a| a.to_token_stream().to_string())
+                .collect(),
+        }
+    }
+}
+
 impl<'ast> std::fmt::Display for ItemKey<'ast> {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        f.write_str(&self.label)
+        write!(f, "// {}", self.in_module(&self.label()))
     }
 }
 
@@ -123,6 +603,8 @@ macro_rules! define_bounds_types {
                 item: ItemKey<'ast>,
                 type_params: Vec<TypeParamBounds>,
                 where_preds: Vec<WhereTypeBounds>,
+                impl_args: Vec<ImplTraitArgBounds>,
+                opaque: Vec<OpaqueTypeBounds>,
             }
 
             impl<'ast> $name<'ast> {
@@ -132,6 +614,12 @@ macro_rules! define_bounds_types {
                 #[allow(missing_docs, reason = "macro-generated code")]
                 pub fn where_bounds(&self) -> &[WhereTypeBounds] { &self.where_preds }
 
+                #[allow(missing_docs, reason = "macro-generated code")]
+                pub fn impl_trait_arg_bounds(&self) -> &[ImplTraitArgBounds] { &self.impl_args }
+
+                #[allow(missing_docs, reason = "macro-generated code")]
+                pub fn opaque_type_bounds(&self) -> &[OpaqueTypeBounds] { &self.opaque }
+
                 #[allow(missing_docs, reason = "macro-generated code")]
                 pub fn item_key(&self) -> &ItemKey<'ast> { &self.item }
 
@@ -150,6 +638,31 @@ define_bounds_types! {
     StructBounds,
 }
 
+/// The byte order mark some editors put at the start of UTF-8 files.
+pub const UTF8_BOM: &str = "\u{feff}";
+
+/// A source file that is not valid UTF-8.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct NonUtf8Source {
+    /// The offending file.
+    pub path: PathBuf,
+    /// Byte offset of the first invalid sequence.
+    pub valid_up_to: usize,
+}
+
+impl std::fmt::Display for NonUtf8Source {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "{} is not valid UTF-8 (invalid byte at offset {}); re-encode it as UTF-8 to analyze it",
+            self.path.display(),
+            self.valid_up_to
+        )
+    }
+}
+
+impl std::error::Error for NonUtf8Source {}
+
 /// A collection of items found in a file.
 pub struct ItemBounds<'ast> {
     fns: Vec<FnBounds<'ast>>,
@@ -193,11 +706,36 @@ paste! {
     }
 }
 
+macro_rules! push_candidates {
+    ( $out:ident, $items:ident; $( $slice:ident => $collect:ident ),+ $(,)? ) => {
+        $(
+            for b in &$items.$slice {
+                for c in BoundCandidate::$collect(b) {
+                    $out.push((&b.item, c));
+                }
+            }
+        )+
+    };
+}
+
 impl<'ast> ItemBounds<'ast> {
-    /// Parse a file from disk.
+    /// Parse a file from disk. A leading byte order mark is accepted and ignored.
     pub fn parse_file(path: &std::path::Path) -> TraitError<syn::File> {
-        let src = std::fs::read_to_string(path)?;
-        Ok(syn::parse_file(&src)?)
+        let src = Self::read_source(path)?;
+        syn::parse_file(&src).map_err(|e| crate::error::WinnowerError::parse(path, e))
+    }
+
+    /// Read a source file as UTF-8, failing with [`NonUtf8Source`] otherwise.
+    pub fn read_source(path: &std::path::Path) -> TraitError<String> {
+        let bytes =
+            std::fs::read(path).map_err(|e| crate::error::WinnowerError::io("reading", path, e))?;
+        String::from_utf8(bytes).map_err(|e| {
+            NonUtf8Source {
+                path: path.to_path_buf(),
+                valid_up_to: e.utf8_error().valid_up_to(),
+            }
+            .into()
+        })
     }
 
     /// Main entry: parse a file from disk and collect items.
@@ -218,9 +756,95 @@ impl<'ast> ItemBounds<'ast> {
             .chain(self.structs.iter().map(|s| &s.item))
     }
 
+    /// Every prunable bound with the item declaring it, items in
+    /// [`ItemBounds::iter_all_items`] order.
+    pub fn candidates(&self) -> impl Iterator<Item = (&ItemKey<'ast>, BoundCandidate)> {
+        let mut out = Vec::new();
+        push_candidates! { out, self;
+            fns => collect_function_candidates,
+            traits => collect_trait_candidates,
+            impls => collect_impl_candidates,
+            trait_methods => collect_trait_method_candidates,
+            impl_methods => collect_impl_method_candidates,
+            enums => collect_enum_candidates,
+            structs => collect_struct_candidates,
+        }
+        out.into_iter()
+    }
+
+    /// Keep the items of `types`, taken in that order, while the candidates `tried` counts
+    /// of them add up to at most `max`, and drop the rest of those types. `tried` gets an
+    /// item's candidates and says how many would be tried, once filters have had their say.
+    /// Returns how many candidates went, by the same count.
+    pub fn truncate_candidates(
+        &mut self,
+        types: &[TargetType],
+        max: usize,
+        tried: impl Fn(&ItemKey<'ast>, Vec<BoundCandidate>) -> usize,
+    ) -> usize {
+        let mut budget = max;
+        let mut dropped = 0;
+        macro_rules! keep_within {
+            ($list:ident, $collect:ident) => {
+                self.$list.retain(|b| {
+                    let n = tried(&b.item, BoundCandidate::$collect(b));
+                    if n <= budget {
+                        budget -= n;
+                        return true;
+                    }
+                    budget = 0;
+                    dropped += n;
+                    false
+                })
+            };
+        }
+        for ty in types {
+            match ty {
+                TargetType::All => {}
+                TargetType::Function => keep_within!(fns, collect_function_candidates),
+                TargetType::Impl => keep_within!(impls, collect_impl_candidates),
+                TargetType::Trait => keep_within!(traits, collect_trait_candidates),
+                TargetType::TraitMethod => {
+                    keep_within!(trait_methods, collect_trait_method_candidates)
+                }
+                TargetType::ImplMethod => {
+                    keep_within!(impl_methods, collect_impl_method_candidates)
+                }
+                TargetType::Enum => keep_within!(enums, collect_enum_candidates),
+                TargetType::Struct => keep_within!(structs, collect_struct_candidates),
+            }
+        }
+        dropped
+    }
+
+    /// Every prunable bound in owned form. Locators are not yet placed in a file; see
+    /// [`CrateBounds`] for that.
+    pub fn all_candidates(&self) -> impl Iterator<Item = (ItemLocator, OwnedBoundCandidate)> {
+        self.candidates()
+            .map(|(key, c)| (ItemLocator::from(key), OwnedBoundCandidate::from(&c)))
+    }
+
+    /// Like [`ItemBounds::all_candidates`], keeping items of the given target types and
+    /// bounds naming one of `traits` (all bounds when `traits` is empty).
+    pub fn filtered_candidates<'a>(
+        &'a self,
+        types: &'a [TargetType],
+        traits: &'a [String],
+    ) -> impl Iterator<Item = (ItemLocator, OwnedBoundCandidate)> + 'a {
+        self.all_candidates()
+            .filter(move |(loc, c)| selects(types, traits, loc, c))
+    }
+
     fn collect_items_from_src(file: &'ast syn::File) -> TraitError<ItemBounds<'ast>> {
         let mut v = Collector {
             out: ItemBounds::empty(),
+            modules: Vec::new(),
+            path: Vec::new(),
+            next: 0,
+            visibility: ItemVisibility::Public,
+            cfgs: cfg_predicates(&file.attrs).collect(),
+            ordinals: BTreeMap::new(),
+            blocks: 0,
         };
         v.visit_file(file);
         Ok(v.out)
@@ -239,8 +863,123 @@ impl<'ast> ItemBounds<'ast> {
     }
 }
 
+/// Whether a candidate passes a target type and trait filter.
+fn selects(
+    types: &[TargetType],
+    traits: &[String],
+    loc: &ItemLocator,
+    candidate: &OwnedBoundCandidate,
+) -> bool {
+    let kind = loc.kind.target_type();
+    types
+        .iter()
+        .any(|t| *t == TargetType::All || Some(*t) == kind)
+        && (traits.is_empty() || traits.iter().any(|t| names_bound(t, &candidate.bound_text)))
+}
+
+/// Whether `filter` names `bound`: its [`trait_path`] (`std::fmt::Debug`) or the last
+/// segment of it (`Debug`), whatever arguments the bound has, so `Iterator` names
+/// `Iterator<Item = u8>`. A filter with arguments or modifiers of its own, like
+/// `Iterator<Item = u8>` or `?Sized`, names only that bound, whitespace aside.
+pub fn names_bound(filter: &str, bound: &str) -> bool {
+    let compact = |s: &str| s.split_whitespace().collect::<String>();
+    let filter_path = trait_path(filter);
+    if filter_path != compact(filter) {
+        return compact(filter) == compact(bound);
+    }
+    let path = trait_path(bound);
+    filter_path == path || path.rsplit("::").next() == Some(filter_path.as_str())
+}
+
+/// The trait a bound names, by its path without generic arguments, `Fn(..) -> ..`
+/// signature, `?` or `for<..>`: `Iterator<Item = u32>` is `Iterator`, `std::ops::Fn(u8) ->
+/// bool` is `std::ops::Fn`. Bounds are grouped and filtered by it and shown in full.
+/// Lifetimes and anything else that does not parse as a trait bound stay as written.
+pub fn trait_path(bound: &str) -> String {
+    let Ok(b) = syn::parse_str::<syn::TraitBound>(bound) else {
+        return bound.to_owned();
+    };
+    let segments: Vec<String> = b
+        .path
+        .segments
+        .iter()
+        .map(|s| s.ident.to_string())
+        .collect();
+    let lead = if b.path.leading_colon.is_some() {
+        "::"
+    } else {
+        ""
+    };
+    format!("{lead}{}", segments.join("::"))
+}
+
+/// Items collected from several files of a crate, each kept with its file.
+#[derive(Default)]
+pub struct CrateBounds<'ast> {
+    files: Vec<(PathBuf, ItemBounds<'ast>)>,
+}
+
+impl<'ast> CrateBounds<'ast> {
+    /// An empty aggregation.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Add the items collected from `path`.
+    pub fn push(&mut self, path: impl Into<PathBuf>, items: ItemBounds<'ast>) {
+        self.files.push((path.into(), items));
+    }
+
+    /// The files added so far, in insertion order, with their items.
+    pub fn files(&self) -> impl Iterator<Item = (&Path, &ItemBounds<'ast>)> {
+        self.files.iter().map(|(p, items)| (p.as_path(), items))
+    }
+
+    /// Every prunable bound of every file, with locators placed in their file.
+    pub fn all_candidates(&self) -> impl Iterator<Item = (ItemLocator, OwnedBoundCandidate)> {
+        self.files.iter().flat_map(|(path, items)| {
+            items
+                .all_candidates()
+                .map(move |(loc, c)| (loc.in_file(path), c))
+        })
+    }
+
+    /// Like [`CrateBounds::all_candidates`], filtered as in
+    /// [`ItemBounds::filtered_candidates`].
+    pub fn filtered_candidates<'a>(
+        &'a self,
+        types: &'a [TargetType],
+        traits: &'a [String],
+    ) -> impl Iterator<Item = (ItemLocator, OwnedBoundCandidate)> + 'a {
+        self.all_candidates()
+            .filter(move |(loc, c)| selects(types, traits, loc, c))
+    }
+}
+
 struct Collector<'ast> {
     out: ItemBounds<'ast>,
+    modules: Vec<String>,
+    /// [`ItemKey::path`] of the item being visited.
+    path: Vec<usize>,
+    /// Index of the next item among its siblings.
+    next: usize,
+    /// The least visibility among the enclosing inline modules; `Public` at file level, as
+    /// the file's own module is declared elsewhere.
+    visibility: ItemVisibility,
+    /// `#[cfg]` predicates of the file and the enclosing modules.
+    cfgs: Vec<syn::Meta>,
+    /// Items seen so far by module path, kind and ident (see [`ItemKey::ordinal`]).
+    ordinals: BTreeMap<(String, ItemKind, String), usize>,
+    /// How many blocks enclose the item being visited.
+    blocks: usize,
+}
+
+/// The predicates of the `#[cfg(...)]` attributes among `attrs`.
+fn cfg_predicates(attrs: &[syn::Attribute]) -> impl Iterator<Item = syn::Meta> + '_ {
+    attrs
+        .iter()
+        .filter(|a| a.path().is_ident("cfg"))
+        .filter_map(|a| a.parse_args::<syn::Meta>().ok())
 }
 
 /// Where a bound lives on a type parameter in the function's generic list.
@@ -248,6 +987,7 @@ pub struct TypeParamBounds {
     ident: Ident,
     bounds: Punctuated<TypeParamBound, Plus>,
     param_index: usize,
+    default: Option<Box<Type>>,
 }
 
 impl TypeParamBounds {
@@ -263,14 +1003,22 @@ impl TypeParamBounds {
         &self.bounds
     }
 
-    /// The index of the type parameter in the generic list.
+    /// The index of the type parameter in the generic list, lifetimes and const parameters
+    /// included: `T` is 2 in `<'a, const N: usize, T>`.
     #[inline]
     pub fn param_index(&self) -> usize {
         self.param_index
     }
+
+    /// The default of the type parameter, e.g. `String` in `<T: Clone = String>`.
+    #[inline]
+    pub fn default(&self) -> Option<&Type> {
+        self.default.as_deref()
+    }
 }
 
-/// Where a bound lives on a type parameter in the function's generic list.
+/// Where a bound lives in a predicate of the item's where clause, like `T: Clone` in
+/// `where T: Clone, Vec<T>: Debug`.
 pub struct WhereTypeBounds {
     ty: Box<Type>,
     bounds: Punctuated<TypeParamBound, Plus>,
@@ -284,19 +1032,83 @@ impl WhereTypeBounds {
         &self.ty
     }
 
-    /// The bounds of the type parameter.
+    /// The bounds of the predicate.
     #[inline]
     pub fn bounds(&self) -> &Punctuated<TypeParamBound, Plus> {
         &self.bounds
     }
 
-    /// The index of the type parameter in the generic list.
+    /// The index of the predicate in the where clause, lifetime predicates included:
+    /// `Vec<T>: Debug` is 1 in `where T: Clone, Vec<T>: Debug`.
     #[inline]
     pub fn pred_index(&self) -> usize {
         self.pred_index
     }
 }
 
+/// Where a bound lives in an argument-position `impl Trait`, like `x: impl Clone + Debug`.
+pub struct ImplTraitArgBounds {
+    arg: Box<Pat>,
+    bounds: Punctuated<TypeParamBound, Plus>,
+    arg_index: usize,
+}
+
+impl ImplTraitArgBounds {
+    /// The pattern of the argument, usually its name.
+    #[inline]
+    pub fn arg(&self) -> &Pat {
+        &self.arg
+    }
+
+    /// The bounds of the `impl Trait` type.
+    #[inline]
+    pub fn bounds(&self) -> &Punctuated<TypeParamBound, Plus> {
+        &self.bounds
+    }
+
+    /// The index of the argument in the signature, counting a `self` receiver.
+    #[inline]
+    pub fn arg_index(&self) -> usize {
+        self.arg_index
+    }
+}
+
+/// Where a bound lives in a return-position `impl Trait` or a `dyn Trait`, like
+/// `-> impl Iterator<Item = u32> + Send` or `Box<dyn Error + Send + Sync>`. Removing one
+/// changes a type the item exposes.
+pub struct OpaqueTypeBounds {
+    ty: Box<Type>,
+    path: TypePath,
+    bounds: Punctuated<TypeParamBound, Plus>,
+    trait_object: bool,
+}
+
+impl OpaqueTypeBounds {
+    /// The `impl Trait` or `dyn Trait` type.
+    #[inline]
+    pub fn ty(&self) -> &Type {
+        &self.ty
+    }
+
+    /// Where the type sits in the item's declaration.
+    #[inline]
+    pub fn path(&self) -> &TypePath {
+        &self.path
+    }
+
+    /// The bounds of the type.
+    #[inline]
+    pub fn bounds(&self) -> &Punctuated<TypeParamBound, Plus> {
+        &self.bounds
+    }
+
+    /// Whether the type is a `dyn Trait`, rather than an `impl Trait`.
+    #[inline]
+    pub fn is_trait_object(&self) -> bool {
+        self.trait_object
+    }
+}
+
 impl<'ast> Collector<'ast> {
     fn type_param_bounds(&self, gens: &syn::Generics) -> Vec<TypeParamBounds> {
         use syn::{GenericParam, TypeParam};
@@ -304,13 +1116,17 @@ impl<'ast> Collector<'ast> {
             .iter()
             .enumerate()
             .filter_map(|(idx, p)| match p {
-                GenericParam::Type(TypeParam { ident, bounds, .. }) if !bounds.is_empty() => {
-                    Some(TypeParamBounds {
-                        ident: ident.clone(),
-                        bounds: bounds.clone(),
-                        param_index: idx,
-                    })
-                }
+                GenericParam::Type(TypeParam {
+                    ident,
+                    bounds,
+                    default,
+                    ..
+                }) if !bounds.is_empty() => Some(TypeParamBounds {
+                    ident: ident.clone(),
+                    bounds: bounds.clone(),
+                    param_index: idx,
+                    default: default.clone().map(Box::new),
+                }),
                 _ => None,
             })
             .collect()
@@ -334,153 +1150,353 @@ impl<'ast> Collector<'ast> {
         out
     }
 
-    fn push_if_any<F>(&mut self, gens: &syn::Generics, mut push: F)
-    where
-        F: FnMut(&mut Self, Vec<TypeParamBounds>, Vec<WhereTypeBounds>),
+    fn module_path(&self) -> String {
+        self.modules.join("::")
+    }
+
+    /// The [`ItemKey::ordinal`] of an item of `kind` named `ident` in the current module,
+    /// counting it.
+    fn ordinal(&mut self, kind: ItemKind, ident: Option<&Ident>) -> Option<usize> {
+        if self.blocks > 0 {
+            return None;
+        }
+        let name = ident.map(ToString::to_string).unwrap_or_default();
+        let seen = self
+            .ordinals
+            .entry((self.module_path(), kind, name))
+            .or_default();
+        *seen += 1;
+        Some(*seen - 1)
+    }
+
+    /// Visit a child of the current item with `visit`, its index appended to the path.
+    fn enter(&mut self, visit: impl FnOnce(&mut Self)) {
+        self.path.push(self.next);
+        let siblings = std::mem::replace(&mut self.next, 0);
+        visit(self);
+        self.next = siblings + 1;
+        self.path.pop();
+    }
+
+    /// The path of the `index`th impl or trait item of the current item.
+    fn member_path(&self, index: usize) -> Vec<usize> {
+        let mut path = self.path.clone();
+        path.push(index);
+        path
+    }
+
+    /// The visibility of an item declaring `own`, within the enclosing modules.
+    fn visibility(&self, own: ItemVisibility) -> ItemVisibility {
+        own.min(self.visibility)
+    }
+
+    /// The cfg predicates in scope for an item with `attrs`: its parent's, then its own.
+    fn cfg(&self, attrs: &[&[syn::Attribute]]) -> Vec<syn::Meta> {
+        let mut out = self.cfgs.clone();
+        for a in attrs {
+            out.extend(cfg_predicates(a));
+        }
+        out
+    }
+
+    /// `impl Trait` argument types with more than one bound; a lone bound cannot go.
+    /// Only argument types that are themselves `impl Trait` count, not ones nesting it.
+    fn impl_trait_arg_bounds(
+        &self,
+        inputs: Option<&Punctuated<FnArg, Comma>>,
+    ) -> Vec<ImplTraitArgBounds> {
+        inputs
+            .into_iter()
+            .flatten()
+            .enumerate()
+            .filter_map(|(arg_index, arg)| match arg {
+                FnArg::Typed(pt) => match &*pt.ty {
+                    Type::ImplTrait(it) if it.bounds.len() > 1 => Some(ImplTraitArgBounds {
+                        arg: pt.pat.clone(),
+                        bounds: it.bounds.clone(),
+                        arg_index,
+                    }),
+                    _ => None,
+                },
+                FnArg::Receiver(_) => None,
+            })
+            .collect()
+    }
+
+    /// Return-position `impl Trait` and `dyn Trait` types with more than one bound, anywhere
+    /// in the signature `sig` or the struct `fields`.
+    fn opaque_type_bounds(
+        &self,
+        sig: Option<&syn::Signature>,
+        fields: Option<&syn::Fields>,
+    ) -> Vec<OpaqueTypeBounds> {
+        fn walk(ty: &Type, path: TypePath, out: &mut Vec<OpaqueTypeBounds>) {
+            let (bounds, trait_object) = match ty {
+                Type::ImplTrait(t) if path.root == TypeRoot::Return => (Some(&t.bounds), false),
+                Type::TraitObject(t) => (Some(&t.bounds), true),
+                _ => (None, false),
+            };
+            if let Some(bounds) = bounds.filter(|b| b.len() > 1) {
+                out.push(OpaqueTypeBounds {
+                    ty: Box::new(ty.clone()),
+                    path: path.clone(),
+                    bounds: bounds.clone(),
+                    trait_object,
+                });
+            }
+            for (i, nested) in type_path::nested(ty).into_iter().enumerate() {
+                walk(nested, path.child(i), out);
+            }
+        }
+
+        let mut roots: Vec<(TypeRoot, &Type)> = Vec::new();
+        if let Some(sig) = sig {
+            roots.extend(type_path::return_type(&sig.output).map(|ty| (TypeRoot::Return, ty)));
+            roots.extend(
+                sig.inputs
+                    .iter()
+                    .enumerate()
+                    .filter_map(|(i, arg)| match arg {
+                        FnArg::Typed(pt) => Some((TypeRoot::Arg(i), &*pt.ty)),
+                        FnArg::Receiver(_) => None,
+                    }),
+            );
+        }
+        if let Some(fields) = fields {
+            roots.extend(
+                fields
+                    .iter()
+                    .enumerate()
+                    .map(|(i, f)| (TypeRoot::Field(i), &f.ty)),
+            );
+        }
+        let mut out = Vec::new();
+        for (root, ty) in roots {
+            walk(
+                ty,
+                TypePath {
+                    root,
+                    steps: Vec::new(),
+                },
+                &mut out,
+            );
+        }
+        out
+    }
+
+    fn push_if_any<F>(
+        &mut self,
+        gens: &syn::Generics,
+        sig: Option<&syn::Signature>,
+        fields: Option<&syn::Fields>,
+        mut push: F,
+    ) where
+        F: FnMut(
+            &mut Self,
+            Vec<TypeParamBounds>,
+            Vec<WhereTypeBounds>,
+            Vec<ImplTraitArgBounds>,
+            Vec<OpaqueTypeBounds>,
+        ),
     {
         let tp = self.type_param_bounds(gens);
         let wb = self.where_bounds(gens);
-        if !tp.is_empty() || !wb.is_empty() {
-            push(self, tp, wb);
+        let ia = self.impl_trait_arg_bounds(sig.map(|s| &s.inputs));
+        let ot = self.opaque_type_bounds(sig, fields);
+        if !tp.is_empty() || !wb.is_empty() || !ia.is_empty() || !ot.is_empty() {
+            push(self, tp, wb, ia, ot);
         }
     }
 }
 
-impl<'ast> Visit<'ast> for Collector<'ast> {
-    fn visit_item(&mut self, i: &'ast Item) {
+impl<'ast> Collector<'ast> {
+    /// Collect the bounds of `i`, then visit the items within it.
+    fn collect_item(&mut self, i: &'ast Item) {
         match i {
             Item::Fn(f) => {
-                let name = f.sig.ident.to_string();
-                let label = ItemKey::fn_label(&name);
-                self.push_if_any(&f.sig.generics, |this, tp, wb| {
-                    this.out.fns.push(FnBounds {
-                        item: ItemKey {
-                            item: ItemRef::Func(f),
-                            label: label.clone(),
-                            span: f.sig.ident.span(),
-                        },
-                        type_params: tp,
-                        where_preds: wb,
-                    });
-                });
+                let ordinal = self.ordinal(ItemKind::Fn, Some(&f.sig.ident));
+                self.push_if_any(
+                    &f.sig.generics,
+                    Some(&f.sig),
+                    None,
+                    |this, tp, wb, ia, ot| {
+                        this.out.fns.push(FnBounds {
+                            item: ItemKey {
+                                item: ItemRef::Func(f),
+                                span: f.sig.ident.span(),
+                                path: this.path.clone(),
+                                module: this.module_path(),
+                                ordinal,
+                                visibility: this.visibility(ItemVisibility::of(&f.vis)),
+                                cfg: this.cfg(&[&f.attrs]),
+                            },
+                            type_params: tp,
+                            where_preds: wb,
+                            impl_args: ia,
+                            opaque: ot,
+                        });
+                    },
+                );
             }
 
             Item::Struct(s) => {
-                let name = s.ident.to_string();
-                let label = ItemKey::struct_label(&name);
-                self.push_if_any(&s.generics, |this, tp, wb| {
-                    this.out.structs.push(StructBounds {
-                        item: ItemKey {
-                            item: ItemRef::Struct(s),
-                            label: label.clone(),
-                            span: s.ident.span(),
-                        },
-                        type_params: tp,
-                        where_preds: wb,
-                    });
-                });
+                let ordinal = self.ordinal(ItemKind::Struct, Some(&s.ident));
+                self.push_if_any(
+                    &s.generics,
+                    None,
+                    Some(&s.fields),
+                    |this, tp, wb, ia, ot| {
+                        this.out.structs.push(StructBounds {
+                            item: ItemKey {
+                                item: ItemRef::Struct(s),
+                                span: s.ident.span(),
+                                path: this.path.clone(),
+                                module: this.module_path(),
+                                ordinal,
+                                visibility: this.visibility(ItemVisibility::of(&s.vis)),
+                                cfg: this.cfg(&[&s.attrs]),
+                            },
+                            type_params: tp,
+                            where_preds: wb,
+                            impl_args: ia,
+                            opaque: ot,
+                        });
+                    },
+                );
             }
 
             Item::Enum(e) => {
-                let name = e.ident.to_string();
-                let label = ItemKey::enum_label(&name);
-                self.push_if_any(&e.generics, |this, tp, wb| {
+                let ordinal = self.ordinal(ItemKind::Enum, Some(&e.ident));
+                self.push_if_any(&e.generics, None, None, |this, tp, wb, ia, ot| {
                     this.out.enums.push(EnumBounds {
                         item: ItemKey {
                             item: ItemRef::Enum(e),
-                            label: label.clone(),
                             span: e.ident.span(),
+                            path: this.path.clone(),
+                            module: this.module_path(),
+                            ordinal,
+                            visibility: this.visibility(ItemVisibility::of(&e.vis)),
+                            cfg: this.cfg(&[&e.attrs]),
                         },
                         type_params: tp,
                         where_preds: wb,
+                        impl_args: ia,
+                        opaque: ot,
                     });
                 });
             }
 
             Item::Trait(t) => {
-                let trait_name = t.ident.to_string();
-                let label = ItemKey::trait_label(&trait_name);
-                self.push_if_any(&t.generics, |this, tp, wb| {
+                let ordinal = self.ordinal(ItemKind::Trait, Some(&t.ident));
+                self.push_if_any(&t.generics, None, None, |this, tp, wb, ia, ot| {
                     this.out.traits.push(TraitBounds {
                         item: ItemKey {
                             item: ItemRef::Trait(t),
-                            label: label.clone(),
                             span: t.ident.span(),
+                            path: this.path.clone(),
+                            module: this.module_path(),
+                            ordinal,
+                            visibility: this.visibility(ItemVisibility::of(&t.vis)),
+                            cfg: this.cfg(&[&t.attrs]),
                         },
                         type_params: tp,
                         where_preds: wb,
+                        impl_args: ia,
+                        opaque: ot,
                     });
                 });
 
                 // Trait methods: generics live on the method *signature*.
-                for it in &t.items {
+                for (index, it) in t.items.iter().enumerate() {
                     if let syn::TraitItem::Fn(m) = it {
-                        let trait_name = t.ident.to_string();
-                        let mlabel =
-                            ItemKey::trait_method_label(&trait_name, &m.sig.ident.to_string());
-                        self.push_if_any(&m.sig.generics, |this, tp, wb| {
-                            this.out.trait_methods.push(TraitMethodBounds {
-                                item: ItemKey {
-                                    item: ItemRef::TraitMethod {
-                                        trait_ident: &t.ident,
-                                        method: m,
+                        let ordinal = self.ordinal(ItemKind::TraitMethod, Some(&m.sig.ident));
+                        self.push_if_any(
+                            &m.sig.generics,
+                            Some(&m.sig),
+                            None,
+                            |this, tp, wb, ia, ot| {
+                                this.out.trait_methods.push(TraitMethodBounds {
+                                    item: ItemKey {
+                                        item: ItemRef::TraitMethod {
+                                            trait_ident: &t.ident,
+                                            method: m,
+                                        },
+                                        span: m.sig.ident.span(),
+                                        path: this.member_path(index),
+                                        module: this.module_path(),
+                                        ordinal,
+                                        visibility: this.visibility(ItemVisibility::of(&t.vis)),
+                                        cfg: this.cfg(&[&t.attrs, &m.attrs]),
                                     },
-                                    label: mlabel.clone(),
-                                    span: m.sig.ident.span(),
-                                },
-                                type_params: tp,
-                                where_preds: wb,
-                            });
-                        });
+                                    type_params: tp,
+                                    where_preds: wb,
+                                    impl_args: ia,
+                                    opaque: ot,
+                                });
+                            },
+                        );
                     }
                 }
             }
 
             Item::Impl(im) => {
-                use quote::ToTokens;
                 let trait_path_ref: Option<&'ast syn::Path> = im.trait_.as_ref().map(|(_, p, _)| p);
-                let self_ty_str = im.self_ty.to_token_stream().to_string();
-                let impl_label = if let Some(tp) = trait_path_ref {
-                    ItemKey::impl_trait_label(&tp.to_token_stream().to_string(), &self_ty_str)
-                } else {
-                    ItemKey::impl_inherent_label(&self_ty_str)
-                };
-
-                self.push_if_any(&im.generics, |this, tp, wb| {
+                let ordinal = self.ordinal(ItemKind::Impl, None);
+
+                self.push_if_any(&im.generics, None, None, |this, tp, wb, ia, ot| {
                     this.out.impls.push(ImplBounds {
                         item: ItemKey {
                             item: ItemRef::Impl(im),
-                            label: impl_label.clone(),
                             span: im.impl_token.span,
+                            path: this.path.clone(),
+                            module: this.module_path(),
+                            ordinal,
+                            visibility: this.visibility(ItemVisibility::Public),
+                            cfg: this.cfg(&[&im.attrs]),
                         },
                         type_params: tp,
                         where_preds: wb,
+                        impl_args: ia,
+                        opaque: ot,
                     });
                 });
 
                 // Impl methods (method generics are on the signature)
-                for ii in &im.items {
+                for (index, ii) in im.items.iter().enumerate() {
                     if let syn::ImplItem::Fn(m) = ii {
-                        let owner = trait_path_ref
-                            .map(|tp| format!("{} for {}", tp.to_token_stream(), self_ty_str))
-                            .unwrap_or_else(|| self_ty_str.clone());
-                        let mlabel = ItemKey::impl_method_label(&owner, &m.sig.ident.to_string());
-
-                        self.push_if_any(&m.sig.generics, |this, tp, wb| {
-                            this.out.impl_methods.push(ImplMethodBounds {
-                                item: ItemKey {
-                                    item: ItemRef::ImplMethod {
-                                        self_ty: &im.self_ty,
-                                        trait_path: trait_path_ref,
-                                        method: m,
+                        let ordinal = self.ordinal(ItemKind::ImplMethod, Some(&m.sig.ident));
+                        // Methods of trait impls are as visible as the trait.
+                        let method_visibility = match trait_path_ref {
+                            Some(_) => ItemVisibility::Public,
+                            None => ItemVisibility::of(&m.vis),
+                        };
+
+                        self.push_if_any(
+                            &m.sig.generics,
+                            Some(&m.sig),
+                            None,
+                            |this, tp, wb, ia, ot| {
+                                this.out.impl_methods.push(ImplMethodBounds {
+                                    item: ItemKey {
+                                        item: ItemRef::ImplMethod {
+                                            self_ty: &im.self_ty,
+                                            trait_path: trait_path_ref,
+                                            method: m,
+                                        },
+                                        span: m.sig.ident.span(),
+                                        path: this.member_path(index),
+                                        module: this.module_path(),
+                                        ordinal,
+                                        visibility: this.visibility(method_visibility),
+                                        cfg: this.cfg(&[&im.attrs, &m.attrs]),
                                     },
-                                    label: mlabel.clone(),
-                                    span: m.sig.ident.span(),
-                                },
-                                type_params: tp,
-                                where_preds: wb,
-                            });
-                        });
+                                    type_params: tp,
+                                    where_preds: wb,
+                                    impl_args: ia,
+                                    opaque: ot,
+                                });
+                            },
+                        );
                     }
                 }
             }
@@ -492,6 +1508,38 @@ impl<'ast> Visit<'ast> for Collector<'ast> {
     }
 }
 
+impl<'ast> Visit<'ast> for Collector<'ast> {
+    fn visit_item(&mut self, i: &'ast Item) {
+        self.enter(|this| this.collect_item(i));
+    }
+
+    fn visit_impl_item(&mut self, i: &'ast syn::ImplItem) {
+        self.enter(|this| syn::visit::visit_impl_item(this, i));
+    }
+
+    fn visit_trait_item(&mut self, i: &'ast syn::TraitItem) {
+        self.enter(|this| syn::visit::visit_trait_item(this, i));
+    }
+
+    fn visit_block(&mut self, b: &'ast syn::Block) {
+        self.blocks += 1;
+        syn::visit::visit_block(self, b);
+        self.blocks -= 1;
+    }
+
+    fn visit_item_mod(&mut self, m: &'ast syn::ItemMod) {
+        let outer = self.cfgs.len();
+        self.cfgs.extend(cfg_predicates(&m.attrs));
+        self.modules.push(m.ident.to_string());
+        let outer_visibility = self.visibility;
+        self.visibility = self.visibility(ItemVisibility::of(&m.vis));
+        syn::visit::visit_item_mod(self, m);
+        self.visibility = outer_visibility;
+        self.modules.pop();
+        self.cfgs.truncate(outer);
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -517,7 +1565,7 @@ mod tests {
     fn labels_from_src(src: &str) -> TraitError<Vec<String>> {
         let file = syn::parse_file(src)?;
         let items = ItemBounds::collect_items_in_file(&file)?;
-        Ok(items.iter_all_items().map(|i| i.label.clone()).collect())
+        Ok(items.iter_all_items().map(|i| i.label()).collect())
     }
 
     fn assert_has(labels: &[String], expected: &[Label<'_>]) {
@@ -539,6 +1587,30 @@ mod tests {
         );
     }
 
+    #[test]
+    fn read_source_rejects_non_utf8() {
+        let dir = std::env::temp_dir().join(format!("tw-latin1-{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        let path = dir.join("legacy.rs");
+        // "// caf\xe9" in Latin-1.
+        std::fs::write(&path, b"// caf\xe9\nfn f() {}\n").unwrap();
+        let err = ItemBounds::read_source(&path).unwrap_err();
+        let crate::error::WinnowerError::NotUtf8(non_utf8) = &err else {
+            panic!("expected NotUtf8, got {err:?}");
+        };
+        assert_eq!(non_utf8.valid_up_to, 6);
+        assert!(err.to_string().contains("not valid UTF-8"));
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn parse_accepts_leading_bom() -> TraitError<()> {
+        let src = format!("{UTF8_BOM}fn foo<T: Copy>() {{}}");
+        let labels = labels_from_src(&src)?;
+        assert_has(&labels, &[Label::Eq("// fn foo")]);
+        Ok(())
+    }
+
     #[test]
     fn item_bounds_fn() -> TraitError<()> {
         let src = r#"
@@ -552,6 +1624,24 @@ mod tests {
         Ok(())
     }
 
+    #[test]
+    fn unsafe_and_extern_fns_are_collected_like_any_fn() -> TraitError<()> {
+        let src = r#"
+        unsafe fn a<T: Copy>(t: *const T) -> T { *t }
+        extern "C" fn b<T: Copy>() {}
+        pub const unsafe extern "C" fn c<T: Copy>() {}
+        extern "C" {
+            fn d(x: i32);
+        }
+        unsafe extern "C" {
+            safe fn e(x: i32);
+        }
+        "#;
+        let labels = labels_from_src(src)?;
+        assert_eq!(labels, ["// fn a", "// fn b", "// fn c"]);
+        Ok(())
+    }
+
     #[test]
     fn item_bounds_fn_no_bounds() -> TraitError<()> {
         let src = r#"
@@ -573,7 +1663,45 @@ mod tests {
         let items = ItemBounds::collect_items_in_file(&file)?;
         assert_eq!(items.fns().len(), 1);
         let info = &items.fns()[0];
-        assert_eq!(info.item.label, "// fn foo");
+        assert_eq!(info.item.label(), "// fn foo");
+        assert_eq!(info.item.to_string(), "// outer fn foo");
+        Ok(())
+    }
+
+    #[test]
+    fn item_visibility_is_capped_by_enclosing_modules() -> TraitError<()> {
+        let src = r#"
+        pub fn a<T: Copy>() {}
+        pub(crate) mod outer {
+            pub fn b<T: Copy>() {}
+            pub mod inner {
+                fn c<T: Copy>() {}
+            }
+        }
+        pub struct S;
+        impl S {
+            pub(super) fn d<T: Copy>() {}
+        }
+        impl<T: Copy> Clone for Box<T> {
+            fn clone<U: Copy>(&self) -> Self { todo!() }
+        }
+        "#;
+        let file = syn::parse_file(src)?;
+        let items = ItemBounds::collect_items_in_file(&file)?;
+        let vis: Vec<(String, ItemVisibility)> = items
+            .iter_all_items()
+            .map(|k| (k.to_string(), k.visibility()))
+            .collect();
+        for (label, want) in [
+            ("// fn a", ItemVisibility::Public),
+            ("// outer fn b", ItemVisibility::Crate),
+            ("// outer::inner fn c", ItemVisibility::Private),
+            ("// S::d", ItemVisibility::Crate),
+            ("// impl Clone for Box < T >", ItemVisibility::Public),
+            ("// Clone for Box < T >::clone", ItemVisibility::Public),
+        ] {
+            assert!(vis.contains(&(label.to_owned(), want)), "{label}: {vis:?}");
+        }
         Ok(())
     }
 
@@ -590,6 +1718,30 @@ mod tests {
         Ok(())
     }
 
+    #[test]
+    fn type_param_defaults_are_recorded() -> TraitError<()> {
+        let src = "pub struct S<A: Copy = u8, B: Clone, C: Default + Send = ()>(A, B, C);";
+        let file = syn::parse_file(src)?;
+        let items = ItemBounds::collect_items_in_file(&file)?;
+        let defaults: Vec<(String, Option<String>)> = items.structs()[0]
+            .type_param_bounds()
+            .iter()
+            .map(|tp| {
+                let default = tp.default().map(|d| d.to_token_stream().to_string());
+                (tp.ident().to_string(), default)
+            })
+            .collect();
+        assert_eq!(
+            defaults,
+            [
+                ("A".to_owned(), Some("u8".to_owned())),
+                ("B".to_owned(), None),
+                ("C".to_owned(), Some("()".to_owned())),
+            ]
+        );
+        Ok(())
+    }
+
     #[test]
     fn item_bounds_struct_no_bounds() -> TraitError<()> {
         let src = r#"
@@ -724,6 +1876,134 @@ mod tests {
         assert_none(&labels);
         Ok(())
     }
+
+    #[test]
+    fn cfg_predicates_include_enclosing_modules_and_impls() -> TraitError<()> {
+        let src = r#"
+        #![cfg(unix)]
+        #[cfg(feature = "rare")]
+        mod gated {
+            #[cfg(any(test, windows))]
+            impl<T: Clone> S<T> {
+                #[cfg(debug_assertions)]
+                fn m<U: Copy>(&self) {}
+            }
+        }
+        fn plain<T: Send>() {}
+        "#;
+        let file = syn::parse_file(src)?;
+        let items = ItemBounds::collect_items_in_file(&file)?;
+        let cfg: Vec<(String, Vec<String>)> = items
+            .iter_all_items()
+            .map(|k| {
+                let preds = k.cfg().iter().map(|m| m.to_token_stream().to_string());
+                (k.label(), preds.collect())
+            })
+            .collect();
+        let of = |label: &str| {
+            cfg.iter()
+                .find(|(l, _)| l.contains(label))
+                .map(|(_, c)| c.join(" / "))
+        };
+        assert_eq!(of("fn plain").as_deref(), Some("unix"));
+        assert_eq!(
+            of("impl S").as_deref(),
+            Some("unix / feature = \"rare\" / any (test , windows)")
+        );
+        assert_eq!(
+            of("::m").as_deref(),
+            Some("unix / feature = \"rare\" / any (test , windows) / debug_assertions")
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn blanket_impls_have_a_type_parameter_for_self() -> TraitError<()> {
+        let src = r#"
+        impl<T: Display> Tr for T {}
+        impl<T: Display> Tr for &T {}
+        impl<'a, T: Display> Tr for &'a mut T {}
+        impl<T: ?Sized + Tr> Tr for Box<T> {}
+        impl<T: Display> Display for Wrapper<&T> {}
+        impl<T: Display> Tr for Box<u8> {}
+        fn f<T: Display>(t: T) {}
+        "#;
+        let file = syn::parse_file(src)?;
+        let items = ItemBounds::collect_items_in_file(&file)?;
+        let mut blanket: Vec<(ItemKind, Option<String>, bool)> = items
+            .iter_all_items()
+            .map(|k| {
+                let self_ty = k.impl_header().map(|h| h.self_ty);
+                (k.kind(), self_ty, k.item().is_blanket_impl())
+            })
+            .collect();
+        blanket.sort();
+        let want = |self_ty: &str, on| (ItemKind::Impl, Some(self_ty.to_owned()), on);
+        assert_eq!(
+            blanket,
+            [
+                (ItemKind::Fn, None, false),
+                want("& 'a mut T", true),
+                want("& T", true),
+                want("Box < T >", true),
+                want("Box < u8 >", false),
+                want("T", true),
+                want("Wrapper < & T >", false),
+            ]
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn labels_are_made_from_kinds_names_and_impl_headers() -> TraitError<()> {
+        let src = r#"
+        trait Tr { fn t<T: Copy>(); }
+        impl<T: Clone> Wrapper<T> { fn id<U: Copy>(u: U) {} }
+        impl<T: Display> Display for Wrapper<T> { fn fmt<U: Copy>(u: U) {} }
+        "#;
+        let file = syn::parse_file(src)?;
+        let items = ItemBounds::collect_items_in_file(&file)?;
+        let mut keys: Vec<(ItemKind, String, Option<ImplHeader>)> = Vec::new();
+        for k in items.iter_all_items() {
+            let locator = ItemLocator::from(k);
+            assert_eq!(locator.label(), k.label());
+            keys.push((locator.kind, k.label(), locator.impl_header));
+        }
+        keys.sort_by(|a, b| a.1.cmp(&b.1));
+        let header = |trait_path: Option<&str>| {
+            Some(ImplHeader {
+                self_ty: "Wrapper < T >".to_owned(),
+                trait_path: trait_path.map(str::to_owned),
+            })
+        };
+        assert_eq!(
+            keys,
+            [
+                (
+                    ItemKind::ImplMethod,
+                    "// Display for Wrapper < T >::fmt".to_owned(),
+                    header(Some("Display")),
+                ),
+                (
+                    ItemKind::ImplMethod,
+                    "// Wrapper < T >::id".to_owned(),
+                    header(None),
+                ),
+                (
+                    ItemKind::Impl,
+                    "// impl Display for Wrapper < T >".to_owned(),
+                    header(Some("Display")),
+                ),
+                (
+                    ItemKind::Impl,
+                    "// impl Wrapper < T >".to_owned(),
+                    header(None),
+                ),
+                (ItemKind::TraitMethod, "// trait Tr::t".to_owned(), None),
+            ]
+        );
+        Ok(())
+    }
 }
 
 // TODO: Check supertraits and their methods.
@@ -0,0 +1,264 @@
+// src/annotate.rs
+//! `prune --annotate-retained`: a comment above each item keeping a bound that the rejecting
+//! check blamed on a bound elsewhere, and `prune --strip-annotations` to take them out.
+//!
+//! The note names the bound and what required it, e.g.
+//! ``// trait-winnower: `T: Clone` required by a bound in `cache_insert` (src/cache.rs:88)``.
+//! It goes above the item's attributes and doc comments, at the item's indentation. Comments
+//! do not survive the tree reprint, so notes are text edits on the source as `prune` left it
+//! (see [`apply_edits`]). Every marker line is stripped before notes are placed, so a second
+//! run replaces the notes of the first instead of adding to them.
+
+#![deny(missing_docs)]
+
+use crate::analysis::{ItemBounds, ItemKey, ItemLocator, ItemRef, UTF8_BOM};
+use crate::dynamic_analysis::text_edit::{TextEdit, apply_edits};
+use crate::error::{TraitError, WinnowerError};
+use crate::patch::FileChange;
+use crate::plan::locate;
+use crate::report::{Removal, RemovalStatus, Report};
+use std::collections::BTreeMap;
+use std::path::Path;
+use syn::spanned::Spanned;
+
+/// How every note starts; a line starting with it, after indentation, is a marker.
+pub const MARKER: &str = "// trait-winnower: ";
+
+/// The note for `removal`, when it was retained and its check's errors blamed a bound:
+/// ``T: Clone` required by a bound in `cache_insert` (src/cache.rs:88)`.
+pub fn note(removal: &Removal) -> Option<String> {
+    if removal.status != RemovalStatus::Retained || removal.blames.is_empty() {
+        return None;
+    }
+    let cites: Vec<String> = removal
+        .blames
+        .iter()
+        .map(|b| format!("`{}` ({}:{})", b.item, b.file.display(), b.line))
+        .collect();
+    let f = &removal.finding;
+    Some(format!(
+        "`{}: {}` required by {} in {}",
+        f.bounded,
+        f.bound,
+        if cites.len() == 1 {
+            "a bound"
+        } else {
+            "bounds"
+        },
+        cites.join(", ")
+    ))
+}
+
+/// Whether `line` is a marker line.
+pub fn is_marker(line: &str) -> bool {
+    line.trim_start().starts_with(MARKER.trim_end())
+}
+
+/// `source` without its marker lines.
+pub fn strip(source: &str) -> String {
+    source
+        .split_inclusive('\n')
+        .filter(|line| !is_marker(line))
+        .collect()
+}
+
+/// `source`, the text of the file `rel`, with its marker lines replaced by `notes`, each
+/// placed above the item its locator names: the item of that module path, kind and name,
+/// the closest to the recorded position when several match. Notes whose item is gone are
+/// dropped; an item's notes keep their order, repeats dropped.
+pub fn annotate_source(
+    rel: &Path,
+    source: &str,
+    notes: &[(&ItemLocator, String)],
+) -> TraitError<String> {
+    let stripped = strip(source);
+    if notes.is_empty() {
+        return Ok(stripped);
+    }
+    let file = syn::parse_file(&stripped).map_err(|e| WinnowerError::parse(rel, e))?;
+    let items = ItemBounds::collect_items_in_file(&file)?;
+    let keys: Vec<&ItemKey<'_>> = items.iter_all_items().collect();
+    let mut by_line: BTreeMap<usize, Vec<&str>> = BTreeMap::new();
+    for (locator, note) in notes {
+        let Some(k) = locate(&keys, rel, locator) else {
+            continue;
+        };
+        let above = by_line.entry(first_line(keys[k].item())).or_default();
+        if !above.contains(&note.as_str()) {
+            above.push(note);
+        }
+    }
+    let body = stripped.strip_prefix(UTF8_BOM).unwrap_or(&stripped);
+    let base = stripped.len() - body.len();
+    let newline = if body.contains("\r\n") { "\r\n" } else { "\n" };
+    let line_starts: Vec<usize> = std::iter::once(0)
+        .chain(body.match_indices('\n').map(|(i, _)| i + 1))
+        .collect();
+    let edits: Vec<TextEdit> = by_line
+        .into_iter()
+        .filter_map(|(line, notes)| {
+            let start = *line_starts.get(line - 1)?;
+            let text = &body[start..];
+            let indent = &text[..text.len() - text.trim_start_matches([' ', '\t']).len()];
+            Some(TextEdit {
+                start: base + start,
+                end: base + start,
+                replacement: notes
+                    .iter()
+                    .map(|n| format!("{indent}{MARKER}{n}{newline}"))
+                    .collect(),
+            })
+        })
+        .collect();
+    Ok(apply_edits(&stripped, &edits))
+}
+
+/// 1-based line where `item` starts, its outer attributes and doc comments included.
+fn first_line(item: &ItemRef<'_>) -> usize {
+    let own = match item {
+        ItemRef::Func(f) => f.span(),
+        ItemRef::Struct(s) => s.span(),
+        ItemRef::Enum(e) => e.span(),
+        ItemRef::Trait(t) => t.span(),
+        ItemRef::Impl(i) => i.span(),
+        ItemRef::ImplMethod { method, .. } => method.span(),
+        ItemRef::TraitMethod { method, .. } => method.span(),
+    };
+    item.attrs()
+        .iter()
+        .map(|a| a.span().start().line)
+        .fold(own.start().line, usize::min)
+}
+
+/// Place the notes of `report`'s retained removals (see [`note`]) in the files under
+/// `root`, replacing the notes an earlier run left, and return the files changed. A bound
+/// some trial removed gets no note.
+pub fn annotate_report(root: &Path, report: &Report) -> TraitError<Vec<FileChange>> {
+    let mut changes = Vec::new();
+    for file in &report.files {
+        let notes: Vec<(&ItemLocator, String)> = file
+            .removals
+            .iter()
+            .filter(|r| {
+                !file.removals.iter().any(|o| {
+                    o.status == RemovalStatus::Removed
+                        && o.finding.locator == r.finding.locator
+                        && o.finding.candidate.key() == r.finding.candidate.key()
+                })
+            })
+            .filter_map(|r| Some((&r.finding.locator, note(r)?)))
+            .collect();
+        let path = root.join(&file.path);
+        let source = ItemBounds::read_source(&path)?;
+        if notes.is_empty() && !source.lines().any(is_marker) {
+            continue;
+        }
+        let new = annotate_source(&file.path, &source, &notes)?;
+        if new != source {
+            std::fs::write(&path, &new).map_err(|e| WinnowerError::io("annotating", &path, e))?;
+            changes.push(FileChange {
+                path: file.path.clone(),
+                old: source,
+                new,
+            });
+        }
+    }
+    Ok(changes)
+}
+
+/// Strip the marker lines of `files`, writing them unless `dry_run`, and return the files
+/// changed, with paths relative to `root` where they are under it.
+pub fn strip_files(
+    root: &Path,
+    files: &[impl AsRef<Path>],
+    dry_run: bool,
+) -> TraitError<Vec<FileChange>> {
+    let mut changes = Vec::new();
+    for path in files {
+        let path = path.as_ref();
+        let source = ItemBounds::read_source(path)?;
+        if !source.lines().any(is_marker) {
+            continue;
+        }
+        let new = strip(&source);
+        if !dry_run {
+            std::fs::write(path, &new).map_err(|e| WinnowerError::io("writing", path, e))?;
+        }
+        changes.push(FileChange {
+            path: path.strip_prefix(root).unwrap_or(path).to_path_buf(),
+            old: source,
+            new,
+        });
+    }
+    Ok(changes)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// The locator of the item labeled `name` in `src`, as a report records it.
+    fn locator(src: &str, name: &str) -> ItemLocator {
+        let file = syn::parse_file(src).unwrap();
+        let items = ItemBounds::collect_items_in_file(&file).unwrap();
+        let key = items
+            .iter_all_items()
+            .find(|k| ItemLocator::from(*k).name == name)
+            .unwrap();
+        ItemLocator::from(key).in_file(Path::new("src/lib.rs"))
+    }
+
+    const SRC: &str = "\
+use std::fmt::Debug;
+
+/// Doubles.
+#[inline]
+pub fn double<T: Clone>(t: T) -> (T, T) {
+    (t.clone(), t)
+}
+
+impl S {
+    #[must_use]
+    fn m<U: Debug>(&self) {}
+}
+";
+
+    #[test]
+    fn notes_go_above_attributes_and_doc_comments() {
+        let (double, m) = (locator(SRC, "double"), locator(SRC, "S::m"));
+        let notes = [
+            (
+                &double,
+                "`T: Clone` required by a bound in `a` (src/a.rs:1)".to_owned(),
+            ),
+            (
+                &m,
+                "`U: Debug` required by a bound in `b` (src/b.rs:2)".to_owned(),
+            ),
+        ];
+        let out = annotate_source(Path::new("src/lib.rs"), SRC, &notes).unwrap();
+        assert!(out.contains(
+            "\n// trait-winnower: `T: Clone` required by a bound in `a` (src/a.rs:1)\n\
+             /// Doubles.\n#[inline]\npub fn double"
+        ));
+        assert!(out.contains(
+            "impl S {\n    // trait-winnower: `U: Debug` required by a bound in `b` (src/b.rs:2)\n    \
+             #[must_use]\n    fn m"
+        ));
+    }
+
+    #[test]
+    fn annotating_again_replaces_the_notes() {
+        let double = locator(SRC, "double");
+        let rel = Path::new("src/lib.rs");
+        let once = annotate_source(rel, SRC, &[(&double, "first".into())]).unwrap();
+        let twice = annotate_source(rel, &once, &[(&double, "second".into())]).unwrap();
+        assert_eq!(twice.matches(MARKER).count(), 1);
+        assert!(twice.contains("// trait-winnower: second\n/// Doubles."));
+        assert_eq!(
+            annotate_source(rel, &twice, &[(&double, "second".into())]).unwrap(),
+            twice
+        );
+        assert_eq!(strip(&twice), SRC);
+    }
+}
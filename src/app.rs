@@ -0,0 +1,965 @@
+// src/app.rs
+//! What the `trait-winnower` and `cargo-winnow` binaries run once they have their
+//! arguments.
+
+#![deny(missing_docs)]
+
+use anyhow::Context;
+use std::io::{IsTerminal, Write};
+use std::path::{Path, PathBuf};
+use std::process::{Command, ExitCode};
+
+use crate::analysis::NonUtf8Source;
+use crate::bench::{self, bench};
+use crate::cancel::CancelToken;
+use crate::cli;
+use crate::config::{Config, cargo_program};
+use crate::crate_map::CrateMap;
+use crate::diff::diff_revisions;
+use crate::doctor::doctor;
+use crate::dynamic_analysis::journal::{Journal, recover};
+use crate::dynamic_analysis::lock_wait::LockNotice;
+use crate::dynamic_analysis::trial_cache::TrialCache;
+use crate::error::WinnowerError;
+use crate::explain::{ItemPath, explain_item};
+use crate::hook;
+use crate::impact::ImpactOptions;
+use crate::info::{ProgressLog, TraitInfo};
+use crate::patch::unified_diff;
+use crate::plan::{ApplyOptions, Plan, apply_plan_with};
+use crate::report::breakdown::Breakdown;
+use crate::report::diagnostic::Diagnostics;
+use crate::report::html::{Html, HtmlSummary};
+use crate::report::json::{Json, Value};
+use crate::report::junit::Junit;
+use crate::report::lsp::Lsp;
+use crate::report::markdown::Markdown;
+use crate::report::rustfix::Rustfix;
+use crate::report::suggestions::Suggestions;
+use crate::report::{RemovalStatus, Report, write_atomic};
+use crate::strategy::Strategy;
+use crate::target::TargetKind;
+use crate::watch::{self, WatchEvent};
+use crate::winnower::Winnower;
+
+/// Run the command `args` ask for, reporting an error on stderr and in the exit code.
+pub fn main(args: cli::Cli) -> ExitCode {
+    match run(args) {
+        Ok(()) => ExitCode::SUCCESS,
+        Err(e) => {
+            eprintln!("Error: {e:?}");
+            // Bad targets and configuration exit with 2, everything else with 1.
+            let code = e
+                .downcast_ref::<WinnowerError>()
+                .map_or(1, WinnowerError::exit_code);
+            ExitCode::from(code)
+        }
+    }
+}
+
+/// Run the command `args` ask for.
+pub fn run(args: cli::Cli) -> anyhow::Result<()> {
+    let verbosity = args.verbose;
+    let top = match args.number_of_items.as_deref() {
+        Some(s)
+            if s.eq_ignore_ascii_case("all")
+                || s.eq_ignore_ascii_case("max")
+                || s.eq_ignore_ascii_case("maxx") =>
+        {
+            usize::MAX
+        }
+        Some(s) => s.parse::<usize>().unwrap_or(10),
+        None => 10,
+    };
+
+    let format = args.format;
+    let mut reports = args.reports.clone();
+    let legacy = [
+        (cli::ReportFormat::Html, &args.report_html),
+        (cli::ReportFormat::Markdown, &args.report_markdown),
+    ];
+    for (report_format, path) in legacy {
+        if let Some(path) = path {
+            reports.push(cli::ReportFile {
+                format: report_format,
+                path: path.clone(),
+            });
+        }
+    }
+    // Whether stdout or a `--report` file is in `report_format`.
+    let wants = |report_format: cli::ReportFormat| {
+        cli::ReportFormat::of_output(format) == Some(report_format)
+            || reports.iter().any(|r| r.format == report_format)
+    };
+    let mut lsp_files = Vec::new();
+    let mut rustfix_files: Vec<(PathBuf, Vec<Value>)> = Vec::new();
+    let mut print_modified = false;
+    let mut dry_run = false;
+    let mut commit = None;
+    let mut commit_message = None;
+    let mut patches = None;
+    let mut watch = false;
+    let mut fix = false;
+    let mut import_clippy = false;
+    let mut impact = None;
+    let mut record = None;
+    let mut replay = None;
+    let mut incremental = None;
+    let mut keep_target_dir = true;
+    let mut assume_consistent = None;
+    let mut batch_size = None;
+    let mut max_candidates_per_file = None;
+    let mut strategy = None;
+    let mut explain_test_only = false;
+    let mut reject_slow_removals = false;
+    let mut tidy_imports = false;
+    let mut annotate_retained = false;
+    let mut strip_annotations = false;
+    let mut modified_paths: Vec<PathBuf> = Vec::new();
+
+    let (command, target) = match args.command {
+        // init: initializes project config (e.g., default path);
+        cli::Commands::Init { path, force } => {
+            let mut root: PathBuf = path.unwrap_or_else(|| PathBuf::from("."));
+            if root.is_file()
+                && let Some(parent) = root.parent()
+            {
+                root = parent.to_path_buf();
+            }
+            let path_written = Config::write_default_config_at(root.as_path(), force)?;
+            println!(
+                "{} .trait-winnower.toml at {}",
+                if force { "Overwrote" } else { "Initialized" },
+                path_written.display()
+            );
+            return Ok(());
+        }
+        cli::Commands::Prune { .. } if wants(cli::ReportFormat::Rustfix) => {
+            anyhow::bail!("--format rustfix needs `check`: `prune` applies its removals itself");
+        }
+        cli::Commands::Prune {
+            target,
+            print_modified: print,
+            dry_run: dry,
+            commit_per_removal,
+            commit_granularity,
+            commit_message: message,
+            output_patches,
+            single_patch,
+            record: record_to,
+            replay: replay_from,
+            incremental: mode,
+            keep_target_dir: keep,
+            assume_consistent: consistent,
+            batch_size: size,
+            max_candidates_per_file: max_candidates,
+            strategy: name,
+            explain_test_only: explain,
+            reject_slow_removals: reject_slow,
+            tidy_imports: tidy,
+            annotate_retained: annotate,
+            strip_annotations: strip,
+        } => {
+            print_modified = print;
+            dry_run = dry || wants(cli::ReportFormat::Suggestions);
+            commit = commit_granularity
+                .or(commit_per_removal.then_some(cli::CommitGranularity::Removal))
+                .filter(|_| !dry_run);
+            commit_message = message;
+            patches = output_patches.map(|dir| (dir, single_patch));
+            record = record_to;
+            replay = replay_from;
+            incremental = mode;
+            keep_target_dir = keep;
+            assume_consistent = consistent;
+            batch_size = size;
+            max_candidates_per_file = max_candidates;
+            strategy = name;
+            explain_test_only = explain;
+            reject_slow_removals = reject_slow;
+            tidy_imports = tidy;
+            annotate_retained = annotate;
+            strip_annotations = strip;
+            ("prune", target)
+        }
+        cli::Commands::Check { .. } if wants(cli::ReportFormat::Suggestions) => {
+            anyhow::bail!("--format suggestions needs `prune`: `check` removes nothing");
+        }
+        cli::Commands::Check { watch: true, .. }
+            if !matches!(format, cli::OutputFormat::Human | cli::OutputFormat::Short)
+                || !reports.is_empty() =>
+        {
+            anyhow::bail!("--watch prints findings as files change; it has no other format");
+        }
+        cli::Commands::Check { fix: true, .. }
+            if !matches!(format, cli::OutputFormat::Human | cli::OutputFormat::Short)
+                || !reports.is_empty() =>
+        {
+            anyhow::bail!("--fix prints a summary of its edits; it has no other format");
+        }
+        cli::Commands::Check {
+            target,
+            watch: w,
+            import_clippy: clippy,
+            fix: f,
+            dry_run: dry,
+        } => {
+            watch = w;
+            import_clippy = clippy;
+            fix = f;
+            dry_run = dry;
+            ("check", target)
+        }
+        // apply: applies a saved plan to a crate or workspace, validating once at the end.
+        cli::Commands::Apply {
+            plan,
+            target,
+            strict,
+        } => {
+            let root = crate_root(target, "apply")?;
+            let plan = Plan::load(&plan)?;
+            let options = ApplyOptions {
+                strict,
+                ignore_fingerprint: args.ignore_fingerprint,
+                ..ApplyOptions::default()
+            };
+            let applied = apply_plan_with(&root, &plan, &options)?;
+            if !args.quiet {
+                if let Some(drift) = &applied.drift {
+                    TraitInfo::show_drift(drift);
+                }
+                TraitInfo::show_apply(&applied);
+            }
+            if let Some(check) = applied.validation.as_ref().filter(|_| applied.reverted) {
+                return Err(WinnowerError::validation("cargo check", check).into());
+            }
+            return Ok(());
+        }
+        // recover: puts back the originals of trials an interrupted prune left on disk.
+        cli::Commands::Recover { target } => {
+            let root = crate_root(target, "recover")?;
+            let restored = recover(&root)?;
+            if !args.quiet {
+                TraitInfo::show_recovered(&restored);
+            }
+            return Ok(());
+        }
+        // doctor: every preflight check, reported whether or not it fails.
+        cli::Commands::Doctor { target } => {
+            let root = crate_root(target, "doctor")?;
+            let diagnoses = doctor(&root)?;
+            TraitInfo::show_doctor(&diagnoses);
+            let failed = diagnoses.iter().filter(|d| d.result.is_err()).count();
+            if failed > 0 {
+                anyhow::bail!("{failed} check(s) failed");
+            }
+            return Ok(());
+        }
+        // cache: the trial cache the target's configuration places, whether or not enabled.
+        cli::Commands::Cache { action } => {
+            let (cli::CacheAction::Stats { target } | cli::CacheAction::Clear { target }) = &action;
+            let root = crate_root(target.clone(), "cache")?;
+            let config = Config::load_or_default(&root)?
+                .trial_cache
+                .unwrap_or_default();
+            let cache = TrialCache::open(&root, &config);
+            match action {
+                cli::CacheAction::Stats { .. } => {
+                    let stats = cache.stats();
+                    println!(
+                        "{}: {} trial(s), {:.1} of {} MB",
+                        cache.dir().display(),
+                        stats.entries,
+                        stats.bytes as f64 / (1024.0 * 1024.0),
+                        config.max_cache_mb
+                    );
+                }
+                cli::CacheAction::Clear { .. } => {
+                    let removed = cache.clear()?;
+                    println!("removed {removed} trial(s) from {}", cache.dir().display());
+                }
+            }
+            return Ok(());
+        }
+        // hook: a git hook script, or the pre-commit framework's entry on stdout.
+        cli::Commands::Hook { action } => {
+            match action {
+                cli::HookAction::Install {
+                    target,
+                    stage,
+                    mode,
+                    framework: cli::HookFramework::PreCommit,
+                    ..
+                } => {
+                    let root = crate_root(target, "hook install")?;
+                    print!(
+                        "{}",
+                        hook::pre_commit_config(stage, mode, &hook::target_in_repo(&root)?)
+                    );
+                }
+                cli::HookAction::Install {
+                    target,
+                    stage,
+                    mode,
+                    framework: cli::HookFramework::Git,
+                    force,
+                } => {
+                    let root = crate_root(target, "hook install")?;
+                    let path = hook::install(&root, stage, mode, force)?;
+                    println!("installed {} hook at {}", stage.name(), path.display());
+                }
+                cli::HookAction::Uninstall {
+                    target,
+                    stage,
+                    force,
+                } => {
+                    let root = crate_root(target, "hook uninstall")?;
+                    match hook::uninstall(&root, stage, force)? {
+                        Some(path) => println!("removed {}", path.display()),
+                        None => println!("no {} hook installed", stage.name()),
+                    }
+                }
+            }
+            return Ok(());
+        }
+        // owners: `file -> package (dir), ...`, dirs relative to the workspace root.
+        cli::Commands::Owners { files, target } => {
+            let root = crate_root(target, "owners")?;
+            let cargo = Config::load_or_default(&root)?.cargo_check.cargo();
+            let map = CrateMap::from_metadata(&root, &cargo)?;
+            let workspace = map
+                .workspace_root()
+                .map(|w| w.canonicalize().unwrap_or_else(|_| w.to_path_buf()));
+            for file in files {
+                let owners: Vec<String> = map
+                    .owners_of(&file)
+                    .into_iter()
+                    .map(|o| {
+                        let dir = workspace
+                            .as_deref()
+                            .and_then(|w| o.manifest_dir.strip_prefix(w).ok())
+                            .unwrap_or(&o.manifest_dir);
+                        let dir = if dir.as_os_str().is_empty() {
+                            Path::new(".")
+                        } else {
+                            dir
+                        };
+                        format!("{} ({})", o.name, dir.display())
+                    })
+                    .collect();
+                let owners = if owners.is_empty() {
+                    "(no package)".to_owned()
+                } else {
+                    owners.join(", ")
+                };
+                println!("{} -> {owners}", file.display());
+            }
+            return Ok(());
+        }
+        // diff: each revision checked from its sources alone, compared item by item.
+        cli::Commands::Diff { from, to, target } => {
+            if !matches!(format, cli::OutputFormat::Human | cli::OutputFormat::Json) {
+                anyhow::bail!("diff prints text or --format json");
+            }
+            let root = crate_root(target, "diff")?;
+            let diff = diff_revisions(&root, &from, &to, |tree| {
+                Winnower::builder()
+                    .target(tree)
+                    .target_types([args.target_type])
+                    .only_traits(args.only_trait.clone())
+                    .build()?
+                    .check_static()
+            })?;
+            let mut stdout = std::io::stdout().lock();
+            if format == cli::OutputFormat::Json {
+                serde_json::to_writer_pretty(&mut stdout, &diff)?;
+                writeln!(stdout)?;
+            } else {
+                diff.write_text(&mut stdout)?;
+            }
+            return Ok(());
+        }
+        // explain-item: what is known about one item; with --trial, each candidate tried alone.
+        cli::Commands::ExplainItem {
+            item,
+            target,
+            trial,
+        } => {
+            if !matches!(format, cli::OutputFormat::Human | cli::OutputFormat::Json) {
+                anyhow::bail!("explain-item prints text or --format json");
+            }
+            let root = crate_root(target, "explain-item")?;
+            let winnower = Winnower::builder().target(&root).build()?;
+            let report = winnower.check_static()?;
+            let mut items = explain_item(&root, &report, &ItemPath::parse(&item))?;
+            if trial {
+                for item in &mut items {
+                    winnower.trial_item(item)?;
+                }
+            }
+            let mut stdout = std::io::stdout().lock();
+            if format == cli::OutputFormat::Json {
+                serde_json::to_writer_pretty(&mut stdout, &items)?;
+                writeln!(stdout)?;
+            } else {
+                for item in &items {
+                    item.write_text(&mut stdout)?;
+                }
+            }
+            return Ok(());
+        }
+        // impact: runs like prune, on copies; rustfix output needs `check`.
+        cli::Commands::Impact { .. } if wants(cli::ReportFormat::Rustfix) => {
+            anyhow::bail!("--format rustfix needs `check`: `impact` measures compile times");
+        }
+        cli::Commands::Impact {
+            target,
+            iterations,
+            build,
+        } => {
+            impact = Some(ImpactOptions { iterations, build });
+            ("impact", target)
+        }
+        // bench: every strategy on a copy of the fixture, as a table on stdout.
+        cli::Commands::Bench { fixture } => {
+            print!("{}", bench(&fixture, &bench::Strategy::ALL)?);
+            return Ok(());
+        }
+    };
+
+    let target_path = target.unwrap_or_else(|| PathBuf::from("."));
+    let mut builder = Winnower::builder()
+        .target(&target_path)
+        .target_types([args.target_type])
+        .brute_force(args.brute_force || impact.is_some())
+        .allow_dyn_compat_changes(args.allow_dyn_compat_changes)
+        .allow_unsafe_impl_pruning(args.allow_unsafe_impl_pruning)
+        .include_opaque_types(args.include_opaque_types)
+        .skip_blanket_impls(args.skip_blanket_impls)
+        .ignore_fingerprint(args.ignore_fingerprint)
+        .explain_test_only(explain_test_only)
+        .reject_slow_removals(reject_slow_removals)
+        .tidy_imports(tidy_imports)
+        .annotate_retained(annotate_retained)
+        .only_traits(args.only_trait.clone())
+        .sort_by(args.sort_by)
+        .min_weight(args.min_weight)
+        .keep_target_dir(keep_target_dir)
+        .no_ignore(args.no_ignore)
+        .assume_consistent(assume_consistent.unwrap_or(true))
+        .batch_size(batch_size.unwrap_or(1))
+        .dry_run(dry_run)
+        .import_clippy(import_clippy)
+        .limit(top);
+    if let Some(mode) = incremental {
+        builder = builder.incremental(mode);
+    }
+    if let Some(n) = max_candidates_per_file {
+        builder = builder.max_candidates_per_file(n);
+    }
+    if let Some(secs) = args.lock_wait_timeout {
+        builder = builder.lock_wait_timeout(secs);
+    }
+    if !args.quiet {
+        builder = builder.on_lock_wait(LockNotice::new(TraitInfo::show_lock_wait));
+    }
+    if let Some(name) = strategy {
+        builder = builder.strategy(name);
+    }
+    if let Some(rev) = &args.since {
+        builder = builder.since(rev);
+    }
+    if let Some(granularity) = commit {
+        builder = builder.commit_each(granularity);
+    }
+    if let Some(template) = commit_message {
+        builder = builder.commit_message(template);
+    }
+    if let Some((dir, single)) = patches {
+        builder = builder.output_patches(dir, single);
+    }
+    if let Some(path) = record {
+        builder = builder.record(path);
+    }
+    if let Some(path) = replay {
+        builder = builder.replay(path);
+    }
+    let winnower = builder.build()?;
+    let strategy = winnower.strategy();
+    if let Some(strategy) = &strategy {
+        reject_contradictions(strategy, args.brute_force, batch_size, assume_consistent)?;
+        if !args.quiet {
+            TraitInfo::show_strategy(strategy);
+        }
+    }
+    // prune --strip-annotations: comments out, a diff on stdout with --dry-run.
+    if strip_annotations {
+        let stripped = winnower.strip_annotations()?;
+        if dry_run {
+            let mut stdout = std::io::stdout().lock();
+            for change in &stripped {
+                write!(stdout, "{}", unified_diff(change))?;
+            }
+        }
+        if !args.quiet {
+            TraitInfo::show_stripped(&stripped, dry_run);
+        }
+        return Ok(());
+    }
+    let brute_force = strategy.map_or(args.brute_force, |s| s.trials);
+    if command == "prune" && brute_force {
+        offer_recovery(&target_path)?;
+    }
+    // prune --strategy static: what `check --fix` removes, so cargo never runs.
+    if strategy.is_some_and(|s| !s.trials)
+        && (!matches!(format, cli::OutputFormat::Human | cli::OutputFormat::Short)
+            || !reports.is_empty())
+    {
+        anyhow::bail!("--strategy static prints a summary of its edits; it has no other format");
+    }
+    fix |= strategy.is_some_and(|s| !s.trials);
+    if watch {
+        return watch_target(&winnower);
+    }
+    // check --fix: static fixes only, a diff on stdout with --dry-run.
+    if fix {
+        let fixed = winnower.fix()?;
+        if dry_run {
+            let mut stdout = std::io::stdout().lock();
+            for change in &fixed.changes {
+                write!(stdout, "{}", unified_diff(change))?;
+            }
+        }
+        if !args.quiet {
+            TraitInfo::show_fixed(&fixed, dry_run);
+        }
+        return Ok(());
+    }
+
+    let report: Report = match command {
+        // prune: prunes undue/overly-strong trait bounds while preserving correctness.
+        "prune" => {
+            let verbosity = if args.quiet { 0 } else { verbosity };
+            if let Some(v) = winnower.validation()
+                && brute_force
+                && !args.quiet
+            {
+                TraitInfo::show_validation_gaps(&v);
+                TraitInfo::show_validation_matrix(&v);
+            }
+            let pruned = winnower.prune_with(&mut ProgressLog::new(verbosity))?;
+            // Edits of others that `prune` stepped around are worth a warning even when quiet.
+            TraitInfo::show_external_edits(&pruned.external_edits);
+            if let Some(v) = &pruned.report.validation
+                && verbosity > 0
+            {
+                TraitInfo::show_toolchain(v);
+            }
+            if !args.quiet {
+                TraitInfo::show_skipped(&pruned.skipped, verbosity);
+                TraitInfo::show_shared(&pruned.shared);
+                show_not_utf8(&pruned.not_utf8);
+                if let Some(v) = &pruned.report.validation {
+                    TraitInfo::show_target_dir(v);
+                }
+                if let Some(drift) = &pruned.drift {
+                    TraitInfo::show_drift(drift);
+                }
+                if let Some(stats) = &pruned.cache {
+                    TraitInfo::show_cache(stats);
+                }
+                TraitInfo::show_prefilter(&pruned.report);
+                TraitInfo::show_memo_hits(&pruned.report);
+                TraitInfo::show_anchor_misses(
+                    &pruned.report,
+                    verbosity > 0 || args.anchor_diagnostics,
+                );
+                TraitInfo::show_truncated(&pruned.report);
+                TraitInfo::show_aborted(&pruned.report);
+                TraitInfo::show_trial_cache_hits(&pruned.report);
+                if let Some(log) = &pruned.commits {
+                    TraitInfo::show_commits(log);
+                }
+                TraitInfo::show_patches(&pruned.patches);
+                TraitInfo::show_unlocks(&pruned.report.unlocks);
+                TraitInfo::show_slow_removals(&pruned.report);
+                TraitInfo::show_tidied_imports(&pruned.report);
+                TraitInfo::show_derive_candidates(&pruned.report);
+                TraitInfo::show_follow_ups(&pruned.report);
+                TraitInfo::show_test_only(&pruned.report);
+                TraitInfo::show_annotated(pruned.annotated);
+                TraitInfo::show_not_validated(&pruned.report);
+                TraitInfo::show_breakdown(&Breakdown::of(&pruned.report));
+            }
+            if let Some(stderr) = &pruned.matrix_failure
+                && !args.quiet
+            {
+                TraitInfo::show_matrix_failure(stderr, verbosity);
+            }
+            if let Some(stderr) = &pruned.clippy_failure
+                && !args.quiet
+            {
+                TraitInfo::show_clippy_failure(stderr, verbosity);
+            }
+            if let Some(semver) = &pruned.semver
+                && !args.quiet
+            {
+                TraitInfo::show_semver(semver, verbosity);
+            }
+            modified_paths = pruned.modified;
+            pruned.report
+        }
+        // impact: prunes a copy and times builds of it and of another, unpruned copy.
+        "impact" => {
+            let verbosity = if args.quiet { 0 } else { verbosity };
+            let options = impact.unwrap_or_default();
+            let measured = winnower.impact(&options, &mut ProgressLog::new(verbosity))?;
+            if !args.quiet {
+                TraitInfo::show_skipped(&measured.skipped, verbosity);
+                TraitInfo::show_shared(&measured.shared);
+                show_not_utf8(&measured.not_utf8);
+                if let Some(impact) = &measured.report.impact
+                    && matches!(format, cli::OutputFormat::Human | cli::OutputFormat::Short)
+                {
+                    TraitInfo::show_impact(impact);
+                }
+            }
+            measured.report
+        }
+        // check: per-file items at -v and above (capped by --top), diagnostics by default.
+        _ => {
+            let checked = winnower.check_each(|file| {
+                if format == cli::OutputFormat::Human && !args.quiet {
+                    Diagnostics::write_file(
+                        file.report,
+                        file.source,
+                        &mut std::io::stdout().lock(),
+                    )?;
+                }
+                if wants(cli::ReportFormat::Rustfix) {
+                    rustfix_files.push((
+                        file.report.path.clone(),
+                        Rustfix::file(file.report, file.path, file.source)?,
+                    ));
+                }
+                if wants(cli::ReportFormat::LspJson) {
+                    lsp_files.push((
+                        file.report.path.clone(),
+                        Lsp::file(file.report, file.path, file.source),
+                    ));
+                }
+                // Short listing: locations at -v, signatures at -vv, full items at -vvv.
+                if format == cli::OutputFormat::Short && verbosity > 0 {
+                    let mut keys: Vec<_> = file.items.iter_all_items().collect();
+                    keys.sort_by_key(|k| (k.span().start().line, k.span().start().column));
+                    for key in keys.into_iter().take(top) {
+                        TraitInfo::show_item(&file.report.path, key);
+                        match verbosity {
+                            2 => TraitInfo::show_signature(key.item()),
+                            3.. => TraitInfo::debug_print_itemref(key.item()),
+                            _ => {}
+                        }
+                    }
+                }
+                Ok(())
+            })?;
+            if !args.quiet {
+                TraitInfo::show_missing_modules(&checked.missing_modules);
+                TraitInfo::show_skipped(&checked.skipped, verbosity);
+                TraitInfo::show_shared(&checked.shared);
+                show_not_utf8(&checked.not_utf8);
+            }
+            checked.report
+        }
+    };
+
+    lsp_files.sort_by(|a: &(PathBuf, Value), b| a.0.cmp(&b.0));
+    if !args.quiet {
+        TraitInfo::show_metrics(&report, verbosity);
+    }
+    // With --print-modified, stdout carries only the modified paths.
+    let mut out: Box<dyn Write> = if print_modified {
+        Box::new(std::io::stderr().lock())
+    } else {
+        Box::new(std::io::stdout().lock())
+    };
+    rustfix_files.sort_by(|a, b| a.0.cmp(&b.0));
+    let renderer = Renderer {
+        command,
+        target: &target_path,
+        winnower: &winnower,
+        report: &report,
+        lsp_files,
+        rustfix_files,
+    };
+    if let Some(report_format) = cli::ReportFormat::of_output(format) {
+        out.write_all(&renderer.render(report_format)?)?;
+    }
+    // Every file from the one report, so they agree with stdout and with each other.
+    for file in &reports {
+        write_atomic(&file.path, &renderer.render(file.format)?)
+            .with_context(|| format!("writing the {} report", file.format.name()))?;
+    }
+    if !args.quiet {
+        TraitInfo::show_reports_written(
+            reports.iter().map(|r| (r.format.name(), r.path.as_path())),
+        );
+    }
+
+    if print_modified {
+        let mut stdout = std::io::stdout().lock();
+        for p in &modified_paths {
+            let p = p.strip_prefix(".").unwrap_or(p);
+            writeln!(stdout, "{}", p.to_string_lossy().replace('\\', "/"))?;
+        }
+    }
+
+    if args.fail_on_findings {
+        match command {
+            "prune" | "impact" => {
+                let removed = report.count_removals(RemovalStatus::Removed);
+                if removed > 0 {
+                    anyhow::bail!("removed {removed} unnecessary bound(s)");
+                }
+            }
+            _ => {
+                let flagged = report.total_flagged();
+                if flagged > 0 {
+                    anyhow::bail!("{flagged} bound(s) flagged as likely unnecessary");
+                }
+            }
+        }
+    }
+    Ok(())
+}
+
+/// Renders the report of a run in each file format, for stdout and `--report` alike.
+struct Renderer<'a> {
+    command: &'a str,
+    target: &'a Path,
+    winnower: &'a Winnower,
+    report: &'a Report,
+    /// LSP diagnostics by file, sorted; collected only when a format asks for them.
+    lsp_files: Vec<(PathBuf, Value)>,
+    /// rustc diagnostics by file, sorted; collected only when a format asks for them.
+    rustfix_files: Vec<(PathBuf, Vec<Value>)>,
+}
+
+impl Renderer<'_> {
+    /// The report in `format`.
+    fn render(&self, format: cli::ReportFormat) -> anyhow::Result<Vec<u8>> {
+        let report = self.report;
+        let mut out = Vec::new();
+        match format {
+            cli::ReportFormat::Junit => Junit::write(report, &mut out)?,
+            cli::ReportFormat::Json => Json::write(report, self.command, &mut out)?,
+            cli::ReportFormat::LspJson => {
+                Value::Array(self.lsp_files.iter().map(|(_, v)| v.clone()).collect())
+                    .write(&mut out)?
+            }
+            cli::ReportFormat::Rustfix => {
+                for diagnostic in self.rustfix_files.iter().flat_map(|(_, d)| d) {
+                    diagnostic.write_line(&mut out)?;
+                }
+            }
+            cli::ReportFormat::Suggestions => {
+                // Report paths are relative to the crate or workspace root, or as given
+                // for a single file; suggestions want them relative to the top of the
+                // repository.
+                let root = match self.winnower.kind() {
+                    TargetKind::SingleFile(_) => PathBuf::new(),
+                    kind => kind.path().to_path_buf(),
+                };
+                let in_git = if root.as_os_str().is_empty() {
+                    Path::new(".")
+                } else {
+                    &root
+                };
+                let prefix = hook::target_in_repo(in_git).unwrap_or_else(|_| ".".to_owned());
+                Suggestions::write(report, &root, Path::new(&prefix), &mut out)?
+            }
+            cli::ReportFormat::Html => {
+                let summary = HtmlSummary {
+                    command: self.command,
+                    target: self.target,
+                    config: self.winnower.config(),
+                };
+                Html::write(report, &summary, &mut out)?;
+            }
+            cli::ReportFormat::Markdown => {
+                Markdown::write(report, self.command, self.target, &mut out)?
+            }
+        }
+        Ok(out)
+    }
+}
+
+/// Set by the SIGINT handler [`stop_on_interrupt`] installs.
+#[cfg(unix)]
+static INTERRUPTED: std::sync::atomic::AtomicBool = std::sync::atomic::AtomicBool::new(false);
+
+/// Cancel `cancel` on Ctrl-C instead of dying, so the caller can finish its output.
+fn stop_on_interrupt(cancel: &CancelToken) {
+    #[cfg(unix)]
+    {
+        use std::sync::atomic::Ordering;
+        extern "C" fn on_sigint(_: libc::c_int) {
+            INTERRUPTED.store(true, Ordering::Relaxed);
+        }
+        // SAFETY: the handler only stores to an atomic, which is async-signal-safe.
+        unsafe {
+            libc::signal(
+                libc::SIGINT,
+                on_sigint as extern "C" fn(libc::c_int) as libc::sighandler_t,
+            );
+        }
+        let cancel = cancel.clone();
+        std::thread::spawn(move || {
+            while !INTERRUPTED.load(Ordering::Relaxed) {
+                std::thread::sleep(std::time::Duration::from_millis(50));
+            }
+            cancel.cancel();
+        });
+    }
+    #[cfg(not(unix))]
+    let _ = cancel;
+}
+
+/// `check --watch`: each file's findings as it is saved, until Ctrl-C. On a terminal,
+/// diagnostics under a live summary line; otherwise one line per event.
+fn watch_target(winnower: &Winnower) -> anyhow::Result<()> {
+    let cancel = CancelToken::new();
+    stop_on_interrupt(&cancel);
+    let tty = std::io::stdout().is_terminal();
+    let stdout = std::io::stdout();
+    let shown = |p: &Path| p.to_string_lossy().replace('\\', "/");
+    watch::watch(winnower, watch::DEBOUNCE, &cancel, |event, summary| {
+        let mut out = stdout.lock();
+        let totals = format!(
+            "{} finding(s) in {} file(s)",
+            summary.findings, summary.files
+        );
+        if tty {
+            eprint!("\r\x1b[2K");
+        }
+        match event {
+            WatchEvent::Ready if !tty => writeln!(out, "watching {totals}")?,
+            WatchEvent::Ready => {}
+            WatchEvent::Checked { report, source } if tty => {
+                if !report.findings.is_empty() {
+                    Diagnostics::write_file(report, source, &mut out)?;
+                }
+            }
+            WatchEvent::Checked { report, .. } => writeln!(
+                out,
+                "checked {}: {} finding(s); {totals}",
+                shown(&report.path),
+                report.findings.len()
+            )?,
+            WatchEvent::Removed { path } => {
+                writeln!(out, "removed {}; {totals}", shown(path))?;
+            }
+            WatchEvent::Failed { path, error } if tty => {
+                eprintln!("error: {}: {error}", shown(path));
+            }
+            WatchEvent::Failed { path, error } => {
+                let error = error.to_string().replace('\n', " ");
+                writeln!(out, "failed {}: {error}; {totals}", shown(path))?;
+            }
+        }
+        out.flush()?;
+        if tty {
+            eprint!("watching: {totals} (Ctrl-C to stop)");
+        }
+        Ok(())
+    })?;
+    if tty {
+        eprintln!();
+    }
+    Ok(())
+}
+
+/// The crate or workspace root `target` names; `command` needs one.
+fn crate_root(target: Option<PathBuf>, command: &str) -> anyhow::Result<PathBuf> {
+    match TargetKind::get_target(target)? {
+        TargetKind::SingleFile(path) => Err(WinnowerError::Target {
+            path,
+            detail: format!("{command} requires a crate or workspace root"),
+        }
+        .into()),
+        kind => Ok(kind.path().to_path_buf()),
+    }
+}
+
+/// Fail when a flag asks `prune` for something other than what its `--strategy` decides.
+fn reject_contradictions(
+    strategy: &Strategy,
+    brute_force: bool,
+    batch_size: Option<usize>,
+    assume_consistent: Option<bool>,
+) -> anyhow::Result<()> {
+    let name = strategy.name;
+    if brute_force && !strategy.trials {
+        anyhow::bail!("--strategy {name} runs no trials; drop --brute-force");
+    }
+    if let Some(n) = batch_size.filter(|&n| n.max(1) != strategy.batch_size) {
+        anyhow::bail!(
+            "--batch-size {n} contradicts --strategy {name}, which uses {}; set `batch_size` \
+             under [strategy.{name}] instead",
+            strategy.batch_size
+        );
+    }
+    if let Some(on) = assume_consistent.filter(|&on| on != strategy.assume_consistent) {
+        anyhow::bail!(
+            "--assume-consistent {on} contradicts --strategy {name}, which uses {}; set \
+             `assume_consistent` under [strategy.{name}] instead",
+            strategy.assume_consistent
+        );
+    }
+    Ok(())
+}
+
+/// At a terminal, offer to restore the files an interrupted run left mid-trial. Declined,
+/// or without a terminal, `prune` refuses to start and points at `trait-winnower recover`.
+fn offer_recovery(target: &Path) -> anyhow::Result<()> {
+    if !(std::io::stdin().is_terminal() && std::io::stderr().is_terminal()) {
+        return Ok(());
+    }
+    let Ok(root) = crate_root(Some(target.to_path_buf()), "prune") else {
+        return Ok(());
+    };
+    let pending = Journal::pending(&root)?;
+    if pending.is_empty() {
+        return Ok(());
+    }
+    eprintln!("an interrupted run left trial edits in:");
+    for entry in &pending {
+        eprintln!("  {}", entry.path.display());
+    }
+    eprint!("restore the originals now? [y/N] ");
+    let mut answer = String::new();
+    std::io::stdin().read_line(&mut answer)?;
+    if answer.trim().eq_ignore_ascii_case("y") {
+        TraitInfo::show_recovered(&recover(&root)?);
+    }
+    Ok(())
+}
+
+/// Warn about discovered files skipped because they are not UTF-8.
+fn show_not_utf8(skipped: &[NonUtf8Source]) {
+    for e in skipped {
+        eprintln!("skipping: {e}");
+    }
+}
+
+/// The root of the workspace the current directory is in, as `cargo locate-project` finds
+/// it; `None` outside one or when cargo cannot tell.
+pub fn workspace_root() -> Option<PathBuf> {
+    let out = Command::new(cargo_program(None))
+        .args(["locate-project", "--workspace", "--message-format", "plain"])
+        .output()
+        .ok()
+        .filter(|out| out.status.success())?;
+    let manifest = PathBuf::from(String::from_utf8(out.stdout).ok()?.trim());
+    manifest.parent().map(Path::to_path_buf)
+}
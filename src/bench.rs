@@ -0,0 +1,329 @@
+// src/bench.rs
+//! Compare pruning strategies on a fixture crate without building it.
+//!
+//! Each [`Strategy`] prunes a fresh copy of the fixture, validated by an [`Oracle`] that
+//! reads the answer off the fixture itself: a trial fails when it touches the bounds of an
+//! item documented `/// bench: keep`. Checks then cost nothing, so what [`bench()`]
+//! measures is how many checks a strategy asks for and the time it spends around them.
+
+#![deny(missing_docs)]
+
+use crate::config::Config;
+use crate::dynamic_analysis::common::ValidationOutcome;
+use crate::dynamic_analysis::validate::Validator;
+use crate::error::{TraitError, WinnowerError};
+use crate::report::RemovalStatus;
+use crate::winnower::Winnower;
+use quote::ToTokens;
+use std::cell::Cell;
+use std::fmt;
+use std::path::{Path, PathBuf};
+use std::rc::Rc;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::time::Duration;
+use syn::visit::{self, Visit};
+
+/// Marks, in a doc comment, a fixture item whose bounds must all stay.
+pub const KEEP_MARKER: &str = "bench: keep";
+
+/// How `prune` goes about its trials.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Strategy {
+    /// Shown in the table.
+    pub name: &'static str,
+    /// Files whose removals share a check (see [`Winnower`]'s `batch_size`); 1 checks
+    /// every candidate alone.
+    pub batch_size: usize,
+    /// Keep bounds a body plainly uses without a check.
+    pub prefilter: bool,
+    /// Keep bounds whose removal was rejected elsewhere in the same impl or trait.
+    pub memo: bool,
+}
+
+impl Strategy {
+    /// The strategies `trait-winnower bench` compares.
+    pub const ALL: [Strategy; 6] = [
+        Strategy::new("one-by-one", 1, false, false),
+        Strategy::new("prefilter", 1, true, false),
+        Strategy::new("memo", 1, false, true),
+        Strategy::new("prefilter+memo", 1, true, true),
+        Strategy::new("batch-bisect", 8, false, false),
+        Strategy::new("batch-bisect+prefilter+memo", 8, true, true),
+    ];
+
+    const fn new(name: &'static str, batch_size: usize, prefilter: bool, memo: bool) -> Self {
+        Self {
+            name,
+            batch_size,
+            prefilter,
+            memo,
+        }
+    }
+}
+
+/// What one strategy did to the fixture.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct BenchRow {
+    /// The strategy.
+    pub strategy: Strategy,
+    /// Bounds considered for removal.
+    pub candidates: usize,
+    /// Of those, the ones a check had to decide: neither prefiltered nor memoized.
+    pub trialed: usize,
+    /// Checks run.
+    pub checks: usize,
+    /// Bounds removed; the same for every strategy unless one is unsound.
+    pub removed: usize,
+    /// Wall time outside checks.
+    pub overhead: Duration,
+}
+
+/// The rows of a [`bench()`] run, in [`Strategy::ALL`] order.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct BenchReport {
+    /// One per strategy.
+    pub rows: Vec<BenchRow>,
+}
+
+impl BenchReport {
+    /// The row of the strategy called `name`.
+    pub fn row(&self, name: &str) -> Option<&BenchRow> {
+        self.rows.iter().find(|r| r.strategy.name == name)
+    }
+}
+
+impl fmt::Display for BenchReport {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let width = self
+            .rows
+            .iter()
+            .map(|r| r.strategy.name.len())
+            .max()
+            .unwrap_or(0)
+            .max("strategy".len());
+        writeln!(
+            f,
+            "{:<width$}  {:>10}  {:>7}  {:>6}  {:>7}  {:>9}",
+            "strategy", "candidates", "trialed", "checks", "removed", "overhead"
+        )?;
+        for row in &self.rows {
+            writeln!(
+                f,
+                "{:<width$}  {:>10}  {:>7}  {:>6}  {:>7}  {:>7.1}ms",
+                row.strategy.name,
+                row.candidates,
+                row.trialed,
+                row.checks,
+                row.removed,
+                row.overhead.as_secs_f64() * 1000.0
+            )?;
+        }
+        Ok(())
+    }
+}
+
+/// Accepts a tree while every item of the fixture marked [`KEEP_MARKER`] keeps all its
+/// bounds.
+#[derive(Debug, Clone, Default)]
+pub struct Oracle {
+    /// The generics of marked items, by file relative to the root.
+    kept: Vec<(PathBuf, Vec<String>)>,
+    /// Validations asked for, shared with clones.
+    calls: Rc<Cell<usize>>,
+}
+
+impl Oracle {
+    /// The oracle of the `.rs` files of the fixture at `root`.
+    pub fn new(root: &Path) -> TraitError<Self> {
+        let mut kept = Vec::new();
+        for path in rust_files(root)? {
+            let generics = kept_generics(&path)?;
+            if !generics.is_empty() {
+                let rel = path.strip_prefix(root).unwrap_or(&path).to_path_buf();
+                kept.push((rel, generics));
+            }
+        }
+        Ok(Self {
+            kept,
+            calls: Rc::default(),
+        })
+    }
+
+    /// How many validations this oracle and its clones answered.
+    pub fn calls(&self) -> usize {
+        self.calls.get()
+    }
+
+    /// The same oracle, counting from zero.
+    fn fresh(&self) -> Self {
+        Self {
+            kept: self.kept.clone(),
+            calls: Rc::default(),
+        }
+    }
+}
+
+impl Validator for Oracle {
+    fn validate(&self, root: &Path) -> TraitError<ValidationOutcome> {
+        self.calls.set(self.calls.get() + 1);
+        let mut broken = None;
+        for (rel, generics) in &self.kept {
+            if kept_generics(&root.join(rel))? != *generics {
+                broken = Some(rel);
+                break;
+            }
+        }
+        Ok(ValidationOutcome {
+            success: broken.is_none(),
+            code: Some(i32::from(broken.is_some())),
+            stderr: broken
+                .map(|rel| format!("a kept bound of {} went", rel.display()))
+                .unwrap_or_default(),
+            ..ValidationOutcome::default()
+        })
+    }
+}
+
+/// The generics, where clause included, of every item in `path` marked [`KEEP_MARKER`],
+/// in source order.
+fn kept_generics(path: &Path) -> TraitError<Vec<String>> {
+    struct Marked(Vec<String>);
+
+    impl Marked {
+        fn visit(&mut self, attrs: &[syn::Attribute], generics: &syn::Generics) {
+            let marked = attrs.iter().any(|a| {
+                a.path().is_ident("doc") && a.to_token_stream().to_string().contains(KEEP_MARKER)
+            });
+            if marked {
+                let clause = &generics.where_clause;
+                self.0.push(quote::quote!(#generics #clause).to_string());
+            }
+        }
+    }
+
+    impl<'ast> Visit<'ast> for Marked {
+        fn visit_item_fn(&mut self, node: &'ast syn::ItemFn) {
+            self.visit(&node.attrs, &node.sig.generics);
+            visit::visit_item_fn(self, node);
+        }
+        fn visit_impl_item_fn(&mut self, node: &'ast syn::ImplItemFn) {
+            self.visit(&node.attrs, &node.sig.generics);
+            visit::visit_impl_item_fn(self, node);
+        }
+        fn visit_trait_item_fn(&mut self, node: &'ast syn::TraitItemFn) {
+            self.visit(&node.attrs, &node.sig.generics);
+            visit::visit_trait_item_fn(self, node);
+        }
+        fn visit_item_struct(&mut self, node: &'ast syn::ItemStruct) {
+            self.visit(&node.attrs, &node.generics);
+            visit::visit_item_struct(self, node);
+        }
+        fn visit_item_impl(&mut self, node: &'ast syn::ItemImpl) {
+            self.visit(&node.attrs, &node.generics);
+            visit::visit_item_impl(self, node);
+        }
+        fn visit_item_trait(&mut self, node: &'ast syn::ItemTrait) {
+            self.visit(&node.attrs, &node.generics);
+            visit::visit_item_trait(self, node);
+        }
+    }
+
+    let src = std::fs::read_to_string(path).map_err(|e| WinnowerError::io("reading", path, e))?;
+    let file = syn::parse_file(&src).map_err(|e| WinnowerError::parse(path, e))?;
+    let mut marked = Marked(Vec::new());
+    marked.visit_file(&file);
+    Ok(marked.0)
+}
+
+/// Prune a copy of the fixture crate at `fixture` with each of `strategies`.
+pub fn bench(fixture: &Path, strategies: &[Strategy]) -> TraitError<BenchReport> {
+    let oracle = Oracle::new(fixture)?;
+    static RUNS: AtomicUsize = AtomicUsize::new(0);
+    let run_id = RUNS.fetch_add(1, Ordering::Relaxed);
+    let scratch = std::env::temp_dir().join(format!(
+        "trait-winnower-bench-{}-{run_id}",
+        std::process::id()
+    ));
+    std::fs::create_dir_all(&scratch).map_err(|e| WinnowerError::io("creating", &scratch, e))?;
+    let mut report = BenchReport::default();
+    for (i, &strategy) in strategies.iter().enumerate() {
+        let copy = scratch.join(i.to_string());
+        copy_dir(fixture, &copy)?;
+        let row = run(&copy, strategy, oracle.fresh());
+        std::fs::remove_dir_all(&copy).map_err(|e| WinnowerError::io("removing", &copy, e))?;
+        report.rows.push(row?);
+    }
+    std::fs::remove_dir(&scratch).map_err(|e| WinnowerError::io("removing", &scratch, e))?;
+    Ok(report)
+}
+
+fn run(root: &Path, strategy: Strategy, oracle: Oracle) -> TraitError<BenchRow> {
+    let calls = Rc::clone(&oracle.calls);
+    let config = Config {
+        prefilter: strategy.prefilter,
+        ..Config::load_or_default(root)?
+    };
+    let pruned = Winnower::builder()
+        .target(root)
+        .config(config)
+        .validator(oracle)
+        .brute_force(true)
+        .assume_consistent(strategy.memo)
+        .batch_size(strategy.batch_size)
+        .build()?
+        .prune()?;
+    let report = &pruned.report;
+    // One-by-one runs also list the bounds each pass over a kind of item skipped; the
+    // prefilter's are skipped too, but were candidates.
+    let candidates = report
+        .files
+        .iter()
+        .flat_map(|f| &f.removals)
+        .filter(|r| r.status != RemovalStatus::Skipped || r.finding.statically_used.is_some())
+        .count();
+    Ok(BenchRow {
+        strategy,
+        candidates,
+        trialed: candidates - report.total_prefiltered() - report.total_memo_hits(),
+        checks: calls.get(),
+        removed: report.count_removals(RemovalStatus::Removed),
+        overhead: report.wall_time.saturating_sub(report.total_check_time()),
+    })
+}
+
+/// The `.rs` files under `root`, leaving out `target/`.
+fn rust_files(root: &Path) -> TraitError<Vec<PathBuf>> {
+    let mut files = Vec::new();
+    let mut dirs = vec![root.to_path_buf()];
+    while let Some(dir) = dirs.pop() {
+        let entries = std::fs::read_dir(&dir).map_err(|e| WinnowerError::io("reading", &dir, e))?;
+        for entry in entries.flatten() {
+            let path = entry.path();
+            if path.is_dir() && path != root.join("target") {
+                dirs.push(path);
+            } else if path.extension().is_some_and(|e| e == "rs") {
+                files.push(path);
+            }
+        }
+    }
+    files.sort();
+    Ok(files)
+}
+
+/// Copy `from` to `to`, leaving out `target/`.
+fn copy_dir(from: &Path, to: &Path) -> TraitError<()> {
+    std::fs::create_dir_all(to).map_err(|e| WinnowerError::io("creating", to, e))?;
+    let entries = std::fs::read_dir(from).map_err(|e| WinnowerError::io("reading", from, e))?;
+    for entry in entries.flatten() {
+        let path = entry.path();
+        let copy = to.join(entry.file_name());
+        if path.is_dir() {
+            if entry.file_name() != "target" {
+                copy_dir(&path, &copy)?;
+            }
+        } else {
+            std::fs::copy(&path, &copy).map_err(|e| WinnowerError::io("copying", &path, e))?;
+        }
+    }
+    Ok(())
+}
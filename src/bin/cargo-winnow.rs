@@ -0,0 +1,16 @@
+// src/bin/cargo-winnow.rs
+//! `cargo winnow`: the Trait Winnower CLI as a cargo subcommand. Targets default to the
+//! root of the workspace cargo is run in rather than `.`.
+
+#![deny(missing_docs)]
+
+use std::process::ExitCode;
+use trait_winnower::{app, cli};
+
+fn main() -> ExitCode {
+    let mut args = cli::Cli::parse_cargo_winnow(std::env::args_os());
+    if let Some(root) = app::workspace_root() {
+        args.command.default_target(&root);
+    }
+    app::main(args)
+}
@@ -2,28 +2,117 @@
 
 #![deny(missing_docs)]
 
-use clap::{Parser, Subcommand, ValueEnum};
-use std::path::PathBuf;
-
-/// Target types for pruning trait bounds.
-#[derive(Debug, Clone, ValueEnum)]
-pub enum TargetType {
-    /// Prune all types of trait bounds (default).
-    All,
-    /// Prune function trait bounds.
-    Function,
-    /// Prune impl trait bounds.
-    Impl,
-    /// Prune trait trait bounds.
-    Trait,
-    /// Prune trait method trait bounds.
-    TraitMethod,
-    /// Prune impl method trait bounds.
-    ImplMethod,
-    /// Prune enum trait bounds.
-    Enum,
-    /// Prune struct trait bounds.
-    Struct,
+use clap::{CommandFactory, FromArgMatches, Parser, Subcommand, ValueEnum};
+use std::ffi::OsString;
+use std::path::{Path, PathBuf};
+
+pub use crate::commit::CommitGranularity;
+pub use crate::dynamic_analysis::incremental::Incremental;
+pub use crate::hook::{HookMode, HookStage};
+pub use crate::references::SortBy;
+pub use crate::strategy::StrategyName;
+pub use crate::target::TargetType;
+
+/// Output formats for reports.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
+pub enum OutputFormat {
+    /// Compiler-style warnings with source snippets (default).
+    Human,
+    /// Terse item labels, listed at `-v 2` and above.
+    Short,
+    /// JUnit XML, one test suite per file and one failed test case per finding.
+    Junit,
+    /// JSON document with findings, removals and timing metrics.
+    Json,
+    /// LSP `publishDiagnostics` JSON: 0-based lines, UTF-16 columns.
+    LspJson,
+    /// rustc JSON diagnostics with suggestions, one per line, for `cargo fix` and other
+    /// rustfix consumers; `check` only.
+    Rustfix,
+    /// GitHub pull request suggestions for what `prune` removes, as a JSON array of review
+    /// comments; implies `prune --dry-run`.
+    Suggestions,
+}
+
+/// Formats `--report` writes to a file.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
+pub enum ReportFormat {
+    /// JUnit XML, as `--format junit`.
+    Junit,
+    /// The JSON report, as `--format json`.
+    Json,
+    /// LSP diagnostics, as `--format lsp-json`.
+    LspJson,
+    /// rustc JSON diagnostics, as `--format rustfix`; `check` only.
+    Rustfix,
+    /// Pull request suggestions, as `--format suggestions`; implies `prune --dry-run`.
+    Suggestions,
+    /// A self-contained HTML report.
+    Html,
+    /// A Markdown summary, with removals by package and by trait.
+    Markdown,
+}
+
+impl ReportFormat {
+    /// The name `--report` takes, e.g. `lsp-json`.
+    pub fn name(self) -> &'static str {
+        match self {
+            ReportFormat::Junit => "junit",
+            ReportFormat::Json => "json",
+            ReportFormat::LspJson => "lsp-json",
+            ReportFormat::Rustfix => "rustfix",
+            ReportFormat::Suggestions => "suggestions",
+            ReportFormat::Html => "html",
+            ReportFormat::Markdown => "markdown",
+        }
+    }
+
+    /// The file format `--format` prints to stdout as well, if any.
+    pub fn of_output(format: OutputFormat) -> Option<Self> {
+        match format {
+            OutputFormat::Human | OutputFormat::Short => None,
+            OutputFormat::Junit => Some(Self::Junit),
+            OutputFormat::Json => Some(Self::Json),
+            OutputFormat::LspJson => Some(Self::LspJson),
+            OutputFormat::Rustfix => Some(Self::Rustfix),
+            OutputFormat::Suggestions => Some(Self::Suggestions),
+        }
+    }
+}
+
+/// A report `--report FORMAT=FILE` asks for.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ReportFile {
+    /// The format.
+    pub format: ReportFormat,
+    /// Where to write it.
+    pub path: PathBuf,
+}
+
+impl ReportFile {
+    /// Parse `FORMAT=FILE`.
+    pub fn parse(spec: &str) -> Result<Self, String> {
+        let Some((format, path)) = spec.split_once('=') else {
+            return Err(format!("expected FORMAT=FILE, got `{spec}`"));
+        };
+        let format = ReportFormat::from_str(format, true).map_err(|_| {
+            let names: Vec<&str> = ReportFormat::value_variants()
+                .iter()
+                .map(|f| f.name())
+                .collect();
+            format!(
+                "unknown report format `{format}`; expected one of {}",
+                names.join(", ")
+            )
+        })?;
+        if path.is_empty() {
+            return Err(format!("no file for the {} report", format.name()));
+        }
+        Ok(Self {
+            format,
+            path: PathBuf::from(path),
+        })
+    }
 }
 
 /// Reduce unnecessary Rust trait requirements.
@@ -50,10 +139,35 @@ pub struct Cli {
     #[arg(short, long, global = true)]
     pub quiet: bool,
 
+    /// After `prune`, explain each edit whose anchor matched no node: what it looked for
+    /// and the nodes nearest it. Implied by -v.
+    #[arg(long, global = true)]
+    pub anchor_diagnostics: bool,
+
     /// Brute force removal of trait bounds.
     #[arg(short, long, global = true)]
     pub brute_force: bool,
 
+    /// Also try removing `where Self: Sized` on traits and trait methods, which can change
+    /// whether a trait is dyn-compatible.
+    #[arg(long, global = true)]
+    pub allow_dyn_compat_changes: bool,
+
+    /// Also try removing the bounds of `unsafe impl` blocks, such as `T: Send` in
+    /// `unsafe impl<T: Send> Send for Wrapper<T>`, which may be all that keeps them sound.
+    #[arg(long, global = true)]
+    pub allow_unsafe_impl_pruning: bool,
+
+    /// Also try removing bounds of return-position `impl Trait` and `dyn Trait` types,
+    /// which changes the public type of the item.
+    #[arg(long, global = true)]
+    pub include_opaque_types: bool,
+
+    /// Leave the bounds of blanket impls (`impl<T: Tr> Other for &T`) alone in `prune`;
+    /// they decide which types the impl covers.
+    #[arg(long, global = true)]
+    pub skip_blanket_impls: bool,
+
     /// Show only the top N trait bounds.
     #[arg(short, long, global = true)]
     pub number_of_items: Option<String>,
@@ -68,11 +182,96 @@ pub struct Cli {
     )]
     pub target_type: TargetType,
 
+    /// Only look at bounds naming this trait, by path or last segment (`Iterator` matches
+    /// `Iterator<Item = u32>`); repeat for several.
+    #[arg(long = "only-trait", value_name = "TRAIT", global = true)]
+    pub only_trait: Vec<String>,
+
+    /// Order findings, and the files `prune` works through: by location, or heaviest
+    /// first, going by how many times the workspace refers to each item.
+    #[arg(long, value_enum, default_value = "location", global = true)]
+    pub sort_by: SortBy,
+
+    /// Leave out the bounds of items the workspace refers to fewer than N times.
+    #[arg(long, value_name = "N", default_value_t = 0, global = true)]
+    pub min_weight: usize,
+
+    /// Output format.
+    #[arg(long, value_enum, default_value = "human", global = true)]
+    pub format: OutputFormat,
+
+    /// Also write a report in FORMAT to FILE, from the same run as stdout's `--format`;
+    /// repeatable, e.g. `--report json=winnow.json --report junit=winnow.xml`. Formats:
+    /// junit, json, lsp-json, rustfix, suggestions, html, markdown. Missing parent
+    /// directories are created.
+    #[arg(
+        long = "report",
+        value_name = "FORMAT=FILE",
+        value_parser = ReportFile::parse,
+        global = true
+    )]
+    pub reports: Vec<ReportFile>,
+
+    /// Also write a self-contained HTML report to this path; same as `--report html=FILE`.
+    #[arg(long, value_name = "FILE", global = true)]
+    pub report_html: Option<PathBuf>,
+
+    /// Also write a Markdown summary, with removals by package and by trait, to this path;
+    /// same as `--report markdown=FILE`.
+    #[arg(long, value_name = "FILE", global = true)]
+    pub report_markdown: Option<PathBuf>,
+
+    /// Only look at files git sees changed since REV, committed or not, and untracked
+    /// files.
+    #[arg(long, value_name = "REV", global = true)]
+    pub since: Option<String>,
+
+    /// Exit with 1 when `check` flags a bound with a static rule or `prune` removes one.
+    #[arg(long, global = true)]
+    pub fail_on_findings: bool,
+
+    /// Trust `--incremental` caches and plans recorded with another `Cargo.lock` or rustc;
+    /// by default such a cache is bypassed and such a plan applied with a warning.
+    #[arg(long, global = true)]
+    pub ignore_fingerprint: bool,
+
+    /// Also analyze the files `.gitignore`, `.ignore` and git's exclude files leave out;
+    /// overrides `respect_gitignore`.
+    #[arg(long, global = true)]
+    pub no_ignore: bool,
+
+    /// Stop when a `cargo check` has waited more than SECS seconds for a file lock another
+    /// cargo process holds, such as rust-analyzer's on the target directory; overrides
+    /// `lock_wait_timeout` under [cargo_check].
+    #[arg(long, value_name = "SECS", global = true)]
+    pub lock_wait_timeout: Option<u64>,
+
     /// Subcommand to run.
     #[command(subcommand)]
     pub command: Commands,
 }
 
+impl Cli {
+    /// Parse `args` the way cargo passes them to `cargo-winnow` for `cargo winnow`: the
+    /// binary, then `winnow`, then the user's arguments. Help and errors name
+    /// `cargo winnow`. Exits on bad arguments, like [`Parser::parse_from`].
+    pub fn parse_cargo_winnow<I, T>(args: I) -> Self
+    where
+        I: IntoIterator<Item = T>,
+        T: Into<OsString>,
+    {
+        let mut args: Vec<OsString> = args.into_iter().map(Into::into).collect();
+        if args.get(1).is_some_and(|a| a == "winnow") {
+            args.remove(1);
+        }
+        let matches = Self::command()
+            .name("cargo-winnow")
+            .bin_name("cargo winnow")
+            .get_matches_from(args);
+        Self::from_arg_matches(&matches).unwrap_or_else(|e| e.exit())
+    }
+}
+
 /// Top-level subcommands supported by the CLI.
 #[derive(Subcommand, Debug)]
 pub enum Commands {
@@ -91,11 +290,351 @@ pub enum Commands {
     Prune {
         /// Target to operate on. Defaults to ".".
         target: Option<PathBuf>,
+
+        /// Print only the paths of files left modified, one per line; all other
+        /// output goes to stderr.
+        #[arg(long)]
+        print_modified: bool,
+
+        /// Validate removals, then put every file back: report what would be removed
+        /// without changing anything.
+        #[arg(long)]
+        dry_run: bool,
+
+        /// Commit each removal kept to git, once the run is done; the work tree must be
+        /// clean. Short for `--commit-granularity removal`.
+        #[arg(long, conflicts_with_all = ["dry_run", "commit_granularity"])]
+        commit_per_removal: bool,
+
+        /// Commit the removals kept to git, one commit per removal, file or run; the work
+        /// tree must be clean.
+        #[arg(
+            long,
+            value_enum,
+            value_name = "GRANULARITY",
+            conflicts_with = "dry_run"
+        )]
+        commit_granularity: Option<CommitGranularity>,
+
+        /// Commit message template; `{bound}`, `{item}`, `{file}` and `{count}` are filled
+        /// in. Defaults to `winnow: remove `{bound}` from {item} in {file}` per removal.
+        #[arg(long, value_name = "TEMPLATE")]
+        commit_message: Option<String>,
+
+        /// Prune a temporary copy instead of the tree and write what it removed into DIR as
+        /// one `git apply`-able patch per modified file, numbered in path order.
+        #[arg(
+            long,
+            value_name = "DIR",
+            conflicts_with_all = ["dry_run", "commit_per_removal", "commit_granularity"]
+        )]
+        output_patches: Option<PathBuf>,
+
+        /// With `--output-patches`, write one `winnow.patch` covering every file.
+        #[arg(long, requires = "output_patches")]
+        single_patch: bool,
+
+        /// Save every validation (arguments, source hashes, outcome) to FILE, to reproduce
+        /// the run elsewhere with `--replay`.
+        #[arg(long, value_name = "FILE", conflicts_with = "replay")]
+        record: Option<PathBuf>,
+
+        /// Answer validations from a recording made with `--record` instead of running
+        /// cargo; fails on a validation the recording does not hold.
+        #[arg(long, value_name = "FILE")]
+        replay: Option<PathBuf>,
+
+        /// Skip files unchanged since the last run: with `safe`, only while no file of the
+        /// crate changed; with `aggressive`, whenever the file itself did not.
+        #[arg(
+            long,
+            value_enum,
+            value_name = "MODE",
+            num_args = 0..=1,
+            require_equals = true,
+            default_missing_value = "safe"
+        )]
+        incremental: Option<Incremental>,
+
+        /// Keep the directory validation builds in for the next run; `false` removes it
+        /// once the run is done.
+        #[arg(long, value_name = "BOOL", default_value_t = true, action = clap::ArgAction::Set)]
+        keep_target_dir: bool,
+
+        /// Keep a bound without a check when removing it from another item of the same
+        /// impl or trait was rejected; `false` checks every candidate. Defaults to `true`.
+        #[arg(long, value_name = "BOOL", action = clap::ArgAction::Set)]
+        assume_consistent: Option<bool>,
+
+        /// Validate removals from up to N distinct files with one check, bisecting the batch
+        /// when it fails; 1, the default, checks every candidate alone.
+        #[arg(long, value_name = "N")]
+        batch_size: Option<usize>,
+
+        /// Try at most N candidates of each file, those of its first items; bounds
+        /// `--only-trait` or a caution leaves alone don't count, and the rest are reported
+        /// as not tried. A safety valve for very large generated files.
+        #[arg(long, value_name = "N")]
+        max_candidates_per_file: Option<usize>,
+
+        /// Prune with a named strategy, deciding brute force, the order, batch size,
+        /// prefilter, memo and unlock pass; `[strategy.NAME]` in the configuration adjusts
+        /// it. Flags it decides otherwise are rejected.
+        #[arg(long, value_enum, value_name = "NAME")]
+        strategy: Option<StrategyName>,
+
+        /// Try each retained bound again with a check leaving test targets out, and report
+        /// those only `#[cfg(test)]` code needs; the tree is left as pruned. Needs checks
+        /// that build test targets, like the default `--all-targets`.
+        #[arg(long)]
+        explain_test_only: bool,
+
+        /// Put back removals whose check took more than `slow_removal_factor` times the
+        /// run's median check (5 by default), rather than only flagging them as possibly
+        /// slowing builds down.
+        #[arg(long)]
+        reject_slow_removals: bool,
+
+        /// Drop the imports removed bounds were the last users of, like
+        /// `use serde::Serialize;` once `T: Serialize` is gone; each is checked, and put
+        /// back if the crate stops building without it.
+        #[arg(long)]
+        tidy_imports: bool,
+
+        /// Write a `// trait-winnower:` comment above each item keeping a bound the failed
+        /// check blamed on a bound elsewhere, naming it; a later run replaces them.
+        #[arg(long, conflicts_with = "dry_run")]
+        annotate_retained: bool,
+
+        /// Remove the comments `--annotate-retained` wrote, and prune nothing; cargo never
+        /// runs. With `--dry-run`, print the edits as a diff instead of making them.
+        #[arg(long, conflicts_with = "annotate_retained")]
+        strip_annotations: bool,
     },
 
     /// Check target and report likely unnecessary trait bounds.
     Check {
         /// Target to check. Defaults to ".".
         target: Option<PathBuf>,
+
+        /// Keep running and re-check each file as it is saved, static rules only; prints
+        /// one line per event when stdout is not a terminal. Ctrl-C stops.
+        #[arg(long)]
+        watch: bool,
+
+        /// Run `cargo clippy` once with its bound lints on and report the bounds they flag
+        /// under the `clippy` rule; crates and workspaces only.
+        #[arg(long, conflicts_with = "watch")]
+        import_clippy: bool,
+
+        /// Remove the bounds the `duplicate-predicate`, `duplicate-bound`,
+        /// `redundant-sized` and `implied-by-supertrait` rules flag with high confidence,
+        /// editing files in place; cargo never runs.
+        #[arg(long, conflicts_with_all = ["watch", "import_clippy"])]
+        fix: bool,
+
+        /// With `--fix`, print the edits as a diff instead of making them.
+        #[arg(long, requires = "fix")]
+        dry_run: bool,
     },
+
+    /// Apply a saved removal plan, then validate once.
+    Apply {
+        /// Plan file to apply.
+        plan: PathBuf,
+
+        /// Crate or workspace root the plan refers to. Defaults to ".".
+        target: Option<PathBuf>,
+
+        /// Change nothing if any plan entry no longer matches the source.
+        #[arg(long)]
+        strict: bool,
+    },
+
+    /// Restore files an interrupted prune left mid-trial.
+    Recover {
+        /// Crate or workspace root of the interrupted run. Defaults to ".".
+        target: Option<PathBuf>,
+    },
+
+    /// Run the checks `prune` makes before its first trial and report each: interrupted
+    /// runs, the toolchain, the target directory and its locks, clippy and matrix targets.
+    Doctor {
+        /// Crate or workspace root to check. Defaults to ".".
+        target: Option<PathBuf>,
+    },
+
+    /// Inspect or empty the cache of trial outcomes `prune` shares across runs.
+    Cache {
+        /// What to do with the cache.
+        #[command(subcommand)]
+        action: CacheAction,
+    },
+
+    /// Install or remove a git hook running trait-winnower on changed files.
+    Hook {
+        /// What to do with the hook.
+        #[command(subcommand)]
+        action: HookAction,
+    },
+
+    /// Print the packages owning each file: those whose module graph reaches it, else the
+    /// one whose manifest directory holds it.
+    Owners {
+        /// Source files to look up.
+        #[arg(required = true)]
+        files: Vec<PathBuf>,
+
+        /// Crate or workspace to ask `cargo metadata` about. Defaults to ".".
+        #[arg(long)]
+        target: Option<PathBuf>,
+    },
+
+    /// Compare the bounds of the target at two git revisions, each checked out into a
+    /// temporary worktree: per item, the bounds added, removed, newly flagged and no longer
+    /// flagged. Items match by module path, kind and name, so a renamed item shows as
+    /// removed and added. Text or `--format json`.
+    Diff {
+        /// The earlier revision.
+        from: String,
+
+        /// The later revision.
+        to: String,
+
+        /// Crate or workspace in the repository, as either revision has it. Defaults to ".".
+        #[arg(long)]
+        target: Option<PathBuf>,
+    },
+
+    /// Print everything known about one item: where it is, its type parameters and where
+    /// predicates with their indices, its candidates with the static rule flagging each and
+    /// what the last prune did with it, and with `--trial` how removing each alone goes.
+    /// Text or `--format json`.
+    ExplainItem {
+        /// The item, e.g. `crate::b::Wrapper::id`, or its label as reports print it, e.g.
+        /// `inner fn foo`.
+        item: String,
+
+        /// Crate or workspace holding the item. Defaults to ".".
+        target: Option<PathBuf>,
+
+        /// Remove each candidate alone and run `cargo check`, restoring the file after each.
+        #[arg(long)]
+        trial: bool,
+    },
+
+    /// Time `cargo check` before and after pruning a copy of the target, leaving the target
+    /// untouched; brute force is implied.
+    Impact {
+        /// Crate or workspace to measure. Defaults to ".".
+        target: Option<PathBuf>,
+
+        /// Timed runs per tree and command, each after a warm-up run.
+        #[arg(long, value_name = "N", default_value_t = crate::impact::DEFAULT_ITERATIONS)]
+        iterations: usize,
+
+        /// Time `cargo build` as well.
+        #[arg(long)]
+        build: bool,
+    },
+
+    /// Compare pruning strategies on a fixture crate, answering checks from its
+    /// `// bench: keep` markers instead of building it.
+    #[command(hide = true)]
+    Bench {
+        /// Fixture crate; it is copied, never edited.
+        #[arg(long)]
+        fixture: PathBuf,
+    },
+}
+
+impl Commands {
+    /// Point the target of the command at `root` where none was given.
+    pub fn default_target(&mut self, root: &Path) {
+        let target = match self {
+            Commands::Init { path: target, .. }
+            | Commands::Prune { target, .. }
+            | Commands::Check { target, .. }
+            | Commands::Apply { target, .. }
+            | Commands::Recover { target }
+            | Commands::Doctor { target }
+            | Commands::Cache {
+                action: CacheAction::Stats { target } | CacheAction::Clear { target },
+            }
+            | Commands::Hook {
+                action: HookAction::Install { target, .. } | HookAction::Uninstall { target, .. },
+            }
+            | Commands::Owners { target, .. }
+            | Commands::Diff { target, .. }
+            | Commands::ExplainItem { target, .. }
+            | Commands::Impact { target, .. } => target,
+            Commands::Bench { .. } => return,
+        };
+        target.get_or_insert_with(|| root.to_path_buf());
+    }
+}
+
+/// Operations on the trial cache.
+#[derive(Subcommand, Debug)]
+pub enum CacheAction {
+    /// Print where the cache is, how many trials it holds and its size.
+    Stats {
+        /// Crate or workspace root whose configuration places the cache. Defaults to ".".
+        target: Option<PathBuf>,
+    },
+    /// Remove every stored trial.
+    Clear {
+        /// Crate or workspace root whose configuration places the cache. Defaults to ".".
+        target: Option<PathBuf>,
+    },
+}
+
+/// Operations on the git hook.
+#[derive(Subcommand, Debug)]
+pub enum HookAction {
+    /// Write a hook failing when changed files gain unnecessary bounds; checks against the
+    /// upstream branch, or `HEAD` without one.
+    Install {
+        /// Crate or workspace root the hook checks. Defaults to ".".
+        target: Option<PathBuf>,
+
+        /// The git hook to install.
+        #[arg(long, value_enum, default_value = "pre-commit")]
+        stage: HookStage,
+
+        /// `check` runs the static rules; `verify` prunes with `cargo check` and leaves
+        /// what it removed for review.
+        #[arg(long, value_enum, default_value = "check")]
+        mode: HookMode,
+
+        /// Print a `.pre-commit-config.yaml` entry instead of writing a hook.
+        #[arg(long, value_enum, default_value = "git")]
+        framework: HookFramework,
+
+        /// Replace a hook trait-winnower did not install.
+        #[arg(long)]
+        force: bool,
+    },
+    /// Remove the hook `install` wrote.
+    Uninstall {
+        /// Crate or workspace root in the repository. Defaults to ".".
+        target: Option<PathBuf>,
+
+        /// The git hook to remove.
+        #[arg(long, value_enum, default_value = "pre-commit")]
+        stage: HookStage,
+
+        /// Remove a hook trait-winnower did not install.
+        #[arg(long)]
+        force: bool,
+    },
+}
+
+/// How the hook is run.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
+pub enum HookFramework {
+    /// A script in the repository's hooks directory.
+    Git,
+    /// An entry for the pre-commit framework's configuration.
+    PreCommit,
 }
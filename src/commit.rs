@@ -0,0 +1,225 @@
+// src/commit.rs
+//! Git commits recording what `prune` removed, for archaeology and partial reverts.
+//!
+//! Commits are made once the run has settled what stays, after the matrix and semver
+//! gates: a per-removal history replays each file's removals one at a time on its original
+//! text, the last one landing on the file as `prune` left it. A failing git command stops
+//! the commits, not the run; the removals stay in the working tree.
+
+#![deny(missing_docs)]
+
+use crate::dynamic_analysis::text_edit::apply_edits;
+use crate::error::{TraitError, WinnowerError};
+use crate::hook::git;
+use crate::plan::{PlanEntry, entry_edits};
+use crate::report::{FileReport, Removal, RemovalStatus};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+
+/// How much each commit covers.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[cfg_attr(feature = "cli", derive(clap::ValueEnum))]
+#[serde(rename_all = "lowercase")]
+pub enum CommitGranularity {
+    /// One commit per removed bound.
+    #[default]
+    Removal,
+    /// One commit per modified file.
+    File,
+    /// One commit for the whole run.
+    Run,
+}
+
+impl CommitGranularity {
+    /// The message template used without [`WinnowerBuilder::commit_message`](crate::winnower::WinnowerBuilder::commit_message).
+    pub fn default_template(self) -> &'static str {
+        match self {
+            CommitGranularity::Removal => "winnow: remove `{bound}` from {item} in {file}",
+            CommitGranularity::File => "winnow: remove {count} bound(s) from {file}",
+            CommitGranularity::Run => "winnow: remove {count} bound(s)",
+        }
+    }
+}
+
+/// A commit `prune` made.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct CommitRecord {
+    /// The full hash.
+    pub hash: String,
+    /// The message.
+    pub message: String,
+}
+
+/// The commits of a run, and why they stopped early if they did.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct CommitLog {
+    /// Commits made, oldest first.
+    pub commits: Vec<CommitRecord>,
+    /// The git failure that stopped the commits.
+    pub failure: Option<String>,
+}
+
+/// Fail unless the work tree holding `root` has no changes, untracked files included, so
+/// the commits hold only what `prune` changed.
+pub fn require_clean(root: &Path) -> TraitError<()> {
+    let status = git(root, &["status", "--porcelain"])?;
+    if status.is_empty() {
+        return Ok(());
+    }
+    Err(WinnowerError::Target {
+        path: root.to_path_buf(),
+        detail: format!("committing removals needs a clean work tree; found changes:\n{status}"),
+    })
+}
+
+/// `template` with `{bound}`, `{item}`, `{file}` and `{count}` filled in.
+fn message(template: &str, removals: &[&Removal], files: &[&Path]) -> String {
+    let join = |parts: Vec<String>| parts.join(", ");
+    template
+        .replace(
+            "{bound}",
+            &join(
+                removals
+                    .iter()
+                    .map(|r| format!("{}: {}", r.finding.bounded, r.finding.bound))
+                    .collect(),
+            ),
+        )
+        .replace(
+            "{item}",
+            &join(removals.iter().map(|r| r.finding.item.clone()).collect()),
+        )
+        .replace(
+            "{file}",
+            &join(files.iter().map(|f| f.display().to_string()).collect()),
+        )
+        .replace("{count}", &removals.len().to_string())
+}
+
+/// Commits of the removals `files` report, at `granularity`, messages from `template`.
+/// `root` holds the files; `originals` their text before the run, keyed by path relative
+/// to `root`. Commits skip hooks, which may well run `trait-winnower` again.
+pub fn commit_removals(
+    root: &Path,
+    files: &[FileReport],
+    originals: &HashMap<PathBuf, String>,
+    granularity: CommitGranularity,
+    template: &str,
+) -> CommitLog {
+    let mut log = CommitLog::default();
+    if let Err(e) = commit_all(root, files, originals, granularity, template, &mut log) {
+        log.failure = Some(e.to_string());
+    }
+    log
+}
+
+fn commit_all(
+    root: &Path,
+    files: &[FileReport],
+    originals: &HashMap<PathBuf, String>,
+    granularity: CommitGranularity,
+    template: &str,
+    log: &mut CommitLog,
+) -> TraitError<()> {
+    let changed: Vec<(&FileReport, Vec<&Removal>)> = files
+        .iter()
+        .filter(|f| originals.contains_key(&f.path))
+        .map(|f| {
+            let removed = f
+                .removals
+                .iter()
+                .filter(|r| r.status == RemovalStatus::Removed)
+                .collect();
+            (f, removed)
+        })
+        .filter(|(_, removed): &(_, Vec<_>)| !removed.is_empty())
+        .collect();
+    if changed.is_empty() {
+        return Ok(());
+    }
+    if granularity == CommitGranularity::Run {
+        let paths: Vec<&Path> = changed.iter().map(|(f, _)| f.path.as_path()).collect();
+        let removals: Vec<&Removal> = changed.iter().flat_map(|(_, r)| r.clone()).collect();
+        return commit(root, &paths, &message(template, &removals, &paths), log);
+    }
+    for (file, removals) in changed {
+        let path = root.join(&file.path);
+        let rel = [file.path.as_path()];
+        if granularity == CommitGranularity::File {
+            commit(root, &rel, &message(template, &removals, &rel), log)?;
+            continue;
+        }
+        let last =
+            std::fs::read_to_string(&path).map_err(|e| WinnowerError::io("reading", &path, e))?;
+        let replayed = replay(
+            root,
+            file,
+            &removals,
+            &originals[&file.path],
+            &last,
+            template,
+            log,
+        );
+        // Whatever happened, the file ends as `prune` left it.
+        std::fs::write(&path, &last).map_err(|e| WinnowerError::io("writing", &path, e))?;
+        replayed?;
+    }
+    Ok(())
+}
+
+/// One commit per removal of `file`: each applies the next removal to `original`, the last
+/// one commits `last`.
+fn replay(
+    root: &Path,
+    file: &FileReport,
+    removals: &[&Removal],
+    original: &str,
+    last: &str,
+    template: &str,
+    log: &mut CommitLog,
+) -> TraitError<()> {
+    let path = root.join(&file.path);
+    let rel = [file.path.as_path()];
+    let parsed = syn::parse_file(original).map_err(|e| WinnowerError::parse(&path, e))?;
+    let entries: Vec<PlanEntry> = removals
+        .iter()
+        .map(|r| PlanEntry {
+            locator: r.finding.locator.clone(),
+            candidate: r.finding.candidate.clone(),
+        })
+        .collect();
+    for k in 1..=entries.len() {
+        let text = if k == entries.len() {
+            last.to_owned()
+        } else {
+            let prefix: Vec<&PlanEntry> = entries[..k].iter().collect();
+            let (edits, _) = entry_edits(&file.path, original, &parsed, &prefix)?;
+            apply_edits(original, &edits)
+        };
+        std::fs::write(&path, &text).map_err(|e| WinnowerError::io("writing", &path, e))?;
+        commit(
+            root,
+            &rel,
+            &message(template, &removals[k - 1..k], &rel),
+            log,
+        )?;
+    }
+    Ok(())
+}
+
+/// Stage `paths`, relative to `root`, and commit them with `message`.
+fn commit(root: &Path, paths: &[&Path], message: &str, log: &mut CommitLog) -> TraitError<()> {
+    let paths: Vec<String> = paths.iter().map(|p| p.display().to_string()).collect();
+    let mut add = vec!["add", "--"];
+    add.extend(paths.iter().map(String::as_str));
+    git(root, &add)?;
+    let mut args = vec!["commit", "--quiet", "--no-verify", "-m", message, "--"];
+    args.extend(paths.iter().map(String::as_str));
+    git(root, &args)?;
+    log.commits.push(CommitRecord {
+        hash: git(root, &["rev-parse", "HEAD"])?,
+        message: message.to_owned(),
+    });
+    Ok(())
+}
@@ -3,7 +3,8 @@
 
 #![deny(missing_docs)]
 
-use crate::error::TraitError;
+use crate::error::{TraitError, WinnowerError};
+use crate::strategy::StrategyConfig;
 use serde::{Deserialize, Serialize};
 use std::{fs, path::Path, path::PathBuf};
 
@@ -11,7 +12,502 @@ use std::{fs, path::Path, path::PathBuf};
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct CargoCheckConfig {
     /// Cargo check arguments (e.g., ["--workspace", "--all-features", "--all-targets", "--quiet"]).
+    #[serde(default)]
     pub args: Vec<String>,
+    /// Further configurations to check besides the host, e.g.
+    /// `{ target = "x86_64-pc-windows-msvc" }` or `{ rustflags = "--cfg special" }`. A removal
+    /// stands only if every one of them builds.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub matrix: Vec<MatrixEntry>,
+    /// When the matrix runs: for every candidate, or once on the final state.
+    #[serde(default)]
+    pub matrix_mode: MatrixMode,
+    /// The cargo to run, for setups where the one on `PATH` is missing or wrong. Overrides
+    /// the `CARGO` environment variable; relative paths start at the config file.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub cargo_path: Option<PathBuf>,
+    /// Where validation builds, relative to the crate root. A directory of its own keeps
+    /// trials from invalidating the user's incremental state and from contending with
+    /// rust-analyzer for the lock on `target/`.
+    #[serde(default = "CargoCheckConfig::default_target_dir")]
+    pub target_dir: PathBuf,
+    /// What each trial checks: the package owning the edited file and its dependents, or
+    /// the whole workspace as `args` say.
+    #[serde(default)]
+    pub check_scope: CheckScope,
+    /// Stop a trial's `cargo check` at the first error in a workspace package instead of
+    /// letting it build the rest; checks that pass still run to completion. Has no effect
+    /// when `args` pick a `--message-format`.
+    #[serde(default = "CargoCheckConfig::default_fail_fast")]
+    pub fail_fast: bool,
+    /// Lints, such as `unused_imports`, whose new errors a trial may raise and still pass:
+    /// under `-D warnings` or a `deny` lint level, a removal that leaves an import unused
+    /// is accepted, and what it left behind is listed as a follow-up in the report. The
+    /// check is then run again with the lints forced to warnings, so the packages
+    /// depending on the one they failed are checked too. Has no effect when `args` pick a
+    /// `--message-format`.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub tolerate_new_lints: Vec<String>,
+    /// Seconds a check may wait for a file lock another cargo process holds, such as the
+    /// build directory lock of rust-analyzer, before the run stops with an error; unset,
+    /// checks wait as long as cargo does (see
+    /// [`lock_wait`](crate::dynamic_analysis::lock_wait)).
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub lock_wait_timeout: Option<u64>,
+}
+
+/// What a trial's `cargo check` covers.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum CheckScope {
+    /// The packages compiling the edited file and the workspace packages depending on them,
+    /// when `cargo metadata` tells which those are; else as [`CheckScope::Workspace`].
+    #[default]
+    Package,
+    /// Whatever `args` select, usually `--workspace`.
+    Workspace,
+}
+
+/// One extra configuration of the validation matrix.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct MatrixEntry {
+    /// Target triple passed as `--target`.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub target: Option<String>,
+    /// Appended to `RUSTFLAGS`, e.g. `--cfg special`.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub rustflags: Option<String>,
+    /// Features enabled on top of the check arguments' with `--features`, e.g. `extra`.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub features: Option<String>,
+}
+
+impl std::fmt::Display for MatrixEntry {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let mut parts = Vec::new();
+        if let Some(t) = &self.target {
+            parts.push(format!("--target {t}"));
+        }
+        if let Some(features) = &self.features {
+            parts.push(format!("--features {features}"));
+        }
+        if let Some(r) = &self.rustflags {
+            parts.push(format!("RUSTFLAGS=\"{r}\""));
+        }
+        if parts.is_empty() {
+            return f.write_str("host");
+        }
+        f.write_str(&parts.join(" "))
+    }
+}
+
+/// When the validation matrix runs.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum MatrixMode {
+    /// Every candidate is checked on the host and on every entry.
+    #[default]
+    PerCandidate,
+    /// Candidates are checked on the host only; the whole matrix runs once after pruning,
+    /// and every removal is put back if it fails.
+    FinalOnly,
+}
+
+impl MatrixMode {
+    /// Stable identifier, e.g. `final-only`.
+    pub fn id(self) -> &'static str {
+        match self {
+            MatrixMode::PerCandidate => "per-candidate",
+            MatrixMode::FinalOnly => "final-only",
+        }
+    }
+}
+
+/// The order `prune` works through files in.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum Order {
+    /// Files whose bounds static rules flag most confidently first, then those of packages
+    /// cheaper to check; a run cut short has then tried the likeliest removals.
+    #[default]
+    Priority,
+    /// Files in the order discovery lists them.
+    Source,
+}
+
+impl Order {
+    /// Stable identifier, e.g. `priority`.
+    pub fn id(self) -> &'static str {
+        match self {
+            Order::Priority => "priority",
+            Order::Source => "source",
+        }
+    }
+}
+
+/// A kind of target cargo keeps in directories of its own: integration tests, examples
+/// and benches. Discovery leaves out those not analyzed (see [`Config::analyzes`]).
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum TargetArea {
+    /// `tests/`.
+    Tests,
+    /// `examples/`.
+    Examples,
+    /// `benches/`.
+    Benches,
+}
+
+impl TargetArea {
+    /// Every area.
+    pub const ALL: [TargetArea; 3] = [TargetArea::Tests, TargetArea::Examples, TargetArea::Benches];
+
+    /// The directory name, e.g. `tests`.
+    pub fn dir(self) -> &'static str {
+        match self {
+            TargetArea::Tests => "tests",
+            TargetArea::Examples => "examples",
+            TargetArea::Benches => "benches",
+        }
+    }
+
+    /// The `cargo check` flag that builds the area's targets, e.g. `--tests`.
+    pub fn flag(self) -> &'static str {
+        match self {
+            TargetArea::Tests => "--tests",
+            TargetArea::Examples => "--examples",
+            TargetArea::Benches => "--benches",
+        }
+    }
+
+    /// The config knob that turns the area on, e.g. `analyze_tests`.
+    pub fn knob(self) -> &'static str {
+        match self {
+            TargetArea::Tests => "analyze_tests",
+            TargetArea::Examples => "analyze_examples",
+            TargetArea::Benches => "analyze_benches",
+        }
+    }
+
+    /// Whether the root-relative path `rel` lies in a directory named after the area, at
+    /// any depth.
+    pub fn contains(self, rel: &Path) -> bool {
+        rel.parent()
+            .is_some_and(|dir| dir.components().any(|c| c.as_os_str() == self.dir()))
+    }
+}
+
+impl CargoCheckConfig {
+    /// The cargo binary to run: `cargo_path`, else `$CARGO`, else `cargo` from `PATH`.
+    pub fn cargo(&self) -> PathBuf {
+        cargo_program(self.cargo_path.as_deref())
+    }
+
+    /// The directory validation builds in for the crate at `root`.
+    pub fn target_dir(&self, root: &Path) -> PathBuf {
+        root.join(&self.target_dir)
+    }
+
+    /// `args` narrowed to `packages` (see [`CheckScope::Package`]): `--workspace` becomes
+    /// `-p` per package. Unchanged with [`CheckScope::Workspace`], without packages, and
+    /// when `args` already pick packages themselves.
+    pub fn scoped_args(&self, packages: &[String]) -> Vec<String> {
+        let picks = self.args.iter().any(|a| {
+            matches!(
+                a.as_str(),
+                "-p" | "--package" | "--exclude" | "--manifest-path"
+            ) || a.starts_with("--package=")
+                || a.starts_with("--exclude=")
+                || a.starts_with("--manifest-path=")
+                || (a.starts_with("-p") && !a.starts_with("--"))
+        });
+        if self.check_scope == CheckScope::Workspace || packages.is_empty() || picks {
+            return self.args.clone();
+        }
+        let mut args: Vec<String> = self
+            .args
+            .iter()
+            .filter(|a| !matches!(a.as_str(), "--workspace" | "--all"))
+            .cloned()
+            .collect();
+        for package in packages {
+            args.push("-p".into());
+            args.push(package.clone());
+        }
+        args
+    }
+
+    /// Whether these arguments build the targets of `area`.
+    pub fn builds_area(&self, area: TargetArea) -> bool {
+        self.args
+            .iter()
+            .any(|a| a == "--all-targets" || a == area.flag())
+    }
+
+    /// Add the flag of each of `areas` these arguments do not build yet, so removals in
+    /// its sources are validated.
+    pub fn cover_areas(&mut self, areas: impl IntoIterator<Item = TargetArea>) {
+        for area in areas {
+            if !self.builds_area(area) {
+                self.args.push(area.flag().into());
+            }
+        }
+    }
+
+    fn default_target_dir() -> PathBuf {
+        PathBuf::from("target/trait-winnower-check")
+    }
+
+    fn default_fail_fast() -> bool {
+        true
+    }
+
+    /// What `cargo check` run with these arguments does not build, so bounds it accepts
+    /// removing may still be needed there. Empty for the default arguments.
+    pub fn coverage_gaps(&self) -> Vec<CoverageGap> {
+        let has = |flag: &str| self.args.iter().any(|a| a == flag);
+        let narrowed = self.args.iter().any(|a| {
+            matches!(a.as_str(), "-p" | "--package" | "--exclude")
+                || a.starts_with("--package=")
+                || a.starts_with("--exclude=")
+                || (a.starts_with("-p") && !a.starts_with("--"))
+        });
+        let mut gaps = Vec::new();
+        if !has("--all-features") {
+            gaps.push(CoverageGap::Features);
+        }
+        if !has("--all-targets") {
+            gaps.push(CoverageGap::Targets);
+        }
+        if narrowed {
+            gaps.push(CoverageGap::Packages);
+        }
+        gaps
+    }
+}
+
+impl CargoCheckConfig {
+    /// Whether validation builds code compiled under every predicate in `cfg` (as
+    /// [`ItemKey::cfg`](crate::analysis::ItemKey::cfg) lists them) in at least one of its
+    /// configurations: the host and each matrix entry. `None` when that turns on what the
+    /// arguments do not say, such as default features or cfgs set by build scripts.
+    pub fn builds_cfg(&self, cfg: &[syn::Meta]) -> Option<bool> {
+        std::iter::once(&MatrixEntry::default())
+            .chain(&self.matrix)
+            .map(|entry| {
+                let env = CfgEnv::new(&self.args, entry);
+                cfg.iter().map(|m| env.eval(m)).fold(Some(true), cfg_and)
+            })
+            .fold(Some(false), cfg_or)
+    }
+}
+
+/// `configured`, else `$CARGO`, else `cargo` from `PATH`.
+pub fn cargo_program(configured: Option<&Path>) -> PathBuf {
+    configured
+        .map(Path::to_path_buf)
+        .or_else(|| std::env::var_os("CARGO").map(PathBuf::from))
+        .unwrap_or_else(|| PathBuf::from("cargo"))
+}
+
+/// Three-valued `&&`: `false` wins, then unknown.
+fn cfg_and(a: Option<bool>, b: Option<bool>) -> Option<bool> {
+    match (a, b) {
+        (Some(false), _) | (_, Some(false)) => Some(false),
+        (Some(true), Some(true)) => Some(true),
+        _ => None,
+    }
+}
+
+/// Three-valued `||`: `true` wins, then unknown.
+fn cfg_or(a: Option<bool>, b: Option<bool>) -> Option<bool> {
+    match (a, b) {
+        (Some(true), _) | (_, Some(true)) => Some(true),
+        (Some(false), Some(false)) => Some(false),
+        _ => None,
+    }
+}
+
+/// What one validated configuration sets, as far as its arguments tell.
+struct CfgEnv<'a> {
+    all_features: bool,
+    no_default_features: bool,
+    features: Vec<&'a str>,
+    all_targets: bool,
+    release: bool,
+    /// `--cfg` values from the entry's `RUSTFLAGS`, without spaces, e.g. `feature="x"`.
+    cfgs: Vec<String>,
+    target: Option<&'a str>,
+}
+
+impl<'a> CfgEnv<'a> {
+    fn new(args: &'a [String], entry: &'a MatrixEntry) -> Self {
+        let has = |flag: &str| args.iter().any(|a| a == flag);
+        let mut features: Vec<&str> = entry
+            .features
+            .iter()
+            .flat_map(|l| l.split([',', ' ']))
+            .filter(|f| !f.is_empty())
+            .collect();
+        for (i, a) in args.iter().enumerate() {
+            let list = match a.as_str() {
+                "--features" | "-F" => args.get(i + 1).map(String::as_str),
+                _ => a
+                    .strip_prefix("--features=")
+                    .or_else(|| a.strip_prefix("-F")),
+            };
+            features.extend(
+                list.into_iter()
+                    .flat_map(|l| l.split([',', ' ']))
+                    .filter(|f| !f.is_empty()),
+            );
+        }
+        let flags: Vec<&str> = entry
+            .rustflags
+            .as_deref()
+            .unwrap_or_default()
+            .split_whitespace()
+            .collect();
+        let cfgs = flags
+            .iter()
+            .enumerate()
+            .filter_map(|(i, f)| match *f {
+                "--cfg" => flags.get(i + 1).copied(),
+                _ => f.strip_prefix("--cfg="),
+            })
+            .map(|c| c.replace(['\'', ' '], ""))
+            .collect();
+        Self {
+            all_features: has("--all-features"),
+            no_default_features: has("--no-default-features"),
+            features,
+            all_targets: has("--all-targets") || has("--tests"),
+            release: has("--release") || has("-r"),
+            cfgs,
+            target: entry.target.as_deref(),
+        }
+    }
+
+    fn eval(&self, meta: &syn::Meta) -> Option<bool> {
+        let name = meta.path().get_ident()?.to_string();
+        match meta {
+            syn::Meta::Path(_) => match name.as_str() {
+                "test" => Some(self.all_targets),
+                "debug_assertions" => Some(!self.release),
+                "unix" | "windows" => self.family().map(|f| f == name),
+                "doc" | "doctest" | "miri" => Some(false),
+                _ => self.cfgs.contains(&name).then_some(true),
+            },
+            syn::Meta::NameValue(nv) => {
+                let syn::Expr::Lit(syn::ExprLit {
+                    lit: syn::Lit::Str(value),
+                    ..
+                }) = &nv.value
+                else {
+                    return None;
+                };
+                let value = value.value();
+                match name.as_str() {
+                    "feature" if self.all_features || self.features.contains(&value.as_str()) => {
+                        Some(true)
+                    }
+                    "feature" if self.no_default_features => Some(false),
+                    "target_os" => self.os().map(|os| os == value),
+                    "target_family" => self.family().map(|f| f == value),
+                    "target_arch" => Some(self.arch() == value),
+                    _ => self
+                        .cfgs
+                        .contains(&format!("{name}=\"{value}\""))
+                        .then_some(true),
+                }
+            }
+            syn::Meta::List(list) => {
+                let nested = list
+                    .parse_args_with(
+                        syn::punctuated::Punctuated::<syn::Meta, syn::Token![,]>::parse_terminated,
+                    )
+                    .ok()?;
+                let mut values = nested.iter().map(|m| self.eval(m));
+                match name.as_str() {
+                    "all" => values.fold(Some(true), cfg_and),
+                    "any" => values.fold(Some(false), cfg_or),
+                    "not" if nested.len() == 1 => values.next().flatten().map(|v| !v),
+                    _ => None,
+                }
+            }
+        }
+    }
+
+    fn arch(&self) -> &str {
+        match self.target {
+            Some(t) => t.split('-').next().unwrap_or(t),
+            None => std::env::consts::ARCH,
+        }
+    }
+
+    fn os(&self) -> Option<&str> {
+        let Some(target) = self.target else {
+            return Some(std::env::consts::OS);
+        };
+        // Android triples also name linux.
+        [
+            ("android", "android"),
+            ("linux", "linux"),
+            ("windows", "windows"),
+            ("darwin", "macos"),
+            ("ios", "ios"),
+            ("freebsd", "freebsd"),
+            ("netbsd", "netbsd"),
+            ("openbsd", "openbsd"),
+            ("wasi", "wasi"),
+        ]
+        .into_iter()
+        .find(|(part, _)| target.split('-').any(|p| p.starts_with(part)))
+        .map(|(_, os)| os)
+    }
+
+    fn family(&self) -> Option<&str> {
+        match self.os()? {
+            "windows" => Some("windows"),
+            "wasi" => Some("wasm"),
+            _ => Some("unix"),
+        }
+    }
+}
+
+/// Code a weakened `cargo check` configuration does not build.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum CoverageGap {
+    /// Code behind non-default features: `--all-features` is missing.
+    Features,
+    /// Tests, benches and examples: `--all-targets` is missing.
+    Targets,
+    /// Workspace members left out by `-p`/`--package` or `--exclude`.
+    Packages,
+}
+
+impl CoverageGap {
+    /// Stable identifier, e.g. `features`.
+    pub fn id(self) -> &'static str {
+        match self {
+            CoverageGap::Features => "features",
+            CoverageGap::Targets => "targets",
+            CoverageGap::Packages => "packages",
+        }
+    }
+}
+
+impl std::fmt::Display for CoverageGap {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(match self {
+            CoverageGap::Features => "code behind non-default features (no `--all-features`)",
+            CoverageGap::Targets => "tests, benches and examples (no `--all-targets`)",
+            CoverageGap::Packages => {
+                "workspace members not selected (`-p`/`--package` or `--exclude`)"
+            }
+        })
+    }
 }
 
 impl Default for CargoCheckConfig {
@@ -23,12 +519,131 @@ impl Default for CargoCheckConfig {
                 "--all-targets".into(),
                 "--quiet".into(),
             ],
+            matrix: Vec::new(),
+            matrix_mode: MatrixMode::default(),
+            cargo_path: None,
+            target_dir: Self::default_target_dir(),
+            check_scope: CheckScope::default(),
+            fail_fast: Self::default_fail_fast(),
+            tolerate_new_lints: Vec::new(),
+            lock_wait_timeout: None,
+        }
+    }
+}
+
+/// What validates `prune`'s removals.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum ValidatorKind {
+    /// `cargo check`.
+    #[default]
+    Check,
+    /// `cargo clippy` as the [`clippy`](Config::clippy) table configures it: a removal that
+    /// builds but trips a lint the crate enforces is put back.
+    Clippy,
+}
+
+/// What `prune` does with the bounds of impls of traits the workspace does not define
+/// (see [`TraitIndex::foreign_trait`](crate::trait_index::TraitIndex::foreign_trait)):
+/// loosening them can change which of the dependency's blanket impls apply to other types
+/// while the crate still builds.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum ForeignTraitImpls {
+    /// Try them like any other bounds.
+    Allow,
+    /// Try them, and mark their findings and removals with the trait.
+    #[default]
+    Warn,
+    /// Leave them alone, reported as skipped.
+    Skip,
+}
+
+/// When clippy runs with `validator = "clippy"`.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum ClippyGate {
+    /// Clippy instead of `cargo check` for every candidate.
+    #[default]
+    PerCandidate,
+    /// Candidates are checked with `cargo check`; clippy runs once after pruning, and
+    /// every removal is put back if it fails.
+    Final,
+}
+
+/// How `cargo clippy` runs, as a validator and for `check --import-clippy`.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ClippyConfig {
+    /// Arguments after `cargo clippy` and `cargo_check.args`.
+    #[serde(default = "ClippyConfig::default_args")]
+    pub args: Vec<String>,
+    /// Arguments for clippy itself, after `--`; they decide which lints fail validation.
+    #[serde(default = "ClippyConfig::default_lint_args")]
+    pub lint_args: Vec<String>,
+    /// Whether clippy checks every candidate or the final state only.
+    #[serde(default)]
+    pub gate: ClippyGate,
+}
+
+impl Default for ClippyConfig {
+    fn default() -> Self {
+        Self {
+            args: Self::default_args(),
+            lint_args: Self::default_lint_args(),
+            gate: ClippyGate::default(),
+        }
+    }
+}
+
+impl ClippyConfig {
+    fn default_args() -> Vec<String> {
+        vec!["--no-deps".into()]
+    }
+
+    fn default_lint_args() -> Vec<String> {
+        vec!["-D".into(), "warnings".into()]
+    }
+}
+
+/// Where `prune` keeps the outcomes of trials for later runs (see
+/// [`TrialCache`](crate::dynamic_analysis::trial_cache::TrialCache)).
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct TrialCacheConfig {
+    /// The cache directory, relative to the crate root. CI can persist it between jobs.
+    #[serde(default = "TrialCacheConfig::default_dir")]
+    pub dir: PathBuf,
+    /// Size the directory is trimmed back to after each run, least recently used entries
+    /// first.
+    #[serde(default = "TrialCacheConfig::default_max_cache_mb")]
+    pub max_cache_mb: u64,
+}
+
+impl Default for TrialCacheConfig {
+    fn default() -> Self {
+        Self {
+            dir: Self::default_dir(),
+            max_cache_mb: Self::default_max_cache_mb(),
         }
     }
 }
 
+impl TrialCacheConfig {
+    fn default_dir() -> PathBuf {
+        PathBuf::from("target/trait-winnower-trials")
+    }
+
+    fn default_max_cache_mb() -> u64 {
+        512
+    }
+
+    /// The cache directory for the crate at `root`.
+    pub fn dir(&self, root: &Path) -> PathBuf {
+        root.join(&self.dir)
+    }
+}
+
 /// Config struct for trait-winnower.
-#[derive(Debug, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct Config {
     /// Include files.
     pub include: Vec<String>,
@@ -36,23 +651,145 @@ pub struct Config {
     pub exclude: Vec<String>,
     /// Cargo check configuration.
     pub cargo_check: CargoCheckConfig,
+    /// Follow symbolic links while discovering files (off by default).
+    #[serde(default)]
+    pub follow_symlinks: bool,
+    /// Leave out of discovery what `.gitignore` and `.ignore` files and git's exclude files
+    /// ignore; on by default. Turn off to analyze ignored code such as vendored forks.
+    #[serde(default = "Config::default_true")]
+    pub respect_gitignore: bool,
+    /// With `respect_gitignore`, also honor the global gitignore (`core.excludesFile`); on
+    /// by default. Turn off so every machine, CI included, discovers the same files.
+    #[serde(default = "Config::default_true")]
+    pub global_gitignore: bool,
+    /// Analyze integration tests, in `tests/` directories; off by default. Pruning them
+    /// validates with `--tests` unless `cargo_check.args` build them already.
+    #[serde(default)]
+    pub analyze_tests: bool,
+    /// Analyze examples, in `examples/` directories; on by default. Pruning them validates
+    /// with `--examples` unless `cargo_check.args` build them already.
+    #[serde(default = "Config::default_true")]
+    pub analyze_examples: bool,
+    /// Analyze benches, in `benches/` directories; on by default. Pruning them validates
+    /// with `--benches` unless `cargo_check.args` build them already.
+    #[serde(default = "Config::default_true")]
+    pub analyze_benches: bool,
+    /// Refuse to prune when `cargo_check.args` leave code unchecked (see
+    /// [`CargoCheckConfig::coverage_gaps`]); off by default, which only warns.
+    #[serde(default)]
+    pub strict_validation: bool,
+    /// After pruning, run `cargo semver-checks` against the crate as it was and put back
+    /// removals on public items if it finds a breaking change.
+    #[serde(default)]
+    pub semver_check: bool,
+    /// Leave the bounds of structs and enums with `#[derive]`s alone in `prune`: derived
+    /// impls copy them, and `cargo check` only proves them unneeded where the derived code
+    /// is used.
+    #[serde(default)]
+    pub skip_derived_types: bool,
+    /// Skip bounds the body plainly uses (see [`evident_use`](crate::findings::evident_use))
+    /// in `prune` instead of spending a `cargo check` on each; on by default.
+    #[serde(default = "Config::default_prefilter")]
+    pub prefilter: bool,
+    /// Reuse the outcomes of trials across runs and machines; off unless the table is
+    /// present.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub trial_cache: Option<TrialCacheConfig>,
+    /// The order `prune` works through files in.
+    #[serde(default)]
+    pub order: Order,
+    /// Run `prune`'s trials in a copy of the workspace and write only accepted removals to
+    /// the real files (see [`Worktree`](crate::dynamic_analysis::worktree::Worktree)); on by
+    /// default. Ignored unless `cargo metadata` shows the target is its workspace's root.
+    #[serde(default = "Config::default_worktree")]
+    pub worktree: bool,
+    /// What validates removals: `cargo check` or `cargo clippy`.
+    #[serde(default)]
+    pub validator: ValidatorKind,
+    /// How clippy runs, with `validator = "clippy"` and `check --import-clippy`.
+    #[serde(default)]
+    pub clippy: ClippyConfig,
+    /// Adjustments to the presets of `prune --strategy`.
+    #[serde(default, skip_serializing_if = "StrategyConfig::is_empty")]
+    pub strategy: StrategyConfig,
+    /// What `prune` does with the bounds of impls of foreign traits; `warn` by default.
+    #[serde(default)]
+    pub foreign_trait_impls: ForeignTraitImpls,
+    /// Leave alone in `prune` files an editor's swap, lock or backup file suggests are open
+    /// with unsaved changes (see [`editor_lock`](crate::dynamic_analysis::external::editor_lock));
+    /// off by default.
+    #[serde(default)]
+    pub respect_editor_locks: bool,
+    /// Flag a removal whose check took more than this many times a check of the unmodified
+    /// crate as possibly slowing builds down (see
+    /// [`SlowRemovals`](crate::report::SlowRemovals)); 5 by default.
+    #[serde(default = "Config::default_slow_removal_factor")]
+    pub slow_removal_factor: f64,
 }
 
 impl Default for Config {
     fn default() -> Self {
         Self {
             include: vec!["**/*.rs".into()],
-            exclude: vec![
-                "target/**".into(),
-                "**/.git/**".into(),
-                "**/tests/**".into(),
-            ],
+            exclude: vec!["target/**".into(), "**/.git/**".into()],
             cargo_check: CargoCheckConfig::default(),
+            follow_symlinks: false,
+            respect_gitignore: true,
+            global_gitignore: true,
+            analyze_tests: false,
+            analyze_examples: true,
+            analyze_benches: true,
+            strict_validation: false,
+            semver_check: false,
+            skip_derived_types: false,
+            prefilter: true,
+            trial_cache: None,
+            order: Order::default(),
+            worktree: Self::default_worktree(),
+            validator: ValidatorKind::default(),
+            clippy: ClippyConfig::default(),
+            strategy: StrategyConfig::default(),
+            foreign_trait_impls: ForeignTraitImpls::default(),
+            respect_editor_locks: false,
+            slow_removal_factor: Config::default_slow_removal_factor(),
         }
     }
 }
 
 impl Config {
+    fn default_prefilter() -> bool {
+        true
+    }
+
+    fn default_worktree() -> bool {
+        true
+    }
+
+    fn default_true() -> bool {
+        true
+    }
+
+    fn default_slow_removal_factor() -> f64 {
+        5.0
+    }
+
+    /// Whether discovery takes in the sources of `area`.
+    pub fn analyzes(&self, area: TargetArea) -> bool {
+        match area {
+            TargetArea::Tests => self.analyze_tests,
+            TargetArea::Examples => self.analyze_examples,
+            TargetArea::Benches => self.analyze_benches,
+        }
+    }
+
+    /// The areas discovery leaves out.
+    pub fn left_out_areas(&self) -> Vec<TargetArea> {
+        TargetArea::ALL
+            .into_iter()
+            .filter(|&a| !self.analyzes(a))
+            .collect()
+    }
+
     /// Load `.trait-winnower.toml` from `dir` (or its parent if `dir` is a file).
     /// If missing, return defaults. Ensures `include/exclude` are never empty.
     pub fn load_or_default(dir: &Path) -> TraitError<Self> {
@@ -63,17 +800,30 @@ impl Config {
         };
         let file = base.join(".trait-winnower.toml");
         if file.exists() {
-            let s = fs::read_to_string(&file)?;
-            let mut cfg: Config = toml::from_str(&s)?;
+            let s = fs::read_to_string(&file).map_err(|e| WinnowerError::Config {
+                path: file.clone(),
+                detail: e.to_string(),
+            })?;
+            let mut cfg: Config = toml::from_str(&s).map_err(|e| WinnowerError::Config {
+                path: file.clone(),
+                detail: e.to_string(),
+            })?;
             if cfg.include.is_empty() {
                 cfg.include = Config::default().include;
             }
             if cfg.exclude.is_empty() {
                 cfg.exclude = Config::default().exclude;
             }
-            // If cargo_check is not specified in the config, use defaults
+            // If cargo_check arguments are not specified in the config, use defaults
             if cfg.cargo_check.args.is_empty() {
-                cfg.cargo_check = CargoCheckConfig::default();
+                cfg.cargo_check.args = CargoCheckConfig::default().args;
+            }
+            // A bare name is looked up on `PATH`; other relative paths start at the config.
+            if let Some(cargo) = &mut cfg.cargo_check.cargo_path
+                && cargo.is_relative()
+                && cargo.components().count() > 1
+            {
+                *cargo = base.join(&*cargo);
             }
             Ok(cfg)
         } else {
@@ -89,8 +839,9 @@ impl Config {
         };
         let file = base.join(".trait-winnower.toml");
         if !file.exists() || force {
-            let s = toml::to_string_pretty(&Self::default())?;
-            fs::write(&file, s)?;
+            let s = toml::to_string_pretty(&Self::default())
+                .map_err(|e| WinnowerError::Internal(format!("serializing default config: {e}")))?;
+            fs::write(&file, s).map_err(|e| WinnowerError::io("writing", &file, e))?;
         }
         Ok(file)
     }
@@ -0,0 +1,430 @@
+// src/crate_map.rs
+//! Which packages compile each source file.
+//!
+//! Discovery walks the directory tree, but what `cargo check` builds is the module graph of
+//! each target: `#[path]` can pull one file into several packages, `include!` splices files
+//! into others, and build scripts generate code into `OUT_DIR`. The map follows the targets
+//! `cargo metadata` lists through their `mod` declarations and literal `include!`s; files
+//! no module graph reaches belong, for [`CrateMap::owners_of`], to the package whose
+//! manifest directory is the longest prefix of their path.
+
+#![deny(missing_docs)]
+
+use crate::discover::Discover;
+use crate::error::{TraitError, WinnowerError};
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, BTreeSet};
+use std::path::{Component, Path, PathBuf};
+use std::process::Command;
+
+/// A package and the root files of its targets.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Package {
+    /// The package name.
+    pub name: String,
+    /// The directory holding its `Cargo.toml`.
+    pub manifest_dir: PathBuf,
+    /// Root file of each target: `src/lib.rs`, binaries, tests, examples, build scripts.
+    pub targets: Vec<PathBuf>,
+    /// Names of the packages it depends on, of any kind.
+    pub dependencies: Vec<String>,
+}
+
+/// A package owning a file, as [`CrateMap::owners_of`] names it.
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
+pub struct PackageId {
+    /// The package name.
+    pub name: String,
+    /// The directory holding its `Cargo.toml`.
+    pub manifest_dir: PathBuf,
+}
+
+/// A file compiled into more than one package; a removal in it has to build in each.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SharedFile {
+    /// The file, as discovery found it.
+    pub path: PathBuf,
+    /// The packages compiling it, sorted.
+    pub packages: Vec<String>,
+}
+
+/// Source files keyed by canonical path, with the packages compiling them.
+#[derive(Debug, Default)]
+pub struct CrateMap {
+    owners: BTreeMap<PathBuf, BTreeSet<String>>,
+    /// Manifest directory of each package, canonical.
+    manifest_dirs: BTreeMap<String, PathBuf>,
+    /// Packages of the map by the packages of the map depending on them.
+    dependents: BTreeMap<String, BTreeSet<String>>,
+    /// Packages of the map by the packages of the map they depend on.
+    dependencies: BTreeMap<String, BTreeSet<String>>,
+    /// The workspace root `cargo metadata` reported.
+    workspace_root: Option<PathBuf>,
+}
+
+impl CrateMap {
+    /// The map of the packages `cargo metadata`, run with `cargo`, lists under `root`.
+    pub fn from_metadata(root: &Path, cargo: &Path) -> TraitError<Self> {
+        let output = Command::new(cargo)
+            .args([
+                "metadata",
+                "--format-version",
+                "1",
+                "--no-deps",
+                "--offline",
+            ])
+            .current_dir(root)
+            .output()
+            .map_err(|e| WinnowerError::cargo_spawn(cargo, &e))?;
+        if !output.status.success() {
+            return Err(WinnowerError::Validation {
+                command: "cargo metadata".into(),
+                status: output.status.code(),
+                stderr: String::from_utf8_lossy(&output.stderr).into_owned(),
+            });
+        }
+        let metadata: serde_json::Value = serde_json::from_slice(&output.stdout)
+            .map_err(|e| WinnowerError::Internal(format!("parsing cargo metadata: {e}")))?;
+        Ok(Self::from_metadata_json(&metadata))
+    }
+
+    /// The map of the packages a `cargo metadata --format-version 1` document lists.
+    pub fn from_metadata_json(metadata: &serde_json::Value) -> Self {
+        let mut map = Self::build(&Self::packages(metadata));
+        map.workspace_root = metadata["workspace_root"].as_str().map(PathBuf::from);
+        map
+    }
+
+    /// The packages of a `cargo metadata` document.
+    fn packages(metadata: &serde_json::Value) -> Vec<Package> {
+        let list = |v: &serde_json::Value, key| v[key].as_array().cloned().unwrap_or_default();
+        list(metadata, "packages")
+            .iter()
+            .map(|p| Package {
+                name: p["name"].as_str().unwrap_or_default().to_owned(),
+                manifest_dir: p["manifest_path"]
+                    .as_str()
+                    .and_then(|m| Path::new(m).parent())
+                    .map(Path::to_path_buf)
+                    .unwrap_or_default(),
+                targets: list(p, "targets")
+                    .iter()
+                    .filter_map(|t| t["src_path"].as_str().map(PathBuf::from))
+                    .collect(),
+                dependencies: list(p, "dependencies")
+                    .iter()
+                    .filter_map(|d| d["name"].as_str().map(str::to_owned))
+                    .collect(),
+            })
+            .collect()
+    }
+
+    /// The map of `packages`, following each target's module tree. Files that fail to
+    /// parse end their branch of the walk.
+    pub fn build(packages: &[Package]) -> Self {
+        let mut map = Self::default();
+        let names: BTreeSet<&str> = packages.iter().map(|p| p.name.as_str()).collect();
+        for package in packages {
+            map.manifest_dirs
+                .insert(package.name.clone(), canonical(&package.manifest_dir));
+            for dependency in &package.dependencies {
+                if names.contains(dependency.as_str()) && *dependency != package.name {
+                    map.dependents
+                        .entry(dependency.clone())
+                        .or_default()
+                        .insert(package.name.clone());
+                    map.dependencies
+                        .entry(package.name.clone())
+                        .or_default()
+                        .insert(dependency.clone());
+                }
+            }
+            for target in &package.targets {
+                let files = Discover::resolve_module_files(target)
+                    .map(|m| m.files)
+                    .unwrap_or_else(|_| vec![target.clone()]);
+                let mut queue = files;
+                let mut seen = BTreeSet::new();
+                while let Some(file) = queue.pop() {
+                    if !seen.insert(canonical(&file)) {
+                        continue;
+                    }
+                    queue.extend(Self::included_files(&file));
+                    map.owners
+                        .entry(canonical(&file))
+                        .or_default()
+                        .insert(package.name.clone());
+                }
+            }
+        }
+        map
+    }
+
+    /// Files spliced into `file` by item-level `include!("...")` with a literal path, with
+    /// the module trees they declare. Includes built from `env!("OUT_DIR")` are generated
+    /// and have no file in the source tree.
+    fn included_files(file: &Path) -> Vec<PathBuf> {
+        let Ok(ast) = crate::analysis::ItemBounds::parse_file(file) else {
+            return Vec::new();
+        };
+        let dir = file.parent().unwrap_or(Path::new(""));
+        ast.items
+            .iter()
+            .filter_map(|item| match item {
+                syn::Item::Macro(m) if m.mac.path.is_ident("include") => {
+                    m.mac.parse_body::<syn::LitStr>().ok()
+                }
+                _ => None,
+            })
+            .map(|lit| dir.join(lit.value()))
+            .filter(|p| p.is_file())
+            .flat_map(|p| {
+                Discover::resolve_module_files(&p)
+                    .map(|m| m.files)
+                    .unwrap_or_else(|_| vec![p])
+            })
+            .collect()
+    }
+
+    /// Names of the packages of the map, sorted.
+    pub fn package_names(&self) -> impl Iterator<Item = &str> {
+        self.manifest_dirs.keys().map(String::as_str)
+    }
+
+    /// The root of the workspace the packages belong to, as `cargo metadata` reported it;
+    /// `None` for maps built otherwise.
+    pub fn workspace_root(&self) -> Option<&Path> {
+        self.workspace_root.as_deref()
+    }
+
+    /// The packages compiling `path`, sorted; empty when none does.
+    pub fn packages_of(&self, path: &Path) -> Vec<&str> {
+        self.owners
+            .get(&canonical(path))
+            .map(|o| o.iter().map(String::as_str).collect())
+            .unwrap_or_default()
+    }
+
+    /// The packages owning `path`, sorted by name: those compiling it or, when no module
+    /// graph reaches it, the package whose manifest directory is the longest prefix of its
+    /// path. Empty outside every package.
+    pub fn owners_of(&self, path: &Path) -> Vec<PackageId> {
+        let id = |name: &str| PackageId {
+            name: name.to_owned(),
+            manifest_dir: self.manifest_dirs.get(name).cloned().unwrap_or_default(),
+        };
+        let compiling = self.packages_of(path);
+        if !compiling.is_empty() {
+            return compiling.into_iter().map(id).collect();
+        }
+        let path = canonical(path);
+        self.manifest_dirs
+            .iter()
+            .filter(|(_, dir)| !dir.as_os_str().is_empty() && path.starts_with(dir))
+            .max_by_key(|(_, dir)| dir.components().count())
+            .map(|(name, _)| id(name))
+            .into_iter()
+            .collect()
+    }
+
+    /// The first of [`CrateMap::owners_of`]; files shared by several packages have more.
+    pub fn owner_of(&self, path: &Path) -> Option<PackageId> {
+        self.owners_of(path).into_iter().next()
+    }
+
+    /// The packages a change to `path` can break: those compiling it and, transitively,
+    /// those depending on them. Sorted; empty when no package compiles it.
+    pub fn check_scope(&self, path: &Path) -> Vec<String> {
+        let mut scope: BTreeSet<String> = BTreeSet::new();
+        let mut queue: Vec<String> = self
+            .packages_of(path)
+            .into_iter()
+            .map(str::to_owned)
+            .collect();
+        while let Some(package) = queue.pop() {
+            if let Some(dependents) = self.dependents.get(&package) {
+                queue.extend(dependents.iter().filter(|d| !scope.contains(*d)).cloned());
+            }
+            scope.insert(package);
+        }
+        scope.into_iter().collect()
+    }
+
+    /// The files a check of `packages` compiles: theirs and those of the packages of the map
+    /// they depend on, transitively. Every file of the map when `packages` is empty, as for
+    /// a check of the whole workspace. Sorted canonical paths.
+    pub fn sources(&self, packages: &[String]) -> Vec<PathBuf> {
+        let mut built: BTreeSet<&str> = BTreeSet::new();
+        let mut queue: Vec<&str> = packages.iter().map(String::as_str).collect();
+        while let Some(package) = queue.pop() {
+            if built.insert(package)
+                && let Some(dependencies) = self.dependencies.get(package)
+            {
+                queue.extend(dependencies.iter().map(String::as_str));
+            }
+        }
+        self.owners
+            .iter()
+            .filter(|(_, owners)| {
+                packages.is_empty() || owners.iter().any(|o| built.contains(o.as_str()))
+            })
+            .map(|(path, _)| path.clone())
+            .collect()
+    }
+
+    /// `path` if more than one package compiles it.
+    pub fn shared(&self, path: &Path) -> Option<SharedFile> {
+        let packages = self.packages_of(path);
+        (packages.len() > 1).then(|| SharedFile {
+            path: path.to_path_buf(),
+            packages: packages.into_iter().map(str::to_owned).collect(),
+        })
+    }
+
+    /// Whether `path` lies in a build script's `OUT_DIR`, `.../build/<package>-<hash>/out/`.
+    /// Such files are regenerated on every build, so editing them proves nothing.
+    pub fn is_generated(path: &Path) -> bool {
+        let parts: Vec<_> = path
+            .components()
+            .filter_map(|c| match c {
+                Component::Normal(s) => s.to_str(),
+                _ => None,
+            })
+            .collect();
+        parts
+            .windows(3)
+            .any(|w| w[0] == "build" && w[1].contains('-') && w[2] == "out")
+    }
+}
+
+fn canonical(path: &Path) -> PathBuf {
+    path.canonicalize().unwrap_or_else(|_| path.to_path_buf())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn write(root: &Path, files: &[(&str, &str)]) -> std::io::Result<()> {
+        for (path, src) in files {
+            let path = root.join(path);
+            std::fs::create_dir_all(path.parent().unwrap_or(root))?;
+            std::fs::write(path, src)?;
+        }
+        Ok(())
+    }
+
+    fn package(root: &Path, name: &str, targets: &[&str]) -> Package {
+        Package {
+            name: name.into(),
+            manifest_dir: root.to_path_buf(),
+            targets: targets.iter().map(|t| root.join(t)).collect(),
+            dependencies: Vec::new(),
+        }
+    }
+
+    #[test]
+    fn a_path_module_shared_by_two_packages_has_both_owners() -> TraitError<()> {
+        let tmp = tempfile::tempdir()?;
+        let root = tmp.path();
+        write(
+            root,
+            &[
+                (
+                    "a/src/lib.rs",
+                    "#[path = \"../../shared/common.rs\"] mod common;\n",
+                ),
+                (
+                    "b/src/main.rs",
+                    "#[path = \"../../shared/common.rs\"]\nmod common;\nfn main() {}\n",
+                ),
+                ("shared/common.rs", "mod inner;\n"),
+                ("shared/inner.rs", ""),
+            ],
+        )?;
+        let map = CrateMap::build(&[
+            package(root, "a", &["a/src/lib.rs"]),
+            package(root, "b", &["b/src/main.rs"]),
+        ]);
+        assert_eq!(map.packages_of(&root.join("shared/common.rs")), ["a", "b"]);
+        assert_eq!(map.packages_of(&root.join("a/src/lib.rs")), ["a"]);
+        // `#[path]` files behave like `mod.rs`: their children sit next to them.
+        assert_eq!(map.packages_of(&root.join("shared/inner.rs")), ["a", "b"]);
+        assert!(map.shared(&root.join("a/src/lib.rs")).is_none());
+        let shared = map
+            .shared(&root.join("shared/common.rs"))
+            .expect("two owners");
+        assert_eq!(shared.packages, ["a", "b"]);
+        Ok(())
+    }
+
+    #[test]
+    fn literal_includes_are_followed_and_out_dir_ones_are_not() -> TraitError<()> {
+        let tmp = tempfile::tempdir()?;
+        let root = tmp.path();
+        write(
+            root,
+            &[
+                (
+                    "src/lib.rs",
+                    "include!(\"parts/spliced.rs\");\n\
+                     include!(concat!(env!(\"OUT_DIR\"), \"/gen.rs\"));\n",
+                ),
+                ("src/parts/spliced.rs", "pub fn f() {}\n"),
+                ("src/gen.rs", "pub fn g() {}\n"),
+                ("src/stray.rs", "pub fn h() {}\n"),
+            ],
+        )?;
+        let map = CrateMap::build(&[package(root, "x", &["src/lib.rs"])]);
+        assert_eq!(map.packages_of(&root.join("src/parts/spliced.rs")), ["x"]);
+        assert!(map.packages_of(&root.join("src/gen.rs")).is_empty());
+        assert!(map.packages_of(&root.join("src/stray.rs")).is_empty());
+        Ok(())
+    }
+
+    #[test]
+    fn out_dir_paths_are_generated() {
+        for (path, generated) in [
+            ("target/debug/build/x-0123abcd/out/gen.rs", true),
+            ("/tmp/t/release/build/my-crate-ff/out/nested/a.rs", true),
+            ("src/build/out/a.rs", false),
+            ("src/build.rs", false),
+            ("build/x-1/src/out.rs", false),
+        ] {
+            assert_eq!(CrateMap::is_generated(Path::new(path)), generated, "{path}");
+        }
+    }
+
+    #[test]
+    fn packages_are_read_from_metadata() {
+        let metadata = serde_json::json!({
+            "packages": [
+                { "name": "a", "manifest_path": "/w/a/Cargo.toml", "targets": [
+                    { "src_path": "/w/a/src/lib.rs" },
+                    { "src_path": "/w/a/build.rs" },
+                ], "dependencies": [
+                    { "name": "b", "kind": null },
+                    { "name": "serde", "kind": "dev" },
+                ]},
+                { "name": "b", "targets": [] },
+            ]
+        });
+        assert_eq!(
+            CrateMap::packages(&metadata),
+            [
+                Package {
+                    name: "a".into(),
+                    manifest_dir: "/w/a".into(),
+                    targets: vec!["/w/a/src/lib.rs".into(), "/w/a/build.rs".into()],
+                    dependencies: vec!["b".into(), "serde".into()],
+                },
+                Package {
+                    name: "b".into(),
+                    manifest_dir: PathBuf::new(),
+                    targets: Vec::new(),
+                    dependencies: Vec::new(),
+                },
+            ]
+        );
+    }
+}
@@ -0,0 +1,617 @@
+// src/derivable.rs
+//! Hand-written impls a `#[derive]` could replace, listed after `prune` as derive
+//! candidates; the tree is never changed.
+//!
+//! An impl of `Clone`, `Debug`, `Default`, `PartialEq`, `Eq` or `Hash` is a candidate when
+//! its type is a struct of the same file, its body does fieldwise what the derive would
+//! (`Self { a: self.a.clone() }`, `f.debug_struct("S").field("a", &self.a).finish()`,
+//! `self.a == other.a && ...`), and its generics are those the derive would write: the
+//! struct's own, each type parameter bounded by the trait besides the struct's bounds.
+//! Pruning is what usually gets an impl there, once the extra bounds it carried are gone;
+//! [`DeriveCandidate::enabled_by_prune`] tells those from impls that were derivable before.
+//! Only common shapes are recognized, so an impl not listed may still be derivable.
+
+#![deny(missing_docs)]
+
+use crate::analysis::ItemBounds;
+use crate::error::{TraitError, WinnowerError};
+use crate::report::{DeriveCandidate, RemovalStatus, Report};
+use quote::ToTokens;
+use std::collections::{BTreeSet, HashMap};
+use std::path::{Path, PathBuf};
+use syn::visit::Visit;
+use syn::{
+    BinOp, Expr, Fields, FnArg, GenericParam, ImplItem, ImplItemFn, ItemImpl, ItemStruct, Lit,
+    Member, Pat, PathArguments, Stmt, Type, TypeParamBound, WherePredicate,
+};
+
+/// The std traits a derive is looked for.
+pub const DERIVABLE: [&str; 6] = ["Clone", "Debug", "Default", "PartialEq", "Eq", "Hash"];
+
+/// A derive candidate of one source text.
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord)]
+pub struct Derivable {
+    /// 1-based line of the `impl`.
+    pub line: usize,
+    /// The trait, e.g. `Clone`.
+    pub derive: String,
+    /// The struct, e.g. `Wrapper`.
+    pub ty: String,
+}
+
+/// The derive candidates of `source`, in file order.
+pub fn derivable_impls(source: &str) -> syn::Result<Vec<Derivable>> {
+    let file = syn::parse_file(source)?;
+    let mut items = Items::default();
+    items.visit_file(&file);
+    let mut out: Vec<Derivable> = items
+        .impls
+        .iter()
+        .filter_map(|imp| {
+            let (derive, ty) = derivable(imp, &items.structs)?;
+            Some(Derivable {
+                line: imp.impl_token.span.start().line,
+                derive,
+                ty,
+            })
+        })
+        .collect();
+    out.sort();
+    Ok(out)
+}
+
+/// The derive candidates of the files of `report` where bounds were removed, as they are
+/// under `root` now. `originals` holds their text before the run, by full path, to tell
+/// which the removals enabled.
+pub fn derive_candidates(
+    root: &Path,
+    report: &Report,
+    originals: &HashMap<PathBuf, String>,
+) -> TraitError<Vec<DeriveCandidate>> {
+    let mut out = Vec::new();
+    for file in &report.files {
+        if !file
+            .removals
+            .iter()
+            .any(|r| r.status == RemovalStatus::Removed)
+        {
+            continue;
+        }
+        let path = root.join(&file.path);
+        let source = ItemBounds::read_source(&path)?;
+        let now = derivable_impls(&source).map_err(|e| WinnowerError::parse(&file.path, e))?;
+        let before: BTreeSet<(String, String)> = originals
+            .get(&path)
+            .and_then(|original| derivable_impls(original).ok())
+            .into_iter()
+            .flatten()
+            .map(|d| (d.derive, d.ty))
+            .collect();
+        for d in now {
+            let enabled_by_prune = !before.contains(&(d.derive.clone(), d.ty.clone()));
+            out.push(DeriveCandidate {
+                file: file.path.clone(),
+                line: d.line,
+                derive: d.derive,
+                ty: d.ty,
+                enabled_by_prune,
+            });
+        }
+    }
+    Ok(out)
+}
+
+/// The structs and trait impls of a file, at any depth.
+#[derive(Default)]
+struct Items<'a> {
+    structs: HashMap<String, &'a ItemStruct>,
+    impls: Vec<&'a ItemImpl>,
+}
+
+impl<'a> Visit<'a> for Items<'a> {
+    fn visit_item_struct(&mut self, item: &'a ItemStruct) {
+        self.structs.insert(item.ident.to_string(), item);
+    }
+
+    fn visit_item_impl(&mut self, item: &'a ItemImpl) {
+        if item.trait_.is_some() {
+            self.impls.push(item);
+        }
+        syn::visit::visit_item_impl(self, item);
+    }
+}
+
+/// The trait and struct of `imp`, when a derive could replace it.
+fn derivable(imp: &ItemImpl, structs: &HashMap<String, &ItemStruct>) -> Option<(String, String)> {
+    if imp.unsafety.is_some() || imp.defaultness.is_some() {
+        return None;
+    }
+    let (negative, path, _) = imp.trait_.as_ref()?;
+    let last = path.segments.last()?;
+    let derive = last.ident.to_string();
+    if negative.is_some()
+        || !matches!(last.arguments, PathArguments::None)
+        || !DERIVABLE.contains(&derive.as_str())
+    {
+        return None;
+    }
+    let Type::Path(self_ty) = &*imp.self_ty else {
+        return None;
+    };
+    if self_ty.qself.is_some() {
+        return None;
+    }
+    let segment = self_ty.path.segments.last()?;
+    let ty = segment.ident.to_string();
+    let item = structs.get(&ty)?;
+    if !generics_match(imp, item, &derive) || !self_args_match(&segment.arguments, item) {
+        return None;
+    }
+    let fields = members(&item.fields);
+    let trivial = match (derive.as_str(), imp.items.as_slice()) {
+        ("Eq", []) => true,
+        (_, [ImplItem::Fn(f)]) => match derive.as_str() {
+            "Clone" => f.sig.ident == "clone" && is_fieldwise_clone(f, &ty, &fields),
+            "Debug" => f.sig.ident == "fmt" && is_fieldwise_debug(f, item, &fields),
+            "Default" => f.sig.ident == "default" && is_fieldwise_default(f, &ty, &fields),
+            "PartialEq" => f.sig.ident == "eq" && is_fieldwise_eq(f, &fields),
+            "Hash" => f.sig.ident == "hash" && is_fieldwise_hash(f, &fields),
+            _ => false,
+        },
+        _ => false,
+    };
+    trivial.then_some((derive, ty))
+}
+
+/// Whether the generics of `imp` are those `#[derive(derive)]` on `item` would write.
+fn generics_match(imp: &ItemImpl, item: &ItemStruct, derive: &str) -> bool {
+    let ours: Vec<String> = imp.generics.params.iter().map(param_name).collect();
+    let theirs: Vec<String> = item.generics.params.iter().map(param_name).collect();
+    if ours != theirs {
+        return false;
+    }
+    let (Some(imp_bounds), Some(mut expected)) = (
+        bounds_by_param(&imp.generics, derive),
+        bounds_by_param(&item.generics, derive),
+    ) else {
+        return false;
+    };
+    for param in item.generics.type_params() {
+        expected
+            .entry(param.ident.to_string())
+            .or_default()
+            .insert(derive.to_owned());
+    }
+    expected.retain(|_, b| !b.is_empty());
+    imp_bounds == expected
+}
+
+fn param_name(param: &GenericParam) -> String {
+    match param {
+        GenericParam::Type(t) => t.ident.to_string(),
+        GenericParam::Lifetime(l) => l.lifetime.to_string(),
+        GenericParam::Const(c) => format!("const {}: {}", c.ident, c.ty.to_token_stream()),
+    }
+}
+
+/// The bounds of each parameter of `generics`, where clause included, as text, a bound
+/// naming `derive` by any path as `derive` alone. `None` when the where clause bounds
+/// anything but a parameter.
+fn bounds_by_param(
+    generics: &syn::Generics,
+    derive: &str,
+) -> Option<HashMap<String, BTreeSet<String>>> {
+    let mut out: HashMap<String, BTreeSet<String>> = HashMap::new();
+    let text = |bound: &TypeParamBound| match bound {
+        TypeParamBound::Trait(t)
+            if matches!(t.modifier, syn::TraitBoundModifier::None)
+                && t.path.segments.last().is_some_and(|s| {
+                    s.ident == derive && matches!(s.arguments, PathArguments::None)
+                }) =>
+        {
+            derive.to_owned()
+        }
+        other => other.to_token_stream().to_string(),
+    };
+    for param in &generics.params {
+        let (name, bounds): (String, BTreeSet<String>) = match param {
+            GenericParam::Type(t) => (t.ident.to_string(), t.bounds.iter().map(text).collect()),
+            GenericParam::Lifetime(l) => (
+                l.lifetime.to_string(),
+                l.bounds.iter().map(|b| b.to_string()).collect(),
+            ),
+            GenericParam::Const(_) => continue,
+        };
+        out.entry(name).or_default().extend(bounds);
+    }
+    for predicate in generics.where_clause.iter().flat_map(|w| &w.predicates) {
+        match predicate {
+            WherePredicate::Type(p) if p.lifetimes.is_none() => {
+                let Type::Path(bounded) = &p.bounded_ty else {
+                    return None;
+                };
+                let name = bounded.path.get_ident()?.to_string();
+                out.get_mut(&name)?.extend(p.bounds.iter().map(text));
+            }
+            WherePredicate::Lifetime(p) => {
+                out.get_mut(&p.lifetime.to_string())?
+                    .extend(p.bounds.iter().map(|b| b.to_string()));
+            }
+            _ => return None,
+        }
+    }
+    out.retain(|_, b| !b.is_empty());
+    Some(out)
+}
+
+/// Whether the arguments of the impl's self type are the struct's parameters, in order.
+fn self_args_match(args: &PathArguments, item: &ItemStruct) -> bool {
+    let given: Vec<String> = match args {
+        PathArguments::None => Vec::new(),
+        PathArguments::AngleBracketed(a) => a
+            .args
+            .iter()
+            .map(|a| a.to_token_stream().to_string())
+            .collect(),
+        PathArguments::Parenthesized(_) => return false,
+    };
+    let wanted: Vec<String> = item
+        .generics
+        .params
+        .iter()
+        .map(|p| match p {
+            GenericParam::Type(t) => t.ident.to_string(),
+            GenericParam::Lifetime(l) => l.lifetime.to_token_stream().to_string(),
+            GenericParam::Const(c) => c.ident.to_string(),
+        })
+        .collect();
+    given == wanted
+}
+
+/// The fields of a struct, as accessed on `self`.
+fn members(fields: &Fields) -> Vec<Member> {
+    fields
+        .iter()
+        .enumerate()
+        .map(|(i, f)| match &f.ident {
+            Some(ident) => Member::Named(ident.clone()),
+            None => Member::Unnamed(i.into()),
+        })
+        .collect()
+}
+
+/// The one expression `f`'s body is.
+fn body_expr(f: &ImplItemFn) -> Option<&Expr> {
+    match f.block.stmts.as_slice() {
+        [Stmt::Expr(expr, None)] => Some(unparen(expr)),
+        _ => None,
+    }
+}
+
+fn unparen(expr: &Expr) -> &Expr {
+    match expr {
+        Expr::Paren(p) => unparen(&p.expr),
+        other => other,
+    }
+}
+
+/// The names of `f`'s typed arguments, in order.
+fn arg_names(f: &ImplItemFn) -> Vec<String> {
+    f.sig
+        .inputs
+        .iter()
+        .filter_map(|arg| match arg {
+            FnArg::Typed(t) => match &*t.pat {
+                Pat::Ident(p) => Some(p.ident.to_string()),
+                _ => None,
+            },
+            FnArg::Receiver(_) => None,
+        })
+        .collect()
+}
+
+fn is_ident(expr: &Expr, name: &str) -> bool {
+    matches!(unparen(expr), Expr::Path(p) if p.qself.is_none() && p.path.is_ident(name))
+}
+
+/// Whether `expr` is `base.member`, e.g. `self.a`.
+fn is_field_of(expr: &Expr, base: &str, member: &Member) -> bool {
+    matches!(unparen(expr), Expr::Field(f) if f.member == *member && is_ident(&f.base, base))
+}
+
+/// Whether `expr` is `&base.member`.
+fn is_ref_field_of(expr: &Expr, base: &str, member: &Member) -> bool {
+    matches!(unparen(expr), Expr::Reference(r) if r.mutability.is_none()
+        && is_field_of(&r.expr, base, member))
+}
+
+/// Whether `path` names the struct: `Self` or its name.
+fn names_struct(path: &syn::Path, ty: &str) -> bool {
+    path.is_ident("Self") || path.segments.last().is_some_and(|s| s.ident == ty)
+}
+
+/// Whether `expr` builds the struct with `value(member, field expr)` true for each field,
+/// in the order of `fields` for tuple structs.
+fn builds_fieldwise(
+    expr: &Expr,
+    ty: &str,
+    fields: &[Member],
+    value: impl Fn(&Member, &Expr) -> bool,
+) -> bool {
+    match expr {
+        Expr::Struct(s) if s.rest.is_none() && s.qself.is_none() && names_struct(&s.path, ty) => {
+            s.fields.len() == fields.len()
+                && fields.iter().all(|m| {
+                    s.fields
+                        .iter()
+                        .any(|fv| fv.member == *m && value(m, unparen(&fv.expr)))
+                })
+        }
+        Expr::Call(c) => {
+            matches!(&*c.func, Expr::Path(p) if p.qself.is_none() && names_struct(&p.path, ty))
+                && !fields.is_empty()
+                && c.args.len() == fields.len()
+                && fields
+                    .iter()
+                    .zip(&c.args)
+                    .all(|(m, e)| matches!(m, Member::Unnamed(_)) && value(m, unparen(e)))
+        }
+        Expr::Path(p) => p.qself.is_none() && fields.is_empty() && names_struct(&p.path, ty),
+        _ => false,
+    }
+}
+
+/// `Self { a: self.a.clone(), .. }`, or `Clone::clone(&self.a)` for a field.
+fn is_fieldwise_clone(f: &ImplItemFn, ty: &str, fields: &[Member]) -> bool {
+    let Some(expr) = body_expr(f) else {
+        return false;
+    };
+    builds_fieldwise(expr, ty, fields, |m, e| match e {
+        Expr::MethodCall(c) => {
+            c.method == "clone" && c.args.is_empty() && is_field_of(&c.receiver, "self", m)
+        }
+        Expr::Call(c) => {
+            path_ends_with(&c.func, &["Clone", "clone"])
+                && c.args.len() == 1
+                && is_ref_field_of(&c.args[0], "self", m)
+        }
+        _ => false,
+    })
+}
+
+/// `Self { a: Default::default(), .. }`.
+fn is_fieldwise_default(f: &ImplItemFn, ty: &str, fields: &[Member]) -> bool {
+    let Some(expr) = body_expr(f) else {
+        return false;
+    };
+    builds_fieldwise(expr, ty, fields, |_, e| {
+        matches!(e, Expr::Call(c) if c.args.is_empty()
+            && path_ends_with(&c.func, &["Default", "default"]))
+    })
+}
+
+/// Whether `expr` is a path ending in `segments`, e.g. `std::clone::Clone::clone`.
+fn path_ends_with(expr: &Expr, segments: &[&str]) -> bool {
+    let Expr::Path(p) = unparen(expr) else {
+        return false;
+    };
+    let names: Vec<String> = p
+        .path
+        .segments
+        .iter()
+        .map(|s| s.ident.to_string())
+        .collect();
+    p.qself.is_none()
+        && names.ends_with(&segments.iter().map(|s| s.to_string()).collect::<Vec<_>>())
+}
+
+/// `f.debug_struct("S").field("a", &self.a).finish()`, `f.debug_tuple("S").field(&self.0)
+/// .finish()`, or `f.write_str("S")` for a unit struct.
+fn is_fieldwise_debug(f: &ImplItemFn, item: &ItemStruct, fields: &[Member]) -> bool {
+    let Some(expr) = body_expr(f) else {
+        return false;
+    };
+    let [formatter] = arg_names(f).try_into().unwrap_or_default();
+    let name = item.ident.to_string();
+    let is_name = |e: &Expr| is_str(e, &name);
+    if let Fields::Unit = item.fields {
+        return matches!(expr, Expr::MethodCall(c) if c.method == "write_str"
+            && is_ident(&c.receiver, &formatter)
+            && c.args.len() == 1
+            && is_name(&c.args[0]));
+    }
+    // From the outside in: `.finish()`, the `.field(..)` calls last first, the builder.
+    let Expr::MethodCall(finish) = expr else {
+        return false;
+    };
+    if finish.method != "finish" || !finish.args.is_empty() {
+        return false;
+    }
+    let mut calls = Vec::new();
+    let mut receiver = unparen(&finish.receiver);
+    while let Expr::MethodCall(c) = receiver
+        && c.method == "field"
+    {
+        calls.push(c);
+        receiver = unparen(&c.receiver);
+    }
+    calls.reverse();
+    let builder = match item.fields {
+        Fields::Named(_) => "debug_struct",
+        _ => "debug_tuple",
+    };
+    let Expr::MethodCall(start) = receiver else {
+        return false;
+    };
+    start.method == builder
+        && is_ident(&start.receiver, &formatter)
+        && start.args.len() == 1
+        && is_name(&start.args[0])
+        && calls.len() == fields.len()
+        && calls.iter().zip(fields).all(|(c, m)| match m {
+            Member::Named(ident) => {
+                c.args.len() == 2
+                    && is_str(&c.args[0], &ident.to_string())
+                    && is_ref_field_of(&c.args[1], "self", m)
+            }
+            Member::Unnamed(_) => c.args.len() == 1 && is_ref_field_of(&c.args[0], "self", m),
+        })
+}
+
+/// Whether `expr` is the string literal `text`.
+fn is_str(expr: &Expr, text: &str) -> bool {
+    matches!(expr, Expr::Lit(l) if matches!(&l.lit, Lit::Str(s) if s.value() == text))
+}
+
+/// `self.a == other.a && self.b == other.b`, or `true` for a struct without fields.
+fn is_fieldwise_eq(f: &ImplItemFn, fields: &[Member]) -> bool {
+    let Some(expr) = body_expr(f) else {
+        return false;
+    };
+    let [other] = arg_names(f).try_into().unwrap_or_default();
+    if fields.is_empty() {
+        return matches!(expr, Expr::Lit(l) if matches!(l.lit, Lit::Bool(ref b) if b.value));
+    }
+    let mut comparisons = Vec::new();
+    let mut rest = expr;
+    while let Expr::Binary(b) = rest
+        && matches!(b.op, BinOp::And(_))
+    {
+        comparisons.push(unparen(&b.right));
+        rest = unparen(&b.left);
+    }
+    comparisons.push(rest);
+    comparisons.reverse();
+    comparisons.len() == fields.len()
+        && comparisons.iter().zip(fields).all(|(e, m)| {
+            matches!(e, Expr::Binary(b) if matches!(b.op, BinOp::Eq(_))
+                && is_field_of(&b.left, "self", m)
+                && is_field_of(&b.right, &other, m))
+        })
+}
+
+/// `self.a.hash(state);` for each field in order, or `Hash::hash(&self.a, state);`.
+fn is_fieldwise_hash(f: &ImplItemFn, fields: &[Member]) -> bool {
+    let [state] = arg_names(f).try_into().unwrap_or_default();
+    let stmts = &f.block.stmts;
+    stmts.len() == fields.len()
+        && stmts.iter().zip(fields).all(|(stmt, m)| {
+            let Stmt::Expr(expr, _) = stmt else {
+                return false;
+            };
+            match unparen(expr) {
+                Expr::MethodCall(c) => {
+                    c.method == "hash"
+                        && is_field_of(&c.receiver, "self", m)
+                        && c.args.len() == 1
+                        && is_ident(&c.args[0], &state)
+                }
+                Expr::Call(c) => {
+                    path_ends_with(&c.func, &["Hash", "hash"])
+                        && c.args.len() == 2
+                        && is_ref_field_of(&c.args[0], "self", m)
+                        && is_ident(&c.args[1], &state)
+                }
+                _ => false,
+            }
+        })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// `(derive, ty)` of each candidate in `src`.
+    fn candidates(src: &str) -> Vec<(String, String)> {
+        derivable_impls(src)
+            .expect("parses")
+            .into_iter()
+            .map(|d| (d.derive, d.ty))
+            .collect()
+    }
+
+    fn pairs(list: &[(&str, &str)]) -> Vec<(String, String)> {
+        list.iter()
+            .map(|&(d, t)| (d.to_owned(), t.to_owned()))
+            .collect()
+    }
+
+    const WRAPPER: &str = "pub struct Wrapper<T> {\n    pub inner: T,\n    pub len: usize,\n}\n";
+
+    #[test]
+    fn fieldwise_impls_of_each_trait_are_candidates() {
+        let src = format!(
+            "{WRAPPER}\
+             impl<T: Clone> Clone for Wrapper<T> {{\n    fn clone(&self) -> Self {{\n        Wrapper {{ len: self.len.clone(), inner: Clone::clone(&self.inner) }}\n    }}\n}}\n\
+             impl<T: std::fmt::Debug> std::fmt::Debug for Wrapper<T> {{\n    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {{\n        f.debug_struct(\"Wrapper\").field(\"inner\", &self.inner).field(\"len\", &self.len).finish()\n    }}\n}}\n\
+             impl<T> Default for Wrapper<T> where T: Default {{\n    fn default() -> Self {{\n        Self {{ inner: Default::default(), len: Default::default() }}\n    }}\n}}\n\
+             impl<T: PartialEq> PartialEq for Wrapper<T> {{\n    fn eq(&self, other: &Self) -> bool {{\n        self.inner == other.inner && self.len == other.len\n    }}\n}}\n\
+             impl<T: Eq> Eq for Wrapper<T> {{}}\n\
+             impl<T: std::hash::Hash> std::hash::Hash for Wrapper<T> {{\n    fn hash<H: std::hash::Hasher>(&self, state: &mut H) {{\n        self.inner.hash(state);\n        std::hash::Hash::hash(&self.len, state);\n    }}\n}}\n"
+        );
+        assert_eq!(
+            candidates(&src),
+            pairs(&[
+                ("Clone", "Wrapper"),
+                ("Debug", "Wrapper"),
+                ("Default", "Wrapper"),
+                ("PartialEq", "Wrapper"),
+                ("Eq", "Wrapper"),
+                ("Hash", "Wrapper"),
+            ])
+        );
+    }
+
+    #[test]
+    fn tuple_and_unit_structs_are_recognized() {
+        let src = "struct Id(u32, String);\n\
+                   impl Clone for Id { fn clone(&self) -> Self { Self(self.0.clone(), self.1.clone()) } }\n\
+                   impl std::fmt::Debug for Id { fn fmt(&self, fmt: &mut std::fmt::Formatter) -> std::fmt::Result { fmt.debug_tuple(\"Id\").field(&self.0).field(&self.1).finish() } }\n\
+                   struct Marker;\n\
+                   impl PartialEq for Marker { fn eq(&self, _: &Self) -> bool { true } }\n\
+                   impl std::fmt::Debug for Marker { fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result { f.write_str(\"Marker\") } }\n\
+                   impl Default for Marker { fn default() -> Self { Marker } }\n";
+        assert_eq!(
+            candidates(src),
+            pairs(&[
+                ("Clone", "Id"),
+                ("Debug", "Id"),
+                ("PartialEq", "Marker"),
+                ("Debug", "Marker"),
+                ("Default", "Marker"),
+            ])
+        );
+    }
+
+    #[test]
+    fn bounds_a_derive_would_not_write_rule_an_impl_out() {
+        for imp in [
+            // An extra bound, as pruning would remove.
+            "impl<T: Clone + Send> Clone for Wrapper<T> { fn clone(&self) -> Self { Self { inner: self.inner.clone(), len: self.len.clone() } } }",
+            // Fewer bounds than the derive's: deriving would narrow the impl.
+            "impl<T> PartialEq for Wrapper<T> where T: Eq { fn eq(&self, other: &Self) -> bool { self.inner == other.inner && self.len == other.len } }",
+            // A concrete self type.
+            "impl Clone for Wrapper<u8> { fn clone(&self) -> Self { Self { inner: self.inner.clone(), len: self.len.clone() } } }",
+        ] {
+            assert_eq!(candidates(&format!("{WRAPPER}{imp}\n")), [], "{imp}");
+        }
+    }
+
+    #[test]
+    fn bodies_doing_more_than_a_derive_are_not_candidates() {
+        for imp in [
+            // A field left out.
+            "impl<T: PartialEq> PartialEq for Wrapper<T> { fn eq(&self, other: &Self) -> bool { self.inner == other.inner } }",
+            // Fields in another order hash differently.
+            "impl<T: Hash> Hash for Wrapper<T> { fn hash<H: Hasher>(&self, state: &mut H) { self.len.hash(state); self.inner.hash(state); } }",
+            // A value of its own.
+            "impl<T: Default> Default for Wrapper<T> { fn default() -> Self { Self { inner: T::default(), len: 1 } } }",
+            // Another name shown.
+            "impl<T: Debug> Debug for Wrapper<T> { fn fmt(&self, f: &mut Formatter) -> fmt::Result { f.debug_struct(\"W\").field(\"inner\", &self.inner).field(\"len\", &self.len).finish() } }",
+            // A method a derive does not write.
+            "impl<T: Clone> Clone for Wrapper<T> { fn clone(&self) -> Self { Self { inner: self.inner.clone(), len: self.len } } fn clone_from(&mut self, o: &Self) { self.len = o.len; } }",
+        ] {
+            assert_eq!(candidates(&format!("{WRAPPER}{imp}\n")), [], "{imp}");
+        }
+    }
+}
@@ -0,0 +1,365 @@
+// src/diff.rs
+//! `diff`: how the bounds of a crate changed between two git revisions.
+//!
+//! Each revision is checked out with `git worktree add` into a temporary directory, so the
+//! user's work tree and index are never touched, and checked there from the sources alone
+//! (see [`Winnower::check_static`](crate::winnower::Winnower::check_static)). Items are
+//! matched across the revisions by module path, kind and name ([`ItemId`]):
+//!
+//! - an item renamed, or moved to another module, shows as removed under its old name and
+//!   added under its new one;
+//! - items without bounds are not tracked, so an item gaining its first bound shows as
+//!   added and one losing its last as removed;
+//! - bounds are compared as declared, so moving `<T: Clone>` into the where clause shows as
+//!   `<T: Clone>` removed and `where T: Clone` added.
+
+#![deny(missing_docs)]
+
+use crate::analysis::ItemKind;
+use crate::error::TraitError;
+use crate::hook::{git, target_in_repo};
+use crate::report::{Finding, Report};
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::io::Write;
+use std::path::{Path, PathBuf};
+
+/// An item as matched across revisions.
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
+pub struct ItemId {
+    /// Module path, the file's included, e.g. `a::inner`; empty at the crate root.
+    pub module_path: String,
+    /// Kind of item.
+    pub kind: ItemKind,
+    /// Name as labeled, e.g. `foo` or `Wrapper < T >::id`.
+    pub name: String,
+}
+
+/// How an item changed between the revisions.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ItemChange {
+    /// Only the later revision has the item with bounds.
+    Added,
+    /// Only the earlier revision has the item with bounds.
+    Removed,
+    /// Both have it, with different bounds or findings.
+    Changed,
+}
+
+impl std::fmt::Display for ItemChange {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(match self {
+            ItemChange::Added => "added",
+            ItemChange::Removed => "removed",
+            ItemChange::Changed => "changed",
+        })
+    }
+}
+
+/// One item whose bounds differ between the revisions.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ItemDiff {
+    /// The item.
+    #[serde(flatten)]
+    pub id: ItemId,
+    /// The file holding it: in the later revision, unless it was removed.
+    pub file: PathBuf,
+    /// The item label, e.g. `fn foo`.
+    pub label: String,
+    /// How it changed.
+    pub change: ItemChange,
+    /// Bounds only the later revision declares, e.g. `<T: Clone>`.
+    pub added: Vec<String>,
+    /// Bounds only the earlier revision declares.
+    pub removed: Vec<String>,
+    /// Bounds a static rule flags only in the later revision, with the rule, e.g.
+    /// `where T: Clone (duplicate-bound)`.
+    pub flagged: Vec<String>,
+    /// Bounds a static rule flags only in the earlier revision.
+    pub unflagged: Vec<String>,
+}
+
+/// Counts over the items of a [`RevisionDiff`].
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct DiffSummary {
+    /// Items added.
+    pub items_added: usize,
+    /// Items removed.
+    pub items_removed: usize,
+    /// Items changed.
+    pub items_changed: usize,
+    /// Bounds added, over all items.
+    pub bounds_added: usize,
+    /// Bounds removed, over all items.
+    pub bounds_removed: usize,
+    /// Bounds newly flagged.
+    pub newly_flagged: usize,
+    /// Bounds no longer flagged.
+    pub no_longer_flagged: usize,
+}
+
+/// How the bounds of a crate changed from one revision to another.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct RevisionDiff {
+    /// The earlier revision, as given.
+    pub from: String,
+    /// The later revision, as given.
+    pub to: String,
+    /// The items that differ, by [`ItemId`].
+    pub items: Vec<ItemDiff>,
+    /// Counts over `items`.
+    pub summary: DiffSummary,
+}
+
+/// What one revision has of one item.
+#[derive(Default)]
+struct ItemState {
+    file: PathBuf,
+    label: String,
+    bounds: Vec<String>,
+    flagged: Vec<String>,
+}
+
+/// The items of `report` with their bounds and flagged bounds, each sorted.
+fn items_of(report: &Report) -> BTreeMap<ItemId, ItemState> {
+    let mut items: BTreeMap<ItemId, ItemState> = BTreeMap::new();
+    for file in &report.files {
+        for f in &file.findings {
+            let id = ItemId {
+                module_path: f.locator.module_path.clone(),
+                kind: f.locator.kind,
+                name: f.locator.name.clone(),
+            };
+            let state = items.entry(id).or_insert_with(|| ItemState {
+                file: file.path.clone(),
+                label: f.item.clone(),
+                ..ItemState::default()
+            });
+            state.bounds.push(f.candidate.declaration());
+            if let Some(flag) = flag(f) {
+                state.flagged.push(flag);
+            }
+        }
+    }
+    for state in items.values_mut() {
+        state.bounds.sort();
+        state.flagged.sort();
+    }
+    items
+}
+
+/// The bound of `finding` with the rule flagging it, if one does.
+fn flag(finding: &Finding) -> Option<String> {
+    let rule = finding.rule.as_ref()?;
+    Some(format!(
+        "{} ({})",
+        finding.candidate.declaration(),
+        rule.rule
+    ))
+}
+
+/// The elements of `a` not in `b`, both sorted, counting repeats.
+fn minus(a: &[String], b: &[String]) -> Vec<String> {
+    let mut b = b.iter().peekable();
+    let mut out = Vec::new();
+    for x in a {
+        while b.next_if(|y| *y < x).is_some() {}
+        if b.next_if(|y| *y == x).is_none() {
+            out.push(x.clone());
+        }
+    }
+    out
+}
+
+/// The items whose bounds or flagged bounds differ from the check `from` to the check `to`.
+pub fn diff_reports(from: &Report, to: &Report) -> Vec<ItemDiff> {
+    let (before, mut after) = (items_of(from), items_of(to));
+    let mut out = Vec::new();
+    let empty = ItemState::default();
+    for (id, old) in before {
+        let new = after.remove(&id);
+        let change = if new.is_some() {
+            ItemChange::Changed
+        } else {
+            ItemChange::Removed
+        };
+        let new = new.as_ref().unwrap_or(&empty);
+        let diff = ItemDiff {
+            file: if change == ItemChange::Removed {
+                old.file.clone()
+            } else {
+                new.file.clone()
+            },
+            label: old.label.clone(),
+            change,
+            added: minus(&new.bounds, &old.bounds),
+            removed: minus(&old.bounds, &new.bounds),
+            flagged: minus(&new.flagged, &old.flagged),
+            unflagged: minus(&old.flagged, &new.flagged),
+            id,
+        };
+        if diff.change == ItemChange::Removed
+            || !(diff.added.is_empty()
+                && diff.removed.is_empty()
+                && diff.flagged.is_empty()
+                && diff.unflagged.is_empty())
+        {
+            out.push(diff);
+        }
+    }
+    for (id, new) in after {
+        out.push(ItemDiff {
+            file: new.file,
+            label: new.label,
+            change: ItemChange::Added,
+            added: new.bounds,
+            removed: Vec::new(),
+            flagged: new.flagged,
+            unflagged: Vec::new(),
+            id,
+        });
+    }
+    out.sort_by(|a, b| a.id.cmp(&b.id));
+    out
+}
+
+impl DiffSummary {
+    /// The counts over `items`.
+    pub fn of(items: &[ItemDiff]) -> Self {
+        let mut s = Self::default();
+        for item in items {
+            match item.change {
+                ItemChange::Added => s.items_added += 1,
+                ItemChange::Removed => s.items_removed += 1,
+                ItemChange::Changed => s.items_changed += 1,
+            }
+            s.bounds_added += item.added.len();
+            s.bounds_removed += item.removed.len();
+            s.newly_flagged += item.flagged.len();
+            s.no_longer_flagged += item.unflagged.len();
+        }
+        s
+    }
+}
+
+impl RevisionDiff {
+    /// Write the diff as text: per item, its file, label and change, then a line per bound
+    /// added (`+`), removed (`-`), newly flagged (`!`) and no longer flagged (`~`), and
+    /// the summary counts last.
+    pub fn write_text<W: Write>(&self, out: &mut W) -> std::io::Result<()> {
+        for item in &self.items {
+            writeln!(
+                out,
+                "{} {}: {}",
+                item.file.display(),
+                item.label,
+                item.change
+            )?;
+            let lines = [
+                ('+', &item.added),
+                ('-', &item.removed),
+                ('!', &item.flagged),
+                ('~', &item.unflagged),
+            ];
+            for (sign, bounds) in lines {
+                for bound in bounds {
+                    writeln!(out, "  {sign} {bound}")?;
+                }
+            }
+        }
+        let s = &self.summary;
+        writeln!(
+            out,
+            "{}..{}: {} item(s) added, {} removed, {} changed; {} bound(s) added, {} removed; \
+             {} newly flagged, {} no longer flagged",
+            self.from,
+            self.to,
+            s.items_added,
+            s.items_removed,
+            s.items_changed,
+            s.bounds_added,
+            s.bounds_removed,
+            s.newly_flagged,
+            s.no_longer_flagged
+        )
+    }
+}
+
+/// A revision checked out with `git worktree add`; removed again when dropped.
+pub struct RevisionTree {
+    repo: PathBuf,
+    dir: PathBuf,
+}
+
+impl RevisionTree {
+    /// Check out `rev` of the repository holding `repo` into `dir`, detached.
+    pub fn add(repo: &Path, rev: &str, dir: &Path) -> TraitError<Self> {
+        let commit = git(
+            repo,
+            &["rev-parse", "--verify", &format!("{rev}^{{commit}}")],
+        )?;
+        let dir_arg = dir.to_string_lossy();
+        git(
+            repo,
+            &["worktree", "add", "--detach", "--quiet", &dir_arg, &commit],
+        )?;
+        Ok(Self {
+            repo: repo.to_path_buf(),
+            dir: dir.to_path_buf(),
+        })
+    }
+
+    /// The top of the checkout.
+    pub fn root(&self) -> &Path {
+        &self.dir
+    }
+}
+
+impl Drop for RevisionTree {
+    fn drop(&mut self) {
+        let dir = self.dir.to_string_lossy();
+        if git(&self.repo, &["worktree", "remove", "--force", &dir]).is_err() {
+            let _ = std::fs::remove_dir_all(&self.dir);
+            let _ = git(&self.repo, &["worktree", "prune"]);
+        }
+    }
+}
+
+/// Diff the crate or workspace at `target`, inside a git repository, between the
+/// revisions `from` and `to`. `check` checks the target as a revision has it, given its
+/// path in that revision's checkout.
+pub fn diff_revisions(
+    target: &Path,
+    from: &str,
+    to: &str,
+    mut check: impl FnMut(&Path) -> TraitError<Report>,
+) -> TraitError<RevisionDiff> {
+    let prefix = target_in_repo(target)?;
+    let base = std::env::temp_dir().join(format!("trait-winnower-diff-{}", std::process::id()));
+    let mut reports = Vec::with_capacity(2);
+    for (i, rev) in [from, to].into_iter().enumerate() {
+        let tree = RevisionTree::add(target, rev, &base.join(i.to_string()))?;
+        reports.push(check(&tree.root().join(&prefix))?);
+    }
+    let _ = std::fs::remove_dir(&base);
+    let items = diff_reports(&reports[0], &reports[1]);
+    Ok(RevisionDiff {
+        from: from.to_owned(),
+        to: to.to_owned(),
+        summary: DiffSummary::of(&items),
+        items,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn minus_counts_repeats() {
+        let v = |s: &[&str]| s.iter().map(|s| s.to_string()).collect::<Vec<_>>();
+        assert_eq!(minus(&v(&["a", "a", "b"]), &v(&["a", "c"])), v(&["a", "b"]));
+        assert_eq!(minus(&v(&["a"]), &v(&["a"])), v(&[]));
+    }
+}
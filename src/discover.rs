@@ -3,72 +3,541 @@
 
 #![deny(missing_docs)]
 
-use crate::error::TraitError;
+use crate::analysis::ItemBounds;
+use crate::config::TargetArea;
+use crate::error::{TraitError, WinnowerError};
 use globset::{Glob, GlobSet, GlobSetBuilder};
-use ignore::WalkBuilder;
+use ignore::gitignore::{Gitignore, GitignoreBuilder};
+use ignore::{Match, WalkBuilder};
+use serde::{Deserialize, Serialize};
+use std::collections::{HashMap, HashSet};
 use std::path::{Path, PathBuf};
+use std::sync::Arc;
 
 /// File discovery utilities.
 pub struct Discover;
 
+/// Why a path under the root was not selected for analysis.
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
+pub enum SkipReason {
+    /// The root-relative path matched none of the include patterns.
+    NotIncluded,
+    /// The root-relative path matched an exclude pattern.
+    Excluded {
+        /// The first exclude pattern that matched.
+        pattern: String,
+    },
+    /// The path lies in a directory of tests, examples or benches, which the config does
+    /// not analyze (see [`Config::analyzes`](crate::config::Config::analyzes)).
+    LeftOut {
+        /// The area.
+        area: TargetArea,
+    },
+    /// The path is ignored by `.gitignore`, `.ignore` or git's exclude files.
+    Ignored {
+        /// The rule that ignores it, when it could be told (see [`IgnoreRule`]).
+        by: Option<IgnoreRule>,
+    },
+    /// The entry is neither a regular file nor a directory (e.g. an unfollowed symlink).
+    NotAFile,
+    /// The file does not have an `.rs` extension.
+    WrongExtension,
+    /// The file was already discovered under another path.
+    Duplicate {
+        /// The path it was first discovered under.
+        of: PathBuf,
+    },
+    /// The file lies in a build script's `OUT_DIR` (see
+    /// [`CrateMap::is_generated`](crate::crate_map::CrateMap::is_generated)).
+    Generated,
+}
+
+impl std::fmt::Display for SkipReason {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            SkipReason::NotIncluded => f.write_str("not matched by include"),
+            SkipReason::Excluded { pattern } => write!(f, "matched exclude pattern `{pattern}`"),
+            SkipReason::LeftOut { area } => write!(
+                f,
+                "in `{}/`, which `{}` leaves out",
+                area.dir(),
+                area.knob()
+            ),
+            SkipReason::Ignored { by: None } => f.write_str("ignored"),
+            SkipReason::Ignored { by: Some(rule) } => write!(f, "ignored by {rule}"),
+            SkipReason::NotAFile => f.write_str("not a file"),
+            SkipReason::WrongExtension => f.write_str("wrong extension"),
+            SkipReason::Duplicate { .. } => f.write_str("duplicate of another path"),
+            SkipReason::Generated => f.write_str("generated into OUT_DIR"),
+        }
+    }
+}
+
+/// A pattern of an ignore file that kept a path out of the walk.
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
+pub struct IgnoreRule {
+    /// The ignore file: a `.gitignore` or `.ignore`, `.git/info/exclude` or the global
+    /// gitignore.
+    pub file: PathBuf,
+    /// The pattern as written, e.g. `vendor/`.
+    pub pattern: String,
+}
+
+impl std::fmt::Display for IgnoreRule {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "`{}` in {}", self.pattern, self.file.display())
+    }
+}
+
+/// Which ignore files discovery honors: `.gitignore` and `.ignore` files, git's
+/// `info/exclude` and, unless turned off, the global gitignore (`core.excludesFile`),
+/// which differs from machine to machine.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct IgnoreFiles {
+    /// Honor ignore files at all.
+    pub respect: bool,
+    /// Honor the global gitignore too.
+    pub global: bool,
+}
+
+impl Default for IgnoreFiles {
+    fn default() -> Self {
+        Self {
+            respect: true,
+            global: true,
+        }
+    }
+}
+
+/// A path that discovery looked at and did not select.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SkippedFile {
+    /// The walked path.
+    pub path: PathBuf,
+    /// Why it was skipped.
+    pub reason: SkipReason,
+}
+
+/// An out-of-line `mod name;` declaration whose file could not be found.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct MissingModule {
+    /// The file containing the declaration.
+    pub declared_in: PathBuf,
+    /// The declared module name.
+    pub name: String,
+    /// Paths that were tried, in order.
+    pub tried: Vec<PathBuf>,
+}
+
+/// Files reachable from a root file through out-of-line `mod` declarations.
+#[derive(Debug, Default)]
+pub struct ModuleFiles {
+    /// The root file followed by resolved module files, in declaration order.
+    pub files: Vec<PathBuf>,
+    /// Declarations that did not resolve to a file.
+    pub missing: Vec<MissingModule>,
+}
+
+/// Result of a discovery run with skip records.
+#[derive(Debug, Default)]
+pub struct Discovery {
+    /// Selected `.rs` files.
+    pub files: Vec<PathBuf>,
+    /// Everything that was looked at but not selected.
+    pub skipped: Vec<SkippedFile>,
+}
+
 impl Discover {
     /// Find `.rs` files under `root`, applying `include` then subtracting `exclude` (exclude wins).
-    /// Glob matching uses root-relative paths; returned file paths are absolute.
+    /// Glob matching uses root-relative paths; returned file paths are joined onto `root`.
+    /// Symbolic links are not followed.
     pub fn discover_rs_files(
         root: &Path,
         include: &[String],
         exclude: &[String],
+    ) -> TraitError<Vec<PathBuf>> {
+        Self::discover_rs_files_with(root, include, exclude, false)
+    }
+
+    /// Like [`Discover::discover_rs_files`], optionally following symbolic links.
+    ///
+    /// Globs always match the walked (non-canonical) root-relative path, so patterns refer to
+    /// what the user sees in the tree. Files reachable under several paths (symlinks,
+    /// case-insensitive filesystems) are deduplicated by canonical path; the first walked path
+    /// is kept.
+    pub fn discover_rs_files_with(
+        root: &Path,
+        include: &[String],
+        exclude: &[String],
+        follow_symlinks: bool,
+    ) -> TraitError<Vec<PathBuf>> {
+        let walk = Walk {
+            follow_symlinks,
+            ignore: IgnoreFiles::default(),
+            left_out: &[],
+        };
+        walk.run(root, include, exclude, None)
+    }
+
+    /// Like [`Discover::discover_rs_files_with`], additionally recording every skipped path
+    /// and the reason. Ignored directories are reported once, without their contents.
+    pub fn discover_rs_files_report(
+        root: &Path,
+        include: &[String],
+        exclude: &[String],
+        follow_symlinks: bool,
+    ) -> TraitError<Discovery> {
+        let ignore = IgnoreFiles::default();
+        Self::discover_rs_files_report_with(root, include, exclude, follow_symlinks, ignore, &[])
+    }
+
+    /// Like [`Discover::discover_rs_files_report`], honoring only the ignore files `ignore`
+    /// asks for and leaving out the sources of the `left_out` areas. Paths ignore files
+    /// ignore are reported with the rule that ignores them.
+    pub fn discover_rs_files_report_with(
+        root: &Path,
+        include: &[String],
+        exclude: &[String],
+        follow_symlinks: bool,
+        ignore: IgnoreFiles,
+        left_out: &[TargetArea],
+    ) -> TraitError<Discovery> {
+        let mut skipped = Vec::new();
+        let walk = Walk {
+            follow_symlinks,
+            ignore,
+            left_out,
+        };
+        let files = walk.run(root, include, exclude, Some(&mut skipped))?;
+        Ok(Discovery { files, skipped })
+    }
+
+    /// The files under `root` that git sees changed since `rev`: differing from `rev` in
+    /// the working tree, staged or not, or untracked and not ignored. Paths are relative
+    /// to `root`.
+    pub fn changed_since(root: &Path, rev: &str) -> TraitError<HashSet<PathBuf>> {
+        let git = |args: &[&str]| -> TraitError<Vec<PathBuf>> {
+            let out = std::process::Command::new("git")
+                .args(args)
+                .current_dir(root)
+                .output()
+                .map_err(|e| WinnowerError::Discovery(format!("running git: {e}")))?;
+            if !out.status.success() {
+                return Err(WinnowerError::Discovery(format!(
+                    "`git {}` failed: {}",
+                    args.join(" "),
+                    String::from_utf8_lossy(&out.stderr).trim()
+                )));
+            }
+            Ok(String::from_utf8_lossy(&out.stdout)
+                .lines()
+                .filter(|l| !l.is_empty())
+                .map(PathBuf::from)
+                .collect())
+        };
+        let mut changed: HashSet<PathBuf> = git(&["diff", "--name-only", "--relative", rev, "--"])?
+            .into_iter()
+            .collect();
+        changed.extend(git(&["ls-files", "--others", "--exclude-standard"])?);
+        Ok(changed)
+    }
+
+    /// Resolve the module tree rooted at `root_file` the way rustc does for a crate root:
+    /// `mod name;` loads `name.rs` or `name/mod.rs`, nested under the declaring module's
+    /// directory, and `#[path = "..."]` overrides the location.
+    pub fn resolve_module_files(root_file: &Path) -> TraitError<ModuleFiles> {
+        let mut out = ModuleFiles::default();
+        let mut seen = HashSet::new();
+        let dir = root_file.parent().unwrap_or(Path::new("")).to_path_buf();
+        Self::resolve_file(root_file, &dir, &mut seen, &mut out)?;
+        Ok(out)
+    }
+
+    fn resolve_file(
+        file: &Path,
+        mod_dir: &Path,
+        seen: &mut HashSet<PathBuf>,
+        out: &mut ModuleFiles,
+    ) -> TraitError<()> {
+        let key = file.canonicalize().unwrap_or_else(|_| file.to_path_buf());
+        if !seen.insert(key) {
+            return Ok(());
+        }
+        out.files.push(file.to_path_buf());
+        let ast = ItemBounds::parse_file(file)?;
+        let file_dir = file.parent().unwrap_or(Path::new(""));
+        Self::resolve_items(&ast.items, file, file_dir, mod_dir, true, seen, out)
+    }
+
+    fn resolve_items(
+        items: &[syn::Item],
+        file: &Path,
+        file_dir: &Path,
+        mod_dir: &Path,
+        top_level: bool,
+        seen: &mut HashSet<PathBuf>,
+        out: &mut ModuleFiles,
+    ) -> TraitError<()> {
+        for item in items {
+            let syn::Item::Mod(m) = item else { continue };
+            let name = m.ident.to_string();
+            let path_attr = Self::path_attr(&m.attrs);
+            // Top-level `#[path]` is relative to the declaring file's directory; inside
+            // inline modules it is relative to the inline module's directory.
+            let attr_base = if top_level { file_dir } else { mod_dir };
+            match (&m.content, path_attr) {
+                (Some((_, inner)), Some(p)) => {
+                    let dir = attr_base.join(p);
+                    Self::resolve_items(inner, file, file_dir, &dir, false, seen, out)?;
+                }
+                (Some((_, inner)), None) => {
+                    let dir = mod_dir.join(&name);
+                    Self::resolve_items(inner, file, file_dir, &dir, false, seen, out)?;
+                }
+                (None, Some(p)) => {
+                    let target = attr_base.join(p);
+                    if target.is_file() {
+                        // Files loaded through `#[path]` behave like `mod.rs` files.
+                        let dir = target.parent().unwrap_or(Path::new("")).to_path_buf();
+                        Self::resolve_file(&target, &dir, seen, out)?;
+                    } else {
+                        out.missing.push(MissingModule {
+                            declared_in: file.to_path_buf(),
+                            name,
+                            tried: vec![target],
+                        });
+                    }
+                }
+                (None, None) => {
+                    let flat = mod_dir.join(format!("{name}.rs"));
+                    let nested = mod_dir.join(&name).join("mod.rs");
+                    if flat.is_file() {
+                        let dir = mod_dir.join(&name);
+                        Self::resolve_file(&flat, &dir, seen, out)?;
+                    } else if nested.is_file() {
+                        let dir = mod_dir.join(&name);
+                        Self::resolve_file(&nested, &dir, seen, out)?;
+                    } else {
+                        out.missing.push(MissingModule {
+                            declared_in: file.to_path_buf(),
+                            name,
+                            tried: vec![flat, nested],
+                        });
+                    }
+                }
+            }
+        }
+        Ok(())
+    }
+
+    fn path_attr(attrs: &[syn::Attribute]) -> Option<String> {
+        attrs.iter().find_map(|a| match &a.meta {
+            syn::Meta::NameValue(nv) if nv.path.is_ident("path") => match &nv.value {
+                syn::Expr::Lit(syn::ExprLit {
+                    lit: syn::Lit::Str(s),
+                    ..
+                }) => Some(s.value()),
+                _ => None,
+            },
+            _ => None,
+        })
+    }
+
+    fn globset(patterns: &[String]) -> TraitError<GlobSet> {
+        let mut b = GlobSetBuilder::new();
+        for p in patterns {
+            b.add(Glob::new(p).map_err(|e| WinnowerError::Discovery(e.to_string()))?);
+        }
+        b.build()
+            .map_err(|e| WinnowerError::Discovery(e.to_string()))
+    }
+}
+
+/// How one discovery walks the tree.
+struct Walk<'a> {
+    follow_symlinks: bool,
+    ignore: IgnoreFiles,
+    left_out: &'a [TargetArea],
+}
+
+impl Walk<'_> {
+    fn run(
+        &self,
+        root: &Path,
+        include: &[String],
+        exclude: &[String],
+        mut skipped: Option<&mut Vec<SkippedFile>>,
     ) -> TraitError<Vec<PathBuf>> {
         let inc = if include.is_empty() {
             vec!["**/*".into()]
         } else {
             include.to_vec()
         };
-        let inc_set = Self::globset(&inc)?;
-        let exc_set = Self::globset(exclude)?;
-
-        let mut walk = WalkBuilder::new(root);
-        walk.hidden(false)
-            .ignore(true)
-            .git_ignore(true)
-            .git_exclude(true)
-            .git_global(true)
-            .follow_links(false);
+        let inc_set = Discover::globset(&inc)?;
+        let exc_set = Discover::globset(exclude)?;
 
+        let mut visited = HashSet::new();
+        let mut seen: HashMap<PathBuf, PathBuf> = HashMap::new();
         let mut out = Vec::new();
-        for dent in walk.build() {
+        for dent in self.walker(root, self.ignore.respect).build() {
             let dent = match dent {
                 Ok(d) => d,
                 Err(_) => continue,
             };
-            if !dent.file_type().map(|t| t.is_file()).unwrap_or(false) {
+            let path = dent.path();
+            if skipped.is_some() {
+                visited.insert(path.to_path_buf());
+            }
+            let file_type = dent.file_type();
+            if file_type.map(|t| t.is_dir()).unwrap_or(false) {
                 continue;
             }
-            if dent.path().extension().and_then(|s| s.to_str()) != Some("rs") {
+            let mut skip = |reason| {
+                if let Some(s) = skipped.as_deref_mut() {
+                    s.push(SkippedFile {
+                        path: path.to_path_buf(),
+                        reason,
+                    });
+                }
+            };
+            if !file_type.map(|t| t.is_file()).unwrap_or(false) {
+                skip(SkipReason::NotAFile);
+                continue;
+            }
+            if path.extension().and_then(|s| s.to_str()) != Some("rs") {
+                skip(SkipReason::WrongExtension);
                 continue;
             }
 
-            let path = dent.path();
             let rel = path.strip_prefix(root).unwrap_or(path);
             let rel_str = rel.to_string_lossy().replace('\\', "/");
 
             if !inc_set.is_match(&rel_str) {
+                skip(SkipReason::NotIncluded);
                 continue;
             }
-            if exc_set.is_match(&rel_str) {
+            if let Some(&area) = self.left_out.iter().find(|a| a.contains(rel)) {
+                skip(SkipReason::LeftOut { area });
+                continue;
+            }
+            if let Some(&idx) = exc_set.matches(&rel_str).first() {
+                skip(SkipReason::Excluded {
+                    pattern: exclude[idx].clone(),
+                });
                 continue;
             }
 
+            let canonical = path.canonicalize().unwrap_or_else(|_| path.to_path_buf());
+            if let Some(first) = seen.get(&canonical) {
+                skip(SkipReason::Duplicate { of: first.clone() });
+                continue;
+            }
+            seen.insert(canonical, path.to_path_buf());
+
             out.push(path.to_path_buf());
         }
+
+        if let Some(skipped) = skipped
+            && self.ignore.respect
+        {
+            self.collect_ignored(root, visited, skipped);
+        }
         Ok(out)
     }
 
-    fn globset(patterns: &[String]) -> TraitError<GlobSet> {
-        let mut b = GlobSetBuilder::new();
-        for p in patterns {
-            b.add(Glob::new(p)?);
+    /// Walk again without ignore files and report the top-most entries the first walk
+    /// never reached.
+    fn collect_ignored(
+        &self,
+        root: &Path,
+        visited: HashSet<PathBuf>,
+        skipped: &mut Vec<SkippedFile>,
+    ) {
+        let visited = Arc::new(visited);
+        let filter = Arc::clone(&visited);
+        let mut walk = self.walker(root, false);
+        walk.filter_entry(move |e| {
+            filter.contains(e.path()) || e.path().parent().is_none_or(|p| filter.contains(p))
+        });
+        for dent in walk.build().flatten() {
+            if !visited.contains(dent.path()) {
+                let is_dir = dent.file_type().is_some_and(|t| t.is_dir());
+                skipped.push(SkippedFile {
+                    path: dent.path().to_path_buf(),
+                    reason: SkipReason::Ignored {
+                        by: self.ignored_by(dent.path(), is_dir),
+                    },
+                });
+            }
+        }
+    }
+
+    fn walker(&self, root: &Path, respect_ignores: bool) -> WalkBuilder {
+        let mut walk = WalkBuilder::new(root);
+        walk.hidden(false)
+            .ignore(respect_ignores)
+            .git_ignore(respect_ignores)
+            .git_exclude(respect_ignores)
+            .git_global(respect_ignores && self.ignore.global)
+            .parents(respect_ignores)
+            .follow_links(self.follow_symlinks)
+            .sort_by_file_name(|a, b| a.cmp(b));
+        walk
+    }
+
+    /// The rule that ignores `path`, a directory if `is_dir`, going by the walker's
+    /// precedence: the `.ignore`, then the `.gitignore` of the nearest directory up to the
+    /// repository root, then `.git/info/exclude`, then the global gitignore. `None` when
+    /// none matches, or the nearest match re-includes it.
+    fn ignored_by(&self, path: &Path, is_dir: bool) -> Option<IgnoreRule> {
+        let mut repo = None;
+        for dir in path.ancestors().skip(1) {
+            for name in [".ignore", ".gitignore"] {
+                let file = dir.join(name);
+                if file.is_file() {
+                    let mut builder = GitignoreBuilder::new(dir);
+                    builder.add(&file);
+                    if let Ok(gi) = builder.build()
+                        && let Some(found) = Self::rule(&gi, path, dir, is_dir)
+                    {
+                        return found;
+                    }
+                }
+            }
+            if dir.join(".git").exists() {
+                repo = Some(dir);
+                break;
+            }
+        }
+        let repo = repo?;
+        let exclude = repo.join(".git/info/exclude");
+        if exclude.is_file() {
+            let mut builder = GitignoreBuilder::new(repo);
+            builder.add(&exclude);
+            if let Ok(gi) = builder.build()
+                && let Some(found) = Self::rule(&gi, path, repo, is_dir)
+            {
+                return found;
+            }
+        }
+        if self.ignore.global {
+            let (gi, _) = Gitignore::global();
+            return Self::rule(&gi, path, repo, is_dir).flatten();
+        }
+        None
+    }
+
+    /// What `gi`, whose patterns are relative to `base`, says of `path`: `None` when it
+    /// does not match, else the rule ignoring it, or `None` within for a re-include.
+    fn rule(gi: &Gitignore, path: &Path, base: &Path, is_dir: bool) -> Option<Option<IgnoreRule>> {
+        let rel = path.strip_prefix(base).ok()?;
+        match gi.matched_path_or_any_parents(rel, is_dir) {
+            Match::None => None,
+            Match::Whitelist(_) => Some(None),
+            Match::Ignore(glob) => Some(Some(IgnoreRule {
+                file: glob.from().map(Path::to_path_buf).unwrap_or_default(),
+                pattern: glob.original().to_owned(),
+            })),
         }
-        Ok(b.build()?)
     }
 }
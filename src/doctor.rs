@@ -0,0 +1,263 @@
+// src/doctor.rs
+//! The checks `prune` makes before its first trial, and `trait-winnower doctor`, which
+//! runs them all and reports each.
+//!
+//! [`preflight`] stops at the first failure, as a run cannot go on past it. [`doctor`]
+//! goes through every [`Check`], also those only worth a warning, such as a build lock
+//! another cargo process holds right now, so a setup can be diagnosed in one go: a
+//! read-only checkout, a target directory owned by another user, a missing component.
+
+#![deny(missing_docs)]
+
+use crate::config::{Config, ValidatorKind};
+use crate::dynamic_analysis::common::{CargoCheck, Toolchain};
+use crate::dynamic_analysis::journal::Journal;
+use crate::error::{TraitError, WinnowerError};
+use std::fmt;
+use std::path::Path;
+
+/// One check of [`doctor`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Check {
+    /// No earlier run left trial edits behind (see [`recover`](crate::dynamic_analysis::journal::recover)).
+    Journal,
+    /// With `strict_validation`, the `cargo check` arguments build everything.
+    StrictValidation,
+    /// Cargo runs and answers like cargo.
+    Toolchain,
+    /// The target directory of validation can be created and written to.
+    TargetDir,
+    /// Clippy is installed when it validates.
+    Clippy,
+    /// The targets of the validation matrix are installed.
+    MatrixTargets,
+    /// No other cargo process holds a lock on the target directory right now.
+    BuildLock,
+}
+
+impl Check {
+    /// Every check, in the order [`doctor`] runs them.
+    pub const ALL: [Check; 7] = [
+        Check::Journal,
+        Check::StrictValidation,
+        Check::Toolchain,
+        Check::TargetDir,
+        Check::Clippy,
+        Check::MatrixTargets,
+        Check::BuildLock,
+    ];
+
+    /// What the check is about, e.g. `target directory`.
+    pub fn name(self) -> &'static str {
+        match self {
+            Check::Journal => "interrupted runs",
+            Check::StrictValidation => "strict validation",
+            Check::Toolchain => "toolchain",
+            Check::TargetDir => "target directory",
+            Check::Clippy => "clippy",
+            Check::MatrixTargets => "matrix targets",
+            Check::BuildLock => "build lock",
+        }
+    }
+
+    /// Whether [`preflight`] makes the check for runs validated by `validation`. Replayed
+    /// runs check only what the recording cannot answer for; custom validators are not
+    /// `cargo check` at all. The build lock is only worth a warning.
+    pub fn in_preflight(self, validation: Validation) -> bool {
+        match self {
+            Check::Journal => true,
+            Check::StrictValidation => validation != Validation::Custom,
+            Check::BuildLock => false,
+            _ => validation == Validation::Cargo,
+        }
+    }
+
+    /// Run the check on the crate or workspace at `root`, configured by `cfg`.
+    pub fn run(self, root: &Path, cfg: &Config) -> TraitError<Status> {
+        let cargo_check = &cfg.cargo_check;
+        match self {
+            Check::Journal => {
+                let pending = Journal::pending(root)?;
+                if !pending.is_empty() {
+                    return Err(WinnowerError::Interrupted {
+                        root: root.to_path_buf(),
+                        files: pending.into_iter().map(|e| e.path).collect(),
+                    });
+                }
+                Ok(Status::Ok("no trial edits left behind".into()))
+            }
+            Check::StrictValidation => {
+                let gaps = cargo_check.coverage_gaps();
+                if !cfg.strict_validation {
+                    return Ok(Status::Skipped("strict_validation is off".into()));
+                }
+                if !gaps.is_empty() {
+                    let gaps: Vec<String> = gaps.iter().map(ToString::to_string).collect();
+                    return Err(WinnowerError::Config {
+                        path: root.join(".trait-winnower.toml"),
+                        detail: format!(
+                            "strict_validation is set but `cargo check {}` leaves out {}",
+                            cargo_check.args.join(" "),
+                            gaps.join("; ")
+                        ),
+                    });
+                }
+                Ok(Status::Ok("`cargo check` builds everything".into()))
+            }
+            Check::Toolchain => Ok(Status::Ok(
+                CargoCheck::toolchain(root, cargo_check)?.to_string(),
+            )),
+            Check::TargetDir => {
+                CargoCheck::check_target_dir(root, cargo_check)?;
+                Ok(Status::Ok(format!(
+                    "{} is writable",
+                    cargo_check.target_dir(root).display()
+                )))
+            }
+            Check::Clippy if cfg.validator != ValidatorKind::Clippy => {
+                Ok(Status::Skipped("validation runs `cargo check`".into()))
+            }
+            Check::Clippy => Ok(Status::Ok(CargoCheck::clippy_version(root, cargo_check)?)),
+            Check::MatrixTargets => {
+                if cargo_check.matrix.iter().all(|e| e.target.is_none()) {
+                    return Ok(Status::Skipped("the matrix names no targets".into()));
+                }
+                let missing = CargoCheck::missing_targets(root, cargo_check);
+                if !missing.is_empty() {
+                    return Err(WinnowerError::Config {
+                        path: root.join(".trait-winnower.toml"),
+                        detail: format!(
+                            "the validation matrix needs targets that are not installed; run `rustup target add {}`",
+                            missing.join(" ")
+                        ),
+                    });
+                }
+                Ok(Status::Ok("installed".into()))
+            }
+            Check::BuildLock => Ok(build_lock(&cargo_check.target_dir(root))),
+        }
+    }
+}
+
+/// What a [`Check`] that did not fail found.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Status {
+    /// All is well.
+    Ok(String),
+    /// Runs will go on, but maybe not as expected.
+    Warning(String),
+    /// The configuration does not need the check.
+    Skipped(String),
+}
+
+impl fmt::Display for Status {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Status::Ok(detail) => write!(f, "ok: {detail}"),
+            Status::Warning(detail) => write!(f, "warning: {detail}"),
+            Status::Skipped(detail) => write!(f, "skipped: {detail}"),
+        }
+    }
+}
+
+/// What validates the trials of a run.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Validation {
+    /// `cargo check`, as the configuration says.
+    Cargo,
+    /// A recording of `cargo check` (see
+    /// [`WinnowerBuilder::replay`](crate::winnower::WinnowerBuilder::replay)).
+    Replay,
+    /// A custom [`Validator`](crate::dynamic_analysis::validate::Validator).
+    Custom,
+}
+
+/// A [`Check`] and what it found.
+#[derive(Debug)]
+pub struct Diagnosis {
+    /// The check.
+    pub check: Check,
+    /// What it found, or why it failed.
+    pub result: TraitError<Status>,
+}
+
+/// Make the checks `prune` needs before its first trial on the crate or workspace at
+/// `root`, configured by `cfg` and validated by `validation` (see [`Check::in_preflight`]),
+/// stopping at the first failure. Returns the toolchain `cargo check` runs with, when
+/// there was a [`Check::Toolchain`].
+pub fn preflight(
+    root: &Path,
+    cfg: &Config,
+    validation: Validation,
+) -> TraitError<Option<Toolchain>> {
+    let mut toolchain = None;
+    for check in Check::ALL
+        .into_iter()
+        .filter(|c| c.in_preflight(validation))
+    {
+        match check {
+            Check::Toolchain => toolchain = Some(CargoCheck::toolchain(root, &cfg.cargo_check)?),
+            _ => {
+                check.run(root, cfg)?;
+            }
+        }
+    }
+    Ok(toolchain)
+}
+
+/// Every [`Check`] on the crate or workspace at `root`, configured by its
+/// `.trait-winnower.toml`; a configuration that does not load fails them all.
+pub fn doctor(root: &Path) -> TraitError<Vec<Diagnosis>> {
+    let cfg = Config::load_or_default(root)?;
+    Ok(Check::ALL
+        .into_iter()
+        .map(|check| Diagnosis {
+            check,
+            result: check.run(root, &cfg),
+        })
+        .collect())
+}
+
+/// Whether another process holds a cargo lock in `dir`: the `.cargo-lock` of a profile
+/// directory (`debug`, `release`, or one under a target triple) it cannot take.
+fn build_lock(dir: &Path) -> Status {
+    let locks = std::fs::read_dir(dir)
+        .into_iter()
+        .flatten()
+        .flatten()
+        .flat_map(|entry| {
+            let path = entry.path();
+            let nested = std::fs::read_dir(&path).into_iter().flatten().flatten();
+            std::iter::once(path).chain(nested.map(|e| e.path()))
+        })
+        .map(|profile| profile.join(".cargo-lock"))
+        .filter(|lock| lock.is_file());
+    for lock in locks {
+        if is_held(&lock) {
+            return Status::Warning(format!(
+                "{} is held by another cargo process; checks will wait for it (see \
+                 `lock_wait_timeout` under [cargo_check])",
+                lock.display()
+            ));
+        }
+    }
+    Status::Ok(format!("nothing holds a lock in {}", dir.display()))
+}
+
+/// Whether another process holds the file lock of `path`: taking it without blocking
+/// fails. The lock is released at once.
+#[cfg(unix)]
+fn is_held(path: &Path) -> bool {
+    use std::os::fd::AsRawFd;
+    let Ok(file) = std::fs::File::open(path) else {
+        return false;
+    };
+    // SAFETY: `flock` only takes a lock on the open descriptor, released when it closes.
+    let taken = unsafe { libc::flock(file.as_raw_fd(), libc::LOCK_EX | libc::LOCK_NB) } == 0;
+    !taken && std::io::Error::last_os_error().kind() == std::io::ErrorKind::WouldBlock
+}
+
+#[cfg(not(unix))]
+fn is_held(_path: &Path) -> bool {
+    false
+}
@@ -0,0 +1,533 @@
+// src/dynamic_analysis/batch.rs
+//! Cross-file batches: one validation for removals from several files.
+
+#![deny(missing_docs)]
+
+use crate::analysis::{ItemBounds, ItemKey};
+use crate::dynamic_analysis::common::{
+    BoundCandidate, BoundRemovalOutcome, BoundRemovalResult, BoundsSnapshot, ValidationOutcome,
+};
+use crate::dynamic_analysis::edit::{Anchor, BoundEditor, PruneContext};
+use crate::dynamic_analysis::text_edit::with_header_of;
+use crate::error::TraitError;
+use crate::observer::Observer;
+use crate::report::{Finding, Removal};
+use crate::target::TargetType;
+use quote::ToTokens;
+use std::collections::VecDeque;
+use std::path::{Path, PathBuf};
+use std::time::{Duration, Instant};
+use syn::visit_mut::VisitMut;
+
+/// A removal waiting for its turn: `group`, the declarations of one bound, from one item.
+struct Pending {
+    anchor: Anchor,
+    label: String,
+    group: Vec<BoundCandidate>,
+    findings: Vec<Finding>,
+    before: String,
+    after: String,
+    /// The same group but its first declaration, tried if the whole group is rejected.
+    repeats: Option<Box<Pending>>,
+}
+
+impl Pending {
+    fn new(ctx: &PruneContext<'_>, key: &ItemKey<'_>, group: Vec<BoundCandidate>) -> Self {
+        let (before, after) = Removal::generics_before_after(key.item(), &group);
+        let label = key.to_string();
+        Self {
+            anchor: Anchor::of(key),
+            label: label.trim_start_matches("// ").to_owned(),
+            findings: group.iter().map(|c| ctx.finding(key, c)).collect(),
+            group,
+            before,
+            after,
+            repeats: None,
+        }
+    }
+    /// Put `original` back as the generics of the item in `working`, undoing the removal.
+    fn undo(&self, working: &mut syn::File, original: BoundsSnapshot) {
+        BoundEditor::<syn::ItemFn>::restoring(&self.anchor, original).visit_file_mut(working);
+    }
+}
+
+/// A file taking part in batches. `working` also holds the removals in flight, `src` and
+/// `hash` only those accepted.
+struct BatchFile {
+    path: PathBuf,
+    working: syn::File,
+    src: String,
+    hash: u32,
+    queue: VecDeque<Pending>,
+    results: Vec<BoundRemovalResult>,
+    tried: usize,
+    checks: usize,
+    aborted: usize,
+    aborted_time: Duration,
+    cached: usize,
+    interrupted: bool,
+}
+
+impl BatchFile {
+    /// Report `pending` as settled with `outcome`; its cost is booked on the first member.
+    fn record(
+        &mut self,
+        pending: &Pending,
+        outcome: BoundRemovalOutcome,
+        (check_time, write_time): (Duration, Duration),
+        batch: Option<usize>,
+        evident: Option<&str>,
+        observer: &mut dyn Observer,
+    ) {
+        observer.on_candidate_trial(
+            &pending.label,
+            &pending.group[0].bound.to_token_stream().to_string(),
+            self.tried,
+            self.tried + 1 + self.queue.len(),
+        );
+        self.tried += 1;
+        for (n, (candidate, finding)) in pending.group.iter().zip(&pending.findings).enumerate() {
+            let mut finding = finding.clone();
+            finding.statically_used = evident.map(str::to_owned);
+            let (check_time, write_time) = if n == 0 {
+                (check_time, write_time)
+            } else {
+                (Duration::ZERO, Duration::ZERO)
+            };
+            self.results.push(BoundRemovalResult {
+                candidate: candidate.clone(),
+                outcome: outcome.clone(),
+                finding,
+                before: pending.before.clone(),
+                after: pending.after.clone(),
+                check_time,
+                write_time,
+                batch,
+            });
+            if let Some(result) = self.results.last() {
+                observer.on_outcome(result);
+            }
+        }
+    }
+}
+
+/// A pending removal applied to the text of its file.
+struct Trial {
+    file: usize,
+    pending: Pending,
+    /// The generics and arguments of the item before the removal, to undo it.
+    original: BoundsSnapshot,
+    src: String,
+    hash: u32,
+    /// Time of the checks this trial led, booked on it whatever its outcome.
+    check_time: Duration,
+    write_time: Duration,
+}
+
+/// What a [`BatchRunner`] did to one file.
+#[derive(Debug)]
+pub struct BatchedFile {
+    /// The file, as passed to [`BatchRunner::add_file`].
+    pub path: PathBuf,
+    /// Every candidate settled, in the order it was.
+    pub results: Vec<BoundRemovalResult>,
+    /// The checks counted against the file: those a trial of it came first in.
+    pub checks: usize,
+    /// Of `checks`, those stopped at their first error, and the time they took.
+    pub aborted: usize,
+    /// Time spent in the `aborted` checks.
+    pub aborted_time: Duration,
+    /// Checks of batches it led answered from the trial cache instead of run.
+    pub cached: usize,
+    /// Whether every candidate was settled; `false` once the run was cancelled.
+    pub finished: bool,
+}
+
+/// Prunes several files at once: each round applies the next removal of up to `size`
+/// distinct files and validates them with one check. A failing round is reverted and split
+/// in halves, each settled the same way, so a single rejected removal costs a few more
+/// checks and every other one in the round is still accepted. Once a half is accepted in
+/// full, the other is known to fail on top of it and is split without checking it again.
+///
+/// Removals from different files are taken to be independent; rejections are not shared
+/// across a scope (see [`RejectionMemo`](crate::dynamic_analysis::edit::RejectionMemo)).
+pub struct BatchRunner<'a> {
+    ctx: &'a PruneContext<'a>,
+    size: usize,
+    files: Vec<BatchFile>,
+    batches: usize,
+}
+
+impl<'a> BatchRunner<'a> {
+    /// A runner writing through `ctx` with batches of at most `size` files.
+    pub fn new(ctx: &'a PruneContext<'a>, size: usize) -> Self {
+        Self {
+            ctx,
+            size: size.max(1),
+            files: Vec::new(),
+            batches: 0,
+        }
+    }
+
+    /// Queue the candidates of the items of `types` in `syntax`, the parsed text of `path`.
+    /// Those [`PruneContext::screen`] leaves out or the prefilter skips are reported at once.
+    pub fn add_file(
+        &mut self,
+        path: &Path,
+        syntax: &syn::File,
+        items: &ItemBounds<'_>,
+        types: &[TargetType],
+        observer: &mut dyn Observer,
+    ) -> TraitError<()> {
+        let src = self.ctx.store.read(path)?;
+        let mut file = BatchFile {
+            path: path.to_path_buf(),
+            working: syntax.clone(),
+            hash: crc32fast::hash(src.as_bytes()),
+            src,
+            queue: VecDeque::new(),
+            results: Vec::new(),
+            tried: 0,
+            checks: 0,
+            aborted: 0,
+            aborted_time: Duration::ZERO,
+            cached: 0,
+            interrupted: false,
+        };
+        let candidates: Vec<_> = items
+            .candidates()
+            .filter(|(key, _)| key.kind().target_type().is_some_and(|t| types.contains(&t)))
+            .collect();
+        for item in candidates.chunk_by(|a, b| std::ptr::eq(a.0, b.0)) {
+            let key = item[0].0;
+            let screened = self.ctx.screen(
+                key,
+                item.iter().map(|(_, c)| c.clone()).collect(),
+                &mut file.results,
+                observer,
+            );
+            for group in BoundCandidate::group_duplicates(screened) {
+                let repeats =
+                    (group.len() > 1).then(|| Pending::new(self.ctx, key, group[1..].to_vec()));
+                if let Some(used) = self.ctx.evident(key, &group[0]) {
+                    let pending = Pending::new(self.ctx, key, group);
+                    let zero = (Duration::ZERO, Duration::ZERO);
+                    let skipped = BoundRemovalOutcome::Skipped;
+                    file.record(&pending, skipped, zero, None, Some(&used), observer);
+                    file.queue.extend(repeats);
+                    continue;
+                }
+                let mut pending = Pending::new(self.ctx, key, group);
+                pending.repeats = repeats.map(Box::new);
+                file.queue.push_back(pending);
+            }
+        }
+        self.files.push(file);
+        Ok(())
+    }
+
+    /// Settle every queued removal. `check` validates the tree with the given files edited;
+    /// it is called once per round and once per half of a failing one.
+    pub fn run(
+        &mut self,
+        check: &dyn Fn(&[&Path]) -> TraitError<ValidationOutcome>,
+        observer: &mut dyn Observer,
+    ) -> TraitError<()> {
+        // Files past a full batch go first in the next one.
+        let mut start = 0;
+        while !self.ctx.cancel.is_cancelled() {
+            let mut batch = Vec::new();
+            let (count, first) = (self.files.len(), start);
+            for fi in (0..count).map(|step| (first + step) % count) {
+                if batch.len() == self.size {
+                    break;
+                }
+                if let Some(trial) = self.next_trial(fi, observer)? {
+                    batch.push(trial);
+                    start = (fi + 1) % count;
+                }
+            }
+            if batch.is_empty() {
+                break;
+            }
+            self.batches += 1;
+            if self
+                .settle(batch, self.batches, None, check, observer)?
+                .is_none()
+            {
+                break;
+            }
+        }
+        Ok(())
+    }
+
+    /// What happened to each file, in the order they were added.
+    pub fn finish(self) -> Vec<BatchedFile> {
+        self.files
+            .into_iter()
+            .map(|f| BatchedFile {
+                finished: f.queue.is_empty() && !f.interrupted,
+                path: f.path,
+                results: f.results,
+                checks: f.checks,
+                aborted: f.aborted,
+                aborted_time: f.aborted_time,
+                cached: f.cached,
+            })
+            .collect()
+    }
+
+    /// The first queued removal of file `fi` that changes its text, reporting those that
+    /// do not as skipped.
+    fn next_trial(&mut self, fi: usize, observer: &mut dyn Observer) -> TraitError<Option<Trial>> {
+        let file = &mut self.files[fi];
+        while let Some(mut pending) = file.queue.pop_front() {
+            let mut editor = BoundEditor::<syn::ItemFn>::new_group(&pending.anchor, &pending.group);
+            editor.visit_file_mut(&mut file.working);
+            if let Some(miss) = editor.take_miss() {
+                for finding in &mut pending.findings {
+                    finding.anchor_miss = Some(miss.clone());
+                }
+            }
+            if let Some(original) = editor.take_original() {
+                let src = with_header_of(&file.src, &prettyplease::unparse(&file.working))?;
+                let hash = crc32fast::hash(src.as_bytes());
+                if hash == file.hash {
+                    pending.undo(&mut file.working, original);
+                } else {
+                    return Ok(Some(Trial {
+                        file: fi,
+                        pending,
+                        original,
+                        src,
+                        hash,
+                        check_time: Duration::ZERO,
+                        write_time: Duration::ZERO,
+                    }));
+                }
+            }
+            let zero = (Duration::ZERO, Duration::ZERO);
+            file.record(
+                &pending,
+                BoundRemovalOutcome::Skipped,
+                zero,
+                None,
+                None,
+                observer,
+            );
+        }
+        Ok(None)
+    }
+
+    /// Decide `trials` of batch `id` on top of the accepted state. `failing` is the outcome
+    /// of a check already known to fail with all of them applied. Returns whether every
+    /// trial was accepted, or `None` if the run was cancelled: the trials in flight are then
+    /// reverted and left unreported.
+    fn settle(
+        &mut self,
+        mut trials: Vec<Trial>,
+        id: usize,
+        failing: Option<ValidationOutcome>,
+        check: &dyn Fn(&[&Path]) -> TraitError<ValidationOutcome>,
+        observer: &mut dyn Observer,
+    ) -> TraitError<Option<bool>> {
+        let store = self.ctx.store;
+        let outcome = match failing {
+            Some(outcome) => outcome,
+            None => {
+                for trial in &mut trials {
+                    let started = Instant::now();
+                    store.write(&self.files[trial.file].path, &trial.src)?;
+                    trial.write_time += started.elapsed();
+                }
+                let paths: Vec<&Path> = trials
+                    .iter()
+                    .map(|t| self.files[t.file].path.as_path())
+                    .collect();
+                let outcome = check(&paths)?;
+                let lead = &mut self.files[trials[0].file];
+                if outcome.cached {
+                    lead.cached += 1;
+                } else {
+                    lead.checks += 1;
+                }
+                if outcome.aborted {
+                    lead.aborted += 1;
+                    lead.aborted_time += outcome.elapsed;
+                }
+                trials[0].check_time += outcome.elapsed;
+                // A round interrupted by cancellation is undone whatever the validator said.
+                let cancelled = self.ctx.cancel.is_cancelled();
+                if outcome.success && !cancelled {
+                    for trial in trials {
+                        let file = &mut self.files[trial.file];
+                        store.keep(&file.path)?;
+                        file.src = trial.src;
+                        file.hash = trial.hash;
+                        // Each removal answers for the lints it left in its own file.
+                        let mut check = outcome.clone();
+                        check.follow_ups.retain(|f| file.path.ends_with(&f.file));
+                        let removed = BoundRemovalOutcome::Removed { check };
+                        let cost = (trial.check_time, trial.write_time);
+                        file.record(&trial.pending, removed, cost, Some(id), None, observer);
+                    }
+                    return Ok(Some(true));
+                }
+                for trial in &mut trials {
+                    let file = &mut self.files[trial.file];
+                    let started = Instant::now();
+                    store.revert(&file.path, &file.src)?;
+                    trial.write_time += started.elapsed();
+                }
+                if cancelled {
+                    for trial in trials {
+                        let file = &mut self.files[trial.file];
+                        trial.pending.undo(&mut file.working, trial.original);
+                        file.interrupted = true;
+                    }
+                    return Ok(None);
+                }
+                outcome
+            }
+        };
+
+        if trials.len() == 1 {
+            let Some(trial) = trials.pop() else {
+                return Ok(Some(false));
+            };
+            let file = &mut self.files[trial.file];
+            trial.pending.undo(&mut file.working, trial.original);
+            let retained = BoundRemovalOutcome::Retained { check: outcome };
+            let cost = (trial.check_time, trial.write_time);
+            file.record(&trial.pending, retained, cost, Some(id), None, observer);
+            // A bound the item needs may still be declared more often than it needs.
+            if let Some(repeats) = trial.pending.repeats {
+                file.queue.push_front(*repeats);
+            }
+            return Ok(Some(false));
+        }
+        let right = trials.split_off(trials.len() / 2);
+        let Some(left_accepted) = self.settle(trials, id, None, check, observer)? else {
+            return Ok(None);
+        };
+        let failing = left_accepted.then_some(outcome);
+        let right_accepted = self.settle(right, id, failing, check, observer)?;
+        Ok(right_accepted.map(|accepted| left_accepted && accepted))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::cancel::CancelToken;
+    use crate::dynamic_analysis::store::MemoryStore;
+    use crate::dynamic_analysis::validate::AlwaysOk;
+    use crate::observer::NoopObserver;
+    use std::cell::RefCell;
+
+    const SRC: &str = "fn f<T: Clone>(t: T) {}\n";
+    const FILES: [&str; 4] = ["a.rs", "b.rs", "c.rs", "d.rs"];
+
+    /// Prune [`FILES`] in batches of `size`; the build breaks once `needed`, if any, loses
+    /// its bound. Returns the files each check saw edited and what the runner did.
+    fn run(size: usize, needed: Option<&str>) -> TraitError<(Vec<Vec<String>>, Vec<BatchedFile>)> {
+        let store = FILES
+            .iter()
+            .fold(MemoryStore::new(), |s, f| s.with_file(*f, SRC));
+        let ctx = PruneContext {
+            crate_root: Path::new("."),
+            validator: &AlwaysOk,
+            cancel: &CancelToken::new(),
+            store: &store,
+            allow_dyn_compat_changes: false,
+            allow_unsafe_impl_pruning: false,
+            include_opaque_types: false,
+            only_traits: &[],
+            skip_derived_types: false,
+            skip_blanket_impls: false,
+            trait_index: None,
+            foreign_trait_impls: crate::config::ForeignTraitImpls::Allow,
+            cargo_check: None,
+            prefilter: false,
+            references: None,
+            min_weight: 0,
+            memo: None,
+        };
+        let mut runner = BatchRunner::new(&ctx, size);
+        let syntax = syn::parse_file(SRC)?;
+        let items = ItemBounds::collect_items_in_file(&syntax)?;
+        for f in FILES {
+            runner.add_file(
+                Path::new(f),
+                &syntax,
+                &items,
+                &[TargetType::Function],
+                &mut NoopObserver,
+            )?;
+        }
+        let seen = RefCell::new(Vec::new());
+        let check = |paths: &[&Path]| {
+            seen.borrow_mut()
+                .push(paths.iter().map(|p| p.display().to_string()).collect());
+            let text = needed.and_then(|f| store.get(Path::new(f)));
+            Ok(ValidationOutcome {
+                success: text.is_none_or(|t| t.contains("T: Clone")),
+                ..ValidationOutcome::default()
+            })
+        };
+        runner.run(&check, &mut NoopObserver)?;
+        let done = runner.finish();
+        for f in FILES {
+            let kept = store
+                .get(Path::new(f))
+                .unwrap_or_default()
+                .contains("T: Clone");
+            assert_eq!(kept, needed == Some(f), "{f}");
+        }
+        Ok((seen.into_inner(), done))
+    }
+
+    #[test]
+    fn a_failing_batch_is_bisected_down_to_the_culprit() -> TraitError<()> {
+        let (seen, done) = run(4, Some("c.rs"))?;
+        // The whole batch, then `a` and `b`; `c` and `d` are known to fail together and
+        // are tried one by one.
+        assert_eq!(
+            seen,
+            [
+                vec!["a.rs", "b.rs", "c.rs", "d.rs"],
+                vec!["a.rs", "b.rs"],
+                vec!["c.rs"],
+                vec!["d.rs"],
+            ]
+        );
+        let statuses: Vec<_> = done
+            .iter()
+            .map(|f| {
+                let removed = matches!(f.results[0].outcome, BoundRemovalOutcome::Removed { .. });
+                (removed, f.results[0].batch, f.finished)
+            })
+            .collect();
+        assert_eq!(
+            statuses,
+            [
+                (true, Some(1), true),
+                (true, Some(1), true),
+                (false, Some(1), true),
+                (true, Some(1), true),
+            ]
+        );
+        let checks: Vec<usize> = done.iter().map(|f| f.checks).collect();
+        assert_eq!(checks, [2, 0, 1, 1]);
+        Ok(())
+    }
+
+    #[test]
+    fn batches_take_one_removal_from_each_file() -> TraitError<()> {
+        let (seen, done) = run(3, None)?;
+        assert_eq!(seen, [vec!["a.rs", "b.rs", "c.rs"], vec!["d.rs"]]);
+        let batches: Vec<_> = done.iter().map(|f| f.results[0].batch).collect();
+        assert_eq!(batches, [Some(1), Some(1), Some(1), Some(2)]);
+        Ok(())
+    }
+}
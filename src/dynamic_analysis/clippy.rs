@@ -0,0 +1,144 @@
+// src/dynamic_analysis/clippy.rs
+//! Clippy's verdicts on bounds, imported as findings by `check --import-clippy`.
+//!
+//! Two lints speak to bounds: `trait_duplication_in_bounds` flags a bound repeated in one
+//! list or already required by the trait declaration, `type_repetition_in_bounds` a type
+//! bounded by more than one predicate. Neither is on by default; the import turns both on.
+//! Their primary spans are matched to the findings of a file: a finding inside one gets
+//! the lint as its rule unless a static rule already flags it.
+
+#![deny(missing_docs)]
+
+use crate::config::Config;
+use crate::dynamic_analysis::messages::{CompilerMessage, Diagnostic};
+use crate::error::{TraitError, WinnowerError};
+use crate::findings::{Confidence, Rule, RuleMatch};
+use crate::report::FileReport;
+use std::path::{Path, PathBuf};
+use std::process::Command;
+
+/// The lint flagging repeated bounds.
+pub const TRAIT_DUPLICATION: &str = "clippy::trait_duplication_in_bounds";
+
+/// The lint flagging types bounded by several predicates.
+pub const TYPE_REPETITION: &str = "clippy::type_repetition_in_bounds";
+
+/// Diagnostics of the imported lints, by the file they point into.
+#[derive(Debug, Clone, Default)]
+pub struct ClippyFindings {
+    diagnostics: Vec<(PathBuf, Diagnostic)>,
+}
+
+fn canonical(path: &Path) -> PathBuf {
+    path.canonicalize().unwrap_or_else(|_| path.to_path_buf())
+}
+
+impl ClippyFindings {
+    /// Run `cargo clippy` with JSON output in `root`, with `cargo_check.args` and
+    /// `clippy.args` of `cfg` and the imported lints turned on. `workspace` is the root
+    /// of the workspace, which diagnostics name files relative to.
+    pub fn collect(root: &Path, workspace: &Path, cfg: &Config) -> TraitError<Self> {
+        let cargo = cfg.cargo_check.cargo();
+        let mut command = Command::new(&cargo);
+        command
+            .arg("clippy")
+            .args(&cfg.cargo_check.args)
+            .args(&cfg.clippy.args)
+            .arg("--message-format=json")
+            .arg("--");
+        for lint in [TRAIT_DUPLICATION, TYPE_REPETITION] {
+            command.args(["-W", lint]);
+        }
+        let output = command
+            .env("CARGO_TARGET_DIR", cfg.cargo_check.target_dir(root))
+            .current_dir(root)
+            .output()
+            .map_err(|e| WinnowerError::cargo_spawn(&cargo, &e))?;
+        let stdout = String::from_utf8_lossy(&output.stdout);
+        let found = Self::from_messages(workspace, &stdout);
+        // A crate that does not build still leaves its messages; no message at all means
+        // clippy did not run.
+        if !output.status.success() && CompilerMessage::all(&stdout).next().is_none() {
+            return Err(WinnowerError::Validation {
+                command: "cargo clippy".into(),
+                status: output.status.code(),
+                stderr: String::from_utf8_lossy(&output.stderr).into_owned(),
+            });
+        }
+        Ok(found)
+    }
+
+    /// The diagnostics of the imported lints among `stdout`, JSON messages of cargo run in
+    /// `workspace`.
+    pub fn from_messages(workspace: &Path, stdout: &str) -> Self {
+        let diagnostics = CompilerMessage::all(stdout)
+            .filter(|m| {
+                m.message
+                    .code
+                    .as_deref()
+                    .is_some_and(|c| c == TRAIT_DUPLICATION || c == TYPE_REPETITION)
+            })
+            .filter_map(|m| {
+                let file = m.primary_spans().next()?.file_name.clone();
+                Some((canonical(&workspace.join(file)), m.message))
+            })
+            .collect();
+        Self { diagnostics }
+    }
+
+    /// Whether clippy flagged nothing.
+    pub fn is_empty(&self) -> bool {
+        self.diagnostics.is_empty()
+    }
+
+    /// Tag the findings of `report`, the report of the file at `path`, that the imported
+    /// lints flag. A repeated bound is flagged where it repeats an earlier one of the
+    /// span; a bound alone in its span, on its own.
+    pub fn merge(&self, path: &Path, report: &mut FileReport) {
+        let path = canonical(path);
+        for (_, diagnostic) in self.diagnostics.iter().filter(|(p, _)| *p == path) {
+            let lint = diagnostic.code.as_deref().unwrap_or_default();
+            let confidence = match lint {
+                TRAIT_DUPLICATION => Confidence::High,
+                // Merging the predicates is the fix; a bound may still go, but rarely.
+                _ => Confidence::Low,
+            };
+            for span in diagnostic.spans.iter().filter(|s| s.is_primary) {
+                let inside: Vec<usize> = report
+                    .findings
+                    .iter()
+                    .enumerate()
+                    .filter(|(_, f)| {
+                        (f.line, f.column) >= (span.line_start, span.column_start)
+                            && (f.end_line, f.end_column) <= (span.line_end, span.column_end)
+                    })
+                    .map(|(i, _)| i)
+                    .collect();
+                let flagged: Vec<usize> = if lint == TYPE_REPETITION || inside.len() == 1 {
+                    inside
+                } else {
+                    inside
+                        .iter()
+                        .enumerate()
+                        .filter(|&(k, &i)| {
+                            inside[..k]
+                                .iter()
+                                .any(|&j| report.findings[j].bound == report.findings[i].bound)
+                        })
+                        .map(|(_, &i)| i)
+                        .collect()
+                };
+                for i in flagged {
+                    let finding = &mut report.findings[i];
+                    if finding.rule.is_none() {
+                        finding.rule = Some(RuleMatch {
+                            rule: Rule::Clippy,
+                            confidence,
+                            message: format!("{} (`{lint}`)", diagnostic.message),
+                        });
+                    }
+                }
+            }
+        }
+    }
+}
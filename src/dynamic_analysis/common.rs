@@ -4,19 +4,30 @@
 #![deny(missing_docs)]
 
 use crate::analysis::{
-    EnumBounds, FnBounds, ImplBounds, ImplMethodBounds, StructBounds, TraitBounds,
-    TraitMethodBounds, TypeParamBounds, WhereTypeBounds,
+    EnumBounds, FnBounds, ImplBounds, ImplMethodBounds, ImplTraitArgBounds, OpaqueTypeBounds,
+    StructBounds, TraitBounds, TraitMethodBounds, TypeParamBounds, WhereTypeBounds,
 };
-use crate::config::CargoCheckConfig;
-use crate::error::TraitError;
+use crate::config::{CargoCheckConfig, ClippyConfig, MatrixEntry, MatrixMode};
+use crate::dynamic_analysis::lock_wait::{self, LockNotice};
+use crate::dynamic_analysis::messages::CompilerMessage;
+use crate::dynamic_analysis::session::Session;
+use crate::error::{TraitError, WinnowerError};
+use crate::report::{Finding, SiteKind};
+use crate::type_path::{TypePath, TypeRoot};
 
-use anyhow::Context;
 use quote::ToTokens;
-use std::path::Path;
-use std::process::{Command, ExitStatus};
+use serde::{Deserialize, Serialize};
+use std::path::{Path, PathBuf};
+use std::process::{Command, Output};
+use std::sync::Arc;
+use std::time::{Duration, Instant};
 use syn::GenericParam;
-use syn::{Ident, Type, TypeParamBound};
-use syn::{WherePredicate, punctuated::Punctuated, token::Comma};
+use syn::{Fields, FnArg, Ident, Pat, ReturnType, Type, TypeParamBound};
+use syn::{
+    WherePredicate,
+    punctuated::Punctuated,
+    token::{Comma, Plus},
+};
 
 /// A structural coordinate describing precisely and concretely the location of a trait/lifetime bound
 #[derive(Clone)]
@@ -26,7 +37,8 @@ pub enum BoundSite {
     TypeParam {
         /// The type parameter identifier (T).
         ident: Ident,
-        /// Index of the type parameter in generics (e.g. T is 0 in <T, U>).
+        /// Index of the type parameter in generics, counting lifetimes and const parameters
+        /// (e.g. T is 0 in <T, U> and 2 in <'a, const N: usize, T>).
         param_index: usize,
         /// Index of the bound for this type param (0 for first, etc.).
         bound_index: usize,
@@ -41,6 +53,36 @@ pub enum BoundSite {
         /// Index of bound within that where-clause predicate.
         bound_index: usize,
     },
+    /// Bound in an argument-position `impl Trait`, like x: impl Clone + Debug.
+    /// For example, fn foo(x: impl Clone + Debug) { ... }
+    ImplTraitArg {
+        /// The pattern of the argument (x).
+        arg: Box<Pat>,
+        /// Index of the argument in the signature, counting a `self` receiver.
+        arg_index: usize,
+        /// Index of the bound within the `impl Trait` type.
+        bound_index: usize,
+    },
+    /// Bound in a return-position `impl Trait`, like impl Iterator<Item = u32> + Send.
+    /// For example, fn foo() -> impl Iterator<Item = u32> + Send { ... }
+    ReturnImplTrait {
+        /// The `impl Trait` type.
+        ty: Box<Type>,
+        /// Where the type sits in the signature.
+        path: TypePath,
+        /// Index of the bound within the type.
+        bound_index: usize,
+    },
+    /// Bound in a trait object type in a signature or a struct field, like dyn Error + Send.
+    /// For example, struct S { e: Box<dyn Error + Send + Sync> }
+    TraitObject {
+        /// The `dyn Trait` type.
+        ty: Box<Type>,
+        /// Where the type sits in the signature or fields.
+        path: TypePath,
+        /// Index of the bound within the type.
+        bound_index: usize,
+    },
 }
 
 impl core::fmt::Debug for BoundSite {
@@ -66,6 +108,36 @@ impl core::fmt::Debug for BoundSite {
                 .field("pred_index", pred_index)
                 .field("bound_index", bound_index)
                 .finish(),
+            BoundSite::ImplTraitArg {
+                arg,
+                arg_index,
+                bound_index,
+            } => f
+                .debug_struct("ImplTraitArg")
+                .field("arg", &arg.to_token_stream())
+                .field("arg_index", arg_index)
+                .field("bound_index", bound_index)
+                .finish(),
+            BoundSite::ReturnImplTrait {
+                ty,
+                path,
+                bound_index,
+            } => f
+                .debug_struct("ReturnImplTrait")
+                .field("ty", &ty.to_token_stream())
+                .field("path", path)
+                .field("bound_index", bound_index)
+                .finish(),
+            BoundSite::TraitObject {
+                ty,
+                path,
+                bound_index,
+            } => f
+                .debug_struct("TraitObject")
+                .field("ty", &ty.to_token_stream())
+                .field("path", path)
+                .field("bound_index", bound_index)
+                .finish(),
         }
     }
 }
@@ -88,12 +160,218 @@ impl std::fmt::Debug for BoundCandidate {
     }
 }
 
+/// Owned, serializable mirror of [`BoundSite`], with types rendered as text.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum OwnedBoundSite {
+    /// Bound on a type parameter, e.g. `T` in `<T: Clone>`.
+    TypeParam {
+        /// The type parameter name.
+        ident: String,
+        /// Index of the type parameter in generics, lifetimes and const parameters included.
+        param_index: usize,
+        /// Index of the bound for this type parameter.
+        bound_index: usize,
+    },
+    /// Bound in a where clause predicate, e.g. `Vec<T>` in `where Vec<T>: Debug`.
+    WhereClause {
+        /// The bounded type as tokens.
+        ty: String,
+        /// Index of the predicate in the where clause.
+        pred_index: usize,
+        /// Index of the bound within the predicate.
+        bound_index: usize,
+    },
+    /// Bound in an argument-position `impl Trait`, e.g. `x` in `x: impl Clone + Debug`.
+    ImplTraitArg {
+        /// The argument pattern as tokens.
+        arg: String,
+        /// Index of the argument in the signature, counting a `self` receiver.
+        arg_index: usize,
+        /// Index of the bound within the `impl Trait` type.
+        bound_index: usize,
+    },
+    /// Bound in a return-position `impl Trait`, e.g. `Send` in `-> impl Iterator + Send`.
+    ReturnImplTrait {
+        /// The `impl Trait` type as tokens.
+        ty: String,
+        /// Where the type sits in the signature.
+        path: TypePath,
+        /// Index of the bound within the type.
+        bound_index: usize,
+    },
+    /// Bound in a trait object type, e.g. `Send` in `Box<dyn Error + Send>`.
+    TraitObject {
+        /// The `dyn Trait` type as tokens.
+        ty: String,
+        /// Where the type sits in the signature or fields.
+        path: TypePath,
+        /// Index of the bound within the type.
+        bound_index: usize,
+    },
+}
+
+impl Default for OwnedBoundSite {
+    fn default() -> Self {
+        OwnedBoundSite::TypeParam {
+            ident: String::new(),
+            param_index: 0,
+            bound_index: 0,
+        }
+    }
+}
+
+impl OwnedBoundSite {
+    /// Whether the bound sits on a type parameter, in the where clause, in an argument's or
+    /// the return type's `impl Trait` or in a `dyn Trait`.
+    pub fn kind(&self) -> SiteKind {
+        match self {
+            OwnedBoundSite::TypeParam { .. } => SiteKind::TypeParam,
+            OwnedBoundSite::WhereClause { .. } => SiteKind::WhereClause,
+            OwnedBoundSite::ImplTraitArg { .. } => SiteKind::ImplTraitArg,
+            OwnedBoundSite::ReturnImplTrait { .. } => SiteKind::ReturnImplTrait,
+            OwnedBoundSite::TraitObject { .. } => SiteKind::TraitObject,
+        }
+    }
+
+    /// Index of the bound within its parameter, predicate or type.
+    pub fn bound_index(&self) -> usize {
+        match self {
+            OwnedBoundSite::TypeParam { bound_index, .. }
+            | OwnedBoundSite::WhereClause { bound_index, .. }
+            | OwnedBoundSite::ImplTraitArg { bound_index, .. }
+            | OwnedBoundSite::ReturnImplTrait { bound_index, .. }
+            | OwnedBoundSite::TraitObject { bound_index, .. } => *bound_index,
+        }
+    }
+
+    /// The bounded parameter, type or argument, e.g. `T`; for `impl Trait` and `dyn Trait`
+    /// types outside arguments, the type itself.
+    pub fn bounded(&self) -> &str {
+        match self {
+            OwnedBoundSite::TypeParam { ident, .. } => ident,
+            OwnedBoundSite::WhereClause { ty, .. }
+            | OwnedBoundSite::ReturnImplTrait { ty, .. }
+            | OwnedBoundSite::TraitObject { ty, .. } => ty,
+            OwnedBoundSite::ImplTraitArg { arg, .. } => arg,
+        }
+    }
+}
+
+impl From<&BoundSite> for OwnedBoundSite {
+    fn from(site: &BoundSite) -> Self {
+        match site {
+            BoundSite::TypeParam {
+                ident,
+                param_index,
+                bound_index,
+            } => OwnedBoundSite::TypeParam {
+                ident: ident.to_string(),
+                param_index: *param_index,
+                bound_index: *bound_index,
+            },
+            BoundSite::WhereClause {
+                ty,
+                pred_index,
+                bound_index,
+            } => OwnedBoundSite::WhereClause {
+                ty: ty.to_token_stream().to_string(),
+                pred_index: *pred_index,
+                bound_index: *bound_index,
+            },
+            BoundSite::ImplTraitArg {
+                arg,
+                arg_index,
+                bound_index,
+            } => OwnedBoundSite::ImplTraitArg {
+                arg: arg.to_token_stream().to_string(),
+                arg_index: *arg_index,
+                bound_index: *bound_index,
+            },
+            BoundSite::ReturnImplTrait {
+                ty,
+                path,
+                bound_index,
+            } => OwnedBoundSite::ReturnImplTrait {
+                ty: ty.to_token_stream().to_string(),
+                path: path.clone(),
+                bound_index: *bound_index,
+            },
+            BoundSite::TraitObject {
+                ty,
+                path,
+                bound_index,
+            } => OwnedBoundSite::TraitObject {
+                ty: ty.to_token_stream().to_string(),
+                path: path.clone(),
+                bound_index: *bound_index,
+            },
+        }
+    }
+}
+
+/// Owned, serializable mirror of [`BoundCandidate`].
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct OwnedBoundCandidate {
+    /// Where the bound is declared.
+    pub site: OwnedBoundSite,
+    /// The bound as tokens, e.g. `Clone`.
+    pub bound_text: String,
+}
+
+impl OwnedBoundCandidate {
+    /// The bounded parameter or type and the bound, as tokens. Two candidates of one item
+    /// with the same key declare one bound twice, e.g. `<T: Clone>` and `where T: Clone`.
+    pub fn key(&self) -> (&str, &str) {
+        (self.site.bounded(), &self.bound_text)
+    }
+
+    /// The declaration as written, e.g. `<T: Clone>`, `where T: Clone` or `x: impl Clone`;
+    /// `Send in dyn Error + Send` for `impl Trait` and `dyn Trait` types outside arguments.
+    pub fn declaration(&self) -> String {
+        match self.site.kind() {
+            SiteKind::TypeParam => format!("<{}: {}>", self.site.bounded(), self.bound_text),
+            SiteKind::WhereClause => format!("where {}: {}", self.site.bounded(), self.bound_text),
+            SiteKind::ImplTraitArg => {
+                format!("{}: impl {}", self.site.bounded(), self.bound_text)
+            }
+            SiteKind::ReturnImplTrait | SiteKind::TraitObject => {
+                format!("{} in {}", self.bound_text, self.site.bounded())
+            }
+        }
+    }
+}
+
+impl From<&BoundCandidate> for OwnedBoundCandidate {
+    fn from(c: &BoundCandidate) -> Self {
+        Self {
+            site: OwnedBoundSite::from(&c.site),
+            bound_text: BoundCandidate::to_tokens_string(&c.bound),
+        }
+    }
+}
+
 impl BoundCandidate {
     #[inline]
     fn to_tokens_string(bound: &TypeParamBound) -> String {
         bound.to_token_stream().to_string()
     }
 
+    /// `candidates` of one item grouped by [`OwnedBoundCandidate::key`]: each group is one
+    /// bound however often it is declared, and `prune` removes it everywhere in one trial.
+    /// Groups keep the order of their first member.
+    pub fn group_duplicates(candidates: Vec<Self>) -> Vec<Vec<Self>> {
+        let mut groups: Vec<(OwnedBoundCandidate, Vec<Self>)> = Vec::new();
+        for candidate in candidates {
+            let owned = OwnedBoundCandidate::from(&candidate);
+            match groups.iter_mut().find(|(o, _)| o.key() == owned.key()) {
+                Some((_, group)) => group.push(candidate),
+                None => groups.push((owned, vec![candidate])),
+            }
+        }
+        groups.into_iter().map(|(_, group)| group).collect()
+    }
+
     #[inline]
     fn push_type_param_candidates(out: &mut Vec<BoundCandidate>, tp: &TypeParamBounds) {
         for (bound_index, bound) in tp.bounds().iter().cloned().enumerate() {
@@ -121,6 +399,41 @@ impl BoundCandidate {
             });
         }
     }
+
+    #[inline]
+    fn push_opaque_candidates(out: &mut Vec<BoundCandidate>, ot: &OpaqueTypeBounds) {
+        for (bound_index, bound) in ot.bounds().iter().cloned().enumerate() {
+            let (ty, path) = (Box::new(ot.ty().clone()), ot.path().clone());
+            let site = if ot.is_trait_object() {
+                BoundSite::TraitObject {
+                    ty,
+                    path,
+                    bound_index,
+                }
+            } else {
+                BoundSite::ReturnImplTrait {
+                    ty,
+                    path,
+                    bound_index,
+                }
+            };
+            out.push(BoundCandidate { site, bound });
+        }
+    }
+
+    #[inline]
+    fn push_impl_trait_arg_candidates(out: &mut Vec<BoundCandidate>, ia: &ImplTraitArgBounds) {
+        for (bound_index, bound) in ia.bounds().iter().cloned().enumerate() {
+            out.push(BoundCandidate {
+                site: BoundSite::ImplTraitArg {
+                    arg: Box::new(ia.arg().clone()),
+                    arg_index: ia.arg_index(),
+                    bound_index,
+                },
+                bound,
+            });
+        }
+    }
 }
 
 /// Macro generating a collect_*_candidates() function for each analysis struct.
@@ -137,6 +450,12 @@ macro_rules! define_collect_candidate_fns {
                     for wb in bounds.where_bounds() {
                         Self::push_where_candidates(&mut out, wb);
                     }
+                    for ia in bounds.impl_trait_arg_bounds() {
+                        Self::push_impl_trait_arg_candidates(&mut out, ia);
+                    }
+                    for ot in bounds.opaque_type_bounds() {
+                        Self::push_opaque_candidates(&mut out, ot);
+                    }
                     out
                 }
             }
@@ -154,37 +473,276 @@ define_collect_candidate_fns! {
     (collect_struct_candidates, StructBounds),
 }
 
+/// Auto traits: a trait object may name any number of them besides its one principal trait.
+const AUTO_TRAITS: &[&str] = &["Send", "Sync", "Unpin", "UnwindSafe", "RefUnwindSafe"];
+
+/// Whether `bound`, a bound of a `dyn Trait`, is its principal trait: a trait other than an
+/// auto trait, judged by name. Removing it would change what the object can do, not just
+/// where it can go, so it stays.
+pub fn is_principal_trait(bound: &TypeParamBound) -> bool {
+    match bound {
+        TypeParamBound::Trait(t) => t
+            .path
+            .segments
+            .last()
+            .is_some_and(|s| !AUTO_TRAITS.contains(&s.ident.to_string().as_str())),
+        _ => false,
+    }
+}
+
+/// A bound on a type parameter whose parameter index names another generic parameter of
+/// the item, or none: the index was taken from other generics.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ParamMismatch {
+    /// The type parameter expected.
+    pub ident: String,
+    /// Its index among all generic parameters, lifetimes and consts included.
+    pub param_index: usize,
+    /// What is there instead; `None` past the last parameter.
+    pub found: Option<String>,
+}
+
+impl std::fmt::Display for ParamMismatch {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "type parameter `{}` at generic parameter {}",
+            self.ident, self.param_index
+        )
+    }
+}
+
 /// A stateless utility for removing a bound from a generics block in-place.
 pub struct Remove;
 
 impl Remove {
     /// Remove a single trait/lifetime bound from a generic block by its coordinates.
+    ///
+    /// The bound text must match: when an earlier removal has shifted the bound, it is looked
+    /// up on the same parameter or bounded type instead, so the bound removed is always the
+    /// one the candidate names. Returns `false` when it is no longer there.
     pub fn apply_to_item_with_generics<T: HasGenerics>(
         item: &mut T,
         candidate: &BoundCandidate,
     ) -> bool {
+        let text = candidate.bound.to_token_stream().to_string();
         match &candidate.site {
             BoundSite::TypeParam {
+                ident,
                 param_index,
                 bound_index,
-                ..
-            } => Self::remove_tp_bound_by_index(item.generics_mut(), *param_index, *bound_index),
+            } => {
+                let Ok(tp) = Self::type_param_mut(item.generics_mut(), ident, *param_index) else {
+                    return false;
+                };
+                match Self::find_bound(&tp.bounds, *bound_index, &text) {
+                    Some(i) => Self::remove_tp_bound_by_index(tp, i),
+                    None => false,
+                }
+            }
             BoundSite::WhereClause {
+                ty,
                 pred_index,
                 bound_index,
-                ..
-            } => Self::remove_where_bound_by_index(item.generics_mut(), *pred_index, *bound_index),
+            } => {
+                let ty = ty.to_token_stream().to_string();
+                let generics = item.generics_mut();
+                let preds: Vec<(usize, &Punctuated<TypeParamBound, Plus>)> = generics
+                    .where_clause
+                    .iter()
+                    .flat_map(|wc| wc.predicates.iter().enumerate())
+                    .filter_map(|(i, p)| match p {
+                        WherePredicate::Type(pt)
+                            if pt.bounded_ty.to_token_stream().to_string() == ty =>
+                        {
+                            Some((i, &pt.bounds))
+                        }
+                        _ => None,
+                    })
+                    .collect();
+                let found = preds
+                    .iter()
+                    .filter(|(i, _)| i == pred_index)
+                    .chain(preds.iter().filter(|(i, _)| i != pred_index))
+                    .find_map(|(i, bounds)| {
+                        Some((*i, Self::find_bound(bounds, *bound_index, &text)?))
+                    });
+                match found {
+                    Some((p, i)) => Self::remove_where_bound_by_index(generics, p, i),
+                    None => false,
+                }
+            }
+            BoundSite::ImplTraitArg {
+                arg,
+                arg_index,
+                bound_index,
+            } => {
+                let arg = arg.to_token_stream().to_string();
+                let Some(inputs) = item.inputs_mut() else {
+                    return false;
+                };
+                let args: Vec<(usize, &Punctuated<TypeParamBound, Plus>)> = inputs
+                    .iter()
+                    .enumerate()
+                    .filter_map(|(i, a)| match a {
+                        FnArg::Typed(pt) if pt.pat.to_token_stream().to_string() == arg => {
+                            match &*pt.ty {
+                                Type::ImplTrait(it) => Some((i, &it.bounds)),
+                                _ => None,
+                            }
+                        }
+                        _ => None,
+                    })
+                    .collect();
+                let found = args
+                    .iter()
+                    .filter(|(i, _)| i == arg_index)
+                    .chain(args.iter().filter(|(i, _)| i != arg_index))
+                    .find_map(|(i, bounds)| {
+                        Some((*i, Self::find_bound(bounds, *bound_index, &text)?))
+                    });
+                match found {
+                    Some((a, i)) => Self::remove_impl_trait_bound_by_index(inputs, a, i),
+                    None => false,
+                }
+            }
+            BoundSite::ReturnImplTrait { path, .. } | BoundSite::TraitObject { path, .. } => {
+                match item
+                    .type_root_mut(path.root)
+                    .and_then(|root| path.resolve_mut(root))
+                {
+                    Some(ty) => Self::apply_to_type(ty, candidate),
+                    None => false,
+                }
+            }
         }
     }
 
-    fn remove_tp_bound_by_index(
-        generics: &mut syn::Generics,
-        param_index: usize,
-        bound_index: usize,
-    ) -> bool {
-        let Some(GenericParam::Type(tp)) = generics.params.iter_mut().nth(param_index) else {
+    /// Remove a return-position `impl Trait` or `dyn Trait` candidate from `ty`, the type
+    /// its path leads to. A trait object keeps its principal trait (see
+    /// [`is_principal_trait`]), and either kind keeps a trait bound.
+    pub fn apply_to_type(ty: &mut Type, candidate: &BoundCandidate) -> bool {
+        let text = candidate.bound.to_token_stream().to_string();
+        let (bounds, object) = match (&candidate.site, ty) {
+            (BoundSite::ReturnImplTrait { .. }, Type::ImplTrait(t)) => (&mut t.bounds, false),
+            (BoundSite::TraitObject { .. }, Type::TraitObject(t)) => (&mut t.bounds, true),
+            _ => return false,
+        };
+        let (BoundSite::ReturnImplTrait { bound_index, .. }
+        | BoundSite::TraitObject { bound_index, .. }) = &candidate.site
+        else {
             return false;
         };
+        let Some(index) = Self::find_bound(bounds, *bound_index, &text) else {
+            return false;
+        };
+        if object && bounds.iter().nth(index).is_some_and(is_principal_trait) {
+            return false;
+        }
+        Self::remove_keeping_a_trait(bounds, index)
+    }
+
+    /// Remove the type parameter `ident` from `item` with everything that only exists for it:
+    /// its bounds and default, where predicates bounding exactly `ident`, and the `<>` once
+    /// no parameter is left. Refused, leaving `item` untouched, while anything else in the
+    /// item (signature, other bounds and predicates, body) still names `ident`.
+    pub fn remove_type_param<T: HasGenerics + ToTokens + Clone>(
+        item: &mut T,
+        ident: &Ident,
+    ) -> ParamRemoval {
+        let mut trial = item.clone();
+        let generics = trial.generics_mut();
+        let Some(index) = generics
+            .params
+            .iter()
+            .position(|p| matches!(p, GenericParam::Type(tp) if tp.ident == *ident))
+        else {
+            return ParamRemoval::Skipped {
+                note: format!("`{ident}` is not a type parameter of the item"),
+            };
+        };
+        Self::remove_punctuated_at(&mut generics.params, index);
+        if generics.params.is_empty() {
+            generics.lt_token = None;
+            generics.gt_token = None;
+        }
+        if let Some(wc) = generics.where_clause.as_mut() {
+            while let Some(p) = wc.predicates.iter().position(|p| {
+                matches!(p, WherePredicate::Type(pt)
+                    if matches!(&pt.bounded_ty, Type::Path(tp)
+                        if tp.qself.is_none() && tp.path.is_ident(ident)))
+            }) {
+                wc.predicates = Self::drop_predicate_at(std::mem::take(&mut wc.predicates), p);
+            }
+            if wc.predicates.is_empty() {
+                generics.where_clause = None;
+            }
+        }
+        if mentions(trial.to_token_stream(), ident) {
+            return ParamRemoval::Skipped {
+                note: format!(
+                    "`{ident}` is still used in the signature, where clause or body of the item"
+                ),
+            };
+        }
+        *item = trial;
+        ParamRemoval::Removed
+    }
+
+    /// Where a bound written as `text` sits in `bounds`: at `index` if it is still there,
+    /// else its first occurrence.
+    fn find_bound(
+        bounds: &Punctuated<TypeParamBound, Plus>,
+        index: usize,
+        text: &str,
+    ) -> Option<usize> {
+        let is_it = |b: &TypeParamBound| b.to_token_stream().to_string() == text;
+        if bounds.iter().nth(index).is_some_and(is_it) {
+            return Some(index);
+        }
+        bounds.iter().position(is_it)
+    }
+
+    /// How the parameter index of `candidate`, a bound on a type parameter, misses `item`:
+    /// `None` when it names the parameter, or the bound sits elsewhere. Removing it edits
+    /// nothing then, and the caller says why.
+    pub fn param_mismatch<T: HasGenerics>(
+        item: &mut T,
+        candidate: &BoundCandidate,
+    ) -> Option<ParamMismatch> {
+        let BoundSite::TypeParam {
+            ident, param_index, ..
+        } = &candidate.site
+        else {
+            return None;
+        };
+        Self::type_param_mut(item.generics_mut(), ident, *param_index).err()
+    }
+
+    /// The type parameter at `param_index` of `generics`, which counts lifetimes and const
+    /// parameters too, provided it is `ident`. Anything else there means the index was taken
+    /// from other generics.
+    fn type_param_mut<'g>(
+        generics: &'g mut syn::Generics,
+        ident: &Ident,
+        param_index: usize,
+    ) -> Result<&'g mut syn::TypeParam, ParamMismatch> {
+        let mismatch = |found: Option<&GenericParam>| ParamMismatch {
+            ident: ident.to_string(),
+            param_index,
+            found: found.map(|p| p.to_token_stream().to_string()),
+        };
+        let found = generics.params.iter().nth(param_index);
+        if !matches!(found, Some(GenericParam::Type(tp)) if tp.ident == *ident) {
+            return Err(mismatch(found));
+        }
+        match generics.params.iter_mut().nth(param_index) {
+            Some(GenericParam::Type(tp)) => Ok(tp),
+            _ => Err(mismatch(None)),
+        }
+    }
+
+    fn remove_tp_bound_by_index(tp: &mut syn::TypeParam, bound_index: usize) -> bool {
         let removed = Self::remove_punctuated_at(&mut tp.bounds, bound_index);
         if removed && tp.bounds.is_empty() {
             tp.colon_token = None;
@@ -218,6 +776,33 @@ impl Remove {
         }
     }
 
+    /// Remove a bound from the `impl Trait` type of argument `arg_index`, unless no trait
+    /// bound would be left: `impl 'a` and `impl` alone are not types.
+    fn remove_impl_trait_bound_by_index(
+        inputs: &mut Punctuated<FnArg, Comma>,
+        arg_index: usize,
+        bound_index: usize,
+    ) -> bool {
+        let Some(FnArg::Typed(pt)) = inputs.iter_mut().nth(arg_index) else {
+            return false;
+        };
+        let Type::ImplTrait(it) = &mut *pt.ty else {
+            return false;
+        };
+        Self::remove_keeping_a_trait(&mut it.bounds, bound_index)
+    }
+
+    fn remove_keeping_a_trait(
+        bounds: &mut Punctuated<TypeParamBound, Plus>,
+        bound_index: usize,
+    ) -> bool {
+        let trait_left = bounds
+            .iter()
+            .enumerate()
+            .any(|(i, b)| i != bound_index && matches!(b, TypeParamBound::Trait(_)));
+        trait_left && Self::remove_punctuated_at(bounds, bound_index)
+    }
+
     fn remove_punctuated_at<T, P>(list: &mut Punctuated<T, P>, idx: usize) -> bool
     where
         T: Clone,
@@ -272,29 +857,131 @@ impl Remove {
         out
     }
 }
-/// A result of running cargo check.
-#[derive(Debug)]
-pub struct CommandOutput {
-    /// The status of the cargo check.
-    pub status: ExitStatus,
-    /// The stdout of the cargo check.
+/// The result of validating an edited tree (e.g. running cargo check).
+#[derive(Debug, Clone, Default)]
+pub struct ValidationOutcome {
+    /// Whether the tree is still valid.
+    pub success: bool,
+    /// Exit code of the validating command, if it ran and exited normally.
+    pub code: Option<i32>,
+    /// The stdout of the validating command.
     pub stdout: String,
-    /// The stderr of the cargo check.
+    /// The stderr of the validating command.
     pub stderr: String,
+    /// How long validation took.
+    pub elapsed: Duration,
+    /// The packages `cargo check` was limited to (see [`CargoCheck::with_packages`]); empty
+    /// when it checked what its arguments select.
+    pub scope: Vec<String>,
+    /// Killed at its first error rather than run to completion (see
+    /// [`CargoCheckConfig::fail_fast`]).
+    pub aborted: bool,
+    /// Answered from the [`TrialCache`](crate::dynamic_analysis::trial_cache::TrialCache)
+    /// instead of run; `stdout` is not kept there.
+    pub cached: bool,
+    /// Lints the check raised and [`CargoCheckConfig::tolerate_new_lints`] let pass; only
+    /// when it succeeded.
+    pub follow_ups: Vec<FollowUp>,
+}
+
+/// A lint a trial newly raised that [`CargoCheckConfig::tolerate_new_lints`] accepted:
+/// cleanup left to do, such as deleting the import a removal left unused.
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
+pub struct FollowUp {
+    /// The file the lint points at, relative to the workspace root.
+    pub file: PathBuf,
+    /// 1-based line.
+    pub line: usize,
+    /// 1-based column.
+    pub column: usize,
+    /// The lint, e.g. `unused_imports`.
+    pub lint: String,
+    /// What rustc said, e.g. ``unused import: `std::fmt::Debug` ``.
+    pub message: String,
+}
+
+impl std::fmt::Display for FollowUp {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "{}:{}:{}: {} ({})",
+            self.file.display(),
+            self.line,
+            self.column,
+            self.message,
+            self.lint
+        )
+    }
+}
+
+impl ValidationOutcome {
+    /// Summarize the output of a finished command.
+    pub fn from_output(output: &Output, elapsed: Duration) -> Self {
+        Self {
+            success: output.status.success(),
+            code: output.status.code(),
+            stdout: String::from_utf8_lossy(&output.stdout).into_owned(),
+            stderr: String::from_utf8_lossy(&output.stderr).into_owned(),
+            elapsed,
+            scope: Vec::new(),
+            aborted: false,
+            cached: false,
+            follow_ups: Vec::new(),
+        }
+    }
+}
+
+/// How a validation command ended.
+pub(crate) enum Exit {
+    /// It ran to completion.
+    Finished(Output),
+    /// It was killed at its first error; the output is what it printed until then.
+    Aborted(Output),
+    /// It was killed because the run was cancelled.
+    Cancelled,
+    /// It was killed after waiting `waited` for a file lock on `what`, longer than the
+    /// configuration allows.
+    LockWait {
+        /// What cargo waited for, as it names it.
+        what: String,
+        /// How long it waited.
+        waited: Duration,
+    },
+}
+
+/// What [`Remove::remove_type_param`] did.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ParamRemoval {
+    /// The parameter and its bounds are gone.
+    Removed,
+    /// Nothing changed.
+    Skipped {
+        /// Why the parameter was kept.
+        note: String,
+    },
+}
+
+/// Whether `tokens` contain the identifier `ident`, at any depth.
+fn mentions(tokens: proc_macro2::TokenStream, ident: &Ident) -> bool {
+    tokens.into_iter().any(|tt| match tt {
+        proc_macro2::TokenTree::Ident(i) => i == *ident,
+        proc_macro2::TokenTree::Group(g) => mentions(g.stream(), ident),
+        _ => false,
+    })
 }
 
 /// A result of removing a bound.
-#[derive(Debug)]
+#[derive(Debug, Clone)]
 pub enum BoundRemovalOutcome {
     /// The bound was removed and cargo check was successful.
     Removed {
         /// The output of the cargo check.
-        check: CommandOutput,
+        check: ValidationOutcome,
     },
     /// The bound was retained and cargo check was successful.  
     Retained {
         /// The output of the cargo check.
-        check: CommandOutput,
+        check: ValidationOutcome,
     },
     /// The bound was skipped.
     Skipped,
@@ -307,40 +994,654 @@ pub struct BoundRemovalResult {
     pub candidate: BoundCandidate,
     /// The outcome of the removal attempt.
     pub outcome: BoundRemovalOutcome,
+    /// Label and location of the candidate bound.
+    pub finding: Finding,
+    /// The item's generics and where clause before the removal.
+    pub before: String,
+    /// The item's generics and where clause with the candidate removed.
+    pub after: String,
+    /// Time spent in `cargo check` for this candidate.
+    pub check_time: Duration,
+    /// Time spent writing and reverting the file for this candidate.
+    pub write_time: Duration,
+    /// The cross-file batch the candidate was validated in (see
+    /// [`BatchRunner`](crate::dynamic_analysis::batch::BatchRunner)); `None` when it was
+    /// validated alone.
+    pub batch: Option<usize>,
 }
 
-/// A utility for running cargo check.
-pub struct CargoCheck;
+/// The cargo and rustc a run validates with.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Toolchain {
+    /// What `cargo --version` printed, e.g. `cargo 1.90.0 (840b83a10 2025-07-30)`.
+    pub cargo: String,
+    /// What `rustc --version` printed; `None` if it could not run.
+    pub rustc: Option<String>,
+}
+
+impl std::fmt::Display for Toolchain {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(&self.cargo)?;
+        if let Some(rustc) = &self.rustc {
+            write!(f, ", {rustc}")?;
+        }
+        Ok(())
+    }
+}
+
+/// The directory validation built in, as a run left it.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct CheckTargetDir {
+    /// The directory (see [`CargoCheckConfig::target_dir`]).
+    pub path: PathBuf,
+    /// Its size in bytes at the end of the run.
+    pub bytes: u64,
+    /// Whether it was left in place for the next run.
+    pub kept: bool,
+}
+
+impl CheckTargetDir {
+    /// Measure `path`, then remove it unless `keep`.
+    pub fn finish(path: PathBuf, keep: bool) -> TraitError<Self> {
+        let bytes = Self::size(&path);
+        if !keep && path.exists() {
+            std::fs::remove_dir_all(&path).map_err(|e| WinnowerError::io("removing", &path, e))?;
+        }
+        Ok(Self {
+            path,
+            bytes,
+            kept: keep,
+        })
+    }
+
+    /// Total size of the files under `path`, not following symlinks; unreadable entries
+    /// count as empty.
+    fn size(path: &Path) -> u64 {
+        let Ok(meta) = std::fs::symlink_metadata(path) else {
+            return 0;
+        };
+        if !meta.is_dir() {
+            return meta.len();
+        }
+        std::fs::read_dir(path)
+            .map(|entries| entries.flatten().map(|e| Self::size(&e.path())).sum())
+            .unwrap_or(0)
+    }
+}
+
+impl std::fmt::Display for CheckTargetDir {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let mib = self.bytes as f64 / (1024.0 * 1024.0);
+        write!(f, "{} ({mib:.1} MiB", self.path.display())?;
+        f.write_str(if self.kept { ")" } else { ", removed)" })
+    }
+}
+
+/// A utility for running cargo check; as a [`Validator`](crate::dynamic_analysis::validate::Validator)
+/// it runs with the configuration it was built with: on the host, then on each entry of
+/// the validation matrix, stopping at the first failure.
+#[derive(Debug, Clone, Default)]
+pub struct CargoCheck {
+    config: CargoCheckConfig,
+    full_matrix: bool,
+    packages: Vec<String>,
+    clippy: Option<ClippyConfig>,
+    session: Option<Arc<Session>>,
+    lock_notice: Option<LockNotice>,
+}
 
 impl CargoCheck {
+    /// A validator running `cargo check` with `config`.
+    pub fn new(config: CargoCheckConfig) -> Self {
+        Self {
+            config,
+            full_matrix: false,
+            packages: Vec::new(),
+            clippy: None,
+            session: None,
+            lock_notice: None,
+        }
+    }
+
+    /// The same validator, calling `notice` when a check starts waiting for a file lock
+    /// another cargo process holds (see [`lock_wait`]).
+    pub fn with_lock_notice(mut self, notice: LockNotice) -> Self {
+        self.lock_notice = Some(notice);
+        self
+    }
+
+    /// What [`CargoCheck::with_lock_notice`] set.
+    pub(crate) fn lock_notice(&self) -> Option<&LockNotice> {
+        self.lock_notice.as_ref()
+    }
+
+    /// How long a check may wait for a lock (see
+    /// [`CargoCheckConfig::lock_wait_timeout`]).
+    pub(crate) fn lock_wait_timeout(&self) -> Option<Duration> {
+        self.config.lock_wait_timeout.map(Duration::from_secs)
+    }
+
+    /// The same validator, recording its checks in `session` or answering them from it.
+    pub fn with_session(mut self, session: Arc<Session>) -> Self {
+        self.session = Some(session);
+        self
+    }
+
+    /// The same validator, running `cargo clippy` as `clippy` configures it instead of
+    /// `cargo check`.
+    pub fn with_clippy(mut self, clippy: ClippyConfig) -> Self {
+        self.clippy = Some(clippy);
+        self
+    }
+
+    /// The same validator, checking only `packages` where the configuration's
+    /// [`check_scope`](CargoCheckConfig::check_scope) allows (see
+    /// [`CargoCheckConfig::scoped_args`]).
+    pub fn with_packages(mut self, packages: Vec<String>) -> Self {
+        self.packages = if self.config.scoped_args(&packages) == self.config.args {
+            Vec::new()
+        } else {
+            packages
+        };
+        self
+    }
+
+    /// The arguments after `cargo` of each check: `check`, then the configured arguments,
+    /// scoped to the packages of [`CargoCheck::with_packages`]. With
+    /// [`CargoCheck::with_clippy`], `clippy` and the clippy arguments after them.
+    pub fn check_args(&self) -> Vec<String> {
+        let (command, extra) = match &self.clippy {
+            Some(clippy) => ("clippy", clippy.args.as_slice()),
+            None => ("check", [].as_slice()),
+        };
+        std::iter::once(command.to_owned())
+            .chain(self.config.scoped_args(&self.packages))
+            .chain(extra.iter().cloned())
+            .collect()
+    }
+
+    /// The arguments each check passes clippy after `--`; empty for `cargo check`.
+    pub fn lint_args(&self) -> &[String] {
+        self.clippy.as_ref().map_or(&[], |c| c.lint_args.as_slice())
+    }
+
+    /// The same validator, running the whole matrix even in [`MatrixMode::FinalOnly`]; for
+    /// checks of a final state rather than of a single candidate.
+    pub fn with_full_matrix(mut self) -> Self {
+        self.full_matrix = true;
+        self
+    }
+
+    /// The configuration `validate` runs with.
+    pub fn config(&self) -> &CargoCheckConfig {
+        &self.config
+    }
+
+    /// The packages checks are limited to (see [`CargoCheck::with_packages`]); empty when
+    /// they check what the arguments select.
+    pub fn packages(&self) -> &[String] {
+        &self.packages
+    }
+
+    /// The matrix entries each validation runs after the host.
+    pub fn matrix(&self) -> &[MatrixEntry] {
+        match self.config.matrix_mode {
+            MatrixMode::FinalOnly if !self.full_matrix => &[],
+            _ => &self.config.matrix,
+        }
+    }
+
     /// Run cargo check with the given configuration.
-    pub fn run_cargo_check(root: &Path, config: &CargoCheckConfig) -> TraitError<CommandOutput> {
-        let mut command = Command::new("cargo");
-        command.arg("check");
-        for arg in &config.args {
-            command.arg(arg);
+    pub fn run_cargo_check(
+        root: &Path,
+        config: &CargoCheckConfig,
+    ) -> TraitError<ValidationOutcome> {
+        Self::new(config.clone()).run(root, |command| command.output().map(Exit::Finished))
+    }
+
+    /// Whether checks print JSON messages to stop at the first error (see
+    /// [`CargoCheckConfig::fail_fast`]).
+    pub fn fails_fast(&self) -> bool {
+        self.config.fail_fast && !self.picks_message_format()
+    }
+
+    /// Whether checks print JSON messages, to stop at the first error or to tell the lints
+    /// of [`CargoCheckConfig::tolerate_new_lints`] from other errors.
+    fn json_messages(&self) -> bool {
+        (self.config.fail_fast || !self.config.tolerate_new_lints.is_empty())
+            && !self.picks_message_format()
+    }
+
+    fn picks_message_format(&self) -> bool {
+        self.config
+            .args
+            .iter()
+            .any(|a| a.starts_with("--message-format"))
+    }
+
+    /// What stops a check early, when it [fails fast](Self::fails_fast): any error, or,
+    /// with lints to tolerate, any error but a lint's, which is only judged once the check
+    /// has finished.
+    pub(crate) fn fatal(&self) -> Option<fn(&str) -> bool> {
+        self.fails_fast()
+            .then_some(if self.config.tolerate_new_lints.is_empty() {
+                Self::is_fatal
+            } else {
+                Self::is_fatal_unless_lint
+            })
+    }
+
+    /// Whether `line`, a JSON message of `cargo check --message-format=json`, is an error
+    /// in a package built from a path: a workspace member or a path dependency, which a
+    /// trial can break. Errors in registry crates are the build's, not the trial's.
+    pub fn is_fatal(line: &str) -> bool {
+        CompilerMessage::parse(line).is_some_and(|m| Self::is_path_error(&m))
+    }
+
+    /// Like [`is_fatal`](Self::is_fatal), but not for the errors of lints.
+    pub fn is_fatal_unless_lint(line: &str) -> bool {
+        CompilerMessage::parse(line)
+            .is_some_and(|m| Self::is_path_error(&m) && m.message.lint().is_none())
+    }
+
+    fn is_path_error(message: &CompilerMessage) -> bool {
+        message.message.level == "error" && message.in_path_package()
+    }
+
+    /// The follow-ups of a failed check whose errors, JSON messages in `stdout`, were all
+    /// raised by lints in [`CargoCheckConfig::tolerate_new_lints`]; `None` when any was
+    /// not, or when there were none to tell why it failed.
+    fn tolerated(&self, stdout: &str) -> Option<Vec<FollowUp>> {
+        if !self.json_messages() {
+            return None;
+        }
+        let tolerates = |lint: &str| {
+            self.config
+                .tolerate_new_lints
+                .iter()
+                .any(|t| t.replace('-', "_") == lint)
+        };
+        let mut follow_ups = Vec::new();
+        for message in CompilerMessage::all(stdout).filter(Self::is_path_error) {
+            let lint = message.message.lint().filter(|l| tolerates(l))?;
+            let span = message.primary_spans().next();
+            follow_ups.push(FollowUp {
+                file: span
+                    .map(|s| PathBuf::from(&s.file_name))
+                    .unwrap_or_default(),
+                line: span.map_or(0, |s| s.line_start),
+                column: span.map_or(0, |s| s.column_start),
+                lint: lint.to_owned(),
+                message: message.message.message.clone(),
+            });
+        }
+        follow_ups.sort();
+        // A lib and its test target report the same lines.
+        follow_ups.dedup();
+        (!follow_ups.is_empty()).then_some(follow_ups)
+    }
+
+    /// The diagnostics in `stdout`, JSON messages of `cargo check`, rendered as rustc
+    /// prints them.
+    fn rendered(stdout: &str) -> String {
+        CompilerMessage::all(stdout)
+            .filter_map(|m| m.message.rendered)
+            .collect()
+    }
+
+    /// Run the host check and the matrix through `exec`. The outcome is the first
+    /// failure's, or the last run's.
+    pub(crate) fn run(
+        &self,
+        root: &Path,
+        exec: impl FnMut(&mut Command) -> std::io::Result<Exit>,
+    ) -> TraitError<ValidationOutcome> {
+        match &self.session {
+            Some(session) => {
+                let mut args = self.check_args();
+                if !self.lint_args().is_empty() {
+                    args.push("--".into());
+                    args.extend(self.lint_args().iter().cloned());
+                }
+                args.extend(self.matrix().iter().map(|e| format!("matrix:{e}")));
+                args.extend(
+                    (self.config.tolerate_new_lints.iter()).map(|l| format!("tolerate:{l}")),
+                );
+                session.validate(args, root, || self.run_cargo(root, exec))
+            }
+            None => self.run_cargo(root, exec),
+        }
+    }
+
+    fn run_cargo(
+        &self,
+        root: &Path,
+        mut exec: impl FnMut(&mut Command) -> std::io::Result<Exit>,
+    ) -> TraitError<ValidationOutcome> {
+        let started = Instant::now();
+        let host = MatrixEntry::default();
+        let mut outcome = ValidationOutcome::default();
+        let mut follow_ups = Vec::new();
+        for (i, entry) in std::iter::once(&host).chain(self.matrix()).enumerate() {
+            let mut tolerant = false;
+            loop {
+                let (mut command, quiet) = self.command(root, entry, i, tolerant);
+                let exit = exec(&mut command).map_err(|e| self.spawn_error(root, e))?;
+                let (mut output, aborted) = match exit {
+                    Exit::Finished(output) => (output, false),
+                    Exit::Aborted(output) => (output, true),
+                    Exit::Cancelled => {
+                        return Ok(ValidationOutcome {
+                            elapsed: started.elapsed(),
+                            ..ValidationOutcome::default()
+                        });
+                    }
+                    Exit::LockWait { what, waited } => {
+                        return Err(WinnowerError::LockWait {
+                            dir: self.config.target_dir(root),
+                            what,
+                            waited,
+                        });
+                    }
+                };
+                if quiet {
+                    output.stderr = lock_wait::strip_statuses(&output.stderr);
+                }
+                outcome = ValidationOutcome::from_output(&output, started.elapsed());
+                outcome.scope = self.packages.clone();
+                outcome.aborted = aborted;
+                if self.json_messages() {
+                    // Diagnostics went to stdout as JSON; show them where rustc would.
+                    outcome.stderr = Self::rendered(&outcome.stdout) + &outcome.stderr;
+                }
+                if !tolerant
+                    && !outcome.success
+                    && !aborted
+                    && let Some(found) = self.tolerated(&outcome.stdout)
+                {
+                    // Cargo stopped at the package the lints failed; check again with them
+                    // as warnings to reach the packages depending on it.
+                    follow_ups.extend(found);
+                    tolerant = true;
+                    continue;
+                }
+                break;
+            }
+            if !outcome.success {
+                if i > 0 {
+                    outcome.stderr = format!("matrix entry {entry}:\n{}", outcome.stderr);
+                }
+                break;
+            }
+        }
+        if outcome.success {
+            follow_ups.sort();
+            follow_ups.dedup();
+            outcome.follow_ups = follow_ups;
+        }
+        Ok(outcome)
+    }
+
+    /// The `cargo check` invocation for `root` on matrix entry `index` (0 is the host), and
+    /// whether its arguments asked for `--quiet`. The invocation leaves it out, so that
+    /// cargo says when it waits for a lock (see [`lock_wait`]); its status lines are then
+    /// not part of the outcome. A `tolerant` invocation forces the lints of
+    /// [`CargoCheckConfig::tolerate_new_lints`] to warnings.
+    fn command(
+        &self,
+        root: &Path,
+        entry: &MatrixEntry,
+        index: usize,
+        tolerant: bool,
+    ) -> (Command, bool) {
+        let config = &self.config;
+        let mut command = Command::new(config.cargo());
+        let (args, quiet) = lock_wait::without_quiet(self.check_args());
+        command.args(args);
+        if self.json_messages() {
+            command.arg("--message-format=json");
+        }
+        if let Some(target) = &entry.target {
+            command.arg("--target").arg(target);
+        }
+        if let Some(features) = &entry.features {
+            command.arg("--features").arg(features);
+        }
+        if !self.lint_args().is_empty() {
+            command.arg("--").args(self.lint_args());
+        }
+        let mut flags: Vec<String> = entry.rustflags.iter().cloned().collect();
+        if tolerant {
+            flags.extend((config.tolerate_new_lints.iter()).map(|l| format!("--force-warn={l}")));
+        }
+        if !flags.is_empty() {
+            let mut all = std::env::var("RUSTFLAGS").unwrap_or_default();
+            for flag in flags {
+                if !all.is_empty() {
+                    all.push(' ');
+                }
+                all.push_str(&flag);
+            }
+            command.env("RUSTFLAGS", all);
+        }
+        let base = config.target_dir(root);
+        // A target directory per entry, so alternating flags or features do not rebuild
+        // everything.
+        let mut dir = if entry.rustflags.is_some() || entry.features.is_some() {
+            base.join(format!("matrix-{index}"))
+        } else {
+            base
+        };
+        if tolerant {
+            dir.push("tolerant");
         }
-        let output = command
+        command.env("CARGO_TARGET_DIR", dir);
+        command.current_dir(root);
+        (command, quiet)
+    }
+
+    /// Matrix targets `rustup` has not installed for the toolchain used in `root`; empty
+    /// when `rustup` cannot tell.
+    pub fn missing_targets(root: &Path, config: &CargoCheckConfig) -> Vec<String> {
+        let mut wanted: Vec<&String> = config
+            .matrix
+            .iter()
+            .filter_map(|e| e.target.as_ref())
+            .collect();
+        wanted.sort();
+        wanted.dedup();
+        if wanted.is_empty() {
+            return Vec::new();
+        }
+        let output = Command::new("rustup")
+            .args(["target", "list", "--installed"])
+            .current_dir(root)
+            .output();
+        let Ok(output) = output.as_ref().map(|o| (o.status.success(), &o.stdout)) else {
+            return Vec::new();
+        };
+        if !output.0 {
+            return Vec::new();
+        }
+        let installed = String::from_utf8_lossy(output.1);
+        let installed: Vec<&str> = installed.lines().map(str::trim).collect();
+        wanted
+            .into_iter()
+            .filter(|t| !installed.contains(&t.as_str()))
+            .cloned()
+            .collect()
+    }
+
+    /// The cargo and rustc `config` runs in `root`, as `--version` prints them. Fails with
+    /// installation guidance when cargo cannot be started, and when the program found does
+    /// not answer like cargo.
+    pub fn toolchain(root: &Path, config: &CargoCheckConfig) -> TraitError<Toolchain> {
+        let cargo = config.cargo();
+        let output = Command::new(&cargo)
+            .arg("--version")
             .current_dir(root)
             .output()
-            .with_context(|| format!("running cargo check in {}", Self::display(root)))?;
-        Ok(CommandOutput {
-            status: output.status,
-            stdout: String::from_utf8_lossy(&output.stdout).into_owned(),
-            stderr: String::from_utf8_lossy(&output.stderr).into_owned(),
+            .map_err(|e| WinnowerError::cargo_spawn(&cargo, &e))?;
+        let version = String::from_utf8_lossy(&output.stdout).trim().to_owned();
+        if !output.status.success() || !version.starts_with("cargo ") {
+            let printed = match version.as_str() {
+                "" => String::from_utf8_lossy(&output.stderr).trim().to_owned(),
+                _ => version,
+            };
+            return Err(WinnowerError::Toolchain {
+                program: cargo,
+                detail: format!(
+                    "`--version` printed `{printed}`, which is not cargo; set `cargo_path` under \
+                     [cargo_check] in .trait-winnower.toml or the CARGO environment variable"
+                ),
+            });
+        }
+        Ok(Toolchain {
+            cargo: version,
+            rustc: Self::rustc_version(root),
         })
     }
 
+    /// What the rustc cargo builds `root` with (`RUSTC`, else `rustc`) prints for
+    /// `--version`; `None` when it cannot be run.
+    pub fn rustc_version(root: &Path) -> Option<String> {
+        let rustc = std::env::var_os("RUSTC").unwrap_or_else(|| "rustc".into());
+        Command::new(rustc)
+            .arg("--version")
+            .current_dir(root)
+            .output()
+            .ok()
+            .filter(|o| o.status.success())
+            .map(|o| String::from_utf8_lossy(&o.stdout).trim().to_owned())
+    }
+
+    /// The version `cargo clippy --version` prints, or a [`WinnowerError::Toolchain`] when
+    /// clippy is not installed for the cargo of `config`.
+    pub fn clippy_version(root: &Path, config: &CargoCheckConfig) -> TraitError<String> {
+        let cargo = config.cargo();
+        let output = Command::new(&cargo)
+            .args(["clippy", "--version"])
+            .current_dir(root)
+            .output()
+            .map_err(|e| WinnowerError::cargo_spawn(&cargo, &e))?;
+        let version = String::from_utf8_lossy(&output.stdout).trim().to_owned();
+        if !output.status.success() || !version.starts_with("clippy ") {
+            return Err(WinnowerError::Toolchain {
+                program: cargo,
+                detail: format!(
+                    "`clippy --version` failed ({}); `validator = \"clippy\"` needs clippy, \
+                     see `rustup component add clippy`",
+                    String::from_utf8_lossy(&output.stderr).trim()
+                ),
+            });
+        }
+        Ok(version)
+    }
+
+    /// Fail with the underlying io error and the path if cargo could not write the build
+    /// output of `config` under `root`, as in sandboxes with a read-only target directory.
+    pub fn check_target_dir(root: &Path, config: &CargoCheckConfig) -> TraitError<()> {
+        let dir = config.target_dir(root);
+        std::fs::create_dir_all(&dir)
+            .map_err(|e| WinnowerError::io("creating the cargo target directory", &dir, e))?;
+        let probe = dir.join(format!(".trait-winnower-probe-{}", std::process::id()));
+        std::fs::write(&probe, b"")
+            .map_err(|e| WinnowerError::io("writing to the cargo target directory", &dir, e))?;
+        std::fs::remove_file(&probe)
+            .map_err(|e| WinnowerError::io("writing to the cargo target directory", &dir, e))
+    }
+
+    fn spawn_error(&self, root: &Path, e: std::io::Error) -> WinnowerError {
+        match e.kind() {
+            std::io::ErrorKind::NotFound | std::io::ErrorKind::PermissionDenied => {
+                WinnowerError::cargo_spawn(&self.config.cargo(), &e)
+            }
+            _ => WinnowerError::Validation {
+                command: match self.clippy {
+                    Some(_) => "cargo clippy".into(),
+                    None => "cargo check".into(),
+                },
+                status: None,
+                stderr: format!("running in {}: {e}", Self::display(root)),
+            },
+        }
+    }
+
     #[inline]
     fn display(path: &Path) -> String {
         path.to_string_lossy().into_owned()
     }
 }
 
+/// The parts of an item bound removals edit: its generics, the arguments and return type of
+/// functions and methods, the fields of structs (see [`HasGenerics::snapshot_bounds`]).
+#[derive(Clone)]
+pub struct BoundsSnapshot {
+    generics: syn::Generics,
+    inputs: Option<Punctuated<FnArg, Comma>>,
+    output: Option<ReturnType>,
+    fields: Option<Fields>,
+}
+
 /// A trait for items that have generics.
 pub trait HasGenerics {
     /// Get a mutable reference to the generics of the item.
     fn generics_mut(&mut self) -> &mut syn::Generics;
+
+    /// Get a mutable reference to the arguments of the item, where argument-position
+    /// `impl Trait` bounds live; `None` for items without arguments.
+    fn inputs_mut(&mut self) -> Option<&mut Punctuated<FnArg, Comma>> {
+        None
+    }
+
+    /// Get a mutable reference to the return type of the item; `None` for items without.
+    fn output_mut(&mut self) -> Option<&mut ReturnType> {
+        None
+    }
+
+    /// Get a mutable reference to the fields of the item; `None` for items without.
+    fn fields_mut(&mut self) -> Option<&mut Fields> {
+        None
+    }
+
+    /// The declared type at `root` (see [`TypePath`]), if the item has it.
+    fn type_root_mut(&mut self, root: TypeRoot) -> Option<&mut Type> {
+        match root {
+            TypeRoot::Return => match self.output_mut()? {
+                ReturnType::Type(_, ty) => Some(ty),
+                ReturnType::Default => None,
+            },
+            TypeRoot::Arg(i) => match self.inputs_mut()?.iter_mut().nth(i)? {
+                FnArg::Typed(pt) => Some(&mut pt.ty),
+                FnArg::Receiver(_) => None,
+            },
+            TypeRoot::Field(i) => self.fields_mut()?.iter_mut().nth(i).map(|f| &mut f.ty),
+        }
+    }
+
+    /// A copy of everything a bound removal can change on the item.
+    fn snapshot_bounds(&mut self) -> BoundsSnapshot {
+        BoundsSnapshot {
+            generics: self.generics_mut().clone(),
+            inputs: self.inputs_mut().cloned(),
+            output: self.output_mut().cloned(),
+            fields: self.fields_mut().cloned(),
+        }
+    }
+
+    /// Put back what [`HasGenerics::snapshot_bounds`] copied.
+    fn restore_bounds(&mut self, snapshot: BoundsSnapshot) {
+        *self.generics_mut() = snapshot.generics;
+        if let (Some(inputs), Some(saved)) = (self.inputs_mut(), snapshot.inputs) {
+            *inputs = saved;
+        }
+        if let (Some(output), Some(saved)) = (self.output_mut(), snapshot.output) {
+            *output = saved;
+        }
+        if let (Some(fields), Some(saved)) = (self.fields_mut(), snapshot.fields) {
+            *fields = saved;
+        }
+    }
 }
 
 macro_rules! impl_has_generics {
@@ -355,12 +1656,221 @@ macro_rules! impl_has_generics {
     };
 }
 
+impl HasGenerics for syn::Generics {
+    fn generics_mut(&mut self) -> &mut syn::Generics {
+        self
+    }
+}
+
 impl_has_generics! {
-    syn::ItemFn => (.sig.generics),
     syn::ItemImpl => (.generics),
     syn::ItemTrait => (.generics),
-    syn::ItemStruct => (.generics),
-    syn::ImplItemFn => (.sig.generics),
-    syn::TraitItemFn => (.sig.generics),
     syn::ItemEnum => (.generics),
 }
+
+impl HasGenerics for syn::ItemStruct {
+    fn generics_mut(&mut self) -> &mut syn::Generics {
+        &mut self.generics
+    }
+
+    fn fields_mut(&mut self) -> Option<&mut Fields> {
+        Some(&mut self.fields)
+    }
+}
+
+macro_rules! impl_has_generics_for_fns {
+    ($($t:ty => ($($sig:tt)*)),* $(,)?) => {
+        $(
+            impl HasGenerics for $t {
+                fn generics_mut(&mut self) -> &mut syn::Generics {
+                    &mut self $($sig)* .generics
+                }
+
+                fn inputs_mut(&mut self) -> Option<&mut Punctuated<FnArg, Comma>> {
+                    Some(&mut self $($sig)* .inputs)
+                }
+
+                fn output_mut(&mut self) -> Option<&mut ReturnType> {
+                    Some(&mut self $($sig)* .output)
+                }
+            }
+        )*
+    };
+}
+
+impl_has_generics_for_fns! {
+    syn::Signature => (),
+    syn::ItemFn => (.sig),
+    syn::ImplItemFn => (.sig),
+    syn::TraitItemFn => (.sig),
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn remove(src: &str) -> TraitError<(String, ParamRemoval)> {
+        let mut f: syn::ItemFn = syn::parse_str(src)?;
+        let t = Ident::new("T", proc_macro2::Span::call_site());
+        let outcome = Remove::remove_type_param(&mut f, &t);
+        Ok((f.to_token_stream().to_string(), outcome))
+    }
+
+    #[test]
+    fn unused_type_params_go_with_their_brackets_and_predicates() -> TraitError<()> {
+        let (out, outcome) = remove("fn f<T: Clone>(x: u8) where T: Send {}")?;
+        assert_eq!(outcome, ParamRemoval::Removed);
+        assert_eq!(out, "fn f (x : u8) { }");
+        // Other parameters and their predicates stay.
+        let (out, outcome) = remove("fn f<'a, T: Clone, U>(x: &'a U) where T: Send, U: Sync {}")?;
+        assert_eq!(outcome, ParamRemoval::Removed);
+        assert_eq!(out, "fn f < 'a , U > (x : & 'a U) where U : Sync { }");
+        Ok(())
+    }
+
+    #[test]
+    fn type_params_still_referenced_are_kept() -> TraitError<()> {
+        for src in [
+            "fn f<T: Default>() -> T { T::default() }",
+            "fn f<T>(x: Vec<T>) {}",
+            "fn f<T, U: From<T>>(u: U) {}",
+            "fn f<T>() where Vec<T>: Send {}",
+            "fn f<T>() { let _ = std::mem::size_of::<T>(); }",
+        ] {
+            let (out, outcome) = remove(src)?;
+            let original: syn::ItemFn = syn::parse_str(src)?;
+            assert_eq!(out, original.to_token_stream().to_string(), "{src}");
+            let ParamRemoval::Skipped { note } = outcome else {
+                panic!("{src} was changed");
+            };
+            assert!(note.contains("`T` is still used"), "{note}");
+        }
+        let (_, outcome) = remove("fn f<U>() {}")?;
+        assert!(matches!(outcome, ParamRemoval::Skipped { .. }));
+        Ok(())
+    }
+
+    const MIXED: &str =
+        "fn f<'a, 'b, T: Clone + Send, const N: usize, U: Copy>(t: &'a T, u: &'b U) {}";
+
+    #[test]
+    fn mixed_parameter_lists_index_every_parameter() -> TraitError<()> {
+        let file = syn::parse_file(MIXED)?;
+        let items = crate::analysis::ItemBounds::collect_items_in_file(&file)?;
+        let fb = &items.fns()[0];
+        let params: Vec<(String, usize)> = fb
+            .type_param_bounds()
+            .iter()
+            .map(|tp| (tp.ident().to_string(), tp.param_index()))
+            .collect();
+        assert_eq!(params, [("T".to_owned(), 2), ("U".to_owned(), 4)]);
+
+        let candidates = BoundCandidate::collect_function_candidates(fb);
+        let sites: Vec<String> = candidates.iter().map(|c| format!("{:?}", c.site)).collect();
+        assert_eq!(candidates.len(), 3, "{sites:?}");
+        for (c, want) in candidates.iter().zip([
+            "< 'a , 'b , T : Send , const N : usize , U : Copy >",
+            "< 'a , 'b , T : Clone , const N : usize , U : Copy >",
+            "< 'a , 'b , T : Clone + Send , const N : usize , U >",
+        ]) {
+            let mut f: syn::ItemFn = syn::parse_str(MIXED)?;
+            assert!(Remove::apply_to_item_with_generics(&mut f, c), "{c:?}");
+            assert_eq!(f.sig.generics.to_token_stream().to_string(), want);
+        }
+        Ok(())
+    }
+
+    #[test]
+    fn a_param_index_naming_another_parameter_edits_nothing() -> TraitError<()> {
+        let bound: TypeParamBound = syn::parse_str("Clone")?;
+        let t = Ident::new("T", proc_macro2::Span::call_site());
+        // Indices 1 and 3 are `'b` and `N`; 4 is `U`; 9 is past the end.
+        for param_index in [1, 3, 4, 9] {
+            let mut f: syn::ItemFn = syn::parse_str(MIXED)?;
+            let candidate = BoundCandidate {
+                site: BoundSite::TypeParam {
+                    ident: t.clone(),
+                    param_index,
+                    bound_index: 0,
+                },
+                bound: bound.clone(),
+            };
+            let mismatch = Remove::param_mismatch(&mut f, &candidate).expect("a mismatch");
+            assert_eq!(
+                mismatch.to_string(),
+                format!("type parameter `T` at generic parameter {param_index}")
+            );
+            assert!(!Remove::apply_to_item_with_generics(&mut f, &candidate));
+            let original: syn::ItemFn = syn::parse_str(MIXED)?;
+            assert_eq!(
+                f.to_token_stream().to_string(),
+                original.to_token_stream().to_string(),
+                "{param_index}"
+            );
+        }
+        Ok(())
+    }
+
+    #[test]
+    fn only_errors_in_path_packages_stop_a_check() {
+        let message = |id: &str, level: &str| {
+            format!(
+                r#"{{"reason":"compiler-message","package_id":"{id}","message":{{"level":"{level}","rendered":"{level}: oops\n"}}}}"#
+            )
+        };
+        let member = "path+file:///work/a#0.1.0";
+        let old_member = "a 0.1.0 (path+file:///work/a)";
+        let registry = "registry+https://github.com/rust-lang/crates.io-index#serde@1.0.0";
+        assert!(CargoCheck::is_fatal(&message(member, "error")));
+        assert!(CargoCheck::is_fatal(&message(old_member, "error")));
+        assert!(!CargoCheck::is_fatal(&message(member, "warning")));
+        assert!(!CargoCheck::is_fatal(&message(registry, "error")));
+        assert!(!CargoCheck::is_fatal(
+            r#"{"reason":"build-finished","success":false}"#
+        ));
+        assert!(!CargoCheck::is_fatal("error: could not compile `a`"));
+
+        let stdout = [message(member, "warning"), message(member, "error")].join("\n");
+        assert_eq!(
+            CargoCheck::rendered(&stdout),
+            "warning: oops\nerror: oops\n"
+        );
+    }
+
+    #[test]
+    fn only_tolerated_lints_let_a_failed_check_pass() {
+        let error = |code: &str, line: usize| {
+            format!(
+                r#"{{"reason":"compiler-message","package_id":"path+file:///work/a#0.1.0","message":{{"message":"m{line}","level":"error","code":{code},"spans":[{{"file_name":"src/lib.rs","line_start":{line},"line_end":{line},"column_start":5,"column_end":9,"is_primary":true}}]}}}}"#
+            )
+        };
+        let unused = error(r#"{"code":"unused_imports"}"#, 1);
+        let semantic = error(r#"{"code":"E0277"}"#, 3);
+        assert!(!CargoCheck::is_fatal_unless_lint(&unused));
+        assert!(CargoCheck::is_fatal_unless_lint(&semantic));
+        assert!(CargoCheck::is_fatal_unless_lint(&error("null", 3)));
+
+        let check = CargoCheck::new(CargoCheckConfig {
+            tolerate_new_lints: vec!["unused-imports".into()],
+            ..CargoCheckConfig::default()
+        });
+        let follow_ups = check.tolerated(&[unused.as_str(), &unused].join("\n"));
+        assert_eq!(
+            follow_ups,
+            Some(vec![FollowUp {
+                file: "src/lib.rs".into(),
+                line: 1,
+                column: 5,
+                lint: "unused_imports".into(),
+                message: "m1".into(),
+            }])
+        );
+        assert_eq!(check.tolerated(&[unused, semantic].join("\n")), None);
+        assert_eq!(check.tolerated("error: could not compile `a`"), None);
+        let strict = CargoCheck::new(CargoCheckConfig::default());
+        assert_eq!(
+            strict.tolerated(&error(r#"{"code":"unused_imports"}"#, 1)),
+            None
+        );
+    }
+}
@@ -3,108 +3,438 @@
 
 #![deny(missing_docs)]
 
-use crate::config::CargoCheckConfig;
+use crate::analysis::{ItemKey, ItemKind, ItemLocator, names_bound};
+use crate::cancel::CancelToken;
+use crate::config::{CargoCheckConfig, ForeignTraitImpls};
 use crate::dynamic_analysis::common::{
-    BoundCandidate, BoundRemovalOutcome, BoundRemovalResult, CargoCheck, HasGenerics,
+    BoundCandidate, BoundRemovalOutcome, BoundRemovalResult, BoundsSnapshot, HasGenerics,
+    OwnedBoundCandidate, Remove, ValidationOutcome,
 };
+use crate::dynamic_analysis::store::SourceStore;
+use crate::dynamic_analysis::text_edit::with_header_of;
+use crate::dynamic_analysis::validate::Validator;
 use crate::error::TraitError;
-use anyhow::Context;
+use crate::findings::{
+    dyn_compat_caution, evident_use, modified_bound_caution, opaque_type_caution,
+    unsafe_impl_caution,
+};
+use crate::observer::Observer;
+use crate::references::References;
+use crate::report::{Finding, Removal};
+use crate::trait_index::TraitIndex;
 use proc_macro2::Span;
-use std::fs;
+use quote::ToTokens;
+use serde::{Deserialize, Serialize};
+use std::cell::RefCell;
+use std::collections::{BTreeMap, HashSet};
+use std::path::Path;
+use std::time::{Duration, Instant};
 use syn::visit_mut::VisitMut;
 
-/// Traversal that locates the *exact* target item by its anchor Span
+/// Which item a [`BoundEditor`] edits: its kind, its name, where it sits in the file's tree
+/// (see [`ItemKey::path`]) and the span of its name, or `impl`.
+///
+/// Kind, name and path decide. The span only speaks for a node whose path differs: spans
+/// compare byte ranges, or lines and columns across parses, and proc-macro2 counts those in
+/// units that can disagree once a file has multibyte text before the item.
+///
+/// When neither finds a node, e.g. after the file was reformatted or a macro moved items
+/// around, the editor falls back to the module path, kind, name and
+/// [ordinal](ItemKey::ordinal), and records an [`AnchorMiss`].
+#[derive(Debug, Clone)]
+pub struct Anchor {
+    kind: ItemKind,
+    ident: Option<String>,
+    path: Vec<usize>,
+    span: Span,
+    module: String,
+    ordinal: Option<usize>,
+}
+
+impl Anchor {
+    /// The anchor of the item `key` names.
+    pub fn of(key: &ItemKey<'_>) -> Self {
+        Self {
+            kind: key.kind(),
+            ident: key.ident().map(ToString::to_string),
+            path: key.path().to_vec(),
+            span: key.span(),
+            module: key.module_path().to_owned(),
+            ordinal: key.ordinal(),
+        }
+    }
+
+    /// Whether a node of `kind` named `ident`, at `path` with its name at `span`, is the
+    /// item: an impl has no name to compare.
+    fn matches(
+        &self,
+        kind: ItemKind,
+        ident: Option<&syn::Ident>,
+        path: &[usize],
+        span: Span,
+    ) -> bool {
+        if kind != self.kind {
+            return false;
+        }
+        if let (Some(want), Some(got)) = (&self.ident, ident)
+            && got != want
+        {
+            return false;
+        }
+        path == self.path || spans_equal(span, self.span)
+    }
+
+    /// Whether a node of `kind` named `ident`, the `ordinal`th such in `module`, is the
+    /// item by the structural fallback.
+    fn matches_structurally(
+        &self,
+        kind: ItemKind,
+        ident: Option<&syn::Ident>,
+        module: &str,
+        ordinal: usize,
+    ) -> bool {
+        kind == self.kind
+            && ident.map(ToString::to_string) == self.ident
+            && module == self.module
+            && Some(ordinal) == self.ordinal
+    }
+}
+
+impl std::fmt::Display for Anchor {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let node = describe_node(
+            self.kind,
+            self.ident.as_deref(),
+            &self.module,
+            &self.path,
+            self.span,
+        );
+        match self.ordinal {
+            Some(n) => write!(f, "{node}, #{n} of its name there"),
+            None => write!(f, "{node}, in a function body"),
+        }
+    }
+}
+
+/// A node as misses report it, e.g. ``fn `helper` in `outer::inner` at [2, 0] (3:8)``.
+fn describe_node(
+    kind: ItemKind,
+    ident: Option<&str>,
+    module: &str,
+    path: &[usize],
+    span: Span,
+) -> String {
+    let name = ident.map(|i| format!(" `{i}`")).unwrap_or_default();
+    let module = if module.is_empty() {
+        "at file level".to_owned()
+    } else {
+        format!("in `{module}`")
+    };
+    let start = span.start();
+    format!(
+        "{}{name} {module} at {path:?} ({}:{})",
+        kind.id(),
+        start.line,
+        start.column + 1
+    )
+}
+
+/// An edit whose [`Anchor`] matched no node of the file: what was looked for, the nodes
+/// nearest it, and whether the structural fallback found the item anyway. A hard miss
+/// leaves the bound untried, reported as skipped.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct AnchorMiss {
+    /// The anchor: kind, name, module path, tree path, position and ordinal; for a bound
+    /// whose parameter index names another parameter, that parameter of the anchor.
+    pub wanted: String,
+    /// Nodes of the same kind and name elsewhere, and nodes at the anchor's tree path
+    /// under another name.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub near: Vec<String>,
+    /// The fallback found the item by module path, kind, name and ordinal.
+    pub recovered: bool,
+}
+
+impl std::fmt::Display for AnchorMiss {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "no node matched {}", self.wanted)?;
+        if self.recovered {
+            write!(f, "; found by module path, kind, name and ordinal")?;
+        } else {
+            write!(
+                f,
+                "; not found by module path, kind, name and ordinal either"
+            )?;
+        }
+        if !self.near.is_empty() {
+            write!(f, "; nearest: {}", self.near.join(", "))?;
+        }
+        Ok(())
+    }
+}
+
+/// The most nodes an [`AnchorMiss`] lists as near.
+const MAX_NEAR: usize = 4;
+
+/// Compare two spans for equality using byte ranges when available.
+#[inline]
+fn spans_equal(span1: Span, span2: Span) -> bool {
+    if span1.byte_range() == span2.byte_range() {
+        return true;
+    }
+    if span1.file() != span2.file() {
+        return false;
+    }
+    let start1 = span1.start();
+    let start2 = span2.start();
+    let end1 = span1.end();
+    let end2 = span2.end();
+    start1.line == start2.line
+        && start1.column == start2.column
+        && end1.line == end2.line
+        && end1.column == end2.column
+}
+
+/// Traversal that locates the *exact* target item by its [`Anchor`]
 pub struct BoundEditor<'a, T: HasGenerics> {
-    target_ident: Option<&'a syn::Ident>,
-    target_anchor: Span,
-    candidate: &'a BoundCandidate,
+    target: &'a Anchor,
+    /// [`ItemKey::path`] of the node being visited, and the index of its next sibling.
+    path: Vec<usize>,
+    next: usize,
+    /// Names of the inline modules enclosing the node being visited.
+    modules: Vec<String>,
+    /// Nodes seen so far by module path, kind and name, as [`ItemKey::ordinal`] counts.
+    seen: BTreeMap<(String, ItemKind, String), usize>,
+    /// How many blocks enclose the node being visited.
+    blocks: usize,
+    /// Whether this pass is the structural fallback.
+    fallback: bool,
+    /// Whether a node matched the target.
+    found: bool,
+    /// Nodes near the target (see [`AnchorMiss::near`]).
+    near: Vec<String>,
+    miss: Option<AnchorMiss>,
+    candidates: &'a [BoundCandidate],
     modified: bool,
+    original: Option<BoundsSnapshot>,
+    restore: Option<BoundsSnapshot>,
     _phantom: std::marker::PhantomData<T>,
 }
 
 impl<'a, T: HasGenerics> BoundEditor<'a, T> {
-    /// Construct a new editor for the given anchor/ident/candidate.
-    pub fn new(
-        target_ident: Option<&'a syn::Ident>,
-        target_anchor: Span,
-        candidate: &'a BoundCandidate,
-    ) -> Self {
+    /// Construct a new editor removing `candidate` from the item at `target`. Only nodes
+    /// of its kind are edited: an `impl` and its first method can share a line.
+    pub fn new(target: &'a Anchor, candidate: &'a BoundCandidate) -> Self {
+        Self::new_group(target, std::slice::from_ref(candidate))
+    }
+
+    /// An editor removing every candidate in `candidates` from the target, e.g. a bound
+    /// declared both on its parameter and in the where clause (see
+    /// [`BoundCandidate::group_duplicates`]).
+    pub fn new_group(target: &'a Anchor, candidates: &'a [BoundCandidate]) -> Self {
         Self {
-            target_ident,
-            target_anchor,
-            candidate,
+            target,
+            path: Vec::new(),
+            next: 0,
+            modules: Vec::new(),
+            seen: BTreeMap::new(),
+            blocks: 0,
+            fallback: false,
+            found: false,
+            near: Vec::new(),
+            miss: None,
+            candidates,
             modified: false,
+            original: None,
+            restore: None,
             _phantom: std::marker::PhantomData,
         }
     }
 
+    /// An editor putting `original`, what [`BoundEditor::take_original`] returned, back on
+    /// the target.
+    pub fn restoring(target: &'a Anchor, original: BoundsSnapshot) -> Self {
+        let mut editor = Self::new_group(target, &[]);
+        editor.restore = Some(original);
+        editor
+    }
+
+    /// The target's generics and arguments as they were before this editor changed them;
+    /// `None` until it has. Only those are kept, so a trial edits the file in place rather
+    /// than a copy of it.
+    pub fn take_original(&mut self) -> Option<BoundsSnapshot> {
+        self.original.take()
+    }
+
+    /// Undo this editor's change to `file`, which it edited in place. Returns whether the
+    /// target was found again.
+    pub fn undo(&mut self, file: &mut syn::File) -> bool {
+        let Some(original) = self.take_original() else {
+            return false;
+        };
+        let mut restorer = Self::restoring(self.target, original);
+        restorer.visit_file_mut(file);
+        self.modified = false;
+        restorer.modified
+    }
+
     /// Returns true if the item was modified.
     #[inline]
     pub fn modified(&self) -> bool {
         self.modified
     }
 
-    /// Compare two spans for equality using byte ranges when available.
-    #[inline]
-    fn spans_equal(&self, span1: Span, span2: Span) -> bool {
-        if span1.byte_range() == span2.byte_range() {
-            return true;
-        }
-        if span1.file() != span2.file() {
-            return false;
-        }
-        let start1 = span1.start();
-        let start2 = span2.start();
-        let end1 = span1.end();
-        let end2 = span2.end();
-        start1.line == start2.line
-            && start1.column == start2.column
-            && end1.line == end2.line
-            && end1.column == end2.column
+    /// How the last visit missed its anchor, if it did; the fallback may still have found
+    /// and edited the item.
+    pub fn take_miss(&mut self) -> Option<AnchorMiss> {
+        self.miss.take()
+    }
+
+    /// Visit a child of the current node with `visit`, its index appended to the path, as
+    /// the collection of [`ItemKey`]s does.
+    fn enter(&mut self, visit: impl FnOnce(&mut Self)) {
+        self.path.push(self.next);
+        let siblings = std::mem::replace(&mut self.next, 0);
+        visit(self);
+        self.next = siblings + 1;
+        self.path.pop();
     }
 
     #[inline]
     fn try_edit_node<N: HasGenerics>(
         &mut self,
         node: &mut N,
+        node_kind: ItemKind,
         node_ident: Option<&syn::Ident>,
         node_anchor: Span,
     ) {
+        let module = self.modules.join("::");
+        let ordinal = (self.blocks == 0).then(|| {
+            let name = node_ident.map(ToString::to_string).unwrap_or_default();
+            let seen = self
+                .seen
+                .entry((module.clone(), node_kind, name))
+                .or_default();
+            *seen += 1;
+            *seen - 1
+        });
         if self.modified {
             return;
         }
-        if !self.spans_equal(node_anchor, self.target_anchor) {
+        let matched = if self.fallback {
+            ordinal.is_some_and(|n| {
+                self.target
+                    .matches_structurally(node_kind, node_ident, &module, n)
+            })
+        } else {
+            self.target
+                .matches(node_kind, node_ident, &self.path, node_anchor)
+        };
+        if !matched {
+            if !self.fallback {
+                self.note_near(node_kind, node_ident, &module, node_anchor);
+            }
             return;
         }
-        if let (Some(want), Some(got)) = (self.target_ident, node_ident)
-            && *want != *got
-        {
+        self.found = true;
+        if let Some(original) = self.restore.take() {
+            node.restore_bounds(original);
+            self.modified = true;
             return;
         }
-        self.modified = crate::dynamic_analysis::common::Remove::apply_to_item_with_generics(
-            node,
-            self.candidate,
-        );
+        let before = node.snapshot_bounds();
+        // Each removal looks its bound up by text, so earlier ones shifting indices is fine.
+        for candidate in self.candidates {
+            if let Some(mismatch) = Remove::param_mismatch(node, candidate) {
+                // The node is the item, but not as the candidate was taken from it.
+                self.miss = Some(AnchorMiss {
+                    wanted: format!("{mismatch} of {}", self.target),
+                    near: mismatch
+                        .found
+                        .into_iter()
+                        .map(|p| format!("`{p}`"))
+                        .collect(),
+                    recovered: false,
+                });
+                continue;
+            }
+            self.modified |= Remove::apply_to_item_with_generics(node, candidate);
+        }
+        if self.modified {
+            self.original = Some(before);
+        }
+    }
+
+    /// Keep the node, unmatched, as near the target if it is of its kind and name, or sits
+    /// at its tree path.
+    fn note_near(&mut self, kind: ItemKind, ident: Option<&syn::Ident>, module: &str, span: Span) {
+        let ident = ident.map(ToString::to_string);
+        let same_name = kind == self.target.kind && ident == self.target.ident;
+        if self.near.len() < MAX_NEAR && (same_name || self.path == self.target.path) {
+            let node = describe_node(kind, ident.as_deref(), module, &self.path, span);
+            self.near.push(node);
+        }
     }
 }
 
 impl<'a, T: HasGenerics> VisitMut for BoundEditor<'a, T> {
+    fn visit_file_mut(&mut self, file: &mut syn::File) {
+        self.found = false;
+        self.near.clear();
+        self.seen.clear();
+        syn::visit_mut::visit_file_mut(self, file);
+        if self.found {
+            return;
+        }
+        // Say what was missed rather than skip the bound silently, and look again by
+        // module path, kind, name and ordinal.
+        self.fallback = true;
+        self.seen.clear();
+        syn::visit_mut::visit_file_mut(self, file);
+        self.fallback = false;
+        self.miss = Some(AnchorMiss {
+            wanted: self.target.to_string(),
+            near: std::mem::take(&mut self.near),
+            recovered: self.found,
+        });
+    }
+
+    fn visit_item_mut(&mut self, node: &mut syn::Item) {
+        self.enter(|this| syn::visit_mut::visit_item_mut(this, node));
+    }
+
+    fn visit_impl_item_mut(&mut self, node: &mut syn::ImplItem) {
+        self.enter(|this| syn::visit_mut::visit_impl_item_mut(this, node));
+    }
+
+    fn visit_trait_item_mut(&mut self, node: &mut syn::TraitItem) {
+        self.enter(|this| syn::visit_mut::visit_trait_item_mut(this, node));
+    }
+
+    fn visit_block_mut(&mut self, node: &mut syn::Block) {
+        self.blocks += 1;
+        syn::visit_mut::visit_block_mut(self, node);
+        self.blocks -= 1;
+    }
+
     fn visit_item_mod_mut(&mut self, node: &mut syn::ItemMod) {
         if self.modified {
             return;
         }
+        self.modules.push(node.ident.to_string());
         syn::visit_mut::visit_item_mod_mut(self, node);
+        self.modules.pop();
     }
 
     fn visit_item_fn_mut(&mut self, node: &mut syn::ItemFn) {
         let id = node.sig.ident.clone();
         let anchor = id.span();
-        self.try_edit_node(node, Some(&id), anchor);
+        self.try_edit_node(node, ItemKind::Fn, Some(&id), anchor);
     }
 
     fn visit_item_impl_mut(&mut self, node: &mut syn::ItemImpl) {
         let anchor = node.impl_token.span;
-        self.try_edit_node(node, None, anchor);
+        self.try_edit_node(node, ItemKind::Impl, None, anchor);
         if !self.modified {
             syn::visit_mut::visit_item_impl_mut(self, node);
         }
@@ -113,7 +443,7 @@ impl<'a, T: HasGenerics> VisitMut for BoundEditor<'a, T> {
     fn visit_item_trait_mut(&mut self, node: &mut syn::ItemTrait) {
         let id = node.ident.clone();
         let anchor = id.span();
-        self.try_edit_node(node, Some(&id), anchor);
+        self.try_edit_node(node, ItemKind::Trait, Some(&id), anchor);
         if !self.modified {
             syn::visit_mut::visit_item_trait_mut(self, node);
         }
@@ -122,25 +452,25 @@ impl<'a, T: HasGenerics> VisitMut for BoundEditor<'a, T> {
     fn visit_item_struct_mut(&mut self, node: &mut syn::ItemStruct) {
         let id = node.ident.clone();
         let anchor = id.span();
-        self.try_edit_node(node, Some(&id), anchor);
+        self.try_edit_node(node, ItemKind::Struct, Some(&id), anchor);
     }
 
     fn visit_item_enum_mut(&mut self, node: &mut syn::ItemEnum) {
         let id = node.ident.clone();
         let anchor = id.span();
-        self.try_edit_node(node, Some(&id), anchor);
+        self.try_edit_node(node, ItemKind::Enum, Some(&id), anchor);
     }
 
     fn visit_impl_item_fn_mut(&mut self, node: &mut syn::ImplItemFn) {
         let id = node.sig.ident.clone();
         let anchor = id.span();
-        self.try_edit_node(node, Some(&id), anchor);
+        self.try_edit_node(node, ItemKind::ImplMethod, Some(&id), anchor);
     }
 
     fn visit_trait_item_fn_mut(&mut self, node: &mut syn::TraitItemFn) {
         let id = node.sig.ident.clone();
         let anchor = id.span();
-        self.try_edit_node(node, Some(&id), anchor);
+        self.try_edit_node(node, ItemKind::TraitMethod, Some(&id), anchor);
     }
 }
 
@@ -148,69 +478,391 @@ impl<'a, T: HasGenerics> VisitMut for BoundEditor<'a, T> {
 fn hash_bytes(s: &str) -> u32 {
     crc32fast::hash(s.as_bytes())
 }
+
+/// What every pruner call of one `prune` run shares.
+#[derive(Clone, Copy)]
+pub struct PruneContext<'a> {
+    /// The crate the validator builds.
+    pub crate_root: &'a Path,
+    /// Decides whether a trial removal is kept.
+    pub validator: &'a dyn Validator,
+    /// Stops the loop between trials and interrupts validation.
+    pub cancel: &'a CancelToken,
+    /// Where sources are read and trials written.
+    pub store: &'a dyn SourceStore,
+    /// Also try bounds whose removal can change a trait's dyn-compatibility (see
+    /// [`dyn_compat_caution`]).
+    pub allow_dyn_compat_changes: bool,
+    /// Also try the bounds of `unsafe impl`s, which may be what keeps them sound (see
+    /// [`unsafe_impl_caution`]).
+    pub allow_unsafe_impl_pruning: bool,
+    /// Also try bounds of return-position `impl Trait` and `dyn Trait` types, whose removal
+    /// changes the item's public type (see [`opaque_type_caution`]).
+    pub include_opaque_types: bool,
+    /// Only try bounds naming one of these traits (see
+    /// [`names_bound`](crate::analysis::names_bound)); empty tries all.
+    pub only_traits: &'a [String],
+    /// Leave the bounds of structs and enums with `#[derive]`s alone (see
+    /// [`ItemRef::derives`](crate::analysis::ItemRef::derives)).
+    pub skip_derived_types: bool,
+    /// Leave the bounds of blanket impls alone (see
+    /// [`ItemRef::is_blanket_impl`](crate::analysis::ItemRef::is_blanket_impl)).
+    pub skip_blanket_impls: bool,
+    /// Traits the workspace defines, to tell impls of foreign traits from the rest (see
+    /// [`TraitIndex::foreign_trait`]); `None` tells none.
+    pub trait_index: Option<&'a TraitIndex>,
+    /// What to do with the bounds of impls of foreign traits.
+    pub foreign_trait_impls: ForeignTraitImpls,
+    /// The configuration the validator checks with, if it is `cargo check`; items it never
+    /// builds (see [`CargoCheckConfig::builds_cfg`]) are skipped rather than tried.
+    pub cargo_check: Option<&'a CargoCheckConfig>,
+    /// Skip bounds the body plainly uses (see [`evident_use`]) rather than check them.
+    pub prefilter: bool,
+    /// References to weigh findings with, when weights were asked for (see
+    /// [`References::weight`]).
+    pub references: Option<&'a References>,
+    /// Leave the items [`references`](Self::references) weighs less than this alone.
+    pub min_weight: usize,
+    /// Rejections to reuse across the items of a scope; `None` checks every candidate.
+    pub memo: Option<&'a RefCell<RejectionMemo>>,
+}
+
+impl PruneContext<'_> {
+    /// The candidates of `item_key` worth a trial: without those whose removal can change
+    /// dyn-compatibility or a public opaque type, unless allowed, those of traits not asked
+    /// for, and all of an item weighing less than `min_weight` or of a skipped derived type
+    /// or blanket impl. Modified bounds (see
+    /// [`modified_bound_caution`]), unless allowed those of an `unsafe impl` (see
+    /// [`unsafe_impl_caution`]), those of an item validation never builds and, with
+    /// [`ForeignTraitImpls::Skip`], those of an impl of a foreign trait are reported as
+    /// skipped into `outcomes`.
+    pub fn screen(
+        &self,
+        item_key: &ItemKey<'_>,
+        candidates: Vec<BoundCandidate>,
+        outcomes: &mut Vec<BoundRemovalResult>,
+        observer: &mut dyn Observer,
+    ) -> Vec<BoundCandidate> {
+        self.sift(item_key, candidates, |skipped, not_validated| {
+            let mark = |f: &mut Finding| f.not_validated = not_validated;
+            self.skip(item_key, skipped, mark, outcomes, observer);
+        })
+    }
+
+    /// How many of `candidates` [`screen`](Self::screen) would leave to try; nothing is
+    /// recorded.
+    pub fn tried(&self, item_key: &ItemKey<'_>, candidates: Vec<BoundCandidate>) -> usize {
+        self.sift(item_key, candidates, |_, _| {}).len()
+    }
+
+    /// The candidates [`screen`](Self::screen) keeps, handing those it reports as skipped to
+    /// `skip` with whether validation never builds their item.
+    fn sift(
+        &self,
+        item_key: &ItemKey<'_>,
+        mut candidates: Vec<BoundCandidate>,
+        mut skip: impl FnMut(&[BoundCandidate], bool),
+    ) -> Vec<BoundCandidate> {
+        if !self.allow_dyn_compat_changes {
+            candidates.retain(|c| dyn_compat_caution(item_key.item(), c).is_none());
+        }
+        if !self.include_opaque_types {
+            candidates.retain(|c| opaque_type_caution(c).is_none());
+        }
+        if !self.only_traits.is_empty() {
+            candidates.retain(|c| {
+                let bound = c.bound.to_token_stream().to_string();
+                self.only_traits.iter().any(|t| names_bound(t, &bound))
+            });
+        }
+        if let Some(references) = self.references
+            && references.weight(&ItemLocator::from(item_key)) < self.min_weight
+        {
+            candidates.clear();
+        }
+        if self.skip_derived_types && !item_key.item().derives().is_empty() {
+            candidates.clear();
+        }
+        if self.skip_blanket_impls && item_key.item().is_blanket_impl() {
+            candidates.clear();
+        }
+        let (modified, mut candidates): (Vec<_>, Vec<_>) = candidates
+            .into_iter()
+            .partition(|c| modified_bound_caution(c).is_some());
+        skip(&modified, false);
+        if !self.allow_unsafe_impl_pruning && unsafe_impl_caution(item_key.item()).is_some() {
+            skip(&candidates, false);
+            candidates.clear();
+        }
+        if self.foreign_trait_impls == ForeignTraitImpls::Skip
+            && self.foreign_trait(item_key).is_some()
+        {
+            skip(&candidates, false);
+            candidates.clear();
+        }
+        if let Some(cargo_check) = self.cargo_check
+            && cargo_check.builds_cfg(item_key.cfg()) == Some(false)
+        {
+            skip(&candidates, true);
+            candidates.clear();
+        }
+        candidates
+    }
+
+    /// The trait `item_key` implements, when it is an impl of a trait the workspace does
+    /// not define and such impls are not allowed (see [`TraitIndex::foreign_trait`]).
+    pub fn foreign_trait(&self, item_key: &ItemKey<'_>) -> Option<String> {
+        if self.foreign_trait_impls == ForeignTraitImpls::Allow {
+            return None;
+        }
+        self.trait_index?.foreign_trait(item_key.item())
+    }
+
+    /// The finding of `candidate` on `item_key`, marked with its foreign trait if any and
+    /// weighed when weights were asked for.
+    pub fn finding(&self, item_key: &ItemKey<'_>, candidate: &BoundCandidate) -> Finding {
+        let mut finding = Finding::new(item_key, candidate);
+        finding.foreign_trait = self.foreign_trait(item_key);
+        if let Some(references) = self.references {
+            references.weigh(&mut finding);
+        }
+        finding
+    }
+
+    /// Report `skipped`, candidates of `item_key`, as skipped into `outcomes`, their
+    /// findings passed through `mark`.
+    fn skip(
+        &self,
+        item_key: &ItemKey<'_>,
+        skipped: &[BoundCandidate],
+        mark: impl Fn(&mut Finding),
+        outcomes: &mut Vec<BoundRemovalResult>,
+        observer: &mut dyn Observer,
+    ) {
+        let label = item_key.to_string();
+        for (index, candidate) in skipped.iter().enumerate() {
+            observer.on_candidate_trial(
+                label.trim_start_matches("// "),
+                &candidate.bound.to_token_stream().to_string(),
+                index,
+                skipped.len(),
+            );
+            let (before, after) =
+                Removal::generics_before_after(item_key.item(), std::slice::from_ref(candidate));
+            let mut finding = self.finding(item_key, candidate);
+            mark(&mut finding);
+            outcomes.push(BoundRemovalResult {
+                candidate: candidate.clone(),
+                outcome: BoundRemovalOutcome::Skipped,
+                finding,
+                before,
+                after,
+                check_time: Duration::ZERO,
+                write_time: Duration::ZERO,
+                batch: None,
+            });
+            if let Some(result) = outcomes.last() {
+                observer.on_outcome(result);
+            }
+        }
+    }
+
+    /// How the body of `item_key` plainly uses `candidate`, if the prefilter is on and it
+    /// does (see [`evident_use`]).
+    pub fn evident(&self, item_key: &ItemKey<'_>, candidate: &BoundCandidate) -> Option<String> {
+        if self.prefilter {
+            evident_use(item_key.item(), candidate)
+        } else {
+            None
+        }
+    }
+}
+
+/// Bounds whose removal was rejected, by scope (see [`ItemKey::scope`]), bounded type and
+/// bound. Removing the same bound from another item of the scope, e.g. from a method after
+/// its impl, is taken to fail the same way until a removal in the scope is accepted.
+#[derive(Debug, Default)]
+pub struct RejectionMemo {
+    rejected: HashSet<(String, String, String)>,
+}
+
+impl RejectionMemo {
+    /// Whether removing `candidate` from an item of `scope` was rejected.
+    pub fn rejected(&self, scope: &str, candidate: &BoundCandidate) -> bool {
+        self.rejected.contains(&Self::key(scope, candidate))
+    }
+
+    /// Record that removing `candidate` from an item of `scope` was rejected.
+    pub fn reject(&mut self, scope: &str, candidate: &BoundCandidate) {
+        self.rejected.insert(Self::key(scope, candidate));
+    }
+
+    /// Forget the rejections in `scope`, where a removal was just accepted.
+    pub fn invalidate(&mut self, scope: &str) {
+        self.rejected.retain(|(s, _, _)| s != scope);
+    }
+
+    fn key(scope: &str, candidate: &BoundCandidate) -> (String, String, String) {
+        let owned = OwnedBoundCandidate::from(candidate);
+        let (bounded, bound) = owned.key();
+        (scope.to_owned(), bounded.to_owned(), bound.to_owned())
+    }
+}
+
 struct CandidateTrialConfig<'a> {
-    file_path: &'a std::path::Path,
-    crate_root: &'a std::path::Path,
-    working: &'a syn::File,
-    target_ident: Option<&'a syn::Ident>,
-    target_anchor: Span,
-    candidate: &'a BoundCandidate,
+    file_path: &'a Path,
+    ctx: &'a PruneContext<'a>,
+    target: &'a Anchor,
+    group: &'a [BoundCandidate],
     current_src: &'a str,
     current_hash: u32,
-    cargo_check_config: &'a CargoCheckConfig,
+}
+struct TrialResult {
+    /// The text the kept removal left, with its hash.
+    accepted: Option<(String, u32)>,
+    cancelled: bool,
+    outcome: BoundRemovalOutcome,
+    write_time: Duration,
+    /// How the edit missed its anchor, if it did.
+    miss: Option<AnchorMiss>,
 }
 impl<'a> CandidateTrialConfig<'a> {
+    fn unchanged(&self) -> TrialResult {
+        TrialResult {
+            accepted: None,
+            cancelled: false,
+            outcome: BoundRemovalOutcome::Skipped,
+            write_time: Duration::ZERO,
+            miss: None,
+        }
+    }
+
+    /// A rejection taken over from the [`RejectionMemo`]; nothing is written or checked.
+    fn memoized(&self) -> TrialResult {
+        TrialResult {
+            outcome: BoundRemovalOutcome::Retained {
+                check: ValidationOutcome::default(),
+            },
+            ..self.unchanged()
+        }
+    }
+
+    /// Try removing the group from `working`, the tree of the current text. It is edited in
+    /// place: an accepted removal stays, a rejected one is undone.
     fn try_candidate_once<T: HasGenerics>(
         config: CandidateTrialConfig<'_>,
-    ) -> TraitError<(bool, BoundRemovalOutcome, String, u32)> {
-        let mut try_working = config.working.clone();
-        let mut editor =
-            BoundEditor::<T>::new(config.target_ident, config.target_anchor, config.candidate);
-        editor.visit_file_mut(&mut try_working);
+        working: &mut syn::File,
+    ) -> TraitError<TrialResult> {
+        let mut editor = BoundEditor::<T>::new_group(config.target, config.group);
+        editor.visit_file_mut(working);
+        let miss = editor.take_miss();
         if !editor.modified() {
-            return Ok((
-                false,
-                BoundRemovalOutcome::Skipped,
-                config.current_src.to_owned(),
-                config.current_hash,
-            ));
+            return Ok(TrialResult {
+                miss,
+                ..config.unchanged()
+            });
         }
 
-        let updated_src = prettyplease::unparse(&try_working);
+        let updated_src = with_header_of(config.current_src, &prettyplease::unparse(working))?;
         let updated_hash = hash_bytes(&updated_src);
 
         if updated_hash == config.current_hash {
-            return Ok((
-                false,
-                BoundRemovalOutcome::Skipped,
-                config.current_src.to_owned(),
-                config.current_hash,
-            ));
-        }
-
-        fs::write(config.file_path, &updated_src)
-            .with_context(|| format!("writing updated {}", config.file_path.display()))?;
-        let check = CargoCheck::run_cargo_check(config.crate_root, config.cargo_check_config)?;
-
-        if check.status.success() {
-            Ok((
-                true,
-                BoundRemovalOutcome::Removed { check },
-                updated_src,
-                updated_hash,
-            ))
+            editor.undo(working);
+            return Ok(TrialResult {
+                miss,
+                ..config.unchanged()
+            });
+        }
+
+        let ctx = config.ctx;
+        let write_started = Instant::now();
+        ctx.store.write(config.file_path, &updated_src)?;
+        let mut write_time = write_started.elapsed();
+        let check = match ctx
+            .validator
+            .validate_cancellable(ctx.crate_root, ctx.cancel)
+        {
+            Ok(check) => check,
+            Err(e) => {
+                // The run stops; leave the tree as it was rather than for `recover`.
+                ctx.store.revert(config.file_path, config.current_src)?;
+                return Err(e);
+            }
+        };
+        // A trial interrupted by cancellation is undone whatever the validator said.
+        let cancelled = ctx.cancel.is_cancelled();
+
+        if check.success && !cancelled {
+            ctx.store.keep(config.file_path)?;
+            Ok(TrialResult {
+                accepted: Some((updated_src, updated_hash)),
+                cancelled,
+                outcome: BoundRemovalOutcome::Removed { check },
+                write_time,
+                miss,
+            })
+        } else {
+            let revert_started = Instant::now();
+            ctx.store.revert(config.file_path, config.current_src)?;
+            write_time += revert_started.elapsed();
+            editor.undo(working);
+            Ok(TrialResult {
+                accepted: None,
+                cancelled,
+                outcome: BoundRemovalOutcome::Retained { check },
+                write_time,
+                miss,
+            })
+        }
+    }
+}
+/// Report the trial that removed `members` together: each gets its outcome, and its cost is
+/// booked once, on the first. `evident` is the proof the prefilter skipped them on;
+/// `memoized`, whether the outcome came from the [`RejectionMemo`].
+#[allow(clippy::too_many_arguments)]
+fn record_group(
+    ctx: &PruneContext<'_>,
+    outcomes: &mut Vec<BoundRemovalResult>,
+    observer: &mut dyn Observer,
+    item_key: &ItemKey<'_>,
+    members: &[BoundCandidate],
+    trial: &TrialResult,
+    evident: Option<&str>,
+    memoized: bool,
+) {
+    let (before, after) = Removal::generics_before_after(item_key.item(), members);
+    let check_time = match &trial.outcome {
+        BoundRemovalOutcome::Removed { check } | BoundRemovalOutcome::Retained { check } => {
+            check.elapsed
+        }
+        BoundRemovalOutcome::Skipped => Duration::ZERO,
+    };
+    for (n, candidate) in members.iter().enumerate() {
+        let (check_time, write_time) = if n == 0 {
+            (check_time, trial.write_time)
         } else {
-            fs::write(config.file_path, config.current_src)
-                .with_context(|| format!("reverting {}", config.file_path.display()))?;
-            Ok((
-                false,
-                BoundRemovalOutcome::Retained { check },
-                config.current_src.to_owned(),
-                config.current_hash,
-            ))
+            (Duration::ZERO, Duration::ZERO)
+        };
+        let mut finding = ctx.finding(item_key, candidate);
+        finding.statically_used = evident.map(str::to_owned);
+        finding.memoized = memoized;
+        finding.anchor_miss = trial.miss.clone();
+        outcomes.push(BoundRemovalResult {
+            candidate: candidate.clone(),
+            outcome: trial.outcome.clone(),
+            finding,
+            before: before.clone(),
+            after: after.clone(),
+            check_time,
+            write_time,
+            batch: None,
+        });
+        if let Some(result) = outcomes.last() {
+            observer.on_outcome(result);
         }
     }
 }
+
 /// A trait for items that can be pruned.
 pub struct PruneItem;
 
@@ -220,53 +872,92 @@ macro_rules! make_pruner {
             impl PruneItem {
                 #[allow(missing_docs, reason = "macro-generated")]
                 pub fn $name(
-                    file_path: &std::path::Path,
-                    crate_root: &std::path::Path,
+                    file_path: &Path,
                     syntax: &mut syn::File,
                     bounds: &mut Vec<$bounds_ty>,
-                    cargo_check_config: &CargoCheckConfig,
+                    ctx: &PruneContext<'_>,
+                    observer: &mut dyn Observer,
                 ) -> crate::error::TraitError<Vec<BoundRemovalResult>> {
-                    let original_src = fs::read_to_string(file_path)
-                        .with_context(|| format!("reading {}", file_path.display()))?;
+                    let original_src = ctx.store.read(file_path)?;
                     let original_hash = hash_bytes(&original_src);
                     let mut outcomes = Vec::new();
-                    let mut working = syntax.clone();
-                    let mut current_src = original_src.clone();
+                    // Trials edit the tree in place and undo what is rejected.
+                    let working = syntax;
+                    let mut current_src = original_src;
                     let mut current_hash = original_hash;
                     let i = 0;
 
                     while i < bounds.len() {
                         let bounds_item = &bounds[i];
                         let item_key = bounds_item.item_key();
-                        let target_ident = item_key.ident();
-                        let target_anchor = item_key.span();
+                        let target = Anchor::of(item_key);
 
-                        let candidates: Vec<BoundCandidate> = ($collect)(bounds_item);
+                        let candidates = ctx.screen(item_key, ($collect)(bounds_item), &mut outcomes, observer);
                         let mut removed_any = false;
 
-                        for candidate in &candidates {
-                            let config = CandidateTrialConfig {
+                        let label = item_key.to_string();
+                        let label = label.trim_start_matches("// ");
+                        let scope = item_key.scope();
+                        let groups = BoundCandidate::group_duplicates(candidates);
+                        for (index, group) in groups.iter().enumerate() {
+                            if ctx.cancel.is_cancelled() {
+                                return Ok(outcomes);
+                            }
+                            observer.on_candidate_trial(
+                                label,
+                                &group[0].bound.to_token_stream().to_string(),
+                                index,
+                                groups.len(),
+                            );
+                            let trial_of = |members| CandidateTrialConfig {
                                 file_path,
-                                crate_root,
-                                working: &working,
-                                target_ident,
-                                target_anchor,
-                                candidate,
+                                ctx,
+                                target: &target,
+                                group: members,
                                 current_src: &current_src,
                                 current_hash,
-                                cargo_check_config,
                             };
-                            let (accepted, outcome, new_src, new_hash) = CandidateTrialConfig::try_candidate_once::<$item_ty>(config)?;
-                            outcomes.push(BoundRemovalResult { candidate: candidate.clone(), outcome });
-
-                            if accepted {
-                                let mut tmp = working.clone();
-                                let mut editor =
-                                    BoundEditor::<$item_ty>::new(target_ident, target_anchor, candidate);
-                                editor.visit_file_mut(&mut tmp);
-                                debug_assert!(editor.modified());
-                                working = tmp;
-                                *syntax = working.clone();
+                            let evident = ctx.evident(item_key, &group[0]);
+                            let memoized = evident.is_none()
+                                && ctx.memo.is_some_and(|m| m.borrow().rejected(&scope, &group[0]));
+                            let mut removed: &[BoundCandidate] = group;
+                            let mut trial = if evident.is_some() {
+                                // The check could only fail.
+                                trial_of(removed).unchanged()
+                            } else if memoized {
+                                trial_of(removed).memoized()
+                            } else {
+                                CandidateTrialConfig::try_candidate_once::<$item_ty>(trial_of(removed), working)?
+                            };
+                            if trial.cancelled {
+                                // Reverted and undecided: not reported.
+                                return Ok(outcomes);
+                            }
+                            if let Some(memo) = ctx.memo
+                                && !memoized
+                                && matches!(trial.outcome, BoundRemovalOutcome::Retained { .. })
+                            {
+                                memo.borrow_mut().reject(&scope, &group[0]);
+                            }
+                            record_group(ctx, &mut outcomes, observer, item_key, removed, &trial, evident.as_deref(), memoized);
+                            // A bound the item needs may still be declared more often than it
+                            // needs: keep the first declaration and try dropping the rest.
+                            if trial.accepted.is_none()
+                                && group.len() > 1
+                                && (evident.is_some()
+                                    || !matches!(trial.outcome, BoundRemovalOutcome::Skipped))
+                            {
+                                removed = &group[1..];
+                                trial = CandidateTrialConfig::try_candidate_once::<$item_ty>(trial_of(removed), working)?;
+                                if trial.cancelled {
+                                    return Ok(outcomes);
+                                }
+                                record_group(ctx, &mut outcomes, observer, item_key, removed, &trial, None, false);
+                            }
+                            if let Some((new_src, new_hash)) = trial.accepted {
+                                if let Some(memo) = ctx.memo {
+                                    memo.borrow_mut().invalidate(&scope);
+                                }
                                 current_src = new_src;
                                 current_hash = new_hash;
                                 removed_any = true;
@@ -310,3 +1001,850 @@ make_pruner! {
     name: prune_impl_method_bounds, item_ty: syn::ImplItemFn, bounds_ty: crate::analysis::ImplMethodBounds<'_>,
     collect_candidates: |b: &crate::analysis::ImplMethodBounds<'_>| { BoundCandidate::collect_impl_method_candidates(b) };
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::analysis::{ItemBounds, UTF8_BOM};
+    use crate::dynamic_analysis::common::ValidationOutcome;
+    use crate::dynamic_analysis::store::MemoryStore;
+    use crate::dynamic_analysis::validate::{AlwaysOk, ScriptedValidator};
+    use crate::error::WinnowerError;
+    use crate::observer::NoopObserver;
+    use crate::report::{Removal, RemovalStatus, SiteKind};
+    use std::cell::RefCell;
+
+    const PATH: &str = "src/lib.rs";
+
+    /// Prune the functions of the file at [`PATH`] in `store` with `validator`; returns the
+    /// status of every trial.
+    fn prune_store(
+        store: &MemoryStore,
+        validator: &dyn Validator,
+    ) -> TraitError<Vec<RemovalStatus>> {
+        Ok(prune_removals(store, validator)?
+            .iter()
+            .map(|r| r.status)
+            .collect())
+    }
+
+    /// Like [`prune_store`], returning every trial.
+    fn prune_removals(store: &MemoryStore, validator: &dyn Validator) -> TraitError<Vec<Removal>> {
+        prune_removals_with(store, validator, false)
+    }
+
+    /// Like [`prune_removals`], with the prefilter on or off.
+    fn prune_removals_with(
+        store: &MemoryStore,
+        validator: &dyn Validator,
+        prefilter: bool,
+    ) -> TraitError<Vec<Removal>> {
+        let src = store.read(Path::new(PATH))?;
+        let file = syn::parse_file(&src)?;
+        let mut items = ItemBounds::collect_items_in_file(&file)?;
+        let ctx = PruneContext {
+            crate_root: Path::new("."),
+            validator,
+            cancel: &CancelToken::new(),
+            store,
+            allow_dyn_compat_changes: false,
+            allow_unsafe_impl_pruning: false,
+            include_opaque_types: false,
+            only_traits: &[],
+            skip_derived_types: false,
+            skip_blanket_impls: false,
+            trait_index: None,
+            foreign_trait_impls: ForeignTraitImpls::Allow,
+            cargo_check: None,
+            prefilter,
+            references: None,
+            min_weight: 0,
+            memo: None,
+        };
+        let results = PruneItem::prune_function_bounds(
+            Path::new(PATH),
+            &mut file.clone(),
+            items.fns_mut(),
+            &ctx,
+            &mut NoopObserver,
+        )?;
+        Ok(results.iter().map(Removal::from_result).collect())
+    }
+
+    /// Prune the functions of `src` with `validator`; returns the final file text and the
+    /// status of every trial.
+    fn prune_fns(src: &str, validator: &dyn Validator) -> TraitError<(String, Vec<RemovalStatus>)> {
+        let store = MemoryStore::new().with_file(PATH, src);
+        let statuses = prune_store(&store, validator)?;
+        Ok((store.get(Path::new(PATH)).unwrap_or_default(), statuses))
+    }
+
+    /// Accepts while the file under validation still contains `required`; records every
+    /// text it was asked about.
+    struct RequiresText<'a> {
+        store: &'a MemoryStore,
+        required: &'a str,
+        seen: RefCell<Vec<String>>,
+    }
+
+    impl Validator for RequiresText<'_> {
+        fn validate(&self, _root: &Path) -> TraitError<ValidationOutcome> {
+            let text = self.store.get(Path::new(PATH)).unwrap_or_default();
+            let success = text.contains(self.required);
+            self.seen.borrow_mut().push(text);
+            Ok(ValidationOutcome {
+                success,
+                ..ValidationOutcome::default()
+            })
+        }
+    }
+
+    #[test]
+    fn rejected_removals_are_reverted_byte_for_byte() -> TraitError<()> {
+        let src = "fn f<T: Clone + Send>(t: T)   {}\n";
+        let validator = ScriptedValidator::new([]);
+        let store = MemoryStore::new().with_file(PATH, src);
+        let statuses = prune_store(&store, &validator)?;
+        assert_eq!(store.get(Path::new(PATH)).as_deref(), Some(src));
+        assert_eq!(statuses, [RemovalStatus::Retained, RemovalStatus::Retained]);
+        assert_eq!(validator.calls(), 2);
+        assert_eq!((store.writes(), store.reverts()), (2, 2));
+        Ok(())
+    }
+
+    #[test]
+    fn accepted_removal_is_kept() -> TraitError<()> {
+        let validator = ScriptedValidator::new([false, true]);
+        let (out, statuses) = prune_fns("fn f<T: Clone + Send>(t: T) {}\n", &validator)?;
+        assert!(out.contains("T: Clone"), "{out}");
+        assert!(!out.contains("Send"), "{out}");
+        assert_eq!(
+            statuses[..2],
+            [RemovalStatus::Retained, RemovalStatus::Removed]
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn accepted_removal_is_written_once_and_never_reverted() -> TraitError<()> {
+        let validator = ScriptedValidator::new([true]);
+        let store = MemoryStore::new().with_file(PATH, "fn f<T: Clone>(t: T) {}\n");
+        let statuses = prune_store(&store, &validator)?;
+        assert_eq!(
+            store.get(Path::new(PATH)).as_deref(),
+            Some("fn f<T>(t: T) {}\n")
+        );
+        assert_eq!(statuses[0], RemovalStatus::Removed);
+        assert_eq!((store.writes(), store.reverts()), (1, 0));
+        Ok(())
+    }
+
+    #[test]
+    fn candidates_that_no_longer_apply_are_skipped_without_a_write() -> TraitError<()> {
+        let validator = ScriptedValidator::new([true]);
+        let store = MemoryStore::new().with_file(PATH, "fn f<T: Clone>(t: T) {}\n");
+        let statuses = prune_store(&store, &validator)?;
+        // The item is tried again after the removal; its only bound is gone by then.
+        assert_eq!(statuses, [RemovalStatus::Removed, RemovalStatus::Skipped]);
+        assert_eq!(validator.calls(), 1);
+        assert_eq!(store.writes(), 1);
+        Ok(())
+    }
+
+    #[test]
+    fn later_trials_build_on_accepted_removals() -> TraitError<()> {
+        let store = MemoryStore::new().with_file(PATH, "fn f<T: Clone + Send>(t: T) {}\n");
+        let validator = RequiresText {
+            store: &store,
+            required: "Clone",
+            seen: RefCell::new(Vec::new()),
+        };
+        prune_store(&store, &validator)?;
+        assert_eq!(
+            store.get(Path::new(PATH)).as_deref(),
+            Some("fn f<T: Clone>(t: T) {}\n")
+        );
+        let seen = validator.seen.borrow();
+        // Clone is rejected, Send accepted; every later trial starts without Send.
+        let accepted = seen
+            .iter()
+            .position(|t| t.contains("Clone"))
+            .expect("an accepted trial");
+        assert!(
+            seen[..accepted].iter().all(|t| t.contains("Send")),
+            "{seen:?}"
+        );
+        assert!(
+            seen[accepted..].iter().all(|t| !t.contains("Send")),
+            "{seen:?}"
+        );
+        assert!(seen.len() > accepted + 1, "{seen:?}");
+        Ok(())
+    }
+
+    #[test]
+    fn write_failure_stops_the_run_and_keeps_accepted_state() -> TraitError<()> {
+        let store = MemoryStore::new()
+            .with_file(PATH, "fn f<T: Clone>(t: T) {}\nfn g<U: Copy>(u: U) {}\n")
+            .failing_write(2);
+        let err = prune_store(&store, &AlwaysOk).expect_err("second write fails");
+        assert!(
+            matches!(
+                err,
+                WinnowerError::Io {
+                    action: "writing updated",
+                    ..
+                }
+            ),
+            "{err:?}"
+        );
+        let out = store.get(Path::new(PATH)).unwrap_or_default();
+        assert!(!out.contains("Clone"), "{out}");
+        assert!(out.contains("U: Copy"), "{out}");
+        assert_eq!(store.reverts(), 0);
+        Ok(())
+    }
+
+    #[test]
+    fn always_ok_removes_type_param_and_where_bounds() -> TraitError<()> {
+        let (out, _) = prune_fns(
+            "fn f<T: Clone>(t: T) where T: Send {}\nfn g<U: Copy>() {}\n",
+            &AlwaysOk,
+        )?;
+        syn::parse_file(&out)?;
+        for bound in ["Clone", "Send", "Copy"] {
+            assert!(!out.contains(bound), "{bound} left in {out}");
+        }
+        Ok(())
+    }
+
+    #[test]
+    fn removals_keep_a_leading_bom() -> TraitError<()> {
+        let (out, statuses) = prune_fns("\u{feff}fn f<T: Clone>(t: T) {}\n", &AlwaysOk)?;
+        assert!(out.starts_with(UTF8_BOM), "{out:?}");
+        assert!(!out.contains("Clone"));
+        assert_eq!(statuses[0], RemovalStatus::Removed);
+        Ok(())
+    }
+
+    #[test]
+    fn removals_keep_the_shebang_and_inner_attributes() -> TraitError<()> {
+        let header = "#!/usr/bin/env run-cargo-script\n\
+                      //! Crate docs,   spaced as written.\n\
+                      #![allow(dead_code,unused)]\n\n\
+                      // A comment between attributes.\n\
+                      #![doc = include_str!(\"../README.md\")]";
+        for body in [
+            "\n\nfn f<T: Clone>(t: T) {}\nfn g() {}\n",
+            "\nmod a {\n    mod b {\n        fn deep<T: Clone>(t: T) {}\n    }\n}\n",
+        ] {
+            let (out, statuses) = prune_fns(&format!("{header}{body}"), &AlwaysOk)?;
+            assert_eq!(statuses[0], RemovalStatus::Removed);
+            assert!(out.starts_with(header), "{out}");
+            assert!(!out[header.len()..].contains("#!"), "{out}");
+            assert!(!out.contains("Clone"), "{out}");
+        }
+        // `#![...]` on the first line is an attribute, not a shebang.
+        let (out, _) = prune_fns("#![allow(unused)]\nfn f<T: Clone>(t: T) {}\n", &AlwaysOk)?;
+        assert!(out.starts_with("#![allow(unused)]\n"), "{out}");
+        assert!(!out.contains("Clone"), "{out}");
+        Ok(())
+    }
+
+    #[test]
+    fn later_items_are_tried_after_a_rejection() -> TraitError<()> {
+        let validator = ScriptedValidator::new([false, true]);
+        let (out, statuses) = prune_fns(
+            "fn f<T: Clone>(t: T) {}\nfn g<U: Copy>(u: U) {}\n",
+            &validator,
+        )?;
+        assert!(out.contains("T: Clone"), "{out}");
+        assert!(!out.contains("Copy"), "{out}");
+        assert_eq!(
+            statuses[..2],
+            [RemovalStatus::Retained, RemovalStatus::Removed]
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn items_without_bounds_never_validate() -> TraitError<()> {
+        let validator = ScriptedValidator::new([]);
+        let store = MemoryStore::new().with_file(PATH, "fn f<T>(t: T) {}\n");
+        let statuses = prune_store(&store, &validator)?;
+        assert_eq!(
+            store.get(Path::new(PATH)).as_deref(),
+            Some("fn f<T>(t: T) {}\n")
+        );
+        assert!(statuses.is_empty());
+        assert_eq!(validator.calls(), 0);
+        assert_eq!(store.writes(), 0);
+        Ok(())
+    }
+
+    /// `where_used_default` from the sandbox, with the bounds repeated on the parameter.
+    const DUPLICATED: &str = "fn where_used_default<T: Default + Clone>(x: Option<T>) -> T\n\
+                              where\n    T: Default + Clone,\n{\n    x.unwrap_or_default()\n}\n";
+
+    #[test]
+    fn duplicated_bounds_are_removed_in_one_trial() -> TraitError<()> {
+        let store = MemoryStore::new().with_file(PATH, DUPLICATED);
+        let validator = RequiresText {
+            store: &store,
+            required: "Default",
+            seen: RefCell::new(Vec::new()),
+        };
+        let removals = prune_removals(&store, &validator)?;
+        let out = store.get(Path::new(PATH)).unwrap_or_default();
+        assert_eq!(
+            out,
+            "fn where_used_default<T: Default>(x: Option<T>) -> T {\n    x.unwrap_or_default()\n}\n"
+        );
+        // Both `Clone`s went in one check; no check saw just one of them.
+        let seen = validator.seen.borrow();
+        assert!(
+            seen.iter().all(|t| t.matches("Clone").count() != 1),
+            "{seen:?}"
+        );
+
+        let mut metrics = crate::report::FileMetrics::default();
+        metrics.add_removals(&removals);
+        assert_eq!(metrics.checks, seen.len());
+        Ok(())
+    }
+
+    #[test]
+    fn a_needed_bound_keeps_one_declaration() -> TraitError<()> {
+        let store = MemoryStore::new().with_file(PATH, DUPLICATED);
+        let validator = RequiresText {
+            store: &store,
+            required: "Default",
+            seen: RefCell::new(Vec::new()),
+        };
+        let removals = prune_removals(&store, &validator)?;
+        let default: Vec<_> = removals
+            .iter()
+            .filter(|r| r.finding.bound == "Default" && r.status != RemovalStatus::Skipped)
+            .map(|r| (r.finding.candidate.site.kind(), r.status))
+            .collect();
+        // Together they are needed; the where clause copy alone is not.
+        assert_eq!(
+            &default[..3],
+            [
+                (SiteKind::TypeParam, RemovalStatus::Retained),
+                (SiteKind::WhereClause, RemovalStatus::Retained),
+                (SiteKind::WhereClause, RemovalStatus::Removed),
+            ]
+        );
+
+        let report =
+            crate::report::FileReport::new(PATH.into(), Vec::new(), removals, Default::default());
+        let clone = report
+            .removals
+            .iter()
+            .find(|r| r.finding.bound == "Clone")
+            .expect("Clone was tried");
+        assert_eq!(clone.finding.duplicates, ["where T: Clone"]);
+        Ok(())
+    }
+
+    #[test]
+    fn an_impl_and_its_method_on_one_line_are_told_apart() -> TraitError<()> {
+        let src = "impl<T: Clone> S<T> { fn f<U: Debug>(&self) {} }\n";
+        let original = syn::parse_file(src)?;
+        let items = ItemBounds::collect_items_in_file(&original)?;
+        let (imp, method) = (&items.impls()[0], &items.impl_methods()[0]);
+        let impl_bound = &BoundCandidate::collect_impl_candidates(imp)[0];
+        let method_bound = &BoundCandidate::collect_impl_method_candidates(method)[0];
+
+        // A second parse: byte ranges differ, and anchors match by path.
+        let mut file = syn::parse_file(src)?;
+        let (impl_anchor, method_anchor) =
+            (Anchor::of(imp.item_key()), Anchor::of(method.item_key()));
+        let method_of_impl = Anchor {
+            kind: ItemKind::ImplMethod,
+            ..impl_anchor.clone()
+        };
+        let mut wrong = BoundEditor::<syn::ImplItemFn>::new(&method_of_impl, impl_bound);
+        wrong.visit_file_mut(&mut file);
+        assert!(!wrong.modified());
+
+        let mut editor = BoundEditor::<syn::ItemImpl>::new(&impl_anchor, impl_bound);
+        editor.visit_file_mut(&mut file);
+        assert!(editor.modified());
+        let mut editor = BoundEditor::<syn::ImplItemFn>::new(&method_anchor, method_bound);
+        editor.visit_file_mut(&mut file);
+        assert!(editor.modified());
+        assert_eq!(
+            file.to_token_stream().to_string(),
+            "impl < T > S < T > { fn f < U > (& self) { } }"
+        );
+        Ok(())
+    }
+
+    /// Multibyte text before items, inside a generic parameter list and in an item of the
+    /// same name in a module.
+    const MULTIBYTE: &str = "\
+// 日本語のコメント: 前にある多バイト文字
+const GREETING: &str = \"héllo 🦀 wörld\";
+
+pub fn pick<T: Clone /* 複製 */ + Send>(t: T) -> T {
+    t
+}
+
+pub struct Pair<A: Clone + Send, const SEP: char = '→'>(A);
+
+pub mod inner {
+    /// «Ünïcödé» 📦
+    pub fn pick<T: Clone + Send>(u: T) -> T {
+        u
+    }
+}
+";
+
+    #[test]
+    fn multibyte_text_does_not_move_anchors() -> TraitError<()> {
+        let original = syn::parse_file(MULTIBYTE)?;
+        let items = ItemBounds::collect_items_in_file(&original)?;
+        let sends: Vec<_> = items
+            .candidates()
+            .filter(|(_, c)| c.bound.to_token_stream().to_string() == "Send")
+            .map(|(key, c)| (Anchor::of(key), c))
+            .collect();
+        assert_eq!(sends.len(), 3);
+        // The same text parsed again, and the text as a trial writes it, without comments.
+        let rewritten = prettyplease::unparse(&original);
+        for text in [MULTIBYTE, rewritten.as_str()] {
+            for (anchor, send) in &sends {
+                let mut file = syn::parse_file(text)?;
+                let mut editor = BoundEditor::<syn::ItemFn>::new(anchor, send);
+                editor.visit_file_mut(&mut file);
+                assert!(editor.modified(), "{anchor:?}");
+                let out = prettyplease::unparse(&file);
+                assert_eq!(out.matches("Send").count(), 2, "{out}");
+                let gone = match anchor.path[..] {
+                    [1] => "pub fn pick<T: Clone>(t: T)",
+                    [2] => "pub struct Pair<A: Clone, const SEP: char = '→'>(A);",
+                    [3, 0] => "pub fn pick<T: Clone>(u: T)",
+                    _ => panic!("{anchor:?}"),
+                };
+                assert!(out.contains(gone), "{out}");
+            }
+        }
+        Ok(())
+    }
+
+    #[test]
+    fn removals_after_multibyte_text_land_on_their_item() -> TraitError<()> {
+        let store = MemoryStore::new().with_file(PATH, MULTIBYTE);
+        // Only the bound of the module's `pick` is needed.
+        let validator = RequiresText {
+            store: &store,
+            required: "Send>(u: T)",
+            seen: RefCell::new(Vec::new()),
+        };
+        prune_store(&store, &validator)?;
+        let out = store.get(Path::new(PATH)).unwrap_or_default();
+        assert!(out.contains("pub fn pick<T>(t: T)"), "{out}");
+        assert!(out.contains("pub fn pick<T: Send>(u: T)"), "{out}");
+        assert!(out.contains("héllo 🦀 wörld"), "{out}");
+        Ok(())
+    }
+
+    const ANCHORED: &str =
+        "mod a {\n    pub fn helper<T: Clone>(t: T) {}\n}\nfn helper<T: Send>() {}\n";
+
+    /// The anchor and candidate of `a::helper` in [`ANCHORED`].
+    fn anchored_helper(original: &syn::File) -> TraitError<(Anchor, BoundCandidate)> {
+        let items = ItemBounds::collect_items_in_file(original)?;
+        let (key, clone) = items.candidates().next().expect("a::helper has a bound");
+        assert_eq!(key.module_path(), "a");
+        Ok((Anchor::of(key), clone))
+    }
+
+    #[test]
+    fn a_drifted_anchor_is_found_by_module_path_kind_name_and_ordinal() -> TraitError<()> {
+        let original = syn::parse_file(ANCHORED)?;
+        let (anchor, clone) = anchored_helper(&original)?;
+        // Items inserted before it move its path and span.
+        let mut file = syn::parse_file(
+            "fn unrelated() {}\nmod a {\n    fn other() {}\n    pub fn helper<T: Clone>(t: T) {}\n}\n\
+             fn helper<T: Send>() {}\n",
+        )?;
+        let mut editor = BoundEditor::<syn::ItemFn>::new(&anchor, &clone);
+        editor.visit_file_mut(&mut file);
+        assert!(editor.modified());
+        let miss = editor.take_miss().expect("the anchor missed");
+        assert!(miss.recovered);
+        assert_eq!(
+            miss.wanted,
+            "fn `helper` in `a` at [0, 0] (2:12), #0 of its name there"
+        );
+        assert_eq!(
+            miss.near,
+            [
+                "fn `helper` in `a` at [1, 1] (4:12)",
+                "fn `helper` at file level at [2] (6:4)"
+            ]
+        );
+        let out = prettyplease::unparse(&file);
+        assert!(out.contains("pub fn helper<T>(t: T)"), "{out}");
+        assert!(out.contains("fn helper<T: Send>()"), "{out}");
+
+        // An anchor that matches needs no fallback.
+        let mut file = syn::parse_file(ANCHORED)?;
+        let mut editor = BoundEditor::<syn::ItemFn>::new(&anchor, &clone);
+        editor.visit_file_mut(&mut file);
+        assert!(editor.modified());
+        assert_eq!(editor.take_miss(), None);
+        Ok(())
+    }
+
+    #[test]
+    fn a_parameter_index_naming_another_parameter_is_a_hard_miss() -> TraitError<()> {
+        let original = syn::parse_file(ANCHORED)?;
+        let (anchor, clone) = anchored_helper(&original)?;
+        // The item is found, but `T` is no longer its first parameter.
+        let src = "mod a {\n    pub fn helper<U, T: Clone>(t: T) {}\n}\nfn helper<T: Send>() {}\n";
+        let mut file = syn::parse_file(src)?;
+        let mut editor = BoundEditor::<syn::ItemFn>::new(&anchor, &clone);
+        editor.visit_file_mut(&mut file);
+        assert!(!editor.modified());
+        let miss = editor.take_miss().expect("the parameter missed");
+        assert!(!miss.recovered);
+        assert_eq!(
+            miss.wanted,
+            "type parameter `T` at generic parameter 0 of fn `helper` in `a` at [0, 0] (2:12), \
+             #0 of its name there"
+        );
+        assert_eq!(miss.near, ["`U`"]);
+        assert_eq!(
+            prettyplease::unparse(&file),
+            prettyplease::unparse(&syn::parse_file(src)?)
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn an_item_found_neither_way_is_a_hard_miss_naming_its_neighbours() -> TraitError<()> {
+        let original = syn::parse_file(ANCHORED)?;
+        let (anchor, clone) = anchored_helper(&original)?;
+        // Renamed: same position, another name, and the same name in another module.
+        let src = "mod a {\n    pub fn helpers<T: Clone>(t: T) {}\n}\nfn helper<T: Send>() {}\n";
+        let mut file = syn::parse_file(src)?;
+        let mut editor = BoundEditor::<syn::ItemFn>::new(&anchor, &clone);
+        editor.visit_file_mut(&mut file);
+        assert!(!editor.modified());
+        let miss = editor.take_miss().expect("the anchor missed");
+        assert!(!miss.recovered);
+        assert_eq!(
+            miss.near,
+            [
+                "fn `helpers` in `a` at [0, 0] (2:12)",
+                "fn `helper` at file level at [1] (4:4)"
+            ]
+        );
+        assert!(
+            miss.to_string()
+                .contains("not found by module path, kind, name and ordinal either"),
+            "{miss}"
+        );
+        assert_eq!(
+            prettyplease::unparse(&file),
+            prettyplease::unparse(&syn::parse_file(src)?)
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn anchor_misses_are_recorded_on_findings_and_counted() -> TraitError<()> {
+        let original = syn::parse_file(
+            "mod a {\n    pub fn helper<T: Clone>(t: T) {}\n}\nfn gone<T: Send>() {}\n",
+        )?;
+        let mut items = ItemBounds::collect_items_in_file(&original)?;
+        // The file as the trials find it: `helper` moved, `gone` gone.
+        let drifted = "fn unrelated() {}\nmod a {\n    pub fn helper<T: Clone>(t: T) {}\n}\n";
+        let store = MemoryStore::new().with_file(PATH, drifted);
+        let ctx = PruneContext {
+            crate_root: Path::new("."),
+            validator: &AlwaysOk,
+            cancel: &CancelToken::new(),
+            store: &store,
+            allow_dyn_compat_changes: false,
+            allow_unsafe_impl_pruning: false,
+            include_opaque_types: false,
+            only_traits: &[],
+            skip_derived_types: false,
+            skip_blanket_impls: false,
+            trait_index: None,
+            foreign_trait_impls: ForeignTraitImpls::Allow,
+            cargo_check: None,
+            prefilter: false,
+            references: None,
+            min_weight: 0,
+            memo: None,
+        };
+        let results = PruneItem::prune_function_bounds(
+            Path::new(PATH),
+            &mut syn::parse_file(drifted)?,
+            items.fns_mut(),
+            &ctx,
+            &mut NoopObserver,
+        )?;
+        let removals: Vec<_> = results.iter().map(Removal::from_result).collect();
+        let outcomes: Vec<_> = removals
+            .iter()
+            .map(|r| {
+                let miss = r.finding.anchor_miss.as_ref();
+                (
+                    r.finding.bound.as_str(),
+                    r.status,
+                    miss.map(|m| m.recovered),
+                )
+            })
+            .collect();
+        assert_eq!(
+            outcomes[0],
+            ("Clone", RemovalStatus::Removed, Some(true)),
+            "{outcomes:?}"
+        );
+        assert_eq!(
+            outcomes.last(),
+            Some(&("Send", RemovalStatus::Skipped, Some(false)))
+        );
+        let out = store.get(Path::new(PATH)).unwrap_or_default();
+        assert!(out.contains("pub fn helper<T>(t: T)"), "{out}");
+
+        let mut metrics = crate::report::FileMetrics::default();
+        metrics.add_removals(&removals);
+        assert_eq!(
+            (metrics.anchors_recovered, metrics.anchor_misses),
+            (outcomes.len() - 1, 1)
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn evidently_used_bounds_are_skipped_without_a_check() -> TraitError<()> {
+        let src = "fn f<T: Clone + Send>(t: T) -> (T, T) {\n    (t.clone(), t)\n}\n";
+        let validator = ScriptedValidator::new([]);
+        let store = MemoryStore::new().with_file(PATH, src);
+        let removals = prune_removals_with(&store, &validator, true)?;
+        let statuses: Vec<_> = removals.iter().map(|r| r.status).collect();
+        assert_eq!(statuses, [RemovalStatus::Skipped, RemovalStatus::Retained]);
+        assert_eq!(
+            removals[0].finding.statically_used.as_deref(),
+            Some("`t.clone()` calls `Clone::clone`")
+        );
+        assert_eq!(removals[0].finding.tags(), ["statically used"]);
+        assert_eq!(validator.calls(), 1);
+        let mut metrics = crate::report::FileMetrics::default();
+        metrics.add_removals(&removals);
+        assert_eq!((metrics.checks, metrics.prefiltered), (1, 1));
+        Ok(())
+    }
+
+    #[test]
+    fn a_used_bound_still_loses_its_repeats() -> TraitError<()> {
+        let store = MemoryStore::new().with_file(
+            PATH,
+            "fn f<T: Clone>(t: T) -> T\nwhere\n    T: Clone,\n{\n    t.clone()\n}\n",
+        );
+        let removals = prune_removals_with(&store, &AlwaysOk, true)?;
+        assert_eq!(
+            store.get(Path::new(PATH)).as_deref(),
+            Some("fn f<T: Clone>(t: T) -> T {\n    t.clone()\n}\n")
+        );
+        let mut metrics = crate::report::FileMetrics::default();
+        metrics.add_removals(&removals);
+        // The item is tried again after the removal, and `Clone` skipped again.
+        assert_eq!((metrics.checks, metrics.prefiltered), (1, 2));
+        Ok(())
+    }
+
+    /// Prune the impls, then the impl methods, of the file at [`PATH`] in `store` with
+    /// `validator`, sharing `memo`.
+    fn prune_impls(
+        store: &MemoryStore,
+        validator: &dyn Validator,
+        memo: Option<&RefCell<RejectionMemo>>,
+    ) -> TraitError<Vec<Removal>> {
+        let src = store.read(Path::new(PATH))?;
+        let file = syn::parse_file(&src)?;
+        let mut items = ItemBounds::collect_items_in_file(&file)?;
+        let ctx = PruneContext {
+            crate_root: Path::new("."),
+            validator,
+            cancel: &CancelToken::new(),
+            store,
+            allow_dyn_compat_changes: false,
+            allow_unsafe_impl_pruning: false,
+            include_opaque_types: false,
+            only_traits: &[],
+            skip_derived_types: false,
+            skip_blanket_impls: false,
+            trait_index: None,
+            foreign_trait_impls: ForeignTraitImpls::Allow,
+            cargo_check: None,
+            prefilter: false,
+            references: None,
+            min_weight: 0,
+            memo,
+        };
+        let mut results = PruneItem::prune_impl_bounds(
+            Path::new(PATH),
+            &mut file.clone(),
+            items.impls_mut(),
+            &ctx,
+            &mut NoopObserver,
+        )?;
+        results.extend(PruneItem::prune_impl_method_bounds(
+            Path::new(PATH),
+            &mut file.clone(),
+            items.impl_methods_mut(),
+            &ctx,
+            &mut NoopObserver,
+        )?);
+        Ok(results.iter().map(Removal::from_result).collect())
+    }
+
+    const IMPL: &str = "struct S<T>(T);\n\
+                        impl<T: Clone> S<T> {\n    \
+                        fn one(&self) -> T {\n        self.0.clone()\n    }\n    \
+                        fn two(&self) -> T\n    where\n        T: Clone,\n    {\n        \
+                        self.0.clone()\n    }\n}\n";
+
+    #[test]
+    fn a_rejection_carries_over_to_the_rest_of_its_scope() -> TraitError<()> {
+        let validator = ScriptedValidator::new([]);
+        let store = MemoryStore::new().with_file(PATH, IMPL);
+        let memo = RefCell::new(RejectionMemo::default());
+        let removals = prune_impls(&store, &validator, Some(&memo))?;
+        let memoized: Vec<_> = removals
+            .iter()
+            .map(|r| (r.finding.item.as_str(), r.status, r.finding.memoized))
+            .collect();
+        assert_eq!(
+            memoized,
+            [
+                ("impl S < T >", RemovalStatus::Retained, false),
+                ("S < T >::two", RemovalStatus::Retained, true),
+            ]
+        );
+        assert_eq!(validator.calls(), 1);
+        let mut metrics = crate::report::FileMetrics::default();
+        metrics.add_removals(&removals);
+        assert_eq!((metrics.checks, metrics.memo_hits), (1, 1));
+
+        // Without the memo, the method's bound is checked on its own.
+        let validator = ScriptedValidator::new([false, true]);
+        let store = MemoryStore::new().with_file(PATH, IMPL);
+        prune_impls(&store, &validator, None)?;
+        assert_eq!(validator.calls(), 2);
+        let out = store.get(Path::new(PATH)).unwrap_or_default();
+        assert!(!out.contains("where"), "{out}");
+        Ok(())
+    }
+
+    #[test]
+    fn an_accepted_removal_clears_its_scope() -> TraitError<()> {
+        let file = syn::parse_file("fn f<T: Clone>(t: T) {}\nfn g<T: Clone>(t: T) {}")?;
+        let items = ItemBounds::collect_items_in_file(&file)?;
+        let (key, clone) = items.candidates().next().expect("a bound");
+        let mut memo = RejectionMemo::default();
+        memo.reject(&key.scope(), &clone);
+        assert!(memo.rejected("fn f", &clone));
+        assert!(!memo.rejected("fn g", &clone));
+        memo.invalidate("fn g");
+        assert!(memo.rejected("fn f", &clone));
+        memo.invalidate("fn f");
+        assert!(!memo.rejected("fn f", &clone));
+        Ok(())
+    }
+
+    /// `n` functions with a few bounds each, like a large generated module.
+    fn large_file(n: usize) -> String {
+        (0..n)
+            .map(|i| {
+                format!(
+                    "pub fn f{i}<T: Clone + Send, U: Default>(t: T, u: U) -> T\nwhere\n    \
+                     T: Sync,\n{{\n    let _ = u;\n    t\n}}\n\n"
+                )
+            })
+            .collect()
+    }
+
+    /// Remove each group of `src` the old way, from a copy of the tree, and the new way, in
+    /// place and undone; both must render the same, and the undone tree like the original.
+    fn assert_undo_matches_copies(src: &str) -> TraitError<()> {
+        let file = syn::parse_file(src)?;
+        let items = ItemBounds::collect_items_in_file(&file)?;
+        let pristine = prettyplease::unparse(&file);
+        let mut working = file.clone();
+        let candidates: Vec<_> = items.candidates().collect();
+        for item in candidates.chunk_by(|a, b| std::ptr::eq(a.0, b.0)) {
+            let key = item[0].0;
+            let group: Vec<_> = item.iter().map(|(_, c)| c.clone()).collect();
+            for group in BoundCandidate::group_duplicates(group) {
+                let anchor = Anchor::of(key);
+                let edit = || BoundEditor::<syn::ItemFn>::new_group(&anchor, &group);
+                let mut copy = file.clone();
+                edit().visit_file_mut(&mut copy);
+                let mut editor = edit();
+                editor.visit_file_mut(&mut working);
+                assert_eq!(
+                    prettyplease::unparse(&working),
+                    prettyplease::unparse(&copy),
+                    "{key}"
+                );
+                editor.undo(&mut working);
+                assert_eq!(prettyplease::unparse(&working), pristine, "{key}");
+            }
+        }
+        Ok(())
+    }
+
+    #[test]
+    fn undone_trials_leave_the_tree_as_a_copy_would() -> TraitError<()> {
+        for src in [
+            include_str!("../../tests/test_files/trait_sandbox/src/a.rs"),
+            include_str!("../../tests/test_files/trait_sandbox/src/b.rs"),
+            include_str!("../../tests/test_files/trait_sandbox/src/c.rs"),
+            include_str!("../../tests/test_files/trait_sandbox/src/traits.rs"),
+            IMPL,
+            &large_file(20),
+        ] {
+            assert_undo_matches_copies(src)?;
+        }
+        Ok(())
+    }
+
+    /// Compare a trial on a copy of the tree with one in place; run with `--ignored
+    /// --nocapture` to see the numbers.
+    #[test]
+    #[ignore = "timing"]
+    fn in_place_trials_beat_copies_on_a_large_file() -> TraitError<()> {
+        let file = syn::parse_file(&large_file(2000))?;
+        let items = ItemBounds::collect_items_in_file(&file)?;
+        let candidates: Vec<_> = items.candidates().take(80).collect();
+        let started = Instant::now();
+        for (key, candidate) in &candidates {
+            let mut copy = file.clone();
+            BoundEditor::<syn::ItemFn>::new(&Anchor::of(key), candidate).visit_file_mut(&mut copy);
+            std::hint::black_box(&copy);
+        }
+        let copies = started.elapsed();
+        let mut working = file.clone();
+        let started = Instant::now();
+        for (key, candidate) in &candidates {
+            let anchor = Anchor::of(key);
+            let mut editor = BoundEditor::<syn::ItemFn>::new(&anchor, candidate);
+            editor.visit_file_mut(&mut working);
+            editor.undo(&mut working);
+        }
+        let in_place = started.elapsed();
+        eprintln!("80 trials on 2000 functions: copies {copies:?}, in place {in_place:?}");
+        assert!(in_place < copies);
+        Ok(())
+    }
+}
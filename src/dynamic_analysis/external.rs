@@ -0,0 +1,143 @@
+// src/dynamic_analysis/external.rs
+//! Files someone else edits while `prune` runs.
+//!
+//! `prune` stamps every file it will try with its CRC32 and modification time when the run
+//! starts ([`FileStamp`]). Just before a file's first trial, the file is compared with its
+//! stamp: if an editor, formatter or another tool wrote it in the meantime, writing the
+//! trials would lose those edits, so the file is left alone and reported as an
+//! [`ExternalEdit`]. The run cache does not record it, so the next `--incremental` run tries
+//! it again. With `respect_editor_locks`, a file an editor has open with unsaved changes,
+//! going by its swap, lock or backup file (see [`editor_lock`]), is left alone too.
+
+#![deny(missing_docs)]
+
+use crate::error::{TraitError, WinnowerError};
+use serde::{Deserialize, Serialize};
+use std::path::{Path, PathBuf};
+use std::time::SystemTime;
+
+/// A file as the run found it.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct FileStamp {
+    /// CRC32 of the file's text.
+    pub hash: u32,
+    /// Modification time, where the platform reports one.
+    pub modified: Option<SystemTime>,
+}
+
+impl FileStamp {
+    /// The stamp of `path` now.
+    pub fn of(path: &Path) -> TraitError<Self> {
+        let text = std::fs::read(path).map_err(|e| WinnowerError::io("reading", path, e))?;
+        Ok(Self {
+            hash: crc32fast::hash(&text),
+            modified: modified(path),
+        })
+    }
+}
+
+/// When `path` was last written, where the platform reports it.
+fn modified(path: &Path) -> Option<SystemTime> {
+    std::fs::metadata(path).and_then(|m| m.modified()).ok()
+}
+
+/// Why a file was left alone.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case", tag = "reason")]
+pub enum ExternalEditKind {
+    /// Its text or modification time changed since the run started.
+    Changed,
+    /// An editor's swap, lock or backup file lies next to it.
+    EditorLock {
+        /// The lock file.
+        lock: PathBuf,
+    },
+}
+
+/// A file `prune` left alone so as not to overwrite edits made outside the run.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ExternalEdit {
+    /// The file.
+    pub path: PathBuf,
+    /// Why.
+    #[serde(flatten)]
+    pub kind: ExternalEditKind,
+}
+
+impl std::fmt::Display for ExternalEdit {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match &self.kind {
+            ExternalEditKind::Changed => write!(
+                f,
+                "{} changed on disk since the run started; left alone so those edits are kept",
+                self.path.display()
+            ),
+            ExternalEditKind::EditorLock { lock } => write!(
+                f,
+                "{} looks open in an editor ({} exists); left alone",
+                self.path.display(),
+                lock.display()
+            ),
+        }
+    }
+}
+
+/// The editor file next to `path` that suggests it is open with unsaved changes, if any:
+/// vim's `.name.swp` and `.name.swo`, emacs's `.#name` lock and `name~` backup.
+pub fn editor_lock(path: &Path) -> Option<PathBuf> {
+    let dir = path.parent()?;
+    let name = path.file_name()?.to_string_lossy();
+    [
+        format!(".{name}.swp"),
+        format!(".{name}.swo"),
+        format!(".#{name}"),
+        format!("{name}~"),
+    ]
+    .into_iter()
+    .map(|lock| dir.join(lock))
+    // Emacs locks are dangling symlinks, so ask for the link itself.
+    .find(|lock| lock.symlink_metadata().is_ok())
+}
+
+/// Why `path`, whose text is now `text`, must be left alone given its `stamp` from the start
+/// of the run, if it must: it changed since, or with `respect_locks` an editor holds it.
+pub fn external_edit(
+    path: &Path,
+    stamp: &FileStamp,
+    text: &str,
+    respect_locks: bool,
+) -> Option<ExternalEdit> {
+    let kind = if crc32fast::hash(text.as_bytes()) != stamp.hash || modified(path) != stamp.modified
+    {
+        ExternalEditKind::Changed
+    } else if respect_locks && let Some(lock) = editor_lock(path) {
+        ExternalEditKind::EditorLock { lock }
+    } else {
+        return None;
+    };
+    Some(ExternalEdit {
+        path: path.to_path_buf(),
+        kind,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn editor_files_next_to_a_source_count_as_locks() {
+        let tmp = tempfile::tempdir().unwrap();
+        let src = tmp.path().join("lib.rs");
+        std::fs::write(&src, "").unwrap();
+        assert_eq!(editor_lock(&src), None);
+        for lock in [".lib.rs.swp", ".#lib.rs", "lib.rs~"] {
+            std::fs::write(tmp.path().join(lock), "").unwrap();
+            assert_eq!(editor_lock(&src), Some(tmp.path().join(lock)));
+            std::fs::remove_file(tmp.path().join(lock)).unwrap();
+        }
+        // Another file's swap file is not this one's.
+        std::fs::write(tmp.path().join(".main.rs.swp"), "").unwrap();
+        assert_eq!(editor_lock(&src), None);
+    }
+}
@@ -0,0 +1,149 @@
+// src/dynamic_analysis/incremental.rs
+//! What each file's last `prune` found, so `--incremental` runs can skip files that have
+//! not changed since.
+//!
+//! After a run, every file it finished is recorded in [`CACHE_FILE`] under
+//! [`JOURNAL_DIR`](crate::dynamic_analysis::journal::JOURNAL_DIR) with the CRC32 of the text
+//! the run left, the candidates tried and the bounds kept. A file that still hashes the
+//! same had every removable bound removed then; the bounds it kept only become removable
+//! when code they serve changes elsewhere, which the hash of the whole crate tracks. A
+//! new `Cargo.lock` or rustc can change any outcome, so the cache records the run's
+//! [`Fingerprint`] and a run that sees another one starts over.
+
+#![deny(missing_docs)]
+
+use crate::dynamic_analysis::journal::Journal;
+use crate::error::{TraitError, WinnowerError};
+use crate::fingerprint::Fingerprint;
+use crate::report::{FileReport, RemovalStatus};
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
+
+/// The cache file, in the journal directory.
+pub const CACHE_FILE: &str = "runs.json";
+
+/// Which unchanged files `prune --incremental` skips.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[cfg_attr(feature = "cli", derive(clap::ValueEnum))]
+pub enum Incremental {
+    /// Only while no file of the crate changed since the last run (default).
+    Safe,
+    /// Whenever the file itself is unchanged, even if others changed.
+    Aggressive,
+}
+
+/// The last run's outcome for one file.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct FileOutcome {
+    /// CRC32 of the file's text after the run.
+    pub hash: u32,
+    /// Candidates tried.
+    pub candidates: usize,
+    /// Bounds removed.
+    pub removed: usize,
+    /// Bounds kept, e.g. `fn f: <T: Clone>`.
+    pub retained: Vec<String>,
+}
+
+impl FileOutcome {
+    /// The outcome `report` records for a file whose text is now `text`.
+    pub fn of(report: &FileReport, text: &[u8]) -> Self {
+        let mut retained = Vec::new();
+        for r in &report.removals {
+            if matches!(r.status, RemovalStatus::Retained | RemovalStatus::Reverted) {
+                let bound = format!("{}: {}", r.finding.item, r.finding.candidate.declaration());
+                if !retained.contains(&bound) {
+                    retained.push(bound);
+                }
+            }
+        }
+        Self {
+            hash: crc32fast::hash(text),
+            candidates: report.metrics.candidates,
+            removed: report
+                .removals
+                .iter()
+                .filter(|r| r.status == RemovalStatus::Removed)
+                .count(),
+            retained,
+        }
+    }
+}
+
+/// Per-file outcomes of the last `prune` of a crate.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct RunCache {
+    /// Hash of the settings the run pruned with; a run with other settings reuses nothing.
+    pub settings: u32,
+    /// Hash of every discovered file as the run left it (see [`RunCache::hash_crate`]).
+    pub crate_hash: u32,
+    /// The `Cargo.lock` and rustc the run saw; a run that sees others reuses nothing
+    /// unless it ignores fingerprints. Caches written before fingerprints have none.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub fingerprint: Option<Fingerprint>,
+    /// Outcomes by path relative to the crate root.
+    pub files: BTreeMap<PathBuf, FileOutcome>,
+}
+
+/// Files an `--incremental` run skipped and pruned.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct CacheStats {
+    /// Files skipped: unchanged since the last run.
+    pub hits: usize,
+    /// Files pruned.
+    pub misses: usize,
+}
+
+impl RunCache {
+    /// The cache file of the crate at `root`.
+    pub fn path(root: &Path) -> PathBuf {
+        Journal::dir(root).join(CACHE_FILE)
+    }
+
+    /// The cache of the last run under `root`; empty when there is none or it does not
+    /// parse, which only costs a full run.
+    pub fn load(root: &Path) -> Self {
+        std::fs::read(Self::path(root))
+            .ok()
+            .and_then(|bytes| serde_json::from_slice(&bytes).ok())
+            .unwrap_or_default()
+    }
+
+    /// Write the cache for the crate at `root`.
+    pub fn save(&self, root: &Path) -> TraitError<()> {
+        let path = Self::path(root);
+        let dir = Journal::dir(root);
+        std::fs::create_dir_all(&dir).map_err(|e| WinnowerError::io("creating", &dir, e))?;
+        let text = serde_json::to_string_pretty(self)
+            .map_err(|e| WinnowerError::Internal(format!("serializing run cache: {e}")))?;
+        std::fs::write(&path, text).map_err(|e| WinnowerError::io("writing", &path, e))
+    }
+
+    /// The recorded outcome of `path` if a `mode` run with `settings` can skip it: its text
+    /// still hashes to `hash` and, under [`Incremental::Safe`], the crate to `crate_hash`.
+    pub fn reusable(
+        &self,
+        mode: Incremental,
+        settings: u32,
+        crate_hash: u32,
+        path: &Path,
+        hash: u32,
+    ) -> Option<&FileOutcome> {
+        if self.settings != settings || (mode == Incremental::Safe && self.crate_hash != crate_hash)
+        {
+            return None;
+        }
+        self.files.get(path).filter(|f| f.hash == hash)
+    }
+
+    /// Hash of a crate from the relative path and text hash of each of its files, in order.
+    pub fn hash_crate<'a>(files: impl IntoIterator<Item = (&'a Path, u32)>) -> u32 {
+        let mut hasher = crc32fast::Hasher::new();
+        for (path, hash) in files {
+            hasher.update(path.to_string_lossy().as_bytes());
+            hasher.update(&hash.to_le_bytes());
+        }
+        hasher.finalize()
+    }
+}
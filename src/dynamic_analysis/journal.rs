@@ -0,0 +1,284 @@
+// src/dynamic_analysis/journal.rs
+//! A write-ahead journal of trial edits, so a run killed mid-trial can be undone.
+//!
+//! Before each trial write the original text is stashed under [`JOURNAL_DIR`] and an entry
+//! naming it is appended to the journal; the entry is resolved once the trial is kept or
+//! reverted. Entries left unresolved mark files that still hold a trial nobody validated,
+//! and [`recover`] puts their stashed originals back.
+
+#![deny(missing_docs)]
+
+use crate::dynamic_analysis::store::SourceStore;
+use crate::error::{TraitError, WinnowerError};
+use serde::{Deserialize, Serialize};
+use std::cell::{Cell, RefCell};
+use std::collections::{BTreeSet, HashMap};
+use std::fs::File;
+use std::io::Write;
+use std::path::{Path, PathBuf};
+
+/// Where the journal and stashed originals live, relative to the crate root.
+pub const JOURNAL_DIR: &str = "target/trait-winnower";
+
+const JOURNAL_FILE: &str = "journal";
+const STASH_DIR: &str = "stash";
+
+/// A trial write that was never kept or reverted.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct JournalEntry {
+    /// The edited file, relative to the crate root.
+    pub path: PathBuf,
+    /// CRC32 of the original text.
+    pub hash: u32,
+    /// The stashed original, relative to the crate root.
+    pub stash: PathBuf,
+}
+
+/// One journal line.
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(tag = "op", rename_all = "snake_case")]
+enum Line {
+    Write {
+        id: u64,
+        path: PathBuf,
+        hash: u32,
+        stash: PathBuf,
+    },
+    Resolved {
+        id: u64,
+    },
+}
+
+/// The journal of one `prune` run.
+#[derive(Debug)]
+pub struct Journal {
+    root: PathBuf,
+    file: RefCell<File>,
+    next_id: Cell<u64>,
+}
+
+impl Journal {
+    /// The journal directory of the crate at `root`.
+    pub fn dir(root: &Path) -> PathBuf {
+        root.join(JOURNAL_DIR)
+    }
+
+    /// Start a fresh journal for `root`, dropping the stash of an earlier run. Call
+    /// [`Journal::pending`] first: unresolved entries are lost.
+    pub fn start(root: &Path) -> TraitError<Self> {
+        let dir = Self::dir(root);
+        let stash = dir.join(STASH_DIR);
+        if stash.exists() {
+            std::fs::remove_dir_all(&stash)
+                .map_err(|e| WinnowerError::io("clearing", &stash, e))?;
+        }
+        std::fs::create_dir_all(&stash).map_err(|e| WinnowerError::io("creating", &stash, e))?;
+        let path = dir.join(JOURNAL_FILE);
+        let file = File::create(&path).map_err(|e| WinnowerError::io("creating", &path, e))?;
+        Ok(Self {
+            root: root.to_path_buf(),
+            file: RefCell::new(file),
+            next_id: Cell::new(0),
+        })
+    }
+
+    /// Entries of the journal under `root` that were never resolved, oldest first; empty
+    /// when there is no journal.
+    pub fn pending(root: &Path) -> TraitError<Vec<JournalEntry>> {
+        let path = Self::dir(root).join(JOURNAL_FILE);
+        if !path.is_file() {
+            return Ok(Vec::new());
+        }
+        let text =
+            std::fs::read_to_string(&path).map_err(|e| WinnowerError::io("reading", &path, e))?;
+        let mut writes = Vec::new();
+        let mut resolved = BTreeSet::new();
+        let lines: Vec<&str> = text.lines().collect();
+        for (n, line) in lines.iter().enumerate() {
+            // A crash while appending leaves at most a torn last line.
+            let parsed = match serde_json::from_str::<Line>(line) {
+                Ok(parsed) => parsed,
+                Err(_) if n + 1 == lines.len() => break,
+                Err(e) => {
+                    return Err(WinnowerError::Format {
+                        artifact: "journal",
+                        path,
+                        detail: format!("line {}: {e}", n + 1),
+                    });
+                }
+            };
+            match parsed {
+                Line::Write {
+                    id,
+                    path,
+                    hash,
+                    stash,
+                } => writes.push((id, JournalEntry { path, hash, stash })),
+                Line::Resolved { id } => {
+                    resolved.insert(id);
+                }
+            }
+        }
+        Ok(writes
+            .into_iter()
+            .filter(|(id, _)| !resolved.contains(id))
+            .map(|(_, entry)| entry)
+            .collect())
+    }
+
+    /// Stash `original`, the text of `path` before a trial, and journal the write; returns
+    /// the entry's id. Both reach the disk before this returns.
+    fn record(&self, path: &Path, original: &str) -> TraitError<u64> {
+        let id = self.next_id.get();
+        self.next_id.set(id + 1);
+        let stash = Path::new(JOURNAL_DIR)
+            .join(STASH_DIR)
+            .join(format!("{id}.orig"));
+        let stash_path = self.root.join(&stash);
+        let mut f =
+            File::create(&stash_path).map_err(|e| WinnowerError::io("stashing", &stash_path, e))?;
+        f.write_all(original.as_bytes())
+            .and_then(|()| f.sync_all())
+            .map_err(|e| WinnowerError::io("stashing", &stash_path, e))?;
+        self.append(&Line::Write {
+            id,
+            path: path.strip_prefix(&self.root).unwrap_or(path).to_path_buf(),
+            hash: crc32fast::hash(original.as_bytes()),
+            stash,
+        })?;
+        Ok(id)
+    }
+
+    /// Mark entry `id` resolved.
+    fn resolve(&self, id: u64) -> TraitError<()> {
+        self.append(&Line::Resolved { id })
+    }
+
+    fn append(&self, line: &Line) -> TraitError<()> {
+        let path = Self::dir(&self.root).join(JOURNAL_FILE);
+        let mut text = serde_json::to_string(line)
+            .map_err(|e| WinnowerError::Internal(format!("serializing journal line: {e}")))?;
+        text.push('\n');
+        let mut file = self.file.borrow_mut();
+        file.write_all(text.as_bytes())
+            .and_then(|()| file.sync_data())
+            .map_err(|e| WinnowerError::io("writing", &path, e))
+    }
+
+    /// End the run: remove the journal and stash. Keeps both if an entry is unresolved.
+    pub fn finish(self) -> TraitError<()> {
+        if Self::pending(&self.root)?.is_empty() {
+            clear(&self.root)?;
+        }
+        Ok(())
+    }
+}
+
+/// Put back the stashed original of every unresolved entry under `root`, then remove the
+/// journal and stash; returns the files restored. Files already holding their original
+/// are left alone and not listed.
+pub fn recover(root: &Path) -> TraitError<Vec<PathBuf>> {
+    let mut seen = BTreeSet::new();
+    let mut restored = Vec::new();
+    // The oldest entry of a file holds its text from before any unresolved trial.
+    for entry in Journal::pending(root)? {
+        if !seen.insert(entry.path.clone()) {
+            continue;
+        }
+        let stash = root.join(&entry.stash);
+        let original =
+            std::fs::read(&stash).map_err(|e| WinnowerError::io("reading stashed", &stash, e))?;
+        if crc32fast::hash(&original) != entry.hash {
+            return Err(WinnowerError::Format {
+                artifact: "stash",
+                path: stash,
+                detail: format!(
+                    "does not match the journal's hash of {}",
+                    entry.path.display()
+                ),
+            });
+        }
+        let path = root.join(&entry.path);
+        if std::fs::read(&path).is_ok_and(|now| now == original) {
+            continue;
+        }
+        std::fs::write(&path, original).map_err(|e| WinnowerError::io("restoring", &path, e))?;
+        restored.push(path);
+    }
+    clear(root)?;
+    Ok(restored)
+}
+
+fn clear(root: &Path) -> TraitError<()> {
+    let dir = Journal::dir(root);
+    for (path, is_dir) in [(dir.join(STASH_DIR), true), (dir.join(JOURNAL_FILE), false)] {
+        let removed = if is_dir {
+            std::fs::remove_dir_all(&path)
+        } else {
+            std::fs::remove_file(&path)
+        };
+        match removed {
+            Err(e) if e.kind() != std::io::ErrorKind::NotFound => {
+                return Err(WinnowerError::io("removing", &path, e));
+            }
+            _ => {}
+        }
+    }
+    Ok(())
+}
+
+/// A [`SourceStore`] journaling every trial it writes through to `inner`.
+pub struct JournaledStore<'a> {
+    inner: &'a dyn SourceStore,
+    journal: Journal,
+    open: RefCell<HashMap<PathBuf, u64>>,
+}
+
+impl<'a> JournaledStore<'a> {
+    /// Journal the trials written to `inner` in `journal`.
+    pub fn new(inner: &'a dyn SourceStore, journal: Journal) -> Self {
+        Self {
+            inner,
+            journal,
+            open: RefCell::new(HashMap::new()),
+        }
+    }
+
+    /// End the run; see [`Journal::finish`].
+    pub fn finish(self) -> TraitError<()> {
+        self.journal.finish()
+    }
+
+    fn resolve(&self, path: &Path) -> TraitError<()> {
+        match self.open.borrow_mut().remove(path) {
+            Some(id) => self.journal.resolve(id),
+            None => Ok(()),
+        }
+    }
+}
+
+impl SourceStore for JournaledStore<'_> {
+    fn read(&self, path: &Path) -> TraitError<String> {
+        self.inner.read(path)
+    }
+
+    fn write(&self, path: &Path, src: &str) -> TraitError<()> {
+        // A trial on top of an unresolved one is its continuation: the first stash stays.
+        if !self.open.borrow().contains_key(path) {
+            let original = self.inner.read(path)?;
+            let id = self.journal.record(path, &original)?;
+            self.open.borrow_mut().insert(path.to_path_buf(), id);
+        }
+        self.inner.write(path, src)
+    }
+
+    fn revert(&self, path: &Path, src: &str) -> TraitError<()> {
+        self.inner.revert(path, src)?;
+        self.resolve(path)
+    }
+
+    fn keep(&self, path: &Path) -> TraitError<()> {
+        self.inner.keep(path)?;
+        self.resolve(path)
+    }
+}
@@ -0,0 +1,112 @@
+// src/dynamic_analysis/lock_wait.rs
+//! Checks waiting on a file lock another cargo process holds.
+//!
+//! Cargo serializes builds sharing a target directory, and downloads into its package
+//! cache, with file locks. A check started while rust-analyzer, an IDE or another build
+//! holds one waits for it, printing `Blocking waiting for file lock on ...` once, and a
+//! run would seem to hang on every trial. That line is a status message, which `--quiet`
+//! silences, so checks run without `--quiet` and their captured output drops the status
+//! lines `--quiet` would have (see [`without_quiet`] and [`strip_statuses`]). A check that
+//! sees the line calls its [`LockNotice`], and fails with [`WinnowerError::LockWait`]
+//! once it has waited longer than
+//! [`lock_wait_timeout`](crate::config::CargoCheckConfig::lock_wait_timeout).
+//!
+//! [`WinnowerError::LockWait`]: crate::error::WinnowerError::LockWait
+
+#![deny(missing_docs)]
+
+use std::fmt;
+use std::path::Path;
+use std::sync::Arc;
+
+/// What cargo prints on stderr when it starts waiting for a lock.
+pub const BLOCKING: &str = "Blocking waiting for file lock";
+
+/// What a check calls when cargo starts waiting for a lock: with the target directory of
+/// the check and what cargo waits for, as it names it (`build directory`, `package
+/// cache`).
+#[derive(Clone)]
+pub struct LockNotice(Arc<NoticeFn>);
+
+type NoticeFn = dyn Fn(&Path, &str) + Send + Sync;
+
+impl LockNotice {
+    /// A notice calling `f`.
+    pub fn new(f: impl Fn(&Path, &str) + Send + Sync + 'static) -> Self {
+        Self(Arc::new(f))
+    }
+
+    /// Tell of a wait of the check building in `dir` for `what`.
+    pub fn notify(&self, dir: &Path, what: &str) {
+        (self.0)(dir, what);
+    }
+}
+
+impl fmt::Debug for LockNotice {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("LockNotice")
+    }
+}
+
+/// What `line` of cargo's stderr says it waits for, when it is the [`BLOCKING`] status.
+pub fn waits_for(line: &str) -> Option<&str> {
+    let rest = line.trim().strip_prefix(BLOCKING)?;
+    Some(rest.trim().strip_prefix("on ").unwrap_or(rest.trim()))
+}
+
+/// `args` without `--quiet` and `-q`, and whether they had one.
+pub fn without_quiet(args: Vec<String>) -> (Vec<String>, bool) {
+    let before = args.len();
+    let args: Vec<String> = args
+        .into_iter()
+        .filter(|a| a != "--quiet" && a != "-q")
+        .collect();
+    let quiet = args.len() < before;
+    (args, quiet)
+}
+
+/// `stderr` without cargo's status lines (`    Checking x v0.1.0`): a capitalized word
+/// right-aligned in twelve columns, then a space.
+pub fn strip_statuses(stderr: &[u8]) -> Vec<u8> {
+    let mut out = Vec::with_capacity(stderr.len());
+    for line in stderr.split_inclusive(|&b| b == b'\n') {
+        if !is_status(&String::from_utf8_lossy(line)) {
+            out.extend_from_slice(line);
+        }
+    }
+    out
+}
+
+fn is_status(line: &str) -> bool {
+    let (Some(head), Some(&b' ')) = (line.get(..12), line.as_bytes().get(12)) else {
+        return false;
+    };
+    let word = head.trim_start_matches(' ');
+    word.starts_with(|c: char| c.is_ascii_uppercase())
+        && word.chars().all(|c| c.is_ascii_alphabetic())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn statuses_are_stripped_and_lock_waits_read() {
+        let stderr = "    Blocking waiting for file lock on build directory\n\
+                      \x20   Checking x v0.1.0 (/x)\n\
+                      warning: unused variable: `a`\n\
+                      \x20 --> src/lib.rs:1:8\n\
+                      \x20   Finished `dev` profile [unoptimized] target(s) in 0.1s\n";
+        assert_eq!(
+            String::from_utf8_lossy(&strip_statuses(stderr.as_bytes())),
+            "warning: unused variable: `a`\n  --> src/lib.rs:1:8\n"
+        );
+        let first = stderr.lines().next().unwrap_or_default();
+        assert_eq!(waits_for(first), Some("build directory"));
+        assert_eq!(waits_for("    Checking x v0.1.0"), None);
+        assert_eq!(
+            without_quiet(vec!["--workspace".into(), "--quiet".into()]),
+            (vec!["--workspace".to_owned()], true)
+        );
+    }
+}
@@ -0,0 +1,154 @@
+// src/dynamic_analysis/messages.rs
+//! The JSON messages cargo prints with `--message-format=json`, as far as `check`, `clippy`
+//! and their validators read them.
+
+#![deny(missing_docs)]
+
+use serde::Deserialize;
+
+/// A `compiler-message` line: one rustc or clippy diagnostic of one package.
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize)]
+pub struct CompilerMessage {
+    /// The package the diagnostic is in, e.g. `path+file:///work/a#0.1.0`.
+    #[serde(default)]
+    pub package_id: String,
+    /// The diagnostic.
+    pub message: Diagnostic,
+}
+
+/// A diagnostic as rustc emits it in JSON.
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize)]
+pub struct Diagnostic {
+    /// The headline, e.g. `these bounds contain repeated elements`.
+    #[serde(default)]
+    pub message: String,
+    /// `error`, `warning`, `note`...
+    pub level: String,
+    /// The lint or error code, e.g. `clippy::trait_duplication_in_bounds` or `E0277`.
+    #[serde(default, deserialize_with = "code")]
+    pub code: Option<String>,
+    /// Where it points.
+    #[serde(default)]
+    pub spans: Vec<DiagnosticSpan>,
+    /// The diagnostic as rustc prints it to a terminal.
+    #[serde(default)]
+    pub rendered: Option<String>,
+}
+
+/// A span of a [`Diagnostic`]; lines and columns are 1-based, columns count chars.
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize)]
+pub struct DiagnosticSpan {
+    /// The file, relative to the workspace root for workspace packages.
+    pub file_name: String,
+    /// First line.
+    pub line_start: usize,
+    /// Last line.
+    pub line_end: usize,
+    /// Column the span starts at on `line_start`.
+    pub column_start: usize,
+    /// Column just past its end on `line_end`.
+    pub column_end: usize,
+    /// Whether this is the span the diagnostic is about rather than context.
+    pub is_primary: bool,
+}
+
+/// `{"code": "E0277", "explanation": ...}` as the code alone.
+fn code<'de, D: serde::Deserializer<'de>>(de: D) -> Result<Option<String>, D::Error> {
+    #[derive(Deserialize)]
+    struct Code {
+        code: String,
+    }
+    Ok(Option::<Code>::deserialize(de)?.map(|c| c.code))
+}
+
+impl Diagnostic {
+    /// The lint that raised the diagnostic, e.g. `unused_imports`; `None` for errors of
+    /// the language itself, coded like `E0277` or not at all.
+    pub fn lint(&self) -> Option<&str> {
+        let code = self.code.as_deref()?;
+        let error_code = code.len() == 5
+            && code.starts_with('E')
+            && code[1..].bytes().all(|b| b.is_ascii_digit());
+        (!error_code).then_some(code)
+    }
+}
+
+impl CompilerMessage {
+    /// The message on `line`, if it is a `compiler-message`.
+    pub fn parse(line: &str) -> Option<Self> {
+        #[derive(Deserialize)]
+        struct Line {
+            reason: String,
+        }
+        let reason: Line = serde_json::from_str(line).ok()?;
+        if reason.reason != "compiler-message" {
+            return None;
+        }
+        serde_json::from_str(line).ok()
+    }
+
+    /// Every `compiler-message` among the lines of `stdout`.
+    pub fn all(stdout: &str) -> impl Iterator<Item = Self> + '_ {
+        stdout.lines().filter_map(Self::parse)
+    }
+
+    /// Whether the package is built from a path: a workspace member or a path dependency.
+    pub fn in_path_package(&self) -> bool {
+        self.package_id.contains("path+file://")
+    }
+
+    /// The spans the diagnostic is about.
+    pub fn primary_spans(&self) -> impl Iterator<Item = &DiagnosticSpan> {
+        self.message.spans.iter().filter(|s| s.is_primary)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn clippy_lints_parse_with_code_and_spans() {
+        let line = r#"{"reason":"compiler-message","package_id":"path+file:///w#cl@0.1.0","message":{"message":"these bounds contain repeated elements","level":"warning","code":{"code":"clippy::trait_duplication_in_bounds","explanation":null},"spans":[{"file_name":"src/lib.rs","line_start":18,"line_end":18,"column_start":15,"column_end":28,"is_primary":true,"text":[]}],"children":[],"rendered":"warning: these bounds\n"}}"#;
+        let message = CompilerMessage::parse(line).expect("a compiler message");
+        assert!(message.in_path_package());
+        assert_eq!(
+            message.message.code.as_deref(),
+            Some("clippy::trait_duplication_in_bounds")
+        );
+        let span = message.primary_spans().next().expect("a primary span");
+        assert_eq!(
+            (span.line_start, span.column_start, span.column_end),
+            (18, 15, 28)
+        );
+
+        assert!(CompilerMessage::parse(r#"{"reason":"build-finished","success":true}"#).is_none());
+        assert!(CompilerMessage::parse("warning: not json").is_none());
+        let uncoded = r#"{"reason":"compiler-message","message":{"message":"m","level":"error","code":null,"spans":[]}}"#;
+        assert_eq!(
+            CompilerMessage::parse(uncoded).map(|m| m.message.code),
+            Some(None)
+        );
+    }
+
+    #[test]
+    fn lints_are_told_from_language_errors() {
+        let diagnostic = |code: Option<&str>| Diagnostic {
+            message: String::new(),
+            level: "error".into(),
+            code: code.map(str::to_owned),
+            spans: Vec::new(),
+            rendered: None,
+        };
+        assert_eq!(
+            diagnostic(Some("unused_imports")).lint(),
+            Some("unused_imports")
+        );
+        assert_eq!(
+            diagnostic(Some("clippy::needless_borrow")).lint(),
+            Some("clippy::needless_borrow")
+        );
+        assert_eq!(diagnostic(Some("E0277")).lint(), None);
+        assert_eq!(diagnostic(None).lint(), None);
+    }
+}
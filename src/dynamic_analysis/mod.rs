@@ -3,5 +3,24 @@
 
 #![deny(missing_docs)]
 
+pub mod batch;
+pub mod clippy;
 pub mod common;
 pub mod edit;
+pub mod external;
+pub mod incremental;
+pub mod journal;
+pub mod lock_wait;
+pub mod messages;
+#[cfg(feature = "discover")]
+pub mod schedule;
+pub mod semver;
+pub mod session;
+pub mod store;
+pub mod test_only;
+pub mod text_edit;
+#[cfg(feature = "discover")]
+pub mod trial_cache;
+pub mod unlock;
+pub mod validate;
+pub mod worktree;
@@ -0,0 +1,84 @@
+// src/dynamic_analysis/schedule.rs
+//! The order `prune` works through files in (see [`Order`]).
+//!
+//! A run cut short by a deadline should have spent its checks where they pay most. With
+//! [`Order::Priority`] files go by the highest confidence of the static rules flagging
+//! their bounds, descending; then by what checking them costs, ascending; then by path.
+//! Trials within a file keep source order. A check's cost is estimated as the bytes of
+//! source it compiles, those of the packages [`CrateMap::check_scope`] picks and of the
+//! workspace packages they depend on, rather than timed, so the same tree always gets the
+//! same order.
+
+#![deny(missing_docs)]
+
+use crate::config::Order;
+use crate::crate_map::CrateMap;
+use crate::findings::{Confidence, analyze_file};
+use crate::report::{Schedule, ScheduledFile};
+use std::cmp::Reverse;
+use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
+
+/// The order to prune `files` of the crate at `root` in: indices into `files`, with the
+/// schedule to report.
+pub fn schedule(
+    root: &Path,
+    files: &[PathBuf],
+    map: &CrateMap,
+    order: Order,
+) -> (Vec<usize>, Schedule) {
+    let rel = |f: &PathBuf| f.strip_prefix(root).unwrap_or(f).to_path_buf();
+    let mut indices: Vec<usize> = (0..files.len()).collect();
+    let scheduled: Vec<ScheduledFile> = match order {
+        Order::Source => files
+            .iter()
+            .map(|f| ScheduledFile {
+                path: rel(f),
+                confidence: None,
+                cost: None,
+            })
+            .collect(),
+        Order::Priority => {
+            let mut costs = BTreeMap::new();
+            files
+                .iter()
+                .map(|f| ScheduledFile {
+                    path: rel(f),
+                    confidence: confidence(f),
+                    cost: Some(cost(map, f, &mut costs)),
+                })
+                .collect()
+        }
+    };
+    if order == Order::Priority {
+        indices.sort_by_key(|&i| {
+            let s = &scheduled[i];
+            (Reverse(s.confidence), s.cost, &s.path)
+        });
+    }
+    let files = indices.iter().map(|&i| scheduled[i].clone()).collect();
+    (indices, Schedule { order, files })
+}
+
+/// The highest confidence of the static rules flagging bounds in `path`; `None` when none
+/// does or the file does not read or parse, which pruning reports itself.
+fn confidence(path: &Path) -> Option<Confidence> {
+    let src = std::fs::read_to_string(path).ok()?;
+    analyze_file(&src)
+        .ok()?
+        .iter()
+        .map(|f| f.verdict.confidence)
+        .max()
+}
+
+/// The bytes of source a trial in `path` compiles, memoized by check scope in `costs`.
+fn cost(map: &CrateMap, path: &Path, costs: &mut BTreeMap<Vec<String>, u64>) -> u64 {
+    let scope = map.check_scope(path);
+    *costs.entry(scope).or_insert_with_key(|scope| {
+        map.sources(scope)
+            .iter()
+            .filter_map(|s| std::fs::metadata(s).ok())
+            .map(|m| m.len())
+            .sum()
+    })
+}
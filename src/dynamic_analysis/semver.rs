@@ -0,0 +1,162 @@
+// src/dynamic_analysis/semver.rs
+//! The semver gate run after `prune`: does the pruned crate still have a compatible public
+//! API?
+
+#![deny(missing_docs)]
+
+use crate::config::cargo_program;
+use crate::error::{TraitError, WinnowerError};
+use crate::report::Finding;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use std::process::Command;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+/// Directories never copied into a baseline.
+const BASELINE_SKIP: &[&str] = &["target", ".git"];
+
+/// Lines of tool output kept in a [`SemverOutcome::Breaking`] summary.
+const SUMMARY_LINES: usize = 40;
+
+/// Compares the public API of the crate after pruning with the crate before it.
+pub trait SemverGate {
+    /// Compare the crate at `current` with `baseline`, a copy of it as it was before pruning.
+    fn compare(&self, current: &Path, baseline: &Path) -> TraitError<SemverOutcome>;
+}
+
+/// What a [`SemverGate`] found.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub enum SemverOutcome {
+    /// No breaking change.
+    Compatible,
+    /// A breaking change.
+    Breaking {
+        /// The tool's report, trimmed to its last lines.
+        summary: String,
+    },
+    /// The gate could not run, e.g. because the tool is not installed.
+    Unavailable {
+        /// Why not.
+        reason: String,
+    },
+}
+
+/// The semver gate of `prune`: what it found and what was put back.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SemverReport {
+    /// What the gate found.
+    pub outcome: SemverOutcome,
+    /// Removals put back after a breaking change, now
+    /// [`RemovalStatus::Reverted`](crate::report::RemovalStatus::Reverted): those on public
+    /// items, and other removals in the same files that fail validation on their own.
+    pub reverted: Vec<Finding>,
+    /// Of [`SemverReport::reverted`], the removals on items the tool's report names.
+    pub triggers: Vec<Finding>,
+}
+
+/// Runs `cargo semver-checks` with the copy of the crate before pruning as its baseline.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct CargoSemverChecks;
+
+impl SemverGate for CargoSemverChecks {
+    fn compare(&self, current: &Path, baseline: &Path) -> TraitError<SemverOutcome> {
+        let output = match Command::new(cargo_program(None))
+            .args(["semver-checks", "check-release", "--baseline-root"])
+            .arg(baseline)
+            .current_dir(current)
+            .output()
+        {
+            Ok(output) => output,
+            Err(e) => {
+                return Ok(SemverOutcome::Unavailable {
+                    reason: format!("cannot run cargo: {e}"),
+                });
+            }
+        };
+        if output.status.success() {
+            return Ok(SemverOutcome::Compatible);
+        }
+        let stdout = String::from_utf8_lossy(&output.stdout);
+        let stderr = String::from_utf8_lossy(&output.stderr);
+        if stderr.contains("no such command") {
+            return Ok(SemverOutcome::Unavailable {
+                reason: "`cargo semver-checks` is not installed".into(),
+            });
+        }
+        let text = format!("{stdout}{stderr}");
+        if text.contains("semver requires new major version") || text.contains("--- failure") {
+            let lines: Vec<&str> = text.lines().collect();
+            let tail = &lines[lines.len().saturating_sub(SUMMARY_LINES)..];
+            return Ok(SemverOutcome::Breaking {
+                summary: tail.join("\n"),
+            });
+        }
+        let tail: Vec<&str> = stderr.lines().rev().take(SUMMARY_LINES).collect();
+        Err(WinnowerError::Validation {
+            command: "cargo semver-checks check-release".into(),
+            status: output.status.code(),
+            stderr: tail.into_iter().rev().collect::<Vec<_>>().join("\n"),
+        })
+    }
+}
+
+/// A temporary copy of a crate as it was before pruning; removed on drop.
+#[derive(Debug)]
+pub struct Baseline {
+    dir: PathBuf,
+}
+
+impl Baseline {
+    /// Copy `root` (without `target/` and `.git/`), putting back the `originals` of files
+    /// `prune` modified.
+    pub fn create(root: &Path, originals: &HashMap<PathBuf, String>) -> TraitError<Self> {
+        let nanos = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map_or(0, |d| d.subsec_nanos());
+        let dir = std::env::temp_dir().join(format!(
+            "trait-winnower-baseline-{}-{nanos}",
+            std::process::id()
+        ));
+        let baseline = Self { dir };
+        copy_tree(root, &baseline.dir)?;
+        for (path, src) in originals {
+            let rel = path.strip_prefix(root).unwrap_or(path);
+            let dest = baseline.dir.join(rel);
+            std::fs::write(&dest, src).map_err(|e| WinnowerError::io("writing", &dest, e))?;
+        }
+        Ok(baseline)
+    }
+
+    /// Root of the copy.
+    pub fn path(&self) -> &Path {
+        &self.dir
+    }
+}
+
+impl Drop for Baseline {
+    fn drop(&mut self) {
+        let _ = std::fs::remove_dir_all(&self.dir);
+    }
+}
+
+fn copy_tree(from: &Path, to: &Path) -> TraitError<()> {
+    std::fs::create_dir_all(to).map_err(|e| WinnowerError::io("creating", to, e))?;
+    let entries = std::fs::read_dir(from).map_err(|e| WinnowerError::io("reading", from, e))?;
+    for entry in entries {
+        let entry = entry.map_err(|e| WinnowerError::io("reading", from, e))?;
+        let name = entry.file_name();
+        let (src, dest) = (entry.path(), to.join(&name));
+        let ty = entry
+            .file_type()
+            .map_err(|e| WinnowerError::io("reading", &src, e))?;
+        if ty.is_dir() {
+            if !BASELINE_SKIP.iter().any(|s| name == *s) {
+                copy_tree(&src, &dest)?;
+            }
+        } else if ty.is_file() {
+            std::fs::copy(&src, &dest).map_err(|e| WinnowerError::io("copying", &src, e))?;
+        }
+    }
+    Ok(())
+}
@@ -0,0 +1,285 @@
+// src/dynamic_analysis/session.rs
+//! Recorded validations, for reproducing a `prune` run without cargo.
+//!
+//! `prune --record FILE` keeps every `cargo check` the run makes in a [`SessionLog`]: its
+//! arguments, the directory it ran in, the sources that differed from the tree the run
+//! started from, by hash, and the outcome. `prune --replay FILE` prunes the same tree
+//! answering each check from the recording instead of running cargo, and fails with
+//! [`WinnowerError::Replay`] as soon as it asks for one the recording does not hold: a
+//! run from CI can be stepped through on any machine, and a recording doubles as a
+//! fixture for the prune loop. Custom validators answer for themselves and are neither
+//! recorded nor replayed.
+
+#![deny(missing_docs)]
+
+use crate::dynamic_analysis::common::{FollowUp, Toolchain, ValidationOutcome};
+use crate::error::{TraitError, WinnowerError};
+use crate::formats::Format;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+
+/// A recording as saved by `prune --record`.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct SessionLog {
+    /// The cargo and rustc of the recording run.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub toolchain: Option<Toolchain>,
+    /// CRC32 of each source when the run started, by path relative to the root.
+    pub files: BTreeMap<PathBuf, u32>,
+    /// The checks, in the order they ran.
+    pub checks: Vec<RecordedCheck>,
+}
+
+/// One check of a [`SessionLog`].
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct RecordedCheck {
+    /// The arguments after `cargo`, and the matrix entries checked after the host.
+    pub args: Vec<String>,
+    /// The tree checked: the root or the worktree copy it was validated in.
+    pub cwd: PathBuf,
+    /// CRC32 of the sources that differed from [`SessionLog::files`]; a missing file
+    /// hashes like an empty one.
+    pub changed: BTreeMap<PathBuf, u32>,
+    /// Whether the tree built.
+    pub success: bool,
+    /// Exit code of cargo, if it exited normally.
+    #[serde(default)]
+    pub code: Option<i32>,
+    /// Its stderr.
+    #[serde(default)]
+    pub stderr: String,
+    /// The packages the check was limited to.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub scope: Vec<String>,
+    /// Killed at its first error.
+    #[serde(default)]
+    pub aborted: bool,
+    /// The lints it tolerated.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub follow_ups: Vec<FollowUp>,
+}
+
+impl RecordedCheck {
+    fn outcome(&self) -> ValidationOutcome {
+        ValidationOutcome {
+            success: self.success,
+            code: self.code,
+            stderr: self.stderr.clone(),
+            scope: self.scope.clone(),
+            aborted: self.aborted,
+            follow_ups: self.follow_ups.clone(),
+            ..ValidationOutcome::default()
+        }
+    }
+}
+
+/// A recording being written, or one being replayed, shared by the validators of a run.
+#[derive(Debug)]
+pub struct Session {
+    path: PathBuf,
+    replay: bool,
+    /// The sources hashed, relative to the root.
+    sources: Vec<PathBuf>,
+    log: Mutex<SessionLog>,
+}
+
+fn hash(root: &Path, rel: &Path) -> u32 {
+    crc32fast::hash(&std::fs::read(root.join(rel)).unwrap_or_default())
+}
+
+impl Session {
+    /// Start recording the checks of a run over `sources` of the tree at `root`, to be
+    /// saved to `path` by [`Session::save`].
+    pub fn record(path: &Path, root: &Path, sources: &[PathBuf]) -> Self {
+        let sources = Self::relative(root, sources);
+        let files = sources.iter().map(|f| (f.clone(), hash(root, f))).collect();
+        Self {
+            path: path.to_path_buf(),
+            replay: false,
+            sources,
+            log: Mutex::new(SessionLog {
+                files,
+                ..SessionLog::default()
+            }),
+        }
+    }
+
+    /// Load the recording at `path` to answer the checks of a run over `sources` of the
+    /// tree at `root`. Fails if the sources are not those the recording started from.
+    pub fn replay(path: &Path, root: &Path, sources: &[PathBuf]) -> TraitError<Self> {
+        let text =
+            std::fs::read_to_string(path).map_err(|e| WinnowerError::io("reading", path, e))?;
+        let log: SessionLog = Format::Session.from_json(path, &text)?;
+        let sources = Self::relative(root, sources);
+        let now: BTreeMap<PathBuf, u32> =
+            sources.iter().map(|f| (f.clone(), hash(root, f))).collect();
+        let mut differ: Vec<String> = now
+            .iter()
+            .filter(|(f, h)| log.files.get(*f) != Some(h))
+            .map(|(f, _)| f.display().to_string())
+            .collect();
+        differ.extend(
+            log.files
+                .keys()
+                .filter(|f| !now.contains_key(*f))
+                .map(|f| format!("{} (not discovered)", f.display())),
+        );
+        if !differ.is_empty() {
+            return Err(WinnowerError::Replay {
+                path: path.to_path_buf(),
+                detail: format!(
+                    "the sources are not those the recording started from: {}",
+                    differ.join(", ")
+                ),
+            });
+        }
+        Ok(Self {
+            path: path.to_path_buf(),
+            replay: true,
+            sources,
+            log: Mutex::new(log),
+        })
+    }
+
+    fn relative(root: &Path, sources: &[PathBuf]) -> Vec<PathBuf> {
+        let mut rel: Vec<PathBuf> = sources
+            .iter()
+            .map(|p| p.strip_prefix(root).unwrap_or(p).to_path_buf())
+            .collect();
+        rel.sort();
+        rel
+    }
+
+    fn log(&self) -> std::sync::MutexGuard<'_, SessionLog> {
+        self.log.lock().unwrap_or_else(|e| e.into_inner())
+    }
+
+    /// Whether checks are answered from the recording.
+    pub fn is_replay(&self) -> bool {
+        self.replay
+    }
+
+    /// The toolchain of the recording run.
+    pub fn toolchain(&self) -> Option<Toolchain> {
+        self.log().toolchain.clone()
+    }
+
+    /// Note the toolchain the recorded checks run with.
+    pub fn set_toolchain(&self, toolchain: Toolchain) {
+        self.log().toolchain = Some(toolchain);
+    }
+
+    /// The outcome of the check `args` of the tree at `root`: `run` records it, replay
+    /// looks it up. Cancelled checks are not recorded.
+    pub fn validate(
+        &self,
+        args: Vec<String>,
+        root: &Path,
+        run: impl FnOnce() -> TraitError<ValidationOutcome>,
+    ) -> TraitError<ValidationOutcome> {
+        let baseline = self.log().files.clone();
+        let changed: BTreeMap<PathBuf, u32> = self
+            .sources
+            .iter()
+            .map(|f| (f.clone(), hash(root, f)))
+            .filter(|(f, h)| baseline.get(f) != Some(h))
+            .collect();
+        if self.replay {
+            let log = self.log();
+            return match log
+                .checks
+                .iter()
+                .find(|c| c.args == args && c.changed == changed)
+            {
+                Some(check) => Ok(check.outcome()),
+                None => Err(WinnowerError::Replay {
+                    path: self.path.clone(),
+                    detail: format!(
+                        "no recorded `cargo {}` with {}",
+                        args.join(" "),
+                        match changed.len() {
+                            0 => "the sources unchanged".to_owned(),
+                            _ => format!(
+                                "{} as edited",
+                                changed
+                                    .keys()
+                                    .map(|f| f.display().to_string())
+                                    .collect::<Vec<_>>()
+                                    .join(", ")
+                            ),
+                        }
+                    ),
+                }),
+            };
+        }
+        let outcome = run()?;
+        // A cancelled check neither exited nor was aborted; it says nothing about the tree.
+        if outcome.code.is_some() || outcome.aborted {
+            self.log().checks.push(RecordedCheck {
+                args,
+                cwd: root.to_path_buf(),
+                changed,
+                success: outcome.success,
+                code: outcome.code,
+                stderr: outcome.stderr.clone(),
+                scope: outcome.scope.clone(),
+                aborted: outcome.aborted,
+                follow_ups: outcome.follow_ups.clone(),
+            });
+        }
+        Ok(outcome)
+    }
+
+    /// Write the recording to its file; nothing to do when replaying.
+    pub fn save(&self) -> TraitError<()> {
+        if self.replay {
+            return Ok(());
+        }
+        let text = Format::Session.to_json(&*self.log())?;
+        std::fs::write(&self.path, text).map_err(|e| WinnowerError::io("writing", &self.path, e))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn replay_answers_recorded_checks_only() -> TraitError<()> {
+        let tmp = tempfile::tempdir()?;
+        let root = tmp.path();
+        let file = root.join("a.rs");
+        std::fs::write(&file, "fn f<T: Clone>() {}\n")?;
+        let path = root.join("session.json");
+        let args = vec!["check".to_owned()];
+
+        let recording = Session::record(&path, root, std::slice::from_ref(&file));
+        std::fs::write(&file, "fn f<T>() {}\n")?;
+        let failed = ValidationOutcome {
+            code: Some(101),
+            stderr: "error[E0599]".into(),
+            ..ValidationOutcome::default()
+        };
+        recording.validate(args.clone(), root, || Ok(failed.clone()))?;
+        recording.save()?;
+
+        let replay = Session::replay(&path, root, std::slice::from_ref(&file));
+        assert!(matches!(replay, Err(WinnowerError::Replay { .. })));
+
+        std::fs::write(&file, "fn f<T: Clone>() {}\n")?;
+        let replay = Session::replay(&path, root, std::slice::from_ref(&file))?;
+        std::fs::write(&file, "fn f<T>() {}\n")?;
+        let outcome = replay.validate(args.clone(), root, || {
+            Err(WinnowerError::Internal("cargo ran".into()))
+        })?;
+        assert!(!outcome.success);
+        assert_eq!(outcome.stderr, "error[E0599]");
+
+        std::fs::write(&file, "fn f<T: Copy>() {}\n")?;
+        let missing = replay.validate(args, root, || Ok(ValidationOutcome::default()));
+        assert!(matches!(missing, Err(WinnowerError::Replay { .. })));
+        Ok(())
+    }
+}
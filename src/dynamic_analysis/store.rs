@@ -0,0 +1,116 @@
+// src/dynamic_analysis/store.rs
+//! Where the prune loop reads sources and writes its trials.
+
+#![deny(missing_docs)]
+
+use crate::analysis::ItemBounds;
+use crate::error::{TraitError, WinnowerError};
+use std::cell::{Cell, RefCell};
+use std::collections::HashMap;
+use std::io;
+use std::path::{Path, PathBuf};
+
+/// Source files as the prune loop sees them.
+pub trait SourceStore {
+    /// The current text of `path`.
+    fn read(&self, path: &Path) -> TraitError<String>;
+
+    /// Replace `path` with the text of a trial.
+    fn write(&self, path: &Path, src: &str) -> TraitError<()>;
+
+    /// Put `src` back into `path` after a rejected trial.
+    fn revert(&self, path: &Path, src: &str) -> TraitError<()>;
+
+    /// Keep the trial last written to `path`; it passed validation.
+    fn keep(&self, _path: &Path) -> TraitError<()> {
+        Ok(())
+    }
+}
+
+/// The real filesystem.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct FsStore;
+
+impl SourceStore for FsStore {
+    fn read(&self, path: &Path) -> TraitError<String> {
+        ItemBounds::read_source(path)
+    }
+
+    fn write(&self, path: &Path, src: &str) -> TraitError<()> {
+        std::fs::write(path, src).map_err(|e| WinnowerError::io("writing updated", path, e))
+    }
+
+    fn revert(&self, path: &Path, src: &str) -> TraitError<()> {
+        std::fs::write(path, src).map_err(|e| WinnowerError::io("reverting", path, e))
+    }
+}
+
+/// Files held in memory, for driving the prune loop without touching the disk.
+#[derive(Debug, Default)]
+pub struct MemoryStore {
+    files: RefCell<HashMap<PathBuf, String>>,
+    writes: Cell<usize>,
+    reverts: Cell<usize>,
+    fail_write: Option<usize>,
+}
+
+impl MemoryStore {
+    /// An empty store.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// The same store, holding `src` at `path`.
+    pub fn with_file(self, path: impl Into<PathBuf>, src: impl Into<String>) -> Self {
+        self.files.borrow_mut().insert(path.into(), src.into());
+        self
+    }
+
+    /// The same store, failing its `n`th write (counting from 1) like a full disk would.
+    pub fn failing_write(mut self, n: usize) -> Self {
+        self.fail_write = Some(n);
+        self
+    }
+
+    /// The current text of `path`, if the store holds it.
+    pub fn get(&self, path: &Path) -> Option<String> {
+        self.files.borrow().get(path).cloned()
+    }
+
+    /// Trial writes so far, failed ones included.
+    pub fn writes(&self) -> usize {
+        self.writes.get()
+    }
+
+    /// Reverts so far.
+    pub fn reverts(&self) -> usize {
+        self.reverts.get()
+    }
+}
+
+impl SourceStore for MemoryStore {
+    fn read(&self, path: &Path) -> TraitError<String> {
+        self.get(path)
+            .ok_or_else(|| WinnowerError::io("reading", path, io::ErrorKind::NotFound.into()))
+    }
+
+    fn write(&self, path: &Path, src: &str) -> TraitError<()> {
+        self.writes.set(self.writes.get() + 1);
+        if self.fail_write == Some(self.writes.get()) {
+            let e = io::Error::other("simulated write failure");
+            return Err(WinnowerError::io("writing updated", path, e));
+        }
+        self.files
+            .borrow_mut()
+            .insert(path.to_path_buf(), src.into());
+        Ok(())
+    }
+
+    fn revert(&self, path: &Path, src: &str) -> TraitError<()> {
+        self.reverts.set(self.reverts.get() + 1);
+        self.files
+            .borrow_mut()
+            .insert(path.to_path_buf(), src.into());
+        Ok(())
+    }
+}
@@ -0,0 +1,191 @@
+// src/dynamic_analysis/test_only.rs
+//! Retained bounds that only `#[cfg(test)]` code needs.
+//!
+//! A bound on a public function that only the unit tests of its file exercise still fails
+//! its trial when validation builds test targets (`--all-targets`), so production callers
+//! keep paying for a requirement the tests imposed. With `--explain-test-only`,
+//! [`explain_test_only`] tries every retained bound again, alone, with a check that
+//! leaves test targets out (see [`without_test_targets`]), and marks those that pass as
+//! [`Removal::test_only`]. Bounds of items that check never builds, such as those of
+//! `mod tests` itself, would pass vacuously and are not tried. Nothing moves: the tree is
+//! restored after each trial, and restructuring the test or adding a test-only helper is
+//! left to the user.
+
+#![deny(missing_docs)]
+
+use crate::cancel::CancelToken;
+use crate::config::CargoCheckConfig;
+use crate::dynamic_analysis::common::CargoCheck;
+use crate::error::{TraitError, WinnowerError};
+use crate::plan::{ApplyOptions, EntryStatus, Plan, PlanEntry, apply_plan_with};
+use crate::report::{Removal, RemovalStatus, Report};
+use std::path::Path;
+
+/// What the report says about a bound [`explain_test_only`] marks.
+pub const TEST_ONLY_NOTE: &str =
+    "bound required only by tests; consider restructuring the test or adding a test-only helper";
+
+/// `config` with the test targets its arguments select left out: `--all-targets` becomes
+/// `--lib --bins --examples`, and `--tests`, `--benches`, `--test NAME` and
+/// `--bench NAME` go, as benches build with `cfg(test)` too. `None` when it builds no
+/// test targets, so no bound it keeps can be there for tests alone.
+pub fn without_test_targets(config: &CargoCheckConfig) -> Option<CargoCheckConfig> {
+    let mut args = Vec::new();
+    let mut tests = false;
+    let mut iter = config.args.iter();
+    while let Some(arg) = iter.next() {
+        match arg.as_str() {
+            "--all-targets" => {
+                args.extend(["--lib", "--bins", "--examples"].map(String::from));
+                tests = true;
+            }
+            "--tests" | "--benches" => tests = true,
+            "--test" | "--bench" => {
+                iter.next();
+                tests = true;
+            }
+            a if a.starts_with("--test=") || a.starts_with("--bench=") => tests = true,
+            _ => args.push(arg.clone()),
+        }
+    }
+    args.dedup();
+    tests.then(|| CargoCheckConfig {
+        args,
+        ..config.clone()
+    })
+}
+
+/// Try again, in the tree at `root` validated with `cargo check` as `config` (a
+/// configuration without test targets) says, every retained removal of `report` that
+/// `config` builds and no unlock chain already removes, and mark the removals of those
+/// that pass as [`Removal::test_only`]. Declarations of one bound on one item go
+/// together. Returns how many bounds were marked; the tree is restored after each trial.
+pub fn explain_test_only(
+    root: &Path,
+    report: &mut Report,
+    config: &CargoCheckConfig,
+    cancel: &CancelToken,
+) -> TraitError<usize> {
+    let validator = CargoCheck::new(config.clone());
+    // Retained bounds, one entry per bound of an item: (file index, removal index) of its
+    // declarations.
+    let mut groups: Vec<Vec<(usize, usize)>> = Vec::new();
+    for (fi, file) in report.files.iter().enumerate() {
+        for (ri, r) in file.removals.iter().enumerate() {
+            if r.status != RemovalStatus::Retained
+                || unlocked(report, r)
+                || !builds(config, &r.finding.cfg)
+            {
+                continue;
+            }
+            let same = |&(gf, gr): &(usize, usize)| {
+                let g = &report.files[gf].removals[gr].finding;
+                g.locator == r.finding.locator && g.candidate.key() == r.finding.candidate.key()
+            };
+            match groups.iter_mut().find(|group| group.iter().any(same)) {
+                Some(group) => group.push((fi, ri)),
+                None => groups.push(vec![(fi, ri)]),
+            }
+        }
+    }
+
+    let mut marked = 0;
+    for group in groups {
+        if cancel.is_cancelled() {
+            break;
+        }
+        let removal = |&(fi, ri): &(usize, usize)| &report.files[fi].removals[ri];
+        let mut entries: Vec<PlanEntry> = Vec::new();
+        for r in group.iter().map(removal) {
+            if !entries.iter().any(|e| e.candidate == r.finding.candidate) {
+                entries.push(PlanEntry {
+                    locator: r.finding.locator.clone(),
+                    candidate: r.finding.candidate.clone(),
+                });
+            }
+        }
+        let path = root.join(&entries[0].locator.file);
+        let before = std::fs::read(&path).map_err(|e| WinnowerError::io("reading", &path, e))?;
+        let plan = Plan {
+            fingerprint: None,
+            entries,
+        };
+        let options = ApplyOptions {
+            validator: Some(&validator),
+            ..ApplyOptions::default()
+        };
+        let applied = apply_plan_with(root, &plan, &options);
+        std::fs::write(&path, &before).map_err(|e| WinnowerError::io("restoring", &path, e))?;
+        let applied = applied?;
+        let passed = !applied.reverted
+            && applied.validation.as_ref().is_some_and(|v| v.success)
+            && applied
+                .entries
+                .iter()
+                .all(|e| e.status == EntryStatus::Applied);
+        if passed {
+            for (fi, ri) in group {
+                report.files[fi].removals[ri].test_only = true;
+            }
+            marked += 1;
+        }
+    }
+    Ok(marked)
+}
+
+/// Whether `config` may build an item compiled under the `cfg` predicates of a
+/// [`Finding`](crate::report::Finding); predicates that do not parse count as built.
+fn builds(config: &CargoCheckConfig, cfg: &[String]) -> bool {
+    let metas: Vec<syn::Meta> = cfg.iter().filter_map(|c| syn::parse_str(c).ok()).collect();
+    config.builds_cfg(&metas) != Some(false)
+}
+
+/// Whether an unlock chain of `report` removes `r`'s bound.
+fn unlocked(report: &Report, r: &Removal) -> bool {
+    report.unlocks.iter().any(|chain| {
+        chain.finding.locator == r.finding.locator
+            && chain.finding.candidate.key() == r.finding.candidate.key()
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn args(config: Option<CargoCheckConfig>) -> Option<Vec<String>> {
+        config.map(|c| c.args)
+    }
+
+    #[test]
+    fn test_targets_are_left_out() {
+        let config = |args: &[&str]| CargoCheckConfig {
+            args: args.iter().map(|a| a.to_string()).collect(),
+            ..CargoCheckConfig::default()
+        };
+        assert_eq!(
+            args(without_test_targets(&config(&[
+                "--workspace",
+                "--all-targets",
+                "--quiet"
+            ]))),
+            Some(
+                ["--workspace", "--lib", "--bins", "--examples", "--quiet"]
+                    .map(String::from)
+                    .to_vec()
+            )
+        );
+        assert_eq!(
+            args(without_test_targets(&config(&[
+                "--lib",
+                "--test",
+                "it",
+                "--bench=speed"
+            ]))),
+            Some(vec!["--lib".to_owned()])
+        );
+        assert_eq!(
+            args(without_test_targets(&config(&["--workspace", "--lib"]))),
+            None
+        );
+    }
+}
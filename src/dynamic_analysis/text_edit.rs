@@ -0,0 +1,401 @@
+// src/dynamic_analysis/text_edit.rs
+//! Bound removals as byte-range edits on the original source, computed without writing.
+
+#![deny(missing_docs)]
+
+use crate::analysis::{ItemBounds, ItemRef, UTF8_BOM};
+use crate::dynamic_analysis::common::{OwnedBoundCandidate, OwnedBoundSite, is_principal_trait};
+use crate::error::TraitError;
+use crate::type_path::TypePath;
+use proc_macro2::LineColumn;
+use quote::ToTokens;
+use serde::{Deserialize, Serialize};
+use syn::punctuated::Punctuated;
+use syn::spanned::Spanned;
+use syn::token::Comma;
+use syn::{FnArg, GenericParam, Type, TypeParamBound, WherePredicate};
+
+/// Replace the bytes `start..end` of a source text with `replacement`.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct TextEdit {
+    /// Byte offset where the replaced range starts.
+    pub start: usize,
+    /// Byte offset one past the end of the replaced range.
+    pub end: usize,
+    /// The new text; empty for deletions.
+    pub replacement: String,
+}
+
+/// The edits removing `candidates` from `file_src`, sorted and non-overlapping.
+///
+/// Each edit deletes a bound together with the punctuation joining it to its neighbours
+/// (`: `, ` + `, `, `), and whole predicates or `where` clauses once they are empty, so the
+/// result parses to the same tree the pruner writes. A candidate applies to every item of
+/// the file declaring that bound at that site; nothing is written to disk.
+pub fn propose_edits(
+    file_src: &str,
+    candidates: &[OwnedBoundCandidate],
+) -> TraitError<Vec<TextEdit>> {
+    let file = syn::parse_file(file_src)?;
+    let items = ItemBounds::collect_items_in_file(&file)?;
+    Ok(item_edits(
+        file_src,
+        items.iter_all_items().map(|key| (key.item(), candidates)),
+    ))
+}
+
+/// The edits removing, from each item's generics, arguments, return type and fields, the
+/// candidates paired with it. `file_src` is the text the items were parsed from.
+pub(crate) fn item_edits<'g>(
+    file_src: &str,
+    items: impl IntoIterator<Item = (&'g ItemRef<'g>, &'g [OwnedBoundCandidate])>,
+) -> Vec<TextEdit> {
+    let offsets = ByteOffsets::new(file_src);
+    let mut ranges = Vec::new();
+    for (item, candidates) in items {
+        generics_ranges(item.generics(), candidates, &offsets, &mut ranges);
+        if let Some(inputs) = item.inputs() {
+            impl_trait_arg_ranges(inputs, candidates, &offsets, &mut ranges);
+        }
+        opaque_type_ranges(item, candidates, &offsets, &mut ranges);
+    }
+    ranges.sort_unstable();
+    // A bound removed whole takes the types nested in it along, removals in them included.
+    let mut reach = 0;
+    ranges.retain(|&(start, end)| {
+        let outside = start >= reach;
+        reach = reach.max(end);
+        outside
+    });
+    ranges
+        .into_iter()
+        .map(|(start, end)| TextEdit {
+            start,
+            end,
+            replacement: String::new(),
+        })
+        .collect()
+}
+
+/// Apply `edits` to `src`. The edits must be sorted and non-overlapping, as
+/// [`propose_edits`] returns them.
+pub fn apply_edits(src: &str, edits: &[TextEdit]) -> String {
+    let mut out = String::with_capacity(src.len());
+    let mut pos = 0;
+    for edit in edits {
+        debug_assert!(pos <= edit.start && edit.start <= edit.end, "{edit:?}");
+        out.push_str(&src[pos..edit.start]);
+        out.push_str(&edit.replacement);
+        pos = edit.end;
+    }
+    out.push_str(&src[pos..]);
+    out
+}
+
+/// `rewritten` with its header replaced by the header of `original`, byte for byte. The
+/// header is the leading BOM, shebang line and inner attributes (`#![...]`, `//!`) with
+/// whatever lies between them; `prettyplease` drops the BOM and reformats the rest.
+pub(crate) fn with_header_of(original: &str, rewritten: &str) -> TraitError<String> {
+    let keep = header_end(original, &syn::parse_file(original)?);
+    let drop = header_end(rewritten, &syn::parse_file(rewritten)?);
+    Ok(format!("{}{}", &original[..keep], &rewritten[drop..]))
+}
+
+/// Byte offset just past the header of `src` (see [`with_header_of`]).
+fn header_end(src: &str, file: &syn::File) -> usize {
+    let offsets = ByteOffsets::new(src);
+    // syn keeps the newline ending the shebang in the parsed text, so lines still match.
+    let shebang_end = offsets.base + file.shebang.as_ref().map_or(0, String::len);
+    file.attrs
+        .iter()
+        .filter(|a| matches!(a.style, syn::AttrStyle::Inner(_)))
+        .map(|a| offsets.end(a))
+        .fold(shebang_end, usize::max)
+}
+
+/// Maps proc-macro2 positions (1-based line, char column) to byte offsets in the source.
+pub(crate) struct ByteOffsets<'a> {
+    body: &'a str,
+    base: usize,
+    line_starts: Vec<usize>,
+}
+
+impl<'a> ByteOffsets<'a> {
+    /// Index `src`; syn parses it without its leading BOM, so positions start after it.
+    pub(crate) fn new(src: &'a str) -> Self {
+        let base = if src.starts_with(UTF8_BOM) {
+            UTF8_BOM.len()
+        } else {
+            0
+        };
+        let body = &src[base..];
+        let line_starts = std::iter::once(0)
+            .chain(body.match_indices('\n').map(|(i, _)| i + 1))
+            .collect();
+        Self {
+            body,
+            base,
+            line_starts,
+        }
+    }
+
+    fn at(&self, pos: LineColumn) -> usize {
+        let start = self.line_starts[pos.line.saturating_sub(1)];
+        let line = &self.body[start..];
+        let column = line
+            .char_indices()
+            .nth(pos.column)
+            .map_or(line.len(), |(i, _)| i);
+        self.base + start + column
+    }
+
+    pub(crate) fn start<T: Spanned>(&self, node: &T) -> usize {
+        self.at(node.span().start())
+    }
+
+    pub(crate) fn end<T: Spanned>(&self, node: &T) -> usize {
+        self.at(node.span().end())
+    }
+}
+
+fn tokens<T: ToTokens>(node: &T) -> String {
+    node.to_token_stream().to_string()
+}
+
+/// Byte ranges removing the requested bounds of one item's generics.
+fn generics_ranges(
+    generics: &syn::Generics,
+    candidates: &[OwnedBoundCandidate],
+    offsets: &ByteOffsets<'_>,
+    out: &mut Vec<(usize, usize)>,
+) {
+    for (param_index, param) in generics.params.iter().enumerate() {
+        let GenericParam::Type(tp) = param else {
+            continue;
+        };
+        let removed = requested(&tp.bounds, candidates, |site| {
+            matches!(site, OwnedBoundSite::TypeParam { ident, param_index: p, .. }
+                if *p == param_index && tp.ident == ident)
+        });
+        if removed.is_empty() {
+            continue;
+        }
+        if removed.len() == tp.bounds.len() {
+            // `T: A + B` -> `T`
+            out.push((offsets.end(&tp.ident), end_with_punct(&tp.bounds, offsets)));
+        } else {
+            out.extend(run_ranges(&tp.bounds, &removed, offsets));
+        }
+    }
+
+    let Some(wc) = &generics.where_clause else {
+        return;
+    };
+    let mut dropped = Vec::new();
+    for (pred_index, pred) in wc.predicates.iter().enumerate() {
+        let WherePredicate::Type(pt) = pred else {
+            continue;
+        };
+        let bounded = tokens(&pt.bounded_ty);
+        let removed = requested(&pt.bounds, candidates, |site| {
+            matches!(site, OwnedBoundSite::WhereClause { ty, pred_index: p, .. }
+                if *p == pred_index && *ty == bounded)
+        });
+        if removed.is_empty() {
+            continue;
+        }
+        if removed.len() == pt.bounds.len() {
+            dropped.push(pred_index);
+        } else {
+            out.extend(run_ranges(&pt.bounds, &removed, offsets));
+        }
+    }
+    if dropped.is_empty() {
+        return;
+    }
+    if dropped.len() == wc.predicates.len() {
+        // Drop the whole clause along with the whitespace in front of `where`.
+        let kept = offsets.body[..offsets.start(&wc.where_token) - offsets.base].trim_end();
+        out.push((
+            offsets.base + kept.len(),
+            end_with_punct(&wc.predicates, offsets),
+        ));
+    } else {
+        out.extend(run_ranges(&wc.predicates, &dropped, offsets));
+    }
+}
+
+/// Byte ranges removing the requested bounds of argument-position `impl Trait` types. Like
+/// the pruner, a removal that would leave no trait bound is refused; of several, the one
+/// of the first trait bound.
+fn impl_trait_arg_ranges(
+    inputs: &Punctuated<FnArg, Comma>,
+    candidates: &[OwnedBoundCandidate],
+    offsets: &ByteOffsets<'_>,
+    out: &mut Vec<(usize, usize)>,
+) {
+    for (arg_index, arg) in inputs.iter().enumerate() {
+        let FnArg::Typed(pt) = arg else {
+            continue;
+        };
+        let Type::ImplTrait(it) = &*pt.ty else {
+            continue;
+        };
+        let pat = tokens(&pt.pat);
+        let requested = requested(&it.bounds, candidates, |site| {
+            matches!(site, OwnedBoundSite::ImplTraitArg { arg, arg_index: a, .. }
+                if *a == arg_index && *arg == pat)
+        });
+        let removed = keeping_a_trait(&it.bounds, requested, false);
+        if !removed.is_empty() {
+            out.extend(run_ranges(&it.bounds, &removed, offsets));
+        }
+    }
+}
+
+/// Ranges removing return-position `impl Trait` and `dyn Trait` bounds of `item`, refusing
+/// as [`Remove`](crate::dynamic_analysis::common::Remove) does: a trait object's principal
+/// trait stays, and each type keeps a trait bound.
+fn opaque_type_ranges(
+    item: &ItemRef<'_>,
+    candidates: &[OwnedBoundCandidate],
+    offsets: &ByteOffsets<'_>,
+    out: &mut Vec<(usize, usize)>,
+) {
+    let mut paths: Vec<&TypePath> = candidates
+        .iter()
+        .filter_map(|c| match &c.site {
+            OwnedBoundSite::ReturnImplTrait { path, .. }
+            | OwnedBoundSite::TraitObject { path, .. } => Some(path),
+            _ => None,
+        })
+        .collect();
+    paths.sort_by_key(|p| p.to_string());
+    paths.dedup();
+    for path in paths {
+        let Some(ty) = item
+            .type_root(path.root)
+            .and_then(|root| path.resolve(root))
+        else {
+            continue;
+        };
+        let (bounds, object) = match ty {
+            Type::ImplTrait(it) => (&it.bounds, false),
+            Type::TraitObject(to) => (&to.bounds, true),
+            _ => continue,
+        };
+        let requested = requested(bounds, candidates, |site| match site {
+            OwnedBoundSite::ReturnImplTrait { path: p, .. } => !object && p == path,
+            OwnedBoundSite::TraitObject { path: p, .. } => object && p == path,
+            _ => false,
+        });
+        let removed = keeping_a_trait(bounds, requested, object);
+        if !removed.is_empty() {
+            out.extend(run_ranges(bounds, &removed, offsets));
+        }
+    }
+}
+
+/// The `requested` indices (ascending) whose removal leaves `bounds` a trait bound and,
+/// with `keep_principal`, the principal trait of a trait object. Refusals go last first,
+/// as the tree mutation's do, so the trait bound kept is the first one.
+fn keeping_a_trait<P>(
+    bounds: &Punctuated<TypeParamBound, P>,
+    requested: Vec<usize>,
+    keep_principal: bool,
+) -> Vec<usize> {
+    let mut traits_left = bounds
+        .iter()
+        .filter(|b| matches!(b, TypeParamBound::Trait(_)))
+        .count();
+    let mut removed: Vec<usize> = requested
+        .into_iter()
+        .rev()
+        .filter(|&i| {
+            if keep_principal && is_principal_trait(&bounds[i]) {
+                return false;
+            }
+            if !matches!(bounds[i], TypeParamBound::Trait(_)) {
+                return true;
+            }
+            traits_left -= 1;
+            if traits_left == 0 {
+                traits_left = 1;
+                return false;
+            }
+            true
+        })
+        .collect();
+    removed.reverse();
+    removed
+}
+
+/// Indices of the bounds in `bounds` that some candidate at a matching site asks to remove.
+fn requested<P>(
+    bounds: &Punctuated<syn::TypeParamBound, P>,
+    candidates: &[OwnedBoundCandidate],
+    site_matches: impl Fn(&OwnedBoundSite) -> bool,
+) -> Vec<usize> {
+    bounds
+        .iter()
+        .enumerate()
+        .filter(|(i, bound)| {
+            candidates.iter().any(|c| {
+                c.site.bound_index() == *i
+                    && site_matches(&c.site)
+                    && c.bound_text == tokens(*bound)
+            })
+        })
+        .map(|(i, _)| i)
+        .collect()
+}
+
+/// End of the last element of `list`, including a trailing separator.
+fn end_with_punct<T: Spanned, P: Spanned>(
+    list: &Punctuated<T, P>,
+    offsets: &ByteOffsets<'_>,
+) -> usize {
+    match list.pairs().last() {
+        Some(pair) => match pair.punct() {
+            Some(p) => offsets.end(*p),
+            None => offsets.end(*pair.value()),
+        },
+        None => 0,
+    }
+}
+
+/// Ranges removing the elements at `removed` (sorted, not all of `list`) with their separators.
+///
+/// A run of removed elements followed by a kept one is cut up to that element's start
+/// (`A + B + C` -> `C`); a run reaching the end is cut from the previous element's end
+/// (`A + B + C` -> `A`), taking a trailing separator with it.
+pub(crate) fn run_ranges<T: Spanned, P: Spanned>(
+    list: &Punctuated<T, P>,
+    removed: &[usize],
+    offsets: &ByteOffsets<'_>,
+) -> Vec<(usize, usize)> {
+    let values: Vec<&T> = list.iter().collect();
+    let mut out = Vec::new();
+    let mut k = 0;
+    while k < removed.len() {
+        let first = removed[k];
+        let mut last = first;
+        while k + 1 < removed.len() && removed[k + 1] == last + 1 {
+            k += 1;
+            last += 1;
+        }
+        k += 1;
+        if last + 1 < values.len() {
+            out.push((
+                offsets.start(values[first]),
+                offsets.start(values[last + 1]),
+            ));
+        } else {
+            out.push((
+                offsets.end(values[first - 1]),
+                end_with_punct(list, offsets),
+            ));
+        }
+    }
+    out
+}
@@ -0,0 +1,387 @@
+// src/dynamic_analysis/trial_cache.rs
+//! Outcomes of trials kept by what they checked, for later runs and other machines.
+//!
+//! A trial's key hashes everything its `cargo check` reads from the workspace: the text of
+//! every file of the packages it builds and of the workspace packages those depend on, with
+//! the trial's edit in place; the manifests of those files and `Cargo.lock`; the toolchain
+//! and the check configuration. Hashing every file of the package, not only the edited one,
+//! keeps an entry from outliving the code that made it true: a removal from one file stops
+//! building once another file of the package relies on the bound. The candidate needs no
+//! coordinates of its own, as the edited text already says which bound went.
+//!
+//! Entries are small JSON files named by their key under
+//! [`TrialCacheConfig::dir`], so a CI job can keep the directory as a cache artifact. A hit
+//! bumps the entry's mtime and [`TrialCache::evict`] drops the least recently used entries
+//! once the directory outgrows [`TrialCacheConfig::max_cache_mb`].
+
+#![deny(missing_docs)]
+
+use crate::cancel::CancelToken;
+use crate::config::{CargoCheckConfig, TrialCacheConfig};
+use crate::crate_map::CrateMap;
+use crate::dynamic_analysis::common::{CargoCheck, FollowUp, ValidationOutcome};
+use crate::dynamic_analysis::validate::Validator;
+use crate::error::{TraitError, WinnowerError};
+use crate::report::write_atomic;
+use serde::{Deserialize, Serialize};
+use std::path::{Path, PathBuf};
+use std::time::{Instant, SystemTime};
+
+/// Bumped whenever what goes into a key or an entry changes.
+const VERSION: u32 = 2;
+
+/// What a trial's check said, as stored.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+struct Entry {
+    success: bool,
+    code: Option<i32>,
+    stderr: String,
+    scope: Vec<String>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    follow_ups: Vec<FollowUp>,
+}
+
+impl From<&ValidationOutcome> for Entry {
+    fn from(outcome: &ValidationOutcome) -> Self {
+        Self {
+            success: outcome.success,
+            code: outcome.code,
+            stderr: outcome.stderr.clone(),
+            scope: outcome.scope.clone(),
+            follow_ups: outcome.follow_ups.clone(),
+        }
+    }
+}
+
+/// CRC32 and 64-bit FNV-1a side by side: enough to tell trials apart, not to resist forged
+/// collisions. The cache directory is trusted as much as `target/` is.
+#[derive(Clone)]
+struct Digest {
+    crc: crc32fast::Hasher,
+    fnv: u64,
+}
+
+impl Digest {
+    fn new() -> Self {
+        Self {
+            crc: crc32fast::Hasher::new(),
+            fnv: 0xcbf2_9ce4_8422_2325,
+        }
+    }
+
+    /// Add `bytes` as one field, length first, so neighbouring fields cannot run together.
+    fn field(&mut self, bytes: &[u8]) {
+        self.update(&(bytes.len() as u64).to_le_bytes());
+        self.update(bytes);
+    }
+
+    fn update(&mut self, bytes: &[u8]) {
+        self.crc.update(bytes);
+        for &b in bytes {
+            self.fnv = (self.fnv ^ u64::from(b)).wrapping_mul(0x0100_0000_01b3);
+        }
+    }
+
+    fn finish(self) -> String {
+        format!("{:08x}{:016x}", self.crc.finalize(), self.fnv)
+    }
+}
+
+/// How much a trial cache holds.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct TrialCacheStats {
+    /// Stored trials.
+    pub entries: usize,
+    /// Their size on disk.
+    pub bytes: u64,
+}
+
+/// A directory of trial outcomes (see the module docs).
+#[derive(Debug, Clone)]
+pub struct TrialCache {
+    dir: PathBuf,
+    max_bytes: u64,
+}
+
+impl TrialCache {
+    /// The cache `config` places under the crate at `root`.
+    pub fn open(root: &Path, config: &TrialCacheConfig) -> Self {
+        Self {
+            dir: config.dir(root),
+            max_bytes: config.max_cache_mb.saturating_mul(1024 * 1024),
+        }
+    }
+
+    /// The cache directory.
+    pub fn dir(&self) -> &Path {
+        &self.dir
+    }
+
+    fn path(&self, key: &str) -> PathBuf {
+        self.dir.join(&key[..2]).join(format!("{key}.json"))
+    }
+
+    /// The outcome stored under `key`, marked as just used; `None` when there is none or it
+    /// does not parse.
+    fn get(&self, key: &str) -> Option<Entry> {
+        let path = self.path(key);
+        let entry = serde_json::from_slice(&std::fs::read(&path).ok()?).ok()?;
+        // Best effort: a read-only cache still answers, it only stops ageing.
+        if let Ok(file) = std::fs::File::options().write(true).open(&path) {
+            let _ = file.set_modified(SystemTime::now());
+        }
+        Some(entry)
+    }
+
+    /// Store `entry` under `key`. It is written beside its place and renamed into it, so
+    /// runs sharing the directory never read half an entry.
+    fn put(&self, key: &str, entry: &Entry) -> TraitError<()> {
+        let json = serde_json::to_vec(entry)
+            .map_err(|e| WinnowerError::Internal(format!("serializing a trial: {e}")))?;
+        write_atomic(&self.path(key), &json)
+    }
+
+    /// Every entry with its size and mtime. Only `<xx>/<key>.json` files count, so a
+    /// directory shared with anything else keeps the rest.
+    fn entries(&self) -> Vec<(PathBuf, u64, SystemTime)> {
+        let Ok(shards) = std::fs::read_dir(&self.dir) else {
+            return Vec::new();
+        };
+        shards
+            .flatten()
+            .filter(|s| s.file_name().len() == 2 && s.path().is_dir())
+            .filter_map(|s| std::fs::read_dir(s.path()).ok())
+            .flat_map(|files| files.flatten())
+            .filter(|f| f.path().extension().is_some_and(|e| e == "json"))
+            .filter_map(|f| {
+                let meta = f.metadata().ok()?;
+                Some((f.path(), meta.len(), meta.modified().ok()?))
+            })
+            .collect()
+    }
+
+    /// How many entries the cache holds and their size.
+    pub fn stats(&self) -> TrialCacheStats {
+        let entries = self.entries();
+        TrialCacheStats {
+            entries: entries.len(),
+            bytes: entries.iter().map(|(_, len, _)| len).sum(),
+        }
+    }
+
+    /// Remove every entry; returns how many there were.
+    pub fn clear(&self) -> TraitError<usize> {
+        let entries = self.entries();
+        for (path, _, _) in &entries {
+            std::fs::remove_file(path).map_err(|e| WinnowerError::io("removing", path, e))?;
+            if let Some(shard) = path.parent() {
+                // Fails while other entries are left in it.
+                let _ = std::fs::remove_dir(shard);
+            }
+        }
+        Ok(entries.len())
+    }
+
+    /// Remove the least recently used entries until the rest fit in `max_cache_mb`;
+    /// returns how many went.
+    pub fn evict(&self) -> TraitError<usize> {
+        let mut entries = self.entries();
+        let mut total: u64 = entries.iter().map(|(_, len, _)| len).sum();
+        entries.sort_by_key(|(_, _, used)| *used);
+        let mut removed = 0;
+        for (path, len, _) in entries {
+            if total <= self.max_bytes {
+                break;
+            }
+            match std::fs::remove_file(&path) {
+                Ok(()) => removed += 1,
+                // Another run sharing the directory got there first.
+                Err(e) if e.kind() == std::io::ErrorKind::NotFound => {}
+                Err(e) => return Err(WinnowerError::io("removing", &path, e)),
+            }
+            total -= len;
+        }
+        Ok(removed)
+    }
+}
+
+/// A [`CargoCheck`] answering from a [`TrialCache`] where it can and filling it where it
+/// cannot.
+pub struct CachedCheck<'a> {
+    check: CargoCheck,
+    cache: &'a TrialCache,
+    /// The files the key hashes, relative to the crate root where they are under it:
+    /// sources, their manifests and `Cargo.lock`.
+    files: Vec<PathBuf>,
+    /// What the key holds besides those files.
+    base: Digest,
+}
+
+impl<'a> CachedCheck<'a> {
+    /// `check` of trials editing `edited`, in the crate at `root` that `map` describes,
+    /// validated with `toolchain` (see [`Toolchain`](crate::dynamic_analysis::common::Toolchain)).
+    pub fn new(
+        cache: &'a TrialCache,
+        check: CargoCheck,
+        map: &CrateMap,
+        edited: &[&Path],
+        root: &Path,
+        toolchain: &str,
+    ) -> Self {
+        let root = root.canonicalize().unwrap_or_else(|_| root.to_path_buf());
+        let mut files = map.sources(check.packages());
+        files.extend(
+            edited
+                .iter()
+                .map(|p| p.canonicalize().unwrap_or_else(|_| p.to_path_buf())),
+        );
+        let manifests: Vec<PathBuf> = files
+            .iter()
+            .filter_map(|f| {
+                f.ancestors()
+                    .skip(1)
+                    .take_while(|d| d.starts_with(&root))
+                    .map(|d| d.join("Cargo.toml"))
+                    .find(|m| m.is_file())
+            })
+            .collect();
+        files.extend(manifests);
+        files.push(root.join("Cargo.toml"));
+        files.push(root.join("Cargo.lock"));
+        // Read relative to the tree being validated, which may be a copy of `root`.
+        let mut files: Vec<PathBuf> = files
+            .into_iter()
+            .map(|f| f.strip_prefix(&root).map_or(f.clone(), Path::to_path_buf))
+            .collect();
+        files.sort();
+        files.dedup();
+
+        let mut base = Digest::new();
+        base.field(&VERSION.to_le_bytes());
+        base.field(toolchain.as_bytes());
+        // Where it builds does not change what a check says.
+        let config = CargoCheckConfig {
+            target_dir: PathBuf::new(),
+            ..check.config().clone()
+        };
+        base.field(
+            serde_json::to_string(&config)
+                .unwrap_or_default()
+                .as_bytes(),
+        );
+        base.field(check.check_args().join(" ").as_bytes());
+        if !check.lint_args().is_empty() {
+            base.field(check.lint_args().join(" ").as_bytes());
+        }
+        base.field(std::env::var("RUSTFLAGS").unwrap_or_default().as_bytes());
+        Self {
+            check,
+            cache,
+            files,
+            base,
+        }
+    }
+
+    /// The key of the tree at `root` as it is on disk now. A missing file hashes like an
+    /// empty one.
+    fn key(&self, root: &Path) -> String {
+        let mut digest = self.base.clone();
+        for file in &self.files {
+            digest.field(file.to_string_lossy().as_bytes());
+            digest.field(&std::fs::read(root.join(file)).unwrap_or_default());
+        }
+        digest.finish()
+    }
+}
+
+impl Validator for CachedCheck<'_> {
+    fn validate(&self, root: &Path) -> TraitError<ValidationOutcome> {
+        self.validate_cancellable(root, &CancelToken::new())
+    }
+
+    fn validate_cancellable(
+        &self,
+        root: &Path,
+        cancel: &CancelToken,
+    ) -> TraitError<ValidationOutcome> {
+        let started = Instant::now();
+        let key = self.key(root);
+        if let Some(entry) = self.cache.get(&key) {
+            return Ok(ValidationOutcome {
+                success: entry.success,
+                code: entry.code,
+                stderr: entry.stderr,
+                scope: entry.scope,
+                follow_ups: entry.follow_ups,
+                elapsed: started.elapsed(),
+                cached: true,
+                ..ValidationOutcome::default()
+            });
+        }
+        let outcome = self.check.validate_cancellable(root, cancel)?;
+        if !cancel.is_cancelled() {
+            let entry = Entry::from(&outcome);
+            // The check may have written `Cargo.lock`: in place it stays for the next run,
+            // in a worktree it goes with the copy. Either tree should find the entry.
+            let after = self.key(root);
+            if after != key {
+                self.cache.put(&after, &entry)?;
+            }
+            self.cache.put(&key, &entry)?;
+        }
+        Ok(outcome)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::time::Duration;
+
+    fn entry(success: bool) -> Entry {
+        Entry {
+            success,
+            code: Some(i32::from(!success)),
+            stderr: "x".repeat(600 * 1024),
+            scope: Vec::new(),
+            follow_ups: Vec::new(),
+        }
+    }
+
+    #[test]
+    fn eviction_drops_the_least_recently_used_entries() -> TraitError<()> {
+        let tmp = tempfile::tempdir()?;
+        let config = TrialCacheConfig {
+            dir: "cache".into(),
+            max_cache_mb: 1,
+        };
+        let cache = TrialCache::open(tmp.path(), &config);
+        let keys = ["aa01", "bb02", "cc03"];
+        let old = SystemTime::now() - Duration::from_secs(3600);
+        for (i, key) in keys.iter().enumerate() {
+            cache.put(key, &entry(i == 0))?;
+            let file = std::fs::File::options().write(true).open(cache.path(key))?;
+            file.set_modified(old + Duration::from_secs(i as u64))?;
+        }
+        // Reading the oldest makes it the most recently used.
+        assert_eq!(cache.get("aa01"), Some(entry(true)));
+        assert_eq!(cache.stats().entries, 3);
+        assert_eq!(cache.evict()?, 2);
+        assert!(cache.get("aa01").is_some());
+        assert!(cache.get("bb02").is_none() && cache.get("cc03").is_none());
+        Ok(())
+    }
+
+    #[test]
+    fn clearing_leaves_files_that_are_not_entries() -> TraitError<()> {
+        let tmp = tempfile::tempdir()?;
+        let cache = TrialCache::open(tmp.path(), &TrialCacheConfig::default());
+        cache.put("ab12", &entry(true))?;
+        std::fs::write(cache.dir().join("README"), "kept")?;
+        assert_eq!(cache.clear()?, 1);
+        assert_eq!(cache.stats(), TrialCacheStats::default());
+        assert!(cache.dir().join("README").is_file());
+        assert!(!cache.dir().join("ab").exists());
+        Ok(())
+    }
+}
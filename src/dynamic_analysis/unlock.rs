@@ -0,0 +1,306 @@
+// src/dynamic_analysis/unlock.rs
+//! Retained bounds that only guarded another, since removed bound.
+//!
+//! In `fn a<T: Clone>() { b::<T>() }` with `fn b<T: Clone>() {}`, trying `a`'s bound
+//! first fails because `b` asks for `Clone`; `b`'s goes later, and `a`'s stays although
+//! nothing needs it any more. rustc names the culprit: its error notes that the bound
+//! was "required by a bound in `b`" and points at it. Each retained removal keeps these
+//! [`Blame`]s. After the prune, [`unlock_chains`] tries again the retained bounds whose
+//! failure blamed a bound that is gone, and reports those that now go as
+//! [`UnlockChain`]s. The tree is left as the prune left it: a chain is a suggestion, and
+//! running `prune` again removes its bounds.
+
+#![deny(missing_docs)]
+
+use crate::cancel::CancelToken;
+use crate::dynamic_analysis::validate::Validator;
+use crate::error::{TraitError, WinnowerError};
+use crate::plan::{ApplyOptions, EntryStatus, Plan, PlanEntry, apply_plan_with};
+use crate::report::{Finding, RemovalStatus, Report};
+use serde::{Deserialize, Serialize};
+use std::path::{Path, PathBuf};
+
+/// A bound a rustc error names as the reason it failed: `required by a bound in `b``,
+/// pointing at the bound.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Blame {
+    /// The item declaring the bound, as rustc names it, e.g. `b` or `S::m`.
+    pub item: String,
+    /// The file, as rustc prints it: relative to the workspace root for its members.
+    pub file: PathBuf,
+    /// 1-based line of the bound.
+    pub line: usize,
+    /// 1-based column (in chars) of the bound.
+    pub column: usize,
+}
+
+impl Blame {
+    /// The bounds the errors in `stderr`, rendered as rustc prints them, blame.
+    pub fn parse(stderr: &str) -> Vec<Self> {
+        const NOTE: &str = "note: required by a bound in `";
+        let mut blames: Vec<Self> = Vec::new();
+        let mut lines = stderr.lines();
+        while let Some(line) = lines.next() {
+            let Some(item) = line.trim_start().strip_prefix(NOTE) else {
+                continue;
+            };
+            let Some(item) = item.strip_suffix('`') else {
+                continue;
+            };
+            let Some(location) = lines
+                .next()
+                .and_then(|l| l.trim_start().strip_prefix("--> "))
+            else {
+                continue;
+            };
+            let mut parts = location.trim().rsplitn(3, ':');
+            let (Some(column), Some(line), Some(file)) = (parts.next(), parts.next(), parts.next())
+            else {
+                continue;
+            };
+            let (Ok(line), Ok(column)) = (line.parse(), column.parse()) else {
+                continue;
+            };
+            let blame = Self {
+                item: item.to_owned(),
+                file: PathBuf::from(file),
+                line,
+                column,
+            };
+            if !blames.contains(&blame) {
+                blames.push(blame);
+            }
+        }
+        blames
+    }
+
+    /// Whether the blamed bound is `finding`'s, by position.
+    fn points_at(&self, finding: &Finding) -> bool {
+        self.in_file_of(finding) && (self.line, self.column) == (finding.line, finding.column)
+    }
+
+    /// Whether the blamed bound is declared by `finding`'s item. Edits between the failed
+    /// trial and the finding's can move the bound; the item keeps its name.
+    fn names_item_of(&self, finding: &Finding) -> bool {
+        let last = |name: &str| {
+            name.rsplit("::")
+                .next()
+                .unwrap_or_default()
+                .trim()
+                .to_owned()
+        };
+        self.in_file_of(finding) && last(&self.item) == last(&finding.locator.name)
+    }
+
+    fn in_file_of(&self, finding: &Finding) -> bool {
+        !finding.locator.file.as_os_str().is_empty() && self.file.ends_with(&finding.locator.file)
+    }
+}
+
+/// A retained bound that goes once the bounds it was blamed on are gone.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct UnlockChain {
+    /// The retained bound; its locator names its file.
+    pub finding: Finding,
+    /// The bounds its failure blamed, removed by the prune or themselves unlocked.
+    pub unlocked_by: Vec<Finding>,
+}
+
+/// The blocked bounds of a retained bound: those removed that `blames` point at, or, when
+/// none does exactly, those removed from the items they name.
+fn blockers(blames: &[Blame], gone: &[Finding]) -> Vec<Finding> {
+    let mut found: Vec<Finding> = Vec::new();
+    for blame in blames {
+        let exact: Vec<&Finding> = gone.iter().filter(|f| blame.points_at(f)).collect();
+        let matched = if exact.is_empty() {
+            gone.iter().filter(|f| blame.names_item_of(f)).collect()
+        } else {
+            exact
+        };
+        for f in matched {
+            if !found.contains(f) {
+                found.push(f.clone());
+            }
+        }
+    }
+    found
+}
+
+/// Try again, in the tree at `root` validated with `validator`, every retained removal of
+/// `report` whose failure blamed a bound the prune removed, and every one blaming a bound
+/// found removable this way, until none is left. Declarations of one bound on one item go
+/// together. The tree is restored before returning.
+pub fn unlock_chains(
+    root: &Path,
+    report: &Report,
+    validator: &dyn Validator,
+    cancel: &CancelToken,
+) -> TraitError<Vec<UnlockChain>> {
+    let mut gone: Vec<Finding> = Vec::new();
+    // Retained bounds with blames, one entry per bound of an item: its declarations.
+    let mut retained: Vec<(Vec<Finding>, Vec<Blame>)> = Vec::new();
+    for r in report.files.iter().flat_map(|f| &f.removals) {
+        match r.status {
+            RemovalStatus::Removed => gone.push(r.finding.clone()),
+            RemovalStatus::Retained if !r.blames.is_empty() => {
+                let f = &r.finding;
+                match retained.iter_mut().find(|(group, _)| {
+                    group[0].locator == f.locator && group[0].candidate.key() == f.candidate.key()
+                }) {
+                    Some((group, blames)) => {
+                        if !group.iter().any(|g| g.candidate == f.candidate) {
+                            group.push(f.clone());
+                        }
+                        for blame in &r.blames {
+                            if !blames.contains(blame) {
+                                blames.push(blame.clone());
+                            }
+                        }
+                    }
+                    None => retained.push((vec![f.clone()], r.blames.clone())),
+                }
+            }
+            _ => {}
+        }
+    }
+
+    let mut chains = Vec::new();
+    let mut tried = vec![false; retained.len()];
+    let mut originals: Vec<(PathBuf, Vec<u8>)> = Vec::new();
+    let outcome = (|| -> TraitError<()> {
+        loop {
+            let mut unlocked = false;
+            for (i, (group, blames)) in retained.iter().enumerate() {
+                if tried[i] || cancel.is_cancelled() {
+                    continue;
+                }
+                let unlocked_by = blockers(blames, &gone);
+                if unlocked_by.is_empty() {
+                    continue;
+                }
+                tried[i] = true;
+                let path = root.join(&group[0].locator.file);
+                let before =
+                    std::fs::read(&path).map_err(|e| WinnowerError::io("reading", &path, e))?;
+                let plan = Plan {
+                    fingerprint: None,
+                    entries: group
+                        .iter()
+                        .map(|f| PlanEntry {
+                            locator: f.locator.clone(),
+                            candidate: f.candidate.clone(),
+                        })
+                        .collect(),
+                };
+                let options = ApplyOptions {
+                    strict: false,
+                    validator: Some(validator),
+                    ..ApplyOptions::default()
+                };
+                let applied = apply_plan_with(root, &plan, &options)?;
+                let passed = !applied.reverted
+                    && applied.validation.as_ref().is_some_and(|v| v.success)
+                    && applied
+                        .entries
+                        .iter()
+                        .all(|e| e.status == EntryStatus::Applied);
+                if !passed {
+                    continue;
+                }
+                if !originals.iter().any(|(p, _)| *p == path) {
+                    originals.push((path, before));
+                }
+                gone.extend(group.iter().cloned());
+                chains.push(UnlockChain {
+                    finding: group[0].clone(),
+                    unlocked_by,
+                });
+                unlocked = true;
+            }
+            if !unlocked {
+                return Ok(());
+            }
+        }
+    })();
+    for (path, original) in &originals {
+        std::fs::write(path, original).map_err(|e| WinnowerError::io("restoring", path, e))?;
+    }
+    outcome.map(|()| chains)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn blames_come_from_required_by_notes() {
+        let stderr = "error[E0277]: the trait bound `T: Clone` is not satisfied
+ --> src/lib.rs:2:9
+  |
+2 |     b::<T>()
+  |         ^ the trait `Clone` is not implemented for `T`
+  |
+note: required by a bound in `b`
+ --> src/lib.rs:5:13
+  |
+5 | pub fn b<T: Clone>() {}
+  |             ^^^^^ required by this bound in `b`
+
+error[E0277]: the trait bound `T: Clone` is not satisfied
+   --> crates/x/src/deep.rs:120:30
+    |
+note: required by a bound in `S::m`
+   --> crates/x/src/deep.rs:140:17
+note: required by a bound in `b`
+ --> src/lib.rs:5:13
+";
+        let blames = Blame::parse(stderr);
+        assert_eq!(
+            blames,
+            [
+                Blame {
+                    item: "b".into(),
+                    file: "src/lib.rs".into(),
+                    line: 5,
+                    column: 13,
+                },
+                Blame {
+                    item: "S::m".into(),
+                    file: "crates/x/src/deep.rs".into(),
+                    line: 140,
+                    column: 17,
+                },
+            ]
+        );
+        assert!(Blame::parse("error: could not compile `a`\n").is_empty());
+    }
+
+    #[test]
+    fn blockers_match_by_position_then_by_item() {
+        let finding = |name: &str, line, column| Finding {
+            line,
+            column,
+            locator: crate::analysis::ItemLocator {
+                file: "src/lib.rs".into(),
+                name: name.into(),
+                ..Default::default()
+            },
+            ..Finding::default()
+        };
+        let gone = [finding("b", 5, 13), finding("b", 5, 20), finding("c", 9, 9)];
+        let blame = |line, column| Blame {
+            item: "b".into(),
+            file: "member/src/lib.rs".into(),
+            line,
+            column,
+        };
+        assert_eq!(blockers(&[blame(5, 13)], &gone), [gone[0].clone()]);
+        // Moved since: every removed bound of `b`.
+        assert_eq!(blockers(&[blame(4, 13)], &gone), gone[..2]);
+        let elsewhere = Blame {
+            file: "src/main.rs".into(),
+            ..blame(5, 13)
+        };
+        assert!(blockers(&[elsewhere], &gone).is_empty());
+    }
+}
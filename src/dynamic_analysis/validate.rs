@@ -0,0 +1,373 @@
+// src/dynamic_analysis/validate.rs
+//! Validation of edited trees: does the crate still build after a removal?
+
+#![deny(missing_docs)]
+
+use crate::cancel::CancelToken;
+use crate::dynamic_analysis::common::{CargoCheck, Exit, ValidationOutcome};
+use crate::dynamic_analysis::lock_wait::{self, LockNotice};
+use crate::error::{TraitError, WinnowerError};
+use std::cell::{Cell, RefCell};
+use std::collections::VecDeque;
+use std::io::{BufRead, BufReader, Read};
+use std::path::{Path, PathBuf};
+use std::process::{Child, Command, Output, Stdio};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, OnceLock};
+use std::time::{Duration, Instant};
+
+/// How often a running validation checks for cancellation.
+const CANCEL_POLL: Duration = Duration::from_millis(20);
+
+/// Decides whether the tree under `root` is still valid after an edit.
+pub trait Validator {
+    /// Validate the tree rooted at `root`.
+    fn validate(&self, root: &Path) -> TraitError<ValidationOutcome>;
+
+    /// Like [`Validator::validate`], but may stop early once `cancel` fires, returning an
+    /// unsuccessful outcome. The default runs `validate` to completion.
+    fn validate_cancellable(
+        &self,
+        root: &Path,
+        _cancel: &CancelToken,
+    ) -> TraitError<ValidationOutcome> {
+        self.validate(root)
+    }
+
+    /// How long validating the tree at `root` as it is takes, the yardstick a trial's check
+    /// is called slow against (see [`SlowRemovals`](crate::report::SlowRemovals)). `None`
+    /// when it failed or stopped early, and by default: most validators run no real build.
+    fn baseline(&self, _root: &Path, _cancel: &CancelToken) -> TraitError<Option<Duration>> {
+        Ok(None)
+    }
+}
+
+impl Validator for CargoCheck {
+    fn validate(&self, root: &Path) -> TraitError<ValidationOutcome> {
+        let lock = LockWatch::of(self, root);
+        let never = CancelToken::new();
+        self.run(root, |command| {
+            output_until(command, &never, None, Some(&lock))
+        })
+    }
+
+    fn validate_cancellable(
+        &self,
+        root: &Path,
+        cancel: &CancelToken,
+    ) -> TraitError<ValidationOutcome> {
+        let fatal = self.fatal();
+        let lock = LockWatch::of(self, root);
+        self.run(root, |command| {
+            output_until(command, cancel, fatal, Some(&lock))
+        })
+    }
+
+    fn baseline(&self, root: &Path, cancel: &CancelToken) -> TraitError<Option<Duration>> {
+        let outcome = self.validate_cancellable(root, cancel)?;
+        Ok((outcome.success && !outcome.aborted).then_some(outcome.elapsed))
+    }
+}
+
+/// How [`output_until`] watches cargo wait for a file lock (see [`lock_wait`]).
+struct LockWatch {
+    /// The target directory of the check.
+    dir: PathBuf,
+    notice: Option<LockNotice>,
+    timeout: Option<Duration>,
+}
+
+impl LockWatch {
+    fn of(check: &CargoCheck, root: &Path) -> Self {
+        Self {
+            dir: check.config().target_dir(root),
+            notice: check.lock_notice().cloned(),
+            timeout: check.lock_wait_timeout(),
+        }
+    }
+}
+
+/// Run `command` to completion like [`Command::output`], killing it and the processes it
+/// started instead once `cancel` fires, once `fatal` holds for a line of its stdout (a
+/// build that already failed need not finish), or once it has waited for a file lock
+/// longer than `lock` allows.
+fn output_until(
+    command: &mut Command,
+    cancel: &CancelToken,
+    fatal: Option<fn(&str) -> bool>,
+    lock: Option<&LockWatch>,
+) -> std::io::Result<Exit> {
+    // Its own process group, so killing it reaches the compilers it runs.
+    #[cfg(unix)]
+    std::os::unix::process::CommandExt::process_group(command, 0);
+    let mut child = command
+        .stdin(Stdio::null())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .spawn()?;
+    let failed = Arc::new(AtomicBool::new(false));
+    // Drain both pipes on their own threads so a chatty child cannot block on a full pipe.
+    let stdout = {
+        let pipe = child.stdout.take();
+        let failed = Arc::clone(&failed);
+        std::thread::spawn(move || {
+            let mut buf = Vec::new();
+            let Some(pipe) = pipe else {
+                return buf;
+            };
+            let mut reader = BufReader::new(pipe);
+            let mut line = Vec::new();
+            while reader.read_until(b'\n', &mut line).unwrap_or(0) > 0 {
+                if fatal.is_some_and(|fatal| fatal(&String::from_utf8_lossy(&line))) {
+                    failed.store(true, Ordering::Relaxed);
+                }
+                buf.append(&mut line);
+            }
+            buf
+        })
+    };
+    // When cargo started waiting for a lock, and on what.
+    let waiting: Arc<OnceLock<(Instant, String)>> = Arc::new(OnceLock::new());
+    let stderr = {
+        let pipe = child.stderr.take();
+        let waiting = Arc::clone(&waiting);
+        let notice = lock.and_then(|l| Some((l.dir.clone(), l.notice.clone()?)));
+        let watch = lock.is_some();
+        std::thread::spawn(move || {
+            let mut buf = Vec::new();
+            let Some(mut pipe) = pipe else {
+                return buf;
+            };
+            if !watch {
+                let _ = pipe.read_to_end(&mut buf);
+                return buf;
+            }
+            let mut reader = BufReader::new(pipe);
+            let mut line = Vec::new();
+            while reader.read_until(b'\n', &mut line).unwrap_or(0) > 0 {
+                let text = String::from_utf8_lossy(&line);
+                if let Some(what) = lock_wait::waits_for(&text)
+                    && waiting.set((Instant::now(), what.to_owned())).is_ok()
+                    && let Some((dir, notice)) = &notice
+                {
+                    notice.notify(dir, what);
+                }
+                buf.append(&mut line);
+            }
+            buf
+        })
+    };
+    let timeout = lock.and_then(|l| l.timeout);
+    loop {
+        if let Some(status) = child.try_wait()? {
+            return Ok(Exit::Finished(Output {
+                status,
+                stdout: stdout.join().unwrap_or_default(),
+                stderr: stderr.join().unwrap_or_default(),
+            }));
+        }
+        if failed.load(Ordering::Relaxed) {
+            kill(&mut child);
+            let status = child.wait()?;
+            return Ok(Exit::Aborted(Output {
+                status,
+                stdout: stdout.join().unwrap_or_default(),
+                stderr: stderr.join().unwrap_or_default(),
+            }));
+        }
+        if cancel.is_cancelled() {
+            // Grandchildren may keep the pipes open; the drain threads are left to finish.
+            kill(&mut child);
+            let _ = child.wait();
+            return Ok(Exit::Cancelled);
+        }
+        if let (Some(timeout), Some((since, what))) = (timeout, waiting.get())
+            && since.elapsed() > timeout
+        {
+            kill(&mut child);
+            let _ = child.wait();
+            return Ok(Exit::LockWait {
+                what: what.clone(),
+                waited: since.elapsed(),
+            });
+        }
+        std::thread::sleep(CANCEL_POLL);
+    }
+}
+
+/// Kill `child` and, on Unix, its process group: the compilers cargo started with it.
+fn kill(child: &mut Child) {
+    #[cfg(unix)]
+    if let Ok(pid) = i32::try_from(child.id()) {
+        // SAFETY: `kill` only sends a signal; the negated pid names the group `child` leads.
+        unsafe {
+            libc::kill(-pid, libc::SIGKILL);
+        }
+    }
+    let _ = child.kill();
+}
+
+/// The outcome of a run started at `started`; a killed run is unsuccessful.
+fn outcome(exit: Exit, started: Instant) -> ValidationOutcome {
+    match exit {
+        Exit::Finished(output) => ValidationOutcome::from_output(&output, started.elapsed()),
+        Exit::Aborted(output) => ValidationOutcome {
+            aborted: true,
+            ..ValidationOutcome::from_output(&output, started.elapsed())
+        },
+        Exit::Cancelled | Exit::LockWait { .. } => ValidationOutcome {
+            elapsed: started.elapsed(),
+            ..ValidationOutcome::default()
+        },
+    }
+}
+
+/// Runs an arbitrary command in the root; exit status 0 means valid.
+#[derive(Debug, Clone)]
+pub struct CommandValidator {
+    argv: Vec<String>,
+}
+
+impl CommandValidator {
+    /// A validator running `argv[0]` with the remaining arguments.
+    pub fn new(argv: impl IntoIterator<Item = impl Into<String>>) -> TraitError<Self> {
+        let argv: Vec<String> = argv.into_iter().map(Into::into).collect();
+        if argv.is_empty() {
+            return Err(WinnowerError::Validation {
+                command: String::new(),
+                status: None,
+                stderr: "validation command is empty".into(),
+            });
+        }
+        Ok(Self { argv })
+    }
+}
+
+impl Validator for CommandValidator {
+    fn validate(&self, root: &Path) -> TraitError<ValidationOutcome> {
+        let started = Instant::now();
+        let output = Command::new(&self.argv[0])
+            .args(&self.argv[1..])
+            .current_dir(root)
+            .output()
+            .map_err(|e| self.spawn_error(root, e))?;
+        Ok(ValidationOutcome::from_output(&output, started.elapsed()))
+    }
+
+    fn validate_cancellable(
+        &self,
+        root: &Path,
+        cancel: &CancelToken,
+    ) -> TraitError<ValidationOutcome> {
+        let started = Instant::now();
+        let mut command = Command::new(&self.argv[0]);
+        command.args(&self.argv[1..]).current_dir(root);
+        let exit = output_until(&mut command, cancel, None, None)
+            .map_err(|e| self.spawn_error(root, e))?;
+        Ok(outcome(exit, started))
+    }
+}
+
+impl CommandValidator {
+    fn spawn_error(&self, root: &Path, e: std::io::Error) -> WinnowerError {
+        WinnowerError::Validation {
+            command: self.argv.join(" "),
+            status: None,
+            stderr: format!("running in {}: {e}", root.display()),
+        }
+    }
+}
+
+/// Accepts every edit without running anything.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct AlwaysOk;
+
+impl Validator for AlwaysOk {
+    fn validate(&self, _root: &Path) -> TraitError<ValidationOutcome> {
+        Ok(ValidationOutcome {
+            success: true,
+            ..ValidationOutcome::default()
+        })
+    }
+}
+
+/// Answers validations from a fixed script, in order; rejects once the script runs out.
+#[derive(Debug, Default)]
+pub struct ScriptedValidator {
+    script: RefCell<VecDeque<bool>>,
+    calls: Cell<usize>,
+    delay: Duration,
+    delays: RefCell<VecDeque<Duration>>,
+    baseline: Option<Duration>,
+}
+
+impl ScriptedValidator {
+    /// A validator answering `script[0]`, then `script[1]`, and so on.
+    pub fn new(script: impl IntoIterator<Item = bool>) -> Self {
+        Self {
+            script: RefCell::new(script.into_iter().collect()),
+            calls: Cell::new(0),
+            delay: Duration::ZERO,
+            delays: RefCell::default(),
+            baseline: None,
+        }
+    }
+
+    /// Take `delay` to answer each validation, like a slow build would.
+    pub fn with_delay(mut self, delay: Duration) -> Self {
+        self.delay = delay;
+        self
+    }
+
+    /// Take `delays[0]` to answer the first validation, `delays[1]` the second, and so
+    /// on; the delay of [`ScriptedValidator::with_delay`] once they run out.
+    pub fn with_delays(self, delays: impl IntoIterator<Item = Duration>) -> Self {
+        *self.delays.borrow_mut() = delays.into_iter().collect();
+        self
+    }
+
+    /// Report `baseline` as the time of a check of the unmodified tree, without taking it
+    /// or counting a call.
+    pub fn with_baseline(mut self, baseline: Duration) -> Self {
+        self.baseline = Some(baseline);
+        self
+    }
+
+    /// How many validations were requested.
+    pub fn calls(&self) -> usize {
+        self.calls.get()
+    }
+}
+
+impl Validator for ScriptedValidator {
+    fn validate(&self, root: &Path) -> TraitError<ValidationOutcome> {
+        self.validate_cancellable(root, &CancelToken::new())
+    }
+
+    fn validate_cancellable(
+        &self,
+        _root: &Path,
+        cancel: &CancelToken,
+    ) -> TraitError<ValidationOutcome> {
+        self.calls.set(self.calls.get() + 1);
+        let started = Instant::now();
+        let delay = self.delays.borrow_mut().pop_front().unwrap_or(self.delay);
+        while started.elapsed() < delay {
+            if cancel.is_cancelled() {
+                return Ok(outcome(Exit::Cancelled, started));
+            }
+            std::thread::sleep(CANCEL_POLL.min(delay.saturating_sub(started.elapsed())));
+        }
+        let success = self.script.borrow_mut().pop_front().unwrap_or(false);
+        Ok(ValidationOutcome {
+            success,
+            code: Some(if success { 0 } else { 1 }),
+            elapsed: started.elapsed(),
+            ..ValidationOutcome::default()
+        })
+    }
+
+    fn baseline(&self, _root: &Path, _cancel: &CancelToken) -> TraitError<Option<Duration>> {
+        Ok(self.baseline)
+    }
+}
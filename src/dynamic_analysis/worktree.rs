@@ -0,0 +1,323 @@
+// src/dynamic_analysis/worktree.rs
+//! A copy of the workspace for trials to run in, so the real tree only sees accepted
+//! removals.
+//!
+//! Editing the user's files for every trial makes rust-analyzer rescan, file watchers
+//! fire and editors reload, even for removals that are put back a second later. A
+//! [`Worktree`] mirrors the workspace once per run, hardlinking sources where it can, and
+//! [`ShadowStore`] writes trials there; a trial reaches the real file only when it is
+//! kept. Manifests are copied rather than linked and their path dependencies leading out
+//! of the workspace point back at the originals.
+
+#![deny(missing_docs)]
+
+use crate::dynamic_analysis::store::SourceStore;
+use crate::error::{TraitError, WinnowerError};
+use std::cell::RefCell;
+use std::collections::HashMap;
+use std::path::{Component, Path, PathBuf};
+
+/// Where the worktree lives, relative to the crate root.
+pub const WORKTREE_DIR: &str = "target/trait-winnower/worktree";
+
+/// Directories never mirrored: build output and version control.
+const SKIPPED: [&str; 5] = ["target", ".git", ".hg", ".jj", ".svn"];
+
+/// Tables of a manifest holding dependencies, directly or per target.
+const DEPENDENCY_TABLES: [&str; 3] = ["dependencies", "dev-dependencies", "build-dependencies"];
+
+/// A mirror of the workspace at `root` (see the module docs).
+#[derive(Debug)]
+pub struct Worktree {
+    real: PathBuf,
+    dir: PathBuf,
+    /// Hardlink sources rather than copy them.
+    link: bool,
+}
+
+impl Worktree {
+    /// Mirror the workspace at `root` into `dir`, replacing what an earlier run left there.
+    /// Paths under `skip` are left out, as are `target/` and version control directories.
+    pub fn create(root: &Path, dir: &Path, skip: &[PathBuf]) -> TraitError<Self> {
+        Self::mirror_into(root, dir, skip, true)
+    }
+
+    /// Like [`Worktree::create`], copying every file instead of hardlinking sources: the
+    /// copy can be written to like any tree without reaching the real one.
+    pub fn copy(root: &Path, dir: &Path, skip: &[PathBuf]) -> TraitError<Self> {
+        Self::mirror_into(root, dir, skip, false)
+    }
+
+    fn mirror_into(root: &Path, dir: &Path, skip: &[PathBuf], link: bool) -> TraitError<Self> {
+        if dir.exists() {
+            std::fs::remove_dir_all(dir).map_err(|e| WinnowerError::io("removing", dir, e))?;
+        }
+        let tree = Self {
+            real: root.to_path_buf(),
+            dir: dir.to_path_buf(),
+            link,
+        };
+        let mut skip = skip.to_vec();
+        skip.push(dir.to_path_buf());
+        skip.extend(SKIPPED.iter().map(|s| root.join(s)));
+        tree.mirror(root, &skip)?;
+        Ok(tree)
+    }
+
+    /// The root of the copy, where trials are validated.
+    pub fn root(&self) -> &Path {
+        &self.dir
+    }
+
+    /// The copy of `path`, a file of the real tree.
+    pub fn shadow(&self, path: &Path) -> TraitError<PathBuf> {
+        let rel = path.strip_prefix(&self.real).map_err(|_| {
+            WinnowerError::Internal(format!(
+                "{} is outside the worktree of {}",
+                path.display(),
+                self.real.display()
+            ))
+        })?;
+        Ok(self.dir.join(rel))
+    }
+
+    /// Remove the copy.
+    pub fn finish(self) -> TraitError<()> {
+        std::fs::remove_dir_all(&self.dir).map_err(|e| WinnowerError::io("removing", &self.dir, e))
+    }
+
+    fn mirror(&self, from: &Path, skip: &[PathBuf]) -> TraitError<()> {
+        let to = self.shadow(from)?;
+        std::fs::create_dir_all(&to).map_err(|e| WinnowerError::io("creating", &to, e))?;
+        let entries = std::fs::read_dir(from).map_err(|e| WinnowerError::io("reading", from, e))?;
+        for entry in entries {
+            let entry = entry.map_err(|e| WinnowerError::io("reading", from, e))?;
+            let path = entry.path();
+            if skip.contains(&path) {
+                continue;
+            }
+            let kind = entry
+                .file_type()
+                .map_err(|e| WinnowerError::io("reading", &path, e))?;
+            let copy = to.join(entry.file_name());
+            if kind.is_dir() {
+                self.mirror(&path, skip)?;
+            } else if kind.is_symlink() {
+                link_symlink(&path, &copy)?;
+            } else if entry.file_name() == "Cargo.toml" {
+                self.write_manifest(&path, &copy)?;
+            } else if entry.file_name() == "Cargo.lock" {
+                // Cargo may rewrite it; a hardlink would carry that into the real tree.
+                std::fs::copy(&path, &copy).map_err(|e| WinnowerError::io("copying", &path, e))?;
+            } else if !self.link || std::fs::hard_link(&path, &copy).is_err() {
+                std::fs::copy(&path, &copy).map_err(|e| WinnowerError::io("copying", &path, e))?;
+            }
+        }
+        Ok(())
+    }
+
+    /// Copy the manifest at `path` to `copy`, pointing path dependencies that leave the
+    /// workspace back at their real location.
+    fn write_manifest(&self, path: &Path, copy: &Path) -> TraitError<()> {
+        let text =
+            std::fs::read_to_string(path).map_err(|e| WinnowerError::io("reading", path, e))?;
+        let mut manifest: toml::Table =
+            toml::from_str(&text).map_err(|e| WinnowerError::Config {
+                path: path.to_path_buf(),
+                detail: e.to_string(),
+            })?;
+        let dir = path.parent().unwrap_or(&self.real);
+        let text = if self.fix_paths(dir, &mut manifest) {
+            toml::to_string(&manifest)
+                .map_err(|e| WinnowerError::Internal(format!("serializing a manifest: {e}")))?
+        } else {
+            text
+        };
+        std::fs::write(copy, text).map_err(|e| WinnowerError::io("writing", copy, e))
+    }
+
+    /// Make the `path` of every dependency in `manifest`, a manifest in `dir`, absolute
+    /// when it leads out of the workspace. Returns whether any changed.
+    fn fix_paths(&self, dir: &Path, manifest: &mut toml::Table) -> bool {
+        let mut tables: Vec<&mut toml::Table> = Vec::new();
+        let mut nested: Vec<&mut toml::Table> = vec![manifest];
+        while let Some(table) = nested.pop() {
+            for (key, value) in table.iter_mut() {
+                let toml::Value::Table(inner) = value else {
+                    continue;
+                };
+                match key.as_str() {
+                    k if DEPENDENCY_TABLES.contains(&k) => tables.push(inner),
+                    // `[target.'cfg(..)'.dependencies]`, `[workspace.dependencies]`,
+                    // `[patch.crates-io]` and `[replace]`.
+                    "target" => {
+                        nested.extend(inner.iter_mut().filter_map(|(_, v)| v.as_table_mut()))
+                    }
+                    "workspace" => nested.push(inner),
+                    "patch" => {
+                        tables.extend(inner.iter_mut().filter_map(|(_, v)| v.as_table_mut()))
+                    }
+                    "replace" => tables.push(inner),
+                    _ => {}
+                }
+            }
+        }
+        let mut changed = false;
+        for table in tables {
+            for (_, dependency) in table.iter_mut() {
+                let Some(toml::Value::String(rel)) =
+                    dependency.as_table_mut().and_then(|d| d.get_mut("path"))
+                else {
+                    continue;
+                };
+                let target = normalize(&dir.join(&*rel));
+                if !target.starts_with(&self.real) {
+                    *rel = target.to_string_lossy().into_owned();
+                    changed = true;
+                }
+            }
+        }
+        changed
+    }
+}
+
+/// `path` with `.` and `..` resolved lexically.
+fn normalize(path: &Path) -> PathBuf {
+    let mut out = PathBuf::new();
+    for component in path.components() {
+        match component {
+            Component::CurDir => {}
+            Component::ParentDir => {
+                out.pop();
+            }
+            c => out.push(c),
+        }
+    }
+    out
+}
+
+#[cfg(unix)]
+fn link_symlink(path: &Path, copy: &Path) -> TraitError<()> {
+    let target = std::fs::read_link(path).map_err(|e| WinnowerError::io("reading", path, e))?;
+    std::os::unix::fs::symlink(target, copy).map_err(|e| WinnowerError::io("linking", copy, e))
+}
+
+#[cfg(not(unix))]
+fn link_symlink(path: &Path, copy: &Path) -> TraitError<()> {
+    if path.is_file() {
+        std::fs::copy(path, copy).map_err(|e| WinnowerError::io("copying", path, e))?;
+    }
+    Ok(())
+}
+
+/// Trials in a [`Worktree`]: each is written to the file's copy, and only those kept reach
+/// the real file, through `real`.
+pub struct ShadowStore<'a> {
+    worktree: &'a Worktree,
+    real: &'a dyn SourceStore,
+    /// The trial last written to each file and not yet kept or reverted.
+    trials: RefCell<HashMap<PathBuf, String>>,
+}
+
+impl<'a> ShadowStore<'a> {
+    /// Trials of the files of `real` in `worktree`.
+    pub fn new(worktree: &'a Worktree, real: &'a dyn SourceStore) -> Self {
+        Self {
+            worktree,
+            real,
+            trials: RefCell::new(HashMap::new()),
+        }
+    }
+
+    /// Replace the copy of `path` with `src`. The copy may be a hardlink to the real file,
+    /// so it is unlinked first rather than written through.
+    fn write_copy(&self, path: &Path, src: &str) -> TraitError<()> {
+        let copy = self.worktree.shadow(path)?;
+        match std::fs::remove_file(&copy) {
+            Ok(()) => {}
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => {}
+            Err(e) => return Err(WinnowerError::io("replacing", &copy, e)),
+        }
+        std::fs::write(&copy, src).map_err(|e| WinnowerError::io("writing updated", &copy, e))
+    }
+}
+
+impl SourceStore for ShadowStore<'_> {
+    fn read(&self, path: &Path) -> TraitError<String> {
+        // Kept trials are in the real file too.
+        self.real.read(path)
+    }
+
+    fn write(&self, path: &Path, src: &str) -> TraitError<()> {
+        self.write_copy(path, src)?;
+        self.trials
+            .borrow_mut()
+            .insert(path.to_path_buf(), src.to_owned());
+        Ok(())
+    }
+
+    fn revert(&self, path: &Path, src: &str) -> TraitError<()> {
+        self.trials.borrow_mut().remove(path);
+        self.write_copy(path, src)
+    }
+
+    fn keep(&self, path: &Path) -> TraitError<()> {
+        let Some(src) = self.trials.borrow_mut().remove(path) else {
+            return Ok(());
+        };
+        self.real.write(path, &src)?;
+        self.real.keep(path)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::dynamic_analysis::store::FsStore;
+
+    #[test]
+    fn only_kept_trials_reach_the_real_tree() -> TraitError<()> {
+        let tmp = tempfile::tempdir()?;
+        let root = tmp.path().join("ws");
+        let outside = tmp.path().join("outside");
+        std::fs::create_dir_all(root.join("src"))?;
+        std::fs::create_dir_all(&outside)?;
+        std::fs::write(
+            root.join("Cargo.toml"),
+            "[package]\nname = \"x\"\nversion = \"0.1.0\"\n\n\
+             [dependencies]\ny = { path = \"../outside\" }\nz = { path = \"z\" }\n",
+        )?;
+        std::fs::write(root.join("src/lib.rs"), "a\n")?;
+        std::fs::create_dir_all(root.join("target/debug"))?;
+        std::fs::write(root.join("target/debug/big"), "")?;
+
+        let tree = Worktree::create(&root, &root.join(WORKTREE_DIR), &[])?;
+        assert!(!tree.root().join("target").exists());
+        let manifest: toml::Table =
+            toml::from_str(&std::fs::read_to_string(tree.root().join("Cargo.toml"))?)
+                .map_err(|e| WinnowerError::Internal(e.to_string()))?;
+        let path = |name: &str| {
+            manifest["dependencies"][name]["path"]
+                .as_str()
+                .map(PathBuf::from)
+        };
+        assert_eq!(path("y"), Some(outside));
+        assert_eq!(path("z"), Some(PathBuf::from("z")));
+
+        let lib = root.join("src/lib.rs");
+        let store = ShadowStore::new(&tree, &FsStore);
+        store.write(&lib, "b\n")?;
+        store.revert(&lib, "a\n")?;
+        store.write(&lib, "c\n")?;
+        assert_eq!(std::fs::read_to_string(&lib)?, "a\n");
+        assert_eq!(
+            std::fs::read_to_string(tree.root().join("src/lib.rs"))?,
+            "c\n"
+        );
+        store.keep(&lib)?;
+        assert_eq!(std::fs::read_to_string(&lib)?, "c\n");
+        tree.finish()?;
+        assert!(!root.join(WORKTREE_DIR).exists());
+        Ok(())
+    }
+}
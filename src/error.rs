@@ -3,5 +3,273 @@
 
 #![deny(missing_docs)]
 
-/// TraitError is alias for anyhow
-pub type TraitError<T> = anyhow::Result<T>;
+use crate::analysis::NonUtf8Source;
+use crate::dynamic_analysis::common::ValidationOutcome;
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+
+/// Result alias used throughout the library.
+pub type TraitError<T> = Result<T, WinnowerError>;
+
+/// Lines of validator stderr kept in [`WinnowerError::Validation`].
+const STDERR_DIGEST_LINES: usize = 20;
+
+/// Everything that can go wrong while checking or pruning.
+#[derive(Debug, thiserror::Error)]
+pub enum WinnowerError {
+    /// Reading or writing a file (or the output stream) failed.
+    #[error("{action} {}: {source}", display_opt(path))]
+    Io {
+        /// What was being done, e.g. `reading` or `reverting`.
+        action: &'static str,
+        /// The file involved; `None` for output streams.
+        path: Option<PathBuf>,
+        /// The underlying error.
+        #[source]
+        source: std::io::Error,
+    },
+    /// A source file is not valid Rust.
+    #[error("failed to parse {}: {source}", parse_location(path, source))]
+    Parse {
+        /// The file that failed to parse; `None` for in-memory sources.
+        path: Option<PathBuf>,
+        /// The syntax error.
+        #[source]
+        source: syn::Error,
+    },
+    /// A source file is not valid UTF-8.
+    #[error(transparent)]
+    NotUtf8(#[from] NonUtf8Source),
+    /// The configuration file is unreadable or invalid.
+    #[error("invalid config {}: {detail}", path.display())]
+    Config {
+        /// The configuration file.
+        path: PathBuf,
+        /// What is wrong with it.
+        detail: String,
+    },
+    /// A plan does not apply and strict mode was requested.
+    #[error("plan {}: {detail}", path.display())]
+    Plan {
+        /// The root the plan was applied to.
+        path: PathBuf,
+        /// What is wrong.
+        detail: String,
+    },
+    /// A saved plan or report is malformed.
+    #[error("invalid {artifact} {}: {detail}", path.display())]
+    Format {
+        /// The kind of document, e.g. `plan`.
+        artifact: &'static str,
+        /// The file read.
+        path: PathBuf,
+        /// What is wrong with it.
+        detail: String,
+    },
+    /// A saved plan or report has a `schema_version` this build cannot read.
+    #[error(
+        "{artifact} {} has schema_version {found}; this trait-winnower reads versions 1 to {supported}{}",
+        path.display(),
+        upgrade_hint(*found, *supported)
+    )]
+    UnsupportedVersion {
+        /// The kind of document, e.g. `plan`.
+        artifact: &'static str,
+        /// The file read.
+        path: PathBuf,
+        /// The version found in the file.
+        found: u32,
+        /// The newest version this build reads.
+        supported: u32,
+    },
+    /// The target is missing or is not a crate, workspace or `.rs` file.
+    #[error("{detail}: {}", path.display())]
+    Target {
+        /// The target as given.
+        path: PathBuf,
+        /// What is wrong with it.
+        detail: String,
+    },
+    /// File discovery failed, e.g. on an invalid include or exclude pattern.
+    #[error("discovery failed: {0}")]
+    Discovery(String),
+    /// The validation command could not run or failed unexpectedly.
+    #[error("validation command `{command}` failed ({}){}", status_text(*status), stderr_text(stderr))]
+    Validation {
+        /// The command line.
+        command: String,
+        /// Exit code, if the command ran and exited normally.
+        status: Option<i32>,
+        /// The last lines of its stderr, or why it could not run.
+        stderr: String,
+    },
+    /// Cargo is missing, or the program found is not cargo.
+    #[error("cannot use {}: {detail}", program.display())]
+    Toolchain {
+        /// The program that was run.
+        program: PathBuf,
+        /// What went wrong, and what to do about it.
+        detail: String,
+    },
+    /// A check waited longer than
+    /// [`lock_wait_timeout`](crate::config::CargoCheckConfig::lock_wait_timeout) for a file
+    /// lock another cargo process holds.
+    #[error(
+        "cargo waited {}s for the file lock on its {what} in {}; another cargo process, such as rust-analyzer or a build, holds it. Stop that process, or set `target_dir` under [cargo_check] in .trait-winnower.toml to a directory no other build uses",
+        waited.as_secs(),
+        dir.display()
+    )]
+    LockWait {
+        /// The target directory of the check.
+        dir: PathBuf,
+        /// What cargo waited for, as it names it (`build directory`, `package cache`).
+        what: String,
+        /// How long it waited.
+        waited: Duration,
+    },
+    /// An earlier `prune` was killed mid-trial and left edits nobody validated.
+    #[error(
+        "{} file(s) under {} still hold trial edits from an interrupted run; run `trait-winnower recover` to restore them",
+        files.len(),
+        root.display()
+    )]
+    Interrupted {
+        /// The crate or workspace root.
+        root: PathBuf,
+        /// The files left mid-trial, relative to `root`.
+        files: Vec<PathBuf>,
+    },
+    /// A replayed run asked for a check its recording does not hold, or replays a tree
+    /// the recording was not made on.
+    #[error("cannot replay {}: {detail}", path.display())]
+    Replay {
+        /// The recording.
+        path: PathBuf,
+        /// What does not match.
+        detail: String,
+    },
+    /// A bug or an unexpected state.
+    #[error("internal error: {0}")]
+    Internal(String),
+}
+
+impl WinnowerError {
+    /// An [`WinnowerError::Io`] error on `path`.
+    pub fn io(action: &'static str, path: &Path, source: std::io::Error) -> Self {
+        WinnowerError::Io {
+            action,
+            path: Some(path.to_path_buf()),
+            source,
+        }
+    }
+
+    /// A [`WinnowerError::Parse`] error in `path`.
+    pub fn parse(path: &Path, source: syn::Error) -> Self {
+        WinnowerError::Parse {
+            path: Some(path.to_path_buf()),
+            source,
+        }
+    }
+
+    /// A [`WinnowerError::Validation`] error for a finished run of `command`.
+    pub fn validation(command: impl Into<String>, outcome: &ValidationOutcome) -> Self {
+        let lines: Vec<&str> = outcome.stderr.lines().collect();
+        let tail = &lines[lines.len().saturating_sub(STDERR_DIGEST_LINES)..];
+        WinnowerError::Validation {
+            command: command.into(),
+            status: outcome.code,
+            stderr: tail.join("\n"),
+        }
+    }
+
+    /// A [`WinnowerError::Toolchain`] error for a `cargo` that could not be started.
+    pub fn cargo_spawn(program: &Path, e: &std::io::Error) -> Self {
+        let detail = match e.kind() {
+            std::io::ErrorKind::NotFound => format!(
+                "not found ({e}); install Rust with rustup (https://rustup.rs), or point \
+                 `cargo_path` under [cargo_check] in .trait-winnower.toml or the CARGO \
+                 environment variable at a cargo binary"
+            ),
+            std::io::ErrorKind::PermissionDenied => format!("not executable ({e})"),
+            _ => e.to_string(),
+        };
+        WinnowerError::Toolchain {
+            program: program.to_path_buf(),
+            detail,
+        }
+    }
+
+    /// Process exit code for this error: 2 for bad targets, configuration, plans, saved
+    /// documents and recordings, a missing cargo, a cargo lock held too long and interrupted
+    /// runs, 1 otherwise.
+    pub fn exit_code(&self) -> u8 {
+        match self {
+            WinnowerError::Target { .. }
+            | WinnowerError::Config { .. }
+            | WinnowerError::Plan { .. }
+            | WinnowerError::Format { .. }
+            | WinnowerError::UnsupportedVersion { .. }
+            | WinnowerError::Toolchain { .. }
+            | WinnowerError::LockWait { .. }
+            | WinnowerError::Interrupted { .. }
+            | WinnowerError::Replay { .. } => 2,
+            _ => 1,
+        }
+    }
+}
+
+impl From<std::io::Error> for WinnowerError {
+    /// Failures writing reports to an output stream.
+    fn from(source: std::io::Error) -> Self {
+        WinnowerError::Io {
+            action: "writing",
+            path: None,
+            source,
+        }
+    }
+}
+
+impl From<syn::Error> for WinnowerError {
+    fn from(source: syn::Error) -> Self {
+        WinnowerError::Parse { path: None, source }
+    }
+}
+
+fn display_opt(path: &Option<PathBuf>) -> String {
+    match path {
+        Some(p) => p.display().to_string(),
+        None => "output".into(),
+    }
+}
+
+fn parse_location(path: &Option<PathBuf>, source: &syn::Error) -> String {
+    let start = source.span().start();
+    let file = match path {
+        Some(p) => p.display().to_string(),
+        None => "<input>".into(),
+    };
+    format!("{file}:{}:{}", start.line, start.column + 1)
+}
+
+fn status_text(status: Option<i32>) -> String {
+    match status {
+        Some(code) => format!("exit code {code}"),
+        None => "did not run".into(),
+    }
+}
+
+fn upgrade_hint(found: u32, supported: u32) -> &'static str {
+    if found > supported {
+        " (written by a newer trait-winnower; upgrade to read it)"
+    } else {
+        ""
+    }
+}
+
+fn stderr_text(stderr: &str) -> String {
+    if stderr.is_empty() {
+        String::new()
+    } else {
+        format!(":\n{stderr}")
+    }
+}
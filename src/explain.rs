@@ -0,0 +1,623 @@
+// src/explain.rs
+//! `explain-item`: everything known about one item, for when a bound is kept and the reason
+//! is unclear.
+//!
+//! Items are named by [`ItemPath`]: a module-qualified path such as `crate::b::Wrapper::id`,
+//! or a label as reports print it, such as `inner fn foo`. Generic arguments are ignored, so
+//! `Wrapper<T>::id` and `Wrapper::id` name the same method. For each match [`explain_item`]
+//! gathers the item's location, its type parameters and where predicates with their indices,
+//! the candidates generated, the static rule flagging each and the last `prune`'s outcome
+//! from the [`RunCache`]; [`trial_candidates`] then tries each candidate alone, restoring the
+//! file after every trial, on a copy of the crate when `explain-item --trial` runs it.
+
+#![deny(missing_docs)]
+
+use crate::analysis::{ItemBounds, ItemKind, ItemLocator};
+use crate::dynamic_analysis::common::{OwnedBoundCandidate, OwnedBoundSite};
+use crate::dynamic_analysis::incremental::RunCache;
+use crate::dynamic_analysis::unlock::Blame;
+use crate::dynamic_analysis::validate::Validator;
+use crate::error::{TraitError, WinnowerError};
+use crate::findings::RuleMatch;
+use crate::plan::{ApplyOptions, EntryStatus, Plan, PlanEntry, apply_plan_with};
+use crate::report::Report;
+use quote::ToTokens;
+use serde::{Deserialize, Serialize};
+use std::io::Write;
+use std::path::Path;
+
+/// Suggestions offered when no item matches.
+const MAX_SUGGESTIONS: usize = 3;
+
+/// Lines of a failed trial's errors kept in its digest.
+const DIGEST_ERRORS: usize = 3;
+
+/// An item as named on the command line.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ItemPath {
+    /// The kinds a keyword before the name allows, e.g. `fn` in `inner fn foo`; empty for
+    /// any.
+    kinds: Vec<ItemKind>,
+    /// The module path and name joined by `::`, without `crate::`, generic arguments or
+    /// spaces around `::`, e.g. `b::Wrapper::id`.
+    path: String,
+}
+
+/// `s` without generic arguments, with runs of whitespace collapsed and none around `::`:
+/// `Wrapper < T >::id` is `Wrapper::id`.
+fn normalize(s: &str) -> String {
+    let mut out = String::with_capacity(s.len());
+    let (mut depth, mut prev) = (0usize, ' ');
+    for c in s.chars() {
+        match c {
+            '<' => depth += 1,
+            '>' if prev != '-' => depth = depth.saturating_sub(1),
+            _ if depth == 0 => out.push(c),
+            _ => {}
+        }
+        prev = c;
+    }
+    out.split_whitespace()
+        .collect::<Vec<_>>()
+        .join(" ")
+        .replace(" ::", "::")
+        .replace(":: ", "::")
+}
+
+/// The kinds of item `word`, a keyword labels start with, may name.
+fn keyword_kinds(word: &str) -> Option<Vec<ItemKind>> {
+    Some(match word {
+        "fn" => vec![ItemKind::Fn, ItemKind::ImplMethod, ItemKind::TraitMethod],
+        "struct" => vec![ItemKind::Struct],
+        "enum" => vec![ItemKind::Enum],
+        "trait" => vec![ItemKind::Trait, ItemKind::TraitMethod],
+        "impl" => vec![ItemKind::Impl],
+        "type" => vec![ItemKind::TypeAlias, ItemKind::AssocType],
+        "union" => vec![ItemKind::Union],
+        _ => return None,
+    })
+}
+
+impl ItemPath {
+    /// Parse `s`: `crate::b::Wrapper::id`, `b::Wrapper::id`, or a label with its module path
+    /// first, e.g. `b Wrapper < T >::id` or `inner fn foo`.
+    pub fn parse(s: &str) -> Self {
+        let s = normalize(s);
+        let s = s.strip_prefix("crate::").unwrap_or(&s);
+        let mut words: Vec<&str> = s.split(' ').collect();
+        // `b fn foo` and `b Wrapper::id` lead with a module path; `Display for S` does not.
+        let module = (words.len() > 1 && keyword_kinds(words[0]).is_none() && words[1] != "for")
+            .then(|| words.remove(0));
+        let kinds = match words.as_slice() {
+            [kw, _, ..] => keyword_kinds(kw),
+            _ => None,
+        };
+        if kinds.is_some() {
+            words.remove(0);
+        }
+        let rest = words.join(" ");
+        Self {
+            kinds: kinds.unwrap_or_default(),
+            path: match module {
+                Some(m) => format!("{m}::{rest}"),
+                None => rest,
+            },
+        }
+    }
+
+    /// The path of the item at `locator`, as [`ItemPath::parse`] reads it back, e.g.
+    /// `crate::b::Wrapper::id`.
+    pub fn of(locator: &ItemLocator) -> String {
+        format!("crate::{}", Self::bare(locator))
+    }
+
+    /// [`ItemPath::of`] without `crate::`.
+    fn bare(locator: &ItemLocator) -> String {
+        let name = normalize(&locator.name);
+        if locator.module_path.is_empty() {
+            name
+        } else {
+            format!("{}::{name}", locator.module_path)
+        }
+    }
+
+    /// Whether this names the item at `locator`.
+    pub fn matches(&self, locator: &ItemLocator) -> bool {
+        (self.kinds.is_empty() || self.kinds.contains(&locator.kind))
+            && Self::bare(locator) == self.path
+    }
+
+    /// Up to three of `paths`, as [`ItemPath::of`] prints them, close to this
+    /// one: with the same name in another module, or a few edits away. Closest first.
+    pub fn suggest<'a>(&self, paths: impl IntoIterator<Item = &'a str>) -> Vec<String> {
+        let wanted = last(&self.path);
+        let limit = 1 + wanted.chars().count() / 3;
+        let mut scored: Vec<(usize, &str)> = paths
+            .into_iter()
+            .filter_map(|p| {
+                let bare = p.strip_prefix("crate::").unwrap_or(p);
+                let score = if bare.ends_with(&format!("::{}", self.path)) {
+                    0
+                } else {
+                    edit_distance(bare, &self.path).min(edit_distance(last(bare), wanted) + 1)
+                };
+                (score <= limit).then_some((score, p))
+            })
+            .collect();
+        scored.sort();
+        scored.dedup_by(|a, b| a.1 == b.1);
+        scored
+            .into_iter()
+            .take(MAX_SUGGESTIONS)
+            .map(|(_, p)| p.to_owned())
+            .collect()
+    }
+}
+
+impl std::fmt::Display for ItemPath {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "crate::{}", self.path)
+    }
+}
+
+/// The last segment of `path`, e.g. `id` in `b::Wrapper::id`.
+fn last(path: &str) -> &str {
+    path.rsplit("::").next().unwrap_or(path)
+}
+
+/// Levenshtein distance between `a` and `b`, in chars.
+fn edit_distance(a: &str, b: &str) -> usize {
+    let b: Vec<char> = b.chars().collect();
+    let mut row: Vec<usize> = (0..=b.len()).collect();
+    for (i, ca) in a.chars().enumerate() {
+        let mut diagonal = row[0];
+        row[0] = i + 1;
+        for (j, cb) in b.iter().enumerate() {
+            let substitute = diagonal + usize::from(ca != *cb);
+            diagonal = row[j + 1];
+            row[j + 1] = substitute.min(row[j] + 1).min(row[j + 1] + 1);
+        }
+    }
+    row[b.len()]
+}
+
+/// A type parameter or where predicate with its bounds as written.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct DeclaredBounds {
+    /// Index among the generic parameters, lifetimes and consts included, or among the
+    /// where predicates.
+    pub index: usize,
+    /// The parameter or bounded type, e.g. `T`.
+    pub bounded: String,
+    /// Each bound, e.g. `Clone`.
+    pub bounds: Vec<String>,
+}
+
+/// What the last `prune` recorded about a bound's file.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum LastRun {
+    /// No `prune` recorded the file.
+    NoRecord,
+    /// The file changed since the last `prune`.
+    FileChanged,
+    /// The last `prune` kept the bound, and the file is as it left it.
+    Retained,
+    /// The last `prune` left the file as it is and did not keep the bound, so it never
+    /// tried it: its filters left it out.
+    NotTried,
+}
+
+impl std::fmt::Display for LastRun {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(match self {
+            LastRun::NoRecord => "no prune recorded",
+            LastRun::FileChanged => "file changed since the last prune",
+            LastRun::Retained => "kept by the last prune",
+            LastRun::NotTried => "not tried by the last prune",
+        })
+    }
+}
+
+/// How removing a bound alone went.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct TrialOutcome {
+    /// The crate still checks without the bound.
+    pub removable: bool,
+    /// Why not: the first errors and the bounds they blame, or why the removal did not
+    /// apply.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub digest: Vec<String>,
+}
+
+/// One candidate of an [`ItemExplanation`].
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct CandidateExplanation {
+    /// The bound and where it is declared.
+    pub candidate: OwnedBoundCandidate,
+    /// 1-based line and column of the bound.
+    pub line: usize,
+    /// See `line`.
+    pub column: usize,
+    /// The static rule that flags the bound, if any.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub rule: Option<RuleMatch>,
+    /// Why `prune` leaves the bound alone by default, if it does.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub caution: Option<String>,
+    /// What the last `prune` recorded.
+    pub last_run: LastRun,
+    /// The bound tried alone, with `--trial`.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub trial: Option<TrialOutcome>,
+}
+
+/// Everything known about one item.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ItemExplanation {
+    /// The item's path, e.g. `crate::b::Wrapper::id`.
+    pub path: String,
+    /// The item's label as reports print it, e.g. `b Wrapper < T >::id`.
+    pub label: String,
+    /// Where the item is.
+    pub locator: ItemLocator,
+    /// The `#[cfg]` predicates the item is compiled under.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub cfg: Vec<String>,
+    /// The type parameters, bounded or not.
+    pub type_params: Vec<DeclaredBounds>,
+    /// The where predicates bounding types.
+    pub where_predicates: Vec<DeclaredBounds>,
+    /// The candidates, in source order.
+    pub candidates: Vec<CandidateExplanation>,
+}
+
+/// Explain every item of `report`'s files, under `root`, that `query` names. Fails with up
+/// to three suggestions (see [`ItemPath::suggest`]) when none does.
+pub fn explain_item(
+    root: &Path,
+    report: &Report,
+    query: &ItemPath,
+) -> TraitError<Vec<ItemExplanation>> {
+    let runs = RunCache::load(root);
+    let mut out = Vec::new();
+    let mut known: Vec<String> = Vec::new();
+    for file in &report.files {
+        let path = root.join(&file.path);
+        let source = ItemBounds::read_source(&path)?;
+        let syntax = syn::parse_file(&source).map_err(|e| WinnowerError::parse(&path, e))?;
+        let items = ItemBounds::collect_items_in_file(&syntax)?;
+        let outcome = runs.files.get(&file.path);
+        let changed = outcome.is_some_and(|o| o.hash != crc32fast::hash(source.as_bytes()));
+        for key in items.iter_all_items() {
+            let locator = ItemLocator::from(key).in_file(&file.path);
+            if !query.matches(&locator) {
+                known.push(ItemPath::of(&locator));
+                continue;
+            }
+            let label = key.to_string().trim_start_matches("// ").to_owned();
+            let generics = key.item().generics();
+            let type_params = generics
+                .params
+                .iter()
+                .enumerate()
+                .filter_map(|(index, p)| match p {
+                    syn::GenericParam::Type(t) => Some(DeclaredBounds {
+                        index,
+                        bounded: t.ident.to_string(),
+                        bounds: tokens(&t.bounds),
+                    }),
+                    _ => None,
+                })
+                .collect();
+            let where_predicates = generics
+                .where_clause
+                .iter()
+                .flat_map(|w| w.predicates.iter().enumerate())
+                .filter_map(|(index, p)| match p {
+                    syn::WherePredicate::Type(t) => Some(DeclaredBounds {
+                        index,
+                        bounded: t.bounded_ty.to_token_stream().to_string(),
+                        bounds: tokens(&t.bounds),
+                    }),
+                    _ => None,
+                })
+                .collect();
+            let candidates = file
+                .findings
+                .iter()
+                .filter(|f| f.locator == locator)
+                .map(|f| {
+                    let retained = format!("{}: {}", f.item, f.candidate.declaration());
+                    CandidateExplanation {
+                        candidate: f.candidate.clone(),
+                        line: f.line,
+                        column: f.column,
+                        rule: f.rule.clone(),
+                        caution: f.caution.clone(),
+                        last_run: match outcome {
+                            None => LastRun::NoRecord,
+                            Some(_) if changed => LastRun::FileChanged,
+                            Some(o) if o.retained.contains(&retained) => LastRun::Retained,
+                            Some(_) => LastRun::NotTried,
+                        },
+                        trial: None,
+                    }
+                })
+                .collect();
+            out.push(ItemExplanation {
+                path: ItemPath::of(&locator),
+                label,
+                cfg: key
+                    .cfg()
+                    .iter()
+                    .map(|m| m.to_token_stream().to_string())
+                    .collect(),
+                locator,
+                type_params,
+                where_predicates,
+                candidates,
+            });
+        }
+    }
+    if out.is_empty() {
+        let suggestions = query.suggest(known.iter().map(String::as_str));
+        let hint = match suggestions.as_slice() {
+            [] => String::new(),
+            [one] => format!(" (did you mean `{one}`?)"),
+            more => format!(
+                " (did you mean one of {}?)",
+                more.iter()
+                    .map(|s| format!("`{s}`"))
+                    .collect::<Vec<_>>()
+                    .join(", ")
+            ),
+        };
+        return Err(WinnowerError::Target {
+            path: root.to_path_buf(),
+            detail: format!("no item `{query}`{hint}"),
+        });
+    }
+    Ok(out)
+}
+
+/// Each bound of `bounds` as tokens.
+fn tokens<T: ToTokens, P>(bounds: &syn::punctuated::Punctuated<T, P>) -> Vec<String> {
+    bounds
+        .iter()
+        .map(|b| b.to_token_stream().to_string())
+        .collect()
+}
+
+/// Remove each candidate of `item`, in the crate at `root`, alone, validate with
+/// `validator`, and record how it went. The file is restored after each trial, but a run
+/// killed mid-trial leaves it edited: give it a copy of the crate, as
+/// [`Winnower::trial_item`](crate::winnower::Winnower::trial_item) does.
+pub fn trial_candidates(
+    root: &Path,
+    validator: &dyn Validator,
+    item: &mut ItemExplanation,
+) -> TraitError<()> {
+    let options = ApplyOptions {
+        validator: Some(validator),
+        ..ApplyOptions::default()
+    };
+    let path = root.join(&item.locator.file);
+    for c in &mut item.candidates {
+        let before = std::fs::read(&path).map_err(|e| WinnowerError::io("reading", &path, e))?;
+        let plan = Plan {
+            fingerprint: None,
+            entries: vec![PlanEntry {
+                locator: item.locator.clone(),
+                candidate: c.candidate.clone(),
+            }],
+        };
+        let applied = apply_plan_with(root, &plan, &options);
+        std::fs::write(&path, &before).map_err(|e| WinnowerError::io("restoring", &path, e))?;
+        let applied = applied?;
+        let status = applied.entries.first().map(|e| &e.status);
+        c.trial = Some(match (status, &applied.validation) {
+            (Some(EntryStatus::Applied), Some(v)) if v.success && !applied.reverted => {
+                TrialOutcome {
+                    removable: true,
+                    digest: Vec::new(),
+                }
+            }
+            (Some(EntryStatus::Applied), Some(v)) => TrialOutcome {
+                removable: false,
+                digest: digest(&v.stderr),
+            },
+            (status, _) => TrialOutcome {
+                removable: false,
+                digest: vec![format!(
+                    "not applied: {}",
+                    status.map_or("no entry".to_owned(), ToString::to_string)
+                )],
+            },
+        });
+    }
+    Ok(())
+}
+
+/// The first errors of `stderr` and the bounds they blame.
+fn digest(stderr: &str) -> Vec<String> {
+    let mut lines: Vec<String> = stderr
+        .lines()
+        .filter(|l| l.starts_with("error") && !l.starts_with("error: could not compile"))
+        .take(DIGEST_ERRORS)
+        .map(str::to_owned)
+        .collect();
+    lines.extend(Blame::parse(stderr).into_iter().map(|b| {
+        format!(
+            "required by a bound in `{}` ({}:{})",
+            b.item,
+            b.file.display(),
+            b.line
+        )
+    }));
+    if lines.is_empty()
+        && let Some(last) = stderr.lines().rfind(|l| !l.trim().is_empty())
+    {
+        lines.push(last.trim().to_owned());
+    }
+    lines
+}
+
+/// Where `site` declares its bound, e.g. `type param 2, bound 0`.
+fn site_text(site: &OwnedBoundSite) -> String {
+    match site {
+        OwnedBoundSite::TypeParam {
+            param_index,
+            bound_index,
+            ..
+        } => format!("type param {param_index}, bound {bound_index}"),
+        OwnedBoundSite::WhereClause {
+            pred_index,
+            bound_index,
+            ..
+        } => format!("where predicate {pred_index}, bound {bound_index}"),
+        OwnedBoundSite::ImplTraitArg {
+            arg_index,
+            bound_index,
+            ..
+        } => format!("argument {arg_index}, bound {bound_index}"),
+        OwnedBoundSite::ReturnImplTrait { bound_index, .. } => {
+            format!("return type, bound {bound_index}")
+        }
+        OwnedBoundSite::TraitObject { bound_index, .. } => {
+            format!("trait object, bound {bound_index}")
+        }
+    }
+}
+
+impl ItemExplanation {
+    /// Write the explanation as text: the path, label and location, the declared bounds with
+    /// their indices, then each candidate with its rule, caution, last run and trial.
+    pub fn write_text<W: Write>(&self, out: &mut W) -> std::io::Result<()> {
+        let at = &self.locator;
+        writeln!(out, "{}", self.path)?;
+        writeln!(out, "  label: {}", self.label)?;
+        writeln!(out, "  at: {}:{}:{}", at.file.display(), at.line, at.column)?;
+        if !self.cfg.is_empty() {
+            writeln!(out, "  cfg: {}", self.cfg.join(", "))?;
+        }
+        let sections = [
+            ("type params", &self.type_params),
+            ("where predicates", &self.where_predicates),
+        ];
+        for (title, declared) in sections {
+            if declared.is_empty() {
+                continue;
+            }
+            writeln!(out, "  {title}:")?;
+            for d in declared {
+                if d.bounds.is_empty() {
+                    writeln!(out, "    [{}] {}", d.index, d.bounded)?;
+                } else {
+                    writeln!(
+                        out,
+                        "    [{}] {}: {}",
+                        d.index,
+                        d.bounded,
+                        d.bounds.join(" + ")
+                    )?;
+                }
+            }
+        }
+        if self.candidates.is_empty() {
+            return writeln!(out, "  no candidates");
+        }
+        writeln!(out, "  candidates:")?;
+        for c in &self.candidates {
+            writeln!(
+                out,
+                "    {} ({}; {}:{})",
+                c.candidate.declaration(),
+                site_text(&c.candidate.site),
+                c.line,
+                c.column
+            )?;
+            match &c.rule {
+                Some(r) => writeln!(
+                    out,
+                    "      rule: {} ({} confidence): {}",
+                    r.rule, r.confidence, r.message
+                )?,
+                None => writeln!(out, "      rule: none")?,
+            }
+            if let Some(caution) = &c.caution {
+                writeln!(out, "      caution: {caution}")?;
+            }
+            writeln!(out, "      last run: {}", c.last_run)?;
+            if let Some(trial) = &c.trial {
+                let verdict = if trial.removable {
+                    "removable"
+                } else {
+                    "needed"
+                };
+                writeln!(out, "      trial: {verdict}")?;
+                for line in &trial.digest {
+                    writeln!(out, "        {line}")?;
+                }
+            }
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn locator(module_path: &str, kind: ItemKind, name: &str) -> ItemLocator {
+        ItemLocator {
+            module_path: module_path.to_owned(),
+            kind,
+            name: name.to_owned(),
+            ..ItemLocator::default()
+        }
+    }
+
+    #[test]
+    fn paths_and_labels_name_the_same_item() {
+        let id = locator("b", ItemKind::ImplMethod, "Wrapper < T >::id");
+        assert_eq!(ItemPath::of(&id), "crate::b::Wrapper::id");
+        for query in [
+            "crate::b::Wrapper::id",
+            "b::Wrapper::id",
+            "crate::b::Wrapper<T>::id",
+            "b Wrapper < T >::id",
+            "b fn Wrapper::id",
+        ] {
+            assert!(ItemPath::parse(query).matches(&id), "{query}");
+        }
+        assert!(!ItemPath::parse("b struct Wrapper::id").matches(&id));
+        assert!(!ItemPath::parse("Wrapper::id").matches(&id));
+
+        let display = locator("", ItemKind::Impl, "Display for Wrapper < T >");
+        assert!(ItemPath::parse("crate::Display for Wrapper").matches(&display));
+        assert!(ItemPath::parse("impl Display for Wrapper<T>").matches(&display));
+        let foo = locator("a::inner", ItemKind::Fn, "foo");
+        assert!(ItemPath::parse("a::inner fn foo").matches(&foo));
+    }
+
+    #[test]
+    fn near_misses_get_suggestions() {
+        let paths = [
+            "crate::b::Wrapper::id",
+            "crate::b::Wrapper::into_inner",
+            "crate::a::helper",
+            "crate::c::Other::id",
+        ];
+        assert_eq!(
+            ItemPath::parse("crate::b::Wrapper::idd").suggest(paths),
+            ["crate::b::Wrapper::id", "crate::c::Other::id"]
+        );
+        assert_eq!(
+            ItemPath::parse("helper").suggest(paths),
+            ["crate::a::helper"]
+        );
+        assert!(ItemPath::parse("crate::zzz").suggest(paths).is_empty());
+        assert_eq!(edit_distance("kitten", "sitting"), 3);
+    }
+}
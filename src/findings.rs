@@ -0,0 +1,1312 @@
+// src/findings.rs
+//! Static rules: bounds that are redundant on their face, found without building the crate.
+//!
+//! [`analyze_file`] runs every rule over a source file, [`analyze`] over one already parsed.
+//! Each bound gets at most one verdict; rules are tried in [`Rule`] order.
+//!
+//! [`dyn_compat_caution`] is not a rule: it marks bounds that `prune` leaves alone because
+//! removing them can change what the crate means while it still builds; so do
+//! [`opaque_type_caution`] and [`modified_bound_caution`]. Nor is
+//! [`evident_use`], the reverse: bounds the body plainly uses, which `prune` need not check.
+
+#![deny(missing_docs)]
+
+use crate::analysis::{ItemBounds, ItemKey, ItemLocator, ItemRef, ItemVisibility};
+use crate::dynamic_analysis::common::{BoundCandidate, BoundSite, OwnedBoundCandidate};
+use crate::error::TraitError;
+use crate::trait_index::{TraitIndex, plain_trait, trait_name};
+use quote::ToTokens;
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeSet, HashMap};
+use syn::visit::Visit;
+use syn::{GenericParam, TraitBoundModifier, Type, TypeParamBound};
+
+/// Supertraits of well-known standard library traits, by name.
+const STD_SUPERTRAITS: &[(&str, &[&str])] = &[
+    ("Copy", &["Clone"]),
+    ("Eq", &["PartialEq"]),
+    ("PartialOrd", &["PartialEq"]),
+    ("Ord", &["Eq", "PartialOrd"]),
+    ("Error", &["Debug", "Display"]),
+    ("DerefMut", &["Deref"]),
+    ("DoubleEndedIterator", &["Iterator"]),
+    ("ExactSizeIterator", &["Iterator"]),
+    ("FusedIterator", &["Iterator"]),
+];
+
+/// A static rule.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum Rule {
+    /// A where predicate requires exactly the bounds an earlier one on the same type does,
+    /// in any order.
+    DuplicatePredicate,
+    /// The same bound appears twice on one parameter or type.
+    DuplicateBound,
+    /// Another bound on the same parameter or type has this one as a supertrait.
+    ImpliedBySupertrait,
+    /// `Sized` on a type parameter, which is `Sized` unless it opts out with `?Sized`.
+    RedundantSized,
+    /// A function body that makes no calls or operations, so nothing in it can use the
+    /// bound.
+    UnusedInBody,
+    /// A clippy lint on the bound, imported with `check --import-clippy` (see
+    /// [`ClippyFindings`](crate::dynamic_analysis::clippy::ClippyFindings)).
+    Clippy,
+}
+
+impl Rule {
+    /// Stable identifier, e.g. `duplicate-bound`.
+    pub fn id(self) -> &'static str {
+        match self {
+            Rule::DuplicatePredicate => "duplicate-predicate",
+            Rule::DuplicateBound => "duplicate-bound",
+            Rule::ImpliedBySupertrait => "implied-by-supertrait",
+            Rule::RedundantSized => "redundant-sized",
+            Rule::UnusedInBody => "unused-in-body",
+            Rule::Clippy => "clippy",
+        }
+    }
+}
+
+impl std::fmt::Display for Rule {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(self.id())
+    }
+}
+
+/// How likely a rule's verdict is right.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Confidence {
+    /// A heuristic; often wrong.
+    Low,
+    /// Right unless names resolve differently than they read.
+    Medium,
+    /// Right by the language rules.
+    High,
+}
+
+impl Confidence {
+    /// One step less sure; `Low` stays `Low`.
+    pub fn lowered(self) -> Self {
+        match self {
+            Confidence::High => Confidence::Medium,
+            Confidence::Medium | Confidence::Low => Confidence::Low,
+        }
+    }
+}
+
+impl std::fmt::Display for Confidence {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(match self {
+            Confidence::Low => "low",
+            Confidence::Medium => "medium",
+            Confidence::High => "high",
+        })
+    }
+}
+
+/// A rule's verdict on one bound.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct RuleMatch {
+    /// The rule that fired.
+    pub rule: Rule,
+    /// How likely it is right.
+    pub confidence: Confidence,
+    /// Why the bound is redundant, e.g. ``Clone` is implied by `Copy```.
+    pub message: String,
+}
+
+/// A bound flagged by a static rule.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Finding {
+    /// The verdict.
+    #[serde(flatten)]
+    pub verdict: RuleMatch,
+    /// The item declaring the bound.
+    pub locator: ItemLocator,
+    /// The bound and where it is declared.
+    pub candidate: OwnedBoundCandidate,
+    /// 1-based line where the bound starts.
+    pub line: usize,
+    /// 1-based column (in chars) where the bound starts.
+    pub column: usize,
+}
+
+impl Finding {
+    fn new(key: &ItemKey<'_>, candidate: &BoundCandidate, verdict: RuleMatch) -> Self {
+        let start = syn::spanned::Spanned::span(&candidate.bound).start();
+        Self {
+            verdict,
+            locator: ItemLocator::from(key),
+            candidate: OwnedBoundCandidate::from(candidate),
+            line: start.line,
+            column: start.column + 1,
+        }
+    }
+
+    /// The rule that fired.
+    pub fn rule(&self) -> Rule {
+        self.verdict.rule
+    }
+
+    /// The bound as tokens, e.g. `Clone`.
+    pub fn bound(&self) -> &str {
+        &self.candidate.bound_text
+    }
+}
+
+/// Run every rule over `src`.
+pub fn analyze_file(src: &str) -> TraitError<Vec<Finding>> {
+    let file = syn::parse_file(src)?;
+    let items = ItemBounds::collect_items_in_file(&file)?;
+    Ok(analyze(&file, &items))
+}
+
+/// Run every rule over the items of `file`, in [`ItemBounds::candidates`] order, knowing
+/// the traits `file` declares.
+pub fn analyze(file: &syn::File, items: &ItemBounds<'_>) -> Vec<Finding> {
+    analyze_in(items, &TraitIndex::of_file(file))
+}
+
+/// Run every rule over `items`, knowing the traits of `index`: those of the whole
+/// workspace, so a bound is found implied through supertraits declared in other files.
+pub fn analyze_in(items: &ItemBounds<'_>, index: &TraitIndex) -> Vec<Finding> {
+    let supertraits = Supertraits::new(index);
+    let candidates: Vec<(&ItemKey<'_>, BoundCandidate)> = items.candidates().collect();
+    let mut out = Vec::new();
+    for group in candidates.chunk_by(|a, b| std::ptr::eq(a.0, b.0)) {
+        let key = group[0].0;
+        let bounds: Vec<&BoundCandidate> = group.iter().map(|(_, c)| c).collect();
+        let item = ItemRules::new(key, &bounds, &supertraits);
+        for (i, candidate) in bounds.iter().enumerate() {
+            if let Some(verdict) = item.verdict(i) {
+                out.push(Finding::new(key, candidate, verdict));
+            }
+        }
+    }
+    out
+}
+
+/// Why removing `candidate` from `item` could change whether a trait is dyn-compatible, if
+/// it could. `where Self: Sized` on a trait keeps it from being used as `dyn Trait`; on a
+/// trait method it exempts the method from the dyn-compatibility rules. Either way the crate
+/// may still build without the bound while its `dyn` story silently changes.
+pub fn dyn_compat_caution(item: &ItemRef<'_>, candidate: &BoundCandidate) -> Option<String> {
+    let BoundSite::WhereClause { ty, .. } = &candidate.site else {
+        return None;
+    };
+    let is_self = matches!(&**ty, Type::Path(p) if p.qself.is_none() && p.path.is_ident("Self"));
+    if !is_self || plain_trait(&candidate.bound).as_deref() != Some("Sized") {
+        return None;
+    }
+    match item {
+        ItemRef::Trait(t) => Some(format!(
+            "`Self: Sized` keeps trait `{}` from being used as `dyn {}`; removing it changes \
+             its dyn-compatibility",
+            t.ident, t.ident
+        )),
+        ItemRef::TraitMethod {
+            trait_ident,
+            method,
+        } => Some(format!(
+            "`Self: Sized` exempts `{trait_ident}::{}` from dyn-compatibility; removing it can \
+             make trait `{trait_ident}` unusable as `dyn {trait_ident}`",
+            method.sig.ident
+        )),
+        _ => None,
+    }
+}
+
+/// Why the bounds of `item` are left alone, if it is an `unsafe impl`: the impl of an
+/// unsafe trait, such as `Send`, `Sync` or one the crate declares `unsafe trait`, vouches
+/// for soundness the compiler cannot check, and its bounds are usually what makes that
+/// claim true. `unsafe impl<T: Send> Send for Wrapper<T>` builds just as well without
+/// `T: Send`, and is then unsound.
+pub fn unsafe_impl_caution(item: &ItemRef<'_>) -> Option<String> {
+    let ItemRef::Impl(im) = item else {
+        return None;
+    };
+    im.unsafety?;
+    let (_, trait_path, _) = im.trait_.as_ref()?;
+    Some(format!(
+        "`unsafe impl {} for {}` vouches for its own soundness; removing a bound can still \
+         build while making the impl unsound",
+        trait_path.to_token_stream(),
+        im.self_ty.to_token_stream()
+    ))
+}
+
+/// Why removing `candidate` changes the item's public type, if it does: bounds of a
+/// return-position `impl Trait` are all callers may rely on, and a trait object's are part
+/// of the type itself. Callers may break without the crate noticing.
+pub fn opaque_type_caution(candidate: &BoundCandidate) -> Option<String> {
+    let bound = candidate.bound.to_token_stream();
+    match &candidate.site {
+        BoundSite::ReturnImplTrait { ty, .. } => Some(format!(
+            "callers of `{}` may rely on `{bound}`; removing it changes the public return type",
+            ty.to_token_stream()
+        )),
+        BoundSite::TraitObject { ty, .. } => Some(format!(
+            "`{bound}` is part of the type `{}`; removing it changes the public type",
+            ty.to_token_stream()
+        )),
+        _ => None,
+    }
+}
+
+/// Why `prune` never tries `candidate`, if it is a modified bound: anything but a plain
+/// trait bound or a lifetime, e.g. `?Sized`, `const Trait` or `[const] Trait`. Removing a
+/// modifier's bound can change what the item means in ways `cargo check`, on this
+/// toolchain, need not notice. (`~const Trait` and `!Trait` are nightly syntax syn does
+/// not parse; files using them fail to parse before any bound is considered.)
+pub fn modified_bound_caution(candidate: &BoundCandidate) -> Option<String> {
+    let plain = match &candidate.bound {
+        TypeParamBound::Trait(t) => matches!(t.modifier, TraitBoundModifier::None),
+        TypeParamBound::Lifetime(_) => true,
+        _ => false,
+    };
+    (!plain).then(|| {
+        format!(
+            "`{}` is a modified bound; removing it can change the item's meaning without \
+             `cargo check` noticing",
+            candidate.bound.to_token_stream()
+        )
+    })
+}
+
+/// The type parameter of `generics` that `candidate` bounds: the one it is declared on, or
+/// the whole bounded type of its where predicate. `None` for concrete and compound types,
+/// like `String` in `where String: From<T>` or `Vec<T>` in `where Vec<T>: IntoIterator`:
+/// the rules cannot judge their predicates by name, so only `prune`'s checks do.
+pub fn bounded_param<'c>(
+    generics: &syn::Generics,
+    candidate: &'c BoundCandidate,
+) -> Option<&'c syn::Ident> {
+    let ident = match &candidate.site {
+        BoundSite::TypeParam { ident, .. } => ident,
+        BoundSite::WhereClause { ty, .. } => match &**ty {
+            Type::Path(p) if p.qself.is_none() => p.path.get_ident()?,
+            _ => return None,
+        },
+        BoundSite::ImplTraitArg { .. }
+        | BoundSite::ReturnImplTrait { .. }
+        | BoundSite::TraitObject { .. } => return None,
+    };
+    generics
+        .type_params()
+        .any(|tp| tp.ident == *ident)
+        .then_some(ident)
+}
+
+/// The default of the type parameter `candidate` bounds, on its own or in a where clause,
+/// when `key` is public: `String` for `T: Clone` in `pub struct S<T: Clone = String>`.
+/// `cargo check` proves the default still meets the bounds left, but loosening the bounds
+/// of a public default is a promise: tightening them again later breaks callers.
+pub fn public_param_default(key: &ItemKey<'_>, candidate: &BoundCandidate) -> Option<String> {
+    if key.visibility() != ItemVisibility::Public {
+        return None;
+    }
+    let param = bounded_param(key.item().generics(), candidate)?;
+    key.item()
+        .generics()
+        .type_params()
+        .find(|tp| tp.ident == *param)?
+        .default
+        .as_ref()
+        .map(tokens)
+}
+
+/// Methods of well-known standard library traits, by trait name, that [`evident_use`]
+/// takes as proof the trait is used. Only methods no other standard trait in the table
+/// declares.
+const STD_METHODS: &[(&str, &[&str])] = &[
+    ("Clone", &["clone", "clone_from"]),
+    ("Default", &["default"]),
+    ("PartialEq", &["eq", "ne"]),
+    ("PartialOrd", &["partial_cmp", "lt", "le", "gt", "ge"]),
+    ("Ord", &["cmp"]),
+    ("Hash", &["hash"]),
+];
+
+/// Standard traits without methods [`evident_use`] could confuse with those of
+/// [`STD_METHODS`].
+const STD_MARKERS: &[&str] = &[
+    "Copy", "Eq", "Debug", "Display", "Send", "Sync", "Sized", "Unpin",
+];
+
+/// Syntactic proof that the body of `item` uses `candidate`, if there is any: a method of
+/// the bound called on a parameter taken by value as the bounded type (`t.clone()` with
+/// `t: T`), or named by path (`T::default()`, `<T as Default>::default`). Only the
+/// methods of [`STD_METHODS`] count, and only while every other bound on the type is a
+/// standard trait that neither declares the method nor implies the bound, so the proof
+/// holds however names resolve. `prune` skips such bounds rather than check them.
+pub fn evident_use(item: &ItemRef<'_>, candidate: &BoundCandidate) -> Option<String> {
+    let (sig, body) = match item {
+        ItemRef::Func(f) => (&f.sig, &*f.block),
+        ItemRef::ImplMethod { method, .. } => (&method.sig, &method.block),
+        ItemRef::TraitMethod { method, .. } => (&method.sig, method.default.as_ref()?),
+        _ => return None,
+    };
+    let param = bounded_param(&sig.generics, candidate)?.clone();
+    let name = plain_trait(&candidate.bound)?;
+    let (_, methods) = STD_METHODS.iter().find(|(t, _)| *t == name)?;
+    if !sibling_bounds(&sig.generics, &param).all(|b| leaves_to(b, &name, methods)) {
+        return None;
+    }
+    let mut uses = Uses {
+        param: &param,
+        by_value: sig
+            .inputs
+            .iter()
+            .filter_map(|arg| match arg {
+                syn::FnArg::Typed(pt) => Some(pt),
+                syn::FnArg::Receiver(_) => None,
+            })
+            .filter(|pt| names_param(&pt.ty, &param))
+            .filter_map(|pt| match &*pt.pat {
+                syn::Pat::Ident(pi) if pi.by_ref.is_none() && pi.subpat.is_none() => {
+                    Some(pi.ident.clone())
+                }
+                _ => None,
+            })
+            .collect(),
+        rebound: Vec::new(),
+        trait_name: &name,
+        methods,
+        found: Vec::new(),
+    };
+    // A parameter shadowed anywhere in the body may not be the one a call goes through.
+    uses.visit_block(body);
+    uses.found
+        .into_iter()
+        .find(|(receiver, _)| receiver.as_ref().is_none_or(|r| !uses.rebound.contains(r)))
+        .map(|(_, proof)| proof)
+}
+
+/// Whether `ty` is just the type parameter `param`.
+fn names_param(ty: &Type, param: &syn::Ident) -> bool {
+    matches!(ty, Type::Path(p) if p.qself.is_none() && p.path.is_ident(param))
+}
+
+/// Every bound declared on type parameter `param` in `generics`, inline or in the where
+/// clause.
+fn sibling_bounds<'g>(
+    generics: &'g syn::Generics,
+    param: &'g syn::Ident,
+) -> impl Iterator<Item = &'g TypeParamBound> + 'g {
+    let inline = generics.params.iter().flat_map(move |p| match p {
+        GenericParam::Type(tp) if tp.ident == *param => tp.bounds.iter().collect(),
+        _ => Vec::new(),
+    });
+    let clauses = generics.where_clause.iter().flat_map(move |w| {
+        w.predicates.iter().flat_map(move |pred| match pred {
+            syn::WherePredicate::Type(pt) if names_param(&pt.bounded_ty, param) => {
+                pt.bounds.iter().collect()
+            }
+            _ => Vec::new(),
+        })
+    });
+    inline.chain(clauses)
+}
+
+/// Whether `bound`, on the same type as trait `name`, leaves calls of `methods` to `name`:
+/// a lifetime, `?Sized`, `name` itself, or a standard trait that neither declares one of
+/// `methods` nor has `name` among its supertraits.
+fn leaves_to(bound: &TypeParamBound, name: &str, methods: &[&str]) -> bool {
+    let TypeParamBound::Trait(t) = bound else {
+        return matches!(bound, TypeParamBound::Lifetime(_));
+    };
+    if matches!(t.modifier, TraitBoundModifier::Maybe(_)) {
+        return true;
+    }
+    let Some(other) = plain_trait(bound) else {
+        return false;
+    };
+    if other == name {
+        return true;
+    }
+    let declares = STD_METHODS
+        .iter()
+        .find(|(t, _)| *t == other)
+        .map(|(_, m)| m.iter().any(|m| methods.contains(m)));
+    let known = declares.is_some() || STD_MARKERS.contains(&other.as_str());
+    let mut stack = vec![other.as_str()];
+    while let Some(t) = stack.pop() {
+        let Some((_, supers)) = STD_SUPERTRAITS.iter().find(|(s, _)| *s == t) else {
+            continue;
+        };
+        if supers.contains(&name) {
+            return false;
+        }
+        stack.extend(supers.iter().copied());
+    }
+    known && declares != Some(true)
+}
+
+/// Calls and paths in a body that go through one bound (see [`evident_use`]). Nested
+/// items are skipped.
+struct Uses<'a> {
+    param: &'a syn::Ident,
+    /// Parameters taken by value as `param`.
+    by_value: Vec<syn::Ident>,
+    /// Names bound anywhere in the body.
+    rebound: Vec<syn::Ident>,
+    trait_name: &'a str,
+    methods: &'a [&'a str],
+    /// Each proof, with the parameter it goes through if it is a method call.
+    found: Vec<(Option<syn::Ident>, String)>,
+}
+
+impl<'ast> Visit<'ast> for Uses<'_> {
+    fn visit_expr_method_call(&mut self, call: &'ast syn::ExprMethodCall) {
+        if let syn::Expr::Path(p) = &*call.receiver
+            && p.qself.is_none()
+            && let Some(receiver) = p.path.get_ident()
+            && self.by_value.contains(receiver)
+            && self.methods.contains(&call.method.to_string().as_str())
+        {
+            self.found.push((
+                Some(receiver.clone()),
+                format!(
+                    "`{receiver}.{}()` calls `{}::{}`",
+                    call.method, self.trait_name, call.method
+                ),
+            ));
+        }
+        syn::visit::visit_expr_method_call(self, call);
+    }
+
+    fn visit_expr_path(&mut self, p: &'ast syn::ExprPath) {
+        let segments: Vec<&syn::PathSegment> = p.path.segments.iter().collect();
+        let (param, name) = (self.param, self.trait_name);
+        let named = match (&p.qself, &segments[..]) {
+            (None, [ty, method])
+                if ty.ident == *param
+                    && ty.arguments.is_none()
+                    && p.path.leading_colon.is_none() =>
+            {
+                Some((method, format!("{param}::{}", method.ident)))
+            }
+            (Some(q), [.., tr, method])
+                if q.position == segments.len() - 1
+                    && tr.ident == name
+                    && names_param(&q.ty, param) =>
+            {
+                Some((method, format!("<{param} as {name}>::{}", method.ident)))
+            }
+            _ => None,
+        };
+        if let Some((method, written)) = named
+            && self.methods.contains(&method.ident.to_string().as_str())
+        {
+            self.found.push((
+                None,
+                format!("`{written}` calls `{name}::{}`", method.ident),
+            ));
+        }
+        syn::visit::visit_expr_path(self, p);
+    }
+
+    fn visit_pat_ident(&mut self, pi: &'ast syn::PatIdent) {
+        self.rebound.push(pi.ident.clone());
+        syn::visit::visit_pat_ident(self, pi);
+    }
+
+    fn visit_item(&mut self, _: &'ast syn::Item) {}
+}
+
+/// Supertrait names by trait name: the standard table, overridden by the traits of an
+/// index. A local trait's are as sure as the standard ones unless another trait, local or
+/// standard, shares its name.
+struct Supertraits {
+    direct: HashMap<String, (Vec<String>, Confidence)>,
+}
+
+impl Supertraits {
+    fn new(index: &TraitIndex) -> Self {
+        let mut direct: HashMap<String, (Vec<String>, Confidence)> = STD_SUPERTRAITS
+            .iter()
+            .map(|(name, supers)| {
+                let supers = supers.iter().map(|s| s.to_string()).collect();
+                (name.to_string(), (supers, Confidence::High))
+            })
+            .collect();
+        for (name, supers) in index.traits() {
+            let shadows_std = STD_SUPERTRAITS.iter().any(|(std, _)| *std == name);
+            let confidence = match index.is_ambiguous(name) || shadows_std {
+                true => Confidence::Medium,
+                false => Confidence::High,
+            };
+            direct.insert(name.to_owned(), (supers.to_vec(), confidence));
+        }
+        Self { direct }
+    }
+
+    /// Whether `sub` has `sup` among its supertraits, transitively, and how sure that is.
+    fn implies(&self, sub: &str, sup: &str) -> Option<Confidence> {
+        let mut stack = vec![(sub, Confidence::High)];
+        let mut seen = vec![sub];
+        while let Some((name, confidence)) = stack.pop() {
+            let Some((supers, own)) = self.direct.get(name) else {
+                continue;
+            };
+            let confidence = confidence.min(*own);
+            for s in supers {
+                if s == sup {
+                    return Some(confidence);
+                }
+                if !seen.contains(&s.as_str()) {
+                    seen.push(s);
+                    stack.push((s, confidence));
+                }
+            }
+        }
+        None
+    }
+}
+
+fn tokens<T: ToTokens>(node: &T) -> String {
+    node.to_token_stream().to_string()
+}
+
+/// The bounds of one item, ready for the rules.
+struct ItemRules<'a> {
+    key: &'a ItemKey<'a>,
+    bounds: &'a [&'a BoundCandidate],
+    supertraits: &'a Supertraits,
+    /// The bounded parameter or type of each bound, as tokens.
+    bounded: Vec<String>,
+    /// Each bound as tokens.
+    texts: Vec<String>,
+    /// The where predicate of each bound, by index; `None` outside the where clause.
+    predicate: Vec<Option<usize>>,
+    /// Whether each bound sits on one of the item's own type parameters.
+    on_own_param: Vec<bool>,
+    /// Whether the item is a struct or enum with `#[derive]`s.
+    derived: bool,
+}
+
+impl<'a> ItemRules<'a> {
+    fn new(
+        key: &'a ItemKey<'a>,
+        bounds: &'a [&'a BoundCandidate],
+        supertraits: &'a Supertraits,
+    ) -> Self {
+        let generics = key.item().generics();
+        let mut bounded = Vec::new();
+        let mut on_own_param = Vec::new();
+        for c in bounds {
+            match &c.site {
+                BoundSite::TypeParam { ident, .. } => {
+                    bounded.push(ident.to_string());
+                    on_own_param.push(true);
+                }
+                BoundSite::WhereClause { ty, .. } => {
+                    // `String: From<T>` or `Vec<T>: IntoIterator`: no rule but those
+                    // comparing bounds on the same type judges a concrete or compound type.
+                    bounded.push(tokens(ty));
+                    on_own_param.push(bounded_param(generics, c).is_some());
+                }
+                BoundSite::ImplTraitArg { arg, .. } => {
+                    // An argument's `impl Trait` is an anonymous type parameter of its own.
+                    bounded.push(tokens(arg));
+                    on_own_param.push(true);
+                }
+                BoundSite::ReturnImplTrait { ty, .. } | BoundSite::TraitObject { ty, .. } => {
+                    bounded.push(tokens(ty));
+                    on_own_param.push(false);
+                }
+            }
+        }
+        let texts = bounds.iter().map(|c| tokens(&c.bound)).collect();
+        let predicate = bounds
+            .iter()
+            .map(|c| match c.site {
+                BoundSite::WhereClause { pred_index, .. } => Some(pred_index),
+                _ => None,
+            })
+            .collect();
+        Self {
+            key,
+            bounds,
+            supertraits,
+            bounded,
+            texts,
+            predicate,
+            on_own_param,
+            derived: !key.item().derives().is_empty(),
+        }
+    }
+
+    /// The first rule that fires on bound `i`, one step less sure on types with derives:
+    /// derived impls copy their where clause, so the bound may be load-bearing after all.
+    fn verdict(&self, i: usize) -> Option<RuleMatch> {
+        let mut verdict = self
+            .duplicate_predicate(i)
+            .or_else(|| self.duplicate(i))
+            .or_else(|| self.implied(i))
+            .or_else(|| self.sized(i))
+            .or_else(|| self.unused_in_body(i))?;
+        if self.derived {
+            verdict.confidence = verdict.confidence.lowered();
+        }
+        Some(verdict)
+    }
+
+    /// Indices of the other bounds on the same parameter or type.
+    fn siblings(&self, i: usize) -> impl Iterator<Item = usize> + '_ {
+        (0..self.bounds.len()).filter(move |&j| j != i && self.bounded[j] == self.bounded[i])
+    }
+
+    /// Indices of the bounds of where predicate `pred`, in source order.
+    fn predicate_bounds(&self, pred: usize) -> impl Iterator<Item = usize> + '_ {
+        (0..self.bounds.len()).filter(move |&j| self.predicate[j] == Some(pred))
+    }
+
+    /// The distinct bounds of where predicate `pred`, as tokens.
+    fn predicate_set(&self, pred: usize) -> BTreeSet<&str> {
+        self.predicate_bounds(pred)
+            .map(|j| self.texts[j].as_str())
+            .collect()
+    }
+
+    /// Where predicate `pred` as written, e.g. `T: Clone + Debug`.
+    fn predicate_text(&self, pred: usize) -> String {
+        let bounds: Vec<&str> = self
+            .predicate_bounds(pred)
+            .map(|j| self.texts[j].as_str())
+            .collect();
+        let first = self.predicate_bounds(pred).next().unwrap_or_default();
+        format!("{}: {}", self.bounded[first], bounds.join(" + "))
+    }
+
+    /// Earlier where predicates on the same type as bound `i`'s, by index.
+    fn earlier_predicates(&self, i: usize) -> Vec<usize> {
+        let Some(pred) = self.predicate[i] else {
+            return Vec::new();
+        };
+        let mut earlier: Vec<usize> = self
+            .siblings(i)
+            .filter_map(|j| self.predicate[j].filter(|&p| p < pred))
+            .collect();
+        earlier.dedup();
+        earlier
+    }
+
+    fn duplicate_predicate(&self, i: usize) -> Option<RuleMatch> {
+        let set = self.predicate_set(self.predicate[i]?);
+        let earlier = self
+            .earlier_predicates(i)
+            .into_iter()
+            .find(|&p| self.predicate_set(p) == set)?;
+        Some(RuleMatch {
+            rule: Rule::DuplicatePredicate,
+            confidence: Confidence::High,
+            message: format!(
+                "`{}` repeats an earlier predicate of the where clause",
+                self.predicate_text(earlier)
+            ),
+        })
+    }
+
+    /// Inline bounds and where predicates are compared bound by bound; where two
+    /// predicates on a type overlap without being equal, the message names the one
+    /// covering the other.
+    fn duplicate(&self, i: usize) -> Option<RuleMatch> {
+        let first = self
+            .siblings(i)
+            .take_while(|&j| j < i)
+            .find(|&j| self.texts[j] == self.texts[i])?;
+        let mut message = format!(
+            "`{}` is already required on `{}`",
+            self.texts[i], self.bounded[i]
+        );
+        if let (Some(p), Some(q)) = (self.predicate[first], self.predicate[i])
+            && p != q
+        {
+            let (earlier, this) = (self.predicate_set(p), self.predicate_set(q));
+            if this.is_subset(&earlier) {
+                message += &format!(
+                    "; `{}` covers all of this predicate",
+                    self.predicate_text(p)
+                );
+            } else if earlier.is_subset(&this) {
+                message += &format!("; this predicate extends `{}`", self.predicate_text(p));
+            }
+        }
+        Some(RuleMatch {
+            rule: Rule::DuplicateBound,
+            confidence: Confidence::High,
+            message,
+        })
+    }
+
+    /// Only plain bounds are implied, as supertraits with generic arguments are not
+    /// indexed; the implying bound may have arguments (`Tagged<u8>` implies `Debug` for
+    /// `trait Tagged<K>: Debug`).
+    fn implied(&self, i: usize) -> Option<RuleMatch> {
+        let name = plain_trait(&self.bounds[i].bound)?;
+        self.siblings(i).find_map(|j| {
+            let by = trait_name(&self.bounds[j].bound)?;
+            let confidence = self.supertraits.implies(&by, &name)?;
+            Some(RuleMatch {
+                rule: Rule::ImpliedBySupertrait,
+                confidence,
+                message: format!("`{name}` is implied by `{by}` on `{}`", self.bounded[i]),
+            })
+        })
+    }
+
+    fn sized(&self, i: usize) -> Option<RuleMatch> {
+        (self.on_own_param[i] && plain_trait(&self.bounds[i].bound).as_deref() == Some("Sized"))
+            .then(|| RuleMatch {
+                rule: Rule::RedundantSized,
+                confidence: Confidence::High,
+                message: format!(
+                    "`{}` is `Sized` already; type parameters opt out with `?Sized`",
+                    self.bounded[i]
+                ),
+            })
+    }
+
+    fn unused_in_body(&self, i: usize) -> Option<RuleMatch> {
+        let body = match self.key.item() {
+            ItemRef::Func(f) => &*f.block,
+            ItemRef::ImplMethod { method, .. } => &method.block,
+            ItemRef::TraitMethod { method, .. } => method.default.as_ref()?,
+            _ => return None,
+        };
+        if !self.on_own_param[i] || !matches!(self.bounds[i].bound, TypeParamBound::Trait(_)) {
+            return None;
+        }
+        let mut activity = Activity::default();
+        activity.visit_block(body);
+        if activity.found {
+            return None;
+        }
+        let name = self
+            .key
+            .ident()
+            .map_or_else(|| "the function".to_owned(), |i| format!("`{i}`"));
+        Some(RuleMatch {
+            rule: Rule::UnusedInBody,
+            confidence: Confidence::Low,
+            message: format!(
+                "the body of {name} makes no calls or operations that could use `{}: {}`",
+                self.bounded[i], self.texts[i]
+            ),
+        })
+    }
+}
+
+/// Finds anything in a body that could go through a trait: calls, operators, macros,
+/// field and index access, loops, matches, `?` and `.await`. Nested items are skipped.
+#[derive(Default)]
+struct Activity {
+    found: bool,
+}
+
+macro_rules! mark_activity {
+    ( $( $visit:ident : $node:ty ),+ $(,)? ) => {
+        $(
+            fn $visit(&mut self, _: &'ast $node) {
+                self.found = true;
+            }
+        )+
+    };
+}
+
+impl<'ast> Visit<'ast> for Activity {
+    mark_activity! {
+        visit_expr_method_call: syn::ExprMethodCall,
+        visit_expr_call: syn::ExprCall,
+        visit_expr_macro: syn::ExprMacro,
+        visit_stmt_macro: syn::StmtMacro,
+        visit_expr_binary: syn::ExprBinary,
+        visit_expr_unary: syn::ExprUnary,
+        visit_expr_field: syn::ExprField,
+        visit_expr_index: syn::ExprIndex,
+        visit_expr_for_loop: syn::ExprForLoop,
+        visit_expr_match: syn::ExprMatch,
+        visit_expr_let: syn::ExprLet,
+        visit_expr_try: syn::ExprTry,
+        visit_expr_await: syn::ExprAwait,
+        visit_expr_closure: syn::ExprClosure,
+    }
+
+    fn visit_item(&mut self, _: &'ast syn::Item) {}
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// `(rule, bound)` of every finding in `src`.
+    fn rules(src: &str) -> TraitError<Vec<(Rule, String)>> {
+        Ok(analyze_file(src)?
+            .into_iter()
+            .map(|f| (f.rule(), f.bound().to_owned()))
+            .collect())
+    }
+
+    #[test]
+    fn duplicate_bounds_flag_the_repeat() -> TraitError<()> {
+        assert_eq!(
+            rules("fn f<T: Clone + Clone>(t: &T) { t.clone(); }")?,
+            [(Rule::DuplicateBound, "Clone".into())]
+        );
+        // Across the parameter list and the where clause.
+        let found = analyze_file("fn f<T: Send>(t: &T) where T: Send { drop(t); }")?;
+        assert_eq!(found.len(), 1);
+        assert_eq!(found[0].rule(), Rule::DuplicateBound);
+        assert!(matches!(
+            found[0].candidate.site,
+            crate::dynamic_analysis::common::OwnedBoundSite::WhereClause { .. }
+        ));
+        assert_eq!(found[0].verdict.confidence, Confidence::High);
+        // Same bound, different parameters.
+        assert!(rules("fn f<T: Send, U: Send>(t: T, u: U) { drop((t, u)); }")?.is_empty());
+        Ok(())
+    }
+
+    #[test]
+    fn repeated_where_predicates_are_flagged_whole() -> TraitError<()> {
+        let found = analyze_file(
+            "fn f<T, U>(t: T, u: U) where T: Clone + Debug, U: Debug, T: Debug + Clone \
+             { drop((t, u)); }",
+        )?;
+        let flagged: Vec<(Rule, &str, Confidence)> = found
+            .iter()
+            .map(|f| (f.rule(), f.bound(), f.verdict.confidence))
+            .collect();
+        assert_eq!(
+            flagged,
+            [
+                (Rule::DuplicatePredicate, "Debug", Confidence::High),
+                (Rule::DuplicatePredicate, "Clone", Confidence::High),
+            ]
+        );
+        assert_eq!(
+            found[0].verdict.message,
+            "`T: Clone + Debug` repeats an earlier predicate of the where clause"
+        );
+        // Repeats within a predicate don't change the bounds it requires.
+        assert_eq!(
+            rules("fn f<T>(t: T) where T: Send + Send, T: Send { drop(t); }")?,
+            [
+                (Rule::DuplicateBound, "Send".into()),
+                (Rule::DuplicatePredicate, "Send".into()),
+            ]
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn overlapping_where_predicates_name_the_covering_one() -> TraitError<()> {
+        let messages = |src: &str| -> TraitError<Vec<(Rule, String)>> {
+            Ok(analyze_file(src)?
+                .into_iter()
+                .map(|f| (f.rule(), f.verdict.message))
+                .collect())
+        };
+        assert_eq!(
+            messages("fn f<T>(t: T) where T: Clone + Debug, T: Clone { drop(t); }")?,
+            [(
+                Rule::DuplicateBound,
+                "`Clone` is already required on `T`; `T: Clone + Debug` covers all of this \
+                 predicate"
+                    .into()
+            )]
+        );
+        assert_eq!(
+            messages("fn f<T>(t: T) where T: Clone, T: Debug + Clone { drop(t); }")?,
+            [(
+                Rule::DuplicateBound,
+                "`Clone` is already required on `T`; this predicate extends `T: Clone`".into()
+            )]
+        );
+        // Overlapping but neither covering the other: just the repeated bound.
+        assert_eq!(
+            messages("fn f<T>(t: T) where T: Clone + Send, T: Clone + Sync { drop(t); }")?,
+            [(
+                Rule::DuplicateBound,
+                "`Clone` is already required on `T`".into()
+            )]
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn inline_bounds_are_no_predicate_to_repeat() -> TraitError<()> {
+        // A where predicate repeating inline bounds is judged bound by bound; one repeating
+        // another where predicate is flagged whole.
+        assert_eq!(
+            rules("fn f<T: Clone>(t: T) where T: Clone, T: Clone { drop(t); }")?,
+            [
+                (Rule::DuplicateBound, "Clone".into()),
+                (Rule::DuplicatePredicate, "Clone".into()),
+            ]
+        );
+        assert_eq!(
+            rules("fn f<T: Clone + Debug>(t: T) where T: Debug + Clone { drop(t); }")?,
+            [
+                (Rule::DuplicateBound, "Debug".into()),
+                (Rule::DuplicateBound, "Clone".into()),
+            ]
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn supertraits_imply_their_subtraits_bounds() -> TraitError<()> {
+        assert_eq!(
+            rules("fn f<T: Clone + Copy>(t: &T) { t.clone(); }")?,
+            [(Rule::ImpliedBySupertrait, "Clone".into())]
+        );
+        // Transitively: `Ord: Eq: PartialEq`.
+        assert_eq!(
+            rules("fn f<T: Ord>(a: T, b: T) -> bool where T: PartialEq { a == b }")?,
+            [(Rule::ImpliedBySupertrait, "PartialEq".into())]
+        );
+        assert!(rules("fn f<T: Clone + Send>(t: &T) { t.clone(); }")?.is_empty());
+        Ok(())
+    }
+
+    #[test]
+    fn local_supertraits_imply_unless_ambiguous() -> TraitError<()> {
+        let found = analyze_file(
+            "trait Shape: Named {}\ntrait Named {}\nfn f<T: Shape + Named>(t: &T) { t.area(); }",
+        )?;
+        assert_eq!(found.len(), 1);
+        assert_eq!(found[0].bound(), "Named");
+        assert_eq!(found[0].verdict.confidence, Confidence::High);
+        // Two traits of one name: which one the bound means is a guess.
+        let found = analyze_file(
+            "trait Shape: Named {}\nmod m { trait Shape {} }\ntrait Named {}\n\
+             fn f<T: Shape + Named>(t: &T) { t.area(); }",
+        )?;
+        assert_eq!(found[0].verdict.confidence, Confidence::Medium);
+        // A local trait shadows the standard one of the same name.
+        assert!(rules("trait Copy {}\nfn f<T: Copy + Clone>(t: &T) { t.clone(); }")?.is_empty());
+        let found = analyze_file("trait Eq: Debug {}\nfn f<T: Eq + Debug>(t: &T) { t.eq(); }")?;
+        assert_eq!(found[0].verdict.confidence, Confidence::Medium);
+        Ok(())
+    }
+
+    #[test]
+    fn supertrait_chains_imply_transitively() -> TraitError<()> {
+        let found = analyze_file(
+            "trait Super: Debug {}\ntrait Sub: Super {}\n\
+             fn f<T: Sub + Debug>(t: &T) { t.go(); }",
+        )?;
+        assert_eq!(found.len(), 1);
+        assert_eq!(
+            (found[0].rule(), found[0].bound()),
+            (Rule::ImpliedBySupertrait, "Debug")
+        );
+        assert_eq!(found[0].verdict.confidence, Confidence::High);
+        // `where Self:` predicates declare supertraits too.
+        assert_eq!(
+            rules("trait Sub where Self: Clone {}\nfn f<T: Sub + Clone>(t: &T) { t.go(); }")?,
+            [(Rule::ImpliedBySupertrait, "Clone".into())]
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn generic_impliers_imply_only_plain_supertraits() -> TraitError<()> {
+        // `Wrap<str>` implies `AsRef<str>`, not `AsRef<[u8]>`.
+        assert!(
+            rules(
+                "trait Wrap<X: ?Sized>: AsRef<X> {}\n\
+                 fn f<T: Wrap<str> + AsRef<[u8]>>(t: &T) { t.as_ref(); }"
+            )?
+            .is_empty()
+        );
+        assert_eq!(
+            rules("trait Tagged<K>: Debug {}\nfn f<T: Tagged<u8> + Debug>(t: &T) { t.go(); }")?,
+            [(Rule::ImpliedBySupertrait, "Debug".into())]
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn supertraits_of_other_files_imply() -> TraitError<()> {
+        let mut index = TraitIndex::default();
+        index.add_file(&syn::parse_file("pub trait Super: Debug {}")?);
+        index.add_file(&syn::parse_file("pub trait Sub: crate::a::Super {}")?);
+        let file = syn::parse_file("fn f<T: Sub + Debug>(t: &T) { t.go(); }")?;
+        let items = ItemBounds::collect_items_in_file(&file)?;
+        assert!(analyze(&file, &items).is_empty());
+        let found = analyze_in(&items, &index);
+        assert_eq!(found.len(), 1);
+        assert_eq!(found[0].bound(), "Debug");
+        Ok(())
+    }
+
+    #[test]
+    fn sized_on_type_parameters_is_redundant() -> TraitError<()> {
+        assert_eq!(
+            rules("struct S<T: Sized>(T);")?,
+            [(Rule::RedundantSized, "Sized".into())]
+        );
+        assert_eq!(
+            rules("struct S<T>(T) where T: Sized;")?,
+            [(Rule::RedundantSized, "Sized".into())]
+        );
+        // `?Sized` opts out; `Self: Sized` and bounds on other types mean something.
+        assert!(rules("struct S<T: ?Sized>(Box<T>);")?.is_empty());
+        assert!(rules("trait Tr { fn f(self) where Self: Sized; }")?.is_empty());
+        assert!(rules("struct S<T>(Vec<T>) where Vec<T>: Sized;")?.is_empty());
+        Ok(())
+    }
+
+    #[test]
+    fn bounds_of_inert_bodies_are_unused() -> TraitError<()> {
+        let found = analyze_file("fn f<T: Clone>(t: T) -> T { let u = t; u }")?;
+        assert_eq!(found.len(), 1);
+        assert_eq!(found[0].rule(), Rule::UnusedInBody);
+        assert_eq!(found[0].verdict.confidence, Confidence::Low);
+        assert_eq!(found[0].locator.name, "f");
+        assert_eq!((found[0].line, found[0].column), (1, 9));
+        // Any call, operator or macro could use the bound.
+        for body in [
+            "t.clone()",
+            "Clone::clone(&t)",
+            "{ println!(\"\"); t }",
+            "{ t == t; t }",
+        ] {
+            let src = format!("fn f<T: Clone + PartialEq>(t: T) -> T {{ {body} }}");
+            assert!(rules(&src)?.is_empty(), "{src}");
+        }
+        // Lifetime bounds, bounds on impl parameters and bodiless methods are left alone.
+        assert!(rules("fn f<'a, T: 'a>(t: &'a T) -> &'a T { t }")?.is_empty());
+        assert!(
+            rules("struct S<T>(T);\nimpl<T: Clone> S<T> { fn get(self) -> T { self.0 } }")?
+                .is_empty()
+        );
+        assert!(rules("trait Tr { fn f<T: Clone>(t: T); }")?.is_empty());
+        Ok(())
+    }
+
+    #[test]
+    fn predicates_on_concrete_types_are_left_to_checks() -> TraitError<()> {
+        // An inert body proves nothing about `String: Clone` or `Vec<T>: IntoIterator`, and
+        // `Sized` on a concrete type is no type parameter's default.
+        let src = "fn f<T>(t: T) -> usize where String: Clone + Clone, Vec<T>: IntoIterator, \
+                   String: Sized, T: Copy { 0 }";
+        assert_eq!(
+            rules(src)?,
+            [
+                (Rule::DuplicateBound, "Clone".into()),
+                (Rule::UnusedInBody, "Copy".into()),
+            ]
+        );
+        let file = syn::parse_file(src)?;
+        let items = ItemBounds::collect_items_in_file(&file)?;
+        let params: Vec<Option<String>> = items
+            .candidates()
+            .map(|(key, c)| bounded_param(key.item().generics(), &c).map(ToString::to_string))
+            .collect();
+        assert_eq!(params, [None, None, None, None, Some("T".to_owned())]);
+        Ok(())
+    }
+
+    #[test]
+    fn each_bound_gets_one_verdict() -> TraitError<()> {
+        assert_eq!(
+            rules("fn f<T: Copy + Clone + Copy + Sized>(t: T) -> T { t }")?,
+            [
+                (Rule::UnusedInBody, "Copy".into()),
+                (Rule::ImpliedBySupertrait, "Clone".into()),
+                (Rule::DuplicateBound, "Copy".into()),
+                (Rule::RedundantSized, "Sized".into()),
+            ]
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn derives_lower_the_confidence() -> TraitError<()> {
+        let found = analyze_file(
+            "#[derive(Clone, serde::Serialize)]\nstruct S<T: Clone + Copy>(T);\n\
+             struct P<T: Clone + Copy>(T);",
+        )?;
+        let confidence: Vec<Confidence> = found.iter().map(|f| f.verdict.confidence).collect();
+        assert_eq!(confidence, [Confidence::Medium, Confidence::High]);
+        // Every `#[derive]` counts, by the last segment of each path.
+        let file =
+            syn::parse_file("#[derive(Debug)]\n#[derive(serde::Serialize)]\nenum E<T> { A(T) }")?;
+        let syn::Item::Enum(e) = &file.items[0] else {
+            unreachable!()
+        };
+        assert_eq!(ItemRef::Enum(e).derives(), ["Debug", "Serialize"]);
+        Ok(())
+    }
+
+    #[test]
+    fn self_sized_on_traits_is_a_dyn_compat_caution() -> TraitError<()> {
+        let file = syn::parse_file(
+            "trait A where Self: Sized + Clone {}\n\
+             trait B { fn f(self) -> Self where Self: Sized; }\n\
+             trait C { fn g<T>(t: T) where T: Sized, Self: Send; }\n\
+             struct S<T>(T) where Self: Sized;",
+        )?;
+        let items = ItemBounds::collect_items_in_file(&file)?;
+        let flagged: Vec<(String, String)> = items
+            .candidates()
+            .filter(|(key, c)| dyn_compat_caution(key.item(), c).is_some())
+            .map(|(key, c)| (key.to_string(), tokens(&c.bound)))
+            .collect();
+        assert_eq!(flagged.len(), 2, "{flagged:?}");
+        assert!(flagged.iter().all(|(_, bound)| bound == "Sized"));
+        // Not a rule: only `T: Sized` is reported as redundant.
+        let found = analyze(&file, &items);
+        assert_eq!(found.len(), 1);
+        assert_eq!(found[0].rule(), Rule::RedundantSized);
+        Ok(())
+    }
+
+    #[test]
+    fn modified_bounds_are_cautioned() -> TraitError<()> {
+        // Nightly-only `const` modifiers parse on stable syn.
+        let file = syn::parse_file(
+            "const fn f<T: [const] Clone + Copy, U: const Default + ?Sized + 'static>() {}",
+        )?;
+        let items = ItemBounds::collect_items_in_file(&file)?;
+        let modified: Vec<(String, bool)> = items
+            .candidates()
+            .map(|(_, c)| (tokens(&c.bound), modified_bound_caution(&c).is_some()))
+            .collect();
+        let want = |bound: &str, modified| (bound.to_owned(), modified);
+        assert_eq!(
+            modified,
+            [
+                want("[const] Clone", true),
+                want("Copy", false),
+                want("const Default", true),
+                want("? Sized", true),
+                want("'static", false),
+            ]
+        );
+        // `~const` and negative bounds do not parse at all.
+        assert!(analyze_file("fn f<T: ~const Clone>() {}").is_err());
+        assert!(analyze_file("fn f<T>() where T: !Unpin {}").is_err());
+        Ok(())
+    }
+
+    #[test]
+    fn defaults_of_public_params_are_noted() -> TraitError<()> {
+        let file = syn::parse_file(
+            "pub struct S<A: Copy = u8, B: Clone>(A, B) where A: Send;\n\
+             struct P<T: Clone = String>(T);\n\
+             pub trait Tr<Rhs: ?Sized = Self> {}\n\
+             mod m { pub enum E<T: Default = ()> { V(T) } }",
+        )?;
+        let items = ItemBounds::collect_items_in_file(&file)?;
+        let noted: Vec<(String, String, Option<String>)> = items
+            .candidates()
+            .map(|(key, c)| {
+                let default = public_param_default(key, &c);
+                (key.to_string(), tokens(&c.bound), default)
+            })
+            .collect();
+        let some = |s: &str| Some(s.to_owned());
+        for want in [
+            ("// struct S", "Copy", some("u8")),
+            ("// struct S", "Send", some("u8")),
+            ("// struct S", "Clone", None),
+            // Private items, and public ones in private modules, make no promise.
+            ("// struct P", "Clone", None),
+            ("// m enum E", "Default", None),
+            ("// trait Tr", "? Sized", some("Self")),
+        ] {
+            let want = (want.0.to_owned(), want.1.to_owned(), want.2);
+            assert!(noted.contains(&want), "{want:?} in {noted:?}");
+        }
+        Ok(())
+    }
+
+    /// The proof [`evident_use`] finds for each bound in `src`, by bound.
+    fn evident(src: &str) -> TraitError<Vec<(String, Option<String>)>> {
+        let file = syn::parse_file(src)?;
+        let items = ItemBounds::collect_items_in_file(&file)?;
+        Ok(items
+            .candidates()
+            .map(|(key, c)| (tokens(&c.bound), evident_use(key.item(), &c)))
+            .collect())
+    }
+
+    #[test]
+    fn plain_calls_of_a_bounds_methods_are_evident_uses() -> TraitError<()> {
+        assert_eq!(
+            evident("fn f<T: Clone + Send>(t: T) -> (T, T) { (t.clone(), t) }")?,
+            [
+                (
+                    "Clone".into(),
+                    Some("`t.clone()` calls `Clone::clone`".into())
+                ),
+                ("Send".into(), None),
+            ]
+        );
+        assert_eq!(
+            evident("fn f<T>() -> T where T: Default { T::default() }")?[0]
+                .1
+                .as_deref(),
+            Some("`T::default` calls `Default::default`")
+        );
+        assert_eq!(
+            evident("fn f<T: Default>() -> Vec<T> { vec![<T as Default>::default()] }")?[0].1,
+            None,
+            "calls inside macros are not parsed"
+        );
+        assert_eq!(
+            evident("fn f<T: Default>() -> Option<T> { Some(<T as Default>::default()) }")?[0]
+                .1
+                .as_deref(),
+            Some("`<T as Default>::default` calls `Default::default`")
+        );
+        assert!(
+            evident("struct S;\nimpl S { fn eq<T: PartialEq>(a: T, b: &T) -> bool { a.eq(b) } }")?
+                [0]
+            .1
+            .is_some()
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn ambiguous_uses_are_not_evident() -> TraitError<()> {
+        for src in [
+            // `&T` is `Clone` whatever `T` is.
+            "fn f<T: Clone>(t: &T) -> &T { t.clone() }",
+            // The parameter is shadowed.
+            "fn f<T: Clone>(t: T) -> u8 { let t = 1u8; t.clone() }",
+            // `Copy` brings `Clone` along.
+            "fn f<T: Copy + Clone>(t: T) -> T { t.clone() }",
+            // An unknown trait may declare `clone` or have `Clone` as a supertrait.
+            "fn f<T: Clone + Shape>(t: T) -> T { t.clone() }",
+            // Not a method of the bound.
+            "fn f<T: Clone>(t: T) -> String { t.into() }",
+            // The bound is on the impl, not on the method's own parameter.
+            "struct S<T>(T);\nimpl<T: Clone> S<T> { fn get(t: T) -> T { t.clone() } }",
+        ] {
+            let found = evident(src)?;
+            assert!(
+                found
+                    .iter()
+                    .all(|(bound, proof)| bound != "Clone" || proof.is_none()),
+                "{src}: {found:?}"
+            );
+        }
+        // A repeat of the bound itself leaves the proof standing.
+        assert!(
+            evident("fn f<T: Clone>(t: T) -> T where T: Clone { t.clone() }")?
+                .iter()
+                .all(|(_, proof)| proof.is_some())
+        );
+        Ok(())
+    }
+}
@@ -0,0 +1,142 @@
+// src/fingerprint.rs
+//! The dependency versions and compiler a run saw, and whether a saved artifact saw the same.
+//!
+//! Whether a bound is removable can depend on both: a new release of a dependency may stop
+//! requiring `Clone`. Each run captures a [`Fingerprint`] of the workspace's `Cargo.lock`
+//! and `rustc --version` when it starts, and records it in its report, in plans made from
+//! the report and in the [`RunCache`](crate::dynamic_analysis::incremental::RunCache) of
+//! `--incremental` runs. Artifacts read back with another fingerprint have [`Drift`]:
+//! `prune` bypasses such a cache and `apply` warns about such a plan, unless told to ignore
+//! fingerprints.
+
+#![deny(missing_docs)]
+
+use crate::dynamic_analysis::common::CargoCheck;
+use serde::{Deserialize, Serialize};
+use std::fmt;
+use std::path::Path;
+
+/// The `Cargo.lock` and compiler of a run.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Fingerprint {
+    /// CRC32 of the workspace's `Cargo.lock` as 8 hex digits; `None` without a lockfile.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub lockfile: Option<String>,
+    /// What `rustc --version` printed; `None` when rustc could not be run.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub rustc: Option<String>,
+}
+
+impl Fingerprint {
+    /// The fingerprint of the crate or workspace at `root`, with the rustc cargo uses there.
+    pub fn capture(root: &Path) -> Self {
+        Self {
+            lockfile: Self::hash_lockfile(root),
+            rustc: CargoCheck::rustc_version(root),
+        }
+    }
+
+    /// CRC32 of the `Cargo.lock` cargo resolves `root` with: the one in `root` or in the
+    /// nearest directory above, which a workspace member shares with its workspace.
+    pub fn hash_lockfile(root: &Path) -> Option<String> {
+        let bytes = root
+            .ancestors()
+            .find_map(|dir| std::fs::read(dir.join("Cargo.lock")).ok())?;
+        Some(format!("{:08x}", crc32fast::hash(&bytes)))
+    }
+
+    /// How `self` differs from `recorded`, the fingerprint of an earlier run, one change
+    /// per entry; empty when they match. An artifact without a fingerprint differs.
+    pub fn changes_since(&self, recorded: Option<&Fingerprint>) -> Vec<String> {
+        let Some(recorded) = recorded else {
+            return vec!["no fingerprint recorded".to_owned()];
+        };
+        let mut changes = Vec::new();
+        match (&recorded.lockfile, &self.lockfile) {
+            (was, now) if was == now => {}
+            (None, Some(_)) => changes.push("Cargo.lock created".to_owned()),
+            (Some(_), None) => changes.push("Cargo.lock removed".to_owned()),
+            _ => changes.push("Cargo.lock changed".to_owned()),
+        }
+        if recorded.rustc != self.rustc {
+            let show = |v: &Option<String>| v.as_deref().unwrap_or("unknown").to_owned();
+            changes.push(format!(
+                "rustc was `{}`, now `{}`",
+                show(&recorded.rustc),
+                show(&self.rustc)
+            ));
+        }
+        changes
+    }
+}
+
+/// A saved artifact made with another fingerprint than the current run's.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Drift {
+    /// The artifact, e.g. `run cache` or `plan`.
+    pub artifact: String,
+    /// How the fingerprints differ (see [`Fingerprint::changes_since`]).
+    pub changes: Vec<String>,
+}
+
+impl Drift {
+    /// The drift of an `artifact` recorded with `recorded` from a run with `current`;
+    /// `None` when they match.
+    pub fn between(
+        artifact: &str,
+        recorded: Option<&Fingerprint>,
+        current: &Fingerprint,
+    ) -> Option<Self> {
+        let changes = current.changes_since(recorded);
+        (!changes.is_empty()).then(|| Self {
+            artifact: artifact.to_owned(),
+            changes,
+        })
+    }
+}
+
+impl fmt::Display for Drift {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{} was produced against different dependency versions; findings may differ ({})",
+            self.artifact,
+            self.changes.join("; ")
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn changes_name_the_lockfile_and_rustc() {
+        let fp = |lockfile: Option<&str>, rustc: &str| Fingerprint {
+            lockfile: lockfile.map(str::to_owned),
+            rustc: Some(rustc.to_owned()),
+        };
+        let now = fp(Some("0000beef"), "rustc 1.90.0");
+        assert!(now.changes_since(Some(&now)).is_empty());
+        assert_eq!(
+            now.changes_since(Some(&fp(Some("0000cafe"), "rustc 1.89.0"))),
+            [
+                "Cargo.lock changed",
+                "rustc was `rustc 1.89.0`, now `rustc 1.90.0`"
+            ]
+        );
+        assert_eq!(
+            now.changes_since(Some(&fp(None, "rustc 1.90.0"))),
+            ["Cargo.lock created"]
+        );
+        assert_eq!(now.changes_since(None), ["no fingerprint recorded"]);
+        let drift = Drift::between("plan", Some(&fp(Some("0000cafe"), "rustc 1.90.0")), &now);
+        assert_eq!(
+            drift.map(|d| d.to_string()).as_deref(),
+            Some(
+                "plan was produced against different dependency versions; findings may differ \
+                 (Cargo.lock changed)"
+            )
+        );
+    }
+}
@@ -0,0 +1,116 @@
+// src/fix.rs
+//! `check --fix`: remove the bounds static rules prove redundant by the language rules
+//! alone, without running cargo.
+//!
+//! Only high-confidence findings of [`FIXABLE_RULES`] are fixed, and none `prune` would
+//! leave alone by default (see [`Finding::caution`]). Edits go through the same text-edit
+//! writer as [`apply_plan`](crate::plan::apply_plan), so formatting and comments around the
+//! bounds survive.
+
+#![deny(missing_docs)]
+
+use crate::analysis::ItemBounds;
+use crate::dynamic_analysis::text_edit::apply_edits;
+use crate::error::{TraitError, WinnowerError};
+use crate::findings::{Confidence, Rule};
+use crate::patch::FileChange;
+use crate::plan::{PlanEntry, entry_edits};
+use crate::report::{Finding, Report};
+use std::collections::BTreeMap;
+use std::path::Path;
+
+/// Rules whose high-confidence findings `check --fix` removes: a where predicate repeating
+/// an earlier one, a bound repeated on the same parameter or type, inline or in the where
+/// clause, `Sized` on a type parameter, and a bound a sibling implies through supertraits
+/// whose declaration is unambiguous. Predicates that only overlap lose just their repeated
+/// bounds.
+pub const FIXABLE_RULES: &[Rule] = &[
+    Rule::DuplicatePredicate,
+    Rule::DuplicateBound,
+    Rule::RedundantSized,
+    Rule::ImpliedBySupertrait,
+];
+
+/// What [`fix_report`] changed, or would change with `--dry-run`.
+#[derive(Debug, Clone, Default)]
+pub struct FixReport {
+    /// Each file fixed, relative to the root, with its text before and after.
+    pub changes: Vec<FileChange>,
+    /// Bounds removed per rule.
+    pub fixed: BTreeMap<Rule, usize>,
+}
+
+impl FixReport {
+    /// Bounds removed across all rules.
+    pub fn total(&self) -> usize {
+        self.fixed.values().sum()
+    }
+}
+
+/// Whether `check --fix` removes the bound of `finding`.
+pub fn is_fixable(finding: &Finding) -> bool {
+    finding.caution.is_none()
+        && !finding.modified_bound
+        && finding
+            .rule
+            .as_ref()
+            .is_some_and(|r| r.confidence == Confidence::High && FIXABLE_RULES.contains(&r.rule))
+}
+
+/// Remove the fixable bounds of `report`, a `check` of the sources under `root`, writing
+/// the files unless `dry_run`. Sources are re-read, so a file changed since the check is
+/// fixed as it now reads, or left alone where a bound no longer matches. A failed write
+/// puts back the files already written.
+pub fn fix_report(root: &Path, report: &Report, dry_run: bool) -> TraitError<FixReport> {
+    let mut out = FixReport::default();
+    for file in &report.files {
+        let fixable: Vec<&Finding> = file.findings.iter().filter(|f| is_fixable(f)).collect();
+        if fixable.is_empty() {
+            continue;
+        }
+        let path = root.join(&file.path);
+        let source = ItemBounds::read_source(&path)?;
+        let parsed = syn::parse_file(&source).map_err(|e| WinnowerError::parse(&path, e))?;
+        let entries: Vec<PlanEntry> = fixable
+            .iter()
+            .map(|f| PlanEntry {
+                locator: f.locator.clone(),
+                candidate: f.candidate.clone(),
+            })
+            .collect();
+        let (edits, statuses) = entry_edits(
+            &file.path,
+            &source,
+            &parsed,
+            &entries.iter().collect::<Vec<_>>(),
+        )?;
+        for (f, status) in fixable.iter().zip(&statuses) {
+            if let (Some(rule), true) = (&f.rule, status.is_applied()) {
+                *out.fixed.entry(rule.rule).or_default() += 1;
+            }
+        }
+        let new = apply_edits(&source, &edits);
+        if new != source {
+            out.changes.push(FileChange {
+                path: file.path.clone(),
+                old: source,
+                new,
+            });
+        }
+    }
+    if dry_run {
+        return Ok(out);
+    }
+    for (i, change) in out.changes.iter().enumerate() {
+        let path = root.join(&change.path);
+        if let Err(e) = std::fs::write(&path, &change.new) {
+            for done in &out.changes[..i] {
+                let done_path = root.join(&done.path);
+                std::fs::write(&done_path, &done.old)
+                    .map_err(|e| WinnowerError::io("reverting", &done_path, e))?;
+            }
+            return Err(WinnowerError::io("writing fixed", &path, e));
+        }
+    }
+    Ok(out)
+}
@@ -0,0 +1,344 @@
+// src/formats.rs
+//! Versioned file formats: documents trait-winnower writes and later reads back, or hands
+//! to other tools.
+//!
+//! Every document carries a top-level `schema_version`:
+//!
+//! | Document | Written by | Version |
+//! |---|---|---|
+//! | Removal plan (TOML) | [`Plan::save`](crate::plan::Plan::save) | [`PLAN_VERSION`] |
+//! | JSON report | [`Json`](crate::report::json::Json) | [`REPORT_VERSION`] |
+//! | Validation recording (JSON) | [`Session::save`](crate::dynamic_analysis::session::Session::save) | [`SESSION_VERSION`] |
+//!
+//! # Compatibility
+//!
+//! - Adding a field that readers can ignore keeps the version; loaders ignore unknown
+//!   fields.
+//! - Removing or renaming a field, or changing what it means, bumps the version.
+//! - Loaders read every version up to their own and upgrade older documents in memory.
+//!   Documents without `schema_version` predate versioning and read as version 1.
+//! - Newer versions are rejected with [`WinnowerError::UnsupportedVersion`] before the
+//!   rest of the document is looked at.
+
+#![deny(missing_docs)]
+
+use crate::analysis::{ImplHeader, ItemKind};
+use crate::error::{TraitError, WinnowerError};
+use crate::findings::RuleMatch;
+use crate::fingerprint::Fingerprint;
+use crate::report::ValidationSummary;
+use crate::strategy::Strategy;
+use serde::de::DeserializeOwned;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::path::Path;
+
+/// Current version of the removal plan format.
+pub const PLAN_VERSION: u32 = 1;
+
+/// Current version of the JSON report format.
+pub const REPORT_VERSION: u32 = 1;
+
+/// Current version of the validation recording format.
+pub const SESSION_VERSION: u32 = 1;
+
+/// A versioned document kind.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Format {
+    /// A removal plan.
+    Plan,
+    /// A JSON report.
+    Report,
+    /// A recording of validations.
+    Session,
+}
+
+impl Format {
+    /// Name used in error messages.
+    pub fn name(self) -> &'static str {
+        match self {
+            Format::Plan => "plan",
+            Format::Report => "JSON report",
+            Format::Session => "validation recording",
+        }
+    }
+
+    /// The version this build writes and the newest it reads.
+    pub fn current(self) -> u32 {
+        match self {
+            Format::Plan => PLAN_VERSION,
+            Format::Report => REPORT_VERSION,
+            Format::Session => SESSION_VERSION,
+        }
+    }
+
+    /// The version to read a document as, given its `schema_version` (if any).
+    pub fn check_version(self, path: &Path, found: Option<u32>) -> TraitError<u32> {
+        let version = found.unwrap_or(1);
+        if version == 0 || version > self.current() {
+            return Err(WinnowerError::UnsupportedVersion {
+                artifact: self.name(),
+                path: path.to_path_buf(),
+                found: version,
+                supported: self.current(),
+            });
+        }
+        Ok(version)
+    }
+
+    fn invalid(self, path: &Path, detail: impl ToString) -> WinnowerError {
+        WinnowerError::Format {
+            artifact: self.name(),
+            path: path.to_path_buf(),
+            detail: detail.to_string(),
+        }
+    }
+
+    /// Parse a TOML document of this format, checking its version first.
+    pub fn from_toml<T: DeserializeOwned>(self, path: &Path, text: &str) -> TraitError<T> {
+        let header: VersionHeader = toml::from_str(text).map_err(|e| self.invalid(path, e))?;
+        self.check_version(path, header.schema_version)?;
+        toml::from_str(text).map_err(|e| self.invalid(path, e))
+    }
+
+    /// Render `body` as a TOML document of this format, `schema_version` first.
+    pub fn to_toml<T: Serialize>(self, body: &T) -> TraitError<String> {
+        let doc = Versioned {
+            schema_version: self.current(),
+            body,
+        };
+        toml::to_string_pretty(&doc)
+            .map_err(|e| WinnowerError::Internal(format!("serializing {}: {e}", self.name())))
+    }
+
+    /// Render `body` as a pretty-printed JSON document of this format, `schema_version`
+    /// first.
+    pub fn to_json<T: Serialize>(self, body: &T) -> TraitError<String> {
+        let doc = Versioned {
+            schema_version: self.current(),
+            body,
+        };
+        serde_json::to_string_pretty(&doc)
+            .map(|text| text + "\n")
+            .map_err(|e| WinnowerError::Internal(format!("serializing {}: {e}", self.name())))
+    }
+
+    /// Parse a JSON document of this format, checking its version first.
+    pub fn from_json<T: DeserializeOwned>(self, path: &Path, text: &str) -> TraitError<T> {
+        let header: VersionHeader =
+            serde_json::from_str(text).map_err(|e| self.invalid(path, e))?;
+        self.check_version(path, header.schema_version)?;
+        serde_json::from_str(text).map_err(|e| self.invalid(path, e))
+    }
+}
+
+/// Just the version of a document; everything else is ignored.
+#[derive(Deserialize)]
+struct VersionHeader {
+    schema_version: Option<u32>,
+}
+
+#[derive(Serialize)]
+struct Versioned<'a, T> {
+    schema_version: u32,
+    #[serde(flatten)]
+    body: &'a T,
+}
+
+/// A JSON report as written by [`Json`](crate::report::json::Json).
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ReportDoc {
+    /// The document's version; 1 for documents that predate versioning.
+    #[serde(default)]
+    pub schema_version: u32,
+    /// `check` or `prune`.
+    pub command: String,
+    /// Run totals.
+    pub metrics: RunMetricsDoc,
+    /// Per-file results.
+    pub files: Vec<FileDoc>,
+    /// The `cargo check` configuration of the run, if it used one.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub validation: Option<ValidationSummary>,
+    /// Compile times before and after pruning (`impact` only).
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub impact: Option<ImpactDoc>,
+    /// Retained bounds removable once the bounds they were kept for had gone (`prune`
+    /// only).
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub unlocks: Vec<UnlockDoc>,
+    /// The strategy `prune` ran with, if named (`prune` only).
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub strategy: Option<Strategy>,
+    /// The `Cargo.lock` and rustc of the run, for crates and workspaces.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub fingerprint: Option<Fingerprint>,
+}
+
+/// A bound of an [`UnlockDoc`] chain, with the path of its file.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct UnlockDoc {
+    /// Path with `/` separators.
+    pub path: String,
+    /// The bound.
+    pub finding: FindingDoc,
+    /// For a retained bound, the removed bounds it was kept for.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub unlocked_by: Vec<UnlockDoc>,
+}
+
+/// The compile times of an `impact` [`ReportDoc`].
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ImpactDoc {
+    /// Timed runs per tree and command.
+    pub iterations: usize,
+    /// `cargo check`.
+    pub check: ComparisonDoc,
+    /// `cargo build`, when measured.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub build: Option<ComparisonDoc>,
+}
+
+/// One command of an [`ImpactDoc`], before and after pruning.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ComparisonDoc {
+    /// On the original tree.
+    pub before: TimingDoc,
+    /// On the pruned tree.
+    pub after: TimingDoc,
+    /// Change of the median, as a fraction of before.
+    pub delta: f64,
+}
+
+/// The runs of a [`ComparisonDoc`] side.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct TimingDoc {
+    /// Wall time of each run.
+    pub runs_ms: Vec<f64>,
+    /// Their median.
+    pub median_ms: f64,
+    /// Median time per crate (nightly only).
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub crates_ms: BTreeMap<String, f64>,
+}
+
+impl ReportDoc {
+    /// Parse a JSON report; `path` only labels errors.
+    pub fn from_json(path: &Path, text: &str) -> TraitError<Self> {
+        let mut doc: Self = Format::Report.from_json(path, text)?;
+        // Unversioned reports read as version 1.
+        doc.schema_version = doc.schema_version.max(1);
+        Ok(doc)
+    }
+
+    /// Read a JSON report from `path`.
+    pub fn load(path: &Path) -> TraitError<Self> {
+        let text =
+            std::fs::read_to_string(path).map_err(|e| WinnowerError::io("reading", path, e))?;
+        Self::from_json(path, &text)
+    }
+}
+
+/// Run totals of a [`ReportDoc`].
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct RunMetricsDoc {
+    /// Wall time of the run.
+    pub wall_ms: f64,
+    /// Files analyzed.
+    pub files: usize,
+    /// Findings across all files.
+    pub findings: usize,
+    /// Validation runs.
+    pub checks: usize,
+    /// Time spent validating.
+    pub check_ms: f64,
+}
+
+/// One file of a [`ReportDoc`].
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct FileDoc {
+    /// Path with `/` separators.
+    pub path: String,
+    /// Timings and counts.
+    pub metrics: FileMetricsDoc,
+    /// Bounds found.
+    pub findings: Vec<FindingDoc>,
+    /// Removal attempts (`prune` only).
+    pub removals: Vec<RemovalDoc>,
+}
+
+/// Timings and counts of a [`FileDoc`].
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct FileMetricsDoc {
+    /// Time spent parsing.
+    pub parse_ms: f64,
+    /// Items with bounds.
+    pub items: usize,
+    /// Candidate bounds.
+    pub candidates: usize,
+    /// Validation runs.
+    pub checks: usize,
+    /// Time spent validating.
+    pub check_ms: f64,
+    /// Time spent writing trials.
+    pub write_ms: f64,
+    /// Total time on the file.
+    pub total_ms: f64,
+}
+
+/// A finding of a [`FileDoc`].
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct FindingDoc {
+    /// Item label, e.g. `fn foo`.
+    pub item: String,
+    /// Kind of the item; absent from reports written before it was recorded.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub kind: Option<ItemKind>,
+    /// The impl block of an impl or impl method.
+    #[serde(default, rename = "impl", skip_serializing_if = "Option::is_none")]
+    pub impl_header: Option<ImplHeader>,
+    /// The bounded parameter or type.
+    pub bounded: String,
+    /// The bound as written.
+    pub bound: String,
+    /// 1-based start line.
+    pub line: usize,
+    /// 1-based start column, in chars.
+    pub column: usize,
+    /// 1-based end line.
+    pub end_line: usize,
+    /// 1-based column just past the end, in chars.
+    pub end_column: usize,
+    /// The static rule that flags the bound (`check` only).
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub rule: Option<RuleMatch>,
+    /// Why `prune` leaves the bound alone by default, if it does.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub caution: Option<String>,
+    /// The packages compiling the file, when more than one does.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub packages: Vec<String>,
+    /// How many times the workspace refers to the item, when weights were asked for.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub weight: Option<usize>,
+}
+
+/// A removal attempt of a [`FileDoc`].
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct RemovalDoc {
+    /// The bound tried.
+    pub finding: FindingDoc,
+    /// What happened, e.g. `removed`.
+    pub status: String,
+    /// The item before the attempt.
+    pub before: String,
+    /// The item after the attempt.
+    pub after: String,
+    /// Time spent validating.
+    pub check_ms: f64,
+    /// Time spent writing.
+    pub write_ms: f64,
+    /// The retained bound only `#[cfg(test)]` code needs (`--explain-test-only`).
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub test_only: bool,
+}
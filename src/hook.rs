@@ -0,0 +1,236 @@
+// src/hook.rs
+//! Git hooks that run `trait-winnower` on what a commit or push changes.
+//!
+//! [`install`] writes a POSIX `sh` script into the repository's hooks directory. It runs
+//! `check` or `prune -b` with `--since` the upstream branch, or `HEAD` without one, and
+//! `--fail-on-findings`, so new unnecessary bounds stop the commit or push. Scripts carry
+//! [`HOOK_MARKER`]; hooks without it are someone else's and are only replaced or removed
+//! when forced.
+
+#![deny(missing_docs)]
+
+use crate::error::{TraitError, WinnowerError};
+use std::path::{Path, PathBuf};
+use std::process::Command;
+
+/// The line marking a hook script as written by [`install`].
+pub const HOOK_MARKER: &str = "# installed by trait-winnower hook install";
+
+/// The git hook to install.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+#[cfg_attr(feature = "cli", derive(clap::ValueEnum))]
+pub enum HookStage {
+    /// Before each commit.
+    #[default]
+    PreCommit,
+    /// Before each push.
+    PrePush,
+}
+
+impl HookStage {
+    /// The hook's file name, as git knows it.
+    pub fn name(self) -> &'static str {
+        match self {
+            HookStage::PreCommit => "pre-commit",
+            HookStage::PrePush => "pre-push",
+        }
+    }
+}
+
+/// What the hook runs.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+#[cfg_attr(feature = "cli", derive(clap::ValueEnum))]
+pub enum HookMode {
+    /// `check`: fail when a static rule flags a bound in a changed file.
+    #[default]
+    Check,
+    /// `prune -b`: fail when `cargo check` accepts removing a bound from a changed file,
+    /// leaving the removals in place for review.
+    Verify,
+}
+
+impl HookMode {
+    /// The arguments of `trait-winnower` before the shared `--since`.
+    fn args(self) -> &'static str {
+        match self {
+            HookMode::Check => "check",
+            HookMode::Verify => "prune -b",
+        }
+    }
+}
+
+/// Shell words quoted for `sh`.
+fn quote(word: &str) -> String {
+    format!("'{}'", word.replace('\'', r"'\''"))
+}
+
+/// The shell that sets `since` to the upstream branch, else `HEAD`, else the empty tree of
+/// a repository without commits.
+const SINCE: &str = r#"since=$(git rev-parse -q --verify '@{upstream}' 2>/dev/null) ||
+    since=$(git rev-parse -q --verify HEAD 2>/dev/null) ||
+    since=$(git hash-object -t tree /dev/null)"#;
+
+/// The hook script for `stage` running `mode` on the crate at `target`, a path relative to
+/// the top of the work tree.
+pub fn script(stage: HookStage, mode: HookMode, target: &str) -> String {
+    format!(
+        "#!/bin/sh\n\
+         {HOOK_MARKER}\n\
+         # The {stage} hook fails when trait-winnower finds unnecessary bounds in changed\n\
+         # files. Set TRAIT_WINNOWER to the binary to run if it is not on PATH.\n\
+         {SINCE}\n\
+         cd \"$(git rev-parse --show-toplevel)\" || exit 1\n\
+         exec \"${{TRAIT_WINNOWER:-trait-winnower}}\" {args} --since \"$since\" --fail-on-findings {target}\n",
+        stage = stage.name(),
+        args = mode.args(),
+        target = quote(target),
+    )
+}
+
+/// A `.pre-commit-config.yaml` entry running `mode` at `stage` on the crate at `target`,
+/// for repositories managing hooks with the pre-commit framework.
+pub fn pre_commit_config(stage: HookStage, mode: HookMode, target: &str) -> String {
+    let script = format!(
+        "{SINCE}\nexec trait-winnower {} --since \"$since\" --fail-on-findings {}",
+        mode.args(),
+        quote(target)
+    );
+    let script: String = script
+        .lines()
+        .map(|l| format!("            {l}\n"))
+        .collect();
+    format!(
+        "repos:\n  \
+         - repo: local\n    \
+         hooks:\n      \
+         - id: trait-winnower\n        \
+         name: trait-winnower {args}\n        \
+         entry: sh\n        \
+         args:\n          \
+         - -c\n          \
+         - |\n\
+         {script}        \
+         language: system\n        \
+         pass_filenames: false\n        \
+         types: [rust]\n        \
+         stages: [{stage}]\n",
+        args = mode.args(),
+        stage = stage.name(),
+    )
+}
+
+/// Run git in `root`; its trimmed stdout.
+pub(crate) fn git(root: &Path, args: &[&str]) -> TraitError<String> {
+    let out = Command::new("git")
+        .args(args)
+        .current_dir(root)
+        .output()
+        .map_err(|e| WinnowerError::Target {
+            path: root.to_path_buf(),
+            detail: format!("running git: {e}"),
+        })?;
+    if !out.status.success() {
+        return Err(WinnowerError::Target {
+            path: root.to_path_buf(),
+            detail: format!(
+                "`git {}` failed: {}",
+                args.join(" "),
+                String::from_utf8_lossy(&out.stderr).trim()
+            ),
+        });
+    }
+    Ok(String::from_utf8_lossy(&out.stdout).trim().to_owned())
+}
+
+/// Where the `stage` hook of the repository holding `root` lives; honours
+/// `core.hooksPath`.
+pub fn hook_path(root: &Path, stage: HookStage) -> TraitError<PathBuf> {
+    Ok(root
+        .join(git(root, &["rev-parse", "--git-path", "hooks"])?)
+        .join(stage.name()))
+}
+
+/// The crate at `root` as a path relative to the top of its work tree, `.` at the top.
+pub fn target_in_repo(root: &Path) -> TraitError<String> {
+    let prefix = git(root, &["rev-parse", "--show-prefix"])?;
+    let prefix = prefix.trim_end_matches('/');
+    Ok(if prefix.is_empty() {
+        ".".to_owned()
+    } else {
+        prefix.to_owned()
+    })
+}
+
+/// Whether the hook at `path` was written by [`install`].
+fn is_ours(path: &Path) -> bool {
+    std::fs::read_to_string(path).is_ok_and(|s| s.lines().any(|l| l == HOOK_MARKER))
+}
+
+/// Write the `stage` hook running `mode` on the crate at `root`; returns where. An
+/// existing hook not written by `install` is only replaced with `force`.
+pub fn install(root: &Path, stage: HookStage, mode: HookMode, force: bool) -> TraitError<PathBuf> {
+    let path = hook_path(root, stage)?;
+    if path.exists() && !force && !is_ours(&path) {
+        return Err(WinnowerError::Target {
+            path,
+            detail: format!(
+                "a {} hook is already installed; pass --force to replace it",
+                stage.name()
+            ),
+        });
+    }
+    let dir = path.parent().unwrap_or(root);
+    std::fs::create_dir_all(dir).map_err(|e| WinnowerError::io("creating", dir, e))?;
+    let script = script(stage, mode, &target_in_repo(root)?);
+    std::fs::write(&path, script).map_err(|e| WinnowerError::io("writing", &path, e))?;
+    make_executable(&path)?;
+    Ok(path)
+}
+
+/// Remove the `stage` hook of the repository holding `root`; returns where it was, or
+/// `None` when there was none. A hook not written by [`install`] is only removed with
+/// `force`.
+pub fn uninstall(root: &Path, stage: HookStage, force: bool) -> TraitError<Option<PathBuf>> {
+    let path = hook_path(root, stage)?;
+    if !path.exists() {
+        return Ok(None);
+    }
+    if !force && !is_ours(&path) {
+        return Err(WinnowerError::Target {
+            path,
+            detail: format!(
+                "the {} hook was not installed by trait-winnower; pass --force to remove it",
+                stage.name()
+            ),
+        });
+    }
+    std::fs::remove_file(&path).map_err(|e| WinnowerError::io("removing", &path, e))?;
+    Ok(Some(path))
+}
+
+#[cfg(unix)]
+fn make_executable(path: &Path) -> TraitError<()> {
+    use std::os::unix::fs::PermissionsExt;
+    std::fs::set_permissions(path, std::fs::Permissions::from_mode(0o755))
+        .map_err(|e| WinnowerError::io("setting permissions of", path, e))
+}
+
+#[cfg(not(unix))]
+fn make_executable(_path: &Path) -> TraitError<()> {
+    // Git for Windows runs hooks through its own sh regardless of permissions.
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn targets_are_quoted_for_sh() {
+        assert_eq!(quote("."), "'.'");
+        assert_eq!(quote("it's"), r"'it'\''s'");
+        let script = script(HookStage::PrePush, HookMode::Verify, "crates/a b");
+        assert!(script.starts_with("#!/bin/sh\n"));
+        assert!(script.contains("prune -b --since \"$since\" --fail-on-findings 'crates/a b'"));
+    }
+}
@@ -0,0 +1,270 @@
+// src/impact.rs
+//! How pruning changes compile times, for `trait-winnower impact`.
+//!
+//! [`measure`] times `cargo check` or `cargo build` of a tree: once to warm its target
+//! directory, then once per iteration after touching the tree's sources, so the
+//! workspace's own crates are rebuilt and its dependencies are not. On a nightly toolchain
+//! each timed run also reports per-crate times through `--timings=json`.
+
+#![deny(missing_docs)]
+
+use crate::config::CargoCheckConfig;
+use crate::error::{TraitError, WinnowerError};
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
+use std::process::Command;
+use std::time::{Duration, Instant, SystemTime};
+
+/// Timed runs per tree and command unless `--iterations` says otherwise.
+pub const DEFAULT_ITERATIONS: usize = 3;
+
+/// What [`Winnower::impact`](crate::winnower::Winnower::impact) measures.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ImpactOptions {
+    /// Timed runs per tree and command, after a warm-up run.
+    pub iterations: usize,
+    /// Time `cargo build` as well as `cargo check`.
+    pub build: bool,
+}
+
+impl Default for ImpactOptions {
+    fn default() -> Self {
+        Self {
+            iterations: DEFAULT_ITERATIONS,
+            build: false,
+        }
+    }
+}
+
+/// Compile times of a tree before and after pruning it.
+#[derive(Debug, Clone, Default, Serialize, Deserialize, PartialEq)]
+pub struct Impact {
+    /// Timed runs per tree and command.
+    pub iterations: usize,
+    /// `cargo check`.
+    pub check: Comparison,
+    /// `cargo build`, when asked for.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub build: Option<Comparison>,
+}
+
+/// Timings of one command on the original tree and on the pruned one.
+#[derive(Debug, Clone, Default, Serialize, Deserialize, PartialEq)]
+pub struct Comparison {
+    /// On the original tree.
+    pub before: Timing,
+    /// On the pruned tree.
+    pub after: Timing,
+}
+
+impl Comparison {
+    /// Change of the median run, as a fraction of before: `-0.1` is 10% faster.
+    pub fn delta(&self) -> f64 {
+        change(self.before.median(), self.after.median())
+    }
+
+    /// Crates timed on both sides, by name: before, after and their change.
+    pub fn crates(&self) -> Vec<(&str, Duration, Duration, f64)> {
+        self.before
+            .crates
+            .iter()
+            .filter_map(|(name, &before)| {
+                let after = *self.after.crates.get(name)?;
+                Some((name.as_str(), before, after, change(before, after)))
+            })
+            .collect()
+    }
+}
+
+/// `after` relative to `before`, as a fraction of `before`.
+fn change(before: Duration, after: Duration) -> f64 {
+    if before.is_zero() {
+        return 0.0;
+    }
+    after.as_secs_f64() / before.as_secs_f64() - 1.0
+}
+
+/// The timed runs of one command on one tree.
+#[derive(Debug, Clone, Default, Serialize, Deserialize, PartialEq)]
+pub struct Timing {
+    /// Wall time of each run.
+    pub runs: Vec<Duration>,
+    /// Median time per crate across runs, by package name; nightly toolchains only.
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub crates: BTreeMap<String, Duration>,
+}
+
+impl Timing {
+    /// The median run; zero without runs.
+    pub fn median(&self) -> Duration {
+        median(self.runs.clone())
+    }
+}
+
+fn median(mut runs: Vec<Duration>) -> Duration {
+    runs.sort();
+    match runs.len() {
+        0 => Duration::ZERO,
+        n if n % 2 == 1 => runs[n / 2],
+        n => (runs[n / 2 - 1] + runs[n / 2]) / 2,
+    }
+}
+
+/// Whether the toolchain whose `cargo --version` or `rustc --version` printed `version`
+/// takes `-Z` flags.
+pub fn is_nightly(version: &str) -> bool {
+    version.contains("-nightly") || version.contains("-dev")
+}
+
+/// Time `cargo <command>` with the arguments of `config` in `root`, building into
+/// `target_dir`: a warm-up run, then `iterations` runs each after touching `sources`. With
+/// `per_crate`, runs pass `-Z unstable-options --timings=json` and per-crate times are kept.
+pub fn measure(
+    root: &Path,
+    config: &CargoCheckConfig,
+    command: &str,
+    target_dir: &Path,
+    sources: &[PathBuf],
+    iterations: usize,
+    per_crate: bool,
+) -> TraitError<Timing> {
+    let cargo = config.cargo();
+    let run = || -> TraitError<(Duration, String)> {
+        let mut cmd = Command::new(&cargo);
+        cmd.arg(command).args(&config.args);
+        if per_crate {
+            cmd.args([
+                "-Z",
+                "unstable-options",
+                "--timings=json",
+                "--message-format=json",
+            ]);
+        }
+        let started = Instant::now();
+        let output = cmd
+            .env("CARGO_TARGET_DIR", target_dir)
+            .current_dir(root)
+            .output()
+            .map_err(|e| WinnowerError::cargo_spawn(&cargo, &e))?;
+        let elapsed = started.elapsed();
+        if !output.status.success() {
+            return Err(WinnowerError::Validation {
+                command: format!("cargo {command}"),
+                status: output.status.code(),
+                stderr: String::from_utf8_lossy(&output.stderr).into_owned(),
+            });
+        }
+        Ok((
+            elapsed,
+            String::from_utf8_lossy(&output.stdout).into_owned(),
+        ))
+    };
+
+    run()?;
+    let mut timing = Timing::default();
+    let mut crates: BTreeMap<String, Vec<Duration>> = BTreeMap::new();
+    for _ in 0..iterations {
+        touch(sources)?;
+        let (elapsed, stdout) = run()?;
+        timing.runs.push(elapsed);
+        for (name, time) in crate_times(&stdout) {
+            crates.entry(name).or_default().push(time);
+        }
+    }
+    timing.crates = crates
+        .into_iter()
+        .map(|(name, times)| (name, median(times)))
+        .collect();
+    Ok(timing)
+}
+
+/// Set the modification time of `files` to now, so cargo rebuilds the crates holding them.
+fn touch(files: &[PathBuf]) -> TraitError<()> {
+    let now = SystemTime::now();
+    for path in files {
+        std::fs::File::options()
+            .append(true)
+            .open(path)
+            .and_then(|f| f.set_modified(now))
+            .map_err(|e| WinnowerError::io("touching", path, e))?;
+    }
+    Ok(())
+}
+
+/// Time per package in `stdout`, cargo's JSON messages with `--timings=json`: the
+/// `timing-info` messages of each of its targets, summed.
+pub fn crate_times(stdout: &str) -> BTreeMap<String, Duration> {
+    #[derive(Deserialize)]
+    struct TimingInfo {
+        reason: String,
+        package_id: String,
+        duration: f64,
+    }
+    let mut times = BTreeMap::new();
+    for info in stdout
+        .lines()
+        .filter_map(|l| serde_json::from_str::<TimingInfo>(l).ok())
+        .filter(|i| i.reason == "timing-info")
+    {
+        *times
+            .entry(package_name(&info.package_id).to_owned())
+            .or_insert(Duration::ZERO) += Duration::from_secs_f64(info.duration.max(0.0));
+    }
+    times
+}
+
+/// The package name of a cargo package id: `path+file:///w/a#b@0.1.0` is `b`,
+/// `path+file:///w/a#0.1.0` is `a`, and the older `a 0.1.0 (path+file:///w/a)` is `a`.
+fn package_name(id: &str) -> &str {
+    match id.rsplit_once('#') {
+        Some((_, fragment)) if fragment.contains('@') => {
+            fragment.split('@').next().unwrap_or(fragment)
+        }
+        Some((url, _)) => url.rsplit('/').next().unwrap_or(url),
+        None => id.split(' ').next().unwrap_or(id),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn timing_info_sums_per_package() {
+        let stdout = [
+            r#"{"reason":"timing-info","package_id":"path+file:///w/a#0.1.0","target":{"name":"a"},"mode":"check","duration":0.5,"rmeta_time":0.4}"#,
+            r#"{"reason":"timing-info","package_id":"path+file:///w/a#0.1.0","target":{"name":"t"},"mode":"check","duration":0.25}"#,
+            r#"{"reason":"timing-info","package_id":"path+file:///w/x#b@0.2.0","target":{"name":"b"},"mode":"check","duration":1.0}"#,
+            r#"{"reason":"compiler-artifact","package_id":"path+file:///w/a#0.1.0"}"#,
+            r#"{"reason":"timing-info","package_id":"serde 1.0.0 (registry+https://x)","duration":2.0}"#,
+            "Compiling a",
+        ]
+        .join("\n");
+        let times = crate_times(&stdout);
+        assert_eq!(
+            times.into_iter().collect::<Vec<_>>(),
+            [
+                ("a".to_owned(), Duration::from_millis(750)),
+                ("b".to_owned(), Duration::from_secs(1)),
+                ("serde".to_owned(), Duration::from_secs(2)),
+            ]
+        );
+    }
+
+    #[test]
+    fn delta_compares_medians() {
+        let ms = |v: &[u64]| Timing {
+            runs: v.iter().map(|&n| Duration::from_millis(n)).collect(),
+            ..Timing::default()
+        };
+        let comparison = Comparison {
+            before: ms(&[300, 100, 200]),
+            after: ms(&[150, 170, 190, 900]),
+        };
+        assert_eq!(comparison.before.median(), Duration::from_millis(200));
+        assert_eq!(comparison.after.median(), Duration::from_millis(180));
+        assert!((comparison.delta() + 0.1).abs() < 1e-9);
+        assert_eq!(Comparison::default().delta(), 0.0);
+    }
+}
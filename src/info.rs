@@ -5,18 +5,722 @@
 
 use crate::analysis::ItemKey;
 use crate::analysis::ItemRef;
+use crate::annotate::is_marker;
+use crate::commit::CommitLog;
+use crate::config::MatrixMode;
+#[cfg(feature = "discover")]
+use crate::crate_map::SharedFile;
+#[cfg(feature = "discover")]
+use crate::discover::{MissingModule, SkippedFile};
+use crate::doctor::Diagnosis;
+use crate::dynamic_analysis::common::BoundRemovalResult;
+use crate::dynamic_analysis::external::ExternalEdit;
+use crate::dynamic_analysis::incremental::CacheStats;
+use crate::dynamic_analysis::semver::{SemverOutcome, SemverReport};
+use crate::dynamic_analysis::test_only::TEST_ONLY_NOTE;
+use crate::dynamic_analysis::unlock::UnlockChain;
+use crate::fingerprint::Drift;
+use crate::fix::FixReport;
+use crate::impact::Impact;
+use crate::observer::Observer;
+use crate::patch::FileChange;
+use crate::plan::ApplyReport;
+use crate::report::breakdown::{Breakdown, Tally};
+use crate::report::{FileMetrics, Removal, Report, ValidationSummary};
+use crate::strategy::Strategy;
 use quote::ToTokens;
+use std::path::{Path, PathBuf};
 use syn::File;
 use syn::Item;
 
 /// Print trait bounds.
 pub struct TraitInfo();
 
+/// Prune progress on stderr: files at verbosity 1, every candidate trial at 2 and above.
+#[derive(Debug, Default)]
+pub struct ProgressLog {
+    verbosity: u8,
+}
+
+impl ProgressLog {
+    /// Log at `verbosity`; 0 logs nothing.
+    pub fn new(verbosity: u8) -> Self {
+        Self { verbosity }
+    }
+}
+
+impl Observer for ProgressLog {
+    fn on_file_start(&mut self, path: &Path) {
+        if self.verbosity > 0 {
+            eprintln!("pruning {}", path.display());
+        }
+    }
+
+    fn on_candidate_trial(&mut self, item: &str, bound: &str, index: usize, total: usize) {
+        if self.verbosity > 1 {
+            eprint!("  [{}/{}] {item}: {bound} ... ", index + 1, total);
+        }
+    }
+
+    fn on_outcome(&mut self, result: &BoundRemovalResult) {
+        if self.verbosity > 1 {
+            let removal = Removal::from_result(result);
+            let tags = removal.finding.tags();
+            if tags.is_empty() {
+                eprintln!("{}", removal.status);
+            } else {
+                eprintln!("{} ({})", removal.status, tags.join(", "));
+            }
+        }
+    }
+
+    fn on_file_done(&mut self, stats: &FileMetrics) {
+        if self.verbosity > 0 {
+            eprintln!(
+                "  {} check(s) in {:.1} ms",
+                stats.checks,
+                stats.total.as_secs_f64() * 1000.0
+            );
+        }
+    }
+}
+
 impl TraitInfo {
-    /// Print a single item.
-    pub fn show_item(it: &ItemKey) {
-        print!("{}", it);
-        println!();
+    /// Print a single item as `path:line:column label`, e.g. `src/b.rs:16:12 Wrapper<T>::id`.
+    pub fn show_item(path: &Path, it: &ItemKey) {
+        let start = it.span().start();
+        println!(
+            "{}:{}:{} {}",
+            path.to_string_lossy().replace('\\', "/"),
+            start.line,
+            start.column + 1,
+            it.to_string().trim_start_matches("// ")
+        );
+    }
+
+    /// Print an item's signature (functions and methods) or its generics and where clause
+    /// (impls, structs, enums and traits), without the body.
+    pub fn show_signature(item: &ItemRef) {
+        println!("    {}", item.signature());
+    }
+
+    /// Print skipped discovery paths to stderr: a per-reason count at verbosity 1,
+    /// plus every path at verbosity 2 and above.
+    #[cfg(feature = "discover")]
+    pub fn show_skipped(skipped: &[SkippedFile], verbosity: u8) {
+        if verbosity == 0 || skipped.is_empty() {
+            return;
+        }
+        let mut counts = std::collections::BTreeMap::new();
+        for s in skipped {
+            *counts.entry(s.reason.to_string()).or_insert(0usize) += 1;
+        }
+        eprintln!("skipped {} path(s):", skipped.len());
+        for (reason, n) in &counts {
+            eprintln!("  {n:>6}  {reason}");
+        }
+        if verbosity > 1 {
+            for s in skipped {
+                eprintln!("  {}: {}", s.path.display(), s.reason);
+            }
+        }
+    }
+
+    /// Warn on stderr about `mod` declarations that did not resolve to a file.
+    #[cfg(feature = "discover")]
+    pub fn show_missing_modules(missing: &[MissingModule]) {
+        for m in missing {
+            let tried: Vec<String> = m.tried.iter().map(|p| p.display().to_string()).collect();
+            eprintln!(
+                "warning: module `{}` declared in {} not found (tried {})",
+                m.name,
+                m.declared_in.display(),
+                tried.join(", ")
+            );
+        }
+    }
+
+    /// Print on stderr the strategy `prune` runs with and what it stands for.
+    pub fn show_strategy(strategy: &Strategy) {
+        eprintln!("strategy: {strategy}");
+    }
+
+    /// Print the cargo and rustc `prune` validated with.
+    pub fn show_toolchain(validation: &ValidationSummary) {
+        if let Some(toolchain) = &validation.toolchain {
+            eprintln!("validated with {toolchain}");
+        }
+    }
+
+    /// Print on stderr where `cargo check` built and how big that got.
+    pub fn show_target_dir(validation: &ValidationSummary) {
+        if let Some(dir) = &validation.target_dir {
+            eprintln!("checked in {dir}");
+        }
+    }
+
+    /// Warn about files several packages compile: a removal in one has to build in all.
+    #[cfg(feature = "discover")]
+    pub fn show_shared(shared: &[SharedFile]) {
+        for s in shared {
+            eprintln!(
+                "warning: {} is compiled by packages {}; its removals must build in each",
+                s.path.display(),
+                s.packages.join(", ")
+            );
+        }
+    }
+
+    /// Warn about files `prune` left alone as someone else wrote them during the run or
+    /// an editor holds them.
+    pub fn show_external_edits(edits: &[ExternalEdit]) {
+        for e in edits {
+            eprintln!("warning: skipping: {e}");
+        }
+    }
+
+    /// Print on stderr how many files an `--incremental` run skipped as unchanged.
+    pub fn show_cache(stats: &CacheStats) {
+        eprintln!(
+            "incremental: {} file(s) unchanged since the last run, {} pruned",
+            stats.hits, stats.misses
+        );
+    }
+
+    /// Warn on stderr that a run cache or plan was made with another `Cargo.lock` or rustc.
+    pub fn show_drift(drift: &Drift) {
+        eprintln!("warning: {drift}");
+    }
+
+    /// Tell on stderr that a check waits for a file lock on `what` another cargo process
+    /// holds, building in `dir`.
+    pub fn show_lock_wait(dir: &Path, what: &str) {
+        eprintln!(
+            "note: cargo is waiting for the file lock on its {what} in {}; another cargo \
+             process, such as rust-analyzer, holds it (see --lock-wait-timeout)",
+            dir.display()
+        );
+    }
+
+    /// Print what each check of `trait-winnower doctor` found, one per line.
+    pub fn show_doctor(diagnoses: &[Diagnosis]) {
+        for d in diagnoses {
+            match &d.result {
+                Ok(status) => println!("{}: {status}", d.check.name()),
+                Err(e) => println!("{}: error: {e}", d.check.name()),
+            }
+        }
+    }
+
+    /// Print on stderr how many checks the prefilter saved; nothing when it saved none.
+    pub fn show_prefilter(report: &Report) {
+        let saved = report.total_prefiltered();
+        if saved > 0 {
+            eprintln!("prefilter: {saved} check(s) saved on bounds the code plainly uses");
+        }
+    }
+
+    /// Print on stderr how many checks rejections earlier in the same impl or trait saved;
+    /// nothing when they saved none.
+    pub fn show_memo_hits(report: &Report) {
+        let hits = report.total_memo_hits();
+        if hits > 0 {
+            eprintln!("memo: {hits} check(s) saved by rejections earlier in the same scope");
+        }
+    }
+
+    /// Print on stderr how many trials missed their item's anchor, found by the structural
+    /// fallback or not at all, and with `explain` what each looked for and found near it;
+    /// nothing when none missed.
+    pub fn show_anchor_misses(report: &Report, explain: bool) {
+        let (recovered, hard) = report.total_anchor_misses();
+        if recovered + hard == 0 {
+            return;
+        }
+        eprintln!(
+            "anchors: {recovered} miss(es) recovered by fallback, {hard} hard miss(es) left untried"
+        );
+        if !explain {
+            return;
+        }
+        for file in &report.files {
+            let mut last = None;
+            for miss in file
+                .removals
+                .iter()
+                .filter_map(|r| r.finding.anchor_miss.as_ref())
+            {
+                // Declarations removed together share their trial's miss.
+                if last != Some(miss) {
+                    eprintln!("  {}: {miss}", file.path.display());
+                }
+                last = Some(miss);
+            }
+        }
+    }
+
+    /// Print on stderr the files `--max-candidates-per-file` cut short, with how many
+    /// candidates were left untried; nothing when none was.
+    pub fn show_truncated(report: &Report) {
+        for (path, untried) in report.truncated() {
+            eprintln!(
+                "{}: {untried} candidate(s) not tried, over --max-candidates-per-file",
+                path.display()
+            );
+        }
+    }
+
+    /// Print on stderr how many checks stopped at their first error and about how much
+    /// time that saved; nothing when none did.
+    pub fn show_aborted(report: &Report) {
+        let aborted = report.total_aborted();
+        if aborted > 0 {
+            eprintln!(
+                "fail fast: {aborted} check(s) stopped at their first error, about {:.1} s saved",
+                report.estimated_abort_savings().as_secs_f64()
+            );
+        }
+    }
+
+    /// Print on stderr how many checks the trial cache answered; nothing when it answered
+    /// none.
+    pub fn show_trial_cache_hits(report: &Report) {
+        let hits = report.total_trial_cache_hits();
+        if hits > 0 {
+            eprintln!("trial cache: {hits} check(s) reused from earlier runs");
+        }
+    }
+
+    /// Warn on stderr that removals validated with `validation` may break the code it does
+    /// not build. Prints nothing when it covers everything.
+    pub fn show_validation_gaps(validation: &ValidationSummary) {
+        if validation.gaps.is_empty() {
+            return;
+        }
+        eprintln!(
+            "warning: removals are validated with `cargo check {}`, which does not build:",
+            validation.args.join(" ")
+        );
+        for gap in &validation.gaps {
+            eprintln!("  - {gap}");
+        }
+        eprintln!(
+            "  bounds needed only there may be removed; set `strict_validation = true` to refuse"
+        );
+    }
+
+    /// Tell on stderr which configurations besides the host removals are validated on.
+    /// Prints nothing without a matrix.
+    pub fn show_validation_matrix(validation: &ValidationSummary) {
+        if validation.matrix.is_empty() {
+            return;
+        }
+        let when = match validation.matrix_mode {
+            MatrixMode::PerCandidate => "each removal",
+            MatrixMode::FinalOnly => "the final state",
+        };
+        eprintln!("{when} is also validated on:");
+        for entry in &validation.matrix {
+            eprintln!("  - {entry}");
+        }
+    }
+
+    /// Report on stderr that the final state failed the validation matrix and every
+    /// removal was put back; `stderr` follows at verbosity 1+.
+    pub fn show_matrix_failure(stderr: &str, verbosity: u8) {
+        eprintln!("the pruned crate fails the validation matrix; put back every removal");
+        if verbosity > 0 {
+            eprintln!("{stderr}");
+        }
+    }
+
+    /// Report on stderr that clippy rejected the final state and every removal was put
+    /// back; `stderr` follows at verbosity 1+.
+    pub fn show_clippy_failure(stderr: &str, verbosity: u8) {
+        eprintln!("the pruned crate fails `cargo clippy`; put back every removal");
+        if verbosity > 0 {
+            eprintln!("{stderr}");
+        }
+    }
+
+    /// Print a compact per-file timing table and run totals to stderr at verbosity 1+.
+    pub fn show_metrics(report: &Report, verbosity: u8) {
+        if verbosity == 0 || report.files.is_empty() {
+            return;
+        }
+        let ms = |d: std::time::Duration| d.as_secs_f64() * 1000.0;
+        eprintln!(
+            "{:>9} {:>6} {:>10} {:>6} {:>10} {:>9}  file",
+            "parse ms", "items", "candidates", "checks", "check ms", "total ms"
+        );
+        for f in &report.files {
+            let m = &f.metrics;
+            eprintln!(
+                "{:>9.1} {:>6} {:>10} {:>6} {:>10.1} {:>9.1}  {}",
+                ms(m.parse),
+                m.items,
+                m.candidates,
+                m.checks,
+                ms(m.check_time),
+                ms(m.total),
+                f.path.display()
+            );
+        }
+        eprintln!(
+            "wall {:.1} ms, {} check(s) taking {:.1} ms",
+            ms(report.wall_time),
+            report.total_checks(),
+            ms(report.total_check_time())
+        );
+    }
+
+    /// Print on stderr where the tried bounds were removed and kept, by package and by
+    /// trait; nothing when none was tried.
+    pub fn show_breakdown(breakdown: &Breakdown) {
+        if breakdown.is_empty() {
+            return;
+        }
+        let table = |column: &str, tallies: &[Tally]| {
+            eprintln!(
+                "{:>8} {:>9} {:>8}  {column}",
+                "removed", "retained", "ratio"
+            );
+            for t in tallies {
+                eprintln!(
+                    "{:>8} {:>9} {:>7.0}%  {}",
+                    t.removed,
+                    t.retained,
+                    t.ratio() * 100.0,
+                    t.name
+                );
+            }
+        };
+        table("package", &breakdown.packages);
+        table("trait", &breakdown.traits);
+    }
+
+    /// Print on stderr the retained bounds that can go now that the bounds they were kept
+    /// for are gone, and what each was kept for; nothing when there are none.
+    pub fn show_unlocks(unlocks: &[UnlockChain]) {
+        if unlocks.is_empty() {
+            return;
+        }
+        let at = |f: &crate::report::Finding| {
+            format!(
+                "{}:{} {}: `{}: {}`",
+                f.locator.file.display(),
+                f.line,
+                f.item,
+                f.bounded,
+                f.bound
+            )
+        };
+        for chain in unlocks {
+            eprintln!("unlocked: {}", at(&chain.finding));
+            for blocker in &chain.unlocked_by {
+                eprintln!("  was kept for {}", at(blocker));
+            }
+        }
+        eprintln!(
+            "{} retained bound(s) only guarded bounds removed since; prune again to remove them",
+            unlocks.len()
+        );
+    }
+
+    /// Print on stderr the report files written, by format, one line each.
+    pub fn show_reports_written<'a>(reports: impl IntoIterator<Item = (&'a str, &'a Path)>) {
+        for (format, path) in reports {
+            eprintln!("wrote {format} report to {}", path.display());
+        }
+    }
+
+    /// Print on stderr the imports `--tidy-imports` dropped, and those it put back; nothing
+    /// when there are none.
+    pub fn show_tidied_imports(report: &Report) {
+        for t in &report.tidied_imports {
+            if t.removed {
+                eprintln!(
+                    "removed unused import `{}` from {}:{}",
+                    t.import,
+                    t.file.display(),
+                    t.line
+                );
+            } else {
+                eprintln!(
+                    "kept import `{}` at {}:{}: the check failed without it",
+                    t.import,
+                    t.file.display(),
+                    t.line
+                );
+            }
+        }
+    }
+
+    /// Print on stderr the impls a `#[derive]` could replace; nothing when there are none.
+    pub fn show_derive_candidates(report: &Report) {
+        for d in &report.derive_candidates {
+            let why = if d.enabled_by_prune {
+                " now that its extra bounds are gone"
+            } else {
+                ""
+            };
+            eprintln!(
+                "derive candidate: {}:{}: `impl {} for {}` could be `#[derive({})]`{why}",
+                d.file.display(),
+                d.line,
+                d.derive,
+                d.ty,
+                d.derive
+            );
+        }
+    }
+
+    /// Print on stderr the cleanup the removals kept under `tolerate_new_lints` left to do;
+    /// nothing when there is none.
+    pub fn show_follow_ups(report: &Report) {
+        let follow_ups = report.follow_ups();
+        if follow_ups.is_empty() {
+            return;
+        }
+        for (u, r) in &follow_ups {
+            let f = &r.finding;
+            eprintln!(
+                "follow-up: {u}, after removing `{}: {}` from {}",
+                f.bounded, f.bound, f.item
+            );
+        }
+        eprintln!(
+            "{} tolerated lint(s) left to clean up before the workspace builds under its lint levels",
+            follow_ups.len()
+        );
+    }
+
+    /// Print on stderr the removals whose check took many times the run's baseline, and
+    /// whether they were put back; nothing when there are none.
+    pub fn show_slow_removals(report: &Report) {
+        let Some(slow) = &report.slow_removals else {
+            return;
+        };
+        for r in &slow.removals {
+            let f = &r.finding;
+            eprintln!(
+                "suspicious: may regress compile time: {}:{} {}: `{}: {}` \
+                 (check took {} ms, {:.1}× the baseline)",
+                f.locator.file.display(),
+                f.line,
+                f.item,
+                f.bounded,
+                f.bound,
+                r.check_time.as_millis(),
+                r.slowdown
+            );
+        }
+        if slow.reverted {
+            eprintln!(
+                "put back {} slow removal(s); prune without --reject-slow-removals to keep them",
+                slow.removals.len()
+            );
+        } else {
+            eprintln!(
+                "{} removal(s) checked over {}× slower than the baseline of {} ms; \
+                 --reject-slow-removals puts them back",
+                slow.removals.len(),
+                slow.factor,
+                slow.baseline.as_millis()
+            );
+        }
+    }
+
+    /// Print on stderr the retained bounds only test code needs, once per bound of an item.
+    pub fn show_test_only(report: &Report) {
+        let mut shown = Vec::new();
+        for r in report.files.iter().flat_map(|f| &f.removals) {
+            let f = &r.finding;
+            let key = (&f.locator, f.candidate.key());
+            if !r.test_only || shown.contains(&key) {
+                continue;
+            }
+            shown.push(key);
+            eprintln!(
+                "{}:{} {}: `{}: {}`: {TEST_ONLY_NOTE}",
+                f.locator.file.display(),
+                f.line,
+                f.item,
+                f.bounded,
+                f.bound
+            );
+        }
+    }
+
+    /// Print on stderr the items `prune` skipped as validation never builds them, once per
+    /// item, with how to have it build them.
+    pub fn show_not_validated(report: &Report) {
+        let mut shown = Vec::new();
+        for r in report.files.iter().flat_map(|f| &f.removals) {
+            let f = &r.finding;
+            let Some(hint) = f.validation_hint() else {
+                continue;
+            };
+            if shown.contains(&&f.locator) {
+                continue;
+            }
+            shown.push(&f.locator);
+            let cfg: Vec<String> = f.cfg.iter().map(|c| format!("`cfg({c})`")).collect();
+            eprintln!(
+                "{}:{} {}: skipped (not covered by validation): only built with {}; {hint}",
+                f.locator.file.display(),
+                f.line,
+                f.item,
+                cfg.join(" and ")
+            );
+        }
+    }
+
+    /// Print each plan entry's status to stdout, then a summary line.
+    pub fn show_apply(applied: &ApplyReport) {
+        for entry in &applied.entries {
+            println!("{entry}");
+        }
+        let validation = match &applied.validation {
+            None => "nothing to validate",
+            Some(_) if applied.reverted => "validation failed, all files restored",
+            Some(_) => "validation passed",
+        };
+        println!(
+            "applied {} of {} entries, {} file(s) modified; {validation}",
+            applied.applied(),
+            applied.entries.len(),
+            applied.modified.len()
+        );
+    }
+
+    /// Print what `check --fix` removed, or would remove with `dry_run`, on stderr: one
+    /// line per rule, then the total.
+    pub fn show_fixed(fixed: &FixReport, dry_run: bool) {
+        for (rule, count) in &fixed.fixed {
+            eprintln!("{count:>8}  {rule}");
+        }
+        let verb = if dry_run { "would remove" } else { "removed" };
+        eprintln!(
+            "{verb} {} bound(s) in {} file(s), without running cargo",
+            fixed.total(),
+            fixed.changes.len()
+        );
+    }
+
+    /// Report on stderr how many notes `prune --annotate-retained` left in the tree.
+    pub fn show_annotated(count: usize) {
+        if count > 0 {
+            eprintln!("annotated {count} retained bound(s) with what requires them");
+        }
+    }
+
+    /// Report on stderr the files `prune --strip-annotations` took notes out of.
+    pub fn show_stripped(changes: &[FileChange], dry_run: bool) {
+        let verb = if dry_run { "would strip" } else { "stripped" };
+        let notes: usize = changes
+            .iter()
+            .map(|c| c.old.lines().filter(|l| is_marker(l)).count())
+            .sum();
+        eprintln!("{verb} {notes} annotation(s) in {} file(s)", changes.len());
+    }
+
+    /// Report the commits `prune` made on stderr, and the git failure that stopped them.
+    pub fn show_commits(log: &CommitLog) {
+        for c in &log.commits {
+            let short = c.hash.get(..12).unwrap_or(&c.hash);
+            eprintln!("committed {short} {}", c.message);
+        }
+        if let Some(failure) = &log.failure {
+            eprintln!(
+                "warning: stopped committing after {} commit(s), the removals stay in the work tree: {failure}",
+                log.commits.len()
+            );
+        }
+    }
+
+    /// Report the patches `prune --output-patches` wrote on stderr.
+    pub fn show_patches(patches: &[PathBuf]) {
+        for path in patches {
+            eprintln!("wrote {}", path.display());
+        }
+    }
+
+    /// Print the compile times `impact` measured: the median of each command before and
+    /// after pruning, then per crate where the toolchain reported them.
+    pub fn show_impact(impact: &Impact) {
+        let secs = |d: std::time::Duration| format!("{:.3}s", d.as_secs_f64());
+        let percent = |delta: f64| format!("{:+.1}%", delta * 100.0);
+        println!("median of {} run(s) per tree:", impact.iterations);
+        let commands = std::iter::once(("check", &impact.check))
+            .chain(impact.build.as_ref().map(|b| ("build", b)));
+        for (command, c) in commands {
+            println!(
+                "  cargo {command}: {} -> {} ({})",
+                secs(c.before.median()),
+                secs(c.after.median()),
+                percent(c.delta())
+            );
+            for (name, before, after, delta) in c.crates() {
+                println!(
+                    "    {name}: {} -> {} ({})",
+                    secs(before),
+                    secs(after),
+                    percent(delta)
+                );
+            }
+        }
+    }
+
+    /// Report the files `recover` restored.
+    pub fn show_recovered(restored: &[PathBuf]) {
+        for path in restored {
+            println!("restored {}", path.display());
+        }
+        match restored.len() {
+            0 => println!("nothing to recover"),
+            n => println!("restored {n} file(s) left mid-trial by an interrupted run"),
+        }
+    }
+
+    /// Report the semver gate of a `prune` run on stderr: a warning when it could not run,
+    /// and the removals it put back after a breaking change.
+    pub fn show_semver(semver: &SemverReport, verbosity: u8) {
+        match &semver.outcome {
+            SemverOutcome::Compatible => {
+                if verbosity > 0 {
+                    eprintln!("semver check passed");
+                }
+            }
+            SemverOutcome::Unavailable { reason } => {
+                eprintln!("warning: semver check skipped: {reason}");
+            }
+            SemverOutcome::Breaking { summary } => {
+                eprintln!(
+                    "semver check found a breaking change; put back {} removal(s):",
+                    semver.reverted.len()
+                );
+                for f in &semver.reverted {
+                    let trigger = if semver.triggers.contains(f) {
+                        " (named by cargo semver-checks)"
+                    } else {
+                        ""
+                    };
+                    eprintln!(
+                        "  {}:{}:{} {}: {}: {}{trigger}",
+                        f.locator.file.display(),
+                        f.line,
+                        f.column,
+                        f.item,
+                        f.bounded,
+                        f.bound
+                    );
+                }
+                if verbosity > 0 {
+                    eprintln!("{summary}");
+                }
+            }
+        }
     }
 
     /// Debug utility: print an `ItemRef` AST to stdout, nicely formatted.
@@ -4,10 +4,44 @@
 #![deny(missing_docs)]
 
 pub mod analysis;
+pub mod annotate;
+#[cfg(feature = "cli")]
+pub mod app;
+#[cfg(feature = "discover")]
+pub mod bench;
+pub mod cancel;
+#[cfg(feature = "cli")]
 pub mod cli;
+pub mod commit;
 pub mod config;
+#[cfg(feature = "discover")]
+pub mod crate_map;
+pub mod derivable;
+pub mod diff;
+#[cfg(feature = "discover")]
 pub mod discover;
+pub mod doctor;
 pub mod dynamic_analysis;
 pub mod error;
+pub mod explain;
+pub mod findings;
+pub mod fingerprint;
+pub mod fix;
+pub mod formats;
+pub mod hook;
+pub mod impact;
 pub mod info;
+pub mod observer;
+pub mod patch;
+pub mod plan;
+pub mod references;
+pub mod report;
+pub mod strategy;
 pub mod target;
+pub mod tidy;
+pub mod trait_index;
+pub mod type_path;
+#[cfg(feature = "watch")]
+pub mod watch;
+#[cfg(feature = "discover")]
+pub mod winnower;
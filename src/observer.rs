@@ -0,0 +1,31 @@
+// src/observer.rs
+//! Progress callbacks for the prune loop.
+
+#![deny(missing_docs)]
+
+use crate::dynamic_analysis::common::BoundRemovalResult;
+use crate::report::FileMetrics;
+use std::path::Path;
+
+/// Receives progress events while `prune` runs. Every method defaults to doing nothing,
+/// so implementors override only what they need. Events carry shared references only:
+/// an observer can watch the run but not change the working tree.
+pub trait Observer {
+    /// A file is about to be pruned.
+    fn on_file_start(&mut self, _path: &Path) {}
+
+    /// Candidate `index` (0-based) of `total` on `item` is about to be tried.
+    fn on_candidate_trial(&mut self, _item: &str, _bound: &str, _index: usize, _total: usize) {}
+
+    /// A candidate trial finished.
+    fn on_outcome(&mut self, _result: &BoundRemovalResult) {}
+
+    /// A file is done; `stats` holds its final counters and timings.
+    fn on_file_done(&mut self, _stats: &FileMetrics) {}
+}
+
+/// An observer that ignores every event.
+#[derive(Debug, Default, Clone, Copy)]
+pub struct NoopObserver;
+
+impl Observer for NoopObserver {}
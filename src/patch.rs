@@ -0,0 +1,274 @@
+// src/patch.rs
+//! Unified diffs of what `prune` changed, in the form `git apply` and `patch -p1` take.
+//!
+//! Files are compared line by line with Myers' algorithm and printed as git does: a
+//! `diff --git` header, `a/` and `b/` prefixed paths, hunks with three lines of context and
+//! `\ No newline at end of file` after a last line without one.
+
+#![deny(missing_docs)]
+
+use crate::error::{TraitError, WinnowerError};
+use std::fmt::Write as _;
+use std::path::{Path, PathBuf};
+
+/// Lines of unchanged text around each change.
+const CONTEXT: usize = 3;
+
+/// The file name of the patch [`write_patches`] writes with `single`.
+pub const SINGLE_PATCH: &str = "winnow.patch";
+
+/// A file `prune` changed: its path as the patch names it, and its text before and after.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct FileChange {
+    /// Path relative to where the patch is applied.
+    pub path: PathBuf,
+    /// Text before the run.
+    pub old: String,
+    /// Text after the run.
+    pub new: String,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Kind {
+    Equal,
+    Delete,
+    Insert,
+}
+
+/// One line of the edit script, with the line index it is at in each text.
+#[derive(Debug, Clone, Copy)]
+struct Op {
+    kind: Kind,
+    old: usize,
+    new: usize,
+}
+
+/// The shortest edit script turning `a` into `b` (Myers, 1986).
+fn edit_script(a: &[&str], b: &[&str]) -> Vec<Op> {
+    let (n, m) = (a.len() as isize, b.len() as isize);
+    let max = n + m;
+    let at = |k: isize| (k + max) as usize;
+    let mut v = vec![0isize; 2 * max as usize + 2];
+    // `v` before each round, to walk the path back.
+    let mut trace = Vec::new();
+    'search: for d in 0..=max {
+        trace.push(v.clone());
+        for k in (-d..=d).step_by(2) {
+            let mut x = if k == -d || (k != d && v[at(k - 1)] < v[at(k + 1)]) {
+                v[at(k + 1)]
+            } else {
+                v[at(k - 1)] + 1
+            };
+            let mut y = x - k;
+            while x < n && y < m && a[x as usize] == b[y as usize] {
+                x += 1;
+                y += 1;
+            }
+            v[at(k)] = x;
+            if x >= n && y >= m {
+                break 'search;
+            }
+        }
+    }
+
+    let mut ops = Vec::new();
+    let (mut x, mut y) = (n, m);
+    for (d, v) in trace.iter().enumerate().rev() {
+        let d = d as isize;
+        let k = x - y;
+        let prev_k = if k == -d || (k != d && v[at(k - 1)] < v[at(k + 1)]) {
+            k + 1
+        } else {
+            k - 1
+        };
+        let prev_x = v[at(prev_k)];
+        let prev_y = prev_x - prev_k;
+        while x > prev_x && y > prev_y {
+            x -= 1;
+            y -= 1;
+            ops.push(Op {
+                kind: Kind::Equal,
+                old: x as usize,
+                new: y as usize,
+            });
+        }
+        if d > 0 {
+            ops.push(if x == prev_x {
+                Op {
+                    kind: Kind::Insert,
+                    old: x as usize,
+                    new: prev_y as usize,
+                }
+            } else {
+                Op {
+                    kind: Kind::Delete,
+                    old: prev_x as usize,
+                    new: y as usize,
+                }
+            });
+        }
+        (x, y) = (prev_x, prev_y);
+    }
+    ops.reverse();
+    ops
+}
+
+/// `start,len` of a hunk header; an empty range names the line before it.
+fn range(first: usize, len: usize) -> String {
+    match len {
+        0 => format!("{first},0"),
+        1 => format!("{}", first + 1),
+        _ => format!("{},{len}", first + 1),
+    }
+}
+
+/// The diff of `change`, empty when its texts are equal.
+pub fn unified_diff(change: &FileChange) -> String {
+    let a: Vec<&str> = change.old.split_inclusive('\n').collect();
+    let b: Vec<&str> = change.new.split_inclusive('\n').collect();
+    let ops = edit_script(&a, &b);
+    let is_change = |op: &Op| op.kind != Kind::Equal;
+    if !ops.iter().any(is_change) {
+        return String::new();
+    }
+
+    let path = change.path.to_string_lossy().replace('\\', "/");
+    let mut out = format!("diff --git a/{path} b/{path}\n--- a/{path}\n+++ b/{path}\n");
+    let mut i = 0;
+    while let Some(first) = ops[i..].iter().position(is_change).map(|p| p + i) {
+        let start = first.saturating_sub(CONTEXT).max(i);
+        // Extend over changes separated by at most twice the context.
+        let mut end = first;
+        loop {
+            while end < ops.len() && is_change(&ops[end]) {
+                end += 1;
+            }
+            let gap = ops[end..].iter().take_while(|op| !is_change(op)).count();
+            if end + gap < ops.len() && gap <= 2 * CONTEXT {
+                end += gap;
+            } else {
+                break;
+            }
+        }
+        let stop = (end + CONTEXT).min(ops.len());
+        let hunk = &ops[start..stop];
+        let old_len = hunk.iter().filter(|op| op.kind != Kind::Insert).count();
+        let new_len = hunk.iter().filter(|op| op.kind != Kind::Delete).count();
+        let _ = writeln!(
+            out,
+            "@@ -{} +{} @@",
+            range(hunk[0].old, old_len),
+            range(hunk[0].new, new_len)
+        );
+        for op in hunk {
+            let (sign, line) = match op.kind {
+                Kind::Equal => (' ', a[op.old]),
+                Kind::Delete => ('-', a[op.old]),
+                Kind::Insert => ('+', b[op.new]),
+            };
+            out.push(sign);
+            out.push_str(line);
+            if !line.ends_with('\n') {
+                out.push_str("\n\\ No newline at end of file\n");
+            }
+        }
+        i = stop;
+    }
+    out
+}
+
+/// Write the diffs of `changes` into `dir`, created if missing: one
+/// `NNNN-<file stem>.patch` per changed file in the order given, or all of them in
+/// [`SINGLE_PATCH`] with `single`. Returns the patches written.
+pub fn write_patches(dir: &Path, changes: &[FileChange], single: bool) -> TraitError<Vec<PathBuf>> {
+    let diffs: Vec<(&FileChange, String)> = changes
+        .iter()
+        .map(|c| (c, unified_diff(c)))
+        .filter(|(_, diff)| !diff.is_empty())
+        .collect();
+    if diffs.is_empty() {
+        return Ok(Vec::new());
+    }
+    std::fs::create_dir_all(dir).map_err(|e| WinnowerError::io("creating", dir, e))?;
+    let mut files = Vec::new();
+    if single {
+        files.push((
+            dir.join(SINGLE_PATCH),
+            diffs.iter().map(|(_, diff)| diff.as_str()).collect(),
+        ));
+    } else {
+        for (n, (change, diff)) in diffs.into_iter().enumerate() {
+            let stem = change
+                .path
+                .file_stem()
+                .map_or("patch".into(), |s| s.to_string_lossy());
+            files.push((dir.join(format!("{:04}-{stem}.patch", n + 1)), diff));
+        }
+    }
+    let mut written = Vec::new();
+    for (path, text) in files {
+        std::fs::write(&path, text).map_err(|e| WinnowerError::io("writing", &path, e))?;
+        written.push(path);
+    }
+    Ok(written)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn diff(old: &str, new: &str) -> String {
+        unified_diff(&FileChange {
+            path: "src/lib.rs".into(),
+            old: old.into(),
+            new: new.into(),
+        })
+    }
+
+    #[test]
+    fn hunks_carry_three_lines_of_context() {
+        let old: String = (1..=12).map(|n| format!("{n}\n")).collect();
+        let new = old.replace("6\n", "six\n");
+        assert_eq!(
+            diff(&old, &new),
+            "diff --git a/src/lib.rs b/src/lib.rs\n--- a/src/lib.rs\n+++ b/src/lib.rs\n\
+             @@ -3,7 +3,7 @@\n 3\n 4\n 5\n-6\n+six\n 7\n 8\n 9\n"
+        );
+        assert_eq!(diff(&old, &old), "");
+    }
+
+    #[test]
+    fn distant_changes_get_their_own_hunks() {
+        let lines = |skip: usize, add: usize| -> String {
+            (1..=20)
+                .filter(|&n| n != skip)
+                .map(|n| {
+                    if n == add {
+                        format!("{n}\n{n}b\n")
+                    } else {
+                        format!("{n}\n")
+                    }
+                })
+                .collect()
+        };
+        let (old, new) = (lines(0, 0), lines(2, 19));
+        let printed = diff(&old, &new);
+        assert!(
+            printed.contains("@@ -1,5 +1,4 @@\n 1\n-2\n 3\n 4\n 5\n"),
+            "{printed}"
+        );
+        assert!(
+            printed.contains("@@ -17,4 +16,5 @@\n 17\n 18\n 19\n+19b\n 20\n"),
+            "{printed}"
+        );
+    }
+
+    #[test]
+    fn a_missing_final_newline_is_marked() {
+        assert_eq!(
+            diff("a\nb", "a\nc"),
+            "diff --git a/src/lib.rs b/src/lib.rs\n--- a/src/lib.rs\n+++ b/src/lib.rs\n\
+             @@ -1,2 +1,2 @@\n a\n-b\n\\ No newline at end of file\n+c\n\\ No newline at end of file\n"
+        );
+    }
+}
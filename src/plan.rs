@@ -0,0 +1,566 @@
+// src/plan.rs
+//! Removal plans: bounds chosen for removal, saved for review and applied later.
+
+#![deny(missing_docs)]
+
+use crate::analysis::{ItemBounds, ItemKey, ItemLocator, ItemRef};
+use crate::config::Config;
+use crate::dynamic_analysis::common::{
+    CargoCheck, OwnedBoundCandidate, OwnedBoundSite, ValidationOutcome,
+};
+use crate::dynamic_analysis::text_edit::{TextEdit, apply_edits, item_edits};
+use crate::dynamic_analysis::validate::Validator;
+use crate::error::{TraitError, WinnowerError};
+use crate::fingerprint::{Drift, Fingerprint};
+use crate::formats::Format;
+use crate::report::Report;
+use crate::type_path::TypePath;
+use quote::ToTokens;
+use serde::{Deserialize, Serialize};
+use std::path::{Path, PathBuf};
+use syn::punctuated::Punctuated;
+use syn::token::Plus;
+use syn::{FnArg, GenericParam, Type, TypeParamBound, WherePredicate};
+
+/// Mismatches listed in the strict-mode error before it is cut short.
+const STRICT_ERROR_ENTRIES: usize = 5;
+
+/// One bound to remove.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct PlanEntry {
+    /// The item declaring the bound. Its file is relative to the crate root; its line and
+    /// column only break ties between items of the same name.
+    pub locator: ItemLocator,
+    /// The bound and the site it is declared at.
+    pub candidate: OwnedBoundCandidate,
+}
+
+/// Bounds to remove, in the order they were planned. Saved as TOML.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct Plan {
+    /// The `Cargo.lock` and rustc of the run the plan was made from, if known.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub fingerprint: Option<Fingerprint>,
+    /// The bounds to remove.
+    #[serde(default)]
+    pub entries: Vec<PlanEntry>,
+}
+
+impl Plan {
+    /// A plan removing every bound `report` lists as a finding.
+    pub fn from_report(report: &Report) -> Self {
+        let entries = report
+            .files
+            .iter()
+            .flat_map(|f| &f.findings)
+            .map(|f| PlanEntry {
+                locator: f.locator.clone(),
+                candidate: f.candidate.clone(),
+            })
+            .collect();
+        Self {
+            fingerprint: report.fingerprint.clone(),
+            entries,
+        }
+    }
+
+    /// Read a plan written by [`Plan::save`].
+    pub fn load(path: &Path) -> TraitError<Self> {
+        let text =
+            std::fs::read_to_string(path).map_err(|e| WinnowerError::io("reading", path, e))?;
+        Format::Plan.from_toml(path, &text)
+    }
+
+    /// Write the plan to `path`, versioned as [`PLAN_VERSION`](crate::formats::PLAN_VERSION).
+    pub fn save(&self, path: &Path) -> TraitError<()> {
+        let text = Format::Plan.to_toml(self)?;
+        std::fs::write(path, text).map_err(|e| WinnowerError::io("writing", path, e))
+    }
+}
+
+/// How one plan entry fared.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum EntryStatus {
+    /// The bound was found and removed.
+    Applied,
+    /// The entry's file does not exist.
+    FileMissing,
+    /// The entry's file could not be read or parsed.
+    FileUnreadable {
+        /// Why.
+        detail: String,
+    },
+    /// No item in the file with the entry's module path, kind and name declares any bounds;
+    /// the item was renamed, moved or removed, or has lost all its bounds.
+    ItemNotFound,
+    /// The item no longer declares the bound.
+    BoundGone,
+    /// A different bound now sits where the entry's bound was.
+    BoundTextDiffers {
+        /// The bound found there instead.
+        found: String,
+    },
+}
+
+impl EntryStatus {
+    /// Whether the entry was applied.
+    pub fn is_applied(&self) -> bool {
+        *self == EntryStatus::Applied
+    }
+}
+
+impl std::fmt::Display for EntryStatus {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            EntryStatus::Applied => f.write_str("applied"),
+            EntryStatus::FileMissing => f.write_str("file missing"),
+            EntryStatus::FileUnreadable { detail } => write!(f, "file unreadable ({detail})"),
+            EntryStatus::ItemNotFound => f.write_str("no bounded item found"),
+            EntryStatus::BoundGone => f.write_str("bound already gone"),
+            EntryStatus::BoundTextDiffers { found } => {
+                write!(f, "bound text differs (found `{found}`)")
+            }
+        }
+    }
+}
+
+/// A plan entry with its status.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct EntryOutcome {
+    /// The entry as planned.
+    pub entry: PlanEntry,
+    /// What happened to it.
+    pub status: EntryStatus,
+}
+
+impl std::fmt::Display for EntryOutcome {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let PlanEntry { locator, candidate } = &self.entry;
+        write!(
+            f,
+            "{}: {}:{}:{} {}: {}",
+            self.status,
+            locator.file.display(),
+            locator.line,
+            locator.column,
+            locator.name,
+            candidate.site.bounded(),
+        )?;
+        write!(f, ": {}", candidate.bound_text)
+    }
+}
+
+/// Result of [`apply_plan`].
+#[derive(Debug, Default)]
+pub struct ApplyReport {
+    /// Every plan entry with its status, in plan order.
+    pub entries: Vec<EntryOutcome>,
+    /// Files changed on disk, relative to the root.
+    pub modified: Vec<PathBuf>,
+    /// The final validation; `None` when no entry applied.
+    pub validation: Option<ValidationOutcome>,
+    /// Validation failed, so every file was restored.
+    pub reverted: bool,
+    /// How the plan's fingerprint differs from the crate's now, when the plan records one
+    /// and [`ApplyOptions::ignore_fingerprint`] is off.
+    pub drift: Option<Drift>,
+}
+
+impl ApplyReport {
+    /// Entries applied.
+    pub fn applied(&self) -> usize {
+        self.entries
+            .iter()
+            .filter(|e| e.status.is_applied())
+            .count()
+    }
+}
+
+/// Options for [`apply_plan_with`].
+#[derive(Default)]
+pub struct ApplyOptions<'a> {
+    /// Fail without changing any file when any entry does not match.
+    pub strict: bool,
+    /// Validate with this instead of `cargo check` as configured for the root.
+    pub validator: Option<&'a dyn Validator>,
+    /// Do not compare the plan's fingerprint with the crate's.
+    pub ignore_fingerprint: bool,
+}
+
+/// Apply `plan` to the crate at `root`, leniently, validating with `cargo check`.
+pub fn apply_plan(root: &Path, plan: &Plan) -> TraitError<ApplyReport> {
+    apply_plan_with(root, plan, &ApplyOptions::default())
+}
+
+/// Apply `plan` to the crate at `root`.
+///
+/// Every referenced file is re-read and each entry's item is located by module path, kind
+/// and name. The bound is re-derived from the stored site and bound text, so entries still
+/// apply after unrelated edits. Entries that no longer match are reported and skipped.
+/// With [`ApplyOptions::strict`] they fail the whole apply instead. All matching removals
+/// are written, then validated once; if validation fails every file is restored.
+pub fn apply_plan_with(
+    root: &Path,
+    plan: &Plan,
+    options: &ApplyOptions<'_>,
+) -> TraitError<ApplyReport> {
+    let mut statuses: Vec<Option<EntryStatus>> = vec![None; plan.entries.len()];
+    let mut writes = Vec::new();
+    for (rel, indices) in group_by_file(plan) {
+        let path = root.join(&rel);
+        let mut fail_all = |status: EntryStatus| {
+            for &i in &indices {
+                statuses[i] = Some(status.clone());
+            }
+        };
+        if !path.exists() {
+            fail_all(EntryStatus::FileMissing);
+            continue;
+        }
+        let parsed = ItemBounds::read_source(&path).and_then(|src| {
+            let file = syn::parse_file(&src).map_err(|e| WinnowerError::parse(&path, e))?;
+            Ok((src, file))
+        });
+        let (source, file) = match parsed {
+            Ok(parsed) => parsed,
+            Err(e) => {
+                fail_all(EntryStatus::FileUnreadable {
+                    detail: e.to_string(),
+                });
+                continue;
+            }
+        };
+        let entries: Vec<&PlanEntry> = indices.iter().map(|&i| &plan.entries[i]).collect();
+        let (edits, found) = entry_edits(&rel, &source, &file, &entries)?;
+        for (&i, status) in indices.iter().zip(found) {
+            statuses[i] = Some(status);
+        }
+        if edits.is_empty() {
+            continue;
+        }
+        let updated = apply_edits(&source, &edits);
+        if updated != source {
+            writes.push((rel, path, source, updated));
+        }
+    }
+
+    let entries: Vec<EntryOutcome> = plan
+        .entries
+        .iter()
+        .zip(statuses)
+        .map(|(entry, status)| EntryOutcome {
+            entry: entry.clone(),
+            status: status.unwrap_or(EntryStatus::ItemNotFound),
+        })
+        .collect();
+    if options.strict {
+        strict_check(root, &entries)?;
+    }
+
+    let mut out = ApplyReport {
+        entries,
+        drift: plan
+            .fingerprint
+            .as_ref()
+            .filter(|_| !options.ignore_fingerprint)
+            .and_then(|recorded| {
+                Drift::between("plan", Some(recorded), &Fingerprint::capture(root))
+            }),
+        ..ApplyReport::default()
+    };
+    if writes.is_empty() {
+        return Ok(out);
+    }
+    for (i, (_, path, _, updated)) in writes.iter().enumerate() {
+        if let Err(e) = std::fs::write(path, updated) {
+            restore(&writes[..i])?;
+            return Err(WinnowerError::io("writing updated", path, e));
+        }
+    }
+
+    let cargo_check;
+    let validator = match options.validator {
+        Some(v) => v,
+        None => {
+            cargo_check = CargoCheck::new(Config::load_or_default(root)?.cargo_check);
+            &cargo_check
+        }
+    };
+    let check = match validator.validate(root) {
+        Ok(check) => check,
+        Err(e) => {
+            restore(&writes)?;
+            return Err(e);
+        }
+    };
+    if check.success {
+        out.modified = writes.into_iter().map(|(rel, ..)| rel).collect();
+    } else {
+        restore(&writes)?;
+        out.reverted = true;
+    }
+    out.validation = Some(check);
+    Ok(out)
+}
+
+/// The edits removing `entries`, all in the file `rel` parsed as `file` from `source`, with
+/// the status of each entry.
+pub(crate) fn entry_edits(
+    rel: &Path,
+    source: &str,
+    file: &syn::File,
+    entries: &[&PlanEntry],
+) -> TraitError<(Vec<TextEdit>, Vec<EntryStatus>)> {
+    let items = ItemBounds::collect_items_in_file(file)?;
+    let keys: Vec<&ItemKey<'_>> = items.iter_all_items().collect();
+    let mut per_item: Vec<(usize, Vec<OwnedBoundCandidate>)> = Vec::new();
+    let mut statuses = Vec::with_capacity(entries.len());
+    for entry in entries {
+        let found = locate(&keys, rel, &entry.locator)
+            .ok_or(EntryStatus::ItemNotFound)
+            .and_then(|k| Ok((k, rederive(keys[k].item(), &entry.candidate)?)));
+        match found {
+            Ok((k, candidate)) => {
+                match per_item.iter_mut().find(|(item, _)| *item == k) {
+                    Some((_, list)) => list.push(candidate),
+                    None => per_item.push((k, vec![candidate])),
+                }
+                statuses.push(EntryStatus::Applied);
+            }
+            Err(status) => statuses.push(status),
+        }
+    }
+    let edits = item_edits(
+        source,
+        per_item
+            .iter()
+            .map(|(k, list)| (keys[*k].item(), list.as_slice())),
+    );
+    Ok((edits, statuses))
+}
+
+/// Entry indices per file, files in order of first appearance.
+fn group_by_file(plan: &Plan) -> Vec<(PathBuf, Vec<usize>)> {
+    let mut files: Vec<(PathBuf, Vec<usize>)> = Vec::new();
+    for (i, entry) in plan.entries.iter().enumerate() {
+        match files.iter_mut().find(|(p, _)| *p == entry.locator.file) {
+            Some((_, indices)) => indices.push(i),
+            None => files.push((entry.locator.file.clone(), vec![i])),
+        }
+    }
+    files
+}
+
+/// The item matching `want` by module path, kind and name; the closest to its recorded
+/// position when several do.
+pub(crate) fn locate(keys: &[&ItemKey<'_>], rel: &Path, want: &ItemLocator) -> Option<usize> {
+    keys.iter()
+        .enumerate()
+        .filter_map(|(i, key)| {
+            let got = ItemLocator::from(*key).in_file(rel);
+            (got.kind == want.kind && got.name == want.name && got.module_path == want.module_path)
+                .then(|| {
+                    (
+                        i,
+                        got.line.abs_diff(want.line),
+                        got.column.abs_diff(want.column),
+                    )
+                })
+        })
+        .min_by_key(|&(_, dl, dc)| (dl, dc))
+        .map(|(i, _, _)| i)
+}
+
+fn tokens<T: ToTokens>(node: &T) -> String {
+    node.to_token_stream().to_string()
+}
+
+/// The candidate with its indices as found in `item`: the stored site and index when they
+/// still hold the bound, else wherever the bound now sits at that parameter, type or
+/// argument.
+fn rederive(
+    item: &ItemRef<'_>,
+    candidate: &OwnedBoundCandidate,
+) -> Result<OwnedBoundCandidate, EntryStatus> {
+    let generics = item.generics();
+    let text = &candidate.bound_text;
+    let site = match &candidate.site {
+        OwnedBoundSite::TypeParam {
+            ident,
+            param_index,
+            bound_index,
+        } => {
+            let params: Vec<(usize, &syn::TypeParam)> = generics
+                .params
+                .iter()
+                .enumerate()
+                .filter_map(|(i, p)| match p {
+                    GenericParam::Type(tp) if tp.ident == ident => Some((i, tp)),
+                    _ => None,
+                })
+                .collect();
+            let (param_index, tp) = params
+                .iter()
+                .find(|(i, _)| i == param_index)
+                .or_else(|| params.first())
+                .ok_or(EntryStatus::BoundGone)?;
+            OwnedBoundSite::TypeParam {
+                ident: ident.clone(),
+                param_index: *param_index,
+                bound_index: find_bound(&tp.bounds, *bound_index, text)?,
+            }
+        }
+        OwnedBoundSite::WhereClause {
+            ty,
+            pred_index,
+            bound_index,
+        } => {
+            let preds: Vec<(usize, &syn::PredicateType)> = generics
+                .where_clause
+                .iter()
+                .flat_map(|wc| wc.predicates.iter().enumerate())
+                .filter_map(|(i, p)| match p {
+                    WherePredicate::Type(pt) if tokens(&pt.bounded_ty) == *ty => Some((i, pt)),
+                    _ => None,
+                })
+                .collect();
+            let (pred_index, pt) = preds
+                .iter()
+                .find(|(i, _)| i == pred_index)
+                .or_else(|| preds.first())
+                .ok_or(EntryStatus::BoundGone)?;
+            OwnedBoundSite::WhereClause {
+                ty: ty.clone(),
+                pred_index: *pred_index,
+                bound_index: find_bound(&pt.bounds, *bound_index, text)?,
+            }
+        }
+        OwnedBoundSite::ImplTraitArg {
+            arg,
+            arg_index,
+            bound_index,
+        } => {
+            let args: Vec<(usize, &syn::TypeImplTrait)> = item
+                .inputs()
+                .into_iter()
+                .flatten()
+                .enumerate()
+                .filter_map(|(i, a)| match a {
+                    FnArg::Typed(pt) if tokens(&pt.pat) == *arg => match &*pt.ty {
+                        Type::ImplTrait(it) => Some((i, it)),
+                        _ => None,
+                    },
+                    _ => None,
+                })
+                .collect();
+            let (arg_index, it) = args
+                .iter()
+                .find(|(i, _)| i == arg_index)
+                .or_else(|| args.first())
+                .ok_or(EntryStatus::BoundGone)?;
+            OwnedBoundSite::ImplTraitArg {
+                arg: arg.clone(),
+                arg_index: *arg_index,
+                bound_index: find_bound(&it.bounds, *bound_index, text)?,
+            }
+        }
+        OwnedBoundSite::ReturnImplTrait {
+            ty,
+            path,
+            bound_index,
+        } => {
+            let bounds = match opaque_type(item, path)? {
+                Type::ImplTrait(it) => &it.bounds,
+                _ => return Err(EntryStatus::BoundGone),
+            };
+            OwnedBoundSite::ReturnImplTrait {
+                ty: ty.clone(),
+                path: path.clone(),
+                bound_index: find_bound(bounds, *bound_index, text)?,
+            }
+        }
+        OwnedBoundSite::TraitObject {
+            ty,
+            path,
+            bound_index,
+        } => {
+            let bounds = match opaque_type(item, path)? {
+                Type::TraitObject(to) => &to.bounds,
+                _ => return Err(EntryStatus::BoundGone),
+            };
+            OwnedBoundSite::TraitObject {
+                ty: ty.clone(),
+                path: path.clone(),
+                bound_index: find_bound(bounds, *bound_index, text)?,
+            }
+        }
+    };
+    Ok(OwnedBoundCandidate {
+        site,
+        bound_text: text.clone(),
+    })
+}
+
+/// The type at `path` in `item`. Unlike parameters and arguments, these types are not
+/// looked for elsewhere: nothing but the path tells apart two `dyn Error + Send`, and the
+/// type's own text changes with each bound removed from it.
+fn opaque_type<'a>(item: &ItemRef<'a>, path: &TypePath) -> Result<&'a Type, EntryStatus> {
+    item.type_root(path.root)
+        .and_then(|root| path.resolve(root))
+        .ok_or(EntryStatus::BoundGone)
+}
+
+fn find_bound(
+    bounds: &Punctuated<TypeParamBound, Plus>,
+    index: usize,
+    text: &str,
+) -> Result<usize, EntryStatus> {
+    let texts: Vec<String> = bounds.iter().map(tokens).collect();
+    if texts.get(index).is_some_and(|t| t == text) {
+        return Ok(index);
+    }
+    if let Some(i) = texts.iter().position(|t| t == text) {
+        return Ok(i);
+    }
+    match texts.get(index) {
+        Some(found) => Err(EntryStatus::BoundTextDiffers {
+            found: found.clone(),
+        }),
+        None => Err(EntryStatus::BoundGone),
+    }
+}
+
+fn strict_check(root: &Path, entries: &[EntryOutcome]) -> TraitError<()> {
+    let mismatched: Vec<&EntryOutcome> =
+        entries.iter().filter(|e| !e.status.is_applied()).collect();
+    if mismatched.is_empty() {
+        return Ok(());
+    }
+    let mut detail = format!(
+        "{} of {} entries do not apply (strict mode, nothing changed)",
+        mismatched.len(),
+        entries.len()
+    );
+    for e in mismatched.iter().take(STRICT_ERROR_ENTRIES) {
+        detail.push_str(&format!("\n  {e}"));
+    }
+    if mismatched.len() > STRICT_ERROR_ENTRIES {
+        detail.push_str(&format!(
+            "\n  ... and {} more",
+            mismatched.len() - STRICT_ERROR_ENTRIES
+        ));
+    }
+    Err(WinnowerError::Plan {
+        path: root.to_path_buf(),
+        detail,
+    })
+}
+
+/// Put back the original text of every written file.
+fn restore(writes: &[(PathBuf, PathBuf, String, String)]) -> TraitError<()> {
+    for (_, path, original, _) in writes {
+        std::fs::write(path, original).map_err(|e| WinnowerError::io("reverting", path, e))?;
+    }
+    Ok(())
+}
@@ -0,0 +1,269 @@
+// src/references.rs
+//! How often the workspace refers to each item: the weight of the item's bounds.
+//!
+//! Not every removable bound is worth the same: dropping `Clone` from a function with two
+//! hundred callers helps every one of them, dropping it from an internal helper barely
+//! matters. [`References`] counts, over every discovered source file, the paths ending in
+//! each name (calls, type uses, bounds, struct literals, patterns) and the method calls of
+//! each name, with every identifier in a macro body. Names are not resolved, so items
+//! sharing a name share their count. An item's [`weight`](References::weight) is the count
+//! of its name; a method's that of the method name, an impl's that of its self type.
+//!
+//! Counts of a crate or workspace are cached per file in [`CACHE_FILE`] under
+//! [`JOURNAL_DIR`](crate::dynamic_analysis::journal::JOURNAL_DIR), with the CRC32 of the
+//! text they were counted from, so later runs only scan the files that changed.
+
+#![deny(missing_docs)]
+
+use crate::analysis::{ItemBounds, ItemKind, ItemLocator};
+use crate::dynamic_analysis::journal::Journal;
+use crate::error::{TraitError, WinnowerError};
+use crate::report::Finding;
+use proc_macro2::{TokenStream, TokenTree};
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
+use syn::visit::Visit;
+
+/// The cache file, in the journal directory.
+pub const CACHE_FILE: &str = "references.json";
+
+/// How `check` and `prune` order findings and files.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[cfg_attr(feature = "cli", derive(clap::ValueEnum))]
+#[serde(rename_all = "snake_case")]
+pub enum SortBy {
+    /// Files by path, findings by position (default). `prune` goes by its `order`.
+    #[default]
+    Location,
+    /// Heaviest first (see [`References::weight`]): findings within a file, and files by
+    /// their heaviest finding; ties by location.
+    Weight,
+}
+
+/// References by name, as counted in one file.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct FileReferences {
+    /// CRC32 of the text counted.
+    pub hash: u32,
+    /// References by name.
+    pub counts: BTreeMap<String, usize>,
+}
+
+/// Cached counts by path relative to the crate root.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+struct Cache {
+    files: BTreeMap<PathBuf, FileReferences>,
+}
+
+/// References by name over a set of files.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct References {
+    counts: BTreeMap<String, usize>,
+}
+
+impl References {
+    /// Count the references in `files`. With a crate `root`, the counts of files unchanged
+    /// since they were cached under it are reused, and the cache is updated. Files that do
+    /// not read or parse count nothing; `check` and `prune` report them on their own.
+    pub fn of_files(root: Option<&Path>, files: &[PathBuf]) -> TraitError<Self> {
+        let cache_path = root.map(|root| Journal::dir(root).join(CACHE_FILE));
+        let cached: Cache = cache_path
+            .as_ref()
+            .and_then(|path| std::fs::read(path).ok())
+            .and_then(|bytes| serde_json::from_slice(&bytes).ok())
+            .unwrap_or_default();
+        let mut next = Cache::default();
+        let mut out = Self::default();
+        for path in files {
+            let Ok(text) = std::fs::read_to_string(path) else {
+                continue;
+            };
+            let rel = root
+                .and_then(|root| path.strip_prefix(root).ok())
+                .unwrap_or(path)
+                .to_path_buf();
+            let hash = crc32fast::hash(text.as_bytes());
+            let file = match cached.files.get(&rel) {
+                Some(file) if file.hash == hash => file.clone(),
+                _ => match syn::parse_file(&text) {
+                    Ok(parsed) => FileReferences {
+                        hash,
+                        counts: Self::count_file(&parsed),
+                    },
+                    Err(_) => continue,
+                },
+            };
+            for (name, n) in &file.counts {
+                *out.counts.entry(name.clone()).or_default() += n;
+            }
+            next.files.insert(rel, file);
+        }
+        if let (Some(root), Some(path)) = (root, cache_path)
+            && next != cached
+        {
+            let dir = Journal::dir(root);
+            std::fs::create_dir_all(&dir).map_err(|e| WinnowerError::io("creating", &dir, e))?;
+            let text = serde_json::to_string(&next)
+                .map_err(|e| WinnowerError::Internal(format!("serializing references: {e}")))?;
+            std::fs::write(&path, text).map_err(|e| WinnowerError::io("writing", &path, e))?;
+        }
+        Ok(out)
+    }
+
+    /// The references `file` makes, by name.
+    pub fn count_file(file: &syn::File) -> BTreeMap<String, usize> {
+        let mut counter = Counter::default();
+        counter.visit_file(file);
+        counter.counts
+    }
+
+    /// How many times the workspace refers to the item of `locator`: to its name, or for a
+    /// method or associated type to the name after the last `::`, or for an impl to the
+    /// last segment of its self type, e.g. `Wrapper` for `impl<T> Wrapper<T>` (`&T` counts
+    /// `T`, so blanket impls weigh what their type parameter's name does).
+    pub fn weight(&self, locator: &ItemLocator) -> usize {
+        let name = match locator.kind {
+            ItemKind::Impl => locator
+                .impl_header
+                .as_ref()
+                .and_then(|h| syn::parse_str::<syn::Type>(&h.self_ty).ok())
+                .and_then(|ty| type_name(&ty)),
+            ItemKind::ImplMethod | ItemKind::TraitMethod | ItemKind::AssocType => {
+                locator.name.rsplit("::").next().map(str::to_owned)
+            }
+            _ => Some(locator.name.clone()),
+        };
+        name.and_then(|n| self.counts.get(&n).copied()).unwrap_or(0)
+    }
+
+    /// The weight of the heaviest item with bounds in the file at `path`; 0 when it does
+    /// not read or parse.
+    pub fn file_weight(&self, path: &Path) -> usize {
+        let Ok(text) = std::fs::read_to_string(path) else {
+            return 0;
+        };
+        let Ok(file) = syn::parse_file(&text) else {
+            return 0;
+        };
+        let Ok(items) = ItemBounds::collect_items_in_file(&file) else {
+            return 0;
+        };
+        items
+            .iter_all_items()
+            .map(|key| self.weight(&ItemLocator::from(key)))
+            .max()
+            .unwrap_or(0)
+    }
+
+    /// Set the weight of `finding`.
+    pub fn weigh(&self, finding: &mut Finding) {
+        finding.weight = Some(self.weight(&finding.locator));
+    }
+}
+
+/// The name the last path segment of `ty` has, through references, pointers, slices and
+/// arrays.
+fn type_name(ty: &syn::Type) -> Option<String> {
+    match ty {
+        syn::Type::Path(p) => p.path.segments.last().map(|s| s.ident.to_string()),
+        syn::Type::Reference(r) => type_name(&r.elem),
+        syn::Type::Ptr(p) => type_name(&p.elem),
+        syn::Type::Slice(s) => type_name(&s.elem),
+        syn::Type::Array(a) => type_name(&a.elem),
+        syn::Type::Paren(p) => type_name(&p.elem),
+        syn::Type::Group(g) => type_name(&g.elem),
+        _ => None,
+    }
+}
+
+/// Counts the names paths end in, method calls and macro bodies use.
+#[derive(Default)]
+struct Counter {
+    counts: BTreeMap<String, usize>,
+}
+
+impl Counter {
+    fn bump(&mut self, name: String) {
+        *self.counts.entry(name).or_default() += 1;
+    }
+
+    /// Macro bodies are tokens, not syntax: count every identifier in them.
+    fn count_tokens(&mut self, tokens: TokenStream) {
+        for tt in tokens {
+            match tt {
+                TokenTree::Ident(ident) => self.bump(ident.to_string()),
+                TokenTree::Group(group) => self.count_tokens(group.stream()),
+                TokenTree::Punct(_) | TokenTree::Literal(_) => {}
+            }
+        }
+    }
+}
+
+impl<'ast> Visit<'ast> for Counter {
+    fn visit_path(&mut self, path: &'ast syn::Path) {
+        if let Some(last) = path.segments.last() {
+            self.bump(last.ident.to_string());
+        }
+        syn::visit::visit_path(self, path);
+    }
+
+    fn visit_expr_method_call(&mut self, call: &'ast syn::ExprMethodCall) {
+        self.bump(call.method.to_string());
+        syn::visit::visit_expr_method_call(self, call);
+    }
+
+    fn visit_macro(&mut self, mac: &'ast syn::Macro) {
+        self.count_tokens(mac.tokens.clone());
+        syn::visit::visit_macro(self, mac);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::analysis::ImplHeader;
+
+    fn locator(kind: ItemKind, name: &str) -> ItemLocator {
+        ItemLocator {
+            kind,
+            name: name.to_owned(),
+            ..ItemLocator::default()
+        }
+    }
+
+    #[test]
+    fn items_weigh_the_references_to_their_names() {
+        let file = syn::parse_file(
+            "fn helper<T: Clone>(t: T) {}
+             struct Wrapper<T>(T);
+             impl<T: Clone> Wrapper<T> { fn get(&self) {} }
+             fn main() {
+                 helper(1);
+                 crate::helper(2);
+                 let w: Wrapper<u8> = Wrapper(3);
+                 w.get();
+                 println!(\"{:?}\", helper(4));
+             }",
+        )
+        .unwrap();
+        let refs = References {
+            counts: References::count_file(&file),
+        };
+        assert_eq!(refs.weight(&locator(ItemKind::Fn, "helper")), 3);
+        assert_eq!(
+            refs.weight(&locator(ItemKind::ImplMethod, "Wrapper < T >::get")),
+            1
+        );
+        // The impl's own self type and the two uses in `main`.
+        let imp = ItemLocator {
+            impl_header: Some(ImplHeader {
+                self_ty: "Wrapper < T >".to_owned(),
+                trait_path: None,
+            }),
+            ..locator(ItemKind::Impl, "Wrapper < T >")
+        };
+        assert_eq!(refs.weight(&imp), 3);
+        assert_eq!(refs.weight(&locator(ItemKind::Fn, "unused")), 0);
+    }
+}
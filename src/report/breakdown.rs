@@ -0,0 +1,208 @@
+// src/report/breakdown.rs
+//! Where the removals of a `prune` run came from: removed and retained bounds by package
+//! and by trait.
+//!
+//! The per-trait ratio of removed to retained bounds shows which bounds an API asks for
+//! out of habit (a high ratio) and which it needs (a low one).
+
+#![deny(missing_docs)]
+
+use crate::analysis::trait_path;
+use crate::report::{RemovalStatus, Report};
+use std::collections::BTreeMap;
+
+/// Label of files no package owns (see [`FileReport::package`](crate::report::FileReport::package)).
+pub const NO_PACKAGE: &str = "(no package)";
+
+/// Removed and retained counts of one package or trait.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Tally {
+    /// The package or trait name.
+    pub name: String,
+    /// Bounds removed.
+    pub removed: usize,
+    /// Bounds tried and kept: retained, or reverted by the semver gate.
+    pub retained: usize,
+}
+
+impl Tally {
+    /// The share of tried bounds that were removed, from 0 to 1.
+    pub fn ratio(&self) -> f64 {
+        match self.removed + self.retained {
+            0 => 0.0,
+            tried => self.removed as f64 / tried as f64,
+        }
+    }
+}
+
+/// Removal counts of a run by package and by trait, each sorted by removed count, then
+/// retained count, descending, then by name.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Breakdown {
+    /// By the package owning the file.
+    pub packages: Vec<Tally>,
+    /// By trait name, as [`trait_name`] spells it.
+    pub traits: Vec<Tally>,
+}
+
+impl Breakdown {
+    /// Count the removals of `report`. Skipped bounds were never tried and count nowhere.
+    pub fn of(report: &Report) -> Self {
+        let mut packages: BTreeMap<String, Tally> = BTreeMap::new();
+        let mut traits: BTreeMap<String, Tally> = BTreeMap::new();
+        for file in &report.files {
+            let package = file.package.as_deref().unwrap_or(NO_PACKAGE);
+            for r in &file.removals {
+                let removed = match r.status {
+                    RemovalStatus::Removed => true,
+                    RemovalStatus::Retained | RemovalStatus::Reverted => false,
+                    RemovalStatus::Skipped => continue,
+                };
+                for (tallies, name) in [
+                    (&mut packages, package.to_owned()),
+                    (&mut traits, trait_name(&r.finding.bound)),
+                ] {
+                    let tally = tallies.entry(name.clone()).or_insert_with(|| Tally {
+                        name,
+                        ..Tally::default()
+                    });
+                    if removed {
+                        tally.removed += 1;
+                    } else {
+                        tally.retained += 1;
+                    }
+                }
+            }
+        }
+        Self {
+            packages: sorted(packages),
+            traits: sorted(traits),
+        }
+    }
+
+    /// Whether no bound was tried.
+    pub fn is_empty(&self) -> bool {
+        self.traits.is_empty()
+    }
+}
+
+fn sorted(tallies: BTreeMap<String, Tally>) -> Vec<Tally> {
+    let mut tallies: Vec<Tally> = tallies.into_values().collect();
+    tallies.sort_by(|a, b| {
+        (b.removed, b.retained)
+            .cmp(&(a.removed, a.retained))
+            .then_with(|| a.name.cmp(&b.name))
+    });
+    tallies
+}
+
+/// The trait a bound names, the last segment of its [`trait_path`]: `std::fmt::Debug` is
+/// `Debug`, `Into<String>` is `Into`, `?Sized` is `Sized`. Lifetimes and anything else that
+/// does not parse as a trait bound stay as written.
+pub fn trait_name(bound: &str) -> String {
+    let path = trait_path(bound);
+    match path.rsplit_once("::") {
+        Some((_, name)) => name.to_owned(),
+        None => path,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::report::{FileMetrics, FileReport, Finding, Removal};
+    use std::time::Duration;
+
+    fn removal(bound: &str, status: RemovalStatus) -> Removal {
+        Removal {
+            finding: Finding {
+                bound: bound.into(),
+                ..Finding::default()
+            },
+            status,
+            before: String::new(),
+            after: String::new(),
+            check_time: Duration::ZERO,
+            write_time: Duration::ZERO,
+            scope: Vec::new(),
+            batch: None,
+            aborted: false,
+            cached: false,
+            blames: Vec::new(),
+            test_only: false,
+            slowdown: None,
+            follow_ups: Vec::new(),
+        }
+    }
+
+    fn file(package: Option<&str>, removals: Vec<Removal>) -> FileReport {
+        FileReport {
+            path: "src/lib.rs".into(),
+            findings: Vec::new(),
+            removals,
+            metrics: FileMetrics::default(),
+            package: package.map(str::to_owned),
+        }
+    }
+
+    #[test]
+    fn counts_by_package_and_trait_most_removed_first() {
+        use RemovalStatus::*;
+        let report = Report {
+            files: vec![
+                file(
+                    Some("b"),
+                    vec![
+                        removal("Clone", Removed),
+                        removal("std :: fmt :: Debug", Retained),
+                        removal("Send", Skipped),
+                    ],
+                ),
+                file(
+                    Some("a"),
+                    vec![
+                        removal("Debug", Removed),
+                        removal("Into < String >", Reverted),
+                    ],
+                ),
+                file(None, vec![removal("Clone", Removed)]),
+            ],
+            ..Report::default()
+        };
+        let breakdown = Breakdown::of(&report);
+        let rows = |t: &[Tally]| -> Vec<(String, usize, usize)> {
+            t.iter()
+                .map(|t| (t.name.clone(), t.removed, t.retained))
+                .collect()
+        };
+        assert_eq!(
+            rows(&breakdown.packages),
+            [
+                ("a".to_owned(), 1, 1),
+                ("b".to_owned(), 1, 1),
+                (NO_PACKAGE.to_owned(), 1, 0),
+            ]
+        );
+        assert_eq!(
+            rows(&breakdown.traits),
+            [
+                ("Clone".to_owned(), 2, 0),
+                ("Debug".to_owned(), 1, 1),
+                ("Into".to_owned(), 0, 1),
+            ]
+        );
+        assert_eq!(breakdown.traits[1].ratio(), 0.5);
+        assert!(Breakdown::of(&Report::default()).is_empty());
+    }
+
+    #[test]
+    fn trait_names_drop_paths_and_arguments() {
+        assert_eq!(trait_name("serde :: Serialize"), "Serialize");
+        assert_eq!(trait_name("Fn (u8) -> u8"), "Fn");
+        assert_eq!(trait_name("for < 'a > FnMut (& 'a str)"), "FnMut");
+        assert_eq!(trait_name("Iterator < Item = u32 >"), "Iterator");
+        assert_eq!(trait_name("Iterator < Item = String >"), "Iterator");
+        assert_eq!(trait_name("? Sized"), "Sized");
+        assert_eq!(trait_name("'a"), "'a");
+    }
+}
@@ -0,0 +1,229 @@
+// src/report/diagnostic.rs
+//! Compiler-style rendering of findings.
+
+#![deny(missing_docs)]
+
+use crate::analysis::UTF8_BOM;
+use crate::error::TraitError;
+use crate::report::{FileReport, Finding};
+use colored::Colorize;
+use std::io::Write;
+
+/// Renders findings like rustc warnings: header, location, source line and carets, help.
+pub struct Diagnostics;
+
+impl Diagnostics {
+    /// Write every finding of `file`, using `source` (the file's text) for the snippet.
+    pub fn write_file<W: Write>(file: &FileReport, source: &str, out: &mut W) -> TraitError<()> {
+        let source = source.strip_prefix(UTF8_BOM).unwrap_or(source);
+        let lines: Vec<&str> = source.lines().collect();
+        for f in &file.findings {
+            Self::write_finding(file, f, &lines, out)?;
+        }
+        Ok(())
+    }
+
+    fn write_finding<W: Write>(
+        file: &FileReport,
+        f: &Finding,
+        lines: &[&str],
+        out: &mut W,
+    ) -> TraitError<()> {
+        let path = file.path.to_string_lossy().replace('\\', "/");
+        let headline = match (&f.caution, &f.rule) {
+            (Some(caution), _) => format!(": {caution}"),
+            (None, Some(rule)) => format!(": {}", rule.message),
+            (None, None) => format!(": possibly unnecessary trait bound `{}`", f.bound),
+        };
+        writeln!(out, "{}{}", "warning".yellow().bold(), headline.bold())?;
+
+        // Spans are unavailable (line 0) or point past the text: location only.
+        let Some(text) = f.line.checked_sub(1).and_then(|i| lines.get(i)) else {
+            writeln!(out, "  {} {}", "-->".blue().bold(), path)?;
+            Self::write_help(f, "", out)?;
+            writeln!(out)?;
+            return Ok(());
+        };
+
+        let gutter = " ".repeat(f.line.to_string().len());
+        writeln!(
+            out,
+            "{gutter}{} {}:{}:{}",
+            "-->".blue().bold(),
+            path,
+            f.line,
+            f.column
+        )?;
+        writeln!(out, "{gutter} {}", "|".blue().bold())?;
+        writeln!(
+            out,
+            "{} {} {}",
+            f.line.to_string().blue().bold(),
+            "|".blue().bold(),
+            text
+        )?;
+        writeln!(
+            out,
+            "{gutter} {} {}",
+            "|".blue().bold(),
+            Self::carets(text, f).yellow().bold()
+        )?;
+        Self::write_help(f, &gutter, out)?;
+        writeln!(out)?;
+        Ok(())
+    }
+
+    fn write_help<W: Write>(f: &Finding, gutter: &str, out: &mut W) -> TraitError<()> {
+        if f.modified_bound {
+            writeln!(
+                out,
+                "{gutter} {} {}: `prune` never tries modified bounds",
+                "=".blue().bold(),
+                "note".bold()
+            )?;
+            return Ok(());
+        }
+        if f.unsafe_impl {
+            writeln!(
+                out,
+                "{gutter} {} {}: `prune` keeps the bounds of an `unsafe impl` unless run with \
+                 `--allow-unsafe-impl-pruning`",
+                "=".blue().bold(),
+                "note".bold()
+            )?;
+            return Ok(());
+        }
+        if f.caution.is_some() {
+            writeln!(
+                out,
+                "{gutter} {} {}: `prune` keeps this bound unless run with \
+                 `--allow-dyn-compat-changes`",
+                "=".blue().bold(),
+                "note".bold()
+            )?;
+            return Ok(());
+        }
+        writeln!(
+            out,
+            "{gutter} {} {}: try removing `{}` from the bounds on `{}` in `{}`",
+            "=".blue().bold(),
+            "help".bold(),
+            f.bound,
+            f.bounded,
+            f.item
+        )?;
+        if let Some(rule) = &f.rule {
+            writeln!(
+                out,
+                "{gutter} {} {}: `{}` rule, {} confidence",
+                "=".blue().bold(),
+                "note".bold(),
+                rule.rule,
+                rule.confidence
+            )?;
+        }
+        if let Some(weight) = f.weight {
+            writeln!(
+                out,
+                "{gutter} {} {}: the workspace refers to `{}` {weight} time(s)",
+                "=".blue().bold(),
+                "note".bold(),
+                f.item
+            )?;
+        }
+        if !f.derives.is_empty() {
+            writeln!(
+                out,
+                "{gutter} {} {}: `{}` derives {}; derived impls copy its bounds, so check \
+                 that code using them still builds",
+                "=".blue().bold(),
+                "note".bold(),
+                f.item,
+                f.derives.join(", ")
+            )?;
+        }
+        if let Some(default) = &f.param_default {
+            writeln!(
+                out,
+                "{gutter} {} {}: `{}` defaults to `{default}` in public `{}`; once loosened, \
+                 its bounds cannot be tightened again without breaking callers",
+                "=".blue().bold(),
+                "note".bold(),
+                f.bounded,
+                f.item
+            )?;
+        }
+        if let Some(tr) = &f.foreign_trait {
+            writeln!(
+                out,
+                "{gutter} {} {}: `{}` implements `{tr}`, which the workspace does not define; \
+                 loosening its bounds can change which of the trait's impls apply elsewhere",
+                "=".blue().bold(),
+                "note".bold(),
+                f.item
+            )?;
+        }
+        if !f.duplicates.is_empty() {
+            let also: Vec<String> = f.duplicates.iter().map(|d| format!("`{d}`")).collect();
+            writeln!(
+                out,
+                "{gutter} {} {}: also declared as {}; `prune` removes or keeps them together",
+                "=".blue().bold(),
+                "note".bold(),
+                also.join(" and ")
+            )?;
+        }
+        if !f.packages.is_empty() {
+            writeln!(
+                out,
+                "{gutter} {} {}: affects packages: {}",
+                "=".blue().bold(),
+                "note".bold(),
+                f.packages.join(", ")
+            )?;
+        }
+        if !f.cfg.is_empty() {
+            let scope = if f.not_validated {
+                "which validation never enables; `prune` leaves it alone"
+            } else {
+                "so removals are only validated where that holds"
+            };
+            let cfg: Vec<String> = f.cfg.iter().map(|c| format!("`cfg({c})`")).collect();
+            writeln!(
+                out,
+                "{gutter} {} {}: `{}` is only built with {}, {scope}",
+                "=".blue().bold(),
+                "note".bold(),
+                f.item,
+                cfg.join(" and ")
+            )?;
+        }
+        if let Some(hint) = f.validation_hint() {
+            writeln!(
+                out,
+                "{gutter} {} {}: {hint}",
+                "=".blue().bold(),
+                "help".bold()
+            )?;
+        }
+        Ok(())
+    }
+
+    /// Padding (keeping tabs so carets line up) followed by `^` under the bound. Multi-line
+    /// bounds are underlined to the end of their first line.
+    fn carets(text: &str, f: &Finding) -> String {
+        let start = f.column.saturating_sub(1);
+        let line_len = text.chars().count();
+        let end = if f.end_line == f.line {
+            f.end_column.saturating_sub(1).min(line_len)
+        } else {
+            line_len
+        };
+        let pad: String = text
+            .chars()
+            .take(start)
+            .map(|c| if c == '\t' { '\t' } else { ' ' })
+            .collect();
+        format!("{pad}{}", "^".repeat(end.saturating_sub(start).max(1)))
+    }
+}
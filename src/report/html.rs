@@ -0,0 +1,368 @@
+// src/report/html.rs
+//! Self-contained HTML rendering of reports.
+
+#![deny(missing_docs)]
+
+use crate::config::Config;
+use crate::error::TraitError;
+use crate::report::breakdown::{Breakdown, Tally};
+use crate::report::{Finding, RemovalStatus, Report, xml_escape};
+use std::io::Write;
+use std::path::Path;
+
+/// Run context shown in the HTML summary header.
+pub struct HtmlSummary<'a> {
+    /// The subcommand that produced the report (`check` or `prune`).
+    pub command: &'a str,
+    /// The analyzed target.
+    pub target: &'a Path,
+    /// Configuration in effect, if a crate or workspace was analyzed.
+    pub config: Option<&'a Config>,
+}
+
+/// HTML emitter: a summary header, a sortable table of findings and removals, and
+/// collapsible before/after snippets. CSS and JS are inlined.
+pub struct Html;
+
+/// How a row of the results table shows its finding.
+struct Row<'a> {
+    class: &'a str,
+    outcome: &'a str,
+    confidence: &'a str,
+    /// The table has a weight column.
+    weighted: bool,
+}
+
+const STYLE: &str = r#"body{font-family:sans-serif;margin:2em;color:#222}
+table{border-collapse:collapse;width:100%}
+th,td{border:1px solid #ccc;padding:4px 8px;text-align:left;vertical-align:top}
+th{background:#eee;cursor:pointer;user-select:none}
+code,pre{font-family:monospace}
+.finding{color:#0550ae}.removed{color:#1a7f37}.retained{color:#9a6700}.skipped{color:#666}
+dl{display:grid;grid-template-columns:max-content auto;gap:2px 12px}"#;
+
+const SCRIPT: &str = r#"document.querySelectorAll('th').forEach(function(th){
+th.addEventListener('click',function(){
+var table=th.closest('table'),body=table.tBodies[0],idx=th.cellIndex;
+var asc=th.dataset.dir!=='asc';th.dataset.dir=asc?'asc':'desc';
+var rows=Array.prototype.slice.call(body.rows);
+rows.sort(function(a,b){
+var x=a.cells[idx].dataset.key||a.cells[idx].textContent,y=b.cells[idx].dataset.key||b.cells[idx].textContent;
+var n=parseFloat(x)-parseFloat(y);var c=isNaN(n)?x.localeCompare(y):n;return asc?c:-c;});
+rows.forEach(function(r){body.appendChild(r);});});});"#;
+
+impl Html {
+    /// Write `report` as a single HTML document.
+    pub fn write<W: Write>(
+        report: &Report,
+        summary: &HtmlSummary<'_>,
+        out: &mut W,
+    ) -> TraitError<()> {
+        writeln!(out, "<!DOCTYPE html>")?;
+        writeln!(out, "<html lang=\"en\">")?;
+        writeln!(out, "<head>")?;
+        writeln!(out, "<meta charset=\"utf-8\">")?;
+        writeln!(out, "<title>trait-winnower {}</title>", summary.command)?;
+        writeln!(out, "<style>\n{STYLE}\n</style>")?;
+        writeln!(out, "</head>")?;
+        writeln!(out, "<body>")?;
+        Self::write_summary(report, summary, out)?;
+        Self::write_table(report, out)?;
+        Self::write_breakdown(report, out)?;
+        Self::write_snippets(report, out)?;
+        Self::write_follow_ups(report, out)?;
+        writeln!(out, "<script>\n{SCRIPT}\n</script>")?;
+        writeln!(out, "</body>")?;
+        writeln!(out, "</html>")?;
+        Ok(())
+    }
+
+    fn write_summary<W: Write>(
+        report: &Report,
+        summary: &HtmlSummary<'_>,
+        out: &mut W,
+    ) -> TraitError<()> {
+        writeln!(out, "<h1>trait-winnower {}</h1>", summary.command)?;
+        writeln!(out, "<dl>")?;
+        Self::row(out, "Target", &summary.target.display().to_string())?;
+        Self::row(out, "Files", &report.files.len().to_string())?;
+        Self::row(out, "Findings", &report.total_findings().to_string())?;
+        for status in [
+            RemovalStatus::Removed,
+            RemovalStatus::Retained,
+            RemovalStatus::Skipped,
+        ] {
+            let label = format!("Bounds {status}");
+            Self::row(out, &label, &report.count_removals(status).to_string())?;
+        }
+        let reverted = report.count_removals(RemovalStatus::Reverted);
+        if reverted > 0 {
+            Self::row(out, "Bounds reverted", &reverted.to_string())?;
+        }
+        if let Some(slow) = &report.slow_removals {
+            let value = format!(
+                "{} (checks over {}× the baseline of {} ms)",
+                slow.removals.len(),
+                slow.factor,
+                slow.baseline.as_millis()
+            );
+            Self::row(out, "Slow removals", &value)?;
+        }
+        if !report.tidied_imports.is_empty() {
+            let removed = report.tidied_imports.iter().filter(|t| t.removed).count();
+            let kept = report.tidied_imports.len() - removed;
+            let value = if kept > 0 {
+                format!("{removed} ({kept} kept: the check failed without them)")
+            } else {
+                removed.to_string()
+            };
+            Self::row(out, "Unused imports removed", &value)?;
+        }
+        if !report.derive_candidates.is_empty() {
+            let enabled = report
+                .derive_candidates
+                .iter()
+                .filter(|d| d.enabled_by_prune)
+                .count();
+            let value = format!(
+                "{} ({enabled} enabled by this run)",
+                report.derive_candidates.len()
+            );
+            Self::row(out, "Derive candidates", &value)?;
+        }
+        if let Some(cfg) = summary.config {
+            Self::row(out, "Include", &cfg.include.join(", "))?;
+            Self::row(out, "Exclude", &cfg.exclude.join(", "))?;
+            Self::row(out, "cargo check", &cfg.cargo_check.args.join(" "))?;
+        }
+        if let Some(v) = &report.validation
+            && !v.gaps.is_empty()
+        {
+            let gaps: Vec<String> = v.gaps.iter().map(ToString::to_string).collect();
+            Self::row(out, "Not validated", &gaps.join("; "))?;
+        }
+        if let Some(v) = &report.validation
+            && !v.matrix.is_empty()
+        {
+            let entries: Vec<String> = v.matrix.iter().map(ToString::to_string).collect();
+            let label = format!("Validation matrix ({})", v.matrix_mode.id());
+            Self::row(out, &label, &entries.join("; "))?;
+        }
+        if let Some(toolchain) = report
+            .validation
+            .as_ref()
+            .and_then(|v| v.toolchain.as_ref())
+        {
+            writeln!(
+                out,
+                "<dt>Toolchain</dt><dd><span class=\"volatile\">{}</span></dd>",
+                xml_escape(&toolchain.to_string())
+            )?;
+        }
+        if let Some(dir) = report
+            .validation
+            .as_ref()
+            .and_then(|v| v.target_dir.as_ref())
+        {
+            writeln!(
+                out,
+                "<dt>Check directory</dt><dd><span class=\"volatile\">{}</span></dd>",
+                xml_escape(&dir.to_string())
+            )?;
+        }
+        writeln!(
+            out,
+            "<dt>Duration</dt><dd><span class=\"volatile\">{:.3}s</span></dd>",
+            report.wall_time.as_secs_f64()
+        )?;
+        writeln!(out, "</dl>")?;
+        Ok(())
+    }
+
+    fn write_table<W: Write>(report: &Report, out: &mut W) -> TraitError<()> {
+        // Weights are only there when asked for; reports without them keep their columns.
+        let weighted = report.files.iter().any(|f| {
+            let removals = f.removals.iter().map(|r| &r.finding);
+            f.findings
+                .iter()
+                .chain(removals)
+                .any(|f| f.weight.is_some())
+        });
+        writeln!(out, "<table id=\"results\">")?;
+        writeln!(
+            out,
+            "<thead><tr><th>File</th><th>Line</th><th>Item</th><th>Bound</th><th>Outcome</th><th>Confidence</th>{}</tr></thead>",
+            if weighted { "<th>Weight</th>" } else { "" }
+        )?;
+        writeln!(out, "<tbody>")?;
+        let slow_reverted = report.slow_removals.as_ref().is_some_and(|s| s.reverted);
+        for file in &report.files {
+            let path = file.path.to_string_lossy().replace('\\', "/");
+            for f in &file.findings {
+                let row = Row {
+                    class: "finding",
+                    outcome: "possibly unnecessary",
+                    confidence: "static",
+                    weighted,
+                };
+                Self::table_row(out, &path, f, &row)?;
+            }
+            for r in &file.removals {
+                let confidence = match r.status {
+                    RemovalStatus::Skipped => "none",
+                    RemovalStatus::Reverted if r.slowdown.is_some() && slow_reverted => {
+                        "check time"
+                    }
+                    RemovalStatus::Reverted => "cargo semver-checks",
+                    _ => "cargo check",
+                };
+                let status = r.status.to_string();
+                let mut tags = r.finding.tags();
+                if r.slowdown.is_some() {
+                    tags.push("suspicious: may regress compile time");
+                }
+                let outcome = if tags.is_empty() {
+                    status.clone()
+                } else {
+                    format!("{status} ({})", tags.join(", "))
+                };
+                let row = Row {
+                    class: &status,
+                    outcome: &outcome,
+                    confidence,
+                    weighted,
+                };
+                Self::table_row(out, &path, &r.finding, &row)?;
+            }
+        }
+        writeln!(out, "</tbody>")?;
+        writeln!(out, "</table>")?;
+        Ok(())
+    }
+
+    fn write_breakdown<W: Write>(report: &Report, out: &mut W) -> TraitError<()> {
+        let breakdown = Breakdown::of(report);
+        if breakdown.is_empty() {
+            return Ok(());
+        }
+        writeln!(out, "<h2>Removals by package and trait</h2>")?;
+        for (column, tallies) in [
+            ("Package", &breakdown.packages),
+            ("Trait", &breakdown.traits),
+        ] {
+            Self::tally_table(out, column, tallies)?;
+        }
+        Ok(())
+    }
+
+    fn tally_table<W: Write>(out: &mut W, column: &str, tallies: &[Tally]) -> TraitError<()> {
+        writeln!(out, "<table class=\"breakdown\">")?;
+        writeln!(
+            out,
+            "<thead><tr><th>{column}</th><th>Removed</th><th>Retained</th><th>Removed %</th></tr></thead>"
+        )?;
+        writeln!(out, "<tbody>")?;
+        for t in tallies {
+            writeln!(
+                out,
+                "<tr><td><code>{}</code></td><td>{}</td><td>{}</td><td>{:.0}</td></tr>",
+                xml_escape(&t.name),
+                t.removed,
+                t.retained,
+                t.ratio() * 100.0
+            )?;
+        }
+        writeln!(out, "</tbody>")?;
+        writeln!(out, "</table>")?;
+        Ok(())
+    }
+
+    fn write_snippets<W: Write>(report: &Report, out: &mut W) -> TraitError<()> {
+        let removed = report
+            .files
+            .iter()
+            .flat_map(|f| f.removals.iter().map(move |r| (f, r)))
+            .filter(|(_, r)| r.status == RemovalStatus::Removed);
+        let mut any = false;
+        for (file, r) in removed {
+            if !any {
+                writeln!(out, "<h2>Removals</h2>")?;
+                any = true;
+            }
+            let f = &r.finding;
+            writeln!(out, "<details>")?;
+            writeln!(
+                out,
+                "<summary><code>{}</code>: {} &minus; <code>{}</code> ({}:{})</summary>",
+                xml_escape(&f.item),
+                xml_escape(&f.bounded),
+                xml_escape(&f.bound),
+                xml_escape(&file.path.to_string_lossy()),
+                f.line
+            )?;
+            writeln!(
+                out,
+                "<pre>- {}\n+ {}</pre>",
+                xml_escape(&r.before),
+                xml_escape(&r.after)
+            )?;
+            writeln!(out, "</details>")?;
+        }
+        Ok(())
+    }
+
+    fn write_follow_ups<W: Write>(report: &Report, out: &mut W) -> TraitError<()> {
+        let follow_ups = report.follow_ups();
+        if follow_ups.is_empty() {
+            return Ok(());
+        }
+        writeln!(out, "<h2>Follow-up actions</h2>")?;
+        writeln!(out, "<ul>")?;
+        for (u, r) in follow_ups {
+            let f = &r.finding;
+            writeln!(
+                out,
+                "<li>{}:{}:{}: {} (<code>{}</code>), left by removing <code>{}: {}</code> from <code>{}</code></li>",
+                xml_escape(&u.file.to_string_lossy()),
+                u.line,
+                u.column,
+                xml_escape(&u.message),
+                xml_escape(&u.lint),
+                xml_escape(&f.bounded),
+                xml_escape(&f.bound),
+                xml_escape(&f.item)
+            )?;
+        }
+        writeln!(out, "</ul>")?;
+        Ok(())
+    }
+
+    fn table_row<W: Write>(out: &mut W, path: &str, f: &Finding, row: &Row<'_>) -> TraitError<()> {
+        let weight = match (row.weighted, f.weight) {
+            (false, _) => String::new(),
+            (true, Some(w)) => format!("<td>{w}</td>"),
+            (true, None) => "<td></td>".to_owned(),
+        };
+        writeln!(
+            out,
+            "<tr><td>{}</td><td data-key=\"{}\">{}:{}</td><td><code>{}</code></td><td><code>{}: {}</code></td><td class=\"{}\">{}</td><td>{}</td>{weight}</tr>",
+            xml_escape(path),
+            f.line,
+            f.line,
+            f.column,
+            xml_escape(&f.item),
+            xml_escape(&f.bounded),
+            xml_escape(&f.bound),
+            row.class,
+            row.outcome,
+            row.confidence
+        )?;
+        Ok(())
+    }
+
+    #[inline]
+    fn row<W: Write>(out: &mut W, key: &str, value: &str) -> TraitError<()> {
+        writeln!(out, "<dt>{}</dt><dd>{}</dd>", key, xml_escape(value))?;
+        Ok(())
+    }
+}
@@ -0,0 +1,626 @@
+// src/report/json.rs
+//! JSON rendering of reports.
+
+#![deny(missing_docs)]
+
+use crate::analysis::ImplHeader;
+use crate::dynamic_analysis::edit::AnchorMiss;
+use crate::error::TraitError;
+use crate::formats::REPORT_VERSION;
+use crate::impact::{Comparison, Timing};
+use crate::report::{FileMetrics, FileReport, Finding, Removal, Report};
+use std::io::Write;
+use std::time::Duration;
+
+/// A JSON value, built in memory and pretty-printed.
+#[derive(Debug, Clone, PartialEq)]
+pub enum Value {
+    /// `null`.
+    Null,
+    /// `true` or `false`.
+    Bool(bool),
+    /// An unsigned integer.
+    Int(u64),
+    /// A floating point number.
+    Float(f64),
+    /// A string.
+    Str(String),
+    /// An array.
+    Array(Vec<Value>),
+    /// An object; keys keep insertion order.
+    Object(Vec<(String, Value)>),
+}
+
+impl Value {
+    /// Build an object from `(key, value)` pairs.
+    pub fn object<const N: usize>(fields: [(&str, Value); N]) -> Self {
+        Value::Object(fields.into_iter().map(|(k, v)| (k.to_owned(), v)).collect())
+    }
+
+    /// Pretty-print with two-space indentation.
+    pub fn write<W: Write>(&self, out: &mut W) -> TraitError<()> {
+        self.write_indented(out, 0)?;
+        writeln!(out)?;
+        Ok(())
+    }
+
+    /// Write on a single line, then a newline, as JSON Lines readers expect.
+    pub fn write_line<W: Write>(&self, out: &mut W) -> TraitError<()> {
+        self.write_compact(out)?;
+        writeln!(out)?;
+        Ok(())
+    }
+
+    fn write_compact<W: Write>(&self, out: &mut W) -> TraitError<()> {
+        match self {
+            Value::Array(items) => {
+                write!(out, "[")?;
+                for (i, v) in items.iter().enumerate() {
+                    if i > 0 {
+                        write!(out, ",")?;
+                    }
+                    v.write_compact(out)?;
+                }
+                write!(out, "]")?;
+            }
+            Value::Object(fields) => {
+                write!(out, "{{")?;
+                for (i, (k, v)) in fields.iter().enumerate() {
+                    if i > 0 {
+                        write!(out, ",")?;
+                    }
+                    write!(out, "{}:", escape(k))?;
+                    v.write_compact(out)?;
+                }
+                write!(out, "}}")?;
+            }
+            scalar => scalar.write_indented(out, 0)?,
+        }
+        Ok(())
+    }
+
+    fn write_indented<W: Write>(&self, out: &mut W, depth: usize) -> TraitError<()> {
+        let pad = "  ".repeat(depth + 1);
+        let close = "  ".repeat(depth);
+        match self {
+            Value::Null => write!(out, "null")?,
+            Value::Bool(b) => write!(out, "{b}")?,
+            Value::Int(n) => write!(out, "{n}")?,
+            Value::Float(f) if f.is_finite() => write!(out, "{f}")?,
+            Value::Float(_) => write!(out, "null")?,
+            Value::Str(s) => write!(out, "{}", escape(s))?,
+            Value::Array(items) if items.is_empty() => write!(out, "[]")?,
+            Value::Array(items) => {
+                writeln!(out, "[")?;
+                for (i, v) in items.iter().enumerate() {
+                    write!(out, "{pad}")?;
+                    v.write_indented(out, depth + 1)?;
+                    writeln!(out, "{}", if i + 1 < items.len() { "," } else { "" })?;
+                }
+                write!(out, "{close}]")?;
+            }
+            Value::Object(fields) if fields.is_empty() => write!(out, "{{}}")?,
+            Value::Object(fields) => {
+                writeln!(out, "{{")?;
+                for (i, (k, v)) in fields.iter().enumerate() {
+                    write!(out, "{pad}{}: ", escape(k))?;
+                    v.write_indented(out, depth + 1)?;
+                    writeln!(out, "{}", if i + 1 < fields.len() { "," } else { "" })?;
+                }
+                write!(out, "{close}}}")?;
+            }
+        }
+        Ok(())
+    }
+}
+
+impl From<&str> for Value {
+    fn from(s: &str) -> Self {
+        Value::Str(s.to_owned())
+    }
+}
+
+impl From<String> for Value {
+    fn from(s: String) -> Self {
+        Value::Str(s)
+    }
+}
+
+impl From<u32> for Value {
+    fn from(n: u32) -> Self {
+        Value::Int(n.into())
+    }
+}
+
+impl From<usize> for Value {
+    fn from(n: usize) -> Self {
+        Value::Int(n as u64)
+    }
+}
+
+impl From<Duration> for Value {
+    /// Durations are rendered as milliseconds with microsecond precision.
+    fn from(d: Duration) -> Self {
+        Value::Float((d.as_secs_f64() * 1_000_000.0).round() / 1000.0)
+    }
+}
+
+/// Quote and escape a string as a JSON string literal.
+pub fn escape(s: &str) -> String {
+    let mut out = String::with_capacity(s.len() + 2);
+    out.push('"');
+    for c in s.chars() {
+        match c {
+            '"' => out.push_str("\\\""),
+            '\\' => out.push_str("\\\\"),
+            '\n' => out.push_str("\\n"),
+            '\r' => out.push_str("\\r"),
+            '\t' => out.push_str("\\t"),
+            c if (c as u32) < 0x20 => out.push_str(&format!("\\u{:04x}", c as u32)),
+            c => out.push(c),
+        }
+    }
+    out.push('"');
+    out
+}
+
+/// JSON emitter for reports.
+pub struct Json;
+
+impl Json {
+    /// Write `report` produced by `command` as a JSON document; it reads back as a
+    /// [`ReportDoc`](crate::formats::ReportDoc).
+    pub fn write<W: Write>(report: &Report, command: &str, out: &mut W) -> TraitError<()> {
+        Self::to_value(report, command).write(out)
+    }
+
+    /// Build the JSON value for `report`.
+    pub fn to_value(report: &Report, command: &str) -> Value {
+        let mut value = Value::object([
+            ("schema_version", REPORT_VERSION.into()),
+            ("command", command.into()),
+            (
+                "metrics",
+                Value::object([
+                    ("wall_ms", report.wall_time.into()),
+                    ("files", report.files.len().into()),
+                    ("findings", report.total_findings().into()),
+                    ("checks", report.total_checks().into()),
+                    ("prefiltered", report.total_prefiltered().into()),
+                    ("memo_hits", report.total_memo_hits().into()),
+                    ("aborted", report.total_aborted().into()),
+                    ("abort_saved_ms", report.estimated_abort_savings().into()),
+                    ("trial_cache_hits", report.total_trial_cache_hits().into()),
+                    ("check_ms", report.total_check_time().into()),
+                ]),
+            ),
+            (
+                "files",
+                Value::Array(report.files.iter().map(Self::file).collect()),
+            ),
+        ]);
+        if let (Some(v), Value::Object(fields)) = (&report.validation, &mut value) {
+            let mut validation = vec![
+                (
+                    "args".to_owned(),
+                    Value::Array(v.args.iter().map(|a| a.as_str().into()).collect()),
+                ),
+                (
+                    "gaps".to_owned(),
+                    Value::Array(v.gaps.iter().map(|g| g.id().into()).collect()),
+                ),
+            ];
+            if !v.matrix.is_empty() {
+                validation.push((
+                    "matrix".to_owned(),
+                    Value::Array(v.matrix.iter().map(|e| e.to_string().into()).collect()),
+                ));
+                validation.push(("matrix_mode".to_owned(), v.matrix_mode.id().into()));
+            }
+            if let Some(t) = &v.toolchain {
+                let mut toolchain = vec![("cargo".to_owned(), t.cargo.as_str().into())];
+                if let Some(rustc) = &t.rustc {
+                    toolchain.push(("rustc".to_owned(), rustc.as_str().into()));
+                }
+                validation.push(("toolchain".to_owned(), Value::Object(toolchain)));
+            }
+            if let Some(dir) = &v.target_dir {
+                validation.push((
+                    "target_dir".to_owned(),
+                    Value::object([
+                        ("path", dir.path.to_string_lossy().replace('\\', "/").into()),
+                        ("bytes", Value::Int(dir.bytes)),
+                        ("kept", Value::Bool(dir.kept)),
+                    ]),
+                ));
+            }
+            fields.push(("validation".into(), Value::Object(validation)));
+        }
+        if let (Some(schedule), Value::Object(fields)) = (&report.schedule, &mut value) {
+            let files = schedule.files.iter().map(|f| {
+                let mut file = vec![(
+                    "path".to_owned(),
+                    f.path.to_string_lossy().replace('\\', "/").into(),
+                )];
+                if let Some(confidence) = f.confidence {
+                    file.push(("confidence".to_owned(), confidence.to_string().into()));
+                }
+                if let Some(cost) = f.cost {
+                    file.push(("cost".to_owned(), Value::Int(cost)));
+                }
+                Value::Object(file)
+            });
+            fields.push((
+                "schedule".into(),
+                Value::object([
+                    ("order", schedule.order.id().into()),
+                    ("files", Value::Array(files.collect())),
+                ]),
+            ));
+        }
+        if let (Some(impact), Value::Object(fields)) = (&report.impact, &mut value) {
+            let mut object = vec![
+                ("iterations".to_owned(), impact.iterations.into()),
+                ("check".to_owned(), Self::comparison(&impact.check)),
+            ];
+            if let Some(build) = &impact.build {
+                object.push(("build".to_owned(), Self::comparison(build)));
+            }
+            fields.push(("impact".into(), Value::Object(object)));
+        }
+        if !report.unlocks.is_empty()
+            && let Value::Object(fields) = &mut value
+        {
+            let unlocks = report.unlocks.iter().map(|chain| {
+                let mut unlock = Self::located(&chain.finding);
+                if let Value::Object(fields) = &mut unlock {
+                    let by = chain.unlocked_by.iter().map(Self::located).collect();
+                    fields.push(("unlocked_by".into(), Value::Array(by)));
+                }
+                unlock
+            });
+            fields.push(("unlocks".into(), Value::Array(unlocks.collect())));
+        }
+        if let (Some(slow), Value::Object(fields)) = (&report.slow_removals, &mut value) {
+            let removals = slow.removals.iter().map(|r| {
+                let mut removal = Self::located(&r.finding);
+                if let Value::Object(fields) = &mut removal {
+                    fields.push(("check_ms".into(), r.check_time.into()));
+                    fields.push(("slowdown".into(), Value::Float(r.slowdown)));
+                }
+                removal
+            });
+            fields.push((
+                "slow_removals".into(),
+                Value::object([
+                    ("baseline_ms", slow.baseline.into()),
+                    ("factor", Value::Float(slow.factor)),
+                    ("reverted", Value::Bool(slow.reverted)),
+                    ("removals", Value::Array(removals.collect())),
+                ]),
+            ));
+        }
+        let follow_ups = report.follow_ups();
+        if !follow_ups.is_empty()
+            && let Value::Object(fields) = &mut value
+        {
+            let follow_ups = follow_ups.into_iter().map(|(u, r)| {
+                Value::object([
+                    ("path", u.file.to_string_lossy().replace('\\', "/").into()),
+                    ("line", u.line.into()),
+                    ("column", u.column.into()),
+                    ("lint", u.lint.as_str().into()),
+                    ("message", u.message.as_str().into()),
+                    ("removal", Self::located(&r.finding)),
+                ])
+            });
+            fields.push(("follow_ups".into(), Value::Array(follow_ups.collect())));
+        }
+        if !report.tidied_imports.is_empty()
+            && let Value::Object(fields) = &mut value
+        {
+            let tidied = report.tidied_imports.iter().map(|t| {
+                Value::object([
+                    ("path", t.file.to_string_lossy().replace('\\', "/").into()),
+                    ("line", t.line.into()),
+                    ("import", t.import.as_str().into()),
+                    ("removed", Value::Bool(t.removed)),
+                ])
+            });
+            fields.push(("tidied_imports".into(), Value::Array(tidied.collect())));
+        }
+        if !report.derive_candidates.is_empty()
+            && let Value::Object(fields) = &mut value
+        {
+            let candidates = report.derive_candidates.iter().map(|d| {
+                Value::object([
+                    ("path", d.file.to_string_lossy().replace('\\', "/").into()),
+                    ("line", d.line.into()),
+                    ("derive", d.derive.as_str().into()),
+                    ("type", d.ty.as_str().into()),
+                    ("enabled_by_prune", Value::Bool(d.enabled_by_prune)),
+                ])
+            });
+            fields.push((
+                "derive_candidates".into(),
+                Value::Array(candidates.collect()),
+            ));
+        }
+        if let (Some(strategy), Value::Object(fields)) = (&report.strategy, &mut value) {
+            fields.push((
+                "strategy".into(),
+                Value::object([
+                    ("name", strategy.name.id().into()),
+                    ("trials", Value::Bool(strategy.trials)),
+                    ("order", strategy.order.id().into()),
+                    ("batch_size", strategy.batch_size.into()),
+                    ("prefilter", Value::Bool(strategy.prefilter)),
+                    ("assume_consistent", Value::Bool(strategy.assume_consistent)),
+                    ("unlocks", Value::Bool(strategy.unlocks)),
+                ]),
+            ));
+        }
+        if let (Some(fp), Value::Object(fields)) = (&report.fingerprint, &mut value) {
+            let mut fingerprint = Vec::new();
+            if let Some(lockfile) = &fp.lockfile {
+                fingerprint.push(("lockfile".to_owned(), lockfile.as_str().into()));
+            }
+            if let Some(rustc) = &fp.rustc {
+                fingerprint.push(("rustc".to_owned(), rustc.as_str().into()));
+            }
+            fields.push(("fingerprint".into(), Value::Object(fingerprint)));
+        }
+        value
+    }
+
+    /// A finding with the path of its file, for lists across files.
+    fn located(f: &Finding) -> Value {
+        Value::object([
+            (
+                "path",
+                f.locator.file.to_string_lossy().replace('\\', "/").into(),
+            ),
+            ("finding", Self::finding(f)),
+        ])
+    }
+
+    fn comparison(c: &Comparison) -> Value {
+        Value::object([
+            ("before", Self::timing(&c.before)),
+            ("after", Self::timing(&c.after)),
+            ("delta", Value::Float(c.delta())),
+        ])
+    }
+
+    fn timing(t: &Timing) -> Value {
+        let mut value = vec![
+            (
+                "runs_ms".to_owned(),
+                Value::Array(t.runs.iter().map(|&d| d.into()).collect()),
+            ),
+            ("median_ms".to_owned(), t.median().into()),
+        ];
+        if !t.crates.is_empty() {
+            value.push((
+                "crates_ms".to_owned(),
+                Value::Object(
+                    t.crates
+                        .iter()
+                        .map(|(name, &d)| (name.clone(), d.into()))
+                        .collect(),
+                ),
+            ));
+        }
+        Value::Object(value)
+    }
+
+    fn file(file: &FileReport) -> Value {
+        Value::object([
+            (
+                "path",
+                file.path.to_string_lossy().replace('\\', "/").into(),
+            ),
+            ("metrics", Self::metrics(&file.metrics)),
+            (
+                "findings",
+                Value::Array(file.findings.iter().map(Self::finding).collect()),
+            ),
+            (
+                "removals",
+                Value::Array(file.removals.iter().map(Self::removal).collect()),
+            ),
+        ])
+    }
+
+    fn metrics(m: &FileMetrics) -> Value {
+        let mut value = Value::object([
+            ("parse_ms", m.parse.into()),
+            ("items", m.items.into()),
+            ("candidates", m.candidates.into()),
+            ("checks", m.checks.into()),
+            ("prefiltered", m.prefiltered.into()),
+            ("memo_hits", m.memo_hits.into()),
+            ("aborted", m.aborted.into()),
+            ("aborted_ms", m.aborted_time.into()),
+            ("trial_cache_hits", m.trial_cache_hits.into()),
+            ("check_ms", m.check_time.into()),
+            ("write_ms", m.write_time.into()),
+            ("total_ms", m.total.into()),
+        ]);
+        if let (true, Value::Object(fields)) = (m.truncated > 0, &mut value) {
+            fields.push(("truncated".into(), m.truncated.into()));
+        }
+        if let (true, Value::Object(fields)) =
+            (m.anchors_recovered + m.anchor_misses > 0, &mut value)
+        {
+            fields.push(("anchors_recovered".into(), m.anchors_recovered.into()));
+            fields.push(("anchor_misses".into(), m.anchor_misses.into()));
+        }
+        value
+    }
+
+    /// The JSON object for a single finding.
+    pub fn finding(f: &Finding) -> Value {
+        let mut value = Value::object([
+            ("item", f.item.as_str().into()),
+            ("kind", f.locator.kind.id().into()),
+            ("bounded", f.bounded.as_str().into()),
+            ("bound", f.bound.as_str().into()),
+            ("line", f.line.into()),
+            ("column", f.column.into()),
+            ("end_line", f.end_line.into()),
+            ("end_column", f.end_column.into()),
+        ]);
+        if let (Some(header), Value::Object(fields)) = (&f.locator.impl_header, &mut value) {
+            fields.push(("impl".into(), Self::impl_header(header)));
+        }
+        if let (Some(rule), Value::Object(fields)) = (&f.rule, &mut value) {
+            fields.push((
+                "rule".into(),
+                Value::object([
+                    ("rule", rule.rule.id().into()),
+                    ("confidence", rule.confidence.to_string().into()),
+                    ("message", rule.message.as_str().into()),
+                ]),
+            ));
+        }
+        if let (Some(caution), Value::Object(fields)) = (&f.caution, &mut value) {
+            fields.push(("caution".into(), caution.as_str().into()));
+        }
+        if let (Some(used), Value::Object(fields)) = (&f.statically_used, &mut value) {
+            fields.push(("statically_used".into(), used.as_str().into()));
+        }
+        if f.memoized
+            && let Value::Object(fields) = &mut value
+        {
+            fields.push(("memoized".into(), Value::Bool(true)));
+        }
+        if let (Some(miss), Value::Object(fields)) = (&f.anchor_miss, &mut value) {
+            fields.push(("anchor_miss".into(), Self::anchor_miss(miss)));
+        }
+        if f.modified_bound
+            && let Value::Object(fields) = &mut value
+        {
+            fields.push(("modified_bound".into(), Value::Bool(true)));
+        }
+        if f.unsafe_impl
+            && let Value::Object(fields) = &mut value
+        {
+            fields.push(("unsafe_impl".into(), Value::Bool(true)));
+        }
+        if let (Some(default), Value::Object(fields)) = (&f.param_default, &mut value) {
+            fields.push(("param_default".into(), default.as_str().into()));
+        }
+        if let (Some(tr), Value::Object(fields)) = (&f.foreign_trait, &mut value) {
+            fields.push(("foreign_trait".into(), tr.as_str().into()));
+        }
+        if let (Some(weight), Value::Object(fields)) = (f.weight, &mut value) {
+            fields.push(("weight".into(), weight.into()));
+        }
+        if !f.derives.is_empty()
+            && let Value::Object(fields) = &mut value
+        {
+            fields.push((
+                "derives".into(),
+                Value::Array(f.derives.iter().map(|d| d.as_str().into()).collect()),
+            ));
+        }
+        if !f.duplicates.is_empty()
+            && let Value::Object(fields) = &mut value
+        {
+            fields.push((
+                "duplicates".into(),
+                Value::Array(f.duplicates.iter().map(|d| d.as_str().into()).collect()),
+            ));
+        }
+        if !f.packages.is_empty()
+            && let Value::Object(fields) = &mut value
+        {
+            fields.push((
+                "packages".into(),
+                Value::Array(f.packages.iter().map(|p| p.as_str().into()).collect()),
+            ));
+        }
+        if !f.cfg.is_empty()
+            && let Value::Object(fields) = &mut value
+        {
+            fields.push((
+                "cfg".into(),
+                Value::Array(f.cfg.iter().map(|c| c.as_str().into()).collect()),
+            ));
+            if f.not_validated {
+                fields.push(("not_validated".into(), Value::Bool(true)));
+            }
+        }
+        value
+    }
+
+    /// How a trial's edit missed its anchor: `wanted`, `recovered`, and `near` when any.
+    pub fn anchor_miss(miss: &AnchorMiss) -> Value {
+        let mut value = Value::object([
+            ("wanted", miss.wanted.as_str().into()),
+            ("recovered", Value::Bool(miss.recovered)),
+        ]);
+        if let (false, Value::Object(fields)) = (miss.near.is_empty(), &mut value) {
+            fields.push((
+                "near".into(),
+                Value::Array(miss.near.iter().map(|n| n.as_str().into()).collect()),
+            ));
+        }
+        value
+    }
+
+    /// The impl block of a finding's item: `self_ty`, and `trait_path` for a trait impl.
+    pub fn impl_header(header: &ImplHeader) -> Value {
+        let mut value = Value::object([("self_ty", header.self_ty.as_str().into())]);
+        if let (Some(tp), Value::Object(fields)) = (&header.trait_path, &mut value) {
+            fields.push(("trait_path".into(), tp.as_str().into()));
+        }
+        value
+    }
+
+    fn removal(r: &Removal) -> Value {
+        let mut value = Value::object([
+            ("finding", Self::finding(&r.finding)),
+            ("status", r.status.to_string().into()),
+            ("before", r.before.as_str().into()),
+            ("after", r.after.as_str().into()),
+            ("check_ms", r.check_time.into()),
+            ("write_ms", r.write_time.into()),
+        ]);
+        if !r.scope.is_empty()
+            && let Value::Object(fields) = &mut value
+        {
+            fields.push((
+                "scope".into(),
+                Value::Array(r.scope.iter().map(|p| p.as_str().into()).collect()),
+            ));
+        }
+        if let Some(batch) = r.batch
+            && let Value::Object(fields) = &mut value
+        {
+            fields.push(("batch".into(), batch.into()));
+        }
+        if r.aborted
+            && let Value::Object(fields) = &mut value
+        {
+            fields.push(("aborted".into(), Value::Bool(true)));
+        }
+        if r.cached
+            && let Value::Object(fields) = &mut value
+        {
+            fields.push(("cached".into(), Value::Bool(true)));
+        }
+        if r.test_only
+            && let Value::Object(fields) = &mut value
+        {
+            fields.push(("test_only".into(), Value::Bool(true)));
+        }
+        if let Some(slowdown) = r.slowdown
+            && let Value::Object(fields) = &mut value
+        {
+            fields.push(("slowdown".into(), Value::Float(slowdown)));
+        }
+        value
+    }
+}
@@ -0,0 +1,108 @@
+// src/report/junit.rs
+//! JUnit XML rendering of check reports.
+
+#![deny(missing_docs)]
+
+use crate::error::TraitError;
+use crate::report::{FileReport, Finding, Report, xml_escape};
+use std::io::Write;
+use std::time::Duration;
+
+/// JUnit XML emitter: one `<testsuite>` per file, one failed `<testcase>` per finding.
+pub struct Junit;
+
+impl Junit {
+    /// Write `report` as a JUnit XML document.
+    pub fn write<W: Write>(report: &Report, out: &mut W) -> TraitError<()> {
+        let cases: usize = report.files.iter().map(Self::case_count).sum();
+        writeln!(out, r#"<?xml version="1.0" encoding="UTF-8"?>"#)?;
+        writeln!(
+            out,
+            r#"<testsuites name="trait-winnower" tests="{}" failures="{}" errors="0" time="{}">"#,
+            cases,
+            report.total_findings(),
+            Self::secs(report.total_duration())
+        )?;
+        for file in &report.files {
+            Self::write_suite(file, out)?;
+        }
+        writeln!(out, "</testsuites>")?;
+        Ok(())
+    }
+
+    fn write_suite<W: Write>(file: &FileReport, out: &mut W) -> TraitError<()> {
+        let path = xml_escape(&file.path.to_string_lossy().replace('\\', "/"));
+        writeln!(
+            out,
+            r#"  <testsuite name="{}" tests="{}" failures="{}" errors="0" skipped="0" time="{}">"#,
+            path,
+            Self::case_count(file),
+            file.findings.len(),
+            Self::secs(file.metrics.total)
+        )?;
+        if file.findings.is_empty() {
+            writeln!(
+                out,
+                r#"    <testcase classname="{path}" name="no possibly unnecessary bounds" time="0"/>"#
+            )?;
+        }
+        for f in &file.findings {
+            let name = xml_escape(&format!("{}: {}: {}", f.item, f.bounded, f.bound));
+            let message = xml_escape(&format!(
+                "possibly unnecessary trait bound `{}` on `{}` in {} at {}:{}:{}",
+                f.bound,
+                f.bounded,
+                f.item,
+                file.path.display(),
+                f.line,
+                f.column
+            ));
+            writeln!(
+                out,
+                r#"    <testcase classname="{path}" name="{name}" time="0">"#
+            )?;
+            Self::write_item_properties(out, f)?;
+            writeln!(
+                out,
+                r#"      <failure message="{message}" type="possibly-unnecessary-bound">{message}</failure>"#
+            )?;
+            writeln!(out, "    </testcase>")?;
+        }
+        writeln!(out, "  </testsuite>")?;
+        Ok(())
+    }
+
+    /// The finding's item kind, and the impl block of an impl or impl method, as testcase
+    /// properties.
+    fn write_item_properties<W: Write>(out: &mut W, f: &Finding) -> TraitError<()> {
+        let header = f.locator.impl_header.as_ref();
+        let properties = [
+            ("kind", Some(f.locator.kind.id())),
+            ("self_ty", header.map(|h| h.self_ty.as_str())),
+            ("trait_path", header.and_then(|h| h.trait_path.as_deref())),
+        ];
+        writeln!(out, "      <properties>")?;
+        for (name, value) in properties {
+            if let Some(value) = value {
+                writeln!(
+                    out,
+                    r#"        <property name="{name}" value="{}"/>"#,
+                    xml_escape(value)
+                )?;
+            }
+        }
+        writeln!(out, "      </properties>")?;
+        Ok(())
+    }
+
+    /// Files without findings contribute one passing case so totals stay meaningful.
+    #[inline]
+    fn case_count(file: &FileReport) -> usize {
+        file.findings.len().max(1)
+    }
+
+    #[inline]
+    fn secs(d: Duration) -> String {
+        format!("{:.3}", d.as_secs_f64())
+    }
+}
@@ -0,0 +1,131 @@
+// src/report/lsp.rs
+//! Language Server Protocol flavored diagnostics.
+
+#![deny(missing_docs)]
+
+use crate::analysis::UTF8_BOM;
+use crate::report::json::{Json, Value};
+use crate::report::{FileReport, Finding};
+use std::path::Path;
+
+/// LSP `DiagnosticSeverity.Warning`.
+const SEVERITY_WARNING: usize = 2;
+
+/// Maps proc-macro2 positions (1-based line, char column) to LSP positions
+/// (0-based line, UTF-16 code unit column).
+pub struct LineIndex<'a> {
+    lines: Vec<&'a str>,
+}
+
+impl<'a> LineIndex<'a> {
+    /// Index `source`. A leading BOM is ignored, as syn ignores it when parsing.
+    pub fn new(source: &'a str) -> Self {
+        let source = source.strip_prefix(UTF8_BOM).unwrap_or(source);
+        Self {
+            lines: source.split('\n').collect(),
+        }
+    }
+
+    /// Convert a 1-based line and 0-based char column to a 0-based line and UTF-16 offset.
+    /// Positions past the end of a line clamp to its end.
+    pub fn position(&self, line: usize, char_column: usize) -> (usize, usize) {
+        let line0 = line.saturating_sub(1);
+        let text = self.lines.get(line0).copied().unwrap_or_default();
+        let text = text.strip_suffix('\r').unwrap_or(text);
+        let utf16 = text.chars().take(char_column).map(char::len_utf16).sum();
+        (line0, utf16)
+    }
+}
+
+/// Emitter for LSP `publishDiagnostics`-style JSON.
+pub struct Lsp;
+
+impl Lsp {
+    /// Diagnostics for one file, in the shape of LSP `PublishDiagnosticsParams`.
+    pub fn file(file: &FileReport, abs_path: &Path, source: &str) -> Value {
+        let index = LineIndex::new(source);
+        Value::object([
+            ("uri", Self::file_uri(abs_path).into()),
+            (
+                "path",
+                file.path.to_string_lossy().replace('\\', "/").into(),
+            ),
+            (
+                "diagnostics",
+                Value::Array(
+                    file.findings
+                        .iter()
+                        .map(|f| Self::diagnostic(f, &index))
+                        .collect(),
+                ),
+            ),
+        ])
+    }
+
+    fn diagnostic(f: &Finding, index: &LineIndex<'_>) -> Value {
+        let (start_line, start_char) = index.position(f.line, f.column.saturating_sub(1));
+        let (end_line, end_char) = index.position(f.end_line, f.end_column.saturating_sub(1));
+        Value::object([
+            (
+                "range",
+                Value::object([
+                    ("start", Self::position(start_line, start_char)),
+                    ("end", Self::position(end_line, end_char)),
+                ]),
+            ),
+            ("severity", SEVERITY_WARNING.into()),
+            ("source", "trait-winnower".into()),
+            ("code", "possibly-unnecessary-bound".into()),
+            (
+                "message",
+                format!(
+                    "possibly unnecessary trait bound `{}` on `{}` in `{}`",
+                    f.bound, f.bounded, f.item
+                )
+                .into(),
+            ),
+            ("data", Self::data(f)),
+        ])
+    }
+
+    /// The item the diagnostic is on, for clients that act on it: its kind, and the impl
+    /// block of an impl or impl method.
+    fn data(f: &Finding) -> Value {
+        let mut value = Value::object([
+            ("item", f.item.as_str().into()),
+            ("kind", f.locator.kind.id().into()),
+        ]);
+        if let (Some(header), Value::Object(fields)) = (&f.locator.impl_header, &mut value) {
+            fields.push(("impl".into(), Json::impl_header(header)));
+        }
+        value
+    }
+
+    fn position(line: usize, character: usize) -> Value {
+        Value::object([("line", line.into()), ("character", character.into())])
+    }
+
+    /// A `file://` URI for `path`, percent-encoding everything but unreserved characters.
+    pub fn file_uri(path: &Path) -> String {
+        let abs = path.canonicalize().unwrap_or_else(|_| path.to_path_buf());
+        let mut s = abs.to_string_lossy().replace('\\', "/");
+        // Verbatim Windows prefix from canonicalize.
+        if let Some(rest) = s.strip_prefix("//?/") {
+            s = rest.to_owned();
+        }
+        if !s.starts_with('/') {
+            s.insert(0, '/');
+        }
+        let mut out = String::from("file://");
+        for b in s.bytes() {
+            match b {
+                b'A'..=b'Z' | b'a'..=b'z' | b'0'..=b'9' | b'-' | b'.' | b'_' | b'~' | b'/' => {
+                    out.push(b as char)
+                }
+                b':' if out.len() == "file:///".len() + 1 => out.push(':'),
+                _ => out.push_str(&format!("%{b:02X}")),
+            }
+        }
+        out
+    }
+}
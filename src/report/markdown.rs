@@ -0,0 +1,83 @@
+// src/report/markdown.rs
+//! Markdown summary of reports, for pull request comments and CI job summaries.
+
+#![deny(missing_docs)]
+
+use crate::error::TraitError;
+use crate::report::breakdown::{Breakdown, Tally};
+use crate::report::{RemovalStatus, Report};
+use std::io::Write;
+use std::path::Path;
+
+/// Markdown emitter: run totals, then the removals by package and by trait (see
+/// [`Breakdown`]). Findings are not listed one by one.
+pub struct Markdown;
+
+impl Markdown {
+    /// Write `report`, produced by `command` on `target`, as a Markdown document.
+    pub fn write<W: Write>(
+        report: &Report,
+        command: &str,
+        target: &Path,
+        out: &mut W,
+    ) -> TraitError<()> {
+        writeln!(out, "## trait-winnower {command}")?;
+        writeln!(out)?;
+        writeln!(out, "- Target: `{}`", target.display())?;
+        writeln!(out, "- Files: {}", report.files.len())?;
+        writeln!(out, "- Findings: {}", report.total_findings())?;
+        for status in [
+            RemovalStatus::Removed,
+            RemovalStatus::Retained,
+            RemovalStatus::Skipped,
+            RemovalStatus::Reverted,
+        ] {
+            let count = report.count_removals(status);
+            if count > 0 || status != RemovalStatus::Reverted {
+                writeln!(out, "- Bounds {status}: {count}")?;
+            }
+        }
+        let follow_ups = report.follow_ups();
+        if !follow_ups.is_empty() {
+            writeln!(out, "- Follow-up actions: {}", follow_ups.len())?;
+            for (u, _) in follow_ups {
+                writeln!(
+                    out,
+                    "  - `{}:{}`: {} (`{}`)",
+                    u.file.display(),
+                    u.line,
+                    u.message,
+                    u.lint
+                )?;
+            }
+        }
+        let breakdown = Breakdown::of(report);
+        if breakdown.is_empty() {
+            return Ok(());
+        }
+        for (column, tallies) in [
+            ("Package", &breakdown.packages),
+            ("Trait", &breakdown.traits),
+        ] {
+            writeln!(out)?;
+            Self::table(out, column, tallies)?;
+        }
+        Ok(())
+    }
+
+    fn table<W: Write>(out: &mut W, column: &str, tallies: &[Tally]) -> TraitError<()> {
+        writeln!(out, "| {column} | Removed | Retained | Removed % |")?;
+        writeln!(out, "| --- | ---: | ---: | ---: |")?;
+        for t in tallies {
+            writeln!(
+                out,
+                "| `{}` | {} | {} | {:.0} |",
+                t.name.replace('|', "\\|"),
+                t.removed,
+                t.retained,
+                t.ratio() * 100.0
+            )?;
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,1086 @@
+// src/report/mod.rs
+//! Structured reports of trait-bound findings.
+
+#![deny(missing_docs)]
+
+pub mod breakdown;
+pub mod diagnostic;
+pub mod html;
+pub mod json;
+pub mod junit;
+pub mod lsp;
+pub mod markdown;
+pub mod rustfix;
+pub mod suggestions;
+
+use crate::analysis::{ItemBounds, ItemKey, ItemLocator};
+use crate::analysis::{ItemRef, render_generics};
+use crate::config::{CargoCheckConfig, CoverageGap, MatrixEntry, MatrixMode, Order};
+use crate::dynamic_analysis::common::{
+    BoundCandidate, BoundRemovalOutcome, BoundRemovalResult, BoundSite, CheckTargetDir, FollowUp,
+    OwnedBoundCandidate, Remove, Toolchain,
+};
+use crate::dynamic_analysis::edit::AnchorMiss;
+use crate::dynamic_analysis::unlock::{Blame, UnlockChain};
+use crate::error::{TraitError, WinnowerError};
+use crate::findings::{
+    Confidence, RuleMatch, dyn_compat_caution, modified_bound_caution, opaque_type_caution,
+    public_param_default, unsafe_impl_caution,
+};
+use crate::fingerprint::Fingerprint;
+use crate::impact::Impact;
+use crate::strategy::Strategy;
+use quote::ToTokens;
+use serde::{Deserialize, Serialize};
+use std::cmp::Reverse;
+use std::collections::{HashMap, HashSet};
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+use syn::spanned::Spanned;
+
+/// A trait bound `check` reports as possibly unnecessary.
+#[derive(Debug, Clone, Default, Serialize, Deserialize, PartialEq)]
+pub struct Finding {
+    /// Item label without the leading `// `, e.g. `fn foo`.
+    pub item: String,
+    /// The bounded parameter or type, e.g. `T`.
+    pub bounded: String,
+    /// The bound as written, e.g. `Clone`.
+    pub bound: String,
+    /// 1-based line where the bound starts.
+    pub line: usize,
+    /// 1-based column (in chars) where the bound starts.
+    pub column: usize,
+    /// 1-based line where the bound ends.
+    pub end_line: usize,
+    /// 1-based column (in chars) just past the end of the bound.
+    pub end_column: usize,
+    /// The item carrying the bound; its file is set once the finding joins a [`FileReport`].
+    pub locator: ItemLocator,
+    /// The bound and where it is declared.
+    pub candidate: OwnedBoundCandidate,
+    /// The static rule that flags the bound, if any.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub rule: Option<RuleMatch>,
+    /// Why `prune` leaves the bound alone by default, if it does (see
+    /// [`unsafe_impl_caution`], [`dyn_compat_caution`], [`opaque_type_caution`] and
+    /// [`modified_bound_caution`]).
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub caution: Option<String>,
+    /// The default of the bounded type parameter of a public item, e.g. `String` in
+    /// `pub struct S<T: Clone = String>` (see [`public_param_default`]).
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub param_default: Option<String>,
+    /// The bound carries a modifier, e.g. `?Sized` or `const Trait`, and `prune` never
+    /// tries it (see [`modified_bound_caution`]).
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub modified_bound: bool,
+    /// The bound is on an `unsafe impl`, and `prune` leaves it alone unless allowed (see
+    /// [`unsafe_impl_caution`]).
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub unsafe_impl: bool,
+    /// Traits the item derives (see [`ItemRef::derives`]); derived impls copy the item's
+    /// where clause, so `cargo check` alone may not show the bound is needed.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub derives: Vec<String>,
+    /// The `#[cfg]` predicates the item is compiled under (see [`ItemKey::cfg`]), e.g.
+    /// `feature = "rare"`.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub cfg: Vec<String>,
+    /// No configuration the validation builds enables `cfg`, so `cargo check` never sees
+    /// the item and `prune` leaves it alone.
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub not_validated: bool,
+    /// The item's other declarations of the same bound, e.g. `where T: Clone` next to
+    /// `<T: Clone>`; `prune` removes or keeps them together.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub duplicates: Vec<String>,
+    /// How the body plainly uses the bound, e.g. ``t.clone()` calls `Clone::clone``, when
+    /// `prune` skipped it without a check (see
+    /// [`evident_use`](crate::findings::evident_use)).
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub statically_used: Option<String>,
+    /// `prune` kept the bound without a check: removing it from another item of the same
+    /// impl or trait had been rejected (see
+    /// [`RejectionMemo`](crate::dynamic_analysis::edit::RejectionMemo)).
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub memoized: bool,
+    /// The trial's edit matched no node by its anchor, found or not by the structural
+    /// fallback (see [`AnchorMiss`]).
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub anchor_miss: Option<AnchorMiss>,
+    /// The packages compiling the finding's file, sorted, when more than one does (see
+    /// [`Report::dedup_shared`]).
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub packages: Vec<String>,
+    /// The trait of the impl carrying the bound, when the workspace does not define it and
+    /// `foreign_trait_impls` is `warn` or `skip` (see
+    /// [`ForeignTraitImpls`](crate::config::ForeignTraitImpls)), e.g. `serde :: Serialize`.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub foreign_trait: Option<String>,
+    /// How many times the workspace refers to the item, when weights were asked for (see
+    /// [`References::weight`](crate::references::References::weight)).
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub weight: Option<usize>,
+}
+
+/// Add `more` to `packages`, keeping them sorted and unique.
+fn merge_packages(packages: &mut Vec<String>, more: &mut Vec<String>) {
+    packages.append(more);
+    packages.sort();
+    packages.dedup();
+}
+
+/// Where a bound is declared. Type parameters sort before where clauses, those before
+/// argument-position `impl Trait`, and those before return-position `impl Trait` and trait
+/// objects.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
+pub enum SiteKind {
+    /// On a type parameter, e.g. `<T: Clone>`.
+    #[default]
+    TypeParam,
+    /// In a where clause predicate, e.g. `where T: Clone`.
+    WhereClause,
+    /// In the `impl Trait` type of an argument, e.g. `x: impl Clone + Debug`.
+    ImplTraitArg,
+    /// In a return-position `impl Trait`, e.g. `-> impl Iterator<Item = u32> + Send`.
+    ReturnImplTrait,
+    /// In a trait object type, e.g. `Box<dyn Error + Send>`.
+    TraitObject,
+}
+
+/// What happened to a candidate during `prune`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum RemovalStatus {
+    /// Removed; the crate still builds.
+    Removed,
+    /// Kept; removing it broke the build, or removing it elsewhere in the scope did (see
+    /// [`Finding::memoized`]).
+    Retained,
+    /// Not attempted (the edit did not apply or changed nothing, validation never builds
+    /// the item, or the body plainly uses the bound; see [`Finding::not_validated`] and
+    /// [`Finding::statically_used`]).
+    Skipped,
+    /// Removed, then put back because the semver gate found a breaking change, or, with
+    /// `--reject-slow-removals`, because its check was slow (see [`SlowRemovals`]).
+    Reverted,
+}
+
+impl std::fmt::Display for RemovalStatus {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(match self {
+            RemovalStatus::Removed => "removed",
+            RemovalStatus::Retained => "retained",
+            RemovalStatus::Skipped => "skipped",
+            RemovalStatus::Reverted => "reverted",
+        })
+    }
+}
+
+/// One bound `prune` tried to remove.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct Removal {
+    /// Label and location of the bound.
+    pub finding: Finding,
+    /// Outcome of the trial.
+    pub status: RemovalStatus,
+    /// The item's generics and where clause before the removal.
+    pub before: String,
+    /// The item's generics and where clause with the bound removed.
+    pub after: String,
+    /// Time spent in `cargo check` for this candidate.
+    pub check_time: Duration,
+    /// Time spent writing and reverting the file for this candidate.
+    pub write_time: Duration,
+    /// The packages the trial's `cargo check` was limited to (see
+    /// [`CheckScope`](crate::config::CheckScope)); empty when it checked the whole
+    /// workspace or was not run.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub scope: Vec<String>,
+    /// The cross-file batch the trial was validated in, with `--batch-size`; its check is
+    /// counted once, against the file of the batch's first trial.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub batch: Option<usize>,
+    /// The trial's `cargo check` was stopped at its first error (see
+    /// [`CargoCheckConfig::fail_fast`](crate::config::CargoCheckConfig::fail_fast)).
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub aborted: bool,
+    /// The trial's outcome came from the
+    /// [`TrialCache`](crate::dynamic_analysis::trial_cache::TrialCache) of an earlier run.
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub cached: bool,
+    /// The bounds the rejecting check's errors blamed (see [`Blame`]); retained removals
+    /// only.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub blames: Vec<Blame>,
+    /// The bound was retained, but goes when validation leaves test targets out: only
+    /// `#[cfg(test)]` code needs it (see
+    /// [`explain_test_only`](crate::dynamic_analysis::test_only::explain_test_only)).
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub test_only: bool,
+    /// How many times the run's baseline check took the trial's check, when that is past
+    /// [`Config::slow_removal_factor`](crate::config::Config::slow_removal_factor) and the
+    /// bound was removed (see [`SlowRemovals`]).
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub slowdown: Option<f64>,
+    /// The lints the removal raised that
+    /// [`tolerate_new_lints`](crate::config::CargoCheckConfig::tolerate_new_lints) let
+    /// pass: what is left to clean up by hand; removed bounds only.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub follow_ups: Vec<FollowUp>,
+}
+
+impl Removal {
+    /// Summarize a pruning result.
+    pub fn from_result(result: &BoundRemovalResult) -> Self {
+        let (status, check) = match &result.outcome {
+            BoundRemovalOutcome::Removed { check } => (RemovalStatus::Removed, Some(check)),
+            BoundRemovalOutcome::Retained { check } => (RemovalStatus::Retained, Some(check)),
+            BoundRemovalOutcome::Skipped => (RemovalStatus::Skipped, None),
+        };
+        Self {
+            finding: result.finding.clone(),
+            status,
+            before: result.before.clone(),
+            after: result.after.clone(),
+            check_time: result.check_time,
+            write_time: result.write_time,
+            scope: check.map(|c| c.scope.clone()).unwrap_or_default(),
+            batch: result.batch,
+            aborted: check.is_some_and(|c| c.aborted),
+            cached: check.is_some_and(|c| c.cached),
+            blames: match &result.outcome {
+                BoundRemovalOutcome::Retained { check } => Blame::parse(&check.stderr),
+                _ => Vec::new(),
+            },
+            test_only: false,
+            slowdown: None,
+            follow_ups: match &result.outcome {
+                BoundRemovalOutcome::Removed { check } => check.follow_ups.clone(),
+                _ => Vec::new(),
+            },
+        }
+    }
+
+    /// Render the generics of `item` before and after removing `candidates`; the arguments
+    /// instead when the candidates are argument-position `impl Trait` bounds, the type
+    /// when they are return-position `impl Trait` or `dyn Trait` bounds.
+    pub fn generics_before_after(
+        item: &ItemRef<'_>,
+        candidates: &[BoundCandidate],
+    ) -> (String, String) {
+        if let Some(path) = candidates.iter().find_map(|c| match &c.site {
+            BoundSite::ReturnImplTrait { path, .. } | BoundSite::TraitObject { path, .. } => {
+                Some(path)
+            }
+            _ => None,
+        }) && let Some(before) = item
+            .type_root(path.root)
+            .and_then(|root| path.resolve(root))
+        {
+            let mut after = before.clone();
+            for candidate in candidates {
+                Remove::apply_to_type(&mut after, candidate);
+            }
+            let render = |ty: &syn::Type| ty.to_token_stream().to_string();
+            return (render(before), render(&after));
+        }
+        if candidates
+            .iter()
+            .any(|c| matches!(c.site, BoundSite::ImplTraitArg { .. }))
+            && let Some(sig) = item.sig()
+        {
+            let mut after = sig.clone();
+            for candidate in candidates {
+                Remove::apply_to_item_with_generics(&mut after, candidate);
+            }
+            let render = |sig: &syn::Signature| format!("({})", sig.inputs.to_token_stream());
+            return (render(sig), render(&after));
+        }
+        let before = item.generics().clone();
+        let mut after = before.clone();
+        for candidate in candidates {
+            Remove::apply_to_item_with_generics(&mut after, candidate);
+        }
+        (render_generics(&before), render_generics(&after))
+    }
+
+    /// Whether this removal rode on the same trial as `prev`, the removal before it: another
+    /// declaration of the same bound on the same item (see [`Finding::duplicates`]), with
+    /// the same outcome and edit.
+    pub fn shares_check_with(&self, prev: &Removal) -> bool {
+        let (a, b) = (&self.finding, &prev.finding);
+        self.status == prev.status
+            && self.after == prev.after
+            && a.locator == b.locator
+            && a.candidate != b.candidate
+            && a.candidate.key() == b.candidate.key()
+    }
+}
+
+/// Findings and removals for a single analyzed file.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct FileReport {
+    /// Path of the file, relative to the target root where possible.
+    pub path: PathBuf,
+    /// Findings in canonical order (see [`Finding::sort_key`]).
+    pub findings: Vec<Finding>,
+    /// Pruning results, in canonical order once sorted (empty for `check`).
+    pub removals: Vec<Removal>,
+    /// Timings and counters for this file.
+    pub metrics: FileMetrics,
+    /// The package owning the file (see [`CrateMap::owner_of`](crate::crate_map::CrateMap::owner_of));
+    /// set by `prune`, `None` outside every package.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub package: Option<String>,
+}
+
+/// Timings and counters for one file.
+#[derive(Debug, Clone, Default, Serialize, Deserialize, PartialEq)]
+pub struct FileMetrics {
+    /// Reading and parsing the source.
+    pub parse: Duration,
+    /// Items with at least one bound.
+    pub items: usize,
+    /// Candidate bounds found (`check`) or trialed (`prune`).
+    pub candidates: usize,
+    /// `cargo check` runs.
+    pub checks: usize,
+    /// Time spent in `cargo check`.
+    pub check_time: Duration,
+    /// Time spent writing and reverting the file.
+    pub write_time: Duration,
+    /// `cargo check` runs the prefilter saved by skipping bounds the body plainly uses.
+    #[serde(default)]
+    pub prefiltered: usize,
+    /// `cargo check` runs saved by rejections earlier in the same scope.
+    #[serde(default)]
+    pub memo_hits: usize,
+    /// `cargo check` runs stopped at their first error, and the time they took.
+    #[serde(default)]
+    pub aborted: usize,
+    /// Time spent in the `aborted` runs, part of `check_time`.
+    #[serde(default)]
+    pub aborted_time: Duration,
+    /// `cargo check` runs answered from the trial cache instead.
+    #[serde(default)]
+    pub trial_cache_hits: usize,
+    /// Trials whose edit missed its anchor but found the item by the structural fallback
+    /// (see [`Finding::anchor_miss`]).
+    #[serde(default)]
+    pub anchors_recovered: usize,
+    /// Trials whose edit found its item neither way, their bounds skipped.
+    #[serde(default)]
+    pub anchor_misses: usize,
+    /// Candidates left untried because the file has more than
+    /// [`max_candidates_per_file`](crate::winnower::WinnowerBuilder::max_candidates_per_file).
+    #[serde(default)]
+    pub truncated: usize,
+    /// Everything spent on this file.
+    pub total: Duration,
+}
+
+impl FileMetrics {
+    /// Fold the per-candidate timings of `removals` into these metrics. Checks of batched
+    /// removals (see [`Removal::batch`]) are shared across files and left to the caller.
+    pub fn add_removals(&mut self, removals: &[Removal]) {
+        self.candidates += removals.len();
+        let mut prev: Option<&Removal> = None;
+        for r in removals {
+            let shared = prev.is_some_and(|p| r.shares_check_with(p));
+            if r.finding.memoized && !shared {
+                self.memo_hits += 1;
+            } else if r.cached && !shared && r.batch.is_none() {
+                self.trial_cache_hits += 1;
+            } else if r.status != RemovalStatus::Skipped && !shared && r.batch.is_none() {
+                self.checks += 1;
+                if r.aborted {
+                    self.aborted += 1;
+                    self.aborted_time += r.check_time;
+                }
+            }
+            if r.finding.statically_used.is_some() && !shared {
+                self.prefiltered += 1;
+            }
+            match &r.finding.anchor_miss {
+                Some(miss) if !shared && miss.recovered => self.anchors_recovered += 1,
+                Some(_) if !shared => self.anchor_misses += 1,
+                _ => {}
+            }
+            prev = Some(r);
+            self.check_time += r.check_time;
+            self.write_time += r.write_time;
+        }
+    }
+}
+
+/// Write `bytes` to `path`, creating missing parent directories. They go to a file beside
+/// it first, renamed into place, so nothing ever reads half of it.
+pub fn write_atomic(path: &Path, bytes: &[u8]) -> TraitError<()> {
+    if let Some(dir) = path.parent().filter(|d| !d.as_os_str().is_empty()) {
+        std::fs::create_dir_all(dir).map_err(|e| WinnowerError::io("creating", dir, e))?;
+    }
+    let tmp = path.with_extension(format!("{}.tmp", std::process::id()));
+    std::fs::write(&tmp, bytes).map_err(|e| WinnowerError::io("writing", &tmp, e))?;
+    std::fs::rename(&tmp, path).map_err(|e| {
+        let _ = std::fs::remove_file(&tmp);
+        WinnowerError::io("writing", path, e)
+    })
+}
+
+/// Findings and removals for a whole run.
+#[derive(Debug, Clone, Default, Serialize, Deserialize, PartialEq)]
+pub struct Report {
+    /// One entry per analyzed file.
+    pub files: Vec<FileReport>,
+    /// Wall-clock time of the whole run.
+    pub wall_time: Duration,
+    /// The `cargo check` configuration removals are (or would be) validated with; `None`
+    /// for single files and custom validators.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub validation: Option<ValidationSummary>,
+    /// The order `prune` worked through files in; `None` for `check`.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub schedule: Option<Schedule>,
+    /// Compile times before and after pruning; `impact` only.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub impact: Option<Impact>,
+    /// Retained bounds found removable once the bounds they were blamed on had gone;
+    /// `prune` only.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub unlocks: Vec<UnlockChain>,
+    /// The strategy `prune` ran with, if named with `--strategy`.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub strategy: Option<Strategy>,
+    /// The `Cargo.lock` and rustc the run saw; `None` for single files.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub fingerprint: Option<Fingerprint>,
+    /// Removals whose check took far longer than the others; `prune` only, and only when
+    /// there are any.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub slow_removals: Option<SlowRemovals>,
+    /// Imports the removals left unused, with `--tidy-imports`; `prune` only.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub tidied_imports: Vec<TidiedImport>,
+    /// Hand-written impls a `#[derive]` could replace once bounds were removed; `prune`
+    /// only.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub derive_candidates: Vec<DeriveCandidate>,
+}
+
+/// An import `--tidy-imports` found unused once bounds were removed (see
+/// [`tidy`](crate::tidy)).
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+pub struct TidiedImport {
+    /// The file, as in [`FileReport::path`].
+    pub file: PathBuf,
+    /// 1-based line of the imported name when it was found.
+    pub line: usize,
+    /// The path imported, e.g. `serde::Serialize`.
+    pub import: String,
+    /// Dropped; `false` when the check failed without it and it was put back.
+    pub removed: bool,
+}
+
+/// An impl of a derivable std trait that does fieldwise what `#[derive]` would, with the
+/// bounds it would write (see [`derivable`](crate::derivable)).
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+pub struct DeriveCandidate {
+    /// The file, as in [`FileReport::path`].
+    pub file: PathBuf,
+    /// 1-based line of the `impl`.
+    pub line: usize,
+    /// The trait, e.g. `Clone`.
+    pub derive: String,
+    /// The struct implementing it.
+    pub ty: String,
+    /// It had bounds a derive would not write before the run.
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub enabled_by_prune: bool,
+}
+
+/// Checks quicker than this are never flagged slow: differences that small are noise.
+pub const MIN_SLOW_CHECK: Duration = Duration::from_millis(100);
+
+/// Removed bounds whose check took many times the run's baseline: the removal may make
+/// trait resolution blow up, and every later build pay for it.
+#[derive(Debug, Clone, Default, Serialize, Deserialize, PartialEq)]
+pub struct SlowRemovals {
+    /// How long a check of the unmodified crate took, before the first trial.
+    pub baseline: Duration,
+    /// How many times the baseline a check took to be flagged.
+    pub factor: f64,
+    /// The flagged removals, in report order.
+    pub removals: Vec<SlowRemoval>,
+    /// They were put back, with `--reject-slow-removals`.
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub reverted: bool,
+}
+
+/// A removal [`SlowRemovals`] flags.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct SlowRemoval {
+    /// The removed bound.
+    pub finding: Finding,
+    /// How long its check took.
+    pub check_time: Duration,
+    /// `check_time` over the baseline.
+    pub slowdown: f64,
+}
+
+/// The order `prune` worked through files in, and what decided it.
+#[derive(Debug, Clone, Default, Serialize, Deserialize, PartialEq)]
+pub struct Schedule {
+    /// How the order was chosen.
+    pub order: Order,
+    /// The files, in the order they were pruned.
+    pub files: Vec<ScheduledFile>,
+}
+
+/// A file's place in a [`Schedule`].
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct ScheduledFile {
+    /// Path of the file, relative to the target root where possible.
+    pub path: PathBuf,
+    /// The highest confidence among static rules flagging its bounds; `None` when none
+    /// does or the order is [`Order::Source`].
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub confidence: Option<Confidence>,
+    /// Estimated cost of checking it: the bytes of source its trials' `cargo check`
+    /// compiles. `None` with [`Order::Source`].
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub cost: Option<u64>,
+}
+
+/// The `cargo check` arguments of a run and what they leave unchecked.
+#[derive(Debug, Clone, Default, Serialize, Deserialize, PartialEq)]
+pub struct ValidationSummary {
+    /// Arguments after `cargo check`.
+    pub args: Vec<String>,
+    /// What those arguments do not build.
+    pub gaps: Vec<CoverageGap>,
+    /// Configurations checked besides the host.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub matrix: Vec<MatrixEntry>,
+    /// When the matrix runs.
+    #[serde(default)]
+    pub matrix_mode: MatrixMode,
+    /// The cargo and rustc `prune` validated with; `None` unless it ran cargo.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub toolchain: Option<Toolchain>,
+    /// Where `prune` ran cargo and how big that got; `None` unless it ran cargo.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub target_dir: Option<CheckTargetDir>,
+}
+
+impl ValidationSummary {
+    /// Summarize `config`.
+    pub fn of(config: &CargoCheckConfig) -> Self {
+        Self {
+            args: config.args.clone(),
+            gaps: config.coverage_gaps(),
+            matrix: config.matrix.clone(),
+            matrix_mode: config.matrix_mode,
+            toolchain: None,
+            target_dir: None,
+        }
+    }
+}
+
+impl Report {
+    /// Put files, findings and removals in canonical order: by path, then as
+    /// [`FileReport::sort`] does. Every output format renders in this order.
+    pub fn sort(&mut self) {
+        self.files.sort_by(|a, b| a.path.cmp(&b.path));
+        for f in &mut self.files {
+            f.sort();
+        }
+    }
+
+    /// Order [`sort`](Self::sort)ed files and their findings and removals heaviest first
+    /// (see [`Finding::weight`]): files by their heaviest finding or removal, ties keeping
+    /// their order.
+    pub fn sort_by_weight(&mut self) {
+        let weight = |f: &Finding| Reverse(f.weight.unwrap_or(0));
+        for f in &mut self.files {
+            f.findings.sort_by_key(weight);
+            f.removals.sort_by_key(|r| weight(&r.finding));
+        }
+        self.files.sort_by_key(|f| {
+            let removals = f.removals.iter().map(|r| &r.finding);
+            let heaviest = f
+                .findings
+                .iter()
+                .chain(removals)
+                .filter_map(|f| f.weight)
+                .max();
+            Reverse(heaviest.unwrap_or(0))
+        });
+    }
+
+    /// Report each finding of a file several packages compile once. `file_of` gives the
+    /// canonical path of a report's file and the packages compiling it. Findings sharing
+    /// canonical path, span and bound with an earlier one, as when two paths reach the same
+    /// file, are folded into it; files left with no findings are dropped. Findings of files
+    /// more than one package compiles list them in [`Finding::packages`].
+    pub fn dedup_shared(&mut self, file_of: impl Fn(&Path) -> (PathBuf, Vec<String>)) {
+        type Identity = (PathBuf, usize, usize, usize, usize, String);
+        let mut first: HashMap<Identity, (usize, usize)> = HashMap::new();
+        let mut seen_files: HashSet<PathBuf> = HashSet::new();
+        let mut keep_files = Vec::with_capacity(self.files.len());
+        for i in 0..self.files.len() {
+            let (canonical, packages) = file_of(&self.files[i].path);
+            let findings = std::mem::take(&mut self.files[i].findings);
+            let mut kept = Vec::with_capacity(findings.len());
+            for mut f in findings {
+                let identity = (
+                    canonical.clone(),
+                    f.line,
+                    f.column,
+                    f.end_line,
+                    f.end_column,
+                    f.bound.clone(),
+                );
+                let mut packages = packages.clone();
+                if let Some(&(file, index)) = first.get(&identity) {
+                    let earlier = if file == i {
+                        &mut kept[index]
+                    } else {
+                        &mut self.files[file].findings[index]
+                    };
+                    merge_packages(&mut earlier.packages, &mut packages);
+                    continue;
+                }
+                merge_packages(&mut f.packages, &mut packages);
+                first.insert(identity, (i, kept.len()));
+                kept.push(f);
+            }
+            self.files[i].findings = kept;
+            let repeated = !seen_files.insert(canonical);
+            keep_files.push(!(repeated && self.files[i].findings.is_empty()));
+        }
+        let mut keep = keep_files.into_iter();
+        self.files.retain(|_| keep.next().unwrap_or(true));
+        for f in self.files.iter_mut().flat_map(|f| &mut f.findings) {
+            if f.packages.len() < 2 {
+                f.packages.clear();
+            }
+        }
+    }
+
+    /// Total number of findings across all files.
+    pub fn total_findings(&self) -> usize {
+        self.files.iter().map(|f| f.findings.len()).sum()
+    }
+
+    /// Findings a static rule flags (see [`Finding::rule`]) across all files.
+    pub fn total_flagged(&self) -> usize {
+        self.files
+            .iter()
+            .flat_map(|f| &f.findings)
+            .filter(|f| f.rule.is_some())
+            .count()
+    }
+
+    /// Number of removals with the given status across all files.
+    pub fn count_removals(&self, status: RemovalStatus) -> usize {
+        self.files
+            .iter()
+            .flat_map(|f| &f.removals)
+            .filter(|r| r.status == status)
+            .count()
+    }
+
+    /// Sum of per-file analysis durations.
+    pub fn total_duration(&self) -> Duration {
+        self.files.iter().map(|f| f.metrics.total).sum()
+    }
+
+    /// Total `cargo check` runs across all files.
+    pub fn total_checks(&self) -> usize {
+        self.files.iter().map(|f| f.metrics.checks).sum()
+    }
+
+    /// Total `cargo check` runs the prefilter saved across all files.
+    pub fn total_prefiltered(&self) -> usize {
+        self.files.iter().map(|f| f.metrics.prefiltered).sum()
+    }
+
+    /// Total trials across all files whose edit missed its anchor, as recovered by the
+    /// structural fallback and hard misses (see [`FileMetrics::anchors_recovered`]).
+    pub fn total_anchor_misses(&self) -> (usize, usize) {
+        self.files.iter().fold((0, 0), |(recovered, hard), f| {
+            (
+                recovered + f.metrics.anchors_recovered,
+                hard + f.metrics.anchor_misses,
+            )
+        })
+    }
+
+    /// Total `cargo check` runs the rejection memo saved across all files.
+    pub fn total_memo_hits(&self) -> usize {
+        self.files.iter().map(|f| f.metrics.memo_hits).sum()
+    }
+
+    /// The files with candidates left untried, and how many (see
+    /// [`FileMetrics::truncated`]).
+    pub fn truncated(&self) -> impl Iterator<Item = (&Path, usize)> {
+        self.files
+            .iter()
+            .filter(|f| f.metrics.truncated > 0)
+            .map(|f| (f.path.as_path(), f.metrics.truncated))
+    }
+
+    /// Total time spent in `cargo check` across all files.
+    pub fn total_check_time(&self) -> Duration {
+        self.files.iter().map(|f| f.metrics.check_time).sum()
+    }
+
+    /// Total `cargo check` runs stopped at their first error across all files.
+    pub fn total_aborted(&self) -> usize {
+        self.files.iter().map(|f| f.metrics.aborted).sum()
+    }
+
+    /// The follow-ups of the bounds removed, each with the first removal that left it, in
+    /// file order (see [`Removal::follow_ups`]).
+    pub fn follow_ups(&self) -> Vec<(&FollowUp, &Removal)> {
+        let mut follow_ups: Vec<(&FollowUp, &Removal)> = self
+            .files
+            .iter()
+            .flat_map(|f| &f.removals)
+            .filter(|r| r.status == RemovalStatus::Removed)
+            .flat_map(|r| r.follow_ups.iter().map(move |u| (u, r)))
+            .collect();
+        follow_ups.sort_by(|a, b| a.0.cmp(b.0));
+        follow_ups.dedup_by(|a, b| a.0 == b.0);
+        follow_ups
+    }
+
+    /// Total `cargo check` runs answered from the trial cache across all files.
+    pub fn total_trial_cache_hits(&self) -> usize {
+        self.files.iter().map(|f| f.metrics.trial_cache_hits).sum()
+    }
+
+    /// Flag the removed bounds whose check took more than `factor` times `baseline`, the
+    /// check of the unmodified crate, and at least [`MIN_SLOW_CHECK`], setting their
+    /// [`Removal::slowdown`]. `None` when none did. Cached and aborted checks, and the
+    /// trials of a batch its check is not counted against, are never flagged.
+    pub fn flag_slow_removals(&mut self, baseline: Duration, factor: f64) -> Option<SlowRemovals> {
+        let ran = |r: &Removal| {
+            r.status != RemovalStatus::Skipped && !r.cached && !r.aborted && !r.check_time.is_zero()
+        };
+        if baseline.is_zero() {
+            return None;
+        }
+        let mut slow = SlowRemovals {
+            baseline,
+            factor,
+            ..SlowRemovals::default()
+        };
+        for r in self.files.iter_mut().flat_map(|f| &mut f.removals) {
+            let slowdown = r.check_time.as_secs_f64() / baseline.as_secs_f64();
+            if r.status == RemovalStatus::Removed
+                && ran(r)
+                && slowdown > factor
+                && r.check_time >= MIN_SLOW_CHECK
+            {
+                r.slowdown = Some(slowdown);
+                slow.removals.push(SlowRemoval {
+                    finding: r.finding.clone(),
+                    check_time: r.check_time,
+                    slowdown,
+                });
+            }
+        }
+        (!slow.removals.is_empty()).then_some(slow)
+    }
+
+    /// About how much stopping checks at their first error saved: what the aborted runs
+    /// would have taken at the mean time of those that ran to completion, less what they
+    /// took. Zero when no run completed.
+    pub fn estimated_abort_savings(&self) -> Duration {
+        let aborted = self.total_aborted();
+        let aborted_time: Duration = self.files.iter().map(|f| f.metrics.aborted_time).sum();
+        let completed = self.total_checks().saturating_sub(aborted);
+        let Ok(completed) = u32::try_from(completed) else {
+            return Duration::ZERO;
+        };
+        if completed == 0 || aborted == 0 {
+            return Duration::ZERO;
+        }
+        let mean = self.total_check_time().saturating_sub(aborted_time) / completed;
+        (mean * u32::try_from(aborted).unwrap_or(u32::MAX)).saturating_sub(aborted_time)
+    }
+}
+
+impl FileReport {
+    /// Build a file report from the items collected in it. Timings are left for the caller.
+    pub fn from_items(path: PathBuf, items: &ItemBounds<'_>) -> Self {
+        let findings: Vec<Finding> = items
+            .candidates()
+            .map(|(key, c)| Finding::new(key, &c))
+            .collect();
+        let metrics = FileMetrics {
+            items: items.iter_all_items().count(),
+            candidates: findings.len(),
+            ..FileMetrics::default()
+        };
+        Self::new(path, findings, Vec::new(), metrics)
+    }
+
+    /// The same report, each finding tagged with the static rule in `rules` that flags
+    /// the same bound.
+    pub fn with_rules(mut self, rules: &[crate::findings::Finding]) -> Self {
+        for f in &mut self.findings {
+            f.rule = rules
+                .iter()
+                .find(|r| (r.line, r.column, &r.candidate) == (f.line, f.column, &f.candidate))
+                .map(|r| r.verdict.clone());
+        }
+        self
+    }
+
+    /// Assemble a file report: places every finding's locator in `path`, then sorts.
+    pub fn new(
+        path: PathBuf,
+        mut findings: Vec<Finding>,
+        mut removals: Vec<Removal>,
+        metrics: FileMetrics,
+    ) -> Self {
+        let all = findings
+            .iter_mut()
+            .chain(removals.iter_mut().map(|r| &mut r.finding));
+        for f in all {
+            f.locator = std::mem::take(&mut f.locator).in_file(&path);
+        }
+        Finding::link_duplicates(findings.iter_mut());
+        Finding::link_duplicates(removals.iter_mut().map(|r| &mut r.finding));
+        let mut report = Self {
+            path,
+            findings,
+            removals,
+            metrics,
+            package: None,
+        };
+        report.sort();
+        report
+    }
+
+    /// Sort findings and removals by [`Finding::sort_key`]. The sort is stable, so
+    /// repeated trials of one bound keep their trial order.
+    pub fn sort(&mut self) {
+        self.findings.sort_by_key(Finding::sort_key);
+        self.removals.sort_by_key(|r| r.finding.sort_key());
+    }
+}
+
+impl Finding {
+    /// Describe one candidate bound on `key`.
+    pub fn new(key: &ItemKey<'_>, candidate: &BoundCandidate) -> Self {
+        let owned = OwnedBoundCandidate::from(candidate);
+        let span = candidate.bound.span();
+        let (start, end) = (span.start(), span.end());
+        Self {
+            item: key.to_string().trim_start_matches("// ").to_owned(),
+            bounded: Self::bounded(key.item(), candidate, &owned),
+            bound: owned.bound_text.clone(),
+            line: start.line,
+            column: start.column + 1,
+            end_line: end.line,
+            end_column: end.column + 1,
+            locator: ItemLocator::from(key),
+            candidate: owned,
+            rule: None,
+            caution: unsafe_impl_caution(key.item())
+                .or_else(|| dyn_compat_caution(key.item(), candidate))
+                .or_else(|| opaque_type_caution(candidate))
+                .or_else(|| modified_bound_caution(candidate)),
+            param_default: public_param_default(key, candidate),
+            modified_bound: modified_bound_caution(candidate).is_some(),
+            unsafe_impl: unsafe_impl_caution(key.item()).is_some(),
+            derives: key.item().derives(),
+            cfg: key
+                .cfg()
+                .iter()
+                .map(|m| m.to_token_stream().to_string())
+                .collect(),
+            not_validated: false,
+            duplicates: Vec::new(),
+            statically_used: None,
+            memoized: false,
+            anchor_miss: None,
+            packages: Vec::new(),
+            foreign_trait: None,
+            weight: None,
+        }
+    }
+
+    /// The bounded parameter or type of `candidate` as written, with the `for<'a>` binder of
+    /// its where predicate: `& 'a [T]` alone names a lifetime nothing declares.
+    fn bounded(
+        item: &ItemRef<'_>,
+        candidate: &BoundCandidate,
+        owned: &OwnedBoundCandidate,
+    ) -> String {
+        let BoundSite::WhereClause { pred_index, .. } = &candidate.site else {
+            return owned.site.bounded().to_owned();
+        };
+        let binder = item
+            .generics()
+            .where_clause
+            .as_ref()
+            .and_then(|wc| wc.predicates.iter().nth(*pred_index))
+            .and_then(|pred| match pred {
+                syn::WherePredicate::Type(pt) => pt.lifetimes.as_ref(),
+                _ => None,
+            });
+        match binder {
+            Some(binder) => format!("{} {}", binder.to_token_stream(), owned.site.bounded()),
+            None => owned.site.bounded().to_owned(),
+        }
+    }
+
+    /// Fill in [`Finding::duplicates`] across `findings`, the findings of one file.
+    pub fn link_duplicates<'a>(findings: impl Iterator<Item = &'a mut Finding>) {
+        let mut findings: Vec<&mut Finding> = findings.collect();
+        let duplicates: Vec<Vec<String>> = findings
+            .iter()
+            .map(|f| {
+                let mut found: Vec<String> = Vec::new();
+                for g in findings.iter().filter(|g| {
+                    g.locator == f.locator
+                        && g.candidate != f.candidate
+                        && g.candidate.key() == f.candidate.key()
+                }) {
+                    // Repeated trials of one declaration list it once.
+                    let declaration = g.candidate.declaration();
+                    if !found.contains(&declaration) {
+                        found.push(declaration);
+                    }
+                }
+                found
+            })
+            .collect();
+        for (f, duplicates) in findings.iter_mut().zip(duplicates) {
+            f.duplicates = duplicates;
+        }
+    }
+
+    /// Short markers for what makes a removal here less certain than `cargo check` says,
+    /// e.g. `has derives`; rendered next to outcomes.
+    pub fn tags(&self) -> Vec<&'static str> {
+        let mut tags = Vec::new();
+        if !self.derives.is_empty() {
+            tags.push("has derives");
+        }
+        if self.modified_bound {
+            tags.push("modified bound");
+        }
+        if self.unsafe_impl {
+            tags.push("unsafe impl");
+        }
+        if self.param_default.is_some() {
+            tags.push("public default");
+        }
+        if self.statically_used.is_some() {
+            tags.push("statically used");
+        }
+        if self.memoized {
+            tags.push("memoized");
+        }
+        if self.foreign_trait.is_some() {
+            tags.push("foreign trait");
+        }
+        if self.not_validated {
+            tags.push("not covered by validation");
+        } else if !self.cfg.is_empty() {
+            tags.push("cfg-gated");
+        }
+        tags
+    }
+
+    /// Mark the finding [`not_validated`](Finding::not_validated) when no configuration of
+    /// `config` builds its `cfg`.
+    pub fn check_cfg(&mut self, config: &CargoCheckConfig) {
+        let cfg: Vec<syn::Meta> = self
+            .cfg
+            .iter()
+            .filter_map(|c| syn::parse_str(c).ok())
+            .collect();
+        self.not_validated = cfg.len() == self.cfg.len() && config.builds_cfg(&cfg) == Some(false);
+    }
+
+    /// How to have validation build the item of a [`not_validated`](Finding::not_validated)
+    /// finding: the features its `cfg` requires, in a matrix entry. `None` for other
+    /// findings.
+    pub fn validation_hint(&self) -> Option<String> {
+        if !self.not_validated {
+            return None;
+        }
+        let mut features = Vec::new();
+        for c in &self.cfg {
+            if let Ok(meta) = syn::parse_str::<syn::Meta>(c) {
+                required_features(&meta, &mut features);
+            }
+        }
+        features.dedup();
+        Some(if features.is_empty() {
+            "add a [[cargo_check.matrix]] entry that builds it to validate it".to_owned()
+        } else {
+            format!(
+                "add `features = \"{}\"` to a [[cargo_check.matrix]] entry to validate it",
+                features.join(",")
+            )
+        })
+    }
+
+    /// Canonical order within a file: span start, then site, then bound index.
+    pub fn sort_key(&self) -> (usize, usize, SiteKind, usize) {
+        (
+            self.line,
+            self.column,
+            self.candidate.site.kind(),
+            self.candidate.site.bound_index(),
+        )
+    }
+}
+
+/// The features `meta` requires: its `feature = "x"`, alone or within `all(...)`.
+fn required_features(meta: &syn::Meta, out: &mut Vec<String>) {
+    match meta {
+        syn::Meta::NameValue(nv) if nv.path.is_ident("feature") => {
+            if let syn::Expr::Lit(syn::ExprLit {
+                lit: syn::Lit::Str(value),
+                ..
+            }) = &nv.value
+            {
+                out.push(value.value());
+            }
+        }
+        syn::Meta::List(list) if list.path.is_ident("all") => {
+            let nested = list.parse_args_with(
+                syn::punctuated::Punctuated::<syn::Meta, syn::Token![,]>::parse_terminated,
+            );
+            for m in nested.iter().flatten() {
+                required_features(m, out);
+            }
+        }
+        _ => {}
+    }
+}
+
+/// Escape text for XML/HTML attribute and element content.
+pub(crate) fn xml_escape(s: &str) -> String {
+    let mut out = String::with_capacity(s.len());
+    for c in s.chars() {
+        match c {
+            '&' => out.push_str("&amp;"),
+            '<' => out.push_str("&lt;"),
+            '>' => out.push_str("&gt;"),
+            '"' => out.push_str("&quot;"),
+            '\'' => out.push_str("&apos;"),
+            _ => out.push(c),
+        }
+    }
+    out
+}
@@ -0,0 +1,204 @@
+// src/report/rustfix.rs
+//! Findings as rustc JSON diagnostics with suggestions, for `cargo fix` and other rustfix
+//! consumers.
+//!
+//! Each finding a static rule flags becomes one diagnostic on its own line, as
+//! `--error-format=json` prints them: the bound as primary span, and a `help` child whose
+//! spans delete it with the punctuation joining it to its neighbours, as
+//! [`propose_edits`](crate::dynamic_analysis::text_edit::propose_edits) would.
+//! High-confidence rules are `MachineApplicable`; heuristics, and bounds whose removal may
+//! change dyn compatibility, are `MaybeIncorrect`. Findings no rule flags carry no
+//! evidence worth a fix and are left out.
+
+#![deny(missing_docs)]
+
+use crate::dynamic_analysis::text_edit::TextEdit;
+use crate::error::{TraitError, WinnowerError};
+use crate::findings::Confidence;
+use crate::plan::{PlanEntry, entry_edits};
+use crate::report::json::Value;
+use crate::report::{FileReport, Finding};
+use std::path::Path;
+
+/// rustc's name for a suggestion safe to apply unseen.
+const MACHINE_APPLICABLE: &str = "MachineApplicable";
+/// rustc's name for a suggestion to review before applying.
+const MAYBE_INCORRECT: &str = "MaybeIncorrect";
+
+/// Lines of a source text, to turn byte offsets and char columns into each other.
+struct Lines<'a> {
+    source: &'a str,
+    /// Byte offset each line starts at.
+    starts: Vec<usize>,
+}
+
+impl<'a> Lines<'a> {
+    fn new(source: &'a str) -> Self {
+        let starts = std::iter::once(0)
+            .chain(source.match_indices('\n').map(|(i, _)| i + 1))
+            .collect();
+        Self { source, starts }
+    }
+
+    /// The text of 1-based `line`, without its line ending.
+    fn text(&self, line: usize) -> &'a str {
+        let start = self
+            .starts
+            .get(line - 1)
+            .copied()
+            .unwrap_or(self.source.len());
+        let end = self.starts.get(line).map_or(self.source.len(), |&e| e - 1);
+        let text = &self.source[start..end.max(start)];
+        text.strip_suffix('\r').unwrap_or(text)
+    }
+
+    /// 1-based line and char column of byte `offset`.
+    fn position(&self, offset: usize) -> (usize, usize) {
+        let line = self.starts.partition_point(|&s| s <= offset).max(1);
+        let start = self.starts[line - 1];
+        (line, self.source[start..offset].chars().count() + 1)
+    }
+
+    /// Byte offset of 1-based `line` and char `column`, clamped to the line.
+    fn offset(&self, line: usize, column: usize) -> usize {
+        let start = self
+            .starts
+            .get(line - 1)
+            .copied()
+            .unwrap_or(self.source.len());
+        let text = self.text(line);
+        start
+            + text
+                .char_indices()
+                .nth(column.saturating_sub(1))
+                .map_or(text.len(), |(i, _)| i)
+    }
+}
+
+/// Emitter for rustc-style JSON diagnostics.
+pub struct Rustfix;
+
+impl Rustfix {
+    /// The diagnostics of `file`, read from `path` as `source`, in finding order.
+    pub fn file(file: &FileReport, path: &Path, source: &str) -> TraitError<Vec<Value>> {
+        let flagged: Vec<&Finding> = file.findings.iter().filter(|f| f.rule.is_some()).collect();
+        if flagged.is_empty() {
+            return Ok(Vec::new());
+        }
+        let parsed = syn::parse_file(source).map_err(|e| WinnowerError::parse(path, e))?;
+        let lines = Lines::new(source);
+        let name = path.to_string_lossy().replace('\\', "/");
+        let mut out = Vec::with_capacity(flagged.len());
+        for f in flagged {
+            let entry = PlanEntry {
+                locator: f.locator.clone(),
+                candidate: f.candidate.clone(),
+            };
+            let (edits, _) = entry_edits(&file.path, source, &parsed, &[&entry])?;
+            out.push(Self::diagnostic(f, &edits, &name, &lines));
+        }
+        Ok(out)
+    }
+
+    fn diagnostic(f: &Finding, edits: &[TextEdit], name: &str, lines: &Lines<'_>) -> Value {
+        let rule = f
+            .rule
+            .as_ref()
+            .expect("only flagged findings get diagnostics");
+        let applicability = if rule.confidence == Confidence::High && f.caution.is_none() {
+            MACHINE_APPLICABLE
+        } else {
+            MAYBE_INCORRECT
+        };
+        let start = lines.offset(f.line, f.column);
+        let end = lines.offset(f.end_line, f.end_column);
+        let message = &rule.message;
+        let help = Value::object([
+            ("message", "remove this bound".into()),
+            ("code", Value::Null),
+            ("level", "help".into()),
+            (
+                "spans",
+                Value::Array(
+                    edits
+                        .iter()
+                        .map(|e| {
+                            let replacement = Some((e.replacement.as_str(), applicability));
+                            Self::span(name, lines, e.start, e.end, replacement)
+                        })
+                        .collect(),
+                ),
+            ),
+            ("children", Value::Array(Vec::new())),
+            ("rendered", Value::Null),
+        ]);
+        Value::object([
+            ("$message_type", "diagnostic".into()),
+            ("message", message.as_str().into()),
+            (
+                "code",
+                Value::object([
+                    ("code", format!("trait_winnower::{}", rule.rule.id()).into()),
+                    ("explanation", Value::Null),
+                ]),
+            ),
+            ("level", "warning".into()),
+            (
+                "spans",
+                Value::Array(vec![Self::span(name, lines, start, end, None)]),
+            ),
+            ("children", Value::Array(vec![help])),
+            (
+                "rendered",
+                format!("warning: {message}\n --> {name}:{}:{}\n", f.line, f.column).into(),
+            ),
+        ])
+    }
+
+    /// A span of bytes `start..end`, replaced by `replacement` with its applicability.
+    fn span(
+        name: &str,
+        lines: &Lines<'_>,
+        start: usize,
+        end: usize,
+        replacement: Option<(&str, &str)>,
+    ) -> Value {
+        let (line_start, column_start) = lines.position(start);
+        let (line_end, column_end) = lines.position(end);
+        let text = (line_start..=line_end)
+            .map(|line| {
+                let text = lines.text(line);
+                let from = if line == line_start { column_start } else { 1 };
+                let to = if line == line_end {
+                    column_end
+                } else {
+                    text.chars().count() + 1
+                };
+                Value::object([
+                    ("text", text.into()),
+                    ("highlight_start", from.into()),
+                    ("highlight_end", to.into()),
+                ])
+            })
+            .collect();
+        let (suggested, applicability) = match replacement {
+            Some((text, applicability)) => (text.into(), applicability.into()),
+            None => (Value::Null, Value::Null),
+        };
+        Value::object([
+            ("file_name", name.into()),
+            ("byte_start", start.into()),
+            ("byte_end", end.into()),
+            ("line_start", line_start.into()),
+            ("line_end", line_end.into()),
+            ("column_start", column_start.into()),
+            ("column_end", column_end.into()),
+            ("is_primary", Value::Bool(true)),
+            ("text", Value::Array(text)),
+            ("label", Value::Null),
+            ("suggested_replacement", suggested),
+            ("suggestion_applicability", applicability),
+            ("expansion", Value::Null),
+        ])
+    }
+}
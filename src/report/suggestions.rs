@@ -0,0 +1,201 @@
+// src/report/suggestions.rs
+//! GitHub pull request suggestions for the bounds a dry-run `prune` would remove.
+//!
+//! Each suggestion replaces a range of lines of the original file with what those lines
+//! become once the bounds are gone, in the shape of a review comment: `path`, `line` and,
+//! for ranges of several lines, `start_line`, with a `body` holding one fenced
+//! `suggestion` block. A removal that empties its line drops the line, so a suggestion
+//! whose lines all go has an empty block.
+
+#![deny(missing_docs)]
+
+use crate::analysis::ItemBounds;
+use crate::dynamic_analysis::text_edit::{TextEdit, apply_edits};
+use crate::error::{TraitError, WinnowerError};
+use crate::plan::{PlanEntry, entry_edits};
+use crate::report::json::Value;
+use crate::report::{RemovalStatus, Report};
+use std::path::Path;
+
+/// One suggestion: 1-based lines `start..=end` of `path` become `lines`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Suggestion {
+    /// The file, relative to the top of the repository, with `/` separators.
+    pub path: String,
+    /// First line replaced.
+    pub start: usize,
+    /// Last line replaced.
+    pub end: usize,
+    /// The replacement lines, without line endings; empty when every line goes.
+    pub lines: Vec<String>,
+}
+
+impl Suggestion {
+    /// The review comment body: a fenced `suggestion` block of the new lines.
+    pub fn body(&self) -> String {
+        let mut body = String::from("```suggestion\n");
+        for line in &self.lines {
+            body.push_str(line);
+            body.push('\n');
+        }
+        body.push_str("```");
+        body
+    }
+
+    /// The suggestion as a review comment on the head side of the diff.
+    pub fn to_value(&self) -> Value {
+        let mut fields = vec![("path".to_owned(), self.path.as_str().into())];
+        if self.start < self.end {
+            fields.push(("start_line".to_owned(), self.start.into()));
+        }
+        fields.extend([
+            ("line".to_owned(), self.end.into()),
+            ("side".to_owned(), "RIGHT".into()),
+            ("body".to_owned(), self.body().into()),
+        ]);
+        Value::Object(fields)
+    }
+}
+
+/// Emitter for suggestion JSON.
+pub struct Suggestions;
+
+impl Suggestions {
+    /// The suggestions removing every bound `report` lists as removed, sorted by path and
+    /// line. Files are read from under `root`, which must hold them as they were before
+    /// the run, as a dry run leaves them; `prefix` is `root` relative to the top of the
+    /// repository.
+    pub fn collect(report: &Report, root: &Path, prefix: &Path) -> TraitError<Vec<Suggestion>> {
+        let mut out = Vec::new();
+        for file in &report.files {
+            let entries: Vec<PlanEntry> = file
+                .removals
+                .iter()
+                .filter(|r| r.status == RemovalStatus::Removed)
+                .map(|r| PlanEntry {
+                    locator: r.finding.locator.clone(),
+                    candidate: r.finding.candidate.clone(),
+                })
+                .collect();
+            if entries.is_empty() {
+                continue;
+            }
+            let path = root.join(&file.path);
+            let source = ItemBounds::read_source(&path)?;
+            let parsed = syn::parse_file(&source).map_err(|e| WinnowerError::parse(&path, e))?;
+            let entries: Vec<&PlanEntry> = entries.iter().collect();
+            let (edits, _) = entry_edits(&file.path, &source, &parsed, &entries)?;
+            let rel = prefix.join(&file.path).to_string_lossy().replace('\\', "/");
+            let rel = rel.strip_prefix("./").unwrap_or(&rel);
+            for (start, end, lines) in hunks(&source, &edits) {
+                out.push(Suggestion {
+                    path: rel.to_owned(),
+                    start,
+                    end,
+                    lines,
+                });
+            }
+        }
+        out.sort_by(|a, b| (&a.path, a.start).cmp(&(&b.path, b.start)));
+        Ok(out)
+    }
+
+    /// Write the suggestions for `report` as a JSON array (see [`Suggestions::collect`]).
+    pub fn write<W: std::io::Write>(
+        report: &Report,
+        root: &Path,
+        prefix: &Path,
+        out: &mut W,
+    ) -> TraitError<()> {
+        let suggestions = Self::collect(report, root, prefix)?;
+        Value::Array(suggestions.iter().map(Suggestion::to_value).collect()).write(out)
+    }
+}
+
+/// `edits` of `source` grouped into runs of lines they touch, each run with its 1-based
+/// first and last line and the lines it becomes. Runs sharing a line are merged.
+fn hunks(source: &str, edits: &[TextEdit]) -> Vec<(usize, usize, Vec<String>)> {
+    // Byte offset each line starts at, plus one past the end.
+    let mut starts: Vec<usize> = std::iter::once(0)
+        .chain(source.match_indices('\n').map(|(i, _)| i + 1))
+        .collect();
+    if starts.last() != Some(&source.len()) {
+        starts.push(source.len());
+    }
+    let line_of = |offset: usize| starts.partition_point(|&s| s <= offset).max(1);
+
+    let mut groups: Vec<(usize, usize, Vec<&TextEdit>)> = Vec::new();
+    for edit in edits {
+        // A deletion ending in a newline leaves the next line alone.
+        let last_byte = if source[..edit.end].ends_with('\n') && edit.end > edit.start {
+            edit.end - 1
+        } else {
+            edit.end
+        };
+        let (first, last) = (line_of(edit.start), line_of(last_byte));
+        match groups.last_mut() {
+            Some((_, end, group)) if first <= *end => {
+                *end = (*end).max(last);
+                group.push(edit);
+            }
+            _ => groups.push((first, last, vec![edit])),
+        }
+    }
+
+    groups
+        .into_iter()
+        .map(|(first, last, group)| {
+            let from = starts[first - 1];
+            let to = starts.get(last).map_or(source.len(), |&s| s);
+            let text = &source[from..to];
+            let shifted: Vec<TextEdit> = group
+                .iter()
+                .map(|e| TextEdit {
+                    start: e.start - from,
+                    end: e.end - from,
+                    replacement: e.replacement.clone(),
+                })
+                .collect();
+            let had_blank = text.lines().any(|l| l.trim().is_empty());
+            let lines = apply_edits(text, &shifted)
+                .lines()
+                .map(|l| l.trim_end().to_owned())
+                .filter(|l| had_blank || !l.is_empty())
+                .collect();
+            (first, last, lines)
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn edit(source: &str, removed: &str) -> TextEdit {
+        let start = source.find(removed).unwrap();
+        TextEdit {
+            start,
+            end: start + removed.len(),
+            replacement: String::new(),
+        }
+    }
+
+    #[test]
+    fn emptied_lines_are_dropped() {
+        let source = "fn f<T>(t: T)\nwhere\n    T: Clone,\n{\n}\n";
+        let joined = hunks(source, &[edit(source, "\nwhere\n    T: Clone,")]);
+        assert_eq!(joined, vec![(1, 3, vec!["fn f<T>(t: T)".to_owned()])]);
+        let deleted = hunks(source, &[edit(source, "where\n    T: Clone,\n")]);
+        assert_eq!(deleted, vec![(2, 3, Vec::new())]);
+    }
+
+    #[test]
+    fn edits_sharing_a_line_make_one_suggestion() {
+        let source = "fn f<T: Clone, U: Copy>(t: T, u: U) {}\n";
+        let found = hunks(source, &[edit(source, ": Clone"), edit(source, ": Copy")]);
+        assert_eq!(
+            found,
+            vec![(1, 1, vec!["fn f<T, U>(t: T, u: U) {}".to_owned()])]
+        );
+    }
+}
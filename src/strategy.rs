@@ -0,0 +1,234 @@
+// src/strategy.rs
+//! Named `prune` strategies: presets of how it orders, batches and screens its trials,
+//! picked with `prune --strategy` and adjusted in the `[strategy]` configuration table.
+//!
+//! | strategy   | trials | order    | batch size | prefilter | memo | unlock pass |
+//! |------------|--------|----------|------------|-----------|------|-------------|
+//! | `thorough` | yes    | priority | 1          | no        | no   | yes         |
+//! | `fast`     | yes    | priority | 8          | yes       | yes  | yes         |
+//! | `static`   | no     | -        | -          | -         | -    | -           |
+//! | `ci`       | yes    | source   | 8          | yes       | yes  | no          |
+//!
+//! `static` runs no trials: the CLI removes what `check --fix` would instead, so cargo
+//! never runs.
+
+#![deny(missing_docs)]
+
+use crate::config::Order;
+use serde::{Deserialize, Serialize};
+use std::fmt;
+
+/// A named strategy (see the [module docs](self)).
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+#[cfg_attr(feature = "cli", derive(clap::ValueEnum))]
+pub enum StrategyName {
+    /// Every candidate checked alone, then the unlock pass: the most checks, and the
+    /// plainest blame for each rejection.
+    Thorough,
+    /// Removals from up to 8 files share a check, bisected when it fails; bounds the body
+    /// plainly uses or rejected elsewhere in the same scope are kept without one.
+    Fast,
+    /// No trials: only the removals static rules prove, like `check --fix`.
+    Static,
+    /// `fast` in source order and without the unlock pass, whose suggestions change
+    /// nothing: reproducible runs for CI.
+    Ci,
+}
+
+impl StrategyName {
+    /// Stable identifier, e.g. `thorough`.
+    pub fn id(self) -> &'static str {
+        match self {
+            StrategyName::Thorough => "thorough",
+            StrategyName::Fast => "fast",
+            StrategyName::Static => "static",
+            StrategyName::Ci => "ci",
+        }
+    }
+}
+
+impl fmt::Display for StrategyName {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.id())
+    }
+}
+
+/// The settings a strategy stands for, once `[strategy]` has adjusted its preset.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Strategy {
+    /// The preset.
+    pub name: StrategyName,
+    /// Whether `prune` tries removals, as with `--brute-force`.
+    pub trials: bool,
+    /// The order files are pruned in.
+    pub order: Order,
+    /// Files whose removals share a check (see
+    /// [`WinnowerBuilder::batch_size`](crate::winnower::WinnowerBuilder::batch_size)).
+    pub batch_size: usize,
+    /// Keep bounds the body plainly uses without a check.
+    pub prefilter: bool,
+    /// Keep bounds whose removal was rejected elsewhere in the same impl or trait (see
+    /// [`WinnowerBuilder::assume_consistent`](crate::winnower::WinnowerBuilder::assume_consistent)).
+    pub assume_consistent: bool,
+    /// Retry retained bounds once the bounds they were blamed on have gone (see
+    /// [`Report::unlocks`](crate::report::Report::unlocks)).
+    pub unlocks: bool,
+}
+
+impl Strategy {
+    /// The preset `name` stands for.
+    pub const fn preset(name: StrategyName) -> Self {
+        let (trials, order, batch_size, prefilter, assume_consistent, unlocks) = match name {
+            StrategyName::Thorough => (true, Order::Priority, 1, false, false, true),
+            StrategyName::Fast => (true, Order::Priority, 8, true, true, true),
+            StrategyName::Static => (false, Order::Priority, 1, false, false, false),
+            StrategyName::Ci => (true, Order::Source, 8, true, true, false),
+        };
+        Self {
+            name,
+            trials,
+            order,
+            batch_size,
+            prefilter,
+            assume_consistent,
+            unlocks,
+        }
+    }
+
+    /// `self` with the settings `overrides` sets.
+    pub fn with(self, overrides: &StrategyOverrides) -> Self {
+        Self {
+            order: overrides.order.unwrap_or(self.order),
+            batch_size: overrides.batch_size.unwrap_or(self.batch_size).max(1),
+            prefilter: overrides.prefilter.unwrap_or(self.prefilter),
+            assume_consistent: overrides
+                .assume_consistent
+                .unwrap_or(self.assume_consistent),
+            unlocks: overrides.unlocks.unwrap_or(self.unlocks),
+            ..self
+        }
+    }
+}
+
+impl fmt::Display for Strategy {
+    /// `fast (priority order, batches of 8, prefilter, memo, unlock pass)`; `static` alone.
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if !self.trials {
+            return write!(f, "{} (no trials)", self.name);
+        }
+        let mut parts = vec![format!("{} order", self.order.id())];
+        parts.push(match self.batch_size {
+            1 => "one candidate per check".to_owned(),
+            n => format!("batches of {n}"),
+        });
+        for (on, part) in [
+            (self.prefilter, "prefilter"),
+            (self.assume_consistent, "memo"),
+            (self.unlocks, "unlock pass"),
+        ] {
+            if on {
+                parts.push(part.to_owned());
+            }
+        }
+        write!(f, "{} ({})", self.name, parts.join(", "))
+    }
+}
+
+/// Settings of one preset changed in `[strategy.<name>]`; those left out keep the
+/// preset's. Whether it runs trials is the preset's alone.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct StrategyOverrides {
+    /// The order files are pruned in.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub order: Option<Order>,
+    /// Files whose removals share a check.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub batch_size: Option<usize>,
+    /// Keep bounds the body plainly uses without a check.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub prefilter: Option<bool>,
+    /// Keep bounds whose removal was rejected elsewhere in the same scope.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub assume_consistent: Option<bool>,
+    /// Run the unlock pass.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub unlocks: Option<bool>,
+}
+
+impl StrategyOverrides {
+    /// Whether it changes nothing.
+    pub fn is_empty(&self) -> bool {
+        *self == Self::default()
+    }
+}
+
+/// The `[strategy]` table: a `[strategy.<name>]` table per preset to adjust.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct StrategyConfig {
+    /// Adjusts `thorough`.
+    #[serde(default, skip_serializing_if = "StrategyOverrides::is_empty")]
+    pub thorough: StrategyOverrides,
+    /// Adjusts `fast`.
+    #[serde(default, skip_serializing_if = "StrategyOverrides::is_empty")]
+    pub fast: StrategyOverrides,
+    /// Adjusts `static`.
+    #[serde(
+        default,
+        rename = "static",
+        skip_serializing_if = "StrategyOverrides::is_empty"
+    )]
+    pub static_only: StrategyOverrides,
+    /// Adjusts `ci`.
+    #[serde(default, skip_serializing_if = "StrategyOverrides::is_empty")]
+    pub ci: StrategyOverrides,
+}
+
+impl StrategyConfig {
+    /// Whether it adjusts no preset.
+    pub fn is_empty(&self) -> bool {
+        *self == Self::default()
+    }
+
+    /// The strategy `name` stands for with this configuration.
+    pub fn resolve(&self, name: StrategyName) -> Strategy {
+        let overrides = match name {
+            StrategyName::Thorough => &self.thorough,
+            StrategyName::Fast => &self.fast,
+            StrategyName::Static => &self.static_only,
+            StrategyName::Ci => &self.ci,
+        };
+        Strategy::preset(name).with(overrides)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn strategies_describe_their_settings() {
+        assert_eq!(
+            Strategy::preset(StrategyName::Fast).to_string(),
+            "fast (priority order, batches of 8, prefilter, memo, unlock pass)"
+        );
+        let config = StrategyConfig {
+            ci: StrategyOverrides {
+                batch_size: Some(0),
+                prefilter: Some(false),
+                ..StrategyOverrides::default()
+            },
+            ..StrategyConfig::default()
+        };
+        assert_eq!(
+            config.resolve(StrategyName::Ci).to_string(),
+            "ci (source order, one candidate per check, memo)"
+        );
+        assert_eq!(
+            Strategy::preset(StrategyName::Static).to_string(),
+            "static (no trials)"
+        );
+    }
+}
@@ -3,11 +3,32 @@
 
 #![deny(missing_docs)]
 
-use crate::error::TraitError;
-use anyhow::{Context, bail};
+use crate::error::{TraitError, WinnowerError};
 use std::fs;
 use std::path::PathBuf;
 
+/// Target types for pruning trait bounds.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "cli", derive(clap::ValueEnum))]
+pub enum TargetType {
+    /// Prune all types of trait bounds (default).
+    All,
+    /// Prune function trait bounds.
+    Function,
+    /// Prune impl trait bounds.
+    Impl,
+    /// Prune trait trait bounds.
+    Trait,
+    /// Prune trait method trait bounds.
+    TraitMethod,
+    /// Prune impl method trait bounds.
+    ImplMethod,
+    /// Prune enum trait bounds.
+    Enum,
+    /// Prune struct trait bounds.
+    Struct,
+}
+
 /// The classification of a target path.
 #[derive(Debug)]
 pub enum TargetKind {
@@ -23,22 +44,27 @@ impl TargetKind {
     /// Resolve the user-provided target (file or directory).
     pub fn get_target(raw: Option<PathBuf>) -> TraitError<TargetKind> {
         let path = raw.unwrap_or_else(|| PathBuf::from("."));
-        let meta =
-            fs::metadata(&path).with_context(|| format!("target not found: {}", path.display()))?;
+        let meta = fs::metadata(&path).map_err(|e| WinnowerError::Target {
+            path: path.clone(),
+            detail: format!("target not found ({e})"),
+        })?;
 
         if meta.is_file() {
             if path.extension().and_then(|s| s.to_str()) != Some("rs") {
-                bail!("single-file mode requires a .rs file: {}", path.display());
+                return Err(WinnowerError::Target {
+                    path,
+                    detail: "single-file mode requires a .rs file".into(),
+                });
             }
             return Ok(TargetKind::SingleFile(path));
         }
 
         let cargo = path.join("Cargo.toml");
         if !cargo.exists() {
-            bail!(
-                "no Cargo.toml in {}. Provide a crate root or a single Rust file",
-                path.display()
-            );
+            return Err(WinnowerError::Target {
+                path,
+                detail: "no Cargo.toml; provide a crate root or a single Rust file".into(),
+            });
         }
         let toml = fs::read_to_string(&cargo).unwrap_or_default();
         if toml.contains("[workspace]") {
@@ -47,4 +73,11 @@ impl TargetKind {
             Ok(TargetKind::Crate(path))
         }
     }
+
+    /// The file or directory this target was resolved from.
+    pub fn path(&self) -> &std::path::Path {
+        match self {
+            TargetKind::SingleFile(p) | TargetKind::Crate(p) | TargetKind::Workspace(p) => p,
+        }
+    }
 }
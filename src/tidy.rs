@@ -0,0 +1,366 @@
+// src/tidy.rs
+//! `prune --tidy-imports`: drop the imports removed bounds leave unused, e.g.
+//! `use serde::Serialize;` once `T: Serialize` is gone.
+//!
+//! The names looked for are those in the removed bounds and in the `unused_imports` lints
+//! their checks tolerated (see [`Removal::follow_ups`]). An import of one goes when nothing
+//! else in the file mentions the name, macro input and doc comments included: a plain
+//! `use a::B;` as a whole, a name in a group `use a::{B, C};` alone. Renames, globs, names in
+//! nested groups and `pub use` re-exports are left alone. The scan is syntactic, and a trait
+//! imported for its methods is used without being named, so `prune` validates each import
+//! dropped and puts it back if the check fails.
+
+#![deny(missing_docs)]
+
+use crate::dynamic_analysis::text_edit::{ByteOffsets, TextEdit, run_ranges};
+use crate::report::{Removal, RemovalStatus};
+use proc_macro2::{TokenStream, TokenTree};
+use quote::ToTokens;
+use std::collections::{BTreeSet, HashMap};
+use syn::visit::Visit;
+use syn::{ItemUse, UseGroup, UseTree, Visibility};
+
+/// An import of a name looked for that nothing else in its file mentions.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct UnusedImport {
+    /// The path imported, e.g. `serde::Serialize`.
+    pub path: String,
+    /// 1-based line of the imported name.
+    pub line: usize,
+    /// The edit dropping it: the whole `use` item, or the name from its group.
+    pub edit: TextEdit,
+}
+
+/// The names the bounds `removals` removed may have been the last users of: the identifiers
+/// of each bound, and the imports the `unused_imports` lints among its follow-ups name.
+pub fn removed_names<'a>(removals: impl IntoIterator<Item = &'a Removal>) -> BTreeSet<String> {
+    let mut names = BTreeSet::new();
+    for r in removals
+        .into_iter()
+        .filter(|r| r.status == RemovalStatus::Removed)
+    {
+        if let Ok(tokens) = r.finding.bound.parse::<TokenStream>() {
+            idents(tokens, &mut names);
+        }
+        for u in r.follow_ups.iter().filter(|u| u.lint == "unused_imports") {
+            names.extend(imported_names(&u.message).map(str::to_owned));
+        }
+    }
+    names
+}
+
+/// The last segments of the paths quoted in a lint message, e.g. `Debug` and `Display` in
+/// ``unused imports: `std::fmt::Debug` and `Display` ``.
+pub fn imported_names(message: &str) -> impl Iterator<Item = &str> {
+    message
+        .split('`')
+        .skip(1)
+        .step_by(2)
+        .filter_map(|path| path.rsplit("::").next())
+}
+
+fn idents(tokens: TokenStream, out: &mut BTreeSet<String>) {
+    for tt in tokens {
+        match tt {
+            TokenTree::Ident(i) => {
+                out.insert(i.to_string());
+            }
+            TokenTree::Group(g) => idents(g.stream(), out),
+            _ => {}
+        }
+    }
+}
+
+/// The imports of `names` in `source` that nothing else in the file mentions, in file
+/// order. Each edit applies to `source` on its own.
+pub fn unused_imports(source: &str, names: &BTreeSet<String>) -> syn::Result<Vec<UnusedImport>> {
+    let file = syn::parse_file(source)?;
+    let mut uses = Uses::default();
+    uses.visit_file(&file);
+    let mut mentions = HashMap::new();
+    count_mentions(file.to_token_stream(), &mut mentions);
+    // What an import brings in is not a use of it; a path through it is.
+    for item in &uses.0 {
+        leaves(&item.tree, &mut |ident| {
+            *mentions.entry(ident.to_string()).or_insert(0) -= 1;
+        });
+    }
+    let unused = |ident: &syn::Ident| {
+        let name = ident.to_string();
+        name != "self" && names.contains(&name) && mentions.get(&name).is_none_or(|&n| n <= 0)
+    };
+    let offsets = ByteOffsets::new(source);
+    let mut out = Vec::new();
+    for item in uses.0 {
+        if !matches!(item.vis, Visibility::Inherited) {
+            continue;
+        }
+        let mut prefix = Vec::new();
+        let mut tree = &item.tree;
+        while let UseTree::Path(p) = tree {
+            prefix.push(p.ident.to_string());
+            tree = &p.tree;
+        }
+        let path = |ident: &syn::Ident| {
+            let mut segments = prefix.clone();
+            segments.push(ident.to_string());
+            segments.join("::")
+        };
+        match tree {
+            UseTree::Name(n) if unused(&n.ident) => out.push(UnusedImport {
+                path: path(&n.ident),
+                line: n.ident.span().start().line,
+                edit: whole_item(source, &offsets, item),
+            }),
+            UseTree::Group(group) => {
+                for (i, tree) in group.items.iter().enumerate() {
+                    if let UseTree::Name(n) = tree
+                        && unused(&n.ident)
+                    {
+                        out.push(UnusedImport {
+                            path: path(&n.ident),
+                            line: n.ident.span().start().line,
+                            edit: group_edit(source, &offsets, item, group, i),
+                        });
+                    }
+                }
+            }
+            _ => {}
+        }
+    }
+    Ok(out)
+}
+
+/// Every `use` item of a file, at any depth.
+#[derive(Default)]
+struct Uses<'a>(Vec<&'a ItemUse>);
+
+impl<'a> Visit<'a> for Uses<'a> {
+    fn visit_item_use(&mut self, item: &'a ItemUse) {
+        self.0.push(item);
+    }
+}
+
+/// Call `f` with each name `tree` brings in under its own name.
+fn leaves(tree: &UseTree, f: &mut impl FnMut(&syn::Ident)) {
+    match tree {
+        UseTree::Path(p) => leaves(&p.tree, f),
+        UseTree::Name(n) => f(&n.ident),
+        UseTree::Group(g) => g.items.iter().for_each(|t| leaves(t, f)),
+        UseTree::Rename(_) | UseTree::Glob(_) => {}
+    }
+}
+
+/// Count the identifiers of `tokens`, and the words of their literals, doc comments among
+/// them: an intra-doc link to a trait needs its import too.
+fn count_mentions(tokens: TokenStream, out: &mut HashMap<String, isize>) {
+    for tt in tokens {
+        match tt {
+            TokenTree::Ident(i) => *out.entry(i.to_string()).or_insert(0) += 1,
+            TokenTree::Group(g) => count_mentions(g.stream(), out),
+            TokenTree::Literal(l) => {
+                let text = l.to_string();
+                let words = text
+                    .split(|c: char| !(c.is_alphanumeric() || c == '_'))
+                    .filter(|w| !w.is_empty());
+                for word in words {
+                    *out.entry(word.to_owned()).or_insert(0) += 1;
+                }
+            }
+            TokenTree::Punct(_) => {}
+        }
+    }
+}
+
+/// The edit deleting `item`, with its line when nothing else is on it.
+fn whole_item(source: &str, offsets: &ByteOffsets<'_>, item: &ItemUse) -> TextEdit {
+    let mut start = offsets.start(item);
+    let mut end = offsets.end(item);
+    let line_start = source[..start].rfind('\n').map_or(0, |i| i + 1);
+    let rest = &source[end..];
+    let line_end = rest.find('\n').map_or(source.len(), |i| end + i + 1);
+    if source[line_start..start].trim().is_empty() && source[end..line_end].trim().is_empty() {
+        start = line_start;
+        end = line_end;
+    }
+    TextEdit {
+        start,
+        end,
+        replacement: String::new(),
+    }
+}
+
+/// The edit dropping element `index` of `group`, the tree `item` ends in: the whole item
+/// when it is the only one, the braces too when one name is left.
+fn group_edit(
+    source: &str,
+    offsets: &ByteOffsets<'_>,
+    item: &ItemUse,
+    group: &UseGroup,
+    index: usize,
+) -> TextEdit {
+    if group.items.len() == 1 {
+        return whole_item(source, offsets, item);
+    }
+    if group.items.len() == 2 {
+        let other = &group.items[1 - index];
+        if !matches!(other, UseTree::Name(n) if n.ident == "self") {
+            return TextEdit {
+                start: offsets.start(group),
+                end: offsets.end(group),
+                replacement: source[offsets.start(other)..offsets.end(other)].to_owned(),
+            };
+        }
+    }
+    let (start, end) = run_ranges(&group.items, &[index], offsets)[0];
+    TextEdit {
+        start,
+        end,
+        replacement: String::new(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::dynamic_analysis::common::FollowUp;
+    use crate::dynamic_analysis::text_edit::apply_edits;
+    use crate::report::Finding;
+    use std::time::Duration;
+
+    /// `src` with each unused import of `names` dropped on its own.
+    fn tidied(src: &str, names: &[&str]) -> Vec<(String, String)> {
+        let names = names.iter().map(|&n| n.to_owned()).collect();
+        unused_imports(src, &names)
+            .expect("parses")
+            .into_iter()
+            .map(|u| (u.path, apply_edits(src, &[u.edit])))
+            .collect()
+    }
+
+    #[test]
+    fn a_plain_import_goes_with_its_line() {
+        let src = "use std::fmt;\nuse serde::Serialize;\n\nfn f<T>() {}\n";
+        assert_eq!(
+            tidied(src, &["Serialize"]),
+            [(
+                "serde::Serialize".to_owned(),
+                "use std::fmt;\n\nfn f<T>() {}\n".to_owned()
+            )]
+        );
+    }
+
+    #[test]
+    fn a_name_goes_from_its_group() {
+        let src = "use std::fmt::{Debug, Display, Write};\n";
+        let out = tidied(src, &["Debug", "Display", "Write"]);
+        let texts: Vec<&str> = out.iter().map(|(_, t)| t.as_str()).collect();
+        assert_eq!(
+            texts,
+            [
+                "use std::fmt::{Display, Write};\n",
+                "use std::fmt::{Debug, Write};\n",
+                "use std::fmt::{Debug, Display};\n",
+            ]
+        );
+        assert_eq!(out[0].0, "std::fmt::Debug");
+
+        assert_eq!(
+            tidied("use std::fmt::{Debug, Display};\n", &["Debug"])[0].1,
+            "use std::fmt::Display;\n"
+        );
+        assert_eq!(
+            tidied("use std::fmt::{self, Debug};\n", &["Debug"])[0].1,
+            "use std::fmt::{self};\n"
+        );
+        assert_eq!(
+            tidied("use std::fmt::{Debug};\nfn f() {}\n", &["Debug"])[0].1,
+            "fn f() {}\n"
+        );
+    }
+
+    #[test]
+    fn renames_globs_nested_groups_and_reexports_stay() {
+        for src in [
+            "use std::fmt::Debug as D;\n",
+            "use std::fmt::*;\n",
+            "use std::{fmt::{Debug, Display}, io};\n",
+            "use std::{fmt::Debug, io};\n",
+            "pub use std::fmt::Debug;\n",
+            "pub(crate) use std::fmt::Debug;\n",
+        ] {
+            assert_eq!(tidied(src, &["Debug"]), [], "{src}");
+        }
+    }
+
+    #[test]
+    fn any_other_mention_keeps_an_import() {
+        for src in [
+            "use std::fmt::Debug;\nfn f(x: &dyn Debug) {}\n",
+            "use serde::Serialize;\n#[derive(Serialize)]\nstruct S;\n",
+            "use std::fmt::Debug;\n/// Shown with [`Debug`].\nstruct S;\n",
+            "use std::fmt::Debug;\nmacro_rules! m { () => { impl Debug for S {} } }\n",
+        ] {
+            assert_eq!(tidied(src, &["Debug", "Serialize"]), [], "{src}");
+        }
+        // A path through an import uses it.
+        let out = tidied("use std::fmt;\nuse fmt::Debug;\n", &["Debug", "fmt"]);
+        assert_eq!(out.len(), 1);
+        assert_eq!(
+            out[0],
+            ("fmt::Debug".to_owned(), "use std::fmt;\n".to_owned())
+        );
+        // Only names looked for go, and imports inside functions are found too.
+        let src = "fn f() {\n    use std::fmt::Debug;\n    use std::io::Write;\n}\n";
+        let out = tidied(src, &["Debug"]);
+        assert_eq!(out.len(), 1);
+        assert_eq!(out[0].1, "fn f() {\n    use std::io::Write;\n}\n");
+    }
+
+    fn removal(bound: &str, status: RemovalStatus) -> Removal {
+        Removal {
+            finding: Finding {
+                bound: bound.into(),
+                ..Finding::default()
+            },
+            status,
+            before: String::new(),
+            after: String::new(),
+            check_time: Duration::ZERO,
+            write_time: Duration::ZERO,
+            scope: Vec::new(),
+            batch: None,
+            aborted: false,
+            cached: false,
+            blames: Vec::new(),
+            test_only: false,
+            slowdown: None,
+            follow_ups: Vec::new(),
+        }
+    }
+
+    #[test]
+    fn names_come_from_bounds_and_lint_messages() {
+        let mut linted = removal("Display", RemovalStatus::Removed);
+        linted.follow_ups.push(FollowUp {
+            file: "src/lib.rs".into(),
+            line: 1,
+            column: 5,
+            lint: "unused_imports".into(),
+            message: "unused import: `std::io::Write`".into(),
+        });
+        let removals = [
+            removal("serde::Serialize", RemovalStatus::Removed),
+            removal("std::fmt::Debug", RemovalStatus::Retained),
+            linted,
+        ];
+        assert_eq!(
+            removed_names(&removals).into_iter().collect::<Vec<_>>(),
+            ["Display", "Serialize", "Write", "serde"]
+        );
+        assert_eq!(
+            imported_names("unused imports: `std::fmt::Debug` and `Display`").collect::<Vec<_>>(),
+            ["Debug", "Display"]
+        );
+    }
+}
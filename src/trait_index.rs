@@ -0,0 +1,238 @@
+// src/trait_index.rs
+//! The traits a workspace defines, by name, with their plain supertraits.
+//!
+//! Built from the `trait` items of every discovered source file and the packages `cargo
+//! metadata` lists, it tells traits of the workspace from those of its dependencies (see
+//! [`TraitIndex::is_local`]) for the `foreign_trait_impls` policy, and gives the
+//! [`implied-by-supertrait`](crate::findings::Rule::ImpliedBySupertrait) rule the
+//! supertraits of local traits.
+
+#![deny(missing_docs)]
+
+use crate::analysis::ItemRef;
+use quote::ToTokens;
+use std::collections::{BTreeMap, BTreeSet};
+use std::path::PathBuf;
+use syn::visit::Visit;
+use syn::{TraitBoundModifier, TypeParamBound};
+
+/// Crates no workspace package can be.
+const STD_CRATES: &[&str] = &["std", "core", "alloc"];
+
+/// Traits defined in the workspace and the crates its packages build.
+#[derive(Debug, Clone, Default)]
+pub struct TraitIndex {
+    /// Trait names, with the names of their plain supertraits, `where Self: Trait`
+    /// predicates included; a name declared more than once has the supertraits of all its
+    /// declarations.
+    traits: BTreeMap<String, Vec<String>>,
+    /// Names more than one trait is declared with.
+    ambiguous: BTreeSet<String>,
+    /// Crate names of the workspace packages, as paths spell them (`my_lib`).
+    crates: BTreeSet<String>,
+}
+
+impl TraitIndex {
+    /// The traits declared anywhere in `file`, nested modules included.
+    pub fn of_file(file: &syn::File) -> Self {
+        let mut index = Self::default();
+        index.add_file(file);
+        index
+    }
+
+    /// The traits declared in `files`, which belong to `packages`. Files that cannot be
+    /// read or parsed add nothing; analyzing them reports why.
+    pub fn of_files<'p>(files: &[PathBuf], packages: impl IntoIterator<Item = &'p str>) -> Self {
+        let mut index = Self::default();
+        for path in files {
+            let Ok(source) = std::fs::read_to_string(path) else {
+                continue;
+            };
+            if let Ok(file) = syn::parse_file(&source) {
+                index.add_file(&file);
+            }
+        }
+        index.crates = packages.into_iter().map(|p| p.replace('-', "_")).collect();
+        index
+    }
+
+    /// Add the traits declared in `file`.
+    pub fn add_file(&mut self, file: &syn::File) {
+        self.visit_file(file);
+    }
+
+    /// Trait names and their plain supertraits, by name.
+    pub fn traits(&self) -> impl Iterator<Item = (&str, &[String])> {
+        self.traits.iter().map(|(n, s)| (n.as_str(), s.as_slice()))
+    }
+
+    /// Whether a trait called `name` is defined in the workspace.
+    pub fn contains(&self, name: &str) -> bool {
+        self.traits.contains_key(name)
+    }
+
+    /// Whether more than one trait called `name` is defined, so a bound naming it may not
+    /// have all the supertraits indexed for the name.
+    pub fn is_ambiguous(&self, name: &str) -> bool {
+        self.ambiguous.contains(name)
+    }
+
+    /// Whether `path`, as an impl names its trait, names a trait of the workspace: paths
+    /// from `crate`, `self`, `super` or a workspace package are; those from `std`, `core`,
+    /// `alloc` or another crate (`::serde::Serialize`) are not; anything else, like
+    /// `Display` or `fmt::Display`, is by its last segment. A local trait sharing a name
+    /// with an imported one hides it.
+    pub fn is_local(&self, path: &syn::Path) -> bool {
+        let (Some(first), Some(last)) = (path.segments.first(), path.segments.last()) else {
+            return false;
+        };
+        let first = first.ident.to_string();
+        if self.crates.contains(&first) && (path.leading_colon.is_some() || path.segments.len() > 1)
+        {
+            return true;
+        }
+        if path.leading_colon.is_some() || STD_CRATES.contains(&first.as_str()) {
+            return false;
+        }
+        matches!(first.as_str(), "crate" | "self" | "super")
+            || self.contains(&last.ident.to_string())
+    }
+
+    /// The trait `item` implements, as tokens, when it is an impl block of a trait the
+    /// workspace does not define, e.g. `serde :: Serialize`.
+    pub fn foreign_trait(&self, item: &ItemRef<'_>) -> Option<String> {
+        let ItemRef::Impl(im) = item else {
+            return None;
+        };
+        let (_, path, _) = im.trait_.as_ref()?;
+        (!self.is_local(path)).then(|| path.to_token_stream().to_string())
+    }
+}
+
+impl<'ast> Visit<'ast> for TraitIndex {
+    fn visit_item_trait(&mut self, t: &'ast syn::ItemTrait) {
+        // `where Self: Trait` is a supertrait spelled differently.
+        let self_bounds = t
+            .generics
+            .where_clause
+            .iter()
+            .flat_map(|w| &w.predicates)
+            .filter_map(|p| match p {
+                syn::WherePredicate::Type(p)
+                    if matches!(&p.bounded_ty, syn::Type::Path(ty)
+                        if ty.qself.is_none() && ty.path.is_ident("Self")) =>
+                {
+                    Some(&p.bounds)
+                }
+                _ => None,
+            })
+            .flatten();
+        let mut supers: Vec<String> = Vec::new();
+        for name in t
+            .supertraits
+            .iter()
+            .chain(self_bounds)
+            .filter_map(plain_trait)
+        {
+            if !supers.contains(&name) {
+                supers.push(name);
+            }
+        }
+        let name = t.ident.to_string();
+        match self.traits.get_mut(&name) {
+            Some(known) => {
+                for s in supers {
+                    if !known.contains(&s) {
+                        known.push(s);
+                    }
+                }
+                self.ambiguous.insert(name);
+            }
+            None => {
+                self.traits.insert(name, supers);
+            }
+        }
+        syn::visit::visit_item_trait(self, t);
+    }
+}
+
+/// The name of a trait bound without modifiers, binders or generic arguments.
+pub(crate) fn plain_trait(bound: &TypeParamBound) -> Option<String> {
+    let TypeParamBound::Trait(t) = bound else {
+        return None;
+    };
+    let last = t.path.segments.last()?;
+    (last.arguments.is_none())
+        .then(|| trait_name(bound))
+        .flatten()
+}
+
+/// The name of a trait bound without modifiers or binders, whatever its generic
+/// arguments: `Tagged` for `Tagged<u8>`.
+pub(crate) fn trait_name(bound: &TypeParamBound) -> Option<String> {
+    let TypeParamBound::Trait(t) = bound else {
+        return None;
+    };
+    if !matches!(t.modifier, TraitBoundModifier::None) || t.lifetimes.is_some() {
+        return None;
+    }
+    Some(t.path.segments.last()?.ident.to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::error::TraitError;
+
+    #[test]
+    fn paths_are_local_by_crate_or_by_name() -> TraitError<()> {
+        let file = syn::parse_file(
+            "trait Shape: Named + Clone {}\nmod inner { pub trait Named {} }\nmod shadow { trait Shape {} }",
+        )?;
+        let mut index = TraitIndex::of_file(&file);
+        index.crates.insert("my_lib".into());
+        assert_eq!(
+            index.traits().collect::<Vec<_>>(),
+            [
+                ("Named", &[][..]),
+                ("Shape", &["Named".to_owned(), "Clone".to_owned()][..])
+            ]
+        );
+        let local = |path: &str| -> TraitError<bool> { Ok(index.is_local(&syn::parse_str(path)?)) };
+        for path in [
+            "Shape",
+            "inner::Named",
+            "crate::inner::Named",
+            "super::Other",
+            "my_lib::Tr",
+        ] {
+            assert!(local(path)?, "{path}");
+        }
+        for path in [
+            "Display",
+            "fmt::Display",
+            "std::fmt::Debug",
+            "::serde::Serialize",
+        ] {
+            assert!(!local(path)?, "{path}");
+        }
+        assert!(local("::my_lib::Tr")?);
+        // `std` is never a workspace crate, whatever the workspace calls its traits.
+        assert!(!local("std::ops::Shape")?);
+        assert!(index.is_ambiguous("Shape") && !index.is_ambiguous("Named"));
+        Ok(())
+    }
+
+    #[test]
+    fn self_predicates_are_supertraits() -> TraitError<()> {
+        let file = syn::parse_file(
+            "trait Sub: Super where Self: Clone + Super, Self::Out: Debug { type Out; }",
+        )?;
+        let index = TraitIndex::of_file(&file);
+        assert_eq!(
+            index.traits().collect::<Vec<_>>(),
+            [("Sub", &["Super".to_owned(), "Clone".to_owned()][..])]
+        );
+        Ok(())
+    }
+}
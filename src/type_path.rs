@@ -0,0 +1,187 @@
+// src/type_path.rs
+//! Positions of types nested in an item's declaration.
+//!
+//! A [`TypePath`] starts at a type the item declares outright (its return type, an
+//! argument's type, a field's type) and descends through the types written inside it,
+//! [`nested`] giving the order. In `fn f(x: Vec<Box<dyn Error + Send>>)` the trait object is
+//! at `arg 0` then `0` (`Box<..>` in `Vec<..>`) then `0` (`dyn ..` in `Box<..>`).
+
+#![deny(missing_docs)]
+
+use serde::{Deserialize, Serialize};
+use syn::{GenericArgument, PathArguments, ReturnType, Type, TypeParamBound};
+
+/// The declared type a [`TypePath`] starts from.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum TypeRoot {
+    /// The return type of a function or method.
+    #[default]
+    Return,
+    /// The type of the argument at this index, counting a `self` receiver.
+    Arg(usize),
+    /// The type of the field at this index of a struct.
+    Field(usize),
+}
+
+/// Where a type sits in an item's declaration: its [`TypeRoot`], then the index among
+/// [`nested`] types of each step down.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub struct TypePath {
+    /// The declared type the path starts from.
+    pub root: TypeRoot,
+    /// Indices into [`nested`], outermost first; empty for the root itself.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub steps: Vec<usize>,
+}
+
+impl std::fmt::Display for TypePath {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self.root {
+            TypeRoot::Return => f.write_str("return")?,
+            TypeRoot::Arg(i) => write!(f, "arg {i}")?,
+            TypeRoot::Field(i) => write!(f, "field {i}")?,
+        }
+        for step in &self.steps {
+            write!(f, ".{step}")?;
+        }
+        Ok(())
+    }
+}
+
+impl TypePath {
+    /// The path of the type nested in this one at `index`.
+    pub fn child(&self, index: usize) -> Self {
+        let mut steps = self.steps.clone();
+        steps.push(index);
+        Self {
+            root: self.root,
+            steps,
+        }
+    }
+
+    /// The type this path leads to from `root`, the type at [`TypePath::root`].
+    pub fn resolve<'a>(&self, root: &'a Type) -> Option<&'a Type> {
+        self.steps
+            .iter()
+            .try_fold(root, |ty, &step| nested(ty).into_iter().nth(step))
+    }
+
+    /// Like [`TypePath::resolve`], mutably.
+    pub fn resolve_mut<'a>(&self, root: &'a mut Type) -> Option<&'a mut Type> {
+        self.steps
+            .iter()
+            .try_fold(root, |ty, &step| nested_mut(ty).into_iter().nth(step))
+    }
+}
+
+/// The type a function returns, unless it is `()` by omission.
+pub fn return_type(output: &ReturnType) -> Option<&Type> {
+    match output {
+        ReturnType::Type(_, ty) => Some(ty),
+        ReturnType::Default => None,
+    }
+}
+
+macro_rules! define_nested {
+    ($name:ident, $($mut_:ident)?) => {
+        /// The types written directly inside `ty`, in source order: element types, generic
+        /// and associated type arguments, `Fn(..) -> ..` arguments and outputs, also in the
+        /// bounds of `impl Trait` and `dyn Trait`.
+        pub fn $name(ty: &$($mut_)? Type) -> Vec<&$($mut_)? Type> {
+            let mut out = Vec::new();
+            let mut bounds = Vec::new();
+            match ty {
+                Type::Array(t) => out.push(&$($mut_)? *t.elem),
+                Type::Group(t) => out.push(&$($mut_)? *t.elem),
+                Type::Paren(t) => out.push(&$($mut_)? *t.elem),
+                Type::Ptr(t) => out.push(&$($mut_)? *t.elem),
+                Type::Reference(t) => out.push(&$($mut_)? *t.elem),
+                Type::Slice(t) => out.push(&$($mut_)? *t.elem),
+                Type::Tuple(t) => out.extend(&$($mut_)? t.elems),
+                Type::BareFn(t) => {
+                    out.extend((&$($mut_)? t.inputs).into_iter().map(|a| &$($mut_)? a.ty));
+                    if let ReturnType::Type(_, output) = &$($mut_)? t.output {
+                        out.push(&$($mut_)? **output);
+                    }
+                }
+                Type::Path(t) => bounds.push(&$($mut_)? t.path),
+                Type::ImplTrait(t) => bounds.extend(
+                    (&$($mut_)? t.bounds).into_iter().filter_map(|b| match b {
+                        TypeParamBound::Trait(tb) => Some(&$($mut_)? tb.path),
+                        _ => None,
+                    }),
+                ),
+                Type::TraitObject(t) => bounds.extend(
+                    (&$($mut_)? t.bounds).into_iter().filter_map(|b| match b {
+                        TypeParamBound::Trait(tb) => Some(&$($mut_)? tb.path),
+                        _ => None,
+                    }),
+                ),
+                _ => {}
+            }
+            for path in bounds {
+                for segment in &$($mut_)? path.segments {
+                    match &$($mut_)? segment.arguments {
+                        PathArguments::AngleBracketed(args) => {
+                            for arg in &$($mut_)? args.args {
+                                match arg {
+                                    GenericArgument::Type(t) => out.push(t),
+                                    GenericArgument::AssocType(a) => out.push(&$($mut_)? a.ty),
+                                    _ => {}
+                                }
+                            }
+                        }
+                        PathArguments::Parenthesized(args) => {
+                            out.extend(&$($mut_)? args.inputs);
+                            if let ReturnType::Type(_, output) = &$($mut_)? args.output {
+                                out.push(&$($mut_)? **output);
+                            }
+                        }
+                        PathArguments::None => {}
+                    }
+                }
+            }
+            out
+        }
+    };
+}
+
+define_nested!(nested,);
+define_nested!(nested_mut, mut);
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use quote::ToTokens;
+
+    fn at(root: &Type, steps: &[usize]) -> Option<String> {
+        let path = TypePath {
+            root: TypeRoot::Arg(0),
+            steps: steps.to_vec(),
+        };
+        path.resolve(root).map(|t| t.to_token_stream().to_string())
+    }
+
+    #[test]
+    fn paths_lead_through_nested_types() -> syn::Result<()> {
+        let mut root: Type = syn::parse_str(
+            "(Vec<Box<dyn Error + Send>>, impl Iterator<Item = &'static dyn Fn(u8) -> u8>)",
+        )?;
+        assert_eq!(at(&root, &[0, 0, 0]).as_deref(), Some("dyn Error + Send"));
+        assert_eq!(at(&root, &[1, 0, 0]).as_deref(), Some("dyn Fn (u8) -> u8"));
+        assert_eq!(at(&root, &[1, 0, 0, 1]).as_deref(), Some("u8"));
+        assert_eq!(at(&root, &[2]), None);
+
+        let path = TypePath {
+            root: TypeRoot::Arg(0),
+            steps: vec![0, 0, 0],
+        };
+        assert_eq!(path.to_string(), "arg 0.0.0.0");
+        if let Some(ty) = path.resolve_mut(&mut root) {
+            *ty = syn::parse_str("dyn Send")?;
+        }
+        assert_eq!(at(&root, &[0, 0, 0]).as_deref(), Some("dyn Send"));
+        Ok(())
+    }
+}
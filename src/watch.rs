@@ -0,0 +1,266 @@
+// src/watch.rs
+//! Re-checking files as they are saved, for `check --watch`.
+//!
+//! [`watch`] checks every file [`Winnower::files`] discovers, then re-checks each one the
+//! file system reports changed, with the static rules only: no cargo runs. Changes arrive
+//! in batches, once no event came for the debounce delay. A batch naming a path outside
+//! the discovered set, or one that is gone, refreshes discovery first, so new and deleted
+//! files and directories are picked up.
+
+#![deny(missing_docs)]
+
+use crate::cancel::CancelToken;
+use crate::error::{TraitError, WinnowerError};
+use crate::report::FileReport;
+use crate::target::TargetKind;
+use crate::winnower::Winnower;
+use notify::event::{AccessKind, AccessMode};
+use notify::{EventKind, RecursiveMode, Watcher};
+use std::collections::{BTreeMap, BTreeSet};
+use std::path::{Path, PathBuf};
+use std::sync::mpsc::{Receiver, RecvTimeoutError, channel};
+use std::time::Duration;
+
+/// How long the file system must stay quiet before a batch of changes is checked.
+pub const DEBOUNCE: Duration = Duration::from_millis(200);
+
+/// How often cancellation is polled while waiting for changes.
+const TICK: Duration = Duration::from_millis(100);
+
+/// Directories whose changes never concern the sources.
+const IGNORED: [&str; 2] = ["target", ".git"];
+
+/// What [`watch`] reports. Paths are relative to the target's root.
+#[derive(Debug)]
+pub enum WatchEvent<'a> {
+    /// Every discovered file was checked once.
+    Ready,
+    /// A file was checked.
+    Checked {
+        /// Its findings.
+        report: &'a FileReport,
+        /// Its text.
+        source: &'a str,
+    },
+    /// A file is gone, or no longer discovered.
+    Removed {
+        /// The file.
+        path: &'a Path,
+    },
+    /// A file could not be checked, typically half-edited; its last findings still count.
+    Failed {
+        /// The file.
+        path: &'a Path,
+        /// Why.
+        error: &'a WinnowerError,
+    },
+}
+
+/// The findings of the watched files, as of an event.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct WatchSummary {
+    /// Files watched.
+    pub files: usize,
+    /// Findings across them.
+    pub findings: usize,
+}
+
+/// Watched files by canonical path: the path as discovered, and its finding count once
+/// checked.
+#[derive(Default)]
+struct Watched {
+    root: PathBuf,
+    files: BTreeMap<PathBuf, (PathBuf, Option<usize>)>,
+}
+
+impl Watched {
+    fn summary(&self) -> WatchSummary {
+        WatchSummary {
+            files: self.files.len(),
+            findings: self.files.values().filter_map(|(_, n)| *n).sum(),
+        }
+    }
+
+    fn rel<'p>(&self, path: &'p Path) -> &'p Path {
+        path.strip_prefix(&self.root).unwrap_or(path)
+    }
+}
+
+/// `path` made absolute and free of symlinks; for a path that is gone, as far as its
+/// parent still exists.
+fn canonical(path: &Path) -> PathBuf {
+    if let Ok(p) = path.canonicalize() {
+        return p;
+    }
+    match (path.parent(), path.file_name()) {
+        (Some(parent), Some(name)) => canonical(parent).join(name),
+        _ => path.to_path_buf(),
+    }
+}
+
+fn watch_error(root: &Path, e: notify::Error) -> WinnowerError {
+    WinnowerError::Target {
+        path: root.to_path_buf(),
+        detail: format!("watching for changes: {e}"),
+    }
+}
+
+/// Check the files of `winnower`'s target, then re-check them as they change, calling
+/// `on_event` with each event and the findings it leaves. Returns once `cancel` fires;
+/// errors of `on_event` stop the watch.
+pub fn watch(
+    winnower: &Winnower,
+    debounce: Duration,
+    cancel: &CancelToken,
+    mut on_event: impl FnMut(&WatchEvent<'_>, WatchSummary) -> TraitError<()>,
+) -> TraitError<()> {
+    // Report paths are relative to this: the crate or workspace, or a single file's directory.
+    let base = match winnower.kind() {
+        TargetKind::SingleFile(p) => p.parent().unwrap_or(Path::new("")),
+        kind => kind.path(),
+    };
+    let root = if base.as_os_str().is_empty() {
+        Path::new(".")
+    } else {
+        base
+    };
+    let (tx, rx) = channel();
+    let mut watcher = notify::recommended_watcher(tx).map_err(|e| watch_error(root, e))?;
+    watcher
+        .watch(root, RecursiveMode::Recursive)
+        .map_err(|e| watch_error(root, e))?;
+    let ignored: Vec<PathBuf> = IGNORED.iter().map(|d| canonical(&root.join(d))).collect();
+
+    let mut watched = Watched {
+        root: base.to_path_buf(),
+        files: BTreeMap::new(),
+    };
+    let all = refresh(winnower, &mut watched, &mut on_event)?;
+    check(winnower, &mut watched, &all, &mut on_event)?;
+    on_event(&WatchEvent::Ready, watched.summary())?;
+
+    while let Some(batch) = next_batch(&rx, debounce, cancel, &ignored, root)? {
+        let mut changed: BTreeSet<PathBuf> = batch
+            .iter()
+            .filter(|p| watched.files.contains_key(*p))
+            .cloned()
+            .collect();
+        if batch
+            .iter()
+            .any(|p| !watched.files.contains_key(p) || !p.exists())
+        {
+            changed.extend(refresh(winnower, &mut watched, &mut on_event)?);
+        }
+        let changed: Vec<PathBuf> = changed
+            .into_iter()
+            .filter(|p| watched.files.contains_key(p))
+            .collect();
+        check(winnower, &mut watched, &changed, &mut on_event)?;
+    }
+    Ok(())
+}
+
+/// Re-run discovery: report files no longer found as removed and return those newly found.
+fn refresh(
+    winnower: &Winnower,
+    watched: &mut Watched,
+    on_event: &mut impl FnMut(&WatchEvent<'_>, WatchSummary) -> TraitError<()>,
+) -> TraitError<Vec<PathBuf>> {
+    let found: BTreeMap<PathBuf, PathBuf> = winnower
+        .files()?
+        .into_iter()
+        .map(|p| (canonical(&p), p))
+        .collect();
+    let gone: Vec<PathBuf> = watched
+        .files
+        .keys()
+        .filter(|k| !found.contains_key(*k))
+        .cloned()
+        .collect();
+    for key in gone {
+        if let Some((path, _)) = watched.files.remove(&key) {
+            let path = watched.rel(&path).to_path_buf();
+            on_event(&WatchEvent::Removed { path: &path }, watched.summary())?;
+        }
+    }
+    let mut added = Vec::new();
+    for (key, path) in found {
+        if !watched.files.contains_key(&key) {
+            watched.files.insert(key.clone(), (path, None));
+            added.push(key);
+        }
+    }
+    Ok(added)
+}
+
+/// Check the watched files `keys`, in order.
+fn check(
+    winnower: &Winnower,
+    watched: &mut Watched,
+    keys: &[PathBuf],
+    on_event: &mut impl FnMut(&WatchEvent<'_>, WatchSummary) -> TraitError<()>,
+) -> TraitError<()> {
+    for key in keys {
+        let Some((path, _)) = watched.files.get(key) else {
+            continue;
+        };
+        let path = path.clone();
+        match winnower.check_file(&path) {
+            Ok((source, report)) => {
+                if let Some((_, count)) = watched.files.get_mut(key) {
+                    *count = Some(report.findings.len());
+                }
+                let event = WatchEvent::Checked {
+                    report: &report,
+                    source: &source,
+                };
+                on_event(&event, watched.summary())?;
+            }
+            Err(error) => {
+                let path = watched.rel(&path);
+                let event = WatchEvent::Failed {
+                    path,
+                    error: &error,
+                };
+                on_event(&event, watched.summary())?;
+            }
+        }
+    }
+    Ok(())
+}
+
+/// The canonical paths of the next batch of changes, outside `ignored`; `None` once
+/// `cancel` fires.
+fn next_batch(
+    rx: &Receiver<notify::Result<notify::Event>>,
+    debounce: Duration,
+    cancel: &CancelToken,
+    ignored: &[PathBuf],
+    root: &Path,
+) -> TraitError<Option<BTreeSet<PathBuf>>> {
+    let mut batch = BTreeSet::new();
+    loop {
+        if cancel.is_cancelled() {
+            return Ok(None);
+        }
+        let wait = if batch.is_empty() { TICK } else { debounce };
+        let event = match rx.recv_timeout(wait) {
+            Ok(event) => event.map_err(|e| watch_error(root, e))?,
+            Err(RecvTimeoutError::Timeout) if batch.is_empty() => continue,
+            Err(RecvTimeoutError::Timeout) => return Ok(Some(batch)),
+            Err(RecvTimeoutError::Disconnected) => return Ok(None),
+        };
+        // Reading a file, as checking does, is no change.
+        if matches!(event.kind, EventKind::Access(kind) if kind != AccessKind::Close(AccessMode::Write))
+        {
+            continue;
+        }
+        batch.extend(
+            event
+                .paths
+                .iter()
+                .map(|p| canonical(p))
+                .filter(|p| !ignored.iter().any(|i| p.starts_with(i))),
+        );
+    }
+}
@@ -0,0 +1,2139 @@
+// src/winnower.rs
+//! Library entry point: resolve a target, then check or prune it.
+//!
+//! ```no_run
+//! use trait_winnower::winnower::Winnower;
+//!
+//! # fn main() -> trait_winnower::error::TraitError<()> {
+//! let report = Winnower::builder().target("path/to/crate").build()?.check()?;
+//! for file in &report.report.files {
+//!     for f in &file.findings {
+//!         println!("{}:{} {}: {}", file.path.display(), f.line, f.bounded, f.bound);
+//!     }
+//! }
+//! # Ok(())
+//! # }
+//! ```
+//!
+//! Pruning a crate edits its files in place, keeping only removals `cargo check` accepts:
+//!
+//! ```
+//! use trait_winnower::winnower::Winnower;
+//!
+//! # fn main() -> Result<(), Box<dyn std::error::Error>> {
+//! let dir = tempfile::tempdir()?;
+//! std::fs::write(
+//!     dir.path().join("Cargo.toml"),
+//!     "[package]\nname = \"demo\"\nversion = \"0.1.0\"\nedition = \"2021\"\n\n[workspace]\n",
+//! )?;
+//! std::fs::create_dir(dir.path().join("src"))?;
+//! std::fs::write(dir.path().join("src/lib.rs"), "pub fn f<T: Clone>(t: T) -> T { t }\n")?;
+//!
+//! let report = Winnower::builder()
+//!     .target(dir.path())
+//!     .brute_force(true)
+//!     .build()?
+//!     .prune()?;
+//!
+//! assert_eq!(report.modified.len(), 1);
+//! let src = std::fs::read_to_string(dir.path().join("src/lib.rs"))?;
+//! assert!(!src.contains("Clone"));
+//! # Ok(())
+//! # }
+//! ```
+
+#![deny(missing_docs)]
+
+use crate::analysis::{ItemBounds, ItemKey, ItemLocator, NonUtf8Source, names_bound};
+use crate::annotate::{annotate_report, is_marker, strip_files};
+use crate::cancel::CancelToken;
+use crate::commit::{CommitGranularity, CommitLog, commit_removals, require_clean};
+use crate::config::{
+    CargoCheckConfig, ClippyGate, Config, ForeignTraitImpls, MatrixMode, TargetArea, ValidatorKind,
+};
+use crate::crate_map::{CrateMap, SharedFile};
+use crate::derivable::derive_candidates;
+use crate::discover::{Discover, IgnoreFiles, MissingModule, SkipReason, SkippedFile};
+use crate::doctor::{Validation, preflight};
+use crate::dynamic_analysis::batch::BatchRunner;
+use crate::dynamic_analysis::clippy::ClippyFindings;
+use crate::dynamic_analysis::common::{BoundRemovalResult, CargoCheck, CheckTargetDir};
+use crate::dynamic_analysis::edit::{PruneContext, PruneItem, RejectionMemo};
+use crate::dynamic_analysis::external::{ExternalEdit, FileStamp, external_edit};
+use crate::dynamic_analysis::incremental::{CacheStats, FileOutcome, Incremental, RunCache};
+use crate::dynamic_analysis::journal::{Journal, JournaledStore};
+use crate::dynamic_analysis::lock_wait::LockNotice;
+use crate::dynamic_analysis::schedule::schedule;
+use crate::dynamic_analysis::semver::{
+    Baseline, CargoSemverChecks, SemverGate, SemverOutcome, SemverReport,
+};
+use crate::dynamic_analysis::session::Session;
+use crate::dynamic_analysis::store::FsStore;
+use crate::dynamic_analysis::test_only::{explain_test_only, without_test_targets};
+use crate::dynamic_analysis::text_edit::apply_edits;
+use crate::dynamic_analysis::trial_cache::{CachedCheck, TrialCache};
+use crate::dynamic_analysis::unlock::unlock_chains;
+use crate::dynamic_analysis::validate::Validator;
+use crate::dynamic_analysis::worktree::{ShadowStore, WORKTREE_DIR, Worktree};
+use crate::error::{TraitError, WinnowerError};
+use crate::explain::{ItemExplanation, trial_candidates};
+use crate::findings;
+use crate::fingerprint::{Drift, Fingerprint};
+use crate::fix::{FixReport, fix_report};
+use crate::hook;
+use crate::impact::{self, Comparison, Impact, ImpactOptions};
+use crate::observer::{NoopObserver, Observer};
+use crate::patch::{FileChange, write_patches};
+use crate::plan::{ApplyOptions, EntryStatus, Plan, PlanEntry, apply_plan_with, locate};
+use crate::references::{References, SortBy};
+use crate::report::{
+    FileMetrics, FileReport, Finding, Removal, RemovalStatus, Report, TidiedImport,
+    ValidationSummary,
+};
+use crate::strategy::{Strategy, StrategyName};
+use crate::target::{TargetKind, TargetType};
+use crate::tidy::{imported_names, removed_names, unused_imports};
+use crate::trait_index::TraitIndex;
+use serde::{Deserialize, Serialize};
+use std::cell::RefCell;
+use std::cmp::Reverse;
+use std::collections::{HashMap, HashSet};
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::time::{Duration, Instant, SystemTime};
+
+/// Every concrete target type, in the order `prune` visits them.
+const ALL_TYPES: [TargetType; 7] = [
+    TargetType::Function,
+    TargetType::Impl,
+    TargetType::Trait,
+    TargetType::TraitMethod,
+    TargetType::ImplMethod,
+    TargetType::Enum,
+    TargetType::Struct,
+];
+
+/// A resolved target with the settings to check or prune it. Build one with
+/// [`Winnower::builder`].
+pub struct Winnower {
+    kind: TargetKind,
+    config: Option<Config>,
+    validator: Option<Box<dyn Validator>>,
+    target_types: Vec<TargetType>,
+    brute_force: bool,
+    allow_dyn_compat_changes: bool,
+    allow_unsafe_impl_pruning: bool,
+    include_opaque_types: bool,
+    skip_blanket_impls: bool,
+    only_traits: Vec<String>,
+    sort_by: SortBy,
+    min_weight: usize,
+    limit: usize,
+    since: Option<String>,
+    incremental: Option<Incremental>,
+    ignore_fingerprint: bool,
+    explain_test_only: bool,
+    reject_slow_removals: bool,
+    tidy_imports: bool,
+    annotate_retained: bool,
+    keep_target_dir: bool,
+    assume_consistent: bool,
+    batch_size: usize,
+    max_candidates_per_file: Option<usize>,
+    dry_run: bool,
+    strategy: Option<Strategy>,
+    commit: Option<CommitGranularity>,
+    commit_message: Option<String>,
+    patches: Option<PatchOutput>,
+    session: Option<SessionFile>,
+    import_clippy: bool,
+    cancel: CancelToken,
+    semver_gate: Option<Box<dyn SemverGate>>,
+    lock_notice: Option<LockNotice>,
+}
+
+/// Builder for [`Winnower`].
+pub struct WinnowerBuilder {
+    target: PathBuf,
+    config: Option<Config>,
+    validator: Option<Box<dyn Validator>>,
+    target_types: Vec<TargetType>,
+    brute_force: bool,
+    allow_dyn_compat_changes: bool,
+    allow_unsafe_impl_pruning: bool,
+    include_opaque_types: bool,
+    skip_blanket_impls: bool,
+    only_traits: Vec<String>,
+    sort_by: SortBy,
+    min_weight: usize,
+    limit: usize,
+    since: Option<String>,
+    incremental: Option<Incremental>,
+    ignore_fingerprint: bool,
+    explain_test_only: bool,
+    reject_slow_removals: bool,
+    tidy_imports: bool,
+    annotate_retained: bool,
+    keep_target_dir: bool,
+    lock_wait_timeout: Option<u64>,
+    no_ignore: bool,
+    assume_consistent: bool,
+    batch_size: usize,
+    max_candidates_per_file: Option<usize>,
+    dry_run: bool,
+    strategy: Option<StrategyName>,
+    commit: Option<CommitGranularity>,
+    commit_message: Option<String>,
+    patches: Option<PatchOutput>,
+    session: Option<SessionFile>,
+    import_clippy: bool,
+    cancel: CancelToken,
+    semver_gate: Option<Box<dyn SemverGate>>,
+    lock_notice: Option<LockNotice>,
+}
+
+/// Where [`WinnowerBuilder::output_patches`] writes its patches.
+#[derive(Debug, Clone)]
+struct PatchOutput {
+    dir: PathBuf,
+    single: bool,
+}
+
+/// The recording of [`WinnowerBuilder::record`] or [`WinnowerBuilder::replay`].
+#[derive(Debug, Clone)]
+enum SessionFile {
+    Record(PathBuf),
+    Replay(PathBuf),
+}
+
+/// Result of [`Winnower::check`].
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct CheckReport {
+    /// Findings per analyzed file, in canonical order.
+    pub report: Report,
+    /// Paths discovery passed over, with the reason.
+    pub skipped: Vec<SkippedFile>,
+    /// `mod` declarations that did not resolve to a file (single-file targets).
+    pub missing_modules: Vec<MissingModule>,
+    /// Discovered files skipped because they are not UTF-8.
+    pub not_utf8: Vec<NonUtf8Source>,
+    /// Discovered files more than one package compiles.
+    pub shared: Vec<SharedFile>,
+}
+
+/// Result of [`Winnower::prune`].
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct PruneReport {
+    /// Removal trials per pruned file, in canonical order.
+    pub report: Report,
+    /// Paths discovery passed over, with the reason.
+    pub skipped: Vec<SkippedFile>,
+    /// Discovered files skipped because they are not UTF-8.
+    pub not_utf8: Vec<NonUtf8Source>,
+    /// Discovered files more than one package compiles; their removals must build in each.
+    pub shared: Vec<SharedFile>,
+    /// Files whose contents differ from before the run, sorted.
+    pub modified: Vec<PathBuf>,
+    /// The run stopped early on its [`CancelToken`]; the report covers the trials that
+    /// finished.
+    pub cancelled: bool,
+    /// What the semver gate found, when the configuration asks for one and a file was
+    /// modified. Cancelled runs skip it.
+    pub semver: Option<SemverReport>,
+    /// With `matrix_mode = "final-only"`, the error output of the matrix entry the final
+    /// state failed on; every removal was then put back and reported as retained.
+    pub matrix_failure: Option<String>,
+    /// With `validator = "clippy"` and `clippy.gate = "final"`, the error output of clippy
+    /// on the final state; every removal was then put back and reported as retained.
+    pub clippy_failure: Option<String>,
+    /// Files skipped as unchanged since the last run, and files pruned, with
+    /// [`WinnowerBuilder::incremental`]. Skipped files have no [`FileReport`].
+    pub cache: Option<CacheStats>,
+    /// With [`WinnowerBuilder::incremental`], how the fingerprint of the last run's cache
+    /// differs from this run's; every file was then pruned again.
+    pub drift: Option<Drift>,
+    /// The commits made with [`WinnowerBuilder::commit_each`].
+    pub commits: Option<CommitLog>,
+    /// The patches written with [`WinnowerBuilder::output_patches`], in series order.
+    pub patches: Vec<PathBuf>,
+    /// Notes [`WinnowerBuilder::annotate_retained`] left in the tree.
+    pub annotated: usize,
+    /// Files left alone because they were written during the run or, with
+    /// [`Config::respect_editor_locks`], are open in an editor. They have no [`FileReport`].
+    pub external_edits: Vec<ExternalEdit>,
+}
+
+/// One file as `check` analyzed it, handed to the callback of [`Winnower::check_each`].
+pub struct CheckedFile<'a, 'ast> {
+    /// Path of the file on disk.
+    pub path: &'a Path,
+    /// The file's text.
+    pub source: &'a str,
+    /// Items with bounds collected from the file.
+    pub items: &'a ItemBounds<'ast>,
+    /// Findings for the file.
+    pub report: &'a FileReport,
+}
+
+impl Default for WinnowerBuilder {
+    fn default() -> Self {
+        Self {
+            target: PathBuf::from("."),
+            config: None,
+            validator: None,
+            target_types: vec![TargetType::All],
+            brute_force: false,
+            allow_dyn_compat_changes: false,
+            allow_unsafe_impl_pruning: false,
+            include_opaque_types: false,
+            skip_blanket_impls: false,
+            only_traits: Vec::new(),
+            sort_by: SortBy::default(),
+            min_weight: 0,
+            limit: usize::MAX,
+            since: None,
+            incremental: None,
+            ignore_fingerprint: false,
+            explain_test_only: false,
+            reject_slow_removals: false,
+            tidy_imports: false,
+            annotate_retained: false,
+            keep_target_dir: true,
+            lock_wait_timeout: None,
+            no_ignore: false,
+            assume_consistent: true,
+            batch_size: 1,
+            max_candidates_per_file: None,
+            dry_run: false,
+            strategy: None,
+            commit: None,
+            commit_message: None,
+            patches: None,
+            session: None,
+            import_clippy: false,
+            cancel: CancelToken::new(),
+            semver_gate: None,
+            lock_notice: None,
+        }
+    }
+}
+
+impl WinnowerBuilder {
+    /// The file, crate or workspace to operate on. Defaults to `.`.
+    pub fn target(mut self, path: impl Into<PathBuf>) -> Self {
+        self.target = path.into();
+        self
+    }
+
+    /// Use `config` instead of loading `.trait-winnower.toml` from the target.
+    pub fn config(mut self, config: Config) -> Self {
+        self.config = Some(config);
+        self
+    }
+
+    /// Validate edits with `validator` instead of `cargo check` with the configured
+    /// arguments.
+    pub fn validator(mut self, validator: impl Validator + 'static) -> Self {
+        self.validator = Some(Box::new(validator));
+        self
+    }
+
+    /// Restrict pruning to these kinds of items. Defaults to [`TargetType::All`].
+    pub fn target_types(mut self, types: impl IntoIterator<Item = TargetType>) -> Self {
+        self.target_types = types.into_iter().collect();
+        self
+    }
+
+    /// Try removing every bound, one `cargo check` per candidate. `prune` does nothing
+    /// without it.
+    pub fn brute_force(mut self, on: bool) -> Self {
+        self.brute_force = on;
+        self
+    }
+
+    /// Also try removing `where Self: Sized` on traits and trait methods. Such removals can
+    /// build fine yet change whether the trait is dyn-compatible, so `prune` skips them by
+    /// default and `check` flags them.
+    pub fn allow_dyn_compat_changes(mut self, on: bool) -> Self {
+        self.allow_dyn_compat_changes = on;
+        self
+    }
+
+    /// Also try removing the bounds of `unsafe impl`s, e.g. `T: Send` in
+    /// `unsafe impl<T: Send> Send for Wrapper<T>`. The impl may build without them while
+    /// becoming unsound, so `prune` skips them by default and `check` flags them.
+    pub fn allow_unsafe_impl_pruning(mut self, on: bool) -> Self {
+        self.allow_unsafe_impl_pruning = on;
+        self
+    }
+
+    /// Also try removing bounds of return-position `impl Trait` and `dyn Trait` types. The
+    /// crate may build without them while its callers break, so `prune` skips them by
+    /// default and `check` flags them.
+    pub fn include_opaque_types(mut self, on: bool) -> Self {
+        self.include_opaque_types = on;
+        self
+    }
+
+    /// Leave the bounds of blanket impls alone in `prune` (see
+    /// [`ItemRef::is_blanket_impl`](crate::analysis::ItemRef::is_blanket_impl)). Loosening
+    /// one widens the types the impl covers, which can overlap impls in downstream crates.
+    pub fn skip_blanket_impls(mut self, on: bool) -> Self {
+        self.skip_blanket_impls = on;
+        self
+    }
+
+    /// Only look at bounds naming one of `traits`, by path or last segment, whatever their
+    /// generic arguments (see [`names_bound`](crate::analysis::names_bound)). Empty, the
+    /// default, looks at all.
+    pub fn only_traits(mut self, traits: impl IntoIterator<Item = impl Into<String>>) -> Self {
+        self.only_traits = traits.into_iter().map(Into::into).collect();
+        self
+    }
+
+    /// Order findings, and the files `prune` works through, by `sort_by`. Sorting by
+    /// weight counts the references to every item over the discovered files first (see
+    /// [`References`]).
+    pub fn sort_by(mut self, sort_by: SortBy) -> Self {
+        self.sort_by = sort_by;
+        self
+    }
+
+    /// Leave out the bounds of items the workspace refers to fewer than `n` times (see
+    /// [`References::weight`]): `check` does not report them and `prune` does not try them.
+    /// 0, the default, counts no references unless sorting by weight.
+    pub fn min_weight(mut self, n: usize) -> Self {
+        self.min_weight = n;
+        self
+    }
+
+    /// Analyze at most `n` discovered files.
+    pub fn limit(mut self, n: usize) -> Self {
+        self.limit = n;
+        self
+    }
+
+    /// Analyze only discovered files git sees changed since `rev` (see
+    /// [`Discover::changed_since`]).
+    pub fn since(mut self, rev: impl Into<String>) -> Self {
+        self.since = Some(rev.into());
+        self
+    }
+
+    /// Skip files `prune` left unchanged last time (see [`RunCache`]). Every brute-force
+    /// run records its outcome, with or without this.
+    pub fn incremental(mut self, mode: Incremental) -> Self {
+        self.incremental = Some(mode);
+        self
+    }
+
+    /// Reuse the outcomes of an [`incremental`](Self::incremental) run's cache even when it
+    /// was recorded with another `Cargo.lock` or rustc (see [`Fingerprint`]); by default
+    /// such a cache is bypassed with a warning in [`PruneReport::drift`].
+    pub fn ignore_fingerprint(mut self, on: bool) -> Self {
+        self.ignore_fingerprint = on;
+        self
+    }
+
+    /// After pruning, try each retained bound again with `cargo check` leaving test
+    /// targets out, and mark those that pass as [`Removal::test_only`] (see
+    /// [`explain_test_only`]). Needs checks building test targets and no custom validator;
+    /// the tree is left as pruned.
+    pub fn explain_test_only(mut self, on: bool) -> Self {
+        self.explain_test_only = on;
+        self
+    }
+
+    /// Put back the removals [`Report::slow_removals`] flags instead of only reporting
+    /// them; the other removals in their files are applied again.
+    pub fn reject_slow_removals(mut self, on: bool) -> Self {
+        self.reject_slow_removals = on;
+        self
+    }
+
+    /// After pruning, drop the imports the removed bounds were the last users of, each
+    /// validated on its own and put back if the check fails (see [`crate::tidy`]).
+    pub fn tidy_imports(mut self, on: bool) -> Self {
+        self.tidy_imports = on;
+        self
+    }
+
+    /// After pruning, write a comment above each item keeping a bound the rejecting check
+    /// blamed on a bound elsewhere, naming it (see [`annotate_report`]), in place of those
+    /// an earlier run wrote. Not with [`dry_run`](Self::dry_run).
+    pub fn annotate_retained(mut self, on: bool) -> Self {
+        self.annotate_retained = on;
+        self
+    }
+
+    /// Leave the directory `cargo check` built in (see [`CargoCheckConfig::target_dir`]) for
+    /// the next run, which then starts from its incremental state. Defaults to `true`.
+    pub fn keep_target_dir(mut self, keep: bool) -> Self {
+        self.keep_target_dir = keep;
+        self
+    }
+
+    /// Stop a run whose `cargo check` has waited more than `secs` seconds for a file lock
+    /// another cargo process holds, overriding the configuration's
+    /// [`lock_wait_timeout`](CargoCheckConfig::lock_wait_timeout).
+    pub fn lock_wait_timeout(mut self, secs: u64) -> Self {
+        self.lock_wait_timeout = Some(secs);
+        self
+    }
+
+    /// Discover the files ignore files leave out too, overriding the configuration's
+    /// [`respect_gitignore`](Config::respect_gitignore).
+    pub fn no_ignore(mut self, no_ignore: bool) -> Self {
+        self.no_ignore = no_ignore;
+        self
+    }
+
+    /// Call `notice` when a check of `prune` starts waiting for a file lock another cargo
+    /// process holds (see [`lock_wait`](crate::dynamic_analysis::lock_wait)).
+    pub fn on_lock_wait(mut self, notice: LockNotice) -> Self {
+        self.lock_notice = Some(notice);
+        self
+    }
+
+    /// Take a bound's removal to be rejected, without a check, once removing it from another
+    /// item of the same impl or trait was (see [`RejectionMemo`]). Defaults to `true`.
+    pub fn assume_consistent(mut self, on: bool) -> Self {
+        self.assume_consistent = on;
+        self
+    }
+
+    /// Validate removals from up to `n` distinct files with one check, bisecting the batch
+    /// when it fails (see [`BatchRunner`]). Defaults to 1, one check per candidate.
+    pub fn batch_size(mut self, n: usize) -> Self {
+        self.batch_size = n.max(1);
+        self
+    }
+
+    /// Try at most `n` candidates of a file, those of its first items in the order they
+    /// are tried. Bounds [`only_traits`](Self::only_traits) or the dyn-compatibility and
+    /// unsafe-impl cautions leave alone don't count; the rest over `n` are counted in
+    /// [`FileMetrics::truncated`](crate::report::FileMetrics::truncated) and left alone. A
+    /// safety valve for very large, often generated, files.
+    pub fn max_candidates_per_file(mut self, n: usize) -> Self {
+        self.max_candidates_per_file = Some(n);
+        self
+    }
+
+    /// Prune with the strategy `name`, as the target's `[strategy]` table adjusts it (see
+    /// [`Strategy`]). It decides brute force, the batch size, whether rejections are
+    /// assumed consistent, and the configuration's `order` and `prefilter`, whatever else
+    /// sets them.
+    pub fn strategy(mut self, name: StrategyName) -> Self {
+        self.strategy = Some(name);
+        self
+    }
+
+    /// Validate removals as usual, then put every file back: the report lists what `prune`
+    /// would remove and nothing changes on disk. [`Winnower::fix`] only works out its edits.
+    /// Defaults to `false`.
+    pub fn dry_run(mut self, on: bool) -> Self {
+        self.dry_run = on;
+        self
+    }
+
+    /// Record the removals `prune` keeps in git commits, one per `granularity` (see
+    /// [`commit_removals`]). The work tree must be clean when the run starts. Ignored on
+    /// dry runs.
+    pub fn commit_each(mut self, granularity: CommitGranularity) -> Self {
+        self.commit = Some(granularity);
+        self
+    }
+
+    /// The commit message template, with `{bound}`, `{item}`, `{file}` and `{count}`
+    /// placeholders. Defaults to [`CommitGranularity::default_template`].
+    pub fn commit_message(mut self, template: impl Into<String>) -> Self {
+        self.commit_message = Some(template.into());
+        self
+    }
+
+    /// Prune a copy of the workspace in a temporary directory and write what it removed as
+    /// unified diffs into `dir`, leaving the real tree untouched: one
+    /// `NNNN-<file stem>.patch` per modified file, or a single [`SINGLE_PATCH`](crate::patch::SINGLE_PATCH) with
+    /// `single`. Paths are relative to the repository holding the target, or to the target
+    /// outside one. Trials build the copy from scratch. Dry runs and commits do not apply.
+    pub fn output_patches(mut self, dir: impl Into<PathBuf>, single: bool) -> Self {
+        self.patches = Some(PatchOutput {
+            dir: dir.into(),
+            single,
+        });
+        self
+    }
+
+    /// Save every `cargo check` of `prune` to `path`: arguments, tree state and outcome
+    /// (see [`Session`]). The trial cache is not used, so that every check is recorded.
+    pub fn record(mut self, path: impl Into<PathBuf>) -> Self {
+        self.session = Some(SessionFile::Record(path.into()));
+        self
+    }
+
+    /// Answer the checks of `prune` from the recording at `path` instead of running cargo,
+    /// failing with [`WinnowerError::Replay`] on one it does not hold.
+    pub fn replay(mut self, path: impl Into<PathBuf>) -> Self {
+        self.session = Some(SessionFile::Replay(path.into()));
+        self
+    }
+
+    /// Run `cargo clippy` once in `check` and tag the findings its bound lints flag with
+    /// [`Rule::Clippy`](crate::findings::Rule::Clippy) (see [`ClippyFindings`]). Crate and
+    /// workspace targets only.
+    pub fn import_clippy(mut self, on: bool) -> Self {
+        self.import_clippy = on;
+        self
+    }
+
+    /// Stop `prune` once `cancel` fires: the trial in flight is reverted and its validation
+    /// killed, removals already accepted are kept.
+    pub fn cancel_token(mut self, cancel: CancelToken) -> Self {
+        self.cancel = cancel;
+        self
+    }
+
+    /// Compare APIs with `gate` instead of `cargo semver-checks` when the configuration
+    /// asks for a semver check.
+    pub fn semver_gate(mut self, gate: impl SemverGate + 'static) -> Self {
+        self.semver_gate = Some(Box::new(gate));
+        self
+    }
+
+    /// Resolve the target and load its configuration.
+    pub fn build(self) -> TraitError<Winnower> {
+        let kind = TargetKind::get_target(Some(self.target))?;
+        let mut config = match (&kind, self.config) {
+            (TargetKind::SingleFile(_), _) => None,
+            (_, Some(cfg)) => Some(cfg),
+            (TargetKind::Crate(root) | TargetKind::Workspace(root), None) => {
+                Some(Config::load_or_default(root)?)
+            }
+        };
+        let strategy = self.strategy.map(|name| match &config {
+            Some(cfg) => cfg.strategy.resolve(name),
+            None => Strategy::preset(name),
+        });
+        if let (Some(strategy), Some(cfg)) = (&strategy, &mut config) {
+            cfg.order = strategy.order;
+            cfg.prefilter = strategy.prefilter;
+        }
+        if let (Some(secs), Some(cfg)) = (self.lock_wait_timeout, &mut config) {
+            cfg.cargo_check.lock_wait_timeout = Some(secs);
+        }
+        if let (true, Some(cfg)) = (self.no_ignore, &mut config) {
+            cfg.respect_gitignore = false;
+        }
+        Ok(Winnower {
+            kind,
+            config,
+            validator: self.validator,
+            target_types: self.target_types,
+            brute_force: strategy.map_or(self.brute_force, |s| s.trials),
+            allow_dyn_compat_changes: self.allow_dyn_compat_changes,
+            allow_unsafe_impl_pruning: self.allow_unsafe_impl_pruning,
+            include_opaque_types: self.include_opaque_types,
+            skip_blanket_impls: self.skip_blanket_impls,
+            only_traits: self.only_traits,
+            sort_by: self.sort_by,
+            min_weight: self.min_weight,
+            limit: self.limit,
+            since: self.since,
+            incremental: self.incremental,
+            ignore_fingerprint: self.ignore_fingerprint,
+            explain_test_only: self.explain_test_only,
+            reject_slow_removals: self.reject_slow_removals,
+            tidy_imports: self.tidy_imports,
+            annotate_retained: self.annotate_retained,
+            keep_target_dir: self.keep_target_dir,
+            assume_consistent: strategy.map_or(self.assume_consistent, |s| s.assume_consistent),
+            batch_size: strategy.map_or(self.batch_size, |s| s.batch_size),
+            max_candidates_per_file: self.max_candidates_per_file,
+            dry_run: self.dry_run && self.patches.is_none(),
+            strategy,
+            commit: self.commit.filter(|_| self.patches.is_none()),
+            commit_message: self.commit_message,
+            patches: self.patches,
+            session: self.session,
+            import_clippy: self.import_clippy,
+            cancel: self.cancel,
+            semver_gate: self.semver_gate,
+            lock_notice: self.lock_notice,
+        })
+    }
+}
+
+impl Winnower {
+    /// Start building a [`Winnower`].
+    pub fn builder() -> WinnowerBuilder {
+        WinnowerBuilder::default()
+    }
+
+    /// The resolved target.
+    pub fn kind(&self) -> &TargetKind {
+        &self.kind
+    }
+
+    /// Configuration in effect; `None` for single files.
+    pub fn config(&self) -> Option<&Config> {
+        self.config.as_ref()
+    }
+
+    /// The strategy `prune` runs with, if one was named (see [`WinnowerBuilder::strategy`]).
+    pub fn strategy(&self) -> Option<Strategy> {
+        self.strategy
+    }
+
+    /// The `cargo check` configuration `prune` validates with and what it leaves unchecked;
+    /// `None` for single files and custom validators.
+    pub fn validation(&self) -> Option<ValidationSummary> {
+        self.cargo_check().map(ValidationSummary::of)
+    }
+
+    /// The `cargo check` configuration removals are validated with, unless a custom
+    /// validator replaces it.
+    fn cargo_check(&self) -> Option<&CargoCheckConfig> {
+        match (&self.validator, &self.config) {
+            (None, Some(cfg)) => Some(&cfg.cargo_check),
+            _ => None,
+        }
+    }
+
+    /// Report likely unnecessary bounds without touching any file.
+    pub fn check(&self) -> TraitError<CheckReport> {
+        self.check_each(|_| Ok(()))
+    }
+
+    /// Like [`Winnower::check`], calling `on_file` as each file is analyzed.
+    pub fn check_each(
+        &self,
+        mut on_file: impl FnMut(&CheckedFile<'_, '_>) -> TraitError<()>,
+    ) -> TraitError<CheckReport> {
+        let started = Instant::now();
+        let mut out = CheckReport::default();
+        out.report.validation = self.validation();
+
+        let mut clippy = None;
+        if let (TargetKind::SingleFile(p), true) = (&self.kind, self.import_clippy) {
+            return Err(WinnowerError::Target {
+                path: p.clone(),
+                detail: "importing clippy lints needs a crate or workspace".into(),
+            });
+        }
+        // Single files fail hard on unreadable sources; crates skip them.
+        let (root, files, map) = match (&self.kind, &self.config) {
+            (TargetKind::SingleFile(p), _) => {
+                let modules = Discover::resolve_module_files(p)?;
+                out.missing_modules = modules.missing;
+                let root = p.parent().unwrap_or(Path::new("")).to_path_buf();
+                (root, modules.files, None)
+            }
+            (TargetKind::Crate(root) | TargetKind::Workspace(root), Some(cfg)) => {
+                let (files, map) = self.discover(root, cfg, &mut out.skipped, &mut out.shared)?;
+                if self.import_clippy {
+                    let workspace = map.workspace_root().unwrap_or(root);
+                    clippy = Some(ClippyFindings::collect(root, workspace, cfg)?);
+                }
+                out.report.fingerprint = Some(Fingerprint::capture(root));
+                (root.clone(), files, Some(map))
+            }
+            (_, None) => {
+                return Err(WinnowerError::Internal(format!(
+                    "no configuration for {:?}",
+                    self.kind
+                )));
+            }
+        };
+
+        let packages = map.iter().flat_map(CrateMap::package_names);
+        let trait_index = TraitIndex::of_files(&files, packages);
+        let references = self.references(&root, &files)?;
+        for path in &files {
+            let file_started = Instant::now();
+            let source = if map.is_none() {
+                ItemBounds::read_source(path)?
+            } else {
+                let Some(source) = read_or_skip(path, &mut out.not_utf8)? else {
+                    continue;
+                };
+                source
+            };
+            let file = syn::parse_file(&source).map_err(|e| WinnowerError::parse(path, e))?;
+            let parse = file_started.elapsed();
+            let items = ItemBounds::collect_items_in_file(&file)?;
+            let mut file_report = self.file_report(&root, path, &file, &items, Some(&trait_index));
+            self.weigh(&mut file_report, references.as_ref());
+            if let Some(clippy) = &clippy {
+                clippy.merge(path, &mut file_report);
+            }
+            if let Some(shared) = map.as_ref().and_then(|m| m.shared(path)) {
+                for f in &mut file_report.findings {
+                    f.packages = shared.packages.clone();
+                }
+            }
+            file_report.metrics.parse = parse;
+            file_report.metrics.total = file_started.elapsed();
+            on_file(&CheckedFile {
+                path,
+                source: &source,
+                items: &items,
+                report: &file_report,
+            })?;
+            out.report.files.push(file_report);
+        }
+
+        if let Some(map) = &map {
+            out.report.dedup_shared(|rel| {
+                let path = root.join(rel);
+                let packages = map.packages_of(&path).into_iter().map(str::to_owned);
+                let packages = packages.collect();
+                (path.canonicalize().unwrap_or(path), packages)
+            });
+        }
+        out.report.wall_time = started.elapsed();
+        out.report.sort();
+        if self.sort_by == SortBy::Weight {
+            out.report.sort_by_weight();
+        }
+        Ok(out)
+    }
+
+    /// Remove the bounds static rules prove redundant (see [`fix`](crate::fix)), without
+    /// running cargo; with [`WinnowerBuilder::dry_run`], only work out what would change.
+    pub fn fix(&self) -> TraitError<FixReport> {
+        if self.import_clippy {
+            return Err(WinnowerError::Target {
+                path: self.kind.path().to_path_buf(),
+                detail: "fixing applies static rules only; clippy lints need cargo".into(),
+            });
+        }
+        let root = match &self.kind {
+            TargetKind::SingleFile(p) => p.parent().unwrap_or(Path::new("")),
+            kind => kind.path(),
+        };
+        fix_report(root, &self.check_static()?, self.dry_run)
+    }
+
+    /// The findings of [`Winnower::check`] from the sources alone: cargo never runs, so
+    /// files are not attributed to packages and clippy lints are not imported.
+    pub fn check_static(&self) -> TraitError<Report> {
+        // `check` asks `cargo metadata` which package owns each file; this need not know.
+        let files = self.files()?;
+        let trait_index = TraitIndex::of_files(&files, []);
+        let root = match &self.kind {
+            TargetKind::SingleFile(p) => p.parent().unwrap_or(Path::new("")),
+            kind => kind.path(),
+        };
+        let references = self.references(root, &files)?;
+        let mut report = Report::default();
+        for path in &files {
+            let mut file_report = self.check_file_in(path, Some(&trait_index))?.1;
+            self.weigh(&mut file_report, references.as_ref());
+            report.files.push(file_report);
+        }
+        report.sort();
+        if self.sort_by == SortBy::Weight {
+            report.sort_by_weight();
+        }
+        Ok(report)
+    }
+
+    /// Remove the notes [`WinnowerBuilder::annotate_retained`] left in the files `check`
+    /// analyzes, unless [`dry_run`](WinnowerBuilder::dry_run); cargo never runs.
+    pub fn strip_annotations(&self) -> TraitError<Vec<FileChange>> {
+        let root = match &self.kind {
+            TargetKind::SingleFile(p) => p.parent().unwrap_or(Path::new("")),
+            kind => kind.path(),
+        };
+        strip_files(root, &self.files()?, self.dry_run)
+    }
+
+    /// The files `check` analyzes, discovered without running cargo: a single file and the
+    /// module files it declares, or the sources of a crate or workspace.
+    pub fn files(&self) -> TraitError<Vec<PathBuf>> {
+        match (&self.kind, &self.config) {
+            (TargetKind::SingleFile(p), _) => Ok(Discover::resolve_module_files(p)?.files),
+            (TargetKind::Crate(root) | TargetKind::Workspace(root), Some(cfg)) => {
+                self.discover_files(root, cfg, &mut Vec::new())
+            }
+            (_, None) => Err(WinnowerError::Internal(format!(
+                "no configuration for {:?}",
+                self.kind
+            ))),
+        }
+    }
+
+    /// Analyze `path`, one of [`Winnower::files`], as `check` does; returns its text and
+    /// report.
+    pub fn check_file(&self, path: &Path) -> TraitError<(String, FileReport)> {
+        self.check_file_in(path, None)
+    }
+
+    /// [`Winnower::check_file`] with the traits of `trait_index` rather than only those
+    /// `path` declares.
+    fn check_file_in(
+        &self,
+        path: &Path,
+        trait_index: Option<&TraitIndex>,
+    ) -> TraitError<(String, FileReport)> {
+        let started = Instant::now();
+        let source = ItemBounds::read_source(path)?;
+        let file = syn::parse_file(&source).map_err(|e| WinnowerError::parse(path, e))?;
+        let parse = started.elapsed();
+        let items = ItemBounds::collect_items_in_file(&file)?;
+        let root = match &self.kind {
+            TargetKind::SingleFile(p) => p.parent().unwrap_or(Path::new("")),
+            kind => kind.path(),
+        };
+        let mut report = self.file_report(root, path, &file, &items, trait_index);
+        report.metrics.parse = parse;
+        report.metrics.total = started.elapsed();
+        Ok((source, report))
+    }
+
+    /// The references to weigh findings with, counted over `files` under `root`, when
+    /// sorting or filtering by weight; only crates and workspaces keep a cache of them.
+    fn references(&self, root: &Path, files: &[PathBuf]) -> TraitError<Option<References>> {
+        if self.sort_by != SortBy::Weight && self.min_weight == 0 {
+            return Ok(None);
+        }
+        let cache_root = (!matches!(self.kind, TargetKind::SingleFile(_))).then_some(root);
+        References::of_files(cache_root, files).map(Some)
+    }
+
+    /// Weigh the findings of `report` with `references`, if any, leaving out those lighter
+    /// than [`WinnowerBuilder::min_weight`].
+    fn weigh(&self, report: &mut FileReport, references: Option<&References>) {
+        let Some(references) = references else {
+            return;
+        };
+        for f in &mut report.findings {
+            references.weigh(f);
+        }
+        report
+            .findings
+            .retain(|f| f.weight.unwrap_or(0) >= self.min_weight);
+    }
+
+    /// The findings of `path`, under `root`, from its syntax tree and bounded items, with the
+    /// supertraits of `trait_index` (of the file alone without one). Unless the
+    /// configuration allows foreign trait impls, the findings of impls of traits
+    /// `trait_index` does not know are marked with the trait.
+    fn file_report(
+        &self,
+        root: &Path,
+        path: &Path,
+        file: &syn::File,
+        items: &ItemBounds<'_>,
+        trait_index: Option<&TraitIndex>,
+    ) -> FileReport {
+        let rel = path.strip_prefix(root).unwrap_or(path).to_path_buf();
+        let findings = match trait_index {
+            Some(index) => findings::analyze_in(items, index),
+            None => findings::analyze(file, items),
+        };
+        let mut report = FileReport::from_items(rel, items).with_rules(&findings);
+        if !self.only_traits.is_empty() {
+            report
+                .findings
+                .retain(|f| self.only_traits.iter().any(|t| names_bound(t, &f.bound)));
+        }
+        if let Some(cargo_check) = self.cargo_check() {
+            for f in &mut report.findings {
+                f.check_cfg(cargo_check);
+            }
+        }
+        let policy = self.config.as_ref().map(|cfg| cfg.foreign_trait_impls);
+        if let Some(index) = trait_index
+            && policy.is_some_and(|p| p != ForeignTraitImpls::Allow)
+        {
+            let foreign: Vec<(ItemLocator, String)> = items
+                .iter_all_items()
+                .filter_map(|key| {
+                    let locator = ItemLocator::from(key).in_file(&report.path);
+                    Some((locator, index.foreign_trait(key.item())?))
+                })
+                .collect();
+            for f in &mut report.findings {
+                f.foreign_trait = foreign
+                    .iter()
+                    .find(|(locator, _)| *locator == f.locator)
+                    .map(|(_, tr)| tr.clone());
+            }
+        }
+        report
+    }
+
+    /// Remove bounds `cargo check` proves unnecessary, editing files in place. Requires
+    /// brute force and a crate or workspace target. Trials are journaled under
+    /// [`JOURNAL_DIR`](crate::dynamic_analysis::journal::JOURNAL_DIR); a run killed mid-trial
+    /// makes the next one fail with [`WinnowerError::Interrupted`] until
+    /// [`recover`](crate::dynamic_analysis::journal::recover) puts the originals back.
+    pub fn prune(&self) -> TraitError<PruneReport> {
+        self.prune_with(&mut NoopObserver)
+    }
+
+    /// Like [`Winnower::prune`], reporting progress to `observer`.
+    pub fn prune_with(&self, observer: &mut dyn Observer) -> TraitError<PruneReport> {
+        let (root, cfg) = match (&self.kind, &self.config) {
+            (TargetKind::SingleFile(_), _) => {
+                if self.brute_force {
+                    return Err(WinnowerError::Target {
+                        path: self.kind.path().to_path_buf(),
+                        detail: "brute force is not supported for single files".into(),
+                    });
+                }
+                return Ok(PruneReport::default());
+            }
+            (TargetKind::Crate(root) | TargetKind::Workspace(root), Some(cfg)) => (root, cfg),
+            (_, None) => {
+                return Err(WinnowerError::Internal(format!(
+                    "no configuration for {:?}",
+                    self.kind
+                )));
+            }
+        };
+        match &self.patches {
+            Some(patches) if self.brute_force => {
+                self.prune_to_patches(root, cfg, patches, observer)
+            }
+            _ => self.prune_in(root, cfg, observer),
+        }
+    }
+
+    /// Prune a copy of the workspace holding `root` and write the diffs of its modified
+    /// files as `patches` asks. The report names no modified files.
+    fn prune_to_patches(
+        &self,
+        root: &Path,
+        cfg: &Config,
+        patches: &PatchOutput,
+        observer: &mut dyn Observer,
+    ) -> TraitError<PruneReport> {
+        let (copy, copy_root) = Self::scratch_copy(root, cfg, "patches")?;
+        let pruned = self.prune_in(&copy_root, cfg, observer);
+        let out = pruned.and_then(|mut out| {
+            let prefix = hook::target_in_repo(root)
+                .ok()
+                .filter(|p| p != ".")
+                .map(PathBuf::from)
+                .unwrap_or_default();
+            let mut changes = Vec::new();
+            for pruned in std::mem::take(&mut out.modified) {
+                let rel = pruned.strip_prefix(&copy_root).unwrap_or(&pruned);
+                let real = root.join(rel);
+                let read = |p: &Path| {
+                    std::fs::read_to_string(p).map_err(|e| WinnowerError::io("reading", p, e))
+                };
+                changes.push(FileChange {
+                    path: prefix.join(rel),
+                    old: read(&real)?,
+                    new: read(&pruned)?,
+                });
+            }
+            out.patches = write_patches(&patches.dir, &changes, patches.single)?;
+            Ok(out)
+        });
+        copy.finish()?;
+        out
+    }
+
+    /// A copy of the workspace holding `root` in a temporary directory named after
+    /// `purpose`, and where `root` is in it.
+    fn scratch_copy(root: &Path, cfg: &Config, purpose: &str) -> TraitError<(Worktree, PathBuf)> {
+        static COPIES: AtomicUsize = AtomicUsize::new(0);
+        // Members of a larger workspace only build with it.
+        let canonical = |p: &Path| p.canonicalize().unwrap_or_else(|_| p.to_path_buf());
+        let workspace = CrateMap::from_metadata(root, &cfg.cargo_check.cargo())
+            .ok()
+            .and_then(|m| m.workspace_root().map(canonical))
+            .filter(|w| canonical(root).starts_with(w))
+            .unwrap_or_else(|| canonical(root));
+        let member = canonical(root)
+            .strip_prefix(&workspace)
+            .map(Path::to_path_buf)
+            .unwrap_or_default();
+        let scratch = std::env::temp_dir().join(format!(
+            "trait-winnower-{purpose}-{}-{}",
+            std::process::id(),
+            COPIES.fetch_add(1, Ordering::Relaxed)
+        ));
+        let copy = Worktree::copy(
+            &workspace,
+            &scratch,
+            &[cfg.cargo_check.target_dir(&workspace)],
+        )?;
+        let copy_root = copy.root().join(&member);
+        Ok((copy, copy_root))
+    }
+
+    /// Measure what pruning does to compile times, leaving the real tree alone: time
+    /// `cargo check`, and `cargo build` with `options.build`, on a copy of the workspace
+    /// holding the target, prune another copy as [`Winnower::prune`] would and time it the
+    /// same way (see [`measure`](crate::impact::measure)). Returns the report of the prune,
+    /// naming no modified files, with [`Report::impact`](crate::report::Report::impact)
+    /// set unless the run was cancelled. Requires brute force and a crate or workspace.
+    pub fn impact(
+        &self,
+        options: &ImpactOptions,
+        observer: &mut dyn Observer,
+    ) -> TraitError<PruneReport> {
+        let (root, cfg) = match (&self.kind, &self.config) {
+            (TargetKind::Crate(root) | TargetKind::Workspace(root), Some(cfg))
+                if self.brute_force =>
+            {
+                (root, cfg)
+            }
+            _ => {
+                return Err(WinnowerError::Target {
+                    path: self.kind.path().to_path_buf(),
+                    detail: "measuring impact needs brute force and a crate or workspace".into(),
+                });
+            }
+        };
+        // A comparison needs a run on each side.
+        let iterations = options.iterations.max(1);
+        let toolchain = CargoCheck::toolchain(root, &cfg.cargo_check)?;
+        let per_crate = impact::is_nightly(&toolchain.cargo);
+        let sources = Discover::discover_rs_files_report_with(
+            root,
+            &cfg.include,
+            &cfg.exclude,
+            cfg.follow_symlinks,
+            Self::ignore_files(cfg),
+            &cfg.left_out_areas(),
+        )?
+        .files;
+        let (before, before_root) = Self::scratch_copy(root, cfg, "impact")?;
+        let (after, after_root) = Self::scratch_copy(root, cfg, "impact")?;
+        let time = |copy_root: &Path, command: &str| {
+            let sources: Vec<PathBuf> = sources
+                .iter()
+                .map(|p| copy_root.join(p.strip_prefix(root).unwrap_or(p)))
+                .collect();
+            impact::measure(
+                copy_root,
+                &cfg.cargo_check,
+                command,
+                &cfg.cargo_check.target_dir(copy_root),
+                &sources,
+                iterations,
+                per_crate,
+            )
+        };
+        let commands: &[&str] = if options.build {
+            &["check", "build"]
+        } else {
+            &["check"]
+        };
+        let measured = (|| {
+            let originals = commands
+                .iter()
+                .map(|c| time(&before_root, c))
+                .collect::<TraitError<Vec<_>>>()?;
+            let mut out = self.prune_in(&after_root, cfg, observer)?;
+            out.modified.clear();
+            if out.cancelled {
+                return Ok(out);
+            }
+            let mut comparisons = Vec::new();
+            for (command, before) in commands.iter().zip(originals) {
+                let after = time(&after_root, command)?;
+                comparisons.push(Comparison { before, after });
+            }
+            let mut comparisons = comparisons.into_iter();
+            out.report.impact = Some(Impact {
+                iterations,
+                check: comparisons.next().unwrap_or_default(),
+                build: comparisons.next(),
+            });
+            Ok(out)
+        })();
+        before.finish()?;
+        after.finish()?;
+        measured
+    }
+
+    /// Try each candidate of `item` alone, as [`trial_candidates`] does, on a copy of the
+    /// workspace holding the target: the real tree is never written, so a run killed
+    /// mid-trial leaves nothing to recover. Checks build into the real target directory to
+    /// reuse its dependencies. Like `prune`, refuses to start while an interrupted run's
+    /// trials are left behind (see [`preflight`]).
+    pub fn trial_item(&self, item: &mut ItemExplanation) -> TraitError<()> {
+        let (root, cfg) = match (&self.kind, &self.config) {
+            (TargetKind::Crate(root) | TargetKind::Workspace(root), Some(cfg)) => (root, cfg),
+            _ => {
+                return Err(WinnowerError::Target {
+                    path: self.kind.path().to_path_buf(),
+                    detail: "trials need a crate or workspace".into(),
+                });
+            }
+        };
+        let validation = if self.validator.is_some() {
+            Validation::Custom
+        } else {
+            Validation::Cargo
+        };
+        preflight(root, cfg, validation)?;
+        let mut check_config = cfg.cargo_check.clone();
+        check_config.target_dir = check_config.target_dir(root);
+        let cargo_check = CargoCheck::new(check_config);
+        let validator = self.validator.as_deref().unwrap_or(&cargo_check);
+        let (copy, copy_root) = Self::scratch_copy(root, cfg, "explain")?;
+        let tried = trial_candidates(&copy_root, validator, item);
+        copy.finish()?;
+        tried
+    }
+
+    /// Prune the crate or workspace at `root`, configured by `cfg`.
+    fn prune_in(
+        &self,
+        root: &Path,
+        cfg: &Config,
+        observer: &mut dyn Observer,
+    ) -> TraitError<PruneReport> {
+        let started = Instant::now();
+        let mut out = PruneReport::default();
+        let mut originals = HashMap::new();
+        out.report.validation = self.validation();
+        out.report.strategy = self.strategy;
+        let (files, map) = self.discover(root, cfg, &mut out.skipped, &mut out.shared)?;
+        let fingerprint = Fingerprint::capture(root);
+        out.report.fingerprint = Some(fingerprint.clone());
+        if !self.brute_force {
+            return Ok(out);
+        }
+        // Each file as the run found it, to tell later whether someone else wrote it.
+        let stamps = Self::stamp_files(root, &files)?;
+        if self.commit.is_some() && !self.dry_run {
+            require_clean(root)?;
+        }
+        let session = match &self.session {
+            Some(SessionFile::Record(path)) => Some(Session::record(path, root, &files)),
+            Some(SessionFile::Replay(path)) => Some(Session::replay(path, root, &files)?),
+            None => None,
+        }
+        .filter(|_| self.validator.is_none())
+        .map(Arc::new);
+        let validation = match &session {
+            _ if self.validator.is_some() => Validation::Custom,
+            Some(session) if session.is_replay() => Validation::Replay,
+            _ => Validation::Cargo,
+        };
+        let toolchain = preflight(root, cfg, validation)?;
+        if let Some(session) = session.as_ref().filter(|s| s.is_replay()) {
+            // Nothing runs cargo: the toolchain is the recording's.
+            if let Some(v) = &mut out.report.validation {
+                v.toolchain = session.toolchain();
+            }
+        } else if let Some(toolchain) = toolchain {
+            if let Some(session) = &session {
+                session.set_toolchain(toolchain.clone());
+            }
+            if let Some(v) = &mut out.report.validation {
+                v.toolchain = Some(toolchain);
+            }
+        }
+        // Custom validators answer for themselves; only `cargo check` outcomes are cached.
+        // Recorded and replayed checks bypass the cache.
+        let trial_cache = cfg
+            .trial_cache
+            .as_ref()
+            .filter(|_| self.validator.is_none() && session.is_none())
+            .map(|c| TrialCache::open(root, c));
+        let worktree = self.worktree(root, cfg, &map, trial_cache.as_ref())?;
+        let trial_root = worktree.as_ref().map_or(root, Worktree::root);
+        let mut check_config = cfg.cargo_check.clone();
+        // Trials in the worktree still build into the real tree's target directory, so
+        // later runs reuse it.
+        check_config.target_dir = check_config.target_dir(root);
+        // Removals in tests, examples and benches only count if their targets build.
+        check_config.cover_areas(TargetArea::ALL.into_iter().filter(|area| {
+            files
+                .iter()
+                .any(|f| area.contains(f.strip_prefix(root).unwrap_or(f)))
+        }));
+        if let Some(v) = &mut out.report.validation {
+            v.args = check_config.args.clone();
+            v.gaps = check_config.coverage_gaps();
+        }
+        let mut cargo_check = CargoCheck::new(check_config);
+        if cfg.validator == ValidatorKind::Clippy && cfg.clippy.gate == ClippyGate::PerCandidate {
+            cargo_check = cargo_check.with_clippy(cfg.clippy.clone());
+        }
+        if let Some(session) = &session {
+            cargo_check = cargo_check.with_session(Arc::clone(session));
+        }
+        if let Some(notice) = &self.lock_notice {
+            cargo_check = cargo_check.with_lock_notice(notice.clone());
+        }
+        let toolchain = out
+            .report
+            .validation
+            .as_ref()
+            .and_then(|v| v.toolchain.as_ref())
+            .map(ToString::to_string)
+            .unwrap_or_default();
+        let trait_index = (cfg.foreign_trait_impls != ForeignTraitImpls::Allow)
+            .then(|| TraitIndex::of_files(&files, map.package_names()));
+        let references = self.references(root, &files)?;
+        let store = JournaledStore::new(&FsStore, Journal::start(root)?);
+        let shadow = worktree.as_ref().map(|tree| ShadowStore::new(tree, &store));
+        let ctx = PruneContext {
+            crate_root: trial_root,
+            validator: self.validator.as_deref().unwrap_or(&cargo_check),
+            cancel: &self.cancel,
+            store: match &shadow {
+                Some(shadow) => shadow,
+                None => &store,
+            },
+            allow_dyn_compat_changes: self.allow_dyn_compat_changes,
+            allow_unsafe_impl_pruning: self.allow_unsafe_impl_pruning,
+            include_opaque_types: self.include_opaque_types,
+            only_traits: &self.only_traits,
+            skip_derived_types: cfg.skip_derived_types,
+            skip_blanket_impls: self.skip_blanket_impls,
+            trait_index: trait_index.as_ref(),
+            foreign_trait_impls: cfg.foreign_trait_impls,
+            cargo_check: self.cargo_check(),
+            prefilter: cfg.prefilter,
+            references: references.as_ref(),
+            min_weight: self.min_weight,
+            memo: None,
+        };
+        let mut cache = RunCache::load(root);
+        // New dependency versions or another compiler can change any recorded outcome.
+        if self.incremental.is_some()
+            && !self.ignore_fingerprint
+            && !cache.files.is_empty()
+            && let Some(drift) =
+                Drift::between("run cache", cache.fingerprint.as_ref(), &fingerprint)
+        {
+            out.drift = Some(drift);
+            cache = RunCache::default();
+        }
+        let settings = self.settings_hash(root, cfg);
+        let crate_hash = RunCache::hash_crate(stamps.iter().map(|(p, s)| (p.as_path(), s.hash)));
+        let mut next = RunCache {
+            settings,
+            ..RunCache::default()
+        };
+        let mut stats = CacheStats::default();
+        let mut finished = Vec::new();
+        let types = self.types();
+        // How long a check of the unmodified crate takes, timed before the first trial.
+        let mut baseline = None;
+        let mut baseline_timed = false;
+        let mut runner = BatchRunner::new(&ctx, self.batch_size);
+        // Files queued on `runner`: path, path relative to the root, text and metrics so far.
+        let mut batched = Vec::new();
+
+        let (mut order, mut schedule) = schedule(root, &files, &map, cfg.order);
+        if let Some(references) = references
+            .as_ref()
+            .filter(|_| self.sort_by == SortBy::Weight)
+        {
+            // Heaviest file first; the schedule's order breaks ties.
+            let weights: Vec<usize> = files.iter().map(|f| references.file_weight(f)).collect();
+            let mut ranked: Vec<_> = order.into_iter().zip(schedule.files).collect();
+            ranked.sort_by_key(|(i, _)| Reverse(weights[*i]));
+            (order, schedule.files) = ranked.into_iter().unzip();
+        }
+        out.report.schedule = Some(schedule);
+
+        for (f, (rel, stamp)) in order.iter().map(|&i| (&files[i], &stamps[i])) {
+            if self.cancel.is_cancelled() {
+                out.cancelled = true;
+                break;
+            }
+            if let Some(mode) = self.incremental
+                && let Some(outcome) = cache.reusable(mode, settings, crate_hash, rel, stamp.hash)
+            {
+                next.files.insert(rel.clone(), outcome.clone());
+                stats.hits += 1;
+                continue;
+            }
+            stats.misses += 1;
+            // A trial can only break the packages compiling the file and their dependents.
+            let scoped = cargo_check.clone().with_packages(map.check_scope(f));
+            let cached = trial_cache
+                .as_ref()
+                .map(|cache| CachedCheck::new(cache, scoped.clone(), &map, &[f], root, &toolchain));
+            let memo = RefCell::new(RejectionMemo::default());
+            let file_ctx = PruneContext {
+                validator: match &cached {
+                    Some(cached) => cached,
+                    None => self.validator.as_deref().unwrap_or(&scoped),
+                },
+                memo: self.assume_consistent.then_some(&memo),
+                ..ctx
+            };
+            let file_started = Instant::now();
+            let Some(source) = read_or_skip(f, &mut out.not_utf8)? else {
+                continue;
+            };
+            // Written since the run started, or open in an editor: trials would lose edits.
+            if let Some(edit) = external_edit(f, stamp, &source, cfg.respect_editor_locks) {
+                out.external_edits.push(edit);
+                continue;
+            }
+            observer.on_file_start(f);
+            let file = syn::parse_file(&source).map_err(|e| WinnowerError::parse(f, e))?;
+            let mut metrics = FileMetrics {
+                parse: file_started.elapsed(),
+                ..FileMetrics::default()
+            };
+            let mut items = ItemBounds::collect_items_in_file(&file)?;
+            metrics.items = items.iter_all_items().count();
+            if let Some(max) = self.max_candidates_per_file {
+                // Count only what would be tried: filtered bounds cost no check.
+                metrics.truncated = items.truncate_candidates(&types, max, |item, candidates| {
+                    file_ctx.tried(item, candidates)
+                });
+            }
+            if items.iter_all_items().next().is_none() {
+                // Nothing to try: free the tree now rather than copy it for trials.
+                drop(items);
+                drop(file);
+                metrics.total = file_started.elapsed();
+                Self::file_done(
+                    f,
+                    rel,
+                    &source,
+                    &[],
+                    metrics,
+                    &mut out,
+                    &mut originals,
+                    observer,
+                )?;
+                finished.push(rel.clone());
+                continue;
+            }
+            // A replay has no recording of the unmodified crate to time.
+            if !baseline_timed && validation != Validation::Replay {
+                baseline_timed = true;
+                let validator = self.validator.as_deref().unwrap_or(&scoped);
+                baseline = self.time_baseline(validator, trial_root, &trial_root.join(rel))?;
+            }
+            if self.batch_size > 1 {
+                runner.add_file(f, &file, &items, &types, observer)?;
+                batched.push((f, rel, source, metrics));
+                continue;
+            }
+
+            let mut results = Vec::new();
+            // One tree for all passes, so each writes the file with the removals before it.
+            let mut working = file.clone();
+            for &ty in &types {
+                results.extend(Self::prune_type(
+                    ty,
+                    &file_ctx,
+                    f,
+                    &mut working,
+                    &mut items,
+                    observer,
+                )?);
+                if self.cancel.is_cancelled() {
+                    out.cancelled = true;
+                    break;
+                }
+            }
+            metrics.total = file_started.elapsed();
+            Self::file_done(
+                f,
+                rel,
+                &source,
+                &results,
+                metrics,
+                &mut out,
+                &mut originals,
+                observer,
+            )?;
+            if out.cancelled {
+                break;
+            }
+            finished.push(rel.clone());
+        }
+
+        if !batched.is_empty() {
+            let check = |paths: &[&Path]| {
+                let scoped = cargo_check
+                    .clone()
+                    .with_packages(Self::batch_scope(&map, paths));
+                match (self.validator.as_deref(), &trial_cache) {
+                    (Some(validator), _) => {
+                        validator.validate_cancellable(trial_root, &self.cancel)
+                    }
+                    (None, Some(cache)) => {
+                        CachedCheck::new(cache, scoped, &map, paths, root, &toolchain)
+                            .validate_cancellable(trial_root, &self.cancel)
+                    }
+                    (None, None) => scoped.validate_cancellable(trial_root, &self.cancel),
+                }
+            };
+            runner.run(&check, observer)?;
+            out.cancelled |= self.cancel.is_cancelled();
+            for (done, (f, rel, source, mut metrics)) in runner.finish().into_iter().zip(batched) {
+                // Each check is counted against the file of the first trial it validated.
+                metrics.checks = done.checks;
+                metrics.aborted = done.aborted;
+                metrics.aborted_time = done.aborted_time;
+                metrics.trial_cache_hits = done.cached;
+                let own = done.results.iter().map(|r| r.check_time + r.write_time);
+                metrics.total = metrics.parse + own.sum::<std::time::Duration>();
+                Self::file_done(
+                    f,
+                    rel,
+                    &source,
+                    &done.results,
+                    metrics,
+                    &mut out,
+                    &mut originals,
+                    observer,
+                )?;
+                if done.finished {
+                    finished.push(rel.clone());
+                }
+            }
+        }
+
+        for file in &mut out.report.files {
+            file.package = map.owner_of(&root.join(&file.path)).map(|p| p.name);
+        }
+        out.report.sort();
+        // A removal whose check took many times the unmodified crate's may slow every build
+        // down.
+        let factor = cfg.slow_removal_factor;
+        if let Some(mut slow) = baseline.and_then(|b| out.report.flag_slow_removals(b, factor)) {
+            if self.reject_slow_removals && !out.cancelled {
+                let revert: HashSet<(usize, usize)> = out
+                    .report
+                    .files
+                    .iter()
+                    .enumerate()
+                    .flat_map(|(fi, f)| {
+                        f.removals
+                            .iter()
+                            .enumerate()
+                            .filter(|(_, r)| r.slowdown.is_some())
+                            .map(move |(ri, _)| (fi, ri))
+                    })
+                    .collect();
+                Self::put_back(root, ctx.validator, &originals, &mut out, &revert)?;
+                slow.reverted = true;
+            }
+            out.report.slow_removals = Some(slow);
+        }
+        if self.validator.is_none()
+            && cfg.cargo_check.matrix_mode == MatrixMode::FinalOnly
+            && !cfg.cargo_check.matrix.is_empty()
+            && !out.cancelled
+            && !originals.is_empty()
+        {
+            let outcome = cargo_check
+                .clone()
+                .with_full_matrix()
+                .validate_cancellable(root, &self.cancel)?;
+            if !outcome.success {
+                Self::revert_all(&originals, &mut out)?;
+                originals.clear();
+                out.matrix_failure = Some(outcome.stderr);
+            }
+        }
+        if self.validator.is_none()
+            && cfg.validator == ValidatorKind::Clippy
+            && cfg.clippy.gate == ClippyGate::Final
+            && !out.cancelled
+            && !originals.is_empty()
+        {
+            let outcome = cargo_check
+                .clone()
+                .with_clippy(cfg.clippy.clone())
+                .validate_cancellable(root, &self.cancel)?;
+            if !outcome.success {
+                Self::revert_all(&originals, &mut out)?;
+                originals.clear();
+                out.clippy_failure = Some(outcome.stderr);
+            }
+        }
+        if cfg.semver_check && !out.cancelled && !originals.is_empty() {
+            out.semver = Some(self.semver_gate(root, ctx.validator, &originals, &mut out)?);
+        }
+        // Once every put-back is done, as a bound put back may need its import again.
+        if self.tidy_imports && !out.cancelled && !originals.is_empty() {
+            self.tidy_imports_in(root, ctx.validator, &mut out)?;
+        }
+        // Bounds kept only for bounds removed after them; suggested, the tree is left alone.
+        if !out.cancelled && self.strategy.is_none_or(|s| s.unlocks) {
+            out.report.unlocks = unlock_chains(root, &out.report, ctx.validator, &self.cancel)?;
+        }
+        // Impls the removals left derivable; suggested, the tree is left alone.
+        if !out.cancelled {
+            out.report.derive_candidates = derive_candidates(root, &out.report, &originals)?;
+        }
+        // Retained bounds only test code needs; diagnosed, the tree is left alone.
+        if self.explain_test_only
+            && !out.cancelled
+            && self.validator.is_none()
+            && session.is_none()
+            && let Some(config) = without_test_targets(cargo_check.config())
+        {
+            explain_test_only(root, &mut out.report, &config, &self.cancel)?;
+        }
+        if let Some(granularity) = self.commit.filter(|_| !self.dry_run) {
+            let by_rel = originals
+                .iter()
+                .map(|(p, s)| (p.strip_prefix(root).unwrap_or(p).to_path_buf(), s.clone()))
+                .collect();
+            let template = self
+                .commit_message
+                .as_deref()
+                .unwrap_or(granularity.default_template());
+            out.commits = Some(commit_removals(
+                root,
+                &out.report.files,
+                &by_rel,
+                granularity,
+                template,
+            ));
+        }
+        if self.dry_run {
+            for (path, original) in &originals {
+                std::fs::write(path, original)
+                    .map_err(|e| WinnowerError::io("restoring", path, e))?;
+            }
+            out.modified.clear();
+        }
+        // Why retained bounds stay, as comments above their items; nothing else moves.
+        if self.annotate_retained && !self.dry_run && !out.cancelled {
+            for change in annotate_report(root, &out.report)? {
+                out.annotated += change.new.lines().filter(|l| is_marker(l)).count();
+                let path = root.join(&change.path);
+                if !out.modified.contains(&path) {
+                    out.modified.push(path);
+                }
+            }
+        }
+        store.finish()?;
+        if let Some(session) = &session {
+            session.save()?;
+        }
+        if let Some(tree) = worktree {
+            tree.finish()?;
+        }
+        if let Some(cache) = &trial_cache {
+            cache.evict()?;
+        }
+        if self.validator.is_none() {
+            let dir = cargo_check.config().target_dir(root);
+            let dir = CheckTargetDir::finish(dir, self.keep_target_dir)?;
+            if let Some(v) = &mut out.report.validation {
+                v.target_dir = Some(dir);
+            }
+        }
+
+        // Record what the run left on disk; files it never finished are tried next time.
+        for file in out
+            .report
+            .files
+            .iter()
+            .filter(|f| finished.contains(&f.path))
+        {
+            let path = root.join(&file.path);
+            let text = std::fs::read(&path).map_err(|e| WinnowerError::io("reading", &path, e))?;
+            next.files
+                .insert(file.path.clone(), FileOutcome::of(file, &text));
+        }
+        let now = Self::hash_files(root, &files)?;
+        next.crate_hash = RunCache::hash_crate(now.iter().map(|(p, h)| (p.as_path(), *h)));
+        // The checks may have written `Cargo.lock`; the next run compares with what they left.
+        next.fingerprint = Some(Fingerprint {
+            lockfile: Fingerprint::hash_lockfile(root),
+            ..fingerprint
+        });
+        // A dry run removed nothing, so its files would be skipped next time unpruned.
+        if !self.dry_run {
+            next.save(root)?;
+        }
+        if self.incremental.is_some() {
+            out.cache = Some(stats);
+        }
+
+        out.modified.sort();
+        out.report.wall_time = started.elapsed();
+        Ok(out)
+    }
+
+    /// Book the pruning of `path`, which read `source` before: whether it changed, its
+    /// removals and `metrics` completed with their counters.
+    #[allow(clippy::too_many_arguments)]
+    fn file_done(
+        path: &Path,
+        rel: &Path,
+        source: &str,
+        results: &[BoundRemovalResult],
+        mut metrics: FileMetrics,
+        out: &mut PruneReport,
+        originals: &mut HashMap<PathBuf, String>,
+        observer: &mut dyn Observer,
+    ) -> TraitError<()> {
+        // Compare final contents: edited-then-reverted files don't count.
+        let now = std::fs::read(path).map_err(|e| WinnowerError::io("reading", path, e))?;
+        if now != source.as_bytes() {
+            out.modified.push(path.to_path_buf());
+            originals.insert(path.to_path_buf(), source.to_owned());
+        }
+        let removals: Vec<Removal> = results.iter().map(Removal::from_result).collect();
+        metrics.add_removals(&removals);
+        observer.on_file_done(&metrics);
+        out.report.files.push(FileReport::new(
+            rel.to_path_buf(),
+            Vec::new(),
+            removals,
+            metrics,
+        ));
+        Ok(())
+    }
+
+    /// The packages a batch editing `paths` can break: the union of their scopes, or the
+    /// whole workspace when one of them has none (see [`CrateMap::check_scope`]).
+    fn batch_scope(map: &CrateMap, paths: &[&Path]) -> Vec<String> {
+        let mut scope = Vec::new();
+        for path in paths {
+            let own = map.check_scope(path);
+            if own.is_empty() {
+                return own;
+            }
+            scope.extend(own);
+        }
+        scope.sort();
+        scope.dedup();
+        scope
+    }
+
+    /// Each of `files` relative to `root`, with the CRC32 of its text.
+    fn hash_files(root: &Path, files: &[PathBuf]) -> TraitError<Vec<(PathBuf, u32)>> {
+        Ok(Self::stamp_files(root, files)?
+            .into_iter()
+            .map(|(rel, stamp)| (rel, stamp.hash))
+            .collect())
+    }
+
+    /// Each of `files` with its path relative to `root` and its [`FileStamp`].
+    /// [`Validator::baseline`] of the crate at `root` as it is, with `file` touched so its
+    /// package is checked again as in a trial.
+    fn time_baseline(
+        &self,
+        validator: &dyn Validator,
+        root: &Path,
+        file: &Path,
+    ) -> TraitError<Option<Duration>> {
+        // Left untouched, cargo may find nothing to do: the baseline is only quicker.
+        let _ = std::fs::File::options()
+            .append(true)
+            .open(file)
+            .and_then(|f| f.set_modified(SystemTime::now()));
+        validator.baseline(root, &self.cancel)
+    }
+
+    fn stamp_files(root: &Path, files: &[PathBuf]) -> TraitError<Vec<(PathBuf, FileStamp)>> {
+        files
+            .iter()
+            .map(|f| {
+                Ok((
+                    f.strip_prefix(root).unwrap_or(f).to_path_buf(),
+                    FileStamp::of(f)?,
+                ))
+            })
+            .collect()
+    }
+
+    /// Hash of what decides a file's outcome besides the crate's sources: the manifest,
+    /// the configuration and the options of this run.
+    fn settings_hash(&self, root: &Path, cfg: &Config) -> u32 {
+        let mut hasher = crc32fast::Hasher::new();
+        hasher.update(&std::fs::read(root.join("Cargo.toml")).unwrap_or_default());
+        hasher.update(serde_json::to_string(cfg).unwrap_or_default().as_bytes());
+        hasher.update(
+            format!(
+                "{:?} {:?} {} {} {} {} {} {} {} {}",
+                self.types(),
+                self.only_traits,
+                self.min_weight,
+                self.allow_dyn_compat_changes,
+                self.allow_unsafe_impl_pruning,
+                self.include_opaque_types,
+                self.skip_blanket_impls,
+                self.assume_consistent,
+                self.batch_size,
+                self.validator.is_some()
+            )
+            .as_bytes(),
+        );
+        hasher.finalize()
+    }
+
+    /// The copy of the workspace at `root` trials run in (see [`Worktree`]), unless the
+    /// configuration turns it off, a custom validator checks the real tree, or `map` shows
+    /// `root` is not the root of its workspace, so a copy would not build on its own.
+    fn worktree(
+        &self,
+        root: &Path,
+        cfg: &Config,
+        map: &CrateMap,
+        trial_cache: Option<&TrialCache>,
+    ) -> TraitError<Option<Worktree>> {
+        let canonical = |p: &Path| p.canonicalize().unwrap_or_else(|_| p.to_path_buf());
+        let own_root = map
+            .workspace_root()
+            .is_some_and(|w| canonical(w) == canonical(root));
+        if !cfg.worktree || self.validator.is_some() || !own_root {
+            return Ok(None);
+        }
+        let mut skip = vec![cfg.cargo_check.target_dir(root)];
+        skip.extend(trial_cache.map(|c| c.dir().to_path_buf()));
+        Worktree::create(root, &root.join(WORKTREE_DIR), &skip).map(Some)
+    }
+
+    /// Restore every file in `originals`, turning its removals into retained bounds.
+    fn revert_all(originals: &HashMap<PathBuf, String>, out: &mut PruneReport) -> TraitError<()> {
+        for (path, original) in originals {
+            std::fs::write(path, original).map_err(|e| WinnowerError::io("reverting", path, e))?;
+        }
+        for r in out.report.files.iter_mut().flat_map(|f| &mut f.removals) {
+            if r.status == RemovalStatus::Removed {
+                r.status = RemovalStatus::Retained;
+            }
+        }
+        out.modified.clear();
+        Ok(())
+    }
+
+    /// Compare the pruned crate with `originals` put back. On a breaking change, restore
+    /// every file with a removal on a public item and re-apply its other removals.
+    fn semver_gate(
+        &self,
+        root: &Path,
+        validator: &dyn Validator,
+        originals: &HashMap<PathBuf, String>,
+        out: &mut PruneReport,
+    ) -> TraitError<SemverReport> {
+        let baseline = Baseline::create(root, originals)?;
+        let gate = self.semver_gate.as_deref().unwrap_or(&CargoSemverChecks);
+        let outcome = gate.compare(root, baseline.path())?;
+        let mut report = SemverReport {
+            outcome,
+            reverted: Vec::new(),
+            triggers: Vec::new(),
+        };
+        let SemverOutcome::Breaking { summary } = &report.outcome else {
+            return Ok(report);
+        };
+
+        // (file, removal) of each removal on a public item.
+        let mut public = HashSet::new();
+        for (fi, file) in out.report.files.iter().enumerate() {
+            let path = root.join(&file.path);
+            let Some(original) = originals.get(&path) else {
+                continue;
+            };
+            let syntax = syn::parse_file(original).map_err(|e| WinnowerError::parse(&path, e))?;
+            let items = ItemBounds::collect_items_in_file(&syntax)?;
+            let keys: Vec<&ItemKey<'_>> = items.iter_all_items().collect();
+            for (ri, r) in file.removals.iter().enumerate() {
+                if r.status == RemovalStatus::Removed
+                    && locate(&keys, &file.path, &r.finding.locator)
+                        .is_some_and(|k| keys[k].item().is_public())
+                {
+                    public.insert((fi, ri));
+                }
+            }
+        }
+        report.reverted = Self::put_back(root, validator, originals, out, &public)?;
+
+        let named: Vec<&str> = summary
+            .split(|c: char| !(c.is_alphanumeric() || c == '_'))
+            .collect();
+        report.triggers = report
+            .reverted
+            .iter()
+            .filter(|f| {
+                let name = f
+                    .locator
+                    .name
+                    .rsplit("::")
+                    .next()
+                    .unwrap_or_default()
+                    .trim();
+                named.contains(&name)
+            })
+            .cloned()
+            .collect();
+        Ok(report)
+    }
+
+    /// Drop the imports the removals of each file left unused, one at a time, putting back
+    /// those the check fails without, and record them in [`Report::tidied_imports`]. The
+    /// tolerated `unused_imports` lints an import dropped answers are no longer follow-ups.
+    fn tidy_imports_in(
+        &self,
+        root: &Path,
+        validator: &dyn Validator,
+        out: &mut PruneReport,
+    ) -> TraitError<()> {
+        let mut tidied = Vec::new();
+        for file in &mut out.report.files {
+            let names = removed_names(&file.removals);
+            if names.is_empty() {
+                continue;
+            }
+            let path = root.join(&file.path);
+            let mut kept = HashSet::new();
+            loop {
+                let source = ItemBounds::read_source(&path)?;
+                let unused = unused_imports(&source, &names)
+                    .map_err(|e| WinnowerError::parse(&file.path, e))?;
+                let Some(import) = unused.into_iter().find(|u| !kept.contains(&u.path)) else {
+                    break;
+                };
+                let tidy = apply_edits(&source, std::slice::from_ref(&import.edit));
+                std::fs::write(&path, tidy).map_err(|e| WinnowerError::io("writing", &path, e))?;
+                let outcome = match validator.validate_cancellable(root, &self.cancel) {
+                    Ok(outcome) => outcome,
+                    Err(e) => {
+                        // The run stops; leave the file with its import.
+                        std::fs::write(&path, &source)
+                            .map_err(|e| WinnowerError::io("reverting", &path, e))?;
+                        return Err(e);
+                    }
+                };
+                let cancelled = self.cancel.is_cancelled();
+                let removed = outcome.success && !cancelled;
+                if !removed {
+                    std::fs::write(&path, &source)
+                        .map_err(|e| WinnowerError::io("reverting", &path, e))?;
+                    kept.insert(import.path.clone());
+                }
+                if cancelled {
+                    // Put back and undecided: not reported.
+                    out.cancelled = true;
+                    break;
+                }
+                tidied.push(TidiedImport {
+                    file: file.path.clone(),
+                    line: import.line,
+                    import: import.path,
+                    removed,
+                });
+            }
+            let gone: HashSet<&str> = tidied
+                .iter()
+                .filter(|t| t.removed && t.file == file.path)
+                .filter_map(|t| t.import.rsplit("::").next())
+                .collect();
+            for r in &mut file.removals {
+                r.follow_ups.retain(|u| {
+                    u.lint != "unused_imports"
+                        || !imported_names(&u.message).all(|n| gone.contains(n))
+                });
+            }
+            if out.cancelled {
+                break;
+            }
+        }
+        out.report.tidied_imports = tidied;
+        Ok(())
+    }
+
+    /// Put back the removals of `out` at `revert`, (file, removal) indices: restore their
+    /// files as `originals` has them and apply the other removals in them again, validated
+    /// by `validator`; those that no longer pass are put back too. Returns the findings of
+    /// every removal put back.
+    fn put_back(
+        root: &Path,
+        validator: &dyn Validator,
+        originals: &HashMap<PathBuf, String>,
+        out: &mut PruneReport,
+        revert: &HashSet<(usize, usize)>,
+    ) -> TraitError<Vec<Finding>> {
+        let mut reverted = Vec::new();
+        let mut reapply = Plan::default();
+        // (file, removal) of each entry in `reapply`.
+        let mut reapplied = Vec::new();
+        let mut restored = Vec::new();
+        for (fi, file) in out.report.files.iter_mut().enumerate() {
+            let path = root.join(&file.path);
+            let Some(original) = originals.get(&path) else {
+                continue;
+            };
+            if !(0..file.removals.len()).any(|ri| revert.contains(&(fi, ri))) {
+                continue;
+            }
+            for (ri, r) in file.removals.iter_mut().enumerate() {
+                if revert.contains(&(fi, ri)) {
+                    r.status = RemovalStatus::Reverted;
+                    reverted.push(r.finding.clone());
+                } else if r.status == RemovalStatus::Removed {
+                    reapply.entries.push(PlanEntry {
+                        locator: r.finding.locator.clone(),
+                        candidate: r.finding.candidate.clone(),
+                    });
+                    reapplied.push((fi, ri));
+                }
+            }
+            std::fs::write(&path, original)
+                .map_err(|e| WinnowerError::io("reverting", &path, e))?;
+            restored.push(path);
+        }
+
+        if !reapply.entries.is_empty() {
+            let options = ApplyOptions {
+                strict: false,
+                validator: Some(validator),
+                ..ApplyOptions::default()
+            };
+            let applied = apply_plan_with(root, &reapply, &options)?;
+            for (entry, &(fi, ri)) in applied.entries.iter().zip(&reapplied) {
+                if applied.reverted || entry.status != EntryStatus::Applied {
+                    let r = &mut out.report.files[fi].removals[ri];
+                    r.status = RemovalStatus::Reverted;
+                    reverted.push(r.finding.clone());
+                }
+            }
+        }
+        for path in restored {
+            let now = std::fs::read(&path).map_err(|e| WinnowerError::io("reading", &path, e))?;
+            if originals.get(&path).is_some_and(|o| o.as_bytes() == now) {
+                out.modified.retain(|p| *p != path);
+            }
+        }
+        Ok(reverted)
+    }
+
+    /// Concrete target types to prune, `All` expanded, without duplicates.
+    fn types(&self) -> Vec<TargetType> {
+        ALL_TYPES
+            .into_iter()
+            .filter(|t| {
+                self.target_types
+                    .iter()
+                    .any(|s| matches!(s, TargetType::All) || s == t)
+            })
+            .collect()
+    }
+
+    fn discover(
+        &self,
+        root: &Path,
+        cfg: &Config,
+        skipped: &mut Vec<SkippedFile>,
+        shared: &mut Vec<SharedFile>,
+    ) -> TraitError<(Vec<PathBuf>, CrateMap)> {
+        let files = self.discover_files(root, cfg, skipped)?;
+        // Without a usable `cargo metadata` nothing is known about which package owns what.
+        let map = CrateMap::from_metadata(root, &cfg.cargo_check.cargo()).unwrap_or_default();
+        shared.extend(files.iter().filter_map(|f| map.shared(f)));
+        Ok((files, map))
+    }
+
+    /// The ignore files `cfg` has discovery honor.
+    fn ignore_files(cfg: &Config) -> IgnoreFiles {
+        IgnoreFiles {
+            respect: cfg.respect_gitignore,
+            global: cfg.global_gitignore,
+        }
+    }
+
+    /// The sources under `root` that `cfg`, `--since` and the file limit let through.
+    fn discover_files(
+        &self,
+        root: &Path,
+        cfg: &Config,
+        skipped: &mut Vec<SkippedFile>,
+    ) -> TraitError<Vec<PathBuf>> {
+        let found = Discover::discover_rs_files_report_with(
+            root,
+            &cfg.include,
+            &cfg.exclude,
+            cfg.follow_symlinks,
+            Self::ignore_files(cfg),
+            &cfg.left_out_areas(),
+        )?;
+        *skipped = found.skipped;
+        let changed = match &self.since {
+            Some(rev) => Some(Discover::changed_since(root, rev)?),
+            None => None,
+        };
+        let mut files = Vec::new();
+        for path in found.files {
+            let rel = path.strip_prefix(root).unwrap_or(&path);
+            if changed.as_ref().is_some_and(|c| !c.contains(rel)) {
+                continue;
+            }
+            if CrateMap::is_generated(rel) {
+                skipped.push(SkippedFile {
+                    path,
+                    reason: SkipReason::Generated,
+                });
+            } else if files.len() < self.limit {
+                files.push(path);
+            }
+        }
+        Ok(files)
+    }
+
+    fn prune_type(
+        ty: TargetType,
+        ctx: &PruneContext<'_>,
+        path: &Path,
+        file: &mut syn::File,
+        items: &mut ItemBounds<'_>,
+        observer: &mut dyn Observer,
+    ) -> TraitError<Vec<BoundRemovalResult>> {
+        match ty {
+            TargetType::All => Ok(Vec::new()),
+            TargetType::Function => {
+                PruneItem::prune_function_bounds(path, file, items.fns_mut(), ctx, observer)
+            }
+            TargetType::Impl => {
+                PruneItem::prune_impl_bounds(path, file, items.impls_mut(), ctx, observer)
+            }
+            TargetType::Trait => {
+                PruneItem::prune_trait_bounds(path, file, items.traits_mut(), ctx, observer)
+            }
+            TargetType::TraitMethod => PruneItem::prune_trait_method_bounds(
+                path,
+                file,
+                items.trait_methods_mut(),
+                ctx,
+                observer,
+            ),
+            TargetType::ImplMethod => PruneItem::prune_impl_method_bounds(
+                path,
+                file,
+                items.impl_methods_mut(),
+                ctx,
+                observer,
+            ),
+            TargetType::Enum => {
+                PruneItem::prune_enum_bounds(path, file, items.enums_mut(), ctx, observer)
+            }
+            TargetType::Struct => {
+                PruneItem::prune_struct_bounds(path, file, items.structs_mut(), ctx, observer)
+            }
+        }
+    }
+}
+
+/// Read a discovered file, recording files that are not UTF-8 instead of failing.
+fn read_or_skip(path: &Path, not_utf8: &mut Vec<NonUtf8Source>) -> TraitError<Option<String>> {
+    match ItemBounds::read_source(path) {
+        Ok(src) => Ok(Some(src)),
+        Err(WinnowerError::NotUtf8(skip)) => {
+            not_utf8.push(skip);
+            Ok(None)
+        }
+        Err(e) => Err(e),
+    }
+}
@@ -0,0 +1,69 @@
+// tests/annotate_tests.rs
+//! `prune --annotate-retained` notes why a retained bound stays above its item, replaces its
+//! notes on the next run, and `prune --strip-annotations` takes them out.
+#![cfg(feature = "cli")]
+
+mod common;
+
+use assert_cmd::Command;
+use common::TestResult;
+use predicates::str::contains;
+use std::path::Path;
+
+const LIB: &str = "\
+pub fn cache_insert<T: Clone>(t: T) -> (T, T) {
+    (t.clone(), t)
+}
+
+/// Stores `t`.
+#[inline]
+pub fn store<T: Clone>(t: T) -> (T, T) {
+    cache_insert(t)
+}
+";
+
+const NOTE: &str = "// trait-winnower: `T: Clone` required by a bound in `cache_insert` \
+                    (src/lib.rs:1)\n/// Stores `t`.\n#[inline]\npub fn store<T: Clone>";
+
+fn prune(root: &Path, flag: &str) -> assert_cmd::assert::Assert {
+    Command::cargo_bin("trait-winnower")
+        .expect("binary")
+        .args(["prune", "-b", flag])
+        .arg(root)
+        .assert()
+        .success()
+}
+
+#[test]
+fn retained_bounds_are_annotated_once_and_stripped() -> TestResult {
+    let tmp = tempfile::tempdir()?;
+    common::write_crate(tmp.path(), "notes", "", &[("src/lib.rs", LIB)])?;
+    let lib = tmp.path().join("src/lib.rs");
+
+    prune(tmp.path(), "--annotate-retained").stderr(contains(
+        "annotated 1 retained bound(s) with what requires them",
+    ));
+    let annotated = std::fs::read_to_string(&lib)?;
+    assert!(annotated.contains(NOTE), "{annotated}");
+
+    // Again: the note is replaced, not repeated.
+    prune(tmp.path(), "--annotate-retained");
+    assert_eq!(std::fs::read_to_string(&lib)?, annotated);
+
+    prune(tmp.path(), "--strip-annotations")
+        .stderr(contains("stripped 1 annotation(s) in 1 file(s)"));
+    assert_eq!(std::fs::read_to_string(&lib)?, LIB);
+    Ok(())
+}
+
+#[test]
+fn annotating_conflicts_with_a_dry_run() -> TestResult {
+    let tmp = tempfile::tempdir()?;
+    common::write_crate(tmp.path(), "notes", "", &[("src/lib.rs", LIB)])?;
+    Command::cargo_bin("trait-winnower")?
+        .args(["prune", "--annotate-retained", "--dry-run"])
+        .arg(tmp.path())
+        .assert()
+        .failure();
+    Ok(())
+}
@@ -0,0 +1,70 @@
+// tests/batch_tests.rs
+//! `--batch-size` validates removals from several files with one check, bisecting on failure.
+#![cfg(feature = "cli")]
+
+mod common;
+
+use common::TestResult;
+use std::path::Path;
+use trait_winnower::report::RemovalStatus;
+use trait_winnower::winnower::{PruneReport, Winnower};
+
+/// `a` has a bound to spare, `b` needs its own.
+const FILES: &[(&str, &str)] = &[
+    ("src/lib.rs", "pub mod a;\npub mod b;\n"),
+    ("src/a.rs", "pub fn f<T: Clone>(t: T) -> T {\n    t\n}\n"),
+    (
+        "src/b.rs",
+        "pub fn g<T: Clone>(t: &T) -> T {\n    t.clone()\n}\n",
+    ),
+];
+
+fn prune(dir: &Path, batch_size: usize) -> Result<PruneReport, Box<dyn std::error::Error>> {
+    Ok(Winnower::builder()
+        .target(dir)
+        .brute_force(true)
+        .batch_size(batch_size)
+        .build()?
+        .prune()?)
+}
+
+#[test]
+fn a_failing_batch_still_keeps_its_good_removals() -> TestResult {
+    let tmp = tempfile::tempdir()?;
+    common::write_crate(tmp.path(), "x", "", FILES)?;
+    let pruned = prune(tmp.path(), 2)?;
+    let status = |item: &str| {
+        pruned
+            .report
+            .files
+            .iter()
+            .flat_map(|f| &f.removals)
+            .find(|r| r.finding.item == item)
+            .map(|r| (r.status, r.batch))
+    };
+    assert_eq!(status("fn f"), Some((RemovalStatus::Removed, Some(1))));
+    assert_eq!(status("fn g"), Some((RemovalStatus::Retained, Some(1))));
+    // Both together, then `f` alone; `g` alone is then known to fail.
+    assert_eq!(pruned.report.total_checks(), 2);
+    let a = std::fs::read_to_string(tmp.path().join("src/a.rs"))?;
+    assert!(a.contains("pub fn f<T>"), "{a}");
+    let b = std::fs::read_to_string(tmp.path().join("src/b.rs"))?;
+    assert!(b.contains("pub fn g<T: Clone>"), "{b}");
+    Ok(())
+}
+
+#[test]
+fn the_cli_reports_the_batch_of_each_removal() -> TestResult {
+    use assert_cmd::Command;
+
+    let tmp = tempfile::tempdir()?;
+    common::write_crate(tmp.path(), "x", "", FILES)?;
+    let out = Command::cargo_bin("trait-winnower")?
+        .args(["prune", "-b", "--format", "json", "--batch-size", "2"])
+        .arg(tmp.path())
+        .assert()
+        .success();
+    let stdout = String::from_utf8(out.get_output().stdout.clone())?;
+    assert!(stdout.contains(r#""batch": 1"#), "{stdout}");
+    Ok(())
+}
@@ -0,0 +1,68 @@
+// tests/bench_tests.rs
+//! `trait-winnower bench` on the standard fixture: every strategy agrees on what goes, and
+//! the cheaper ones stay cheaper.
+#![cfg(feature = "cli")]
+
+mod common;
+
+use common::TestResult;
+use std::path::Path;
+use trait_winnower::bench::{Strategy, bench};
+
+const FIXTURE: &str = "tests/test_files/bench_fixture";
+
+#[test]
+fn strategies_agree_and_batch_bisect_saves_checks() -> TestResult {
+    let report = bench(Path::new(FIXTURE), &Strategy::ALL)?;
+    assert_eq!(report.rows.len(), Strategy::ALL.len());
+    let one_by_one = report.row("one-by-one").expect("listed");
+    for row in &report.rows {
+        assert_eq!(row.removed, one_by_one.removed, "{}", row.strategy.name);
+        assert_eq!(
+            row.candidates, one_by_one.candidates,
+            "{}",
+            row.strategy.name
+        );
+        assert!(row.trialed <= row.candidates);
+    }
+    assert_eq!(one_by_one.checks, one_by_one.candidates);
+
+    // Regression guards: raise these only with a reason.
+    let batch = report.row("batch-bisect").expect("listed");
+    assert!(
+        batch.checks <= 43,
+        "batch-bisect took {} checks",
+        batch.checks
+    );
+    assert!(batch.checks < one_by_one.checks);
+    let all = report.row("batch-bisect+prefilter+memo").expect("listed");
+    assert!(
+        all.checks <= 36,
+        "batch-bisect+prefilter+memo took {} checks",
+        all.checks
+    );
+    for name in ["prefilter", "memo"] {
+        assert!(report.row(name).expect("listed").trialed < one_by_one.trialed);
+    }
+    Ok(())
+}
+
+#[test]
+fn the_cli_prints_a_table_and_leaves_the_fixture_alone() -> TestResult {
+    use assert_cmd::Command;
+    use predicates::str::contains;
+
+    let before = std::fs::read_to_string(Path::new(FIXTURE).join("src/util.rs"))?;
+    Command::cargo_bin("trait-winnower")?
+        .args(["bench", "--fixture", FIXTURE])
+        .assert()
+        .success()
+        .stdout(contains("strategy"))
+        .stdout(contains("batch-bisect+prefilter+memo"));
+    assert_eq!(
+        std::fs::read_to_string(Path::new(FIXTURE).join("src/util.rs"))?,
+        before
+    );
+    assert!(!Path::new(FIXTURE).join("target").exists());
+    Ok(())
+}
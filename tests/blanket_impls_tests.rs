@@ -0,0 +1,156 @@
+// tests/blanket_impls_tests.rs
+//! Impls over a reference or `Box` of a type parameter: `prune` tries and edits their
+//! bounds like any other impl's, and `--skip-blanket-impls` leaves them alone.
+#![cfg(feature = "discover")]
+
+mod common;
+
+use common::TestResult;
+use trait_winnower::report::{RemovalStatus, Report};
+use trait_winnower::winnower::Winnower;
+
+const SRC: &str = "\
+use std::fmt::{self, Debug, Display};
+
+pub trait Describe {
+    fn describe(&self) -> String;
+}
+
+impl<T: Display + Clone> Describe for &T {
+    fn describe(&self) -> String {
+        format!(\"ref {self}\")
+    }
+}
+
+impl<T: Display + Default> Describe for &mut T {
+    fn describe(&self) -> String {
+        format!(\"mut {self}\")
+    }
+}
+
+impl<T: ?Sized + Describe + Debug> Describe for Box<T> {
+    fn describe(&self) -> String {
+        (**self).describe()
+    }
+}
+
+pub struct Wrapper<T>(pub T);
+
+impl<T: Display + Copy> Display for Wrapper<&T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, \"[{}]\", self.0)
+    }
+}
+";
+
+/// `(item, bound, status)` of every removal, sorted.
+fn outcomes(report: &Report) -> Vec<(String, String, RemovalStatus)> {
+    let mut out: Vec<_> = report
+        .files
+        .iter()
+        .flat_map(|f| &f.removals)
+        .map(|r| (r.finding.item.clone(), r.finding.bound.clone(), r.status))
+        .collect();
+    out.sort_by(|a, b| (&a.0, &a.1).cmp(&(&b.0, &b.1)));
+    out.dedup_by(|a, b| a.0 == b.0 && a.1 == b.1);
+    out
+}
+
+#[test]
+fn bounds_of_impls_over_references_and_boxes_are_pruned() -> TestResult {
+    let tmp = tempfile::tempdir()?;
+    common::write_crate(tmp.path(), "blanket", "", &[("src/lib.rs", SRC)])?;
+    let pruned = Winnower::builder()
+        .target(tmp.path())
+        .brute_force(true)
+        .build()?
+        .prune()?;
+
+    let want = |item: &str, bound: &str, status| (item.to_owned(), bound.to_owned(), status);
+    assert_eq!(
+        outcomes(&pruned.report),
+        [
+            want("impl Describe for & T", "Clone", RemovalStatus::Removed),
+            want("impl Describe for & T", "Display", RemovalStatus::Retained),
+            want(
+                "impl Describe for & mut T",
+                "Default",
+                RemovalStatus::Removed
+            ),
+            want(
+                "impl Describe for & mut T",
+                "Display",
+                RemovalStatus::Retained
+            ),
+            want(
+                "impl Describe for Box < T >",
+                "? Sized",
+                RemovalStatus::Skipped
+            ),
+            want(
+                "impl Describe for Box < T >",
+                "Debug",
+                RemovalStatus::Removed
+            ),
+            want(
+                "impl Describe for Box < T >",
+                "Describe",
+                RemovalStatus::Retained
+            ),
+            want(
+                "impl Display for Wrapper < & T >",
+                "Copy",
+                RemovalStatus::Removed
+            ),
+            want(
+                "impl Display for Wrapper < & T >",
+                "Display",
+                RemovalStatus::Retained
+            ),
+        ]
+    );
+
+    let src = std::fs::read_to_string(tmp.path().join("src/lib.rs"))?;
+    for line in [
+        "impl<T: Display> Describe for &T {",
+        "impl<T: Display> Describe for &mut T {",
+        "impl<T: ?Sized + Describe> Describe for Box<T> {",
+        "impl<T: Display> Display for Wrapper<&T> {",
+    ] {
+        assert!(src.contains(line), "{line}\n{src}");
+    }
+    Ok(())
+}
+
+#[test]
+fn skip_blanket_impls_leaves_only_other_impls_to_prune() -> TestResult {
+    let tmp = tempfile::tempdir()?;
+    common::write_crate(tmp.path(), "blanket", "", &[("src/lib.rs", SRC)])?;
+    let pruned = Winnower::builder()
+        .target(tmp.path())
+        .brute_force(true)
+        .skip_blanket_impls(true)
+        .build()?
+        .prune()?;
+
+    let tried: Vec<String> = outcomes(&pruned.report)
+        .into_iter()
+        .map(|(item, _, _)| item)
+        .collect();
+    assert!(
+        tried
+            .iter()
+            .all(|i| i == "impl Display for Wrapper < & T >"),
+        "{tried:?}"
+    );
+    let src = std::fs::read_to_string(tmp.path().join("src/lib.rs"))?;
+    for line in [
+        "impl<T: Display + Clone> Describe for &T {",
+        "impl<T: Display + Default> Describe for &mut T {",
+        "impl<T: ?Sized + Describe + Debug> Describe for Box<T> {",
+        "impl<T: Display> Display for Wrapper<&T> {",
+    ] {
+        assert!(src.contains(line), "{line}\n{src}");
+    }
+    Ok(())
+}
@@ -0,0 +1,137 @@
+// tests/cancel_tests.rs
+//! Cancelling a prune leaves the tree consistent and reports what finished.
+#![cfg(feature = "discover")]
+
+mod common;
+
+use common::TestResult;
+use std::time::{Duration, Instant};
+use trait_winnower::cancel::CancelToken;
+use trait_winnower::dynamic_analysis::common::BoundRemovalResult;
+use trait_winnower::dynamic_analysis::validate::{AlwaysOk, ScriptedValidator};
+use trait_winnower::observer::Observer;
+use trait_winnower::report::RemovalStatus;
+use trait_winnower::winnower::Winnower;
+
+const SRC: &str = "pub fn f<T: Clone + Send>(t: T) {}\npub fn g<U: Copy>(u: U) {}\n";
+
+/// Cancels its token after `after` outcomes.
+struct CancelAfter {
+    token: CancelToken,
+    after: usize,
+    seen: usize,
+}
+
+impl Observer for CancelAfter {
+    fn on_outcome(&mut self, _result: &BoundRemovalResult) {
+        self.seen += 1;
+        if self.seen == self.after {
+            self.token.cancel();
+        }
+    }
+}
+
+fn statuses(report: &trait_winnower::winnower::PruneReport) -> Vec<RemovalStatus> {
+    report
+        .report
+        .files
+        .iter()
+        .flat_map(|f| f.removals.iter().map(|r| r.status))
+        .collect()
+}
+
+#[test]
+fn cancelling_between_trials_keeps_accepted_removals() -> TestResult {
+    let tmp = tempfile::tempdir()?;
+    common::write_crate(tmp.path(), "x", "", &[("src/lib.rs", SRC)])?;
+    let token = CancelToken::new();
+    let mut observer = CancelAfter {
+        token: token.clone(),
+        after: 1,
+        seen: 0,
+    };
+
+    let pruned = Winnower::builder()
+        .target(tmp.path())
+        .brute_force(true)
+        .validator(AlwaysOk)
+        .cancel_token(token)
+        .build()?
+        .prune_with(&mut observer)?;
+
+    assert!(pruned.cancelled);
+    assert_eq!(statuses(&pruned), [RemovalStatus::Removed]);
+    let out = std::fs::read_to_string(tmp.path().join("src/lib.rs"))?;
+    assert!(!out.contains("Clone"), "{out}");
+    assert!(out.contains("Send") && out.contains("Copy"), "{out}");
+    assert_eq!(pruned.modified.len(), 1);
+    Ok(())
+}
+
+#[test]
+fn cancelling_mid_trial_reverts_the_trial() -> TestResult {
+    let tmp = tempfile::tempdir()?;
+    common::write_crate(tmp.path(), "x", "", &[("src/lib.rs", SRC)])?;
+    let token = CancelToken::new().with_timeout(Duration::from_millis(200));
+
+    let started = Instant::now();
+    let pruned = Winnower::builder()
+        .target(tmp.path())
+        .brute_force(true)
+        .validator(ScriptedValidator::new([true; 8]).with_delay(Duration::from_secs(30)))
+        .cancel_token(token)
+        .build()?
+        .prune()?;
+
+    assert!(started.elapsed() < Duration::from_secs(10));
+    assert!(pruned.cancelled);
+    assert!(statuses(&pruned).is_empty());
+    assert_eq!(std::fs::read_to_string(tmp.path().join("src/lib.rs"))?, SRC);
+    assert!(pruned.modified.is_empty());
+    Ok(())
+}
+
+#[test]
+fn cancelled_token_prunes_nothing() -> TestResult {
+    let tmp = tempfile::tempdir()?;
+    common::write_crate(tmp.path(), "x", "", &[("src/lib.rs", SRC)])?;
+    let token = CancelToken::new();
+    token.cancel();
+
+    let pruned = Winnower::builder()
+        .target(tmp.path())
+        .brute_force(true)
+        .validator(AlwaysOk)
+        .cancel_token(token)
+        .build()?
+        .prune()?;
+
+    assert!(pruned.cancelled);
+    assert!(pruned.report.files.is_empty());
+    assert_eq!(std::fs::read_to_string(tmp.path().join("src/lib.rs"))?, SRC);
+    Ok(())
+}
+
+#[cfg(unix)]
+#[test]
+fn cancelling_kills_the_validation_command() -> TestResult {
+    use trait_winnower::dynamic_analysis::validate::CommandValidator;
+
+    let tmp = tempfile::tempdir()?;
+    common::write_crate(tmp.path(), "x", "", &[("src/lib.rs", SRC)])?;
+    let token = CancelToken::new().with_timeout(Duration::from_millis(200));
+
+    let started = Instant::now();
+    let pruned = Winnower::builder()
+        .target(tmp.path())
+        .brute_force(true)
+        .validator(CommandValidator::new(["sleep", "30"])?)
+        .cancel_token(token)
+        .build()?
+        .prune()?;
+
+    assert!(started.elapsed() < Duration::from_secs(10));
+    assert!(pruned.cancelled);
+    assert_eq!(std::fs::read_to_string(tmp.path().join("src/lib.rs"))?, SRC);
+    Ok(())
+}
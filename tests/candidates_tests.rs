@@ -0,0 +1,133 @@
+// tests/candidates_tests.rs
+//! One-call candidate enumeration per file and across a crate.
+#![cfg(feature = "cli")]
+
+mod common;
+
+use common::TestResult;
+use std::path::Path;
+use trait_winnower::analysis::{CrateBounds, ItemBounds, ItemKind};
+use trait_winnower::cli::TargetType;
+use trait_winnower::report::FileReport;
+
+const SANDBOX: &str = "tests/test_files/trait_sandbox/src";
+
+fn parse(name: &str) -> Result<syn::File, Box<dyn std::error::Error>> {
+    Ok(ItemBounds::parse_file(&Path::new(SANDBOX).join(name))?)
+}
+
+#[test]
+fn all_candidates_match_the_check_findings() -> TestResult {
+    for name in ["a.rs", "b.rs", "c.rs", "lib.rs", "traits.rs"] {
+        let file = parse(name)?;
+        let items = ItemBounds::collect_items_in_file(&file)?;
+        let report = FileReport::from_items(name.into(), &items);
+        let mut from_iter: Vec<_> = items.all_candidates().map(|(_, c)| c).collect();
+        let mut from_check: Vec<_> = report.findings.into_iter().map(|f| f.candidate).collect();
+        from_iter.sort_by_key(|c| format!("{c:?}"));
+        from_check.sort_by_key(|c| format!("{c:?}"));
+        assert_eq!(from_iter, from_check, "{name}");
+    }
+    Ok(())
+}
+
+#[test]
+fn filters_select_target_types_and_traits() -> TestResult {
+    let src = "\
+fn f<T: Clone + std::fmt::Debug>(t: T) {}
+struct S<T: Clone>(T);
+impl<T> S<T> where T: Iterator<Item = u8> { fn m<U: Send>(&self) {} }
+";
+    let file = syn::parse_file(src)?;
+    let items = ItemBounds::collect_items_in_file(&file)?;
+    assert_eq!(items.all_candidates().count(), 5);
+
+    let fns: Vec<_> = items
+        .filtered_candidates(&[TargetType::Function], &[])
+        .collect();
+    assert_eq!(fns.len(), 2);
+    assert!(fns.iter().all(|(loc, _)| loc.kind == ItemKind::Fn));
+
+    let all = [TargetType::All];
+    let bound_texts = |traits: &[String]| -> Vec<String> {
+        items
+            .filtered_candidates(&all, traits)
+            .map(|(_, c)| c.bound_text)
+            .collect()
+    };
+    assert_eq!(bound_texts(&["Clone".into()]), ["Clone", "Clone"]);
+    assert_eq!(bound_texts(&["Debug".into()]), ["std :: fmt :: Debug"]);
+    assert_eq!(
+        bound_texts(&["std::fmt::Debug".into(), "Iterator".into()]),
+        ["std :: fmt :: Debug", "Iterator < Item = u8 >"]
+    );
+    assert!(bound_texts(&["Copy".into()]).is_empty());
+    // A filter with arguments names only the bound written that way.
+    assert_eq!(
+        bound_texts(&["Iterator<Item = u8>".into()]),
+        ["Iterator < Item = u8 >"]
+    );
+    assert!(bound_texts(&["Iterator<Item = u16>".into()]).is_empty());
+    Ok(())
+}
+
+#[test]
+fn trait_filters_look_past_arguments_and_signatures() -> TestResult {
+    let src = "\
+fn f<F: Fn(u32) -> bool, G: for<'a> FnMut(&'a str), T: ?Sized + std::iter::Iterator<Item = String>>() {}
+";
+    let file = syn::parse_file(src)?;
+    let items = ItemBounds::collect_items_in_file(&file)?;
+    let all = [TargetType::All];
+    let bound_texts = |filter: &str| -> Vec<String> {
+        items
+            .filtered_candidates(&all, &[filter.to_owned()])
+            .map(|(_, c)| c.bound_text)
+            .collect()
+    };
+    assert_eq!(bound_texts("Fn"), ["Fn (u32) -> bool"]);
+    assert_eq!(bound_texts("FnMut"), ["for < 'a > FnMut (& 'a str)"]);
+    assert_eq!(
+        bound_texts("Iterator"),
+        ["std :: iter :: Iterator < Item = String >"]
+    );
+    assert_eq!(
+        bound_texts("std::iter::Iterator"),
+        ["std :: iter :: Iterator < Item = String >"]
+    );
+    assert_eq!(bound_texts("?Sized"), ["? Sized"]);
+    assert!(bound_texts("iter::Iterator").is_empty());
+    Ok(())
+}
+
+#[test]
+fn crate_bounds_keep_file_association() -> TestResult {
+    let (a, b) = (parse("a.rs")?, parse("b.rs")?);
+    let mut krate = CrateBounds::new();
+    krate.push("src/a.rs", ItemBounds::collect_items_in_file(&a)?);
+    krate.push("src/b.rs", ItemBounds::collect_items_in_file(&b)?);
+
+    let per_file: usize = krate
+        .files()
+        .map(|(_, items)| items.all_candidates().count())
+        .sum();
+    let all: Vec<_> = krate.all_candidates().collect();
+    assert_eq!(all.len(), per_file);
+
+    let in_b: Vec<_> = all
+        .iter()
+        .filter(|(loc, _)| loc.file == Path::new("src/b.rs"))
+        .collect();
+    assert!(!in_b.is_empty());
+    assert!(in_b.iter().all(|(loc, _)| loc.module_path == "b"));
+    assert!(
+        in_b.iter()
+            .any(|(loc, c)| loc.name.ends_with("::id") && c.bound_text == "Ord")
+    );
+
+    let impl_methods = krate
+        .filtered_candidates(&[TargetType::ImplMethod], &[])
+        .count();
+    assert_eq!(impl_methods, 2);
+    Ok(())
+}
@@ -0,0 +1,62 @@
+// tests/cargo_winnow_tests.rs
+//! `cargo-winnow` called the way cargo calls it: `cargo-winnow winnow <args>`, from
+//! anywhere in the workspace.
+#![cfg(feature = "cli")]
+
+mod common;
+
+use assert_cmd::Command;
+use common::TestResult;
+use predicates::str::contains;
+use std::path::Path;
+
+fn write_crate(dir: &Path) -> std::io::Result<()> {
+    common::write_crate(
+        dir,
+        "x",
+        "",
+        &[("src/lib.rs", "pub fn f<T: Clone>(t: T) -> T {\n    t\n}\n")],
+    )?;
+    std::fs::create_dir_all(dir.join("src/nested"))?;
+    Ok(())
+}
+
+#[test]
+fn check_from_a_subdirectory_covers_the_workspace() -> TestResult {
+    let tmp = tempfile::tempdir()?;
+    write_crate(tmp.path())?;
+    let direct = Command::cargo_bin("trait-winnower")?
+        .env("CLICOLOR", "0")
+        .arg("check")
+        .arg(tmp.path())
+        .assert()
+        .success();
+    let direct = direct.get_output().stdout.clone();
+    assert!(!direct.is_empty());
+
+    let via_cargo = Command::cargo_bin("cargo-winnow")?
+        .env("CLICOLOR", "0")
+        .current_dir(tmp.path().join("src/nested"))
+        .args(["winnow", "check"])
+        .assert()
+        .success();
+    assert_eq!(via_cargo.get_output().stdout, direct);
+    Ok(())
+}
+
+#[test]
+fn help_names_the_cargo_subcommand() -> TestResult {
+    Command::cargo_bin("cargo-winnow")?
+        .env("CLICOLOR", "0")
+        .args(["winnow", "--help"])
+        .assert()
+        .success()
+        .stdout(contains("Usage: cargo winnow [OPTIONS] <COMMAND>"));
+    Command::cargo_bin("cargo-winnow")?
+        .env("CLICOLOR", "0")
+        .arg("winnow")
+        .assert()
+        .failure()
+        .stderr(contains("Usage: cargo winnow"));
+    Ok(())
+}
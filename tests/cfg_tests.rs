@@ -0,0 +1,224 @@
+// tests/cfg_tests.rs
+//! Items behind `#[cfg]`s: which ones validation builds, and what `prune` does with the rest.
+#![cfg(feature = "cli")]
+
+mod common;
+
+use common::TestResult;
+use trait_winnower::config::{CargoCheckConfig, Config, MatrixEntry};
+use trait_winnower::report::RemovalStatus;
+use trait_winnower::winnower::Winnower;
+
+fn cfg(preds: &[&str]) -> Vec<syn::Meta> {
+    preds
+        .iter()
+        .map(|p| syn::parse_str(p).expect("a cfg predicate"))
+        .collect()
+}
+
+fn with_args(args: &[&str]) -> CargoCheckConfig {
+    CargoCheckConfig {
+        args: args.iter().map(|a| a.to_string()).collect(),
+        ..CargoCheckConfig::default()
+    }
+}
+
+#[test]
+fn features_follow_the_check_arguments() {
+    let all = CargoCheckConfig::default();
+    assert_eq!(all.builds_cfg(&cfg(&["feature = \"rare\""])), Some(true));
+
+    let none = with_args(&["--no-default-features"]);
+    assert_eq!(none.builds_cfg(&cfg(&["feature = \"rare\""])), Some(false));
+    assert_eq!(
+        none.builds_cfg(&cfg(&["not(feature = \"rare\")"])),
+        Some(true)
+    );
+
+    let some = with_args(&["--no-default-features", "--features", "a,rare"]);
+    assert_eq!(some.builds_cfg(&cfg(&["feature = \"rare\""])), Some(true));
+    assert_eq!(some.builds_cfg(&cfg(&["feature = \"other\""])), Some(false));
+
+    // Default features are not known from the arguments alone.
+    let default = with_args(&[]);
+    assert_eq!(default.builds_cfg(&cfg(&["feature = \"rare\""])), None);
+    assert_eq!(default.builds_cfg(&cfg(&["test"])), Some(false));
+}
+
+#[test]
+fn any_configuration_in_the_matrix_counts() {
+    let mut check = with_args(&["--no-default-features"]);
+    let gated = cfg(&["special", "any(test, debug_assertions)"]);
+    assert_eq!(check.builds_cfg(&gated), None);
+
+    check.matrix.push(MatrixEntry {
+        target: None,
+        rustflags: Some("--cfg special".into()),
+        features: None,
+    });
+    assert_eq!(check.builds_cfg(&gated), Some(true));
+    assert_eq!(check.builds_cfg(&cfg(&["all(special, test)"])), Some(false));
+
+    check.matrix.push(MatrixEntry {
+        target: Some("x86_64-pc-windows-msvc".into()),
+        rustflags: None,
+        features: None,
+    });
+    assert_eq!(
+        check.builds_cfg(&cfg(&["target_os = \"windows\""])),
+        Some(true)
+    );
+
+    let rare = cfg(&["feature = \"rare\""]);
+    assert_eq!(check.builds_cfg(&rare), Some(false));
+    check.matrix.push(MatrixEntry {
+        features: Some("rare".into()),
+        ..MatrixEntry::default()
+    });
+    assert_eq!(check.builds_cfg(&rare), Some(true));
+    assert_eq!(check.matrix[2].to_string(), "--features rare");
+    assert_eq!(
+        check.builds_cfg(&cfg(&["target_arch = \"riscv64\""])),
+        Some(std::env::consts::ARCH == "riscv64")
+    );
+}
+
+const SRC: &str = "#[cfg(feature = \"rare\")]\nmod gated {\n    \
+                   pub fn f<T: Clone>(t: T) -> T {\n        t\n    }\n}\n\n\
+                   pub fn g<T: Copy>(t: T) -> T {\n    t\n}\n";
+
+const MANIFEST: &str = "\n[features]\nrare = []\n";
+
+fn no_default_features() -> Config {
+    let mut config = Config::default();
+    config.cargo_check.args = ["--no-default-features", "--all-targets", "--quiet"]
+        .map(String::from)
+        .to_vec();
+    config
+}
+
+#[test]
+fn prune_skips_items_validation_never_builds() -> TestResult {
+    let tmp = tempfile::tempdir()?;
+    common::write_crate(tmp.path(), "x", MANIFEST, &[("src/lib.rs", SRC)])?;
+    let pruned = Winnower::builder()
+        .target(tmp.path())
+        .config(no_default_features())
+        .brute_force(true)
+        .build()?
+        .prune()?;
+    let removals: Vec<_> = pruned
+        .report
+        .files
+        .iter()
+        .flat_map(|f| &f.removals)
+        .filter(|r| r.status != RemovalStatus::Skipped || r.finding.not_validated)
+        .map(|r| (r.finding.item.as_str(), r.status, r.finding.not_validated))
+        .collect();
+    assert_eq!(
+        removals,
+        [
+            ("gated fn f", RemovalStatus::Skipped, true),
+            ("fn g", RemovalStatus::Removed, false),
+        ]
+    );
+    let src = std::fs::read_to_string(tmp.path().join("src/lib.rs"))?;
+    assert!(src.contains("pub fn f<T: Clone>(t: T)"), "{src}");
+    assert!(src.contains("pub fn g<T>(t: T)"), "{src}");
+
+    let skipped = pruned
+        .report
+        .files
+        .iter()
+        .flat_map(|f| &f.removals)
+        .find(|r| r.finding.not_validated)
+        .expect("the gated item is reported");
+    assert_eq!(
+        skipped.finding.validation_hint().as_deref(),
+        Some("add `features = \"rare\"` to a [[cargo_check.matrix]] entry to validate it")
+    );
+    Ok(())
+}
+
+#[test]
+fn a_matrix_entry_with_the_feature_validates_the_gated_module() -> TestResult {
+    let tmp = tempfile::tempdir()?;
+    common::write_crate(tmp.path(), "x", MANIFEST, &[("src/lib.rs", SRC)])?;
+    let mut config = no_default_features();
+    config.cargo_check.matrix.push(MatrixEntry {
+        features: Some("rare".into()),
+        ..MatrixEntry::default()
+    });
+    let pruned = Winnower::builder()
+        .target(tmp.path())
+        .config(config)
+        .brute_force(true)
+        .build()?
+        .prune()?;
+    let removed: Vec<_> = pruned
+        .report
+        .files
+        .iter()
+        .flat_map(|f| &f.removals)
+        .filter(|r| r.status == RemovalStatus::Removed)
+        .map(|r| (r.finding.item.as_str(), r.finding.not_validated))
+        .collect();
+    assert_eq!(removed, [("gated fn f", false), ("fn g", false)]);
+    let src = std::fs::read_to_string(tmp.path().join("src/lib.rs"))?;
+    assert!(src.contains("pub fn f<T>(t: T)"), "{src}");
+    Ok(())
+}
+
+#[test]
+fn the_cli_hints_at_the_feature_to_validate() -> TestResult {
+    use assert_cmd::Command;
+    use predicates::str::contains;
+
+    let tmp = tempfile::tempdir()?;
+    common::write_crate(tmp.path(), "x", MANIFEST, &[("src/lib.rs", SRC)])?;
+    std::fs::write(
+        tmp.path().join(".trait-winnower.toml"),
+        toml::to_string(&no_default_features())?,
+    )?;
+    Command::cargo_bin("trait-winnower")?
+        .args(["prune", "-b"])
+        .arg(tmp.path())
+        .assert()
+        .success()
+        .stderr(contains(
+            "src/lib.rs:3 gated fn f: skipped (not covered by validation): only built with \
+             `cfg(feature = \"rare\")`; add `features = \"rare\"` to a [[cargo_check.matrix]] \
+             entry to validate it",
+        ));
+    Ok(())
+}
+
+#[test]
+fn check_marks_findings_validation_never_builds() -> TestResult {
+    let tmp = tempfile::tempdir()?;
+    common::write_crate(tmp.path(), "x", MANIFEST, &[("src/lib.rs", SRC)])?;
+    let checked = Winnower::builder()
+        .target(tmp.path())
+        .config(no_default_features())
+        .build()?
+        .check()?;
+    let findings: Vec<_> = checked
+        .report
+        .files
+        .iter()
+        .flat_map(|f| &f.findings)
+        .collect();
+    let gated = findings
+        .iter()
+        .find(|f| f.item == "gated fn f")
+        .expect("the gated item is still analyzed");
+    assert_eq!(gated.cfg, ["feature = \"rare\""]);
+    assert!(gated.not_validated);
+    assert!(
+        findings
+            .iter()
+            .filter(|f| f.item == "fn g")
+            .all(|f| !f.not_validated)
+    );
+    Ok(())
+}
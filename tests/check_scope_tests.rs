@@ -0,0 +1,162 @@
+// tests/check_scope_tests.rs
+//! Trials check the package owning the edited file and its dependents, not the workspace.
+#![cfg(feature = "discover")]
+
+mod common;
+
+use common::TestResult;
+use std::path::Path;
+use trait_winnower::config::{CargoCheckConfig, CheckScope};
+use trait_winnower::crate_map::{CrateMap, Package};
+use trait_winnower::dynamic_analysis::common::CargoCheck;
+use trait_winnower::winnower::Winnower;
+
+fn package(root: &Path, name: &str, dependencies: &[&str]) -> Package {
+    Package {
+        name: name.into(),
+        manifest_dir: root.join(name),
+        targets: vec![root.join(name).join("src/lib.rs")],
+        dependencies: dependencies.iter().map(|d| d.to_string()).collect(),
+    }
+}
+
+/// `core` <- `util` <- `app`, and `other` beside them; `util` also uses an outside crate.
+fn fabricated(root: &Path) -> std::io::Result<CrateMap> {
+    for name in ["core", "util", "app", "other"] {
+        std::fs::create_dir_all(root.join(name).join("src"))?;
+        std::fs::write(root.join(name).join("src/lib.rs"), "")?;
+    }
+    Ok(CrateMap::build(&[
+        package(root, "core", &[]),
+        package(root, "util", &["core", "serde"]),
+        package(root, "app", &["util"]),
+        package(root, "other", &[]),
+    ]))
+}
+
+fn args(config: CargoCheckConfig, packages: Vec<String>) -> Vec<String> {
+    CargoCheck::new(config).with_packages(packages).check_args()
+}
+
+#[test]
+fn a_trial_checks_the_owner_and_its_reverse_dependencies() -> TestResult {
+    let tmp = tempfile::tempdir()?;
+    let map = fabricated(tmp.path())?;
+    let scope = map.check_scope(&tmp.path().join("core/src/lib.rs"));
+    assert_eq!(scope, ["app", "core", "util"]);
+    assert_eq!(
+        args(CargoCheckConfig::default(), scope),
+        [
+            "check",
+            "--all-features",
+            "--all-targets",
+            "--quiet",
+            "-p",
+            "app",
+            "-p",
+            "core",
+            "-p",
+            "util",
+        ]
+    );
+    assert_eq!(map.check_scope(&tmp.path().join("app/src/lib.rs")), ["app"]);
+    assert_eq!(
+        map.check_scope(&tmp.path().join("other/src/lib.rs")),
+        ["other"]
+    );
+    Ok(())
+}
+
+#[test]
+fn the_workspace_is_checked_when_scoping_does_not_apply() -> TestResult {
+    let tmp = tempfile::tempdir()?;
+    let map = fabricated(tmp.path())?;
+    let defaults = CargoCheckConfig::default();
+    let mut whole = vec!["check".to_owned()];
+    whole.extend(defaults.args.iter().cloned());
+
+    // Asked for, with `check_scope = "workspace"`.
+    let workspace = CargoCheckConfig {
+        check_scope: CheckScope::Workspace,
+        ..CargoCheckConfig::default()
+    };
+    let scope = map.check_scope(&tmp.path().join("core/src/lib.rs"));
+    assert_eq!(args(workspace, scope.clone()), whole);
+
+    // No package is known to compile the file.
+    let unknown = map.check_scope(&tmp.path().join("stray.rs"));
+    assert!(unknown.is_empty());
+    assert_eq!(args(defaults.clone(), unknown), whole);
+
+    // The arguments already pick packages.
+    let picked = CargoCheckConfig {
+        args: vec!["-p".into(), "core".into()],
+        ..CargoCheckConfig::default()
+    };
+    assert_eq!(args(picked, scope), ["check", "-p", "core"]);
+    Ok(())
+}
+
+#[test]
+fn a_check_compiles_its_packages_and_their_dependencies() -> TestResult {
+    let tmp = tempfile::tempdir()?;
+    let map = fabricated(tmp.path())?;
+    let file = |name: &str| tmp.path().join(name).join("src/lib.rs").canonicalize();
+    assert_eq!(
+        map.sources(&["app".into()]),
+        [file("app")?, file("core")?, file("util")?]
+    );
+    assert_eq!(map.sources(&["core".into()]), [file("core")?]);
+    assert_eq!(map.sources(&[]).len(), 4);
+    Ok(())
+}
+
+#[test]
+fn prune_records_the_scope_of_each_trial() -> TestResult {
+    let tmp = tempfile::tempdir()?;
+    let root = tmp.path();
+    std::fs::write(
+        root.join("Cargo.toml"),
+        "[workspace]\nmembers = [\"a\", \"b\"]\nresolver = \"2\"\n",
+    )?;
+    for (name, deps, src) in [
+        ("a", "", "pub fn f<T: Clone>(t: T) -> T {\n    t\n}\n"),
+        (
+            "b",
+            "a = { path = \"../a\" }\n",
+            "pub fn g<U: Copy>(u: U) -> U {\n    a::f(u)\n}\n",
+        ),
+    ] {
+        std::fs::create_dir_all(root.join(name).join("src"))?;
+        std::fs::write(
+            root.join(name).join("Cargo.toml"),
+            format!(
+                "[package]\nname = \"{name}\"\nversion = \"0.1.0\"\nedition = \"2021\"\n\n\
+                 [dependencies]\n{deps}"
+            ),
+        )?;
+        std::fs::write(root.join(name).join("src/lib.rs"), src)?;
+    }
+    let pruned = Winnower::builder()
+        .target(root)
+        .brute_force(true)
+        .build()?
+        .prune()?;
+    let scopes: Vec<(&str, &[String])> = pruned
+        .report
+        .files
+        .iter()
+        .flat_map(|f| &f.removals)
+        .filter(|r| !r.scope.is_empty())
+        .map(|r| (r.finding.item.as_str(), &r.scope[..]))
+        .collect();
+    assert!(
+        scopes.contains(&("fn f", &["a".to_owned(), "b".to_owned()][..])),
+        "{scopes:?}"
+    );
+    assert!(
+        scopes.contains(&("fn g", &["b".to_owned()][..])),
+        "{scopes:?}"
+    );
+    Ok(())
+}
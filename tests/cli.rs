@@ -1,16 +1,20 @@
 // tests/cli.rs
 //! Trait Winnower CLI tests.
+#![cfg(feature = "cli")]
+
+mod common;
 
 use assert_cmd::Command;
 use assert_fs::assert::PathAssert;
+use assert_fs::fixture::FileWriteBin;
 use assert_fs::fixture::FileWriteStr;
 use assert_fs::fixture::PathChild;
 use assert_fs::fixture::PathCreateDir;
+use common::TestResult;
+use predicates::prelude::PredicateBooleanExt;
 use predicates::str::contains;
 use trait_winnower::config::Config;
 
-type TestResult = Result<(), Box<dyn std::error::Error>>;
-
 #[test]
 fn dies_no_args() -> TestResult {
     let mut cmd = Command::cargo_bin("trait-winnower")?;
@@ -83,3 +87,341 @@ fn prune_dry_run_on_crate_root_succeeds() -> Result<(), Box<dyn std::error::Erro
     tmp.close()?;
     Ok(())
 }
+
+#[test]
+fn check_verbose_reports_skipped_counts() -> Result<(), Box<dyn std::error::Error>> {
+    let tmp = assert_fs::TempDir::new()?;
+    tmp.child("Cargo.toml")
+        .write_str("[package]\nname=\"x\"\nversion=\"0.1.0\"\n")?;
+    tmp.child("src").create_dir_all()?;
+    tmp.child("src/lib.rs").write_str("// lib\n")?;
+    tmp.child("tests").create_dir_all()?;
+    tmp.child("tests/it.rs").write_str("// it\n")?;
+
+    Command::cargo_bin("trait-winnower")?
+        .current_dir(&tmp)
+        .args(["check", ".", "-v", "1"])
+        .assert()
+        .success()
+        .stderr(contains("in `tests/`, which `analyze_tests` leaves out"))
+        .stderr(contains("tests/it.rs").not());
+
+    Command::cargo_bin("trait-winnower")?
+        .current_dir(&tmp)
+        .args(["check", ".", "-v", "2"])
+        .assert()
+        .success()
+        .stderr(contains("it.rs: in `tests/`"));
+
+    tmp.close()?;
+    Ok(())
+}
+
+#[test]
+fn check_only_trait_matches_bounds_with_arguments() -> TestResult {
+    let tmp = assert_fs::TempDir::new()?;
+    let lib = tmp.child("lib.rs");
+    lib.write_str(
+        "fn f<I: Iterator<Item = u32> + Clone>(i: I) {}\nfn g<F: Fn(u8) -> bool>(f: F) {}\n",
+    )?;
+    Command::cargo_bin("trait-winnower")?
+        .args(["check", "--format", "json", "--only-trait", "Iterator"])
+        .arg(lib.path())
+        .assert()
+        .success()
+        .stdout(contains("Iterator < Item = u32 >"))
+        .stdout(contains("\"Clone\"").not())
+        .stdout(contains("Fn (u8)").not());
+    Ok(())
+}
+
+#[test]
+fn check_single_file_follows_mod_declarations() -> Result<(), Box<dyn std::error::Error>> {
+    Command::cargo_bin("trait-winnower")?
+        .args([
+            "check",
+            "tests/test_files/module_tree/lib.rs",
+            "--format",
+            "short",
+            "-v",
+            "2",
+        ])
+        .assert()
+        .success()
+        .stdout(contains("lib.rs:11:8 fn root_fn\n"))
+        .stdout(contains("a/inner.rs:1:8 fn inner_fn\n"))
+        .stdout(contains(" fn b_fn\n"))
+        .stdout(contains(" fn c_fn\n"))
+        .stdout(contains(" fn deep_fn\n"))
+        .stderr(contains("module `missing`"));
+    Ok(())
+}
+
+#[test]
+fn check_skips_non_utf8_file_in_crate() -> Result<(), Box<dyn std::error::Error>> {
+    let tmp = assert_fs::TempDir::new()?;
+    tmp.child("Cargo.toml")
+        .write_str("[package]\nname=\"x\"\nversion=\"0.1.0\"\n")?;
+    tmp.child("src").create_dir_all()?;
+    tmp.child("src/lib.rs").write_str("mod legacy;\n")?;
+    tmp.child("src/legacy.rs")
+        .write_binary(b"// caf\xe9\npub fn f() {}\n")?;
+
+    Command::cargo_bin("trait-winnower")?
+        .current_dir(&tmp)
+        .args(["check", "."])
+        .assert()
+        .success()
+        .stderr(contains("legacy.rs is not valid UTF-8"));
+
+    Command::cargo_bin("trait-winnower")?
+        .current_dir(&tmp)
+        .args(["check", "src/legacy.rs"])
+        .assert()
+        .failure()
+        .stderr(contains("not valid UTF-8"));
+
+    tmp.close()?;
+    Ok(())
+}
+
+#[test]
+fn prune_keeps_leading_bom() -> Result<(), Box<dyn std::error::Error>> {
+    let tmp = assert_fs::TempDir::new()?;
+    tmp.child("Cargo.toml")
+        .write_str("[package]\nname=\"x\"\nversion=\"0.1.0\"\nedition=\"2021\"\n\n[workspace]\n")?;
+    tmp.child("src").create_dir_all()?;
+    tmp.child("src/lib.rs")
+        .write_str("\u{feff}pub fn f<T: Clone>(t: T) -> T {\n    t\n}\n")?;
+
+    Command::cargo_bin("trait-winnower")?
+        .current_dir(&tmp)
+        .args(["prune", ".", "--brute-force"])
+        .assert()
+        .success();
+
+    let out = std::fs::read_to_string(tmp.child("src/lib.rs").path())?;
+    assert!(out.starts_with('\u{feff}'), "BOM dropped: {out:?}");
+    assert!(!out.contains("Clone"), "bound not pruned: {out:?}");
+
+    tmp.close()?;
+    Ok(())
+}
+
+#[test]
+fn check_short_listing_snapshots_per_verbosity() -> Result<(), Box<dyn std::error::Error>> {
+    for level in ["1", "2", "3"] {
+        let out = Command::cargo_bin("trait-winnower")?
+            .args([
+                "check",
+                "tests/test_files/trait_sandbox/src/b.rs",
+                "--format",
+                "short",
+                "-v",
+                level,
+            ])
+            .output()?;
+        assert!(out.status.success());
+        let expected = std::fs::read_to_string(format!("tests/expected/show_item/b_v{level}.txt"))?;
+        assert_eq!(String::from_utf8(out.stdout)?, expected, "-v {level}");
+    }
+    Ok(())
+}
+
+#[test]
+fn prune_print_modified_lists_only_changed_files() -> Result<(), Box<dyn std::error::Error>> {
+    let tmp = assert_fs::TempDir::new()?;
+    tmp.child("Cargo.toml")
+        .write_str("[package]\nname=\"x\"\nversion=\"0.1.0\"\nedition=\"2021\"\n\n[workspace]\n")?;
+    tmp.child("src/lib.rs")
+        .write_str("pub mod a;\npub mod b;\n")?;
+    // `Clone` is unused here and gets removed.
+    tmp.child("src/a.rs")
+        .write_str("pub fn f<T: Clone>(t: T) -> T {\n    t\n}\n")?;
+    // `Clone` is needed here: the file is edited, then reverted.
+    tmp.child("src/b.rs")
+        .write_str("pub fn g<T: Clone>(t: &T) -> T {\n    t.clone()\n}\n")?;
+
+    let out = Command::cargo_bin("trait-winnower")?
+        .current_dir(tmp.path())
+        .args([
+            "prune",
+            ".",
+            "--brute-force",
+            "--print-modified",
+            "--format",
+            "json",
+            "-v",
+            "1",
+        ])
+        .output()?;
+    assert!(out.status.success());
+    assert_eq!(String::from_utf8(out.stdout)?, "src/a.rs\n");
+    // Other output moves to stderr.
+    assert!(String::from_utf8(out.stderr)?.contains("\"command\": \"prune\""));
+
+    // Nothing left to remove: empty list, still success.
+    Command::cargo_bin("trait-winnower")?
+        .current_dir(tmp.path())
+        .args(["prune", ".", "--brute-force", "--print-modified", "--quiet"])
+        .assert()
+        .success()
+        .stdout("");
+    Ok(())
+}
+
+#[test]
+fn apply_runs_a_saved_plan() -> Result<(), Box<dyn std::error::Error>> {
+    use trait_winnower::plan::Plan;
+    use trait_winnower::winnower::Winnower;
+
+    let tmp = assert_fs::TempDir::new()?;
+    tmp.child("Cargo.toml")
+        .write_str("[package]\nname=\"x\"\nversion=\"0.1.0\"\nedition=\"2021\"\n\n[workspace]\n")?;
+    tmp.child("src/lib.rs")
+        .write_str("pub fn f<T: Clone>(t: T) -> T {\n    t\n}\n")?;
+    let checked = Winnower::builder().target(tmp.path()).build()?.check()?;
+    Plan::from_report(&checked.report).save(&tmp.child("plan.toml"))?;
+
+    Command::cargo_bin("trait-winnower")?
+        .current_dir(tmp.path())
+        .args(["apply", "plan.toml", ".", "--strict"])
+        .assert()
+        .success()
+        .stdout(contains("applied: src/lib.rs:1:8 f: T: Clone\n"))
+        .stdout(contains(
+            "applied 1 of 1 entries, 1 file(s) modified; validation passed",
+        ));
+    tmp.child("src/lib.rs")
+        .assert("pub fn f<T>(t: T) -> T {\n    t\n}\n");
+
+    // `f` has no bounds left: strict mode fails with the plan exit code.
+    Command::cargo_bin("trait-winnower")?
+        .current_dir(tmp.path())
+        .args(["apply", "plan.toml", ".", "--strict"])
+        .assert()
+        .code(2)
+        .stderr(contains(
+            "no bounded item found: src/lib.rs:1:8 f: T: Clone",
+        ));
+    Ok(())
+}
+
+#[test]
+fn prune_keeps_self_sized_on_trait_methods_unless_allowed() -> TestResult {
+    let tmp = assert_fs::TempDir::new()?;
+    tmp.child("Cargo.toml")
+        .write_str("[package]\nname=\"x\"\nversion=\"0.1.0\"\nedition=\"2021\"\n\n[workspace]\n")?;
+    // Without `Self: Sized` the crate still builds, but `dyn Shape` is no longer allowed.
+    tmp.child("src/lib.rs").write_str(
+        "pub trait Shape {\n    fn area(&self) -> f64;\n    fn scaled(self, k: f64) -> Self\n    \
+         where\n        Self: Sized;\n}\n\npub struct Square(pub f64);\n\nimpl Shape for Square {\n    \
+         fn area(&self) -> f64 {\n        self.0 * self.0\n    }\n    fn scaled(self, k: f64) -> Self {\n        \
+         Square(self.0 * k)\n    }\n}\n",
+    )?;
+    let lib = tmp.child("src/lib.rs");
+
+    Command::cargo_bin("trait-winnower")?
+        .current_dir(tmp.path())
+        .args(["check", "."])
+        .assert()
+        .success()
+        .stdout(contains("exempts `Shape::scaled` from dyn-compatibility"))
+        .stdout(contains("--allow-dyn-compat-changes"));
+    Command::cargo_bin("trait-winnower")?
+        .current_dir(tmp.path())
+        .args(["check", ".", "--format", "json"])
+        .assert()
+        .success()
+        .stdout(contains("\"caution\": \"`Self: Sized` exempts"));
+
+    Command::cargo_bin("trait-winnower")?
+        .current_dir(tmp.path())
+        .args(["prune", ".", "--brute-force", "--quiet"])
+        .assert()
+        .success();
+    lib.assert(contains("Self: Sized"));
+
+    Command::cargo_bin("trait-winnower")?
+        .current_dir(tmp.path())
+        .args([
+            "prune",
+            ".",
+            "--brute-force",
+            "--allow-dyn-compat-changes",
+            "--quiet",
+        ])
+        .assert()
+        .success();
+    lib.assert(contains("Self: Sized").not());
+
+    tmp.close()?;
+    Ok(())
+}
+
+#[test]
+fn prune_warns_when_cargo_check_args_are_narrowed() -> TestResult {
+    let tmp = assert_fs::TempDir::new()?;
+    tmp.child("Cargo.toml")
+        .write_str("[package]\nname=\"x\"\nversion=\"0.1.0\"\nedition=\"2021\"\n\n[workspace]\n")?;
+    tmp.child("src/lib.rs")
+        .write_str("pub fn f<T: Clone>(t: T) -> T {\n    t\n}\n")?;
+    tmp.child(".trait-winnower.toml").write_str(
+        "include = [\"**/*.rs\"]\nexclude = []\n\n[cargo_check]\nargs = [\"--quiet\"]\n",
+    )?;
+
+    Command::cargo_bin("trait-winnower")?
+        .current_dir(tmp.path())
+        .args(["prune", ".", "--brute-force"])
+        .assert()
+        .success()
+        .stderr(contains("does not build:"))
+        .stderr(contains("no `--all-features`"))
+        .stderr(contains("no `--all-targets`"));
+
+    // Strict mode refuses before touching anything.
+    tmp.child("src/lib.rs")
+        .write_str("pub fn f<T: Clone>(t: T) -> T {\n    t\n}\n")?;
+    tmp.child(".trait-winnower.toml").write_str(
+        "include = [\"**/*.rs\"]\nexclude = []\nstrict_validation = true\n\n[cargo_check]\nargs = [\"--quiet\"]\n",
+    )?;
+    Command::cargo_bin("trait-winnower")?
+        .current_dir(tmp.path())
+        .args(["prune", ".", "--brute-force"])
+        .assert()
+        .code(2)
+        .stderr(contains("strict_validation is set"));
+    tmp.child("src/lib.rs").assert(contains("T: Clone"));
+
+    tmp.close()?;
+    Ok(())
+}
+
+#[test]
+fn prune_warns_when_cargo_semver_checks_is_missing() -> TestResult {
+    let installed = std::process::Command::new("cargo")
+        .args(["semver-checks", "--version"])
+        .output()
+        .is_ok_and(|o| o.status.success());
+    if installed {
+        return Ok(());
+    }
+    let tmp = assert_fs::TempDir::new()?;
+    tmp.child("Cargo.toml")
+        .write_str("[package]\nname=\"x\"\nversion=\"0.1.0\"\nedition=\"2021\"\n\n[workspace]\n")?;
+    tmp.child("src/lib.rs")
+        .write_str("pub fn f<T: Clone>(t: T) -> T {\n    t\n}\n")?;
+    tmp.child(".trait-winnower.toml")
+        .write_str("include = [\"**/*.rs\"]\nexclude = []\nsemver_check = true\n\n[cargo_check]\nargs = [\"--all-features\", \"--all-targets\", \"--quiet\"]\n")?;
+
+    Command::cargo_bin("trait-winnower")?
+        .current_dir(tmp.path())
+        .args(["prune", ".", "--brute-force"])
+        .assert()
+        .success()
+        .stderr(contains("warning: semver check skipped"));
+    tmp.child("src/lib.rs").assert(contains("Clone").not());
+
+    tmp.close()?;
+    Ok(())
+}
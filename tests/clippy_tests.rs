@@ -0,0 +1,157 @@
+// tests/clippy_tests.rs
+//! `validator = "clippy"` puts back removals clippy rejects, per candidate or once at the
+//! end, and `check --import-clippy` tags the bounds clippy's lints flag.
+#![cfg(all(unix, feature = "discover"))]
+
+mod common;
+
+use common::TestResult;
+use std::os::unix::fs::PermissionsExt;
+use std::path::Path;
+use trait_winnower::config::{ClippyGate, Config, ValidatorKind};
+use trait_winnower::findings::{Confidence, Rule};
+use trait_winnower::report::RemovalStatus;
+use trait_winnower::winnower::Winnower;
+
+const SRC: &str = "pub fn f<T: Clone + Default>(t: T) -> T {\n    t\n}\n";
+
+/// A crate whose `cargo clippy` fails once `T: Clone` is gone from `src/lib.rs`, and a
+/// configuration validating it with clippy at `gate` through a cargo wrapper logging each
+/// clippy invocation to `clippy.log`.
+fn write_crate(root: &Path, gate: ClippyGate) -> Result<Config, Box<dyn std::error::Error>> {
+    common::write_crate(root, "c", "", &[("src/lib.rs", SRC)])?;
+    let real = std::env::var("CARGO").unwrap_or_else(|_| "cargo".into());
+    let cargo = root.join("cargo-wrapper");
+    let log = root.join("clippy.log");
+    std::fs::write(
+        &cargo,
+        format!(
+            "#!/bin/sh\n\
+             if [ \"$1\" = clippy ] && [ \"$2\" != --version ]; then\n\
+             \techo \"$@\" >> '{}'\n\
+             \tgrep -q 'T: Clone' src/lib.rs || {{ echo 'error: lint' >&2; exit 101; }}\n\
+             fi\n\
+             exec \"{real}\" \"$@\"\n",
+            log.display()
+        ),
+    )?;
+    std::fs::set_permissions(&cargo, std::fs::Permissions::from_mode(0o755))?;
+    let mut cfg = Config {
+        validator: ValidatorKind::Clippy,
+        ..Config::default()
+    };
+    cfg.clippy.gate = gate;
+    cfg.cargo_check.cargo_path = Some(cargo);
+    Ok(cfg)
+}
+
+fn statuses(report: &trait_winnower::winnower::PruneReport) -> Vec<(String, RemovalStatus)> {
+    report
+        .report
+        .files
+        .iter()
+        .flat_map(|f| &f.removals)
+        .map(|r| (r.finding.bound.clone(), r.status))
+        .collect()
+}
+
+#[test]
+fn clippy_validates_each_candidate() -> TestResult {
+    let tmp = tempfile::tempdir()?;
+    let cfg = write_crate(tmp.path(), ClippyGate::PerCandidate)?;
+    let pruned = Winnower::builder()
+        .target(tmp.path())
+        .config(cfg)
+        .brute_force(true)
+        .build()?
+        .prune()?;
+
+    assert_eq!(
+        std::fs::read_to_string(tmp.path().join("src/lib.rs"))?,
+        "pub fn f<T: Clone>(t: T) -> T {\n    t\n}\n"
+    );
+    let statuses = statuses(&pruned);
+    assert!(
+        statuses.contains(&("Clone".to_owned(), RemovalStatus::Retained)),
+        "{statuses:?}"
+    );
+    assert!(
+        statuses.contains(&("Default".to_owned(), RemovalStatus::Removed)),
+        "{statuses:?}"
+    );
+    let log = std::fs::read_to_string(tmp.path().join("clippy.log"))?;
+    let first = log.lines().next().ok_or("clippy never ran")?;
+    assert!(first.contains("--no-deps"), "{first}");
+    assert!(first.ends_with("-- -D warnings"), "{first}");
+    Ok(())
+}
+
+#[test]
+fn final_gate_puts_back_every_removal_clippy_rejects() -> TestResult {
+    let tmp = tempfile::tempdir()?;
+    let cfg = write_crate(tmp.path(), ClippyGate::Final)?;
+    let pruned = Winnower::builder()
+        .target(tmp.path())
+        .config(cfg)
+        .brute_force(true)
+        .build()?
+        .prune()?;
+
+    assert_eq!(std::fs::read_to_string(tmp.path().join("src/lib.rs"))?, SRC);
+    assert!(
+        pruned
+            .clippy_failure
+            .is_some_and(|e| e.contains("error: lint"))
+    );
+    assert!(pruned.modified.is_empty(), "{:?}", pruned.modified);
+    // Candidates went through `cargo check`; clippy ran once, on the final state.
+    let log = std::fs::read_to_string(tmp.path().join("clippy.log"))?;
+    assert_eq!(log.lines().count(), 1, "{log}");
+    Ok(())
+}
+
+#[test]
+fn import_tags_bounds_clippy_flags() -> TestResult {
+    let tmp = tempfile::tempdir()?;
+    let root = tmp.path();
+    std::fs::create_dir_all(root.join("src"))?;
+    std::fs::write(
+        root.join("Cargo.toml"),
+        "[package]\nname=\"l\"\nversion=\"0.1.0\"\nedition=\"2021\"\n\n[workspace]\n",
+    )?;
+    std::fs::write(
+        root.join("src/lib.rs"),
+        "pub trait Made: Default {\n    fn make() -> Self\n    where\n        Self: Default;\n}\n\n\
+         pub fn both<T>(t: T) -> T\nwhere\n    T: Clone,\n    T: Default,\n{\n    t.clone()\n}\n",
+    )?;
+
+    let checked = Winnower::builder()
+        .target(root)
+        .import_clippy(true)
+        .build()?
+        .check()?;
+    let tagged: Vec<(usize, &str, Confidence)> = checked.report.files[0]
+        .findings
+        .iter()
+        .filter_map(|f| {
+            let rule = f.rule.as_ref().filter(|r| r.rule == Rule::Clippy)?;
+            Some((f.line, f.bound.as_str(), rule.confidence))
+        })
+        .collect();
+    assert_eq!(
+        tagged,
+        [
+            (4, "Default", Confidence::High),
+            (10, "Default", Confidence::Low)
+        ]
+    );
+
+    let plain = Winnower::builder().target(root).build()?.check()?;
+    assert!(
+        plain.report.files[0]
+            .findings
+            .iter()
+            .all(|f| f.rule.as_ref().is_none_or(|r| r.rule != Rule::Clippy))
+    );
+    Ok(())
+}
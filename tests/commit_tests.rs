@@ -0,0 +1,160 @@
+// tests/commit_tests.rs
+//! `prune --commit-per-removal` leaves one git commit per removal kept, and refuses a dirty
+//! work tree.
+#![cfg(feature = "cli")]
+
+mod common;
+
+use assert_cmd::Command;
+use common::TestResult;
+use predicates::str::contains;
+use std::path::Path;
+
+fn copy_dir(from: &Path, to: &Path) -> std::io::Result<()> {
+    std::fs::create_dir_all(to)?;
+    for entry in std::fs::read_dir(from)? {
+        let entry = entry?;
+        let path = entry.path();
+        if path.is_dir() {
+            copy_dir(&path, &to.join(entry.file_name()))?;
+        } else {
+            std::fs::copy(&path, to.join(entry.file_name()))?;
+        }
+    }
+    Ok(())
+}
+
+fn git(root: &Path, args: &[&str]) -> Result<String, Box<dyn std::error::Error>> {
+    let out = std::process::Command::new("git")
+        .args(["-c", "user.name=t", "-c", "user.email=t@example.com"])
+        .args(args)
+        .current_dir(root)
+        .output()?;
+    assert!(
+        out.status.success(),
+        "git {args:?}: {}",
+        String::from_utf8_lossy(&out.stderr)
+    );
+    Ok(String::from_utf8(out.stdout)?.trim().to_owned())
+}
+
+/// The sandbox fixture, committed in a fresh repository.
+fn sandbox_repo(root: &Path) -> TestResult {
+    copy_dir(Path::new("tests/test_files/trait_sandbox"), root)?;
+    std::fs::write(root.join(".gitignore"), "target/\n")?;
+    git(root, &["init", "-q"])?;
+    git(root, &["config", "user.name", "t"])?;
+    git(root, &["config", "user.email", "t@example.com"])?;
+    git(root, &["add", "-A"])?;
+    git(root, &["commit", "-qm", "init"])?;
+    Ok(())
+}
+
+/// The number of removals the JSON report of `prune` lists, and its stderr.
+fn prune(root: &Path, args: &[&str]) -> Result<(usize, String), Box<dyn std::error::Error>> {
+    let out = Command::cargo_bin("trait-winnower")?
+        .args(["prune", "-b", "-n", "all", "--format", "json"])
+        .args(args)
+        .arg(root)
+        .output()?;
+    assert!(
+        out.status.success(),
+        "{}",
+        String::from_utf8_lossy(&out.stderr)
+    );
+    let removed = String::from_utf8(out.stdout)?
+        .matches(r#""status": "removed""#)
+        .count();
+    Ok((removed, String::from_utf8(out.stderr)?))
+}
+
+#[test]
+fn each_removal_is_a_commit() -> TestResult {
+    let tmp = tempfile::tempdir()?;
+    let root = tmp.path();
+    sandbox_repo(root)?;
+
+    let (removed, stderr) = prune(root, &["--commit-per-removal"])?;
+    assert!(removed > 1, "{stderr}");
+    assert_eq!(stderr.matches("committed ").count(), removed, "{stderr}");
+    assert_eq!(
+        git(root, &["rev-list", "--count", "HEAD"])?,
+        (removed + 1).to_string()
+    );
+    assert_eq!(
+        git(root, &["status", "--porcelain"])?,
+        "",
+        "left uncommitted changes"
+    );
+
+    let subjects = git(root, &["log", "--format=%s", "-n", &removed.to_string()])?;
+    assert!(
+        subjects.lines().all(|s| s.starts_with("winnow: remove `")),
+        "{subjects}"
+    );
+    assert!(subjects.contains("winnow: remove `T: Clone` from fn unused_bound_clone in src/a.rs"));
+    // One removal per commit, so one file each.
+    for hash in git(root, &["rev-list", "-n", &removed.to_string(), "HEAD"])?.lines() {
+        let files = git(root, &["show", "--name-only", "--format=", hash])?;
+        assert_eq!(files.lines().count(), 1, "{hash}: {files}");
+    }
+    Ok(())
+}
+
+#[test]
+fn file_granularity_commits_each_file_once() -> TestResult {
+    let tmp = tempfile::tempdir()?;
+    let root = tmp.path();
+    sandbox_repo(root)?;
+
+    prune(
+        root,
+        &[
+            "--commit-granularity",
+            "file",
+            "--commit-message",
+            "prune {file}: {count}",
+        ],
+    )?;
+    let subjects = git(root, &["log", "--format=%s"])?;
+    let pruned: Vec<&str> = subjects
+        .lines()
+        .filter(|s| s.starts_with("prune "))
+        .collect();
+    let files = git(root, &["diff", "--name-only", "HEAD~", "HEAD"])?;
+    assert_eq!(files.lines().count(), 1);
+    let mut touched: Vec<&str> = pruned
+        .iter()
+        .map(|s| {
+            s.trim_start_matches("prune ")
+                .split(':')
+                .next()
+                .unwrap_or_default()
+        })
+        .collect();
+    touched.sort();
+    touched.dedup();
+    assert_eq!(touched.len(), pruned.len(), "{subjects}");
+    assert_eq!(git(root, &["status", "--porcelain"])?, "");
+    Ok(())
+}
+
+#[test]
+fn a_dirty_work_tree_is_refused() -> TestResult {
+    let tmp = tempfile::tempdir()?;
+    let root = tmp.path();
+    sandbox_repo(root)?;
+    std::fs::write(
+        root.join("src/a.rs"),
+        "pub fn f<T: Clone>(t: T) -> T { t }\n",
+    )?;
+
+    Command::cargo_bin("trait-winnower")?
+        .args(["prune", "-b", "--commit-per-removal"])
+        .arg(root)
+        .assert()
+        .failure()
+        .stderr(contains("clean work tree"));
+    assert_eq!(git(root, &["rev-list", "--count", "HEAD"])?, "1");
+    Ok(())
+}
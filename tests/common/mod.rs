@@ -0,0 +1,36 @@
+// tests/common/mod.rs
+//! Fixtures shared by the integration tests.
+// Each test binary uses only some of them.
+#![allow(dead_code)]
+
+use std::path::Path;
+
+/// What a test returns, so `?` takes any error.
+pub type TestResult = Result<(), Box<dyn std::error::Error>>;
+
+/// Write a crate named `name` under `root`, a workspace of its own: its `Cargo.toml`, with
+/// `manifest_extra` appended, and `files`, `(path under root, text)`, directories made as
+/// needed.
+pub fn write_crate(
+    root: &Path,
+    name: &str,
+    manifest_extra: &str,
+    files: &[(&str, &str)],
+) -> std::io::Result<()> {
+    std::fs::create_dir_all(root.join("src"))?;
+    std::fs::write(
+        root.join("Cargo.toml"),
+        format!(
+            "[package]\nname=\"{name}\"\nversion=\"0.1.0\"\nedition=\"2021\"\n\n[workspace]\n\
+             {manifest_extra}"
+        ),
+    )?;
+    for (path, text) in files {
+        let path = root.join(path);
+        if let Some(dir) = path.parent() {
+            std::fs::create_dir_all(dir)?;
+        }
+        std::fs::write(path, text)?;
+    }
+    Ok(())
+}
@@ -0,0 +1,123 @@
+// tests/concrete_bounds_tests.rs
+//! Where predicates bounding concrete or compound types, like `String: From<T>`: reported
+//! with their type as written, never judged by the body heuristics, and left to `prune`'s
+//! checks.
+#![cfg(feature = "discover")]
+
+mod common;
+
+use common::TestResult;
+use trait_winnower::report::RemovalStatus;
+use trait_winnower::winnower::Winnower;
+
+const SRC: &str = "\
+pub fn label<T>(t: T) -> String
+where
+    String: From<T>,
+{
+    t.into()
+}
+
+pub fn zero<T>(_t: T) -> usize
+where
+    String: Clone,
+{
+    0
+}
+
+pub fn count<T>(v: &[T]) -> usize
+where
+    for<'a> &'a [T]: IntoIterator,
+{
+    v.len()
+}
+";
+
+#[test]
+fn concrete_bounded_types_are_rendered_as_written_without_a_rule() -> TestResult {
+    let tmp = tempfile::tempdir()?;
+    common::write_crate(tmp.path(), "concrete", "", &[("src/lib.rs", SRC)])?;
+    let checked = Winnower::builder().target(tmp.path()).build()?.check()?;
+    let found: Vec<(&str, &str, &str, bool)> = checked
+        .report
+        .files
+        .iter()
+        .flat_map(|f| &f.findings)
+        .map(|f| {
+            (
+                f.item.as_str(),
+                f.bounded.as_str(),
+                f.bound.as_str(),
+                f.rule.is_some(),
+            )
+        })
+        .collect();
+    assert_eq!(
+        found,
+        [
+            ("fn label", "String", "From < T >", false),
+            ("fn zero", "String", "Clone", false),
+            ("fn count", "for < 'a > & 'a [T]", "IntoIterator", false),
+        ]
+    );
+
+    // `--only-trait` looks at the bound, whatever it bounds.
+    let only = Winnower::builder()
+        .target(tmp.path())
+        .only_traits(["From"])
+        .build()?
+        .check()?;
+    let bounds: Vec<&str> = only
+        .report
+        .files
+        .iter()
+        .flat_map(|f| &f.findings)
+        .map(|f| f.bound.as_str())
+        .collect();
+    assert_eq!(bounds, ["From < T >"]);
+    Ok(())
+}
+
+#[test]
+fn prune_removes_trivial_concrete_predicates_and_keeps_used_ones() -> TestResult {
+    let tmp = tempfile::tempdir()?;
+    common::write_crate(tmp.path(), "concrete", "", &[("src/lib.rs", SRC)])?;
+    let pruned = Winnower::builder()
+        .target(tmp.path())
+        .brute_force(true)
+        .build()?
+        .prune()?;
+    let mut outcomes: Vec<(String, RemovalStatus)> = pruned
+        .report
+        .files
+        .iter()
+        .flat_map(|f| &f.removals)
+        .filter(|r| r.status != RemovalStatus::Skipped)
+        .map(|r| {
+            (
+                format!("{}: {}", r.finding.bounded, r.finding.bound),
+                r.status,
+            )
+        })
+        .collect();
+    outcomes.sort_by(|a, b| a.0.cmp(&b.0));
+    let want = |bound: &str, status| (bound.to_owned(), status);
+    assert_eq!(
+        outcomes,
+        [
+            want("String: Clone", RemovalStatus::Removed),
+            want("String: From < T >", RemovalStatus::Retained),
+            want("for < 'a > & 'a [T]: IntoIterator", RemovalStatus::Removed),
+        ]
+    );
+
+    let src = std::fs::read_to_string(tmp.path().join("src/lib.rs"))?;
+    for line in [
+        "    String: From<T>,",
+        "pub fn zero<T>(_t: T) -> usize {",
+        "pub fn count<T>(v: &[T]) -> usize {",
+    ] {
+        assert!(src.contains(line), "{line}\n{src}");
+    }
+    Ok(())
+}
@@ -0,0 +1,111 @@
+// tests/coverage_tests.rs
+//! Weakened `cargo check` configurations are detected, reported and, in strict mode, refused.
+#![cfg(feature = "discover")]
+
+mod common;
+
+use common::TestResult;
+use trait_winnower::config::{CargoCheckConfig, Config, CoverageGap};
+use trait_winnower::error::WinnowerError;
+use trait_winnower::winnower::Winnower;
+
+fn gaps(args: &[&str]) -> Vec<CoverageGap> {
+    CargoCheckConfig {
+        args: args.iter().map(|a| a.to_string()).collect(),
+        ..CargoCheckConfig::default()
+    }
+    .coverage_gaps()
+}
+
+fn narrowed_config(strict: bool) -> Config {
+    let mut cfg = Config::default();
+    cfg.cargo_check.args = vec!["-p".into(), "mylib".into(), "--quiet".into()];
+    cfg.strict_validation = strict;
+    cfg
+}
+
+#[test]
+fn coverage_gaps_follow_the_args() {
+    assert!(CargoCheckConfig::default().coverage_gaps().is_empty());
+    assert_eq!(
+        gaps(&["-p", "mylib"]),
+        [
+            CoverageGap::Features,
+            CoverageGap::Targets,
+            CoverageGap::Packages
+        ]
+    );
+    assert_eq!(
+        gaps(&["--workspace", "--all-targets", "--features", "serde"]),
+        [CoverageGap::Features]
+    );
+    for narrowed in [
+        &["--package=mylib"][..],
+        &["-pmylib"],
+        &["--workspace", "--exclude", "bench"],
+    ] {
+        let found = gaps(&[&["--all-features", "--all-targets"], narrowed].concat());
+        assert_eq!(found, [CoverageGap::Packages], "{narrowed:?}");
+    }
+    // `--profile` is not `-p`.
+    assert!(gaps(&["--all-features", "--all-targets", "--profile", "test"]).is_empty());
+}
+
+#[test]
+fn reports_carry_the_validation_config() -> TestResult {
+    let tmp = tempfile::tempdir()?;
+    std::fs::write(
+        tmp.path().join("Cargo.toml"),
+        "[package]\nname = \"x\"\nversion = \"0.1.0\"\n",
+    )?;
+    std::fs::create_dir(tmp.path().join("src"))?;
+    std::fs::write(
+        tmp.path().join("src/lib.rs"),
+        "pub fn f<T: Clone>(_: T) {}\n",
+    )?;
+
+    let checked = Winnower::builder()
+        .target(tmp.path())
+        .config(narrowed_config(false))
+        .build()?
+        .check()?;
+    let validation = checked.report.validation.expect("crate targets record it");
+    assert_eq!(validation.args, ["-p", "mylib", "--quiet"]);
+    assert_eq!(validation.gaps.len(), 3);
+
+    // Single files are never validated.
+    let single = Winnower::builder()
+        .target(tmp.path().join("src/lib.rs"))
+        .build()?
+        .check()?;
+    assert!(single.report.validation.is_none());
+    Ok(())
+}
+
+#[test]
+fn strict_validation_refuses_to_prune_under_weak_args() -> TestResult {
+    let tmp = tempfile::tempdir()?;
+    std::fs::write(
+        tmp.path().join("Cargo.toml"),
+        "[package]\nname = \"x\"\nversion = \"0.1.0\"\n",
+    )?;
+    std::fs::create_dir(tmp.path().join("src"))?;
+    let src = "pub fn f<T: Clone>(_: T) {}\n";
+    std::fs::write(tmp.path().join("src/lib.rs"), src)?;
+
+    let err = Winnower::builder()
+        .target(tmp.path())
+        .config(narrowed_config(true))
+        .brute_force(true)
+        .build()?
+        .prune()
+        .expect_err("strict mode must refuse");
+    let WinnowerError::Config { detail, .. } = &err else {
+        panic!("expected Config, got {err:?}");
+    };
+    assert!(detail.contains("cargo check -p mylib --quiet"), "{detail}");
+    assert!(detail.contains("--all-features"), "{detail}");
+    assert_eq!(err.exit_code(), 2);
+    assert_eq!(std::fs::read_to_string(tmp.path().join("src/lib.rs"))?, src);
+    Ok(())
+}
@@ -0,0 +1,104 @@
+// tests/derive_candidate_tests.rs
+//! After `prune`, hand-written impls a `#[derive]` could replace are listed, with whether
+//! the run's removals are what made them derivable.
+#![cfg(feature = "cli")]
+
+mod common;
+
+use assert_cmd::Command;
+use common::TestResult;
+use predicates::str::contains;
+use std::path::PathBuf;
+use trait_winnower::config::Config;
+use trait_winnower::winnower::Winnower;
+
+const LIB: &str = "\
+pub struct Wrapper<T> {
+    pub inner: T,
+    pub len: usize,
+}
+impl<T: Clone + std::fmt::Debug> Clone for Wrapper<T> {
+    fn clone(&self) -> Self {
+        Self {
+            inner: self.inner.clone(),
+            len: self.len.clone(),
+        }
+    }
+}
+pub struct Counted<T> {
+    pub inner: T,
+    pub count: usize,
+}
+impl<T: Clone + Send> Clone for Counted<T> {
+    fn clone(&self) -> Self {
+        Self {
+            inner: self.inner.clone(),
+            count: 0,
+        }
+    }
+}
+pub struct Marker;
+impl Default for Marker {
+    fn default() -> Self {
+        Marker
+    }
+}
+";
+
+#[test]
+fn pruned_impls_that_a_derive_would_write_are_listed() -> TestResult {
+    let tmp = tempfile::tempdir()?;
+    common::write_crate(tmp.path(), "derives", "", &[("src/lib.rs", LIB)])?;
+    let pruned = Winnower::builder()
+        .target(tmp.path())
+        .config(Config::default())
+        .brute_force(true)
+        .build()?
+        .prune()?;
+    let pruned_lib = std::fs::read_to_string(tmp.path().join("src/lib.rs"))?;
+    assert_eq!(
+        pruned_lib,
+        LIB.replace("T: Clone + std::fmt::Debug", "T: Clone")
+            .replace("T: Clone + Send", "T: Clone")
+    );
+
+    // `Counted` clones to a count of its own, so only `Wrapper` and `Marker` are listed.
+    let candidates: Vec<(PathBuf, usize, &str, &str, bool)> = pruned
+        .report
+        .derive_candidates
+        .iter()
+        .map(|d| {
+            (
+                d.file.clone(),
+                d.line,
+                d.derive.as_str(),
+                d.ty.as_str(),
+                d.enabled_by_prune,
+            )
+        })
+        .collect();
+    assert_eq!(
+        candidates,
+        [
+            (PathBuf::from("src/lib.rs"), 5, "Clone", "Wrapper", true),
+            (PathBuf::from("src/lib.rs"), 26, "Default", "Marker", false),
+        ]
+    );
+    Ok(())
+}
+
+#[test]
+fn derive_candidates_are_shown() -> TestResult {
+    let tmp = tempfile::tempdir()?;
+    common::write_crate(tmp.path(), "derives", "", &[("src/lib.rs", LIB)])?;
+    Command::cargo_bin("trait-winnower")?
+        .args(["prune", "--brute-force"])
+        .arg(tmp.path())
+        .assert()
+        .success()
+        .stderr(contains(
+            "derive candidate: src/lib.rs:5: `impl Clone for Wrapper` could be \
+             `#[derive(Clone)]` now that its extra bounds are gone",
+        ));
+    Ok(())
+}
@@ -0,0 +1,103 @@
+// tests/derive_tests.rs
+//! Bounds on types with `#[derive]`s: flagged in `check` and `prune`, skippable by config.
+#![cfg(feature = "discover")]
+
+mod common;
+
+use common::TestResult;
+use std::path::Path;
+use trait_winnower::config::Config;
+use trait_winnower::report::{Removal, RemovalStatus};
+use trait_winnower::winnower::Winnower;
+
+const SANDBOX: &str = "tests/test_files/derive_sandbox";
+
+fn copy_sandbox(dst: &Path) -> std::io::Result<()> {
+    std::fs::create_dir_all(dst.join("src"))?;
+    for file in ["Cargo.toml", "Cargo.lock", "src/lib.rs"] {
+        std::fs::copy(Path::new(SANDBOX).join(file), dst.join(file))?;
+    }
+    Ok(())
+}
+
+/// The default configuration, offline: the sandbox depends on serde.
+fn offline_config() -> Config {
+    let mut cfg = Config::default();
+    cfg.cargo_check.args.push("--offline".into());
+    cfg
+}
+
+/// `(item, status)` of every removal that was tried.
+fn outcomes(removals: &[&Removal]) -> Vec<(String, RemovalStatus)> {
+    removals
+        .iter()
+        .filter(|r| r.status != RemovalStatus::Skipped)
+        .map(|r| (r.finding.item.clone(), r.status))
+        .collect()
+}
+
+#[test]
+fn serde_bounds_are_removed_only_where_the_derive_infers_them() -> TestResult {
+    let tmp = tempfile::tempdir()?;
+    copy_sandbox(tmp.path())?;
+    let pruned = Winnower::builder()
+        .target(tmp.path())
+        .config(offline_config())
+        .brute_force(true)
+        .build()?
+        .prune()?;
+    let removals: Vec<&Removal> = pruned
+        .report
+        .files
+        .iter()
+        .flat_map(|f| &f.removals)
+        .collect();
+    assert_eq!(
+        outcomes(&removals),
+        [
+            ("struct Inferred".to_owned(), RemovalStatus::Removed),
+            ("struct Explicit".to_owned(), RemovalStatus::Retained),
+        ]
+    );
+    assert!(removals.iter().all(|r| r.finding.derives == ["Serialize"]));
+    let src = std::fs::read_to_string(tmp.path().join("src/lib.rs"))?;
+    assert!(src.contains("pub struct Inferred<T> {"), "{src}");
+    assert!(src.contains("pub struct Explicit<T: Serialize> {"), "{src}");
+    Ok(())
+}
+
+#[test]
+fn skip_derived_types_leaves_them_alone() -> TestResult {
+    let tmp = tempfile::tempdir()?;
+    copy_sandbox(tmp.path())?;
+    let before = std::fs::read_to_string(tmp.path().join("src/lib.rs"))?;
+    let config = Config {
+        skip_derived_types: true,
+        ..offline_config()
+    };
+    let pruned = Winnower::builder()
+        .target(tmp.path())
+        .config(config)
+        .brute_force(true)
+        .build()?
+        .prune()?;
+    assert_eq!(pruned.report.count_removals(RemovalStatus::Removed), 0);
+    assert!(pruned.modified.is_empty());
+    assert_eq!(
+        std::fs::read_to_string(tmp.path().join("src/lib.rs"))?,
+        before
+    );
+    Ok(())
+}
+
+#[test]
+fn check_marks_findings_on_derived_types() -> TestResult {
+    let checked = Winnower::builder()
+        .target(Path::new(SANDBOX).join("src/lib.rs"))
+        .build()?
+        .check()?;
+    let findings = &checked.report.files[0].findings;
+    assert_eq!(findings.len(), 2);
+    assert!(findings.iter().all(|f| f.derives == ["Serialize"]));
+    Ok(())
+}
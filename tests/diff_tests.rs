@@ -0,0 +1,139 @@
+// tests/diff_tests.rs
+//! `diff <from> <to>` compares the bounds of two git revisions item by item, in temporary
+//! worktrees that leave the user's tree alone.
+#![cfg(feature = "cli")]
+
+mod common;
+
+use assert_cmd::Command;
+use common::TestResult;
+use predicates::str::contains;
+use std::path::Path;
+
+fn git(root: &Path, args: &[&str]) -> Result<String, Box<dyn std::error::Error>> {
+    let out = std::process::Command::new("git")
+        .args(["-c", "user.name=t", "-c", "user.email=t@example.com"])
+        .args(args)
+        .current_dir(root)
+        .output()?;
+    assert!(
+        out.status.success(),
+        "git {args:?}: {}",
+        String::from_utf8_lossy(&out.stderr)
+    );
+    Ok(String::from_utf8(out.stdout)?.trim().to_owned())
+}
+
+const V1: &str = "\
+pub fn keep<T: Clone>(t: T) -> T {
+    t.clone()
+}
+
+pub fn shrink<T: Clone + Send>(t: T) -> T {
+    t
+}
+
+pub fn old_name<T: Copy>(t: T) -> T {
+    t
+}
+";
+
+const V2: &str = "\
+pub fn keep<T: Clone>(t: T) -> T {
+    t.clone()
+}
+
+pub fn shrink<T: Clone + Copy>(t: T) -> T {
+    t
+}
+
+pub fn new_name<T: Copy>(t: T) -> T {
+    t
+}
+";
+
+/// A repository with the crate at `V1`, then `V2`, one commit each.
+fn history(root: &Path) -> TestResult {
+    std::fs::create_dir_all(root.join("src"))?;
+    std::fs::write(
+        root.join("Cargo.toml"),
+        "[package]\nname=\"d\"\nversion=\"0.1.0\"\nedition=\"2021\"\n\n[workspace]\n",
+    )?;
+    git(root, &["init", "-q"])?;
+    std::fs::write(root.join("src/lib.rs"), V1)?;
+    git(root, &["add", "-A"])?;
+    git(root, &["commit", "-qm", "v1"])?;
+    std::fs::write(root.join("src/lib.rs"), V2)?;
+    git(root, &["commit", "-qam", "v2"])?;
+    Ok(())
+}
+
+fn diff(root: &Path, extra: &[&str]) -> Result<String, Box<dyn std::error::Error>> {
+    let out = Command::cargo_bin("trait-winnower")?
+        .args(extra)
+        .args(["diff", "HEAD~1", "HEAD", "--target"])
+        .arg(root)
+        .output()?;
+    assert!(
+        out.status.success(),
+        "{}",
+        String::from_utf8_lossy(&out.stderr)
+    );
+    Ok(String::from_utf8(out.stdout)?)
+}
+
+#[test]
+fn diff_lists_bounds_added_removed_and_flagged_per_item() -> TestResult {
+    let tmp = tempfile::tempdir()?;
+    history(tmp.path())?;
+    // An uncommitted edit stays where it is.
+    std::fs::write(tmp.path().join("src/lib.rs"), "pub fn wip() {}\n")?;
+
+    let text = diff(tmp.path(), &[])?;
+    assert_eq!(
+        text,
+        "src/lib.rs fn new_name: added\n  + <T: Copy>\n  ! <T: Copy> (unused-in-body)\n\
+         src/lib.rs fn old_name: removed\n  - <T: Copy>\n  ~ <T: Copy> (unused-in-body)\n\
+         src/lib.rs fn shrink: changed\n  + <T: Copy>\n  - <T: Send>\n  \
+         ! <T: Clone> (implied-by-supertrait)\n  ! <T: Copy> (unused-in-body)\n  \
+         ~ <T: Clone> (unused-in-body)\n  ~ <T: Send> (unused-in-body)\n\
+         HEAD~1..HEAD: 1 item(s) added, 1 removed, 1 changed; 2 bound(s) added, 2 removed; \
+         3 newly flagged, 3 no longer flagged\n"
+    );
+
+    assert_eq!(
+        std::fs::read_to_string(tmp.path().join("src/lib.rs"))?,
+        "pub fn wip() {}\n"
+    );
+    assert_eq!(git(tmp.path(), &["worktree", "list"])?.lines().count(), 1);
+    Ok(())
+}
+
+#[test]
+fn diff_writes_json_for_dashboards() -> TestResult {
+    let tmp = tempfile::tempdir()?;
+    history(tmp.path())?;
+    let json: serde_json::Value = serde_json::from_str(&diff(tmp.path(), &["--format", "json"])?)?;
+    assert_eq!(json["from"], "HEAD~1");
+    assert_eq!(json["summary"]["items_changed"], 1);
+    let shrink = &json["items"][2];
+    assert_eq!(shrink["name"], "shrink");
+    assert_eq!(shrink["kind"], "fn");
+    assert_eq!(shrink["change"], "changed");
+    assert_eq!(shrink["added"][0], "<T: Copy>");
+    Ok(())
+}
+
+#[test]
+fn diff_fails_on_an_unknown_revision() -> TestResult {
+    let tmp = tempfile::tempdir()?;
+    history(tmp.path())?;
+    Command::cargo_bin("trait-winnower")?
+        .args(["diff", "HEAD~1", "no-such-ref", "--target"])
+        .arg(tmp.path())
+        .assert()
+        .failure()
+        .stderr(contains("no-such-ref"));
+    assert_eq!(git(tmp.path(), &["worktree", "list"])?.lines().count(), 1);
+    Ok(())
+}
@@ -0,0 +1,321 @@
+//! File discovery tests.
+#![cfg(feature = "discover")]
+
+mod common;
+
+use assert_fs::prelude::*;
+use common::TestResult;
+use std::path::Path;
+use trait_winnower::discover::{Discover, IgnoreFiles, IgnoreRule, SkipReason};
+
+/// Create a directory symlink, returning false where the platform refuses.
+fn try_symlink_dir(original: &Path, link: &Path) -> bool {
+    #[cfg(unix)]
+    {
+        std::os::unix::fs::symlink(original, link).is_ok()
+    }
+    #[cfg(windows)]
+    {
+        std::os::windows::fs::symlink_dir(original, link).is_ok()
+    }
+    #[cfg(not(any(unix, windows)))]
+    {
+        let _ = (original, link);
+        false
+    }
+}
+
+/// Create a file symlink, returning false where the platform refuses.
+fn try_symlink_file(original: &Path, link: &Path) -> bool {
+    #[cfg(unix)]
+    {
+        std::os::unix::fs::symlink(original, link).is_ok()
+    }
+    #[cfg(windows)]
+    {
+        std::os::windows::fs::symlink_file(original, link).is_ok()
+    }
+    #[cfg(not(any(unix, windows)))]
+    {
+        let _ = (original, link);
+        false
+    }
+}
+
+fn rel_names(root: &Path, files: &[std::path::PathBuf]) -> Vec<String> {
+    let mut names: Vec<String> = files
+        .iter()
+        .map(|p| {
+            p.strip_prefix(root)
+                .unwrap_or(p)
+                .to_string_lossy()
+                .replace('\\', "/")
+        })
+        .collect();
+    names.sort();
+    names
+}
+
+#[test]
+fn symlinked_dir_skipped_by_default() -> TestResult {
+    let tmp = assert_fs::TempDir::new()?;
+    let vendor = assert_fs::TempDir::new()?;
+    vendor.child("v.rs").write_str("fn v() {}")?;
+    tmp.child("src/lib.rs").write_str("// lib")?;
+    if !try_symlink_dir(vendor.path(), &tmp.path().join("src/vendor")) {
+        eprintln!("symlinks unavailable; skipping");
+        return Ok(());
+    }
+
+    let files = Discover::discover_rs_files(tmp.path(), &["**/*.rs".into()], &[])?;
+    assert_eq!(rel_names(tmp.path(), &files), vec!["src/lib.rs"]);
+    Ok(())
+}
+
+#[test]
+fn symlinked_dir_followed_when_enabled() -> TestResult {
+    let tmp = assert_fs::TempDir::new()?;
+    let vendor = assert_fs::TempDir::new()?;
+    vendor.child("v.rs").write_str("fn v() {}")?;
+    tmp.child("src/lib.rs").write_str("// lib")?;
+    if !try_symlink_dir(vendor.path(), &tmp.path().join("src/vendor")) {
+        eprintln!("symlinks unavailable; skipping");
+        return Ok(());
+    }
+
+    let files = Discover::discover_rs_files_with(tmp.path(), &["**/*.rs".into()], &[], true)?;
+    assert_eq!(
+        rel_names(tmp.path(), &files),
+        vec!["src/lib.rs", "src/vendor/v.rs"]
+    );
+    Ok(())
+}
+
+#[test]
+fn globs_match_non_canonical_relative_path() -> TestResult {
+    let tmp = assert_fs::TempDir::new()?;
+    let vendor = assert_fs::TempDir::new()?;
+    vendor.child("v.rs").write_str("fn v() {}")?;
+    tmp.child("src/lib.rs").write_str("// lib")?;
+    if !try_symlink_dir(vendor.path(), &tmp.path().join("src/vendor")) {
+        eprintln!("symlinks unavailable; skipping");
+        return Ok(());
+    }
+
+    let files = Discover::discover_rs_files_with(
+        tmp.path(),
+        &["**/*.rs".into()],
+        &["src/vendor/**".into()],
+        true,
+    )?;
+    assert_eq!(rel_names(tmp.path(), &files), vec!["src/lib.rs"]);
+    Ok(())
+}
+
+#[test]
+fn same_file_under_two_paths_is_deduplicated() -> TestResult {
+    let tmp = assert_fs::TempDir::new()?;
+    tmp.child("src/lib.rs").write_str("// lib")?;
+    if !try_symlink_file(
+        &tmp.path().join("src/lib.rs"),
+        &tmp.path().join("src/alias.rs"),
+    ) {
+        eprintln!("symlinks unavailable; skipping");
+        return Ok(());
+    }
+
+    let files = Discover::discover_rs_files_with(tmp.path(), &["**/*.rs".into()], &[], true)?;
+    assert_eq!(files.len(), 1, "got: {files:?}");
+    Ok(())
+}
+
+#[test]
+fn report_records_skip_reasons() -> TestResult {
+    let tmp = assert_fs::TempDir::new()?;
+    tmp.child("src/lib.rs").write_str("// lib")?;
+    tmp.child("src/gen/out.rs").write_str("// generated")?;
+    tmp.child("build/skip.rs").write_str("// not included")?;
+    tmp.child("README.md").write_str("readme")?;
+    tmp.child("vendored/deep/x.rs").write_str("// ignored")?;
+    tmp.child(".ignore").write_str("vendored/\n")?;
+
+    let found = Discover::discover_rs_files_report(
+        tmp.path(),
+        &["src/**/*.rs".into()],
+        &["**/gen/**".into()],
+        false,
+    )?;
+    assert_eq!(rel_names(tmp.path(), &found.files), vec!["src/lib.rs"]);
+
+    let reason_of = |rel: &str| {
+        found
+            .skipped
+            .iter()
+            .find(|s| s.path == tmp.path().join(rel))
+            .map(|s| s.reason.clone())
+    };
+    assert_eq!(
+        reason_of("src/gen/out.rs"),
+        Some(SkipReason::Excluded {
+            pattern: "**/gen/**".into()
+        })
+    );
+    assert_eq!(reason_of("build/skip.rs"), Some(SkipReason::NotIncluded));
+    assert_eq!(reason_of("README.md"), Some(SkipReason::WrongExtension));
+    assert_eq!(
+        reason_of("vendored"),
+        Some(SkipReason::Ignored {
+            by: Some(IgnoreRule {
+                file: tmp.path().join(".ignore"),
+                pattern: "vendored/".into(),
+            })
+        })
+    );
+    assert_eq!(reason_of("vendored/deep/x.rs"), None);
+    Ok(())
+}
+
+#[test]
+fn gitignored_paths_name_their_rule_and_are_found_with_ignores_off() -> TestResult {
+    let tmp = assert_fs::TempDir::new()?;
+    tmp.child(".git").create_dir_all()?;
+    tmp.child(".gitignore").write_str("vendor/\n*.gen.rs\n")?;
+    tmp.child("src/lib.rs").write_str("// lib")?;
+    tmp.child("src/table.gen.rs").write_str("// generated")?;
+    tmp.child("vendor/fork/lib.rs")
+        .write_str("// vendored fork")?;
+    let include = ["**/*.rs".to_string()];
+
+    let found = Discover::discover_rs_files_report(tmp.path(), &include, &[], false)?;
+    assert_eq!(rel_names(tmp.path(), &found.files), vec!["src/lib.rs"]);
+    let ignored: Vec<(String, String)> = found
+        .skipped
+        .iter()
+        .filter_map(|s| match &s.reason {
+            SkipReason::Ignored { by: Some(rule) } => {
+                assert_eq!(rule.file, tmp.path().join(".gitignore"));
+                let rel = s.path.strip_prefix(tmp.path()).unwrap_or(&s.path);
+                Some((
+                    rel.to_string_lossy().replace('\\', "/"),
+                    rule.pattern.clone(),
+                ))
+            }
+            _ => None,
+        })
+        .collect();
+    assert_eq!(
+        ignored,
+        [
+            ("src/table.gen.rs".to_string(), "*.gen.rs".to_string()),
+            ("vendor".to_string(), "vendor/".to_string())
+        ]
+    );
+
+    let off = IgnoreFiles {
+        respect: false,
+        ..IgnoreFiles::default()
+    };
+    let found =
+        Discover::discover_rs_files_report_with(tmp.path(), &include, &[], false, off, &[])?;
+    assert_eq!(
+        rel_names(tmp.path(), &found.files),
+        vec!["src/lib.rs", "src/table.gen.rs", "vendor/fork/lib.rs"]
+    );
+    assert!(
+        !found
+            .skipped
+            .iter()
+            .any(|s| matches!(s.reason, SkipReason::Ignored { .. }))
+    );
+    Ok(())
+}
+
+#[test]
+fn report_selects_same_files_as_plain_discovery() -> TestResult {
+    let tmp = assert_fs::TempDir::new()?;
+    tmp.child("src/lib.rs").write_str("// lib")?;
+    tmp.child("src/a.rs").write_str("// a")?;
+    tmp.child("target/debug/b.rs").write_str("// b")?;
+
+    let include = ["**/*.rs".to_string()];
+    let exclude = ["target/**".to_string()];
+    let plain = Discover::discover_rs_files(tmp.path(), &include, &exclude)?;
+    let report = Discover::discover_rs_files_report(tmp.path(), &include, &exclude, false)?;
+    assert_eq!(
+        rel_names(tmp.path(), &plain),
+        rel_names(tmp.path(), &report.files)
+    );
+    Ok(())
+}
+
+#[test]
+fn resolves_module_tree_from_single_file() -> TestResult {
+    let root = Path::new("tests/test_files/module_tree");
+    let modules = Discover::resolve_module_files(&root.join("lib.rs"))?;
+    assert_eq!(
+        rel_names(root, &modules.files),
+        vec![
+            "a.rs",
+            "a/inner.rs",
+            "b/mod.rs",
+            "elsewhere/renamed.rs",
+            "inline/deep.rs",
+            "lib.rs",
+        ]
+    );
+    assert_eq!(modules.files[0], root.join("lib.rs"));
+
+    assert_eq!(modules.missing.len(), 1);
+    let missing = &modules.missing[0];
+    assert_eq!(missing.name, "missing");
+    assert_eq!(missing.declared_in, root.join("lib.rs"));
+    assert_eq!(
+        missing.tried,
+        vec![root.join("missing.rs"), root.join("missing/mod.rs")]
+    );
+    Ok(())
+}
+
+#[test]
+fn check_warns_on_shared_files_and_skips_generated_ones() -> TestResult {
+    let tmp = assert_fs::TempDir::new()?;
+    tmp.child("Cargo.toml")
+        .write_str("[workspace]\nmembers = [\"a\", \"b\"]\nresolver = \"2\"\n")?;
+    for member in ["a", "b"] {
+        tmp.child(format!("{member}/Cargo.toml"))
+            .write_str(&format!(
+                "[package]\nname = \"{member}\"\nversion = \"0.1.0\"\nedition = \"2021\"\n"
+            ))?;
+        tmp.child(format!("{member}/src/lib.rs"))
+            .write_str("#[path = \"../../shared/common.rs\"]\nmod common;\n")?;
+    }
+    tmp.child("shared/common.rs")
+        .write_str("pub fn f<T: Clone>(t: T) -> T {\n    t\n}\n")?;
+    tmp.child("out-of-tree/build/a-0123abcd/out/gen.rs")
+        .write_str("pub fn g<T: Clone>(t: T) -> T {\n    t\n}\n")?;
+
+    let checked = trait_winnower::winnower::Winnower::builder()
+        .target(tmp.path())
+        .build()?
+        .check()?;
+    assert_eq!(
+        checked.shared,
+        [trait_winnower::crate_map::SharedFile {
+            path: tmp.path().join("shared/common.rs"),
+            packages: vec!["a".into(), "b".into()],
+        }]
+    );
+    assert!(
+        checked
+            .skipped
+            .iter()
+            .any(|s| s.reason == SkipReason::Generated
+                && s.path.ends_with("out-of-tree/build/a-0123abcd/out/gen.rs"))
+    );
+    let analyzed: Vec<_> = checked.report.files.iter().map(|f| &f.path).collect();
+    assert!(
+        !analyzed.iter().any(|p| p.ends_with("gen.rs")),
+        "{analyzed:?}"
+    );
+    Ok(())
+}
@@ -0,0 +1,61 @@
+// tests/error_tests.rs
+//! Library failures surface as specific `WinnowerError` variants.
+#![cfg(feature = "discover")]
+
+mod common;
+
+use common::TestResult;
+use trait_winnower::error::WinnowerError;
+use trait_winnower::winnower::Winnower;
+
+#[test]
+fn missing_target_is_a_target_error() {
+    let err = Winnower::builder()
+        .target("tests/test_files/does_not_exist")
+        .build()
+        .err()
+        .expect("missing target must fail");
+    let WinnowerError::Target { path, detail } = &err else {
+        panic!("expected Target, got {err:?}");
+    };
+    assert!(path.ends_with("does_not_exist"));
+    assert!(detail.contains("target not found"));
+    assert_eq!(err.exit_code(), 2);
+}
+
+#[test]
+fn broken_config_is_a_config_error() -> TestResult {
+    let tmp = tempfile::tempdir()?;
+    std::fs::write(tmp.path().join("Cargo.toml"), "[package]\nname = \"x\"\n")?;
+    std::fs::write(tmp.path().join(".trait-winnower.toml"), "include = [\n")?;
+    let err = Winnower::builder()
+        .target(tmp.path())
+        .build()
+        .err()
+        .expect("broken config must fail");
+    let WinnowerError::Config { path, .. } = &err else {
+        panic!("expected Config, got {err:?}");
+    };
+    assert!(path.ends_with(".trait-winnower.toml"));
+    assert!(err.to_string().contains(".trait-winnower.toml"));
+    Ok(())
+}
+
+#[test]
+fn syntax_error_is_a_parse_error_with_location() -> TestResult {
+    let tmp = tempfile::tempdir()?;
+    let file = tmp.path().join("broken.rs");
+    std::fs::write(&file, "fn ok() {}\nfn broken( {}\n")?;
+    let err = Winnower::builder()
+        .target(&file)
+        .build()?
+        .check()
+        .expect_err("syntax error must fail");
+    let WinnowerError::Parse { path, .. } = &err else {
+        panic!("expected Parse, got {err:?}");
+    };
+    assert_eq!(path.as_deref(), Some(file.as_path()));
+    assert!(err.to_string().contains("broken.rs:2:"), "{err}");
+    assert_eq!(err.exit_code(), 1);
+    Ok(())
+}
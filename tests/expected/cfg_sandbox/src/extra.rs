@@ -0,0 +1,5 @@
+//! Built only with the `extra` feature, which validation enables.
+/// `Ord` is needed by `max`; `Copy` and `Clone` are not.
+pub fn pick<T: Ord>(t: T, u: T) -> T {
+    t.max(u)
+}
@@ -0,0 +1,25 @@
+//! Items behind `cfg`: what validation builds is pruned, the rest left alone.
+#[cfg(feature = "extra")]
+pub mod extra;
+/// Compiled by default; `Clone` is not used.
+pub fn first<T>(v: Vec<T>) -> Option<T> {
+    v.into_iter().next()
+}
+/// Only in feature builds; `Default` is not used.
+#[cfg(feature = "extra")]
+pub fn gated<T: Clone>(t: T) -> T {
+    t.clone()
+}
+/// Never built here; its `Send` is left alone.
+#[cfg(target_os = "uefi")]
+pub fn firmware<T: Send>(t: T) -> T {
+    t
+}
+#[cfg(test)]
+mod smoke {
+    use super::*;
+    #[test]
+    fn it_compiles_and_runs() {
+        assert_eq!(first(vec![1, 2]), Some(1));
+    }
+}
@@ -0,0 +1,50 @@
+//! Impl blocks and their methods: bounds at either level, and trait impls.
+use core::fmt::Display;
+pub struct Stack<T>(pub Vec<T>);
+/// `Clone` is needed by `top`; `Default` by nothing.
+impl<T: Clone> Stack<T> {
+    pub fn top(&self) -> Option<T> {
+        self.0.last().cloned()
+    }
+    pub fn len(&self) -> usize {
+        self.0.len()
+    }
+}
+impl<T> Stack<T> {
+    /// `Display` is used; `Ord` is not.
+    pub fn render(&self) -> String
+    where
+        T: Display,
+    {
+        self.0.iter().map(|t| t.to_string()).collect()
+    }
+    /// `Clone` comes with `Copy`.
+    pub fn total(&self) -> u32
+    where
+        T: Copy + Into<u32>,
+    {
+        self.0.iter().map(|t| (*t).into()).sum()
+    }
+}
+pub trait Summary {
+    fn summary(&self) -> String;
+}
+/// `Display` is needed by the trait method; `Send` is not.
+impl<T: Display> Summary for Stack<T> {
+    fn summary(&self) -> String {
+        self.0.iter().map(|t| format!("{t};")).collect()
+    }
+}
+#[cfg(test)]
+mod smoke {
+    use super::*;
+    #[test]
+    fn it_compiles_and_runs() {
+        let s = Stack(vec![1u8, 2]);
+        assert_eq!(s.top(), Some(2));
+        assert_eq!(s.len(), 2);
+        assert_eq!(s.render(), "12");
+        assert_eq!(s.total(), 3);
+        assert_eq!(s.summary(), "1;2;");
+    }
+}
@@ -0,0 +1,13 @@
+//! The same name twice in one file, in different impls.
+pub struct One;
+pub struct Two;
+impl One {
+    pub fn run<T>(t: T) -> T {
+        t
+    }
+}
+impl Two {
+    pub fn run<T: Clone>(t: T) -> T {
+        t.clone()
+    }
+}
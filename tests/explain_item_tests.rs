@@ -0,0 +1,155 @@
+// tests/explain_item_tests.rs
+//! `explain-item <path>` prints what is known about one item, tries its candidates with
+//! `--trial`, and suggests near names when none matches.
+#![cfg(feature = "cli")]
+
+mod common;
+
+use assert_cmd::Command;
+use common::TestResult;
+use predicates::str::contains;
+use std::path::Path;
+
+const LIB: &str = "\
+pub fn cache_insert<T: Clone>(t: T) -> (T, T) {
+    (t.clone(), t)
+}
+
+pub mod b {
+    pub struct Wrapper<T>(pub T);
+
+    impl<T> Wrapper<T> {
+        pub fn id<U: Copy + Clone>(&self, u: U) -> (U, U)
+        where
+            T: Send,
+        {
+            crate::cache_insert(u)
+        }
+    }
+}
+
+pub fn store<T: Clone>(t: T) -> (T, T) {
+    cache_insert(t)
+}
+";
+
+fn explain(root: &Path, args: &[&str]) -> Result<String, Box<dyn std::error::Error>> {
+    let out = Command::cargo_bin("trait-winnower")?
+        .args(args)
+        .arg(root)
+        .output()?;
+    assert!(
+        out.status.success(),
+        "{}",
+        String::from_utf8_lossy(&out.stderr)
+    );
+    Ok(String::from_utf8(out.stdout)?)
+}
+
+#[test]
+fn explain_item_lists_declared_bounds_candidates_and_rules() -> TestResult {
+    let tmp = tempfile::tempdir()?;
+    common::write_crate(tmp.path(), "ex", "", &[("src/lib.rs", LIB)])?;
+    let text = explain(tmp.path(), &["explain-item", "crate::b::Wrapper<T>::id"])?;
+    assert_eq!(
+        text,
+        "crate::b::Wrapper::id\n  label: b Wrapper < T >::id\n  at: src/lib.rs:9:16\n  \
+         type params:\n    [0] U: Copy + Clone\n  where predicates:\n    [0] T: Send\n  \
+         candidates:\n    <U: Copy> (type param 0, bound 0; 9:22)\n      rule: none\n      \
+         last run: no prune recorded\n    <U: Clone> (type param 0, bound 1; 9:29)\n      \
+         rule: implied-by-supertrait (high confidence): `Clone` is implied by `Copy` on `U`\n      \
+         last run: no prune recorded\n    where T: Send (where predicate 0, bound 0; 11:16)\n      \
+         rule: none\n      last run: no prune recorded\n"
+    );
+    Ok(())
+}
+
+#[test]
+fn explain_item_trials_each_candidate_and_restores_the_file() -> TestResult {
+    let tmp = tempfile::tempdir()?;
+    common::write_crate(tmp.path(), "ex", "", &[("src/lib.rs", LIB)])?;
+    let json: serde_json::Value = serde_json::from_str(&explain(
+        tmp.path(),
+        &[
+            "--format",
+            "json",
+            "explain-item",
+            "b fn Wrapper::id",
+            "--trial",
+        ],
+    )?)?;
+    let candidates = &json[0]["candidates"];
+    // `Copy` implies the `Clone` that `cache_insert` needs, so each bound may go alone.
+    assert_eq!(candidates[0]["trial"]["removable"], true);
+    assert_eq!(candidates[1]["trial"]["removable"], true);
+    assert_eq!(candidates[2]["trial"]["removable"], true);
+    assert_eq!(std::fs::read_to_string(tmp.path().join("src/lib.rs"))?, LIB);
+
+    let text = explain(tmp.path(), &["explain-item", "store", "--trial"])?;
+    assert!(text.contains("trial: needed"), "{text}");
+    assert!(text.contains("error[E0277]"), "{text}");
+    assert!(
+        text.contains("required by a bound in `cache_insert` (src/lib.rs:1)"),
+        "{text}"
+    );
+    assert_eq!(std::fs::read_to_string(tmp.path().join("src/lib.rs"))?, LIB);
+    Ok(())
+}
+
+#[test]
+fn explain_item_suggests_near_names() -> TestResult {
+    let tmp = tempfile::tempdir()?;
+    common::write_crate(tmp.path(), "ex", "", &[("src/lib.rs", LIB)])?;
+    Command::cargo_bin("trait-winnower")?
+        .args(["explain-item", "crate::b::Wraper::id"])
+        .arg(tmp.path())
+        .assert()
+        .failure()
+        .stderr(contains(
+            "no item `crate::b::Wraper::id` (did you mean `crate::b::Wrapper::id`?)",
+        ));
+    Command::cargo_bin("trait-winnower")?
+        .args(["explain-item", "id"])
+        .arg(tmp.path())
+        .assert()
+        .failure()
+        .stderr(contains("did you mean `crate::b::Wrapper::id`?"));
+    Ok(())
+}
+
+/// Kills the real binary while `cargo check` validates its first trial; other cargo
+/// commands go to the real cargo.
+#[cfg(unix)]
+#[test]
+fn a_killed_trial_leaves_the_crate_as_it_was() -> TestResult {
+    use std::os::unix::fs::PermissionsExt;
+
+    let tmp = tempfile::tempdir()?;
+    common::write_crate(tmp.path(), "ex", "", &[("src/lib.rs", LIB)])?;
+    let bin = tempfile::tempdir()?;
+    let cargo = bin.path().join("cargo");
+    let real = std::env::var("CARGO").unwrap_or_else(|_| "cargo".into());
+    std::fs::write(
+        &cargo,
+        format!("#!/bin/sh\n[ \"$1\" = check ] && kill -9 $PPID\nexec \"{real}\" \"$@\"\n"),
+    )?;
+    std::fs::set_permissions(&cargo, std::fs::Permissions::from_mode(0o755))?;
+
+    Command::cargo_bin("trait-winnower")?
+        .env("CARGO", &cargo)
+        .args(["explain-item", "store", "--trial"])
+        .arg(tmp.path())
+        .assert()
+        .failure();
+    // Trials run on a copy: the crate holds no trial and nothing is left to recover.
+    assert_eq!(std::fs::read_to_string(tmp.path().join("src/lib.rs"))?, LIB);
+    Command::cargo_bin("trait-winnower")?
+        .arg("recover")
+        .arg(tmp.path())
+        .assert()
+        .success()
+        .stdout(contains("nothing to recover"));
+    let text = explain(tmp.path(), &["explain-item", "store", "--trial"])?;
+    assert!(text.contains("trial: needed"), "{text}");
+    Ok(())
+}
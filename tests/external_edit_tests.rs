@@ -0,0 +1,128 @@
+// tests/external_edit_tests.rs
+//! `prune` leaves alone files written by someone else after the run started, and with
+//! `respect_editor_locks` files an editor holds, instead of overwriting them.
+#![cfg(feature = "discover")]
+
+mod common;
+
+use common::TestResult;
+use std::path::{Path, PathBuf};
+use std::time::{Duration, SystemTime};
+use trait_winnower::config::Config;
+use trait_winnower::dynamic_analysis::external::ExternalEditKind;
+use trait_winnower::dynamic_analysis::validate::AlwaysOk;
+use trait_winnower::observer::Observer;
+use trait_winnower::winnower::{PruneReport, Winnower};
+
+// `a.rs` has the more confident finding, so the run reaches it before `b.rs`.
+const A: &str = "pub fn a<T: Copy + Clone>(t: T) -> T {\n    t\n}\n";
+const B: &str = "pub fn b<T: Clone>(t: T) -> T {\n    t\n}\n";
+const B_SAVED: &str = "pub fn b<T: Clone>(t: T) -> T {\n    t.clone()\n}\n";
+
+/// Does `edit` to `b.rs` once the run starts on `a.rs`: between collection and trial.
+struct EditsB {
+    b: PathBuf,
+    edit: fn(&Path),
+}
+
+impl Observer for EditsB {
+    fn on_file_start(&mut self, path: &Path) {
+        if path.ends_with("a.rs") {
+            (self.edit)(&self.b);
+        }
+    }
+}
+
+const FILES: &[(&str, &str)] = &[
+    ("src/lib.rs", "pub mod a;\npub mod b;\n"),
+    ("src/a.rs", A),
+    ("src/b.rs", B),
+];
+
+fn prune(
+    root: &Path,
+    config: Config,
+    edit: fn(&Path),
+) -> Result<PruneReport, Box<dyn std::error::Error>> {
+    let mut observer = EditsB {
+        b: root.join("src/b.rs"),
+        edit,
+    };
+    Ok(Winnower::builder()
+        .target(root)
+        .config(config)
+        .brute_force(true)
+        .validator(AlwaysOk)
+        .build()?
+        .prune_with(&mut observer)?)
+}
+
+fn pruned_files(report: &PruneReport) -> Vec<PathBuf> {
+    report.report.files.iter().map(|f| f.path.clone()).collect()
+}
+
+#[test]
+fn a_file_written_during_the_run_is_left_alone() -> TestResult {
+    let tmp = tempfile::tempdir()?;
+    common::write_crate(tmp.path(), "ed", "", FILES)?;
+    let report = prune(tmp.path(), Config::default(), |b| {
+        std::fs::write(b, B_SAVED).unwrap();
+    })?;
+
+    assert_eq!(report.external_edits.len(), 1);
+    assert_eq!(report.external_edits[0].path, tmp.path().join("src/b.rs"));
+    assert_eq!(report.external_edits[0].kind, ExternalEditKind::Changed);
+    assert!(!pruned_files(&report).contains(&PathBuf::from("src/b.rs")));
+    assert_eq!(
+        std::fs::read_to_string(tmp.path().join("src/b.rs"))?,
+        B_SAVED
+    );
+    // The rest of the run went on.
+    assert_eq!(
+        std::fs::read_to_string(tmp.path().join("src/a.rs"))?,
+        "pub fn a<T>(t: T) -> T {\n    t\n}\n"
+    );
+    Ok(())
+}
+
+#[test]
+fn a_file_only_touched_during_the_run_is_left_alone_too() -> TestResult {
+    let tmp = tempfile::tempdir()?;
+    common::write_crate(tmp.path(), "ed", "", FILES)?;
+    let report = prune(tmp.path(), Config::default(), |b| {
+        let file = std::fs::File::options().write(true).open(b).unwrap();
+        file.set_modified(SystemTime::now() + Duration::from_secs(60))
+            .unwrap();
+    })?;
+
+    assert_eq!(report.external_edits.len(), 1);
+    assert_eq!(std::fs::read_to_string(tmp.path().join("src/b.rs"))?, B);
+    Ok(())
+}
+
+#[test]
+fn editor_locks_are_honored_only_when_configured() -> TestResult {
+    let tmp = tempfile::tempdir()?;
+    common::write_crate(tmp.path(), "ed", "", FILES)?;
+    std::fs::write(tmp.path().join("src/.b.rs.swp"), "")?;
+    let config = Config {
+        respect_editor_locks: true,
+        ..Config::default()
+    };
+    let report = prune(tmp.path(), config, |_| {})?;
+    assert_eq!(
+        report.external_edits[0].kind,
+        ExternalEditKind::EditorLock {
+            lock: tmp.path().join("src/.b.rs.swp")
+        }
+    );
+    assert_eq!(std::fs::read_to_string(tmp.path().join("src/b.rs"))?, B);
+
+    let report = prune(tmp.path(), Config::default(), |_| {})?;
+    assert!(report.external_edits.is_empty());
+    assert_eq!(
+        std::fs::read_to_string(tmp.path().join("src/b.rs"))?,
+        "pub fn b<T>(t: T) -> T {\n    t\n}\n"
+    );
+    Ok(())
+}
@@ -0,0 +1,107 @@
+// tests/fail_fast_tests.rs
+//! A failing `cargo check` is stopped at its first error; a passing one runs to completion.
+#![cfg(feature = "discover")]
+
+mod common;
+
+use common::TestResult;
+use std::path::Path;
+use trait_winnower::cancel::CancelToken;
+use trait_winnower::config::{CargoCheckConfig, Config};
+use trait_winnower::dynamic_analysis::common::CargoCheck;
+use trait_winnower::dynamic_analysis::validate::Validator;
+use trait_winnower::winnower::Winnower;
+
+/// Takes a couple of seconds to check: long enough to still be building when an error in
+/// a small crate checked beside it comes in.
+const SLOW: &str = "#![allow(long_running_const_eval)]\n\
+                    pub const N: u64 = {\n    let mut i = 0u64;\n    \
+                    while i < 2_000_000 {\n        i += 1;\n    }\n    i\n};\n";
+
+/// The default arguments, with two jobs even on one CPU so `slow` builds beside `a`.
+fn two_jobs() -> CargoCheckConfig {
+    let mut config = CargoCheckConfig::default();
+    config.args.extend(["--jobs".to_owned(), "2".to_owned()]);
+    config
+}
+
+/// A workspace of `a`, `slow`, and `b` using both; `a`'s bound is needed by `b`.
+fn write_workspace(root: &Path) -> std::io::Result<()> {
+    std::fs::write(
+        root.join("Cargo.toml"),
+        "[workspace]\nmembers = [\"a\", \"b\", \"slow\"]\nresolver = \"2\"\n",
+    )?;
+    for (name, deps, src) in [
+        (
+            "a",
+            "",
+            "pub fn f<T: Clone>(t: &T) -> T {\n    t.clone()\n}\n",
+        ),
+        (
+            "b",
+            "a = { path = \"../a\" }\nslow = { path = \"../slow\" }\n",
+            "pub fn g() -> u64 {\n    a::f(&slow::N)\n}\n",
+        ),
+        ("slow", "", SLOW),
+    ] {
+        std::fs::create_dir_all(root.join(name).join("src"))?;
+        std::fs::write(
+            root.join(name).join("Cargo.toml"),
+            format!(
+                "[package]\nname = \"{name}\"\nversion = \"0.1.0\"\nedition = \"2021\"\n\n\
+                 [dependencies]\n{deps}"
+            ),
+        )?;
+        std::fs::write(root.join(name).join("src/lib.rs"), src)?;
+    }
+    Ok(())
+}
+
+#[test]
+fn a_failing_check_stops_early_and_leaves_the_target_dir_usable() -> TestResult {
+    let tmp = tempfile::tempdir()?;
+    let root = tmp.path();
+    write_workspace(root)?;
+    let lib = root.join("a/src/lib.rs");
+    let good = std::fs::read_to_string(&lib)?;
+    std::fs::write(&lib, "pub fn f<T>(t: &T) -> T {\n    t.clone()\n}\n")?;
+
+    let check = CargoCheck::new(two_jobs());
+    let failed = check.validate_cancellable(root, &CancelToken::new())?;
+    assert!(!failed.success);
+    assert!(failed.aborted, "{}", failed.stderr);
+    // The diagnostics came as JSON and are shown as rustc prints them.
+    assert!(failed.stderr.contains("error[E0308]"), "{}", failed.stderr);
+
+    // The killed build released its lock on the target directory.
+    std::fs::write(&lib, good)?;
+    let passed = check.validate_cancellable(root, &CancelToken::new())?;
+    assert!(passed.success, "{}", passed.stderr);
+    assert!(!passed.aborted);
+    Ok(())
+}
+
+#[test]
+fn prune_reports_checks_stopped_early() -> TestResult {
+    let tmp = tempfile::tempdir()?;
+    write_workspace(tmp.path())?;
+    let pruned = Winnower::builder()
+        .target(tmp.path())
+        .config(Config {
+            cargo_check: two_jobs(),
+            ..Config::default()
+        })
+        .brute_force(true)
+        .build()?
+        .prune()?;
+    let f = pruned
+        .report
+        .files
+        .iter()
+        .flat_map(|f| &f.removals)
+        .find(|r| r.finding.item == "fn f")
+        .expect("f was tried");
+    assert!(f.aborted);
+    assert_eq!(pruned.report.total_aborted(), 1);
+    Ok(())
+}
@@ -0,0 +1,32 @@
+// tests/features_tests.rs
+//! The library, and the integration tests gated on the features they need, keep building
+//! without the CLI and discovery features.
+
+mod common;
+
+use common::TestResult;
+use std::process::Command;
+
+#[test]
+fn all_targets_build_with_reduced_features() -> TestResult {
+    // A separate target directory: the outer `cargo test` holds the lock on its own.
+    let target_dir = std::path::Path::new(env!("CARGO_TARGET_TMPDIR")).join("features");
+    for features in [
+        &["--no-default-features"][..],
+        &["--no-default-features", "--features", "discover"],
+    ] {
+        let output = Command::new(env!("CARGO"))
+            .args(["check", "--all-targets", "--offline", "--quiet"])
+            .args(features)
+            .arg("--target-dir")
+            .arg(&target_dir)
+            .current_dir(env!("CARGO_MANIFEST_DIR"))
+            .output()?;
+        assert!(
+            output.status.success(),
+            "cargo check {features:?} failed:\n{}",
+            String::from_utf8_lossy(&output.stderr)
+        );
+    }
+    Ok(())
+}
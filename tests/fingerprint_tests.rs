@@ -0,0 +1,186 @@
+// tests/fingerprint_tests.rs
+//! Runs record the `Cargo.lock` and rustc they saw; `--incremental` caches and plans made
+//! with others are bypassed or warned about, unless fingerprints are ignored.
+#![cfg(feature = "cli")]
+
+mod common;
+
+use common::TestResult;
+use std::path::Path;
+use trait_winnower::dynamic_analysis::incremental::{CacheStats, Incremental, RunCache};
+use trait_winnower::dynamic_analysis::validate::AlwaysOk;
+use trait_winnower::fingerprint::Fingerprint;
+use trait_winnower::formats::ReportDoc;
+use trait_winnower::plan::{ApplyOptions, Plan, apply_plan_with};
+use trait_winnower::report::json::Json;
+use trait_winnower::winnower::{PruneReport, Winnower};
+
+const LIB: &str = "pub mod b;\n\npub fn f<T: Clone>(t: T) -> T {\n    t\n}\n";
+const B: &str = "pub fn g<T: Clone>(t: &T) -> T {\n    t.clone()\n}\n";
+
+fn prune(dir: &Path, ignore_fingerprint: bool) -> Result<PruneReport, Box<dyn std::error::Error>> {
+    Ok(Winnower::builder()
+        .target(dir)
+        .brute_force(true)
+        .incremental(Incremental::Aggressive)
+        .ignore_fingerprint(ignore_fingerprint)
+        .build()?
+        .prune()?)
+}
+
+/// Rewrite the cache of `dir` as if its run had seen another rustc.
+fn fake_rustc(dir: &Path, rustc: &str) -> TestResult {
+    let mut cache = RunCache::load(dir);
+    let fingerprint = cache.fingerprint.get_or_insert_with(Fingerprint::default);
+    fingerprint.rustc = Some(rustc.to_owned());
+    cache.save(dir)?;
+    Ok(())
+}
+
+#[test]
+fn reports_record_the_lockfile_and_rustc() -> TestResult {
+    let tmp = tempfile::tempdir()?;
+    common::write_crate(tmp.path(), "x", "", &[("src/lib.rs", LIB), ("src/b.rs", B)])?;
+    std::fs::write(tmp.path().join("Cargo.lock"), "version = 4\n")?;
+    let checked = Winnower::builder().target(tmp.path()).build()?.check()?;
+    let fingerprint = checked.report.fingerprint.clone().expect("fingerprint");
+    assert_eq!(fingerprint, Fingerprint::capture(tmp.path()));
+    let lockfile = fingerprint.lockfile.as_deref().expect("lockfile");
+    assert_eq!(
+        lockfile,
+        format!("{:08x}", crc32fast::hash(b"version = 4\n"))
+    );
+    assert!(fingerprint.rustc.is_some_and(|r| r.starts_with("rustc ")));
+
+    let mut json = Vec::new();
+    Json::write(&checked.report, "check", &mut json)?;
+    let doc = ReportDoc::from_json(Path::new("report.json"), std::str::from_utf8(&json)?)?;
+    assert_eq!(doc.fingerprint, checked.report.fingerprint);
+
+    // Single files belong to no lockfile.
+    let single = Winnower::builder()
+        .target(tmp.path().join("src/b.rs"))
+        .build()?
+        .check()?;
+    assert_eq!(single.report.fingerprint, None);
+    Ok(())
+}
+
+#[test]
+fn a_cache_from_another_toolchain_is_bypassed_with_a_warning() -> TestResult {
+    let tmp = tempfile::tempdir()?;
+    common::write_crate(tmp.path(), "x", "", &[("src/lib.rs", LIB), ("src/b.rs", B)])?;
+    let first = prune(tmp.path(), false)?;
+    assert_eq!(first.drift, None);
+    let recorded = RunCache::load(tmp.path())
+        .fingerprint
+        .expect("cache fingerprint");
+    assert_eq!(recorded, Fingerprint::capture(tmp.path()));
+
+    fake_rustc(tmp.path(), "rustc 1.0.0 (a28077b28 2015-05-13)")?;
+    let second = prune(tmp.path(), false)?;
+    let drift = second.drift.expect("drift");
+    assert_eq!(drift.artifact, "run cache");
+    assert_eq!(drift.changes.len(), 1);
+    assert!(drift.changes[0].starts_with("rustc was `rustc 1.0.0 (a28077b28 2015-05-13)`"));
+    assert_eq!(second.cache, Some(CacheStats { hits: 0, misses: 2 }));
+    // The bypassing run records the current fingerprint for the next one.
+    assert_eq!(RunCache::load(tmp.path()).fingerprint, Some(recorded));
+    let third = prune(tmp.path(), false)?;
+    assert_eq!(
+        (third.drift, third.cache),
+        (None, Some(CacheStats { hits: 2, misses: 0 }))
+    );
+    Ok(())
+}
+
+#[test]
+fn ignoring_fingerprints_reuses_a_drifted_cache() -> TestResult {
+    let tmp = tempfile::tempdir()?;
+    common::write_crate(tmp.path(), "x", "", &[("src/lib.rs", LIB), ("src/b.rs", B)])?;
+    prune(tmp.path(), false)?;
+    std::fs::write(tmp.path().join("Cargo.lock"), "# bumped\nversion = 4\n")?;
+    let ignored = prune(tmp.path(), true)?;
+    assert_eq!(ignored.drift, None);
+    assert_eq!(ignored.cache, Some(CacheStats { hits: 2, misses: 0 }));
+    Ok(())
+}
+
+#[test]
+fn plans_keep_their_fingerprint_and_warn_on_drift() -> TestResult {
+    let tmp = tempfile::tempdir()?;
+    common::write_crate(tmp.path(), "x", "", &[("src/lib.rs", LIB), ("src/b.rs", B)])?;
+    std::fs::write(tmp.path().join("Cargo.lock"), "version = 4\n")?;
+    let checked = Winnower::builder().target(tmp.path()).build()?.check()?;
+    let plan_path = tmp.path().join("plan.toml");
+    let mut plan = Plan::from_report(&checked.report);
+    assert_eq!(plan.fingerprint, checked.report.fingerprint);
+    plan.fingerprint = Some(Fingerprint {
+        lockfile: Some("0badf00d".into()),
+        ..Fingerprint::capture(tmp.path())
+    });
+    plan.save(&plan_path)?;
+    let plan = Plan::load(&plan_path)?;
+    assert_eq!(
+        plan.fingerprint
+            .as_ref()
+            .and_then(|f| f.lockfile.as_deref()),
+        Some("0badf00d")
+    );
+
+    let options = ApplyOptions {
+        validator: Some(&AlwaysOk),
+        ignore_fingerprint: true,
+        ..ApplyOptions::default()
+    };
+    let applied = apply_plan_with(tmp.path(), &plan, &options)?;
+    assert_eq!(applied.drift, None);
+
+    common::write_crate(tmp.path(), "x", "", &[("src/lib.rs", LIB), ("src/b.rs", B)])?;
+    let options = ApplyOptions {
+        validator: Some(&AlwaysOk),
+        ..ApplyOptions::default()
+    };
+    let applied = apply_plan_with(tmp.path(), &plan, &options)?;
+    let drift = applied.drift.clone().expect("drift");
+    assert_eq!(drift.changes, ["Cargo.lock changed"]);
+    assert_eq!(
+        drift.to_string(),
+        "plan was produced against different dependency versions; findings may differ \
+         (Cargo.lock changed)"
+    );
+    // The warning changes nothing about what applies.
+    assert_eq!(applied.applied(), plan.entries.len());
+    Ok(())
+}
+
+#[test]
+fn the_cli_warns_about_a_drifted_cache() -> TestResult {
+    use assert_cmd::Command;
+    use predicates::prelude::PredicateBooleanExt;
+    use predicates::str::contains;
+
+    let tmp = tempfile::tempdir()?;
+    common::write_crate(tmp.path(), "x", "", &[("src/lib.rs", LIB), ("src/b.rs", B)])?;
+    prune(tmp.path(), false)?;
+    let warning = "warning: run cache was produced against different dependency versions; \
+                   findings may differ (rustc was `rustc 0.0.0`";
+    fake_rustc(tmp.path(), "rustc 0.0.0")?;
+    Command::cargo_bin("trait-winnower")?
+        .args(["prune", "-b", "--incremental", "--ignore-fingerprint"])
+        .arg(tmp.path())
+        .assert()
+        .success()
+        .stderr(contains(warning).not())
+        .stderr(contains("2 file(s) unchanged since the last run, 0 pruned"));
+    // That run recorded the real rustc again.
+    fake_rustc(tmp.path(), "rustc 0.0.0")?;
+    Command::cargo_bin("trait-winnower")?
+        .args(["prune", "-b", "--incremental"])
+        .arg(tmp.path())
+        .assert()
+        .success()
+        .stderr(contains(warning))
+        .stderr(contains("0 file(s) unchanged since the last run, 2 pruned"));
+    Ok(())
+}
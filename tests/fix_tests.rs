@@ -0,0 +1,245 @@
+// tests/fix_tests.rs
+//! `check --fix` removes only what the `duplicate-bound`, `redundant-sized` and
+//! `implied-by-supertrait` rules prove redundant, through the text-edit writer, and never
+//! runs cargo.
+#![cfg(all(unix, feature = "cli"))]
+
+mod common;
+
+use assert_cmd::Command;
+use common::TestResult;
+use predicates::str::contains;
+use std::os::unix::fs::PermissionsExt;
+use std::path::Path;
+use trait_winnower::config::Config;
+use trait_winnower::findings::Rule;
+use trait_winnower::winnower::Winnower;
+
+const SRC: &str = "\
+pub fn dup<T: Clone + Clone>(t: &T) -> T {
+    t.clone()
+}
+
+pub fn both<T: Clone>(t: &T) -> T
+where
+    T: Clone,
+{
+    t.clone()
+}
+
+pub fn sized<T: Sized + Copy>(t: T) -> T {
+    t
+}
+
+// Implied by `Copy`.
+pub fn implied<T: Copy + Clone>(t: T) -> T {
+    t
+}
+
+pub trait Tr
+where
+    Self: Sized + Sized,
+{
+}
+";
+
+const FIXED: &str = "\
+pub fn dup<T: Clone>(t: &T) -> T {
+    t.clone()
+}
+
+pub fn both<T: Clone>(t: &T) -> T
+{
+    t.clone()
+}
+
+pub fn sized<T: Copy>(t: T) -> T {
+    t
+}
+
+// Implied by `Copy`.
+pub fn implied<T: Copy>(t: T) -> T {
+    t
+}
+
+pub trait Tr
+where
+    Self: Sized + Sized,
+{
+}
+";
+
+/// A crate whose configured cargo logs every run to `cargo.log` and fails.
+fn write_crate(root: &Path) -> TestResult {
+    common::write_crate(root, "fix", "", &[("src/lib.rs", SRC)])?;
+    let cargo = root.join("cargo-spy");
+    std::fs::write(
+        &cargo,
+        format!(
+            "#!/bin/sh\necho \"$@\" >> '{}'\nexit 99\n",
+            root.join("cargo.log").display()
+        ),
+    )?;
+    std::fs::set_permissions(&cargo, std::fs::Permissions::from_mode(0o755))?;
+    let mut cfg = Config::default();
+    cfg.cargo_check.cargo_path = Some(cargo);
+    std::fs::write(root.join(".trait-winnower.toml"), toml::to_string(&cfg)?)?;
+    Ok(())
+}
+
+#[test]
+fn fix_removes_provably_redundant_bounds_without_cargo() -> TestResult {
+    let tmp = tempfile::tempdir()?;
+    write_crate(tmp.path())?;
+
+    let fixed = Winnower::builder().target(tmp.path()).build()?.fix()?;
+    assert_eq!(
+        fixed.fixed.into_iter().collect::<Vec<_>>(),
+        [
+            (Rule::DuplicateBound, 2),
+            (Rule::ImpliedBySupertrait, 1),
+            (Rule::RedundantSized, 1)
+        ]
+    );
+    assert_eq!(fixed.changes.len(), 1);
+    assert_eq!(
+        std::fs::read_to_string(tmp.path().join("src/lib.rs"))?,
+        FIXED
+    );
+    assert!(!tmp.path().join("cargo.log").exists());
+
+    // Nothing left to fix.
+    let again = Winnower::builder().target(tmp.path()).build()?.fix()?;
+    assert_eq!(again.total(), 0);
+    assert!(again.changes.is_empty());
+    Ok(())
+}
+
+#[test]
+fn fix_dry_run_prints_the_diff_and_changes_nothing() -> TestResult {
+    let tmp = tempfile::tempdir()?;
+    write_crate(tmp.path())?;
+    Command::cargo_bin("trait-winnower")?
+        .current_dir(tmp.path())
+        .args(["check", "--fix", "--dry-run"])
+        .assert()
+        .success()
+        .stdout(contains("--- a/src/lib.rs\n+++ b/src/lib.rs\n"))
+        .stdout(contains(
+            "-pub fn dup<T: Clone + Clone>(t: &T) -> T {\n+pub fn dup<T: Clone>(t: &T) -> T {\n",
+        ))
+        .stderr(contains(
+            "       2  duplicate-bound\n       1  implied-by-supertrait\n       1  redundant-sized\n",
+        ))
+        .stderr(contains(
+            "would remove 4 bound(s) in 1 file(s), without running cargo",
+        ));
+    assert_eq!(std::fs::read_to_string(tmp.path().join("src/lib.rs"))?, SRC);
+    assert!(!tmp.path().join("cargo.log").exists());
+
+    Command::cargo_bin("trait-winnower")?
+        .current_dir(tmp.path())
+        .args(["check", "--fix", "--only-trait", "Sized"])
+        .assert()
+        .success()
+        .stderr(contains("removed 1 bound(s) in 1 file(s)"));
+    let src = std::fs::read_to_string(tmp.path().join("src/lib.rs"))?;
+    assert!(src.contains("pub fn sized<T: Copy>(t: T) -> T {"), "{src}");
+    assert!(
+        src.contains("pub fn dup<T: Clone + Clone>(t: &T) -> T {"),
+        "{src}"
+    );
+    Ok(())
+}
+
+#[test]
+fn fix_has_no_other_format_and_dry_run_needs_it() -> TestResult {
+    Command::cargo_bin("trait-winnower")?
+        .args(["--format", "json", "check", "--fix"])
+        .assert()
+        .failure()
+        .stderr(contains("--fix prints a summary of its edits"));
+    Command::cargo_bin("trait-winnower")?
+        .args(["check", "--dry-run"])
+        .assert()
+        .failure()
+        .stderr(contains("--fix"));
+    Ok(())
+}
+
+#[test]
+fn fix_removes_repeated_where_predicates_whole() -> TestResult {
+    let tmp = tempfile::tempdir()?;
+    write_crate(tmp.path())?;
+    std::fs::write(
+        tmp.path().join("src/lib.rs"),
+        "\
+pub fn twice<T, U>(t: &T, u: U) -> T
+where
+    T: Clone + Send,
+    U: Send,
+    T: Send + Clone,
+{
+    drop(u);
+    t.clone()
+}
+
+pub fn covered<T>(t: &T) -> T
+where
+    T: Clone + Send,
+    T: Clone,
+{
+    t.clone()
+}
+",
+    )?;
+
+    let fixed = Winnower::builder().target(tmp.path()).build()?.fix()?;
+    assert_eq!(
+        fixed.fixed.into_iter().collect::<Vec<_>>(),
+        [(Rule::DuplicatePredicate, 2), (Rule::DuplicateBound, 1)]
+    );
+    let src = std::fs::read_to_string(tmp.path().join("src/lib.rs"))?;
+    assert!(
+        src.contains("where\n    T: Clone + Send,\n    U: Send\n{"),
+        "{src}"
+    );
+    assert!(src.contains("where\n    T: Clone + Send\n{"), "{src}");
+    assert!(!tmp.path().join("cargo.log").exists());
+    Ok(())
+}
+
+#[test]
+fn fix_removes_bounds_implied_through_supertraits_of_other_files() -> TestResult {
+    let tmp = tempfile::tempdir()?;
+    write_crate(tmp.path())?;
+    std::fs::write(
+        tmp.path().join("src/lib.rs"),
+        "pub mod shapes;\npub mod uses;\n",
+    )?;
+    std::fs::write(
+        tmp.path().join("src/shapes.rs"),
+        "pub trait Named: std::fmt::Debug {}\n\npub trait Shape: Named {}\n",
+    )?;
+    let uses = "\
+use crate::shapes::Shape;
+use std::fmt::Debug;
+
+pub fn show<T: Shape + Debug>(t: &T) -> String {
+    format!(\"{t:?}\")
+}
+";
+    std::fs::write(tmp.path().join("src/uses.rs"), uses)?;
+
+    let fixed = Winnower::builder().target(tmp.path()).build()?.fix()?;
+    assert_eq!(
+        fixed.fixed.into_iter().collect::<Vec<_>>(),
+        [(Rule::ImpliedBySupertrait, 1)]
+    );
+    assert_eq!(
+        std::fs::read_to_string(tmp.path().join("src/uses.rs"))?,
+        uses.replace("T: Shape + Debug", "T: Shape")
+    );
+    assert!(!tmp.path().join("cargo.log").exists());
+    Ok(())
+}
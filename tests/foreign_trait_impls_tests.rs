@@ -0,0 +1,175 @@
+// tests/foreign_trait_impls_tests.rs
+//! Impls of traits the workspace does not define: `foreign_trait_impls` marks their bounds
+//! (`warn`, the default), leaves them alone (`skip`) or treats them like any other (`allow`).
+#![cfg(feature = "discover")]
+
+mod common;
+
+use common::TestResult;
+use std::path::Path;
+use trait_winnower::config::{Config, ForeignTraitImpls};
+use trait_winnower::report::{RemovalStatus, Report};
+use trait_winnower::winnower::Winnower;
+
+const LIB: &str = "\
+use std::fmt::{self, Debug, Display};
+
+mod shapes;
+pub use shapes::Shape;
+
+pub struct Wrapper<T>(pub T);
+
+impl<T: Display + Clone> fmt::Display for Wrapper<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, \"[{}]\", self.0)
+    }
+}
+
+impl<T: Debug + Default> Shape for Wrapper<T> {
+    fn sides(&self) -> usize {
+        4
+    }
+}
+";
+
+const SHAPES: &str = "\
+pub trait Shape {
+    fn sides(&self) -> usize;
+}
+";
+
+fn write_crate(root: &Path, policy: Option<ForeignTraitImpls>) -> TestResult {
+    common::write_crate(
+        root,
+        "foreign",
+        "",
+        &[("src/lib.rs", LIB), ("src/shapes.rs", SHAPES)],
+    )?;
+    if let Some(policy) = policy {
+        let cfg = Config {
+            foreign_trait_impls: policy,
+            ..Config::default()
+        };
+        std::fs::write(root.join(".trait-winnower.toml"), toml::to_string(&cfg)?)?;
+    }
+    Ok(())
+}
+
+/// `(bound, status, foreign trait)` of every removal of `lib.rs`, sorted.
+fn outcomes(report: &Report) -> Vec<(String, RemovalStatus, Option<String>)> {
+    let mut out: Vec<_> = report
+        .files
+        .iter()
+        .filter(|f| f.path.ends_with("lib.rs"))
+        .flat_map(|f| &f.removals)
+        .map(|r| {
+            (
+                r.finding.bound.clone(),
+                r.status,
+                r.finding.foreign_trait.clone(),
+            )
+        })
+        .collect();
+    out.sort_by(|a, b| a.0.cmp(&b.0));
+    out.dedup_by(|a, b| a.0 == b.0);
+    out
+}
+
+#[test]
+fn check_marks_findings_of_foreign_trait_impls() -> TestResult {
+    let tmp = tempfile::tempdir()?;
+    write_crate(tmp.path(), None)?;
+    let checked = Winnower::builder().target(tmp.path()).build()?.check()?;
+    let marked: Vec<(&str, Option<&str>)> = checked
+        .report
+        .files
+        .iter()
+        .flat_map(|f| &f.findings)
+        .map(|f| (f.bound.as_str(), f.foreign_trait.as_deref()))
+        .collect();
+    assert_eq!(
+        marked,
+        [
+            ("Display", Some("fmt :: Display")),
+            ("Clone", Some("fmt :: Display")),
+            ("Debug", None),
+            ("Default", None),
+        ]
+    );
+
+    let tmp = tempfile::tempdir()?;
+    write_crate(tmp.path(), Some(ForeignTraitImpls::Allow))?;
+    let checked = Winnower::builder().target(tmp.path()).build()?.check()?;
+    assert!(
+        checked
+            .report
+            .files
+            .iter()
+            .flat_map(|f| &f.findings)
+            .all(|f| f.foreign_trait.is_none())
+    );
+    Ok(())
+}
+
+#[test]
+fn warn_prunes_and_marks_foreign_trait_impls() -> TestResult {
+    let tmp = tempfile::tempdir()?;
+    write_crate(tmp.path(), None)?;
+    let pruned = Winnower::builder()
+        .target(tmp.path())
+        .brute_force(true)
+        .build()?
+        .prune()?;
+    let display = Some("fmt :: Display".to_owned());
+    let want =
+        |bound: &str, status, foreign: &Option<String>| (bound.to_owned(), status, foreign.clone());
+    assert_eq!(
+        outcomes(&pruned.report),
+        [
+            want("Clone", RemovalStatus::Removed, &display),
+            want("Debug", RemovalStatus::Removed, &None),
+            want("Default", RemovalStatus::Removed, &None),
+            want("Display", RemovalStatus::Retained, &display),
+        ]
+    );
+    let tags = pruned
+        .report
+        .files
+        .iter()
+        .flat_map(|f| &f.removals)
+        .find(|r| r.finding.bound == "Clone")
+        .map(|r| r.finding.tags());
+    assert_eq!(tags, Some(vec!["foreign trait"]));
+    Ok(())
+}
+
+#[test]
+fn skip_leaves_foreign_trait_impls_alone() -> TestResult {
+    let tmp = tempfile::tempdir()?;
+    write_crate(tmp.path(), Some(ForeignTraitImpls::Skip))?;
+    let pruned = Winnower::builder()
+        .target(tmp.path())
+        .brute_force(true)
+        .build()?
+        .prune()?;
+    let display = Some("fmt :: Display".to_owned());
+    let want =
+        |bound: &str, status, foreign: &Option<String>| (bound.to_owned(), status, foreign.clone());
+    assert_eq!(
+        outcomes(&pruned.report),
+        [
+            want("Clone", RemovalStatus::Skipped, &display),
+            want("Debug", RemovalStatus::Removed, &None),
+            want("Default", RemovalStatus::Removed, &None),
+            want("Display", RemovalStatus::Skipped, &display),
+        ]
+    );
+    let src = std::fs::read_to_string(tmp.path().join("src/lib.rs"))?;
+    for line in [
+        "impl<T: Display + Clone> fmt::Display for Wrapper<T> {",
+        "impl<T> Shape for Wrapper<T> {",
+    ] {
+        assert!(src.contains(line), "{line}\n{src}");
+    }
+    Ok(())
+}
@@ -0,0 +1,162 @@
+// tests/formats_tests.rs
+//! Saved plans and JSON reports round-trip, and newer versions fail with a clear error.
+#![cfg(feature = "discover")]
+
+mod common;
+
+use common::TestResult;
+use std::path::Path;
+use trait_winnower::dynamic_analysis::validate::AlwaysOk;
+use trait_winnower::error::WinnowerError;
+use trait_winnower::formats::{PLAN_VERSION, REPORT_VERSION, ReportDoc};
+use trait_winnower::plan::Plan;
+use trait_winnower::report::json::Json;
+use trait_winnower::winnower::Winnower;
+
+const SRC: &str = "pub fn f<T: Clone + Send>(t: T) -> T {\n    t\n}\n";
+
+fn plan_for(dir: &Path) -> Result<Plan, WinnowerError> {
+    let checked = Winnower::builder().target(dir).build()?.check()?;
+    Ok(Plan::from_report(&checked.report))
+}
+
+fn assert_newer_rejected(err: WinnowerError, artifact: &str) {
+    match &err {
+        WinnowerError::UnsupportedVersion {
+            artifact: a,
+            found,
+            supported,
+            ..
+        } => {
+            assert_eq!(*a, artifact);
+            assert_eq!(*found, 999);
+            assert_eq!(*supported, 1);
+        }
+        other => panic!("expected UnsupportedVersion, got {other:?}"),
+    }
+    let msg = err.to_string();
+    assert!(msg.contains("schema_version 999"), "{msg}");
+    assert!(msg.contains("upgrade"), "{msg}");
+    assert_eq!(err.exit_code(), 2);
+}
+
+#[test]
+fn plan_round_trips_with_its_version() -> TestResult {
+    let tmp = tempfile::tempdir()?;
+    common::write_crate(tmp.path(), "x", "", &[("src/lib.rs", SRC)])?;
+    let plan = plan_for(tmp.path())?;
+    let path = tmp.path().join("plan.toml");
+
+    plan.save(&path)?;
+
+    let text = std::fs::read_to_string(&path)?;
+    assert!(
+        text.starts_with(&format!("schema_version = {PLAN_VERSION}\n")),
+        "{text}"
+    );
+    assert_eq!(Plan::load(&path)?, plan);
+    Ok(())
+}
+
+#[test]
+fn unversioned_plan_reads_as_version_one() -> TestResult {
+    let tmp = tempfile::tempdir()?;
+    common::write_crate(tmp.path(), "x", "", &[("src/lib.rs", SRC)])?;
+    let plan = plan_for(tmp.path())?;
+    let path = tmp.path().join("plan.toml");
+    std::fs::write(&path, toml::to_string(&plan)?)?;
+
+    assert_eq!(Plan::load(&path)?, plan);
+    Ok(())
+}
+
+#[test]
+fn newer_plan_is_rejected() -> TestResult {
+    let tmp = tempfile::tempdir()?;
+    let path = tmp.path().join("plan.toml");
+    // A future layout: no `entries`, fields this build has never seen.
+    std::fs::write(&path, "schema_version = 999\n\n[[removals]]\nid = 7\n")?;
+
+    assert_newer_rejected(Plan::load(&path).expect_err("newer plan"), "plan");
+    Ok(())
+}
+
+#[test]
+fn malformed_plan_is_a_format_error() -> TestResult {
+    let tmp = tempfile::tempdir()?;
+    let path = tmp.path().join("plan.toml");
+    std::fs::write(&path, "schema_version = 1\nentries = 3\n")?;
+
+    let err = Plan::load(&path).expect_err("malformed plan");
+    assert!(
+        matches!(
+            err,
+            WinnowerError::Format {
+                artifact: "plan",
+                ..
+            }
+        ),
+        "{err:?}"
+    );
+    Ok(())
+}
+
+#[test]
+fn json_report_round_trips() -> TestResult {
+    let tmp = tempfile::tempdir()?;
+    common::write_crate(tmp.path(), "x", "", &[("src/lib.rs", SRC)])?;
+    let pruned = Winnower::builder()
+        .target(tmp.path())
+        .brute_force(true)
+        .validator(AlwaysOk)
+        .build()?
+        .prune()?;
+    let mut out = Vec::new();
+    Json::write(&pruned.report, "prune", &mut out)?;
+
+    let doc = ReportDoc::from_json(Path::new("report.json"), std::str::from_utf8(&out)?)?;
+
+    assert_eq!(doc.schema_version, REPORT_VERSION);
+    assert_eq!(doc.command, "prune");
+    assert_eq!(doc.metrics.files, pruned.report.files.len());
+    assert_eq!(doc.metrics.findings, pruned.report.total_findings());
+    assert_eq!(doc.metrics.checks, pruned.report.total_checks());
+    for (file, report) in doc.files.iter().zip(&pruned.report.files) {
+        assert_eq!(file.findings.len(), report.findings.len());
+        for (got, want) in file.findings.iter().zip(&report.findings) {
+            assert_eq!(
+                (got.bound.as_str(), got.line, got.column),
+                (want.bound.as_str(), want.line, want.column)
+            );
+        }
+        let statuses: Vec<String> = report
+            .removals
+            .iter()
+            .map(|r| r.status.to_string())
+            .collect();
+        let got: Vec<&str> = file.removals.iter().map(|r| r.status.as_str()).collect();
+        assert_eq!(got, statuses);
+    }
+    assert!(doc.files.iter().any(|f| !f.removals.is_empty()));
+    Ok(())
+}
+
+#[test]
+fn unversioned_json_report_reads_as_version_one() -> TestResult {
+    let text = r#"{"command": "check", "metrics": {"wall_ms": 1.5, "files": 0, "findings": 0,
+        "checks": 0, "check_ms": 0}, "files": []}"#;
+
+    let doc = ReportDoc::from_json(Path::new("report.json"), text)?;
+
+    assert_eq!(doc.schema_version, 1);
+    assert_eq!(doc.command, "check");
+    Ok(())
+}
+
+#[test]
+fn newer_json_report_is_rejected() {
+    let text = r#"{"schema_version": 999, "runs": [{"id": 1}]}"#;
+
+    let err = ReportDoc::from_json(Path::new("report.json"), text).expect_err("newer report");
+    assert_newer_rejected(err, "JSON report");
+}
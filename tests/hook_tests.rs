@@ -0,0 +1,163 @@
+// tests/hook_tests.rs
+//! `hook install` writes a git hook that stops commits adding unnecessary bounds, and
+//! leaves other people's hooks alone.
+#![cfg(feature = "cli")]
+
+mod common;
+
+use assert_cmd::Command;
+use common::TestResult;
+use predicates::str::contains;
+use std::path::Path;
+
+/// `old.rs` holds a bound a static rule flags, committed before the hook.
+const OLD: &str = "pub fn f<T: Clone>(t: T) -> T {\n    t\n}\n";
+const NEW: &str = "pub fn g<T: Clone>(t: T) -> T {\n    t\n}\n";
+
+fn git(root: &Path, args: &[&str]) -> std::io::Result<std::process::Output> {
+    std::process::Command::new("git")
+        .args(["-c", "user.name=t", "-c", "user.email=t@example.com"])
+        .args(args)
+        .current_dir(root)
+        .env(
+            "TRAIT_WINNOWER",
+            assert_cmd::cargo::cargo_bin("trait-winnower"),
+        )
+        .output()
+}
+
+fn write_repo(root: &Path) -> TestResult {
+    std::fs::write(
+        root.join("Cargo.toml"),
+        "[package]\nname=\"x\"\nversion=\"0.1.0\"\nedition=\"2021\"\n\n[workspace]\n",
+    )?;
+    std::fs::create_dir_all(root.join("src"))?;
+    std::fs::write(root.join("src/lib.rs"), "pub mod old;\n")?;
+    std::fs::write(root.join("src/old.rs"), OLD)?;
+    std::fs::write(root.join(".gitignore"), "target/\n")?;
+    git(root, &["init", "-q"])?;
+    git(root, &["add", "-A"])?;
+    assert!(git(root, &["commit", "-qm", "init"])?.status.success());
+    Ok(())
+}
+
+fn winnower() -> Result<Command, Box<dyn std::error::Error>> {
+    Ok(Command::cargo_bin("trait-winnower")?)
+}
+
+#[test]
+fn the_hook_blocks_a_commit_adding_an_unused_bound() -> TestResult {
+    let tmp = tempfile::tempdir()?;
+    let root = tmp.path();
+    write_repo(root)?;
+    winnower()?
+        .args(["hook", "install"])
+        .arg(root)
+        .assert()
+        .success()
+        .stdout(contains("installed pre-commit hook"));
+    let hook = root.join(".git/hooks/pre-commit");
+    let script = std::fs::read_to_string(&hook)?;
+    assert!(script.starts_with("#!/bin/sh\n"));
+    assert!(script.contains("check --since \"$since\" --fail-on-findings '.'"));
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::PermissionsExt;
+        assert_ne!(std::fs::metadata(&hook)?.permissions().mode() & 0o111, 0);
+    }
+
+    // Nothing changed: the flagged bound committed earlier is not this commit's business.
+    let run = std::process::Command::new("sh")
+        .arg(&hook)
+        .current_dir(root)
+        .env(
+            "TRAIT_WINNOWER",
+            assert_cmd::cargo::cargo_bin("trait-winnower"),
+        )
+        .output()?;
+    assert!(
+        run.status.success(),
+        "{}",
+        String::from_utf8_lossy(&run.stderr)
+    );
+
+    std::fs::write(root.join("src/lib.rs"), "pub mod new;\npub mod old;\n")?;
+    std::fs::write(root.join("src/new.rs"), NEW)?;
+    git(root, &["add", "-A"])?;
+    let commit = git(root, &["commit", "-qm", "add g"])?;
+    assert!(!commit.status.success());
+    // Git hands hooks' stdout to stderr.
+    let output = String::from_utf8_lossy(&commit.stderr);
+    assert!(output.contains("new.rs"), "{output}");
+    assert!(!output.contains("old.rs"), "{output}");
+    assert!(output.contains("flagged as likely unnecessary"), "{output}");
+    Ok(())
+}
+
+#[test]
+fn other_hooks_are_kept_unless_forced() -> TestResult {
+    let tmp = tempfile::tempdir()?;
+    let root = tmp.path();
+    write_repo(root)?;
+    let hook = root.join(".git/hooks/pre-push");
+    std::fs::write(&hook, "#!/bin/sh\nexit 0\n")?;
+
+    winnower()?
+        .args(["hook", "install", "--stage", "pre-push"])
+        .arg(root)
+        .assert()
+        .code(2)
+        .stderr(contains("--force"));
+    winnower()?
+        .args(["hook", "uninstall", "--stage", "pre-push"])
+        .arg(root)
+        .assert()
+        .code(2);
+    assert_eq!(std::fs::read_to_string(&hook)?, "#!/bin/sh\nexit 0\n");
+
+    winnower()?
+        .args([
+            "hook", "install", "--stage", "pre-push", "--mode", "verify", "--force",
+        ])
+        .arg(root)
+        .assert()
+        .success();
+    assert!(std::fs::read_to_string(&hook)?.contains("prune -b --since"));
+    // Reinstalling over our own hook needs no force.
+    winnower()?
+        .args(["hook", "install", "--stage", "pre-push"])
+        .arg(root)
+        .assert()
+        .success();
+    winnower()?
+        .args(["hook", "uninstall", "--stage", "pre-push"])
+        .arg(root)
+        .assert()
+        .success()
+        .stdout(contains("removed"));
+    assert!(!hook.exists());
+    winnower()?
+        .args(["hook", "uninstall", "--stage", "pre-push"])
+        .arg(root)
+        .assert()
+        .success()
+        .stdout(contains("no pre-push hook installed"));
+    Ok(())
+}
+
+#[test]
+fn the_pre_commit_framework_gets_a_config_entry() -> TestResult {
+    let tmp = tempfile::tempdir()?;
+    let root = tmp.path();
+    write_repo(root)?;
+    winnower()?
+        .args(["hook", "install", "--framework", "pre-commit"])
+        .arg(root)
+        .assert()
+        .success()
+        .stdout(contains("- repo: local"))
+        .stdout(contains("entry: sh"))
+        .stdout(contains("stages: [pre-commit]"));
+    assert!(!root.join(".git/hooks/pre-commit").exists());
+    Ok(())
+}
@@ -0,0 +1,85 @@
+// tests/ignore_files_tests.rs
+//! `check` leaves out what `.gitignore` ignores, names the rule at `-v`, and finds it with
+//! `--no-ignore` or `respect_gitignore = false`.
+#![cfg(feature = "cli")]
+
+mod common;
+
+use assert_cmd::Command;
+use common::TestResult;
+use predicates::str::contains;
+use std::path::Path;
+use trait_winnower::config::Config;
+
+fn write_crate(root: &Path) -> TestResult {
+    common::write_crate(
+        root,
+        "ign",
+        "",
+        &[
+            (".gitignore", "/target\nsrc/fork.rs\n"),
+            ("src/lib.rs", "pub mod fork;\n"),
+            (
+                "src/fork.rs",
+                "pub fn fork<T: Clone>(t: T) -> T {\n    t\n}\n",
+            ),
+        ],
+    )?;
+    std::fs::create_dir_all(root.join(".git"))?;
+    Ok(())
+}
+
+/// The items `check` finds in `root` with `args`.
+fn checked_items(root: &Path, args: &[&str]) -> Result<Vec<String>, Box<dyn std::error::Error>> {
+    let out = Command::cargo_bin("trait-winnower")?
+        .args(["check", "--format", "json"])
+        .args(args)
+        .arg(root)
+        .output()?;
+    assert!(
+        out.status.success(),
+        "{}",
+        String::from_utf8_lossy(&out.stderr)
+    );
+    let json: serde_json::Value = serde_json::from_slice(&out.stdout)?;
+    Ok(json["files"]
+        .as_array()
+        .into_iter()
+        .flatten()
+        .flat_map(|f| f["findings"].as_array().into_iter().flatten())
+        .filter_map(|f| f["item"].as_str().map(str::to_owned))
+        .collect())
+}
+
+#[test]
+fn gitignored_files_are_left_out_and_their_rule_named_at_v() -> TestResult {
+    let tmp = tempfile::tempdir()?;
+    write_crate(tmp.path())?;
+    assert!(checked_items(tmp.path(), &[])?.is_empty());
+    Command::cargo_bin("trait-winnower")?
+        .args(["check", "-v", "1"])
+        .arg(tmp.path())
+        .assert()
+        .success()
+        .stderr(contains("ignored by `src/fork.rs` in "))
+        .stderr(contains(".gitignore"));
+    Ok(())
+}
+
+#[test]
+fn no_ignore_and_the_config_find_gitignored_files() -> TestResult {
+    let tmp = tempfile::tempdir()?;
+    write_crate(tmp.path())?;
+    assert_eq!(checked_items(tmp.path(), &["--no-ignore"])?, ["fn fork"]);
+
+    let cfg = Config {
+        respect_gitignore: false,
+        ..Config::default()
+    };
+    std::fs::write(
+        tmp.path().join(".trait-winnower.toml"),
+        toml::to_string(&cfg)?,
+    )?;
+    assert_eq!(checked_items(tmp.path(), &[])?, ["fn fork"]);
+    Ok(())
+}
@@ -0,0 +1,78 @@
+// tests/impact_tests.rs
+//! `impact` times builds of the target before and after pruning a copy, reports them in
+//! the JSON report, and leaves the target alone.
+#![cfg(feature = "cli")]
+
+mod common;
+
+use assert_cmd::Command;
+use common::TestResult;
+use std::path::Path;
+use trait_winnower::formats::ReportDoc;
+use trait_winnower::impact::ImpactOptions;
+use trait_winnower::observer::NoopObserver;
+use trait_winnower::winnower::Winnower;
+
+const SRC: &str = "pub fn f<T: Clone + Default>(t: T) -> T {\n    t\n}\n";
+
+#[test]
+fn impact_reports_times_on_both_sides_and_leaves_the_tree_alone() -> TestResult {
+    let tmp = tempfile::tempdir()?;
+    common::write_crate(tmp.path(), "m", "", &[("src/lib.rs", SRC)])?;
+
+    let out = Command::cargo_bin("trait-winnower")?
+        .args([
+            "-q",
+            "--format",
+            "json",
+            "impact",
+            "--iterations",
+            "2",
+            "--build",
+        ])
+        .arg(tmp.path())
+        .output()?;
+    assert!(
+        out.status.success(),
+        "{}",
+        String::from_utf8_lossy(&out.stderr)
+    );
+    let doc = ReportDoc::from_json(Path::new("stdout"), &String::from_utf8(out.stdout)?)?;
+    assert_eq!(doc.command, "impact");
+    let impact = doc.impact.ok_or("no impact in the report")?;
+    assert_eq!(impact.iterations, 2);
+    let build = impact.build.ok_or("no build timings")?;
+    for side in [
+        &impact.check.before,
+        &impact.check.after,
+        &build.before,
+        &build.after,
+    ] {
+        assert_eq!(side.runs_ms.len(), 2, "{side:?}");
+        assert!(side.median_ms > 0.0, "{side:?}");
+    }
+    let removed = doc
+        .files
+        .iter()
+        .flat_map(|f| &f.removals)
+        .filter(|r| r.status == "removed")
+        .count();
+    assert!(removed > 0, "the copy was not pruned");
+
+    assert_eq!(std::fs::read_to_string(tmp.path().join("src/lib.rs"))?, SRC);
+    assert!(!tmp.path().join("target").exists());
+    Ok(())
+}
+
+#[test]
+fn impact_needs_brute_force() -> TestResult {
+    let tmp = tempfile::tempdir()?;
+    common::write_crate(tmp.path(), "m", "", &[("src/lib.rs", SRC)])?;
+    let winnower = Winnower::builder().target(tmp.path()).build()?;
+    assert!(
+        winnower
+            .impact(&ImpactOptions::default(), &mut NoopObserver)
+            .is_err()
+    );
+    Ok(())
+}
@@ -0,0 +1,101 @@
+// tests/impl_trait_arg_tests.rs
+//! Bounds of argument-position `impl Trait` types are pruned like those of type
+//! parameters, down to one trait bound per argument.
+#![cfg(feature = "discover")]
+
+mod common;
+
+use common::TestResult;
+use trait_winnower::report::{RemovalStatus, SiteKind};
+use trait_winnower::winnower::Winnower;
+
+const SRC: &str = "\
+pub fn show(x: impl std::fmt::Debug + Clone) -> String {
+    format!(\"{x:?}\")
+}
+
+pub fn id(x: impl Clone) -> impl Clone {
+    x
+}
+
+pub struct S;
+
+impl S {
+    pub fn twice(&self, v: impl Clone + Default + Send) -> (impl Clone, impl Clone) {
+        (v.clone(), v)
+    }
+}
+
+pub trait Log {
+    fn log(&self, m: impl AsRef<str> + Copy) {
+        let _ = m.as_ref();
+    }
+}
+";
+
+#[test]
+fn impl_trait_argument_bounds_are_pruned_down_to_the_needed_ones() -> TestResult {
+    let tmp = tempfile::tempdir()?;
+    common::write_crate(tmp.path(), "apit", "", &[("src/lib.rs", SRC)])?;
+    let pruned = Winnower::builder()
+        .target(tmp.path())
+        .brute_force(true)
+        .build()?
+        .prune()?;
+
+    let mut outcomes: Vec<(String, String, RemovalStatus)> = pruned
+        .report
+        .files
+        .iter()
+        .flat_map(|f| &f.removals)
+        .inspect(|r| assert_eq!(r.finding.candidate.site.kind(), SiteKind::ImplTraitArg))
+        // Later rounds over an item skip what earlier ones decided.
+        .filter(|r| r.status != RemovalStatus::Skipped)
+        .map(|r| {
+            (
+                r.finding.item.clone(),
+                r.finding.candidate.declaration(),
+                r.status,
+            )
+        })
+        .collect();
+    outcomes.sort_by(|a, b| (&a.0, &a.1).cmp(&(&b.0, &b.1)));
+    outcomes.dedup();
+    let want = |item: &str, decl: &str, status| (item.to_owned(), decl.to_owned(), status);
+    assert_eq!(
+        outcomes,
+        [
+            want("S::twice", "v: impl Clone", RemovalStatus::Retained),
+            want("S::twice", "v: impl Default", RemovalStatus::Removed),
+            want("S::twice", "v: impl Send", RemovalStatus::Removed),
+            want("fn show", "x: impl Clone", RemovalStatus::Removed),
+            want(
+                "fn show",
+                "x: impl std :: fmt :: Debug",
+                RemovalStatus::Retained
+            ),
+            want(
+                "trait Log::log",
+                "m: impl AsRef < str >",
+                RemovalStatus::Retained
+            ),
+            want("trait Log::log", "m: impl Copy", RemovalStatus::Removed),
+        ]
+    );
+
+    let src = std::fs::read_to_string(tmp.path().join("src/lib.rs"))?;
+    assert!(
+        src.contains("pub fn show(x: impl std::fmt::Debug) -> String"),
+        "{src}"
+    );
+    assert!(
+        src.contains("pub fn id(x: impl Clone) -> impl Clone"),
+        "{src}"
+    );
+    assert!(
+        src.contains("pub fn twice(&self, v: impl Clone) -> (impl Clone, impl Clone)"),
+        "{src}"
+    );
+    assert!(src.contains("fn log(&self, m: impl AsRef<str>)"), "{src}");
+    Ok(())
+}
@@ -0,0 +1,101 @@
+// tests/incremental_tests.rs
+//! `prune --incremental`: files unchanged since the last run are skipped.
+#![cfg(feature = "cli")]
+
+mod common;
+
+use common::TestResult;
+use std::path::Path;
+use trait_winnower::dynamic_analysis::incremental::{CacheStats, Incremental, RunCache};
+use trait_winnower::winnower::{PruneReport, Winnower};
+
+const LIB: &str = "pub mod b;\n\npub fn f<T: Clone>(t: T) -> T {\n    t\n}\n";
+const B: &str = "pub fn g<T: Clone>(t: &T) -> T {\n    t.clone()\n}\n";
+
+fn prune(dir: &Path, mode: Option<Incremental>) -> Result<PruneReport, Box<dyn std::error::Error>> {
+    let mut builder = Winnower::builder().target(dir).brute_force(true);
+    if let Some(mode) = mode {
+        builder = builder.incremental(mode);
+    }
+    Ok(builder.build()?.prune()?)
+}
+
+#[test]
+fn an_unchanged_crate_is_skipped_entirely() -> TestResult {
+    let tmp = tempfile::tempdir()?;
+    common::write_crate(tmp.path(), "x", "", &[("src/lib.rs", LIB), ("src/b.rs", B)])?;
+    let first = prune(tmp.path(), None)?;
+    assert_eq!(first.modified.len(), 1);
+    assert!(first.cache.is_none());
+
+    let cache = RunCache::load(tmp.path());
+    let b = &cache.files[Path::new("src/b.rs")];
+    assert_eq!(b.retained, ["fn g: <T: Clone>"]);
+    assert_eq!((b.candidates, b.removed), (1, 0));
+    assert_eq!(cache.files[Path::new("src/lib.rs")].removed, 1);
+
+    let second = prune(tmp.path(), Some(Incremental::Safe))?;
+    assert_eq!(second.cache, Some(CacheStats { hits: 2, misses: 0 }));
+    assert!(second.report.files.is_empty());
+    assert_eq!(second.report.total_checks(), 0);
+    assert_eq!(RunCache::load(tmp.path()), cache);
+    Ok(())
+}
+
+#[test]
+fn a_change_elsewhere_invalidates_safe_but_not_aggressive_runs() -> TestResult {
+    let tmp = tempfile::tempdir()?;
+    common::write_crate(tmp.path(), "x", "", &[("src/lib.rs", LIB), ("src/b.rs", B)])?;
+    prune(tmp.path(), None)?;
+    let lib = tmp.path().join("src/lib.rs");
+    let mut src = std::fs::read_to_string(&lib)?;
+    src.push_str("\npub fn h<U: Copy>(u: U) -> U {\n    u\n}\n");
+    std::fs::write(&lib, &src)?;
+
+    let aggressive = prune(tmp.path(), Some(Incremental::Aggressive))?;
+    assert_eq!(aggressive.cache, Some(CacheStats { hits: 1, misses: 1 }));
+    let paths: Vec<_> = aggressive.report.files.iter().map(|f| &f.path).collect();
+    assert_eq!(paths, [Path::new("src/lib.rs")]);
+
+    std::fs::write(&lib, src.replace("U: Copy", "U: Clone"))?;
+    let safe = prune(tmp.path(), Some(Incremental::Safe))?;
+    assert_eq!(safe.cache, Some(CacheStats { hits: 0, misses: 2 }));
+    Ok(())
+}
+
+#[test]
+fn other_settings_reuse_nothing() -> TestResult {
+    let tmp = tempfile::tempdir()?;
+    common::write_crate(tmp.path(), "x", "", &[("src/lib.rs", LIB), ("src/b.rs", B)])?;
+    prune(tmp.path(), None)?;
+    let pruned = Winnower::builder()
+        .target(tmp.path())
+        .brute_force(true)
+        .allow_dyn_compat_changes(true)
+        .incremental(Incremental::Aggressive)
+        .build()?
+        .prune()?;
+    assert_eq!(pruned.cache, Some(CacheStats { hits: 0, misses: 2 }));
+    Ok(())
+}
+
+#[test]
+fn the_cli_reports_hits_and_misses() -> TestResult {
+    use assert_cmd::Command;
+    use predicates::str::contains;
+
+    let tmp = tempfile::tempdir()?;
+    common::write_crate(tmp.path(), "x", "", &[("src/lib.rs", LIB), ("src/b.rs", B)])?;
+    for expected in [
+        "incremental: 0 file(s) unchanged since the last run, 2 pruned",
+        "incremental: 2 file(s) unchanged since the last run, 0 pruned",
+    ] {
+        Command::cargo_bin("trait-winnower")?
+            .args(["prune", "-b", "--incremental=aggressive"])
+            .arg(tmp.path())
+            .assert()
+            .success()
+            .stderr(contains(expected));
+    }
+    Ok(())
+}
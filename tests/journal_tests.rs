@@ -0,0 +1,159 @@
+// tests/journal_tests.rs
+//! The trial journal: a run killed between a write and its check can be undone.
+#![cfg(feature = "cli")]
+
+mod common;
+
+use common::TestResult;
+use std::path::Path;
+use trait_winnower::dynamic_analysis::journal::{Journal, JournaledStore, recover};
+use trait_winnower::dynamic_analysis::store::{FsStore, SourceStore};
+use trait_winnower::dynamic_analysis::validate::AlwaysOk;
+use trait_winnower::error::WinnowerError;
+use trait_winnower::report::RemovalStatus;
+use trait_winnower::winnower::Winnower;
+
+const SRC: &str = "pub fn f<T: Clone>(t: T) -> T {\n    t\n}\n";
+
+/// Write a trial to `src/lib.rs` and stop, as a killed run would.
+fn crash_mid_trial(root: &Path) -> TestResult {
+    let store = JournaledStore::new(&FsStore, Journal::start(root)?);
+    store.write(
+        &root.join("src/lib.rs"),
+        "pub fn f<T>(t: T) -> T {\n    t\n}\n",
+    )?;
+    Ok(())
+}
+
+#[test]
+fn an_unresolved_trial_is_restored_by_recover() -> TestResult {
+    let tmp = tempfile::tempdir()?;
+    common::write_crate(tmp.path(), "x", "", &[("src/lib.rs", SRC)])?;
+    crash_mid_trial(tmp.path())?;
+
+    let pending = Journal::pending(tmp.path())?;
+    assert_eq!(pending.len(), 1);
+    assert_eq!(pending[0].path, Path::new("src/lib.rs"));
+    assert_eq!(pending[0].hash, crc32fast::hash(SRC.as_bytes()));
+
+    let restored = recover(tmp.path())?;
+    assert_eq!(restored, [tmp.path().join("src/lib.rs")]);
+    assert_eq!(std::fs::read_to_string(tmp.path().join("src/lib.rs"))?, SRC);
+    assert!(Journal::pending(tmp.path())?.is_empty());
+    assert!(recover(tmp.path())?.is_empty());
+    Ok(())
+}
+
+#[test]
+fn prune_refuses_to_start_over_an_interrupted_run() -> TestResult {
+    let tmp = tempfile::tempdir()?;
+    common::write_crate(tmp.path(), "x", "", &[("src/lib.rs", SRC)])?;
+    crash_mid_trial(tmp.path())?;
+    let err = Winnower::builder()
+        .target(tmp.path())
+        .validator(AlwaysOk)
+        .brute_force(true)
+        .build()?
+        .prune()
+        .expect_err("a trial is still on disk");
+    let WinnowerError::Interrupted { files, .. } = &err else {
+        panic!("expected Interrupted, got {err:?}");
+    };
+    assert_eq!(files, &[Path::new("src/lib.rs")]);
+    assert!(err.to_string().contains("trait-winnower recover"), "{err}");
+    assert_eq!(err.exit_code(), 2);
+    Ok(())
+}
+
+#[test]
+fn resolved_trials_leave_no_journal() -> TestResult {
+    let tmp = tempfile::tempdir()?;
+    common::write_crate(tmp.path(), "x", "", &[("src/lib.rs", SRC)])?;
+    let pruned = Winnower::builder()
+        .target(tmp.path())
+        .validator(AlwaysOk)
+        .brute_force(true)
+        .build()?
+        .prune()?;
+    assert_eq!(pruned.report.count_removals(RemovalStatus::Removed), 1);
+    assert!(!Journal::dir(tmp.path()).join("journal").exists());
+    assert!(!Journal::dir(tmp.path()).join("stash").exists());
+    Ok(())
+}
+
+#[test]
+fn a_torn_last_line_is_ignored() -> TestResult {
+    let tmp = tempfile::tempdir()?;
+    common::write_crate(tmp.path(), "x", "", &[("src/lib.rs", SRC)])?;
+    crash_mid_trial(tmp.path())?;
+    let journal = Journal::dir(tmp.path()).join("journal");
+    let mut text = std::fs::read_to_string(&journal)?;
+    text.push_str("{\"op\":\"resol");
+    std::fs::write(&journal, text)?;
+    assert_eq!(Journal::pending(tmp.path())?.len(), 1);
+    Ok(())
+}
+
+/// Kills the real binary while `cargo check` validates its first trial, the check after
+/// the one timing the unmodified crate; other cargo commands go to the real cargo.
+#[cfg(unix)]
+#[test]
+fn a_killed_prune_is_recovered_from_the_command_line() -> TestResult {
+    use assert_cmd::Command;
+    use predicates::str::contains;
+    use std::os::unix::fs::PermissionsExt;
+
+    let tmp = tempfile::tempdir()?;
+    common::write_crate(tmp.path(), "x", "", &[("src/lib.rs", SRC)])?;
+    // Trials must go to the real tree for a kill to leave one there.
+    std::fs::write(
+        tmp.path().join(".trait-winnower.toml"),
+        "include = [\"src/**/*.rs\"]\nexclude = []\nworktree = false\n\n[cargo_check]\n",
+    )?;
+    let bin = tempfile::tempdir()?;
+    let cargo = bin.path().join("cargo");
+    let seen = bin.path().join("checked");
+    let real = std::env::var("CARGO").unwrap_or_else(|_| "cargo".into());
+    std::fs::write(
+        &cargo,
+        format!(
+            "#!/bin/sh\nif [ \"$1\" = check ]; then\n    [ -e \"{seen}\" ] && kill -9 $PPID\n    \
+             touch \"{seen}\"\nfi\nexec \"{real}\" \"$@\"\n",
+            seen = seen.display()
+        ),
+    )?;
+    std::fs::set_permissions(&cargo, std::fs::Permissions::from_mode(0o755))?;
+
+    Command::cargo_bin("trait-winnower")?
+        .env("CARGO", &cargo)
+        .args(["prune", "-b"])
+        .arg(tmp.path())
+        .assert()
+        .failure();
+    let lib = tmp.path().join("src/lib.rs");
+    assert_ne!(std::fs::read_to_string(&lib)?, SRC, "the trial is on disk");
+
+    Command::cargo_bin("trait-winnower")?
+        .env("CARGO", &cargo)
+        .args(["prune", "-b"])
+        .arg(tmp.path())
+        .assert()
+        .code(2)
+        .stderr(contains("trait-winnower recover"));
+
+    Command::cargo_bin("trait-winnower")?
+        .arg("recover")
+        .arg(tmp.path())
+        .assert()
+        .success()
+        .stdout(contains("restored 1 file(s)"));
+    assert_eq!(std::fs::read_to_string(&lib)?, SRC);
+
+    Command::cargo_bin("trait-winnower")?
+        .arg("recover")
+        .arg(tmp.path())
+        .assert()
+        .success()
+        .stdout(contains("nothing to recover"));
+    Ok(())
+}
@@ -0,0 +1,148 @@
+// tests/large_file_tests.rs
+//! Very large files: `--max-candidates-per-file` caps the trials of a file and says so, files
+//! without candidates are let go at once, and memory stays bounded. Its own test binary, so
+//! the peak resident set measured is this run's.
+#![cfg(feature = "cli")]
+
+mod common;
+
+use common::TestResult;
+use trait_winnower::dynamic_analysis::validate::AlwaysOk;
+use trait_winnower::report::RemovalStatus;
+use trait_winnower::winnower::Winnower;
+
+/// Peak resident set a prune of the generated file may reach, in kB.
+const PEAK_RSS_BUDGET_KB: u64 = 768 * 1024;
+
+/// `n` functions of eight lines and four bounds each, like a generated module.
+fn generated(n: usize) -> String {
+    (0..n)
+        .map(|i| {
+            format!(
+                "pub fn f{i}<T: Clone + Send, U: Default>(t: T, u: U) -> T\nwhere\n    \
+                 T: Sync,\n{{\n    let _ = u;\n    t\n}}\n\n"
+            )
+        })
+        .collect()
+}
+
+/// The peak resident set of this process in kB, where the platform tells.
+fn peak_rss_kb() -> Option<u64> {
+    let status = std::fs::read_to_string("/proc/self/status").ok()?;
+    let line = status.lines().find(|l| l.starts_with("VmHWM:"))?;
+    line.split_whitespace().nth(1)?.parse().ok()
+}
+
+#[test]
+fn a_generated_file_is_pruned_within_a_memory_budget() -> TestResult {
+    let tmp = tempfile::tempdir()?;
+    common::write_crate(
+        tmp.path(),
+        "large",
+        "",
+        &[(
+            "src/lib.rs",
+            &format!("mod generated;\nmod plain;\n\n{}", generated(1)),
+        )],
+    )?;
+    std::fs::write(tmp.path().join("src/generated.rs"), generated(4_000))?;
+    std::fs::write(
+        tmp.path().join("src/plain.rs"),
+        "pub fn id(n: u8) -> u8 {\n    n\n}\n",
+    )?;
+
+    let pruned = Winnower::builder()
+        .target(tmp.path())
+        .brute_force(true)
+        .validator(AlwaysOk)
+        .max_candidates_per_file(8)
+        .build()?
+        .prune()?;
+    let file = |name: &str| {
+        pruned
+            .report
+            .files
+            .iter()
+            .find(|f| f.path.ends_with(name))
+            .ok_or(format!("no report for {name}"))
+    };
+
+    let generated_rs = file("generated.rs")?;
+    assert_eq!(generated_rs.metrics.truncated, 16_000 - 8);
+    let tried: Vec<_> = generated_rs
+        .removals
+        .iter()
+        .filter(|r| r.status == RemovalStatus::Removed)
+        .map(|r| r.finding.item.as_str())
+        .collect();
+    assert_eq!(tried.len(), 8);
+    assert!(tried.iter().all(|i| ["fn f0", "fn f1"].contains(i)));
+    // The library has fewer candidates than the cap: all are tried.
+    assert_eq!(file("lib.rs")?.metrics.truncated, 0);
+    let lib_removed = file("lib.rs")?
+        .removals
+        .iter()
+        .filter(|r| r.status == RemovalStatus::Removed);
+    assert_eq!(lib_removed.count(), 4);
+    // A file without candidates is reported, and nothing is tried in it.
+    assert!(file("plain.rs")?.removals.is_empty());
+    assert_eq!(pruned.report.truncated().count(), 1);
+
+    match peak_rss_kb() {
+        Some(peak) => assert!(
+            peak < PEAK_RSS_BUDGET_KB,
+            "peak resident set {peak} kB over {PEAK_RSS_BUDGET_KB} kB"
+        ),
+        // Without a probe, finishing is what is checked.
+        None => assert!(!pruned.cancelled),
+    }
+    Ok(())
+}
+
+#[test]
+fn the_cli_reports_files_cut_short() -> TestResult {
+    use assert_cmd::Command;
+    use predicates::str::contains;
+
+    let tmp = tempfile::tempdir()?;
+    let lib = "pub fn f<T: Clone + Send>(t: T) -> T {\n    t\n}\n";
+    common::write_crate(tmp.path(), "large", "", &[("src/lib.rs", lib)])?;
+    // `f` alone has more candidates than allowed: nothing is tried, and cargo never runs.
+    Command::cargo_bin("trait-winnower")?
+        .args(["prune", "-b", "--max-candidates-per-file", "1"])
+        .arg(tmp.path())
+        .assert()
+        .success()
+        .stderr(contains(
+            "src/lib.rs: 2 candidate(s) not tried, over --max-candidates-per-file",
+        ));
+    assert_eq!(std::fs::read_to_string(tmp.path().join("src/lib.rs"))?, lib);
+    Ok(())
+}
+
+#[test]
+fn bounds_filtered_out_do_not_count_against_the_cap() -> TestResult {
+    let tmp = tempfile::tempdir()?;
+    let lib = "pub fn f<T: Clone + Send>(t: T) -> T {\n    t\n}\n\n\
+               pub fn g<T: Clone + Send>(t: T) -> T {\n    t\n}\n";
+    common::write_crate(tmp.path(), "large", "", &[("src/lib.rs", lib)])?;
+    // Four candidates, but only the two `Clone` bounds are tried: a cap of two fits them.
+    let pruned = Winnower::builder()
+        .target(tmp.path())
+        .brute_force(true)
+        .validator(AlwaysOk)
+        .only_traits(["Clone"])
+        .max_candidates_per_file(2)
+        .build()?
+        .prune()?;
+    let lib = &pruned.report.files[0];
+    assert_eq!(lib.metrics.truncated, 0);
+    let removed: Vec<_> = lib
+        .removals
+        .iter()
+        .filter(|r| r.status == RemovalStatus::Removed)
+        .map(|r| r.finding.item.as_str())
+        .collect();
+    assert_eq!(removed, ["fn f", "fn g"]);
+    Ok(())
+}
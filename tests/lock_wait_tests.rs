@@ -0,0 +1,147 @@
+// tests/lock_wait_tests.rs
+//! Checks waiting on a cargo lock another process holds are reported as they start waiting
+//! and stopped after `lock_wait_timeout`; `doctor` reports every preflight check.
+#![cfg(all(unix, feature = "cli"))]
+
+mod common;
+
+use common::TestResult;
+use std::fs::File;
+use std::os::fd::AsRawFd;
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex};
+use trait_winnower::doctor::{Check, Status, doctor};
+use trait_winnower::dynamic_analysis::journal::Journal;
+use trait_winnower::dynamic_analysis::lock_wait::LockNotice;
+use trait_winnower::error::WinnowerError;
+use trait_winnower::winnower::Winnower;
+
+const LIB: &str = "pub fn f<T: Clone>(t: T) -> T {\n    t\n}\n";
+
+/// Hold cargo's build directory lock in the validation target directory of `root`, as
+/// another cargo building there would, until the file is dropped.
+fn hold_build_lock(root: &Path) -> Result<File, Box<dyn std::error::Error>> {
+    let dir = root.join("target/trait-winnower-check/debug");
+    std::fs::create_dir_all(&dir)?;
+    let lock = File::create(dir.join(".cargo-lock"))?;
+    // SAFETY: `flock` only takes a lock on the open descriptor, released when it closes.
+    let taken = unsafe { libc::flock(lock.as_raw_fd(), libc::LOCK_EX | libc::LOCK_NB) };
+    assert_eq!(taken, 0, "{}", std::io::Error::last_os_error());
+    Ok(lock)
+}
+
+#[test]
+fn a_held_build_lock_is_noticed_and_times_out() -> TestResult {
+    let tmp = tempfile::tempdir()?;
+    common::write_crate(tmp.path(), "locked", "", &[("src/lib.rs", LIB)])?;
+    let lock = hold_build_lock(tmp.path())?;
+    let notices: Arc<Mutex<Vec<(PathBuf, String)>>> = Arc::default();
+    let seen = Arc::clone(&notices);
+    let err = Winnower::builder()
+        .target(tmp.path())
+        .brute_force(true)
+        .lock_wait_timeout(1)
+        .on_lock_wait(LockNotice::new(move |dir, what| {
+            if let Ok(mut seen) = seen.lock() {
+                seen.push((dir.to_path_buf(), what.to_owned()));
+            }
+        }))
+        .build()?
+        .prune()
+        .expect_err("the lock is never released");
+    drop(lock);
+
+    let WinnowerError::LockWait { dir, what, waited } = &err else {
+        panic!("expected LockWait, got {err:?}");
+    };
+    assert!(
+        dir.ends_with("target/trait-winnower-check"),
+        "{}",
+        dir.display()
+    );
+    assert!(what.contains("directory"), "{what}");
+    assert!(waited.as_secs() >= 1);
+    assert_eq!(err.exit_code(), 2);
+    assert!(err.to_string().contains("Stop that process"), "{err}");
+    let notices = notices.lock().map_err(|e| e.to_string())?.clone();
+    assert_eq!(notices, [(dir.clone(), what.clone())]);
+    // The trial in flight was put back, with nothing left for `recover`.
+    assert_eq!(std::fs::read_to_string(tmp.path().join("src/lib.rs"))?, LIB);
+    assert!(Journal::pending(tmp.path())?.is_empty());
+    Ok(())
+}
+
+#[test]
+fn doctor_reports_every_check() -> TestResult {
+    let tmp = tempfile::tempdir()?;
+    common::write_crate(tmp.path(), "locked", "", &[("src/lib.rs", LIB)])?;
+    let status = |diagnoses: &[trait_winnower::doctor::Diagnosis], check: Check| {
+        diagnoses
+            .iter()
+            .find(|d| d.check == check)
+            .map(|d| d.result.as_ref().map_err(ToString::to_string).cloned())
+    };
+
+    let diagnoses = doctor(tmp.path())?;
+    assert_eq!(
+        diagnoses.iter().map(|d| d.check).collect::<Vec<_>>(),
+        Check::ALL
+    );
+    assert!(diagnoses.iter().all(|d| d.result.is_ok()), "{diagnoses:?}");
+    assert!(matches!(
+        status(&diagnoses, Check::Clippy),
+        Some(Ok(Status::Skipped(_)))
+    ));
+
+    let lock = hold_build_lock(tmp.path())?;
+    let diagnoses = doctor(tmp.path())?;
+    drop(lock);
+    let Some(Ok(Status::Warning(warning))) = status(&diagnoses, Check::BuildLock) else {
+        panic!("expected a warning, got {diagnoses:?}");
+    };
+    assert!(warning.contains(".cargo-lock is held"), "{warning}");
+
+    // A target directory that cannot be created fails with the path and the io error.
+    std::fs::remove_dir_all(tmp.path().join("target"))?;
+    std::fs::write(tmp.path().join("target"), "")?;
+    let diagnoses = doctor(tmp.path())?;
+    let Some(Err(error)) = status(&diagnoses, Check::TargetDir) else {
+        panic!("expected a failure, got {diagnoses:?}");
+    };
+    assert!(
+        error.contains("creating the cargo target directory")
+            && error.contains("trait-winnower-check"),
+        "{error}"
+    );
+    Ok(())
+}
+
+#[test]
+fn the_cli_runs_doctor() -> TestResult {
+    use assert_cmd::Command;
+    use predicates::str::contains;
+
+    let tmp = tempfile::tempdir()?;
+    common::write_crate(tmp.path(), "locked", "", &[("src/lib.rs", LIB)])?;
+    Command::cargo_bin("trait-winnower")?
+        .arg("doctor")
+        .arg(tmp.path())
+        .assert()
+        .success()
+        .stdout(contains("toolchain: ok: cargo "))
+        .stdout(contains("target directory: ok: "))
+        .stdout(contains("clippy: skipped: validation runs `cargo check`"));
+
+    std::fs::remove_dir_all(tmp.path().join("target"))?;
+    std::fs::write(tmp.path().join("target"), "")?;
+    Command::cargo_bin("trait-winnower")?
+        .arg("doctor")
+        .arg(tmp.path())
+        .assert()
+        .failure()
+        .stdout(contains(
+            "target directory: error: creating the cargo target directory",
+        ))
+        .stderr(contains("1 check(s) failed"));
+    Ok(())
+}
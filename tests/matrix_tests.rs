@@ -0,0 +1,112 @@
+// tests/matrix_tests.rs
+//! The validation matrix: removals must build on every configured target and cfg.
+#![cfg(feature = "discover")]
+
+mod common;
+
+use common::TestResult;
+use std::path::Path;
+use trait_winnower::config::{Config, MatrixEntry, MatrixMode};
+use trait_winnower::error::WinnowerError;
+use trait_winnower::report::RemovalStatus;
+use trait_winnower::winnower::{PruneReport, Winnower};
+
+/// `Clone` is only needed when built with `--cfg special`.
+const SRC: &str = "pub fn f<T: Clone>(t: T) -> T {\n    \
+                   #[cfg(special)]\n    let t = t.clone();\n    t\n}\n";
+
+const MANIFEST: &str = "\n[lints.rust]\nunexpected_cfgs = \"allow\"\n";
+
+fn prune(
+    dir: &Path,
+    matrix: Vec<MatrixEntry>,
+    mode: MatrixMode,
+) -> Result<PruneReport, WinnowerError> {
+    let mut config = Config::default();
+    config.cargo_check.matrix = matrix;
+    config.cargo_check.matrix_mode = mode;
+    Winnower::builder()
+        .target(dir)
+        .config(config)
+        .brute_force(true)
+        .build()?
+        .prune()
+}
+
+fn special() -> Vec<MatrixEntry> {
+    vec![MatrixEntry {
+        target: None,
+        rustflags: Some("--cfg special".into()),
+        features: None,
+    }]
+}
+
+#[test]
+fn host_only_removes_the_cfg_gated_bound() -> TestResult {
+    let tmp = tempfile::tempdir()?;
+    common::write_crate(tmp.path(), "x", MANIFEST, &[("src/lib.rs", SRC)])?;
+    let pruned = prune(tmp.path(), Vec::new(), MatrixMode::PerCandidate)?;
+    assert_eq!(pruned.report.count_removals(RemovalStatus::Removed), 1);
+    Ok(())
+}
+
+#[test]
+fn every_matrix_entry_must_pass() -> TestResult {
+    let tmp = tempfile::tempdir()?;
+    common::write_crate(tmp.path(), "x", MANIFEST, &[("src/lib.rs", SRC)])?;
+    let pruned = prune(tmp.path(), special(), MatrixMode::PerCandidate)?;
+    assert_eq!(pruned.report.count_removals(RemovalStatus::Removed), 0);
+    assert_eq!(pruned.report.count_removals(RemovalStatus::Retained), 1);
+    assert!(pruned.modified.is_empty());
+    assert_eq!(std::fs::read_to_string(tmp.path().join("src/lib.rs"))?, SRC);
+
+    let validation = pruned.report.validation.expect("crate targets record it");
+    assert_eq!(validation.matrix, special());
+    assert_eq!(
+        validation.matrix[0].to_string(),
+        "RUSTFLAGS=\"--cfg special\""
+    );
+    Ok(())
+}
+
+#[test]
+fn final_only_puts_everything_back_on_failure() -> TestResult {
+    let tmp = tempfile::tempdir()?;
+    common::write_crate(tmp.path(), "x", MANIFEST, &[("src/lib.rs", SRC)])?;
+    let pruned = prune(tmp.path(), special(), MatrixMode::FinalOnly)?;
+    assert_eq!(pruned.report.count_removals(RemovalStatus::Removed), 0);
+    assert_eq!(pruned.report.count_removals(RemovalStatus::Retained), 1);
+    let failure = pruned.matrix_failure.expect("the final state fails");
+    assert!(failure.contains("--cfg special"), "{failure}");
+    assert!(pruned.modified.is_empty());
+    assert_eq!(std::fs::read_to_string(tmp.path().join("src/lib.rs"))?, SRC);
+    Ok(())
+}
+
+#[test]
+fn missing_targets_are_refused_upfront() -> TestResult {
+    if std::process::Command::new("rustup")
+        .arg("--version")
+        .output()
+        .is_err()
+    {
+        return Ok(());
+    }
+    let tmp = tempfile::tempdir()?;
+    common::write_crate(tmp.path(), "x", MANIFEST, &[("src/lib.rs", SRC)])?;
+    let matrix = vec![MatrixEntry {
+        target: Some("nosuch-unknown-none".into()),
+        rustflags: None,
+        features: None,
+    }];
+    let err = prune(tmp.path(), matrix, MatrixMode::PerCandidate).expect_err("not installed");
+    let WinnowerError::Config { detail, .. } = &err else {
+        panic!("expected Config, got {err:?}");
+    };
+    assert!(
+        detail.contains("rustup target add nosuch-unknown-none"),
+        "{detail}"
+    );
+    assert_eq!(std::fs::read_to_string(tmp.path().join("src/lib.rs"))?, SRC);
+    Ok(())
+}
@@ -0,0 +1,82 @@
+// tests/memo_tests.rs
+//! A rejected removal is taken to hold for the same bound elsewhere in its impl or trait.
+#![cfg(feature = "cli")]
+
+mod common;
+
+use common::TestResult;
+use std::path::Path;
+use trait_winnower::report::RemovalStatus;
+use trait_winnower::winnower::{PruneReport, Winnower};
+
+/// `one` needs the impl's `T: Clone`; `two` repeats it in a where clause it could drop.
+const SRC: &str = "pub struct S<T>(pub T);\n\n\
+                   impl<T: Clone> S<T> {\n    \
+                   pub fn one(&self) -> T {\n        self.0.clone()\n    }\n\n    \
+                   pub fn two(&self) -> T\n    where\n        T: Clone,\n    {\n        \
+                   self.0.clone()\n    }\n}\n";
+
+fn prune(dir: &Path, assume_consistent: bool) -> Result<PruneReport, Box<dyn std::error::Error>> {
+    Ok(Winnower::builder()
+        .target(dir)
+        .brute_force(true)
+        .assume_consistent(assume_consistent)
+        .build()?
+        .prune()?)
+}
+
+#[test]
+fn a_rejection_in_an_impl_is_reused_for_its_methods() -> TestResult {
+    let tmp = tempfile::tempdir()?;
+    common::write_crate(tmp.path(), "x", "", &[("src/lib.rs", SRC)])?;
+    let pruned = prune(tmp.path(), true)?;
+    let two = pruned.report.files[0]
+        .removals
+        .iter()
+        .find(|r| r.finding.item.ends_with("::two"))
+        .expect("two was considered");
+    assert_eq!(two.status, RemovalStatus::Retained);
+    assert!(two.finding.memoized);
+    assert_eq!(pruned.report.total_checks(), 1);
+    assert_eq!(pruned.report.total_memo_hits(), 1);
+    assert_eq!(std::fs::read_to_string(tmp.path().join("src/lib.rs"))?, SRC);
+    Ok(())
+}
+
+#[test]
+fn without_the_assumption_every_candidate_is_checked() -> TestResult {
+    let tmp = tempfile::tempdir()?;
+    common::write_crate(tmp.path(), "x", "", &[("src/lib.rs", SRC)])?;
+    let pruned = prune(tmp.path(), false)?;
+    assert_eq!(pruned.report.total_checks(), 2);
+    assert_eq!(pruned.report.total_memo_hits(), 0);
+    let src = std::fs::read_to_string(tmp.path().join("src/lib.rs"))?;
+    assert!(!src.contains("where"), "{src}");
+    Ok(())
+}
+
+#[test]
+fn the_cli_reports_memo_hits() -> TestResult {
+    use assert_cmd::Command;
+    use predicates::prelude::PredicateBooleanExt;
+    use predicates::str::contains;
+
+    let tmp = tempfile::tempdir()?;
+    common::write_crate(tmp.path(), "x", "", &[("src/lib.rs", SRC)])?;
+    Command::cargo_bin("trait-winnower")?
+        .args(["prune", "-b", "-v", "2"])
+        .arg(tmp.path())
+        .assert()
+        .success()
+        .stderr(contains("retained (memoized)"))
+        .stderr(contains(
+            "memo: 1 check(s) saved by rejections earlier in the same scope",
+        ));
+    Command::cargo_bin("trait-winnower")?
+        .args(["prune", "-b", "--assume-consistent=false"])
+        .arg(tmp.path())
+        .assert()
+        .success()
+        .stderr(contains("memo:").not());
+    Ok(())
+}
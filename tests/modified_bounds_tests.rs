@@ -0,0 +1,65 @@
+// tests/modified_bounds_tests.rs
+//! Modified bounds, e.g. `?Sized`, are never tried by `prune`: it lists them as skipped and
+//! leaves them as written, while `check` still reports them.
+#![cfg(feature = "discover")]
+
+mod common;
+
+use common::TestResult;
+use trait_winnower::report::RemovalStatus;
+use trait_winnower::winnower::Winnower;
+
+const SRC: &str = "\
+pub fn show<T: ?Sized + std::fmt::Debug + Clone>(t: &T) -> String {
+    format!(\"{t:?}\")
+}
+";
+
+#[test]
+fn modified_bounds_are_skipped_and_kept_verbatim() -> TestResult {
+    let tmp = tempfile::tempdir()?;
+    common::write_crate(tmp.path(), "modified", "", &[("src/lib.rs", SRC)])?;
+    let winnower = Winnower::builder()
+        .target(tmp.path())
+        .brute_force(true)
+        .build()?;
+
+    let checked = winnower.check()?;
+    let sized = checked
+        .report
+        .files
+        .iter()
+        .flat_map(|f| &f.findings)
+        .find(|f| f.bound == "? Sized")
+        .ok_or("no finding for `?Sized`")?;
+    assert!(sized.modified_bound, "{sized:#?}");
+    assert!(sized.caution.is_some(), "{sized:#?}");
+    assert_eq!(sized.tags(), ["modified bound"]);
+
+    let pruned = winnower.prune()?;
+    let mut outcomes: Vec<(String, RemovalStatus)> = pruned
+        .report
+        .files
+        .iter()
+        .flat_map(|f| &f.removals)
+        .map(|r| (r.finding.bound.clone(), r.status))
+        .collect();
+    outcomes.sort_by(|a, b| a.0.cmp(&b.0));
+    outcomes.dedup_by(|a, b| a.0 == b.0);
+    let want = |bound: &str, status| (bound.to_owned(), status);
+    assert_eq!(
+        outcomes,
+        [
+            want("? Sized", RemovalStatus::Skipped),
+            want("Clone", RemovalStatus::Removed),
+            want("std :: fmt :: Debug", RemovalStatus::Retained),
+        ]
+    );
+
+    let src = std::fs::read_to_string(tmp.path().join("src/lib.rs"))?;
+    assert!(
+        src.contains("pub fn show<T: ?Sized + std::fmt::Debug>(t: &T) -> String {"),
+        "{src}"
+    );
+    Ok(())
+}
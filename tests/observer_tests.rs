@@ -0,0 +1,84 @@
+// tests/observer_tests.rs
+//! Observer event sequence tests.
+#![cfg(feature = "discover")]
+
+mod common;
+
+use common::TestResult;
+use std::path::Path;
+use trait_winnower::dynamic_analysis::common::BoundRemovalResult;
+use trait_winnower::observer::Observer;
+use trait_winnower::report::{FileMetrics, Removal};
+use trait_winnower::winnower::Winnower;
+
+/// Records every event as a line of text.
+#[derive(Default)]
+struct Recorder {
+    events: Vec<String>,
+}
+
+impl Observer for Recorder {
+    fn on_file_start(&mut self, path: &Path) {
+        let name = path.file_name().unwrap_or_default().to_string_lossy();
+        self.events.push(format!("start {name}"));
+    }
+
+    fn on_candidate_trial(&mut self, item: &str, bound: &str, index: usize, total: usize) {
+        self.events
+            .push(format!("trial {item}: {bound} {}/{total}", index + 1));
+    }
+
+    fn on_outcome(&mut self, result: &BoundRemovalResult) {
+        let status = Removal::from_result(result).status;
+        self.events
+            .push(format!("outcome {}: {status}", result.finding.bound));
+    }
+
+    fn on_file_done(&mut self, stats: &FileMetrics) {
+        self.events.push(format!("done {} check(s)", stats.checks));
+    }
+}
+
+#[test]
+fn observer_sees_files_trials_and_outcomes_in_order() -> TestResult {
+    let tmp = tempfile::tempdir()?;
+    common::write_crate(
+        tmp.path(),
+        "x",
+        "",
+        &[
+            ("src/lib.rs", "pub mod a;\n"),
+            (
+                "src/a.rs",
+                "pub fn h<T: Clone + Send>(t: &T) -> T {\n    t.clone()\n}\n",
+            ),
+        ],
+    )?;
+
+    let mut recorder = Recorder::default();
+    let report = Winnower::builder()
+        .target(tmp.path())
+        .brute_force(true)
+        .build()?
+        .prune_with(&mut recorder)?;
+
+    assert_eq!(
+        recorder.events,
+        [
+            "start a.rs",
+            "trial fn h: Clone 1/2",
+            "outcome Clone: retained",
+            "trial fn h: Send 2/2",
+            "outcome Send: removed",
+            "trial fn h: Clone 1/2",
+            "outcome Clone: retained",
+            "trial fn h: Send 2/2",
+            "outcome Send: skipped",
+            "done 3 check(s)",
+            "start lib.rs",
+            "done 0 check(s)",
+        ]
+    );
+    assert_eq!(report.modified.len(), 1);
+    Ok(())
+}
@@ -0,0 +1,126 @@
+// tests/opaque_types_tests.rs
+//! Bounds of return-position `impl Trait` and `dyn Trait` types change an item's public
+//! type: `check` flags them and `prune` tries them only with `include_opaque_types`.
+#![cfg(feature = "discover")]
+
+mod common;
+
+use common::TestResult;
+use trait_winnower::report::{RemovalStatus, SiteKind};
+use trait_winnower::winnower::Winnower;
+
+const SRC: &str = "\
+pub fn evens() -> impl Iterator<Item = u32> + Send {
+    (0..10u32).filter(|n| n % 2 == 0)
+}
+
+pub fn total() -> u32 {
+    evens().sum()
+}
+
+pub fn fail() -> Box<dyn std::error::Error + Send> {
+    Box::new(std::fmt::Error)
+}
+";
+
+#[test]
+fn opaque_type_bounds_are_flagged_and_left_alone_by_default() -> TestResult {
+    let tmp = tempfile::tempdir()?;
+    common::write_crate(tmp.path(), "opaque", "", &[("src/lib.rs", SRC)])?;
+    let winnower = Winnower::builder()
+        .target(tmp.path())
+        .brute_force(true)
+        .build()?;
+
+    let checked = winnower.check()?;
+    let findings: Vec<_> = checked
+        .report
+        .files
+        .iter()
+        .flat_map(|f| &f.findings)
+        .collect();
+    assert_eq!(findings.len(), 4, "{findings:#?}");
+    for f in &findings {
+        let kind = f.candidate.site.kind();
+        assert!(
+            matches!(kind, SiteKind::ReturnImplTrait | SiteKind::TraitObject),
+            "{kind:?}"
+        );
+        assert!(f.caution.is_some(), "{f:#?}");
+    }
+
+    let pruned = winnower.prune()?;
+    assert!(
+        pruned.report.files.iter().all(|f| f.removals.is_empty()),
+        "{:#?}",
+        pruned.report
+    );
+    assert_eq!(std::fs::read_to_string(tmp.path().join("src/lib.rs"))?, SRC);
+    Ok(())
+}
+
+#[test]
+fn opt_in_prunes_auto_traits_but_keeps_the_principal_trait() -> TestResult {
+    let tmp = tempfile::tempdir()?;
+    common::write_crate(tmp.path(), "opaque", "", &[("src/lib.rs", SRC)])?;
+    let pruned = Winnower::builder()
+        .target(tmp.path())
+        .brute_force(true)
+        .include_opaque_types(true)
+        .build()?
+        .prune()?;
+
+    let mut outcomes: Vec<(String, String, RemovalStatus)> = pruned
+        .report
+        .files
+        .iter()
+        .flat_map(|f| &f.removals)
+        .map(|r| {
+            (
+                r.finding.item.clone(),
+                r.finding.candidate.declaration(),
+                r.status,
+            )
+        })
+        .collect();
+    outcomes.sort_by(|a, b| (&a.0, &a.1).cmp(&(&b.0, &b.1)));
+    outcomes.dedup_by(|a, b| (&a.0, &a.1) == (&b.0, &b.1));
+    let want = |item: &str, decl: &str, status| (item.to_owned(), decl.to_owned(), status);
+    assert_eq!(
+        outcomes,
+        [
+            want(
+                "fn evens",
+                "Iterator < Item = u32 > in impl Iterator < Item = u32 > + Send",
+                RemovalStatus::Retained
+            ),
+            want(
+                "fn evens",
+                "Send in impl Iterator < Item = u32 > + Send",
+                RemovalStatus::Removed
+            ),
+            want(
+                "fn fail",
+                "Send in dyn std :: error :: Error + Send",
+                RemovalStatus::Removed
+            ),
+            // The principal trait of a trait object is never removed.
+            want(
+                "fn fail",
+                "std :: error :: Error in dyn std :: error :: Error + Send",
+                RemovalStatus::Skipped
+            ),
+        ]
+    );
+
+    let src = std::fs::read_to_string(tmp.path().join("src/lib.rs"))?;
+    assert!(
+        src.contains("pub fn evens() -> impl Iterator<Item = u32> {"),
+        "{src}"
+    );
+    assert!(
+        src.contains("pub fn fail() -> Box<dyn std::error::Error> {"),
+        "{src}"
+    );
+    Ok(())
+}
@@ -0,0 +1,117 @@
+// tests/owned_types_tests.rs
+//! Owned, serializable mirrors of the syn-backed analysis types.
+#![cfg(feature = "discover")]
+
+mod common;
+
+use common::TestResult;
+use std::path::Path;
+use trait_winnower::analysis::{ItemBounds, ItemKind, ItemLocator, ItemVisibility};
+use trait_winnower::dynamic_analysis::common::{
+    BoundCandidate, OwnedBoundCandidate, OwnedBoundSite,
+};
+use trait_winnower::report::{Report, SiteKind};
+use trait_winnower::winnower::{CheckReport, Winnower};
+
+/// TOML needs a table at the top level.
+#[derive(serde::Serialize, serde::Deserialize, PartialEq, Debug)]
+struct Wrap<T> {
+    value: T,
+}
+
+fn round_trip<T>(value: T) -> Result<T, Box<dyn std::error::Error>>
+where
+    T: serde::Serialize + serde::de::DeserializeOwned,
+{
+    let text = toml::to_string(&Wrap { value })?;
+    Ok(toml::from_str::<Wrap<T>>(&text)?.value)
+}
+
+#[test]
+fn candidates_convert_from_collected_sandbox_items() -> TestResult {
+    let path = Path::new("tests/test_files/trait_sandbox/src/b.rs");
+    let source = std::fs::read_to_string(path)?;
+    let file = syn::parse_file(&source)?;
+    let items = ItemBounds::collect_items_in_file(&file)?;
+
+    let method = items
+        .impl_methods()
+        .iter()
+        .find(|m| m.item_key().ident().is_some_and(|i| i == "id"))
+        .expect("Wrapper::id");
+    let locator = ItemLocator::from(method.item_key()).in_file(Path::new("src/b.rs"));
+    assert_eq!(locator.kind, ItemKind::ImplMethod);
+    assert_eq!(locator.name, "Wrapper < T >::id");
+    assert_eq!(locator.module_path, "b");
+    assert_eq!((locator.line, locator.column), (16, 12));
+
+    let owned: Vec<OwnedBoundCandidate> = BoundCandidate::collect_impl_method_candidates(method)
+        .iter()
+        .map(OwnedBoundCandidate::from)
+        .collect();
+    assert_eq!(
+        owned,
+        [OwnedBoundCandidate {
+            site: OwnedBoundSite::WhereClause {
+                ty: "T".into(),
+                pred_index: 0,
+                bound_index: 0,
+            },
+            bound_text: "Ord".into(),
+        }]
+    );
+    assert_eq!(owned[0].site.kind(), SiteKind::WhereClause);
+    assert_eq!(round_trip(owned.clone())?, owned);
+    assert_eq!(round_trip(locator.clone())?, locator);
+    Ok(())
+}
+
+#[test]
+fn locators_carry_visibility_and_attributes() -> TestResult {
+    let file = syn::parse_file(
+        "mod m {\n    /// Docs.\n    #[inline]\n    #[must_use]\n    pub fn f<T: Clone>(t: T) -> T { t }\n}",
+    )?;
+    let items = ItemBounds::collect_items_in_file(&file)?;
+    let locator = ItemLocator::from(items.fns()[0].item_key());
+    assert_eq!(locator.visibility, ItemVisibility::Private);
+    assert_eq!(locator.attrs, ["# [inline]", "# [must_use]"]);
+    assert_eq!(round_trip(locator.clone())?, locator);
+    Ok(())
+}
+
+#[test]
+fn inline_modules_extend_the_module_path() -> TestResult {
+    let file = syn::parse_file("mod outer { mod inner { fn f<T: Clone>() {} } }")?;
+    let items = ItemBounds::collect_items_in_file(&file)?;
+    let locator = ItemLocator::from(items.fns()[0].item_key()).in_file(Path::new("src/a/mod.rs"));
+    assert_eq!(locator.module_path, "a::outer::inner");
+    assert_eq!(locator.name, "f");
+    Ok(())
+}
+
+#[test]
+fn sandbox_check_report_round_trips() -> TestResult {
+    let checked = Winnower::builder()
+        .target("tests/test_files/trait_sandbox")
+        .build()?
+        .check()?;
+    assert!(checked.report.total_findings() > 0);
+    for file in &checked.report.files {
+        for f in &file.findings {
+            assert_eq!(f.locator.file, file.path);
+            assert_eq!(f.candidate.bound_text, f.bound);
+            // Higher-ranked predicates show their `for<'a>` binder as well.
+            let bounded = f
+                .bounded
+                .strip_prefix("for <")
+                .and_then(|rest| rest.split_once("> "))
+                .map_or(f.bounded.as_str(), |(_, ty)| ty);
+            assert_eq!(f.candidate.site.bounded(), bounded);
+        }
+    }
+    let back: CheckReport = round_trip(checked.clone())?;
+    assert_eq!(back.report, checked.report);
+    let report: Report = round_trip(checked.report.clone())?;
+    assert_eq!(report, checked.report);
+    Ok(())
+}
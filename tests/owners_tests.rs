@@ -0,0 +1,178 @@
+// tests/owners_tests.rs
+//! Which packages own a source file, from `cargo metadata` and the module graph of each
+//! target.
+#![cfg(feature = "cli")]
+
+mod common;
+
+use assert_cmd::Command;
+use common::TestResult;
+use predicates::str::contains;
+use std::path::Path;
+use trait_winnower::crate_map::{CrateMap, PackageId};
+
+fn write(root: &Path, files: &[(&str, &str)]) -> std::io::Result<()> {
+    for (path, src) in files {
+        let path = root.join(path);
+        std::fs::create_dir_all(path.parent().unwrap_or(root))?;
+        std::fs::write(path, src)?;
+    }
+    Ok(())
+}
+
+fn id(root: &Path, name: &str, dir: &str) -> std::io::Result<PackageId> {
+    Ok(PackageId {
+        name: name.into(),
+        manifest_dir: root.join(dir).canonicalize()?,
+    })
+}
+
+#[test]
+fn owners_come_from_module_graphs_then_manifest_dirs() -> TestResult {
+    let tmp = tempfile::tempdir()?;
+    let root = tmp.path();
+    write(
+        root,
+        &[
+            (
+                "crates/a/src/lib.rs",
+                "#[path = \"../../../shared/common.rs\"] mod common;\n",
+            ),
+            (
+                "crates/b/src/main.rs",
+                "#[path = \"../../../shared/common.rs\"]\nmod common;\nfn main() {}\n",
+            ),
+            ("crates/a/src/stray.rs", ""),
+            ("crates/a/nested/src/lib.rs", ""),
+            ("crates/a/nested/src/loose.rs", ""),
+            ("shared/common.rs", ""),
+            ("elsewhere.rs", ""),
+        ],
+    )?;
+    let at = |p: &str| root.join(p).to_string_lossy().into_owned();
+    let metadata = serde_json::json!({
+        "workspace_root": at(""),
+        "packages": [
+            { "name": "a", "manifest_path": at("crates/a/Cargo.toml"),
+              "targets": [{ "src_path": at("crates/a/src/lib.rs") }] },
+            { "name": "b", "manifest_path": at("crates/b/Cargo.toml"),
+              "targets": [{ "src_path": at("crates/b/src/main.rs") }] },
+            { "name": "nested", "manifest_path": at("crates/a/nested/Cargo.toml"),
+              "targets": [{ "src_path": at("crates/a/nested/src/lib.rs") }] },
+        ]
+    });
+    let map = CrateMap::from_metadata_json(&metadata);
+    assert_eq!(map.workspace_root(), Some(root));
+
+    // Shared through `#[path]`: every package compiling it.
+    assert_eq!(
+        map.owners_of(&root.join("shared/common.rs")),
+        [id(root, "a", "crates/a")?, id(root, "b", "crates/b")?]
+    );
+    assert_eq!(
+        map.owner_of(&root.join("crates/b/src/main.rs")),
+        Some(id(root, "b", "crates/b")?)
+    );
+    // Outside any module graph: the deepest manifest directory holding it.
+    assert_eq!(
+        map.owners_of(&root.join("crates/a/src/stray.rs")),
+        [id(root, "a", "crates/a")?]
+    );
+    assert_eq!(
+        map.owners_of(&root.join("crates/a/nested/src/loose.rs")),
+        [id(root, "nested", "crates/a/nested")?]
+    );
+    assert_eq!(map.owner_of(&root.join("elsewhere.rs")), None);
+    // Discovery still sees only what is compiled.
+    assert!(
+        map.packages_of(&root.join("crates/a/src/stray.rs"))
+            .is_empty()
+    );
+    Ok(())
+}
+
+#[test]
+fn the_owners_command_prints_package_and_directory() -> TestResult {
+    let tmp = tempfile::tempdir()?;
+    let root = tmp.path();
+    let manifest =
+        |name: &str| format!("[package]\nname=\"{name}\"\nversion=\"0.1.0\"\nedition=\"2021\"\n");
+    write(
+        root,
+        &[
+            (
+                "Cargo.toml",
+                "[workspace]\nmembers = [\"crates/*\"]\nresolver = \"2\"\n",
+            ),
+            ("crates/mylib/Cargo.toml", &manifest("mylib")),
+            ("crates/mylib/src/lib.rs", "pub mod foo;\n"),
+            ("crates/mylib/src/foo.rs", ""),
+            ("crates/app/Cargo.toml", &manifest("app")),
+            ("crates/app/src/main.rs", "fn main() {}\n"),
+        ],
+    )?;
+    Command::cargo_bin("trait-winnower")?
+        .current_dir(root.join("crates/mylib"))
+        .args([
+            "owners",
+            "src/foo.rs",
+            "../app/src/main.rs",
+            "../../Cargo.toml",
+        ])
+        .assert()
+        .success()
+        .stdout(contains("src/foo.rs -> mylib (crates/mylib)\n"))
+        .stdout(contains("../app/src/main.rs -> app (crates/app)\n"))
+        .stdout(contains("../../Cargo.toml -> (no package)\n"));
+    Ok(())
+}
+
+#[test]
+fn findings_of_a_shared_file_name_every_package_compiling_it() -> TestResult {
+    let tmp = tempfile::tempdir()?;
+    let root = tmp.path();
+    let manifest =
+        |name: &str| format!("[package]\nname=\"{name}\"\nversion=\"0.1.0\"\nedition=\"2021\"\n");
+    write(
+        root,
+        &[
+            (
+                "Cargo.toml",
+                "[workspace]\nmembers = [\"a\", \"b\"]\nresolver = \"2\"\n",
+            ),
+            ("a/Cargo.toml", &manifest("a")),
+            (
+                "a/src/lib.rs",
+                "#[path = \"../../shared/common.rs\"]\nmod common;\n",
+            ),
+            ("b/Cargo.toml", &manifest("b")),
+            ("b/src/lib.rs", "include!(\"../../shared/common.rs\");\n"),
+            (
+                "shared/common.rs",
+                "pub fn f<T: Clone>(t: T) -> T {\n    t\n}\n",
+            ),
+        ],
+    )?;
+    let out = Command::cargo_bin("trait-winnower")?
+        .current_dir(root)
+        .args(["check", "."])
+        .output()?;
+    assert!(out.status.success());
+    let stdout = String::from_utf8(out.stdout)?;
+    assert_eq!(stdout.matches("`Clone`").count(), 1, "{stdout}");
+    assert!(stdout.contains("note: affects packages: a, b"), "{stdout}");
+
+    let checked = trait_winnower::winnower::Winnower::builder()
+        .target(root)
+        .build()?
+        .check()?;
+    let findings: Vec<_> = checked
+        .report
+        .files
+        .iter()
+        .flat_map(|f| &f.findings)
+        .collect();
+    assert_eq!(findings.len(), 1, "{findings:#?}");
+    assert_eq!(findings[0].packages, ["a", "b"]);
+    Ok(())
+}
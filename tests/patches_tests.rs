@@ -0,0 +1,140 @@
+// tests/patches_tests.rs
+//! `prune --output-patches` leaves the crate alone and writes patches that `git apply`
+//! turns it into what a direct `prune` leaves.
+#![cfg(feature = "cli")]
+
+mod common;
+
+use assert_cmd::Command;
+use common::TestResult;
+use std::path::Path;
+
+const SOURCES: [&str; 4] = ["a.rs", "b.rs", "c.rs", "traits.rs"];
+
+fn copy_dir(from: &Path, to: &Path) -> std::io::Result<()> {
+    std::fs::create_dir_all(to)?;
+    for entry in std::fs::read_dir(from)? {
+        let entry = entry?;
+        let path = entry.path();
+        if path.is_dir() {
+            copy_dir(&path, &to.join(entry.file_name()))?;
+        } else {
+            std::fs::copy(&path, to.join(entry.file_name()))?;
+        }
+    }
+    Ok(())
+}
+
+fn prune(root: &Path, args: &[&str]) -> TestResult {
+    let out = Command::cargo_bin("trait-winnower")?
+        .args(["prune", "-q", "-b", "-n", "all"])
+        .args(args)
+        .arg(root)
+        .output()?;
+    assert!(
+        out.status.success(),
+        "{}",
+        String::from_utf8_lossy(&out.stderr)
+    );
+    Ok(())
+}
+
+/// `git apply` each of `patches`, in order, inside `root`.
+fn git_apply(root: &Path, patches: &[std::path::PathBuf]) -> TestResult {
+    for patch in patches {
+        let out = std::process::Command::new("git")
+            .arg("apply")
+            .arg(patch)
+            .current_dir(root)
+            .output()?;
+        assert!(
+            out.status.success(),
+            "{}: {}",
+            patch.display(),
+            String::from_utf8_lossy(&out.stderr)
+        );
+    }
+    Ok(())
+}
+
+/// The pruned sources of a copy of the sandbox pruned in place.
+fn pruned_directly() -> Result<Vec<String>, Box<dyn std::error::Error>> {
+    let tmp = tempfile::tempdir()?;
+    copy_dir(Path::new("tests/test_files/trait_sandbox"), tmp.path())?;
+    prune(tmp.path(), &[])?;
+    SOURCES
+        .iter()
+        .map(|f| Ok(std::fs::read_to_string(tmp.path().join("src").join(f))?))
+        .collect()
+}
+
+fn sources(root: &Path) -> Result<Vec<String>, Box<dyn std::error::Error>> {
+    SOURCES
+        .iter()
+        .map(|f| Ok(std::fs::read_to_string(root.join("src").join(f))?))
+        .collect()
+}
+
+#[test]
+fn patch_series_reproduces_a_direct_prune() -> TestResult {
+    let fixture = Path::new("tests/test_files/trait_sandbox");
+    let tmp = tempfile::tempdir()?;
+    let root = tmp.path().join("crate");
+    let dir = tmp.path().join("patches");
+    copy_dir(fixture, &root)?;
+
+    prune(
+        &root,
+        &["--output-patches", dir.to_str().unwrap_or_default()],
+    )?;
+    assert_eq!(sources(&root)?, sources(fixture)?, "the tree was modified");
+    assert!(!root.join("target/trait-winnower/worktree").exists());
+
+    let mut patches: Vec<_> = std::fs::read_dir(&dir)?
+        .map(|e| e.map(|e| e.path()))
+        .collect::<Result<_, _>>()?;
+    patches.sort();
+    let names: Vec<String> = patches
+        .iter()
+        .filter_map(|p| p.file_name()?.to_str().map(str::to_owned))
+        .collect();
+    assert!(names.len() > 1, "{names:?}");
+    assert!(names[0].starts_with("0001-"), "{names:?}");
+    assert!(names.iter().all(|n| n.ends_with(".patch")), "{names:?}");
+    let first = std::fs::read_to_string(&patches[0])?;
+    assert!(first.starts_with("diff --git a/src/"), "{first}");
+
+    git_apply(&root, &patches)?;
+    assert_eq!(sources(&root)?, pruned_directly()?);
+    Ok(())
+}
+
+#[test]
+fn single_patch_covers_every_file() -> TestResult {
+    let tmp = tempfile::tempdir()?;
+    let root = tmp.path().join("crate");
+    let dir = tmp.path().join("out");
+    copy_dir(Path::new("tests/test_files/trait_sandbox"), &root)?;
+
+    prune(
+        &root,
+        &[
+            "--output-patches",
+            dir.to_str().unwrap_or_default(),
+            "--single-patch",
+        ],
+    )?;
+    let written: Vec<_> = std::fs::read_dir(&dir)?.collect::<Result<_, _>>()?;
+    assert_eq!(written.len(), 1);
+    let patch = dir.join("winnow.patch");
+    assert!(
+        std::fs::read_to_string(&patch)?
+            .matches("diff --git")
+            .count()
+            > 1
+    );
+
+    git_apply(&root, &[patch])?;
+    assert_eq!(sources(&root)?, pruned_directly()?);
+    Ok(())
+}
@@ -0,0 +1,172 @@
+// tests/plan_tests.rs
+//! Saved plans apply to the current source, report entries that no longer match, and
+//! leave the tree untouched when strict or when validation fails.
+#![cfg(feature = "discover")]
+
+mod common;
+
+use common::TestResult;
+use std::path::Path;
+use trait_winnower::dynamic_analysis::validate::{AlwaysOk, ScriptedValidator};
+use trait_winnower::error::WinnowerError;
+use trait_winnower::plan::{ApplyOptions, EntryStatus, Plan, apply_plan_with};
+use trait_winnower::winnower::Winnower;
+
+const LIB: &str = "pub mod a;\n\npub fn f<T: Clone + Send>(t: T) -> T {\n    t\n}\n";
+const A: &str = "pub fn g<U>(u: U)\nwhere\n    U: Copy + Default,\n{\n}\n";
+
+fn plan_for(dir: &Path) -> Result<Plan, WinnowerError> {
+    let checked = Winnower::builder().target(dir).build()?.check()?;
+    Ok(Plan::from_report(&checked.report))
+}
+
+fn read(dir: &Path, rel: &str) -> std::io::Result<String> {
+    std::fs::read_to_string(dir.join(rel))
+}
+
+fn ok_options() -> ApplyOptions<'static> {
+    ApplyOptions {
+        validator: Some(&AlwaysOk),
+        ..ApplyOptions::default()
+    }
+}
+
+#[test]
+fn saved_plan_applies_every_entry() -> TestResult {
+    let tmp = tempfile::tempdir()?;
+    common::write_crate(tmp.path(), "x", "", &[("src/lib.rs", LIB), ("src/a.rs", A)])?;
+    let plan_path = tmp.path().join("plan.toml");
+    plan_for(tmp.path())?.save(&plan_path)?;
+    let plan = Plan::load(&plan_path)?;
+    assert_eq!(plan.entries.len(), 4);
+
+    let applied = apply_plan_with(tmp.path(), &plan, &ok_options())?;
+
+    assert_eq!(applied.applied(), 4);
+    assert!(!applied.reverted);
+    assert!(applied.validation.as_ref().is_some_and(|v| v.success));
+    assert_eq!(applied.modified.len(), 2);
+    assert_eq!(
+        read(tmp.path(), "src/lib.rs")?,
+        "pub mod a;\n\npub fn f<T>(t: T) -> T {\n    t\n}\n"
+    );
+    assert_eq!(read(tmp.path(), "src/a.rs")?, "pub fn g<U>(u: U)\n{\n}\n");
+    Ok(())
+}
+
+#[test]
+fn entries_survive_unrelated_edits_and_report_mismatches() -> TestResult {
+    let tmp = tempfile::tempdir()?;
+    common::write_crate(tmp.path(), "x", "", &[("src/lib.rs", LIB), ("src/a.rs", A)])?;
+    let plan = plan_for(tmp.path())?;
+
+    // `f` moves down and loses `Send`; `Copy` becomes `Sync`.
+    std::fs::write(
+        tmp.path().join("src/lib.rs"),
+        "pub mod a;\n\n// moved\npub fn f<T: Clone>(t: T) -> T {\n    t\n}\n",
+    )?;
+    std::fs::write(
+        tmp.path().join("src/a.rs"),
+        "pub fn g<U>(u: U)\nwhere\n    U: Sync + Default,\n{\n}\n",
+    )?;
+
+    let applied = apply_plan_with(tmp.path(), &plan, &ok_options())?;
+
+    let status = |bound: &str| {
+        applied
+            .entries
+            .iter()
+            .find(|e| e.entry.candidate.bound_text == bound)
+            .map(|e| e.status.clone())
+    };
+    assert_eq!(status("Clone"), Some(EntryStatus::Applied));
+    assert_eq!(status("Send"), Some(EntryStatus::BoundGone));
+    assert_eq!(
+        status("Copy"),
+        Some(EntryStatus::BoundTextDiffers {
+            found: "Sync".into()
+        })
+    );
+    assert_eq!(status("Default"), Some(EntryStatus::Applied));
+    assert_eq!(
+        read(tmp.path(), "src/lib.rs")?,
+        "pub mod a;\n\n// moved\npub fn f<T>(t: T) -> T {\n    t\n}\n"
+    );
+    assert_eq!(
+        read(tmp.path(), "src/a.rs")?,
+        "pub fn g<U>(u: U)\nwhere\n    U: Sync,\n{\n}\n"
+    );
+    Ok(())
+}
+
+#[test]
+fn missing_items_and_files_are_reported() -> TestResult {
+    let tmp = tempfile::tempdir()?;
+    common::write_crate(tmp.path(), "x", "", &[("src/lib.rs", LIB), ("src/a.rs", A)])?;
+    let plan = plan_for(tmp.path())?;
+
+    std::fs::write(
+        tmp.path().join("src/lib.rs"),
+        "pub fn renamed<T: Clone + Send>(t: T) -> T {\n    t\n}\n",
+    )?;
+    std::fs::remove_file(tmp.path().join("src/a.rs"))?;
+
+    let applied = apply_plan_with(tmp.path(), &plan, &ok_options())?;
+
+    let statuses: Vec<EntryStatus> = applied.entries.iter().map(|e| e.status.clone()).collect();
+    assert_eq!(
+        statuses,
+        [
+            EntryStatus::FileMissing,
+            EntryStatus::FileMissing,
+            EntryStatus::ItemNotFound,
+            EntryStatus::ItemNotFound,
+        ]
+    );
+    assert!(applied.validation.is_none());
+    assert!(applied.modified.is_empty());
+    Ok(())
+}
+
+#[test]
+fn strict_mode_changes_nothing_on_mismatch() -> TestResult {
+    let tmp = tempfile::tempdir()?;
+    common::write_crate(tmp.path(), "x", "", &[("src/lib.rs", LIB), ("src/a.rs", A)])?;
+    let plan = plan_for(tmp.path())?;
+    let edited = "pub fn g<U>(u: U)\nwhere\n    U: Copy,\n{\n}\n";
+    std::fs::write(tmp.path().join("src/a.rs"), edited)?;
+
+    let options = ApplyOptions {
+        strict: true,
+        ..ok_options()
+    };
+    let err = apply_plan_with(tmp.path(), &plan, &options).expect_err("strict apply");
+
+    assert!(matches!(err, WinnowerError::Plan { .. }), "{err}");
+    assert!(err.to_string().contains("Default"), "{err}");
+    assert_eq!(err.exit_code(), 2);
+    assert_eq!(read(tmp.path(), "src/lib.rs")?, LIB);
+    assert_eq!(read(tmp.path(), "src/a.rs")?, edited);
+    Ok(())
+}
+
+#[test]
+fn failed_validation_restores_every_file() -> TestResult {
+    let tmp = tempfile::tempdir()?;
+    common::write_crate(tmp.path(), "x", "", &[("src/lib.rs", LIB), ("src/a.rs", A)])?;
+    let plan = plan_for(tmp.path())?;
+    let validator = ScriptedValidator::new([false]);
+
+    let options = ApplyOptions {
+        validator: Some(&validator),
+        ..ApplyOptions::default()
+    };
+    let applied = apply_plan_with(tmp.path(), &plan, &options)?;
+
+    assert!(applied.reverted);
+    assert_eq!(applied.applied(), 4);
+    assert!(applied.modified.is_empty());
+    assert_eq!(read(tmp.path(), "src/lib.rs")?, LIB);
+    assert_eq!(read(tmp.path(), "src/a.rs")?, A);
+    Ok(())
+}
@@ -0,0 +1,91 @@
+// tests/prefilter_tests.rs
+//! `prune` skips bounds the code plainly uses instead of spending a `cargo check` on them.
+#![cfg(feature = "cli")]
+
+mod common;
+
+use common::TestResult;
+use std::path::Path;
+use trait_winnower::config::Config;
+use trait_winnower::report::RemovalStatus;
+use trait_winnower::winnower::{PruneReport, Winnower};
+
+const SRC: &str = "pub fn f<T: Clone>(t: T) -> (T, T) {\n    (t.clone(), t)\n}\n\n\
+                   pub fn g<U: Clone>(u: U) -> U {\n    u\n}\n";
+
+fn prune(dir: &Path, config: Config) -> Result<PruneReport, Box<dyn std::error::Error>> {
+    Ok(Winnower::builder()
+        .target(dir)
+        .config(config)
+        .brute_force(true)
+        .build()?
+        .prune()?)
+}
+
+#[test]
+fn plainly_used_bounds_cost_no_check() -> TestResult {
+    let tmp = tempfile::tempdir()?;
+    common::write_crate(tmp.path(), "x", "", &[("src/lib.rs", SRC)])?;
+    let pruned = prune(tmp.path(), Config::default())?;
+    let f = pruned.report.files[0]
+        .removals
+        .iter()
+        .find(|r| r.finding.item == "fn f")
+        .expect("f was considered");
+    assert_eq!(f.status, RemovalStatus::Skipped);
+    assert_eq!(
+        f.finding.statically_used.as_deref(),
+        Some("`t.clone()` calls `Clone::clone`")
+    );
+    assert_eq!(pruned.report.total_checks(), 1);
+    assert_eq!(pruned.report.total_prefiltered(), 1);
+    let src = std::fs::read_to_string(tmp.path().join("src/lib.rs"))?;
+    assert!(src.contains("pub fn f<T: Clone>"), "{src}");
+    assert!(src.contains("pub fn g<U>"), "{src}");
+    Ok(())
+}
+
+#[test]
+fn the_prefilter_can_be_turned_off() -> TestResult {
+    let tmp = tempfile::tempdir()?;
+    common::write_crate(tmp.path(), "x", "", &[("src/lib.rs", SRC)])?;
+    let config = Config {
+        prefilter: false,
+        ..Config::default()
+    };
+    let pruned = prune(tmp.path(), config)?;
+    assert_eq!(pruned.report.total_checks(), 2);
+    assert_eq!(pruned.report.total_prefiltered(), 0);
+    assert!(
+        pruned.report.files[0]
+            .removals
+            .iter()
+            .all(|r| r.finding.statically_used.is_none())
+    );
+    Ok(())
+}
+
+#[test]
+fn the_cli_reports_the_checks_saved() -> TestResult {
+    use assert_cmd::Command;
+    use predicates::str::contains;
+
+    let tmp = tempfile::tempdir()?;
+    common::write_crate(tmp.path(), "x", "", &[("src/lib.rs", SRC)])?;
+    let out = Command::cargo_bin("trait-winnower")?
+        .args(["prune", "-b", "--format", "json", "-v", "2"])
+        .arg(tmp.path())
+        .assert()
+        .success()
+        .stderr(contains(
+            "prefilter: 1 check(s) saved on bounds the code plainly uses",
+        ))
+        .stderr(contains("skipped (statically used)"));
+    let stdout = String::from_utf8(out.get_output().stdout.clone())?;
+    assert!(stdout.contains(r#""prefiltered": 1"#), "{stdout}");
+    assert!(
+        stdout.contains(r#""statically_used": "`t.clone()` calls `Clone::clone`""#),
+        "{stdout}"
+    );
+    Ok(())
+}
@@ -0,0 +1,110 @@
+// tests/report_files_tests.rs
+//! `--report FORMAT=FILE` writes reports in several formats from one run, next to the
+//! `--format` on stdout.
+#![cfg(feature = "cli")]
+
+mod common;
+
+use assert_cmd::Command;
+use common::TestResult;
+use predicates::str::contains;
+
+const LIB: &str = "\
+pub fn a<T: Copy + Clone>(t: T) -> T {
+    t
+}
+
+pub mod b {
+    pub fn c<T: Copy + Clone>(t: T) -> T {
+        t
+    }
+}
+";
+
+#[test]
+fn two_reports_from_one_run_agree() -> TestResult {
+    let tmp = tempfile::tempdir()?;
+    common::write_crate(tmp.path(), "reports", "", &[("src/lib.rs", LIB)])?;
+    let json_path = tmp.path().join("out/nested/winnow.json");
+    let lsp_path = tmp.path().join("out/winnow.lsp.json");
+    let out = Command::cargo_bin("trait-winnower")?
+        .arg("check")
+        .arg(tmp.path())
+        .arg("--report")
+        .arg(format!("json={}", json_path.display()))
+        .arg("--report")
+        .arg(format!("lsp-json={}", lsp_path.display()))
+        .output()?;
+    assert!(
+        out.status.success(),
+        "{}",
+        String::from_utf8_lossy(&out.stderr)
+    );
+    // The human diagnostics still go to stdout.
+    assert!(String::from_utf8(out.stdout)?.contains("Clone"));
+    let stderr = String::from_utf8(out.stderr)?;
+    assert!(
+        stderr.contains(&format!("wrote json report to {}", json_path.display())),
+        "{stderr}"
+    );
+    assert!(
+        stderr.contains(&format!("wrote lsp-json report to {}", lsp_path.display())),
+        "{stderr}"
+    );
+
+    let json: serde_json::Value = serde_json::from_str(&std::fs::read_to_string(&json_path)?)?;
+    let lsp: serde_json::Value = serde_json::from_str(&std::fs::read_to_string(&lsp_path)?)?;
+    let mut from_json: Vec<u64> = json["files"]
+        .as_array()
+        .ok_or("files")?
+        .iter()
+        .flat_map(|f| f["findings"].as_array().cloned().unwrap_or_default())
+        .map(|f| f["line"].as_u64().unwrap_or_default())
+        .collect();
+    let mut from_lsp: Vec<u64> = lsp
+        .as_array()
+        .ok_or("files")?
+        .iter()
+        .flat_map(|f| f["diagnostics"].as_array().cloned().unwrap_or_default())
+        .map(|d| d["range"]["start"]["line"].as_u64().unwrap_or_default() + 1)
+        .collect();
+    from_json.sort();
+    from_lsp.sort();
+    assert_eq!(from_json.len(), 4);
+    assert_eq!(from_json, from_lsp);
+    assert_eq!(
+        json["metrics"]["findings"].as_u64(),
+        Some(from_lsp.len() as u64)
+    );
+    // Only the finished files are left.
+    let left: Vec<_> = std::fs::read_dir(tmp.path().join("out"))?
+        .map(|e| e.map(|e| e.file_name()))
+        .collect::<Result<_, _>>()?;
+    assert_eq!(left.len(), 2, "{left:?}");
+    Ok(())
+}
+
+#[test]
+fn report_specs_are_checked() -> TestResult {
+    let tmp = tempfile::tempdir()?;
+    common::write_crate(tmp.path(), "reports", "", &[("src/lib.rs", LIB)])?;
+    Command::cargo_bin("trait-winnower")?
+        .args(["check", "--report", "sarif=out.sarif"])
+        .arg(tmp.path())
+        .assert()
+        .failure()
+        .stderr(contains("unknown report format `sarif`"));
+    Command::cargo_bin("trait-winnower")?
+        .args(["check", "--report", "out.json"])
+        .arg(tmp.path())
+        .assert()
+        .failure()
+        .stderr(contains("expected FORMAT=FILE"));
+    Command::cargo_bin("trait-winnower")?
+        .args(["prune", "--report", "rustfix=fixes.json"])
+        .arg(tmp.path())
+        .assert()
+        .failure()
+        .stderr(contains("rustfix needs `check`"));
+    Ok(())
+}
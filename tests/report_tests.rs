@@ -0,0 +1,842 @@
+//! Report format tests.
+#![cfg(feature = "cli")]
+
+mod common;
+
+use assert_cmd::Command;
+use common::TestResult;
+use std::collections::HashMap;
+
+/// A parsed JSON value.
+#[derive(Debug, Clone, PartialEq)]
+enum Json {
+    Null,
+    Bool(bool),
+    Num(f64),
+    Str(String),
+    Arr(Vec<Json>),
+    Obj(Vec<(String, Json)>),
+}
+
+impl Json {
+    fn get(&self, key: &str) -> &Json {
+        self.get_opt(key)
+            .unwrap_or_else(|| panic!("missing key {key}"))
+    }
+
+    fn get_opt(&self, key: &str) -> Option<&Json> {
+        match self {
+            Json::Obj(fields) => fields.iter().find(|(k, _)| k == key).map(|(_, v)| v),
+            _ => panic!("not an object"),
+        }
+    }
+
+    fn num(&self) -> f64 {
+        match self {
+            Json::Num(n) => *n,
+            other => panic!("not a number: {other:?}"),
+        }
+    }
+
+    fn str(&self) -> &str {
+        match self {
+            Json::Str(s) => s,
+            other => panic!("not a string: {other:?}"),
+        }
+    }
+
+    fn arr(&self) -> &[Json] {
+        match self {
+            Json::Arr(a) => a,
+            other => panic!("not an array: {other:?}"),
+        }
+    }
+}
+
+/// Strict recursive-descent JSON parser for test assertions.
+fn parse_json(src: &str) -> Result<Json, String> {
+    fn ws(s: &mut &str) {
+        *s = s.trim_start();
+    }
+    fn value(s: &mut &str) -> Result<Json, String> {
+        ws(s);
+        if let Some(r) = s.strip_prefix("null") {
+            *s = r;
+            Ok(Json::Null)
+        } else if let Some(r) = s.strip_prefix("true") {
+            *s = r;
+            Ok(Json::Bool(true))
+        } else if let Some(r) = s.strip_prefix("false") {
+            *s = r;
+            Ok(Json::Bool(false))
+        } else if s.starts_with('"') {
+            string(s).map(Json::Str)
+        } else if let Some(r) = s.strip_prefix('[') {
+            *s = r;
+            let mut items = Vec::new();
+            ws(s);
+            if let Some(r) = s.strip_prefix(']') {
+                *s = r;
+                return Ok(Json::Arr(items));
+            }
+            loop {
+                items.push(value(s)?);
+                ws(s);
+                if let Some(r) = s.strip_prefix(',') {
+                    *s = r;
+                } else if let Some(r) = s.strip_prefix(']') {
+                    *s = r;
+                    return Ok(Json::Arr(items));
+                } else {
+                    return Err(format!("expected , or ] at {s:.20}"));
+                }
+            }
+        } else if let Some(r) = s.strip_prefix('{') {
+            *s = r;
+            let mut fields = Vec::new();
+            ws(s);
+            if let Some(r) = s.strip_prefix('}') {
+                *s = r;
+                return Ok(Json::Obj(fields));
+            }
+            loop {
+                ws(s);
+                let k = string(s)?;
+                ws(s);
+                *s = s.strip_prefix(':').ok_or("expected :")?;
+                fields.push((k, value(s)?));
+                ws(s);
+                if let Some(r) = s.strip_prefix(',') {
+                    *s = r;
+                } else if let Some(r) = s.strip_prefix('}') {
+                    *s = r;
+                    return Ok(Json::Obj(fields));
+                } else {
+                    return Err(format!("expected , or }} at {s:.20}"));
+                }
+            }
+        } else {
+            let end = s
+                .find(|c: char| !(c.is_ascii_digit() || "+-.eE".contains(c)))
+                .unwrap_or(s.len());
+            let n = s[..end]
+                .parse()
+                .map_err(|_| format!("bad token at {s:.20}"))?;
+            *s = &s[end..];
+            Ok(Json::Num(n))
+        }
+    }
+    fn string(s: &mut &str) -> Result<String, String> {
+        *s = s.strip_prefix('"').ok_or("expected string")?;
+        let mut out = String::new();
+        let mut chars = s.char_indices();
+        while let Some((i, c)) = chars.next() {
+            match c {
+                '"' => {
+                    *s = &s[i + 1..];
+                    return Ok(out);
+                }
+                '\\' => match chars.next().map(|(_, c)| c) {
+                    Some('n') => out.push('\n'),
+                    Some('t') => out.push('\t'),
+                    Some('r') => out.push('\r'),
+                    Some('u') => {
+                        let hex: String = (0..4)
+                            .filter_map(|_| chars.next().map(|(_, c)| c))
+                            .collect();
+                        let code = u32::from_str_radix(&hex, 16).map_err(|e| e.to_string())?;
+                        out.push(char::from_u32(code).ok_or("bad escape")?);
+                    }
+                    Some(c) => out.push(c),
+                    None => return Err("dangling escape".into()),
+                },
+                c if (c as u32) < 0x20 => return Err("control character in string".into()),
+                c => out.push(c),
+            }
+        }
+        Err("unterminated string".into())
+    }
+    let mut s = src;
+    let v = value(&mut s)?;
+    if !s.trim().is_empty() {
+        return Err("trailing data".into());
+    }
+    Ok(v)
+}
+
+/// A parsed XML element: name, attributes, children.
+#[derive(Debug)]
+struct Element {
+    name: String,
+    attrs: HashMap<String, String>,
+    children: Vec<Element>,
+}
+
+/// Minimal well-formedness checking XML parser (no DTDs, no CDATA).
+fn parse_xml(src: &str) -> Result<Element, String> {
+    let body = src
+        .strip_prefix(r#"<?xml version="1.0" encoding="UTF-8"?>"#)
+        .ok_or("missing XML declaration")?;
+    let mut stack: Vec<Element> = Vec::new();
+    let mut root = None;
+    let mut rest = body.trim_start();
+    while !rest.is_empty() {
+        if let Some(after) = rest.strip_prefix("</") {
+            let end = after.find('>').ok_or("unterminated end tag")?;
+            let name = &after[..end];
+            let el = stack.pop().ok_or("unbalanced end tag")?;
+            if el.name != name {
+                return Err(format!("mismatched </{name}> for <{}>", el.name));
+            }
+            match stack.last_mut() {
+                Some(parent) => parent.children.push(el),
+                None => root = Some(el),
+            }
+            rest = &after[end + 1..];
+        } else if let Some(after) = rest.strip_prefix('<') {
+            let end = after.find('>').ok_or("unterminated start tag")?;
+            let tag = &after[..end];
+            let (tag, self_closing) = match tag.strip_suffix('/') {
+                Some(t) => (t, true),
+                None => (tag, false),
+            };
+            let mut parts = tag.splitn(2, ' ');
+            let name = parts.next().unwrap_or_default().to_owned();
+            let mut attrs = HashMap::new();
+            let mut a = parts.next().unwrap_or_default().trim();
+            while !a.is_empty() {
+                let eq = a.find("=\"").ok_or("malformed attribute")?;
+                let key = a[..eq].trim().to_owned();
+                let val_end = a[eq + 2..].find('"').ok_or("unterminated attribute")?;
+                let val = &a[eq + 2..eq + 2 + val_end];
+                if val.contains('<') || val.contains('>') {
+                    return Err(format!("unescaped markup in attribute {key}"));
+                }
+                attrs.insert(key, val.to_owned());
+                a = a[eq + 2 + val_end + 1..].trim_start();
+            }
+            let el = Element {
+                name,
+                attrs,
+                children: Vec::new(),
+            };
+            if self_closing {
+                stack
+                    .last_mut()
+                    .ok_or("self-closing root")?
+                    .children
+                    .push(el);
+            } else {
+                stack.push(el);
+            }
+            rest = &after[end + 1..];
+        } else {
+            let end = rest.find('<').unwrap_or(rest.len());
+            if stack.is_empty() && !rest[..end].trim().is_empty() {
+                return Err("text outside root".into());
+            }
+            rest = &rest[end..];
+        }
+        rest = rest.trim_start();
+    }
+    if !stack.is_empty() {
+        return Err("unclosed elements".into());
+    }
+    root.ok_or_else(|| "no root element".into())
+}
+
+fn require(el: &Element, attrs: &[&str]) {
+    for a in attrs {
+        assert!(
+            el.attrs.contains_key(*a),
+            "<{}> missing required attribute {a}",
+            el.name
+        );
+    }
+}
+
+fn count(el: &Element, attr: &str) -> usize {
+    el.attrs[attr].parse().expect("numeric attribute")
+}
+
+/// Check the structure the common JUnit XSD requires.
+fn validate_junit(root: &Element) {
+    assert_eq!(root.name, "testsuites");
+    require(root, &["tests", "failures", "errors", "time"]);
+    let (mut tests, mut failures) = (0, 0);
+    for suite in &root.children {
+        assert_eq!(suite.name, "testsuite");
+        require(suite, &["name", "tests", "failures", "errors", "time"]);
+        let suite_failures = suite
+            .children
+            .iter()
+            .filter(|c| c.children.iter().any(|f| f.name == "failure"))
+            .count();
+        assert_eq!(count(suite, "tests"), suite.children.len());
+        assert_eq!(count(suite, "failures"), suite_failures);
+        for case in &suite.children {
+            assert_eq!(case.name, "testcase");
+            require(case, &["name", "classname", "time"]);
+            for f in &case.children {
+                match f.name.as_str() {
+                    "properties" => f
+                        .children
+                        .iter()
+                        .for_each(|p| require(p, &["name", "value"])),
+                    "failure" => require(f, &["message", "type"]),
+                    other => panic!("unexpected <{other}> in a testcase"),
+                }
+            }
+        }
+        tests += suite.children.len();
+        failures += suite_failures;
+    }
+    assert_eq!(count(root, "tests"), tests);
+    assert_eq!(count(root, "failures"), failures);
+}
+
+#[test]
+fn junit_output_is_valid_for_sandbox() -> TestResult {
+    let out = Command::cargo_bin("trait-winnower")?
+        .args([
+            "check",
+            "tests/test_files/trait_sandbox",
+            "--format",
+            "junit",
+        ])
+        .output()?;
+    assert!(out.status.success());
+    let xml = String::from_utf8(out.stdout)?;
+    let root = parse_xml(&xml)?;
+    validate_junit(&root);
+
+    let suites: Vec<&str> = root
+        .children
+        .iter()
+        .map(|s| s.attrs["name"].as_str())
+        .collect();
+    assert!(suites.contains(&"src/a.rs"), "suites: {suites:?}");
+    // Files without findings still contribute a passing case.
+    let lib = root
+        .children
+        .iter()
+        .find(|s| s.attrs["name"] == "src/lib.rs")
+        .expect("lib.rs suite");
+    assert_eq!(count(lib, "failures"), 0);
+    assert_eq!(count(lib, "tests"), 1);
+    Ok(())
+}
+
+#[test]
+fn junit_escapes_generic_bounds() -> TestResult {
+    let tmp = assert_fs::TempDir::new()?;
+    let file = tmp.path().join("generic.rs");
+    std::fs::write(&file, "fn f<T: Into<Vec<u8>>>(t: T) {}\n")?;
+    let out = Command::cargo_bin("trait-winnower")?
+        .args(["check", "--format", "junit"])
+        .arg(&file)
+        .output()?;
+    assert!(out.status.success());
+    let xml = String::from_utf8(out.stdout)?;
+    assert!(xml.contains("Into &lt; Vec &lt; u8 &gt; &gt;"), "{xml}");
+    assert!(
+        xml.contains(r#"<property name="kind" value="fn"/>"#),
+        "{xml}"
+    );
+    validate_junit(&parse_xml(&xml)?);
+    Ok(())
+}
+
+/// Replace volatile values (durations) with a fixed marker.
+fn normalize_html(html: &str) -> String {
+    const OPEN: &str = "<span class=\"volatile\">";
+    let mut out = String::new();
+    let mut rest = html;
+    while let Some(start) = rest.find(OPEN) {
+        let body = &rest[start + OPEN.len()..];
+        let end = body.find("</span>").expect("closed volatile span");
+        out.push_str(&rest[..start + OPEN.len()]);
+        out.push_str("NORMALIZED");
+        rest = &body[end..];
+    }
+    out.push_str(rest);
+    out
+}
+
+#[test]
+fn html_report_matches_golden_for_sandbox() -> TestResult {
+    let tmp = assert_fs::TempDir::new()?;
+    let out = tmp.path().join("report.html");
+    Command::cargo_bin("trait-winnower")?
+        .args(["check", "tests/test_files/trait_sandbox", "--report-html"])
+        .arg(&out)
+        .assert()
+        .success();
+
+    let html = normalize_html(&std::fs::read_to_string(&out)?);
+    let golden = std::fs::read_to_string("tests/expected/reports/trait_sandbox_check.html")?;
+    assert_eq!(html, golden);
+    assert!(
+        html.contains("<td><code>fn unused_bound_clone</code></td><td><code>T: Clone</code></td>")
+    );
+    Ok(())
+}
+
+#[test]
+fn html_report_shows_removal_snippets() -> TestResult {
+    let tmp = assert_fs::TempDir::new()?;
+    std::fs::write(
+        tmp.path().join("Cargo.toml"),
+        "[package]\nname=\"x\"\nversion=\"0.1.0\"\nedition=\"2021\"\n\n[workspace]\n",
+    )?;
+    std::fs::create_dir(tmp.path().join("src"))?;
+    std::fs::write(
+        tmp.path().join("src/lib.rs"),
+        "pub fn f<T: Clone>(t: T) -> T {\n    t\n}\n",
+    )?;
+    let out = tmp.path().join("report.html");
+    Command::cargo_bin("trait-winnower")?
+        .current_dir(tmp.path())
+        .args(["prune", ".", "--brute-force", "--report-html"])
+        .arg(&out)
+        .assert()
+        .success();
+
+    let html = std::fs::read_to_string(&out)?;
+    assert!(html.contains("<dt>Bounds removed</dt><dd>1</dd>"), "{html}");
+    assert!(html.contains("<td class=\"removed\">removed</td><td>cargo check</td>"));
+    assert!(html.contains("<details>"));
+    assert!(
+        html.contains("<pre>- &lt; T : Clone &gt;\n+ &lt; T &gt;</pre>"),
+        "{html}"
+    );
+    Ok(())
+}
+
+#[test]
+fn human_output_points_carets_at_bound() -> TestResult {
+    let tmp = assert_fs::TempDir::new()?;
+    let file = tmp.path().join("diag.rs");
+    std::fs::write(
+        &file,
+        "fn f<T>(t: T)\nwhere\n    T: Clone\n        + Send,\n{\n}\n\n\tfn g<U: Copy>() {}\n",
+    )?;
+    let out = Command::cargo_bin("trait-winnower")?
+        .args(["check"])
+        .arg(&file)
+        .output()?;
+    assert!(out.status.success());
+    let text = String::from_utf8(out.stdout)?;
+    let expected_clone = "\
+warning: the body of `f` makes no calls or operations that could use `T: Clone`
+ --> diag.rs:3:8
+  |
+3 |     T: Clone
+  |        ^^^^^
+  = help: try removing `Clone` from the bounds on `T` in `fn f`
+  = note: `unused-in-body` rule, low confidence
+";
+    assert!(text.contains(expected_clone), "{text}");
+    assert!(text.contains(" --> diag.rs:4:11\n"), "{text}");
+    // Tabs before the bound are kept so carets line up.
+    assert!(
+        text.contains("8 | \tfn g<U: Copy>() {}\n  | \t        ^^^^\n"),
+        "{text}"
+    );
+    Ok(())
+}
+
+#[test]
+fn human_output_without_spans_prints_location_only() -> TestResult {
+    use trait_winnower::report::diagnostic::Diagnostics;
+    use trait_winnower::report::{FileReport, Finding};
+
+    let file = FileReport {
+        path: "src/x.rs".into(),
+        findings: vec![Finding {
+            item: "fn x".into(),
+            bounded: "T".into(),
+            bound: "Clone".into(),
+            line: 0,
+            column: 0,
+            end_line: 0,
+            end_column: 0,
+            ..Finding::default()
+        }],
+        removals: Vec::new(),
+        metrics: Default::default(),
+        package: None,
+    };
+    let mut out = Vec::new();
+    Diagnostics::write_file(&file, "fn x<T: Clone>() {}\n", &mut out)?;
+    let text = String::from_utf8(out)?;
+    assert_eq!(
+        text,
+        "warning: possibly unnecessary trait bound `Clone`\n  --> src/x.rs\n = help: try removing `Clone` from the bounds on `T` in `fn x`\n\n"
+    );
+    Ok(())
+}
+
+#[test]
+fn json_report_includes_metrics() -> TestResult {
+    let out = Command::cargo_bin("trait-winnower")?
+        .args([
+            "check",
+            "tests/test_files/trait_sandbox",
+            "--format",
+            "json",
+        ])
+        .output()?;
+    assert!(out.status.success());
+    let json = parse_json(&String::from_utf8(out.stdout)?)?;
+    assert_eq!(json.get("command").str(), "check");
+    let files = json.get("files").arr();
+    let a = files
+        .iter()
+        .find(|f| f.get("path").str() == "src/a.rs")
+        .expect("a.rs");
+    let m = a.get("metrics");
+    assert_eq!(
+        m.get("candidates").num() as usize,
+        a.get("findings").arr().len()
+    );
+    assert!(m.get("items").num() >= 1.0);
+    assert!(m.get("total_ms").num() >= m.get("parse_ms").num());
+    assert_eq!(json.get("metrics").get("files").num() as usize, files.len());
+    Ok(())
+}
+
+#[test]
+fn check_json_tags_findings_with_static_rules() -> TestResult {
+    let tmp = assert_fs::TempDir::new()?;
+    let file = tmp.path().join("rules.rs");
+    std::fs::write(
+        &file,
+        "fn f<T: Clone + Clone>(t: &T) -> T {\n    t.clone()\n}\n",
+    )?;
+    let out = Command::cargo_bin("trait-winnower")?
+        .args(["check", "--format", "json"])
+        .arg(&file)
+        .output()?;
+    assert!(out.status.success());
+    let json = parse_json(&String::from_utf8(out.stdout)?)?;
+    let findings = json.get("files").arr()[0].get("findings").arr();
+    assert_eq!(findings.len(), 2);
+    assert_eq!(findings[0].get("kind").str(), "fn");
+    assert!(findings[0].get_opt("impl").is_none());
+    // The first `Clone` is needed by the body; only the repeat is flagged.
+    assert!(findings[0].get_opt("rule").is_none());
+    let rule = findings[1].get("rule");
+    assert_eq!(rule.get("rule").str(), "duplicate-bound");
+    assert_eq!(rule.get("confidence").str(), "high");
+    assert_eq!(
+        rule.get("message").str(),
+        "`Clone` is already required on `T`"
+    );
+    Ok(())
+}
+
+#[test]
+fn check_json_gives_item_kinds_and_impl_headers() -> TestResult {
+    let tmp = assert_fs::TempDir::new()?;
+    let file = tmp.path().join("impls.rs");
+    std::fs::write(
+        &file,
+        "struct W<T>(T);\n\
+         impl<T: Clone> W<T> {\n    fn id<U: Copy>(&self, u: U) -> U { u }\n}\n\
+         impl<T: Send> std::fmt::Debug for W<T> {\n    \
+         fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result { Ok(()) }\n}\n",
+    )?;
+    let out = Command::cargo_bin("trait-winnower")?
+        .args(["check", "--format", "json"])
+        .arg(&file)
+        .output()?;
+    assert!(out.status.success());
+    let json = parse_json(&String::from_utf8(out.stdout)?)?;
+    let findings = json.get("files").arr()[0].get("findings").arr();
+    let item = |label: &str| {
+        findings
+            .iter()
+            .find(|f| f.get("item").str() == label)
+            .unwrap_or_else(|| panic!("no finding on `{label}`"))
+    };
+
+    let inherent = item("impl W < T >");
+    assert_eq!(inherent.get("kind").str(), "impl");
+    assert_eq!(inherent.get("impl").get("self_ty").str(), "W < T >");
+    assert!(inherent.get("impl").get_opt("trait_path").is_none());
+
+    let method = item("W < T >::id");
+    assert_eq!(method.get("kind").str(), "impl_method");
+    assert_eq!(method.get("impl").get("self_ty").str(), "W < T >");
+
+    let debug = item("impl std :: fmt :: Debug for W < T >");
+    assert_eq!(debug.get("kind").str(), "impl");
+    assert_eq!(
+        debug.get("impl").get("trait_path").str(),
+        "std :: fmt :: Debug"
+    );
+    Ok(())
+}
+
+#[test]
+fn prune_json_counts_checks_and_verbose_prints_table() -> TestResult {
+    let tmp = assert_fs::TempDir::new()?;
+    std::fs::write(
+        tmp.path().join("Cargo.toml"),
+        "[package]\nname=\"x\"\nversion=\"0.1.0\"\nedition=\"2021\"\n\n[workspace]\n",
+    )?;
+    std::fs::create_dir(tmp.path().join("src"))?;
+    std::fs::write(
+        tmp.path().join("src/lib.rs"),
+        "pub fn f<T: Clone>(t: &T) -> T {\n    t.clone()\n}\n",
+    )?;
+    let out = Command::cargo_bin("trait-winnower")?
+        .current_dir(tmp.path())
+        .args(["prune", ".", "--brute-force", "--format", "json", "-v", "1"])
+        .output()?;
+    assert!(out.status.success());
+    let json = parse_json(&String::from_utf8(out.stdout)?)?;
+    assert_eq!(json.get("metrics").get("checks").num(), 1.0);
+    assert!(json.get("metrics").get("check_ms").num() > 0.0);
+    let lib = &json.get("files").arr()[0];
+    let removal = &lib.get("removals").arr()[0];
+    assert_eq!(removal.get("status").str(), "retained");
+    assert!(removal.get("check_ms").num() > 0.0);
+    assert!(removal.get("write_ms").num() > 0.0);
+
+    let stderr = String::from_utf8(out.stderr)?;
+    assert!(stderr.contains("parse ms"), "{stderr}");
+    assert!(stderr.contains("1 check(s)"), "{stderr}");
+    Ok(())
+}
+
+#[test]
+fn prune_breaks_removals_down_by_package_and_trait() -> TestResult {
+    let tmp = assert_fs::TempDir::new()?;
+    std::fs::write(
+        tmp.path().join("Cargo.toml"),
+        "[package]\nname=\"x\"\nversion=\"0.1.0\"\nedition=\"2021\"\n\n[workspace]\n",
+    )?;
+    std::fs::create_dir(tmp.path().join("src"))?;
+    std::fs::write(
+        tmp.path().join("src/lib.rs"),
+        "pub fn f<T: Clone>(t: &T) -> T {\n    t.clone()\n}\n\n\
+         pub fn g<T: std::fmt::Debug + Default>(t: T) -> T {\n    t\n}\n",
+    )?;
+    let (html, md) = (tmp.path().join("report.html"), tmp.path().join("report.md"));
+    let out = Command::cargo_bin("trait-winnower")?
+        .current_dir(tmp.path())
+        .args(["prune", ".", "--brute-force", "--report-html"])
+        .arg(&html)
+        .arg("--report-markdown")
+        .arg(&md)
+        .output()?;
+    assert!(out.status.success());
+
+    let stderr = String::from_utf8(out.stderr)?;
+    assert!(
+        stderr.contains(" removed  retained    ratio  package\n       2         1      67%  x\n"),
+        "{stderr}"
+    );
+    assert!(
+        stderr.contains("       1         0     100%  Debug\n       1         0     100%  Default\n       0         1       0%  Clone\n"),
+        "{stderr}"
+    );
+    let md = std::fs::read_to_string(&md)?;
+    assert!(md.starts_with("## trait-winnower prune\n"), "{md}");
+    assert!(md.contains("- Bounds removed: 2\n"), "{md}");
+    assert!(
+        md.contains("| Trait | Removed | Retained | Removed % |\n| --- | ---: | ---: | ---: |\n| `Debug` | 1 | 0 | 100 |\n| `Default` | 1 | 0 | 100 |\n| `Clone` | 0 | 1 | 0 |\n"),
+        "{md}"
+    );
+    let html = std::fs::read_to_string(&html)?;
+    assert!(
+        html.contains("<tr><td><code>x</code></td><td>2</td><td>1</td><td>67</td></tr>"),
+        "{html}"
+    );
+    Ok(())
+}
+
+#[test]
+fn lsp_json_uses_zero_based_lines_and_utf16_columns() -> TestResult {
+    // (source line, bound, start character, end character) in UTF-16 code units.
+    let cases: &[(&str, &str, usize, usize)] = &[
+        ("fn a<T: Clone>() {}", "Clone", 8, 13),
+        ("/* 😀 */ fn b<T: Clone>() {}", "Clone", 17, 22),
+        ("/* 漢字 */ fn c<T: Copy>() {}", "Copy", 17, 21),
+        ("/* 😀漢 */ fn d<T: Send>() {}", "Send", 18, 22),
+        ("\tfn e<T: Sync>() {}", "Sync", 9, 13),
+    ];
+    let tmp = assert_fs::TempDir::new()?;
+    let file = tmp.path().join("lsp.rs");
+    let source: Vec<&str> = cases.iter().map(|c| c.0).collect();
+    std::fs::write(&file, format!("\u{feff}{}\n", source.join("\n")))?;
+
+    let out = Command::cargo_bin("trait-winnower")?
+        .args(["check", "--format", "lsp-json"])
+        .arg(&file)
+        .output()?;
+    assert!(out.status.success());
+    let json = parse_json(&String::from_utf8(out.stdout)?)?;
+    let files = json.arr();
+    assert_eq!(files.len(), 1);
+    assert!(files[0].get("uri").str().starts_with("file:///"));
+    assert!(files[0].get("uri").str().ends_with("/lsp.rs"));
+    let diagnostics = files[0].get("diagnostics").arr();
+    assert_eq!(diagnostics.len(), cases.len());
+    for (line, (d, (_, bound, start, end))) in diagnostics.iter().zip(cases).enumerate() {
+        let range = d.get("range");
+        assert_eq!(
+            range.get("start").get("line").num() as usize,
+            line,
+            "{bound}"
+        );
+        assert_eq!(range.get("end").get("line").num() as usize, line, "{bound}");
+        assert_eq!(
+            range.get("start").get("character").num() as usize,
+            *start,
+            "{bound}"
+        );
+        assert_eq!(
+            range.get("end").get("character").num() as usize,
+            *end,
+            "{bound}"
+        );
+        assert_eq!(d.get("severity").num() as usize, 2);
+        assert_eq!(d.get("data").get("kind").str(), "fn");
+        assert!(d.get("message").str().contains(bound));
+    }
+    Ok(())
+}
+
+#[test]
+fn shuffled_reports_render_identically_after_sort() -> TestResult {
+    use trait_winnower::analysis::ItemBounds;
+    use trait_winnower::report::html::{Html, HtmlSummary};
+    use trait_winnower::report::json::Json;
+    use trait_winnower::report::junit::Junit;
+    use trait_winnower::report::{FileReport, Removal, RemovalStatus, Report};
+
+    let sources = [
+        (
+            "src/b.rs",
+            "struct S<T: Clone + Send>(T);\nfn f<T: Copy>() where T: Clone + Sync {}\n",
+        ),
+        (
+            "src/a.rs",
+            "impl<T: Default> S<T> where T: Ord {}\ntrait Tr<U: Send> {}\n",
+        ),
+    ];
+    let mut files = Vec::new();
+    for (path, src) in sources {
+        let file = syn::parse_file(src)?;
+        let items = ItemBounds::collect_items_in_file(&file)?;
+        let mut report = FileReport::from_items(path.into(), &items);
+        report.removals = report
+            .findings
+            .iter()
+            .map(|f| Removal {
+                finding: f.clone(),
+                status: RemovalStatus::Retained,
+                before: String::new(),
+                after: String::new(),
+                check_time: Default::default(),
+                write_time: Default::default(),
+                scope: Vec::new(),
+                batch: None,
+                aborted: false,
+                cached: false,
+                blames: Vec::new(),
+                test_only: false,
+                slowdown: None,
+                follow_ups: Vec::new(),
+            })
+            .collect();
+        files.push(report);
+    }
+
+    let render = |report: &Report| -> Result<String, Box<dyn std::error::Error>> {
+        let mut out = Vec::new();
+        Junit::write(report, &mut out)?;
+        Json::write(report, "check", &mut out)?;
+        let summary = HtmlSummary {
+            command: "check",
+            target: std::path::Path::new("."),
+            config: None,
+        };
+        Html::write(report, &summary, &mut out)?;
+        Ok(String::from_utf8(out)?)
+    };
+
+    let mut canonical = Report {
+        files: files.clone(),
+        ..Report::default()
+    };
+    canonical.sort();
+    let expected = render(&canonical)?;
+    assert_eq!(canonical.files[0].path.to_str(), Some("src/a.rs"));
+    let b = &canonical.files[1].findings;
+    let order: Vec<_> = b.iter().map(|f| (f.line, f.bound.as_str())).collect();
+    assert_eq!(
+        order,
+        [
+            (1, "Clone"),
+            (1, "Send"),
+            (2, "Copy"),
+            (2, "Clone"),
+            (2, "Sync")
+        ]
+    );
+
+    // Deterministic shuffles: reversal and rotations of files, findings and removals.
+    for shift in 0..4 {
+        let mut shuffled = Report {
+            files: files.clone(),
+            ..Report::default()
+        };
+        shuffled.files.reverse();
+        shuffled.files.rotate_left(shift % 2);
+        for f in &mut shuffled.files {
+            f.findings.reverse();
+            let n = f.findings.len();
+            f.findings.rotate_left(shift % n);
+            f.removals.rotate_right(shift % n);
+        }
+        shuffled.sort();
+        assert_eq!(render(&shuffled)?, expected, "shift {shift}");
+    }
+    Ok(())
+}
+
+#[test]
+fn findings_reaching_one_file_by_two_paths_are_reported_once() -> TestResult {
+    use trait_winnower::analysis::ItemBounds;
+    use trait_winnower::report::{FileReport, Report};
+
+    let file = syn::parse_file("pub fn f<T: Clone>(t: T) -> T { t }\n")?;
+    let items = ItemBounds::collect_items_in_file(&file)?;
+    let mut report = Report {
+        files: ["a/src/common.rs", "b/src/common.rs", "a/src/own.rs"]
+            .into_iter()
+            .map(|p| FileReport::from_items(p.into(), &items))
+            .collect(),
+        ..Report::default()
+    };
+    // Both `common.rs` paths name `shared/common.rs`, compiled by `a` and `b`.
+    report.dedup_shared(|path| {
+        if path.ends_with("common.rs") {
+            ("shared/common.rs".into(), vec!["a".into(), "b".into()])
+        } else {
+            (path.to_path_buf(), vec!["a".into()])
+        }
+    });
+
+    let files: Vec<_> = report.files.iter().map(|f| f.path.to_str()).collect();
+    assert_eq!(files, [Some("a/src/common.rs"), Some("a/src/own.rs")]);
+    assert_eq!(report.total_findings(), 2);
+    assert_eq!(report.files[0].findings[0].packages, ["a", "b"]);
+    // One package is no news.
+    assert!(report.files[1].findings[0].packages.is_empty());
+    Ok(())
+}
@@ -0,0 +1,144 @@
+// tests/rustfix_tests.rs
+//! `check --format rustfix` prints rustc JSON diagnostics whose suggestions a rustfix-style
+//! applier can apply as they are.
+#![cfg(feature = "cli")]
+
+mod common;
+
+use assert_cmd::Command;
+use common::TestResult;
+use serde_json::Value;
+use std::path::Path;
+
+/// Duplicate and supertrait-implied bounds, which high-confidence rules flag, and a
+/// bound on a function with an empty body, which a heuristic flags.
+const SOURCE: &str = "\
+pub fn dup<T: Clone + Clone>(t: T) -> T {
+    t.clone()
+}
+
+pub fn implied<T>(t: T) -> T
+where
+    T: Copy + Clone,
+{
+    t.clone()
+}
+
+pub fn empty<T: Default>(_t: T) {}
+";
+
+fn diagnostics(root: &Path) -> Result<Vec<Value>, Box<dyn std::error::Error>> {
+    let out = Command::cargo_bin("trait-winnower")?
+        .args(["check", "-q", "--format", "rustfix"])
+        .arg(root)
+        .output()?;
+    assert!(
+        out.status.success(),
+        "{}",
+        String::from_utf8_lossy(&out.stderr)
+    );
+    let stdout = String::from_utf8(out.stdout)?;
+    Ok(stdout
+        .lines()
+        .map(serde_json::from_str)
+        .collect::<Result<_, _>>()?)
+}
+
+/// Apply the suggestions of `diagnostics` to `source` the way rustfix does: every
+/// replacement of the allowed applicabilities, skipping ones overlapping a replacement
+/// already taken.
+fn apply(source: &str, diagnostics: &[Value], allowed: &[&str]) -> String {
+    let mut replacements: Vec<(usize, usize, String)> = Vec::new();
+    for diagnostic in diagnostics {
+        for child in diagnostic["children"].as_array().into_iter().flatten() {
+            let spans: Vec<&Value> = child["spans"].as_array().into_iter().flatten().collect();
+            let applicable = spans.iter().all(|s| {
+                s["suggestion_applicability"]
+                    .as_str()
+                    .is_some_and(|a| allowed.contains(&a))
+            });
+            let parts: Vec<(usize, usize, String)> = spans
+                .iter()
+                .map(|s| {
+                    (
+                        s["byte_start"].as_u64().unwrap() as usize,
+                        s["byte_end"].as_u64().unwrap() as usize,
+                        s["suggested_replacement"].as_str().unwrap().to_owned(),
+                    )
+                })
+                .collect();
+            let overlaps = parts
+                .iter()
+                .any(|&(a, b, _)| replacements.iter().any(|&(c, d, _)| a < d && c < b));
+            if applicable && !overlaps {
+                replacements.extend(parts);
+            }
+        }
+    }
+    replacements.sort_by_key(|&(start, ..)| std::cmp::Reverse(start));
+    let mut out = source.to_owned();
+    for (start, end, text) in replacements {
+        out.replace_range(start..end, &text);
+    }
+    out
+}
+
+#[test]
+fn applied_suggestions_still_parse() -> TestResult {
+    let tmp = tempfile::tempdir()?;
+    let root = tmp.path();
+    std::fs::create_dir_all(root.join("src"))?;
+    std::fs::write(
+        root.join("Cargo.toml"),
+        "[package]\nname=\"x\"\nversion=\"0.1.0\"\nedition=\"2021\"\n",
+    )?;
+    std::fs::write(root.join("src/lib.rs"), SOURCE)?;
+
+    let found = diagnostics(root)?;
+    let applicability = |rule: &str| {
+        found
+            .iter()
+            .filter(|d| d["code"]["code"] == format!("trait_winnower::{rule}").as_str())
+            .map(|d| d["children"][0]["spans"][0]["suggestion_applicability"].clone())
+            .collect::<Vec<_>>()
+    };
+    assert_eq!(applicability("duplicate-bound"), ["MachineApplicable"]);
+    assert_eq!(
+        applicability("implied-by-supertrait"),
+        ["MachineApplicable"]
+    );
+    assert_eq!(applicability("unused-in-body"), ["MaybeIncorrect"]);
+    assert!(found.iter().all(|d| {
+        d["spans"][0]["file_name"]
+            .as_str()
+            .is_some_and(|f| f.ends_with("src/lib.rs"))
+    }));
+
+    let fixed = apply(SOURCE, &found, &["MachineApplicable"]);
+    syn::parse_file(&fixed)?;
+    assert!(
+        fixed.contains("pub fn dup<T: Clone>(t: T) -> T {"),
+        "{fixed}"
+    );
+    assert!(fixed.contains("    T: Copy,\n"), "{fixed}");
+    assert!(fixed.contains("pub fn empty<T: Default>"), "{fixed}");
+
+    let all = apply(SOURCE, &found, &["MachineApplicable", "MaybeIncorrect"]);
+    syn::parse_file(&all)?;
+    assert!(all.contains("pub fn empty<T>(_t: T) {}"), "{all}");
+    Ok(())
+}
+
+#[test]
+fn prune_has_no_rustfix_output() -> TestResult {
+    Command::cargo_bin("trait-winnower")?
+        .args([
+            "prune",
+            "--format",
+            "rustfix",
+            "tests/test_files/trait_sandbox",
+        ])
+        .assert()
+        .failure();
+    Ok(())
+}
@@ -0,0 +1,147 @@
+// tests/schedule_tests.rs
+//! `prune` starts with the files static rules flag most confidently and the packages
+//! cheapest to check, unless told to keep source order.
+#![cfg(feature = "cli")]
+
+mod common;
+
+use common::TestResult;
+use std::path::{Path, PathBuf};
+use trait_winnower::config::{Config, Order};
+use trait_winnower::findings::Confidence;
+use trait_winnower::observer::Observer;
+use trait_winnower::winnower::{PruneReport, Winnower};
+
+#[derive(Default)]
+struct Started(Vec<PathBuf>);
+
+impl Observer for Started {
+    fn on_file_start(&mut self, path: &Path) {
+        self.0.push(path.to_path_buf());
+    }
+}
+
+/// `big` is costly to check, `small` cheap, and `flagged` has a bound `Copy` implies.
+fn write_workspace(root: &Path) -> std::io::Result<()> {
+    std::fs::write(
+        root.join("Cargo.toml"),
+        "[workspace]\nmembers = [\"big\", \"flagged\", \"small\"]\nresolver = \"2\"\n",
+    )?;
+    let filler = "// filler\n".repeat(2000);
+    for (name, src) in [
+        (
+            "big",
+            format!("{filler}pub fn f<T: Clone>(t: T) -> T {{\n    t\n}}\n"),
+        ),
+        (
+            "flagged",
+            "pub fn h<T: Copy + Clone>(t: T) -> T {\n    t\n}\n".to_owned(),
+        ),
+        (
+            "small",
+            "pub fn g<T: Clone>(t: T) -> T {\n    t\n}\n".to_owned(),
+        ),
+    ] {
+        std::fs::create_dir_all(root.join(name).join("src"))?;
+        std::fs::write(
+            root.join(name).join("Cargo.toml"),
+            format!("[package]\nname = \"{name}\"\nversion = \"0.1.0\"\nedition = \"2021\"\n"),
+        )?;
+        std::fs::write(root.join(name).join("src/lib.rs"), src)?;
+    }
+    Ok(())
+}
+
+fn prune(
+    root: &Path,
+    order: Order,
+) -> Result<(PruneReport, Vec<PathBuf>), Box<dyn std::error::Error>> {
+    let mut started = Started::default();
+    let pruned = Winnower::builder()
+        .target(root)
+        .config(Config {
+            order,
+            ..Config::default()
+        })
+        .brute_force(true)
+        .build()?
+        .prune_with(&mut started)?;
+    let started = started
+        .0
+        .iter()
+        .map(|p| p.strip_prefix(root).unwrap_or(p).to_path_buf())
+        .collect();
+    Ok((pruned, started))
+}
+
+fn paths(names: &[&str]) -> Vec<PathBuf> {
+    names
+        .iter()
+        .map(|n| Path::new(n).join("src/lib.rs"))
+        .collect()
+}
+
+#[test]
+fn confident_and_cheap_files_go_first() -> TestResult {
+    let tmp = tempfile::tempdir()?;
+    write_workspace(tmp.path())?;
+    let (pruned, started) = prune(tmp.path(), Order::Priority)?;
+    assert_eq!(started, paths(&["flagged", "small", "big"]));
+    let schedule = pruned.report.schedule.expect("prune records its schedule");
+    assert_eq!(schedule.order, Order::Priority);
+    let recorded: Vec<PathBuf> = schedule.files.iter().map(|f| f.path.clone()).collect();
+    assert_eq!(recorded, started);
+    assert_eq!(schedule.files[0].confidence, Some(Confidence::High));
+    // The same rule flags `small` and `big`: cost decides.
+    assert_eq!(schedule.files[1].confidence, schedule.files[2].confidence);
+    assert!(schedule.files[1].cost < schedule.files[2].cost);
+
+    // The same tree again gets the same order.
+    write_workspace(tmp.path())?;
+    let (_, again) = prune(tmp.path(), Order::Priority)?;
+    assert_eq!(again, started);
+    Ok(())
+}
+
+#[test]
+fn source_order_keeps_discovery_order() -> TestResult {
+    let tmp = tempfile::tempdir()?;
+    write_workspace(tmp.path())?;
+    let (pruned, started) = prune(tmp.path(), Order::Source)?;
+    assert_eq!(started, paths(&["big", "flagged", "small"]));
+    let schedule = pruned.report.schedule.expect("prune records its schedule");
+    assert_eq!(schedule.order, Order::Source);
+    assert!(
+        schedule
+            .files
+            .iter()
+            .all(|f| f.confidence.is_none() && f.cost.is_none())
+    );
+    Ok(())
+}
+
+#[test]
+fn the_json_report_lists_the_schedule() -> TestResult {
+    use assert_cmd::Command;
+
+    let tmp = tempfile::tempdir()?;
+    write_workspace(tmp.path())?;
+    let out = Command::cargo_bin("trait-winnower")?
+        .args(["prune", "-b", "--format", "json"])
+        .arg(tmp.path())
+        .assert()
+        .success();
+    let stdout = String::from_utf8(out.get_output().stdout.clone())?;
+    let json: serde_json::Value = serde_json::from_str(&stdout)?;
+    assert_eq!(json["schedule"]["order"], "priority");
+    assert_eq!(
+        json["schedule"]["files"][0]["path"], "flagged/src/lib.rs",
+        "{stdout}"
+    );
+    assert_eq!(json["schedule"]["files"][0]["confidence"], "high");
+    assert!(
+        json["schedule"]["files"][2]["cost"].as_u64()
+            > json["schedule"]["files"][1]["cost"].as_u64()
+    );
+    Ok(())
+}
@@ -0,0 +1,129 @@
+// tests/semver_tests.rs
+//! The semver gate after `prune`: breaking changes put back removals on public items.
+#![cfg(feature = "discover")]
+
+mod common;
+
+use common::TestResult;
+use std::cell::RefCell;
+use std::path::Path;
+use std::rc::Rc;
+use trait_winnower::config::Config;
+use trait_winnower::dynamic_analysis::semver::{SemverGate, SemverOutcome};
+use trait_winnower::dynamic_analysis::validate::AlwaysOk;
+use trait_winnower::error::TraitError;
+use trait_winnower::report::RemovalStatus;
+use trait_winnower::winnower::{PruneReport, Winnower};
+
+const SRC: &str = "pub fn f<T: Clone>(t: T) -> T {\n    t\n}\n\n\
+                   fn g<U: Copy>(u: U) -> U {\n    u\n}\n";
+
+/// Answers with `outcome`, recording the baseline's `src/lib.rs` as it saw it.
+struct FixedGate {
+    outcome: SemverOutcome,
+    seen: Rc<RefCell<Vec<String>>>,
+}
+
+impl SemverGate for FixedGate {
+    fn compare(&self, _current: &Path, baseline: &Path) -> TraitError<SemverOutcome> {
+        let src = std::fs::read_to_string(baseline.join("src/lib.rs")).unwrap_or_default();
+        self.seen.borrow_mut().push(src);
+        Ok(self.outcome.clone())
+    }
+}
+
+fn prune(
+    dir: &Path,
+    semver_check: bool,
+    outcome: SemverOutcome,
+) -> TraitError<(PruneReport, Vec<String>)> {
+    let seen = Rc::new(RefCell::new(Vec::new()));
+    let config = Config {
+        semver_check,
+        ..Config::default()
+    };
+    let report = Winnower::builder()
+        .target(dir)
+        .config(config)
+        .validator(AlwaysOk)
+        .semver_gate(FixedGate {
+            outcome,
+            seen: Rc::clone(&seen),
+        })
+        .brute_force(true)
+        .build()?
+        .prune()?;
+    let seen = seen.borrow().clone();
+    Ok((report, seen))
+}
+
+#[test]
+fn breaking_change_puts_back_public_removals() -> TestResult {
+    let tmp = tempfile::tempdir()?;
+    common::write_crate(tmp.path(), "x", "", &[("src/lib.rs", SRC)])?;
+    let summary = "--- failure function_requirements_changed: `f` has stricter bounds ---";
+    let (report, seen) = prune(
+        tmp.path(),
+        true,
+        SemverOutcome::Breaking {
+            summary: summary.into(),
+        },
+    )?;
+
+    // The gate compared against the tree before pruning.
+    assert_eq!(seen, [SRC]);
+    let src = std::fs::read_to_string(tmp.path().join("src/lib.rs"))?;
+    assert!(src.contains("pub fn f<T: Clone>"), "{src}");
+    assert!(src.contains("fn g<U>"), "{src}");
+    assert_eq!(report.modified.len(), 1);
+
+    let semver = report.semver.expect("gate ran");
+    let reverted: Vec<&str> = semver.reverted.iter().map(|f| f.bound.as_str()).collect();
+    assert_eq!(reverted, ["Clone"]);
+    assert_eq!(semver.triggers, semver.reverted);
+    let statuses: Vec<(&str, RemovalStatus)> = report.report.files[0]
+        .removals
+        .iter()
+        .filter(|r| r.status != RemovalStatus::Skipped)
+        .map(|r| (r.finding.bound.as_str(), r.status))
+        .collect();
+    assert_eq!(
+        statuses,
+        [
+            ("Clone", RemovalStatus::Reverted),
+            ("Copy", RemovalStatus::Removed)
+        ]
+    );
+    Ok(())
+}
+
+#[test]
+fn compatible_or_unavailable_gates_keep_every_removal() -> TestResult {
+    for outcome in [
+        SemverOutcome::Compatible,
+        SemverOutcome::Unavailable {
+            reason: "`cargo semver-checks` is not installed".into(),
+        },
+    ] {
+        let tmp = tempfile::tempdir()?;
+        common::write_crate(tmp.path(), "x", "", &[("src/lib.rs", SRC)])?;
+        let (report, seen) = prune(tmp.path(), true, outcome.clone())?;
+        assert_eq!(seen.len(), 1);
+        let semver = report.semver.expect("gate ran");
+        assert_eq!(semver.outcome, outcome);
+        assert!(semver.reverted.is_empty());
+        let src = std::fs::read_to_string(tmp.path().join("src/lib.rs"))?;
+        assert!(!src.contains("Clone") && !src.contains("Copy"), "{src}");
+    }
+    Ok(())
+}
+
+#[test]
+fn semver_gate_runs_only_when_configured() -> TestResult {
+    let tmp = tempfile::tempdir()?;
+    common::write_crate(tmp.path(), "x", "", &[("src/lib.rs", SRC)])?;
+    let (report, seen) = prune(tmp.path(), false, SemverOutcome::Compatible)?;
+    assert!(seen.is_empty());
+    assert!(report.semver.is_none());
+    Ok(())
+}
@@ -0,0 +1,135 @@
+// tests/session_tests.rs
+//! `prune --record` saves every check; `--replay` reproduces the run from the recording
+//! without running cargo, and refuses a tree or a trial the recording does not cover.
+#![cfg(all(unix, feature = "discover"))]
+
+mod common;
+
+use common::TestResult;
+use std::os::unix::fs::PermissionsExt;
+use std::path::Path;
+use trait_winnower::config::Config;
+use trait_winnower::error::WinnowerError;
+use trait_winnower::report::RemovalStatus;
+use trait_winnower::winnower::{PruneReport, Winnower};
+
+const SRC: &str = "pub fn f<T: Clone + Default>(t: T) -> T {\n    t.clone()\n}\n";
+
+/// A configuration whose cargo logs to `checks.log` and fails on `check`, so a replay that
+/// runs a check shows.
+fn without_checks(root: &Path) -> Result<Config, Box<dyn std::error::Error>> {
+    let real = std::env::var("CARGO").unwrap_or_else(|_| "cargo".into());
+    let cargo = root.join("cargo-wrapper");
+    std::fs::write(
+        &cargo,
+        format!(
+            "#!/bin/sh\n\
+             [ \"$1\" = check ] && {{ echo \"$@\" >> '{}'; exit 99; }}\n\
+             exec \"{real}\" \"$@\"\n",
+            root.join("checks.log").display()
+        ),
+    )?;
+    std::fs::set_permissions(&cargo, std::fs::Permissions::from_mode(0o755))?;
+    let mut cfg = Config::default();
+    cfg.cargo_check.cargo_path = Some(cargo);
+    Ok(cfg)
+}
+
+fn statuses(report: &PruneReport) -> Vec<(String, RemovalStatus)> {
+    report
+        .report
+        .files
+        .iter()
+        .flat_map(|f| &f.removals)
+        .map(|r| (r.finding.bound.clone(), r.status))
+        .collect()
+}
+
+#[test]
+fn replay_reproduces_a_recorded_prune_without_cargo() -> TestResult {
+    let tmp = tempfile::tempdir()?;
+    let root = tmp.path().join("crate");
+    common::write_crate(&root, "r", "", &[("src/lib.rs", SRC)])?;
+    let recording = tmp.path().join("session.json");
+
+    let recorded = Winnower::builder()
+        .target(&root)
+        .config(Config::default())
+        .brute_force(true)
+        .record(&recording)
+        .build()?
+        .prune()?;
+    let pruned = std::fs::read_to_string(root.join("src/lib.rs"))?;
+    assert_ne!(pruned, SRC);
+    let doc: serde_json::Value = serde_json::from_str(&std::fs::read_to_string(&recording)?)?;
+    assert_eq!(doc["schema_version"], 1);
+    assert!(doc["toolchain"]["cargo"].is_string());
+    let checks = doc["checks"].as_array().ok_or("no checks")?;
+    assert!(!checks.is_empty());
+    assert!(checks.iter().all(|c| c["args"][0] == "check"));
+
+    std::fs::write(root.join("src/lib.rs"), SRC)?;
+    let replayed = Winnower::builder()
+        .target(&root)
+        .config(without_checks(tmp.path())?)
+        .brute_force(true)
+        .replay(&recording)
+        .build()?
+        .prune()?;
+    assert!(
+        !tmp.path().join("checks.log").exists(),
+        "replay ran cargo check"
+    );
+    assert_eq!(statuses(&replayed), statuses(&recorded));
+    assert_eq!(std::fs::read_to_string(root.join("src/lib.rs"))?, pruned);
+    Ok(())
+}
+
+#[test]
+fn replay_fails_loudly_off_the_recording() -> TestResult {
+    let tmp = tempfile::tempdir()?;
+    let root = tmp.path().join("crate");
+    common::write_crate(&root, "r", "", &[("src/lib.rs", SRC)])?;
+    let recording = tmp.path().join("session.json");
+    Winnower::builder()
+        .target(&root)
+        .config(Config::default())
+        .brute_force(true)
+        .record(&recording)
+        .build()?
+        .prune()?;
+    let replay = || {
+        Winnower::builder()
+            .target(&root)
+            .config(without_checks(tmp.path())?)
+            .brute_force(true)
+            .replay(&recording)
+            .build()?
+            .prune()
+            .map_err(Box::<dyn std::error::Error>::from)
+    };
+
+    // Another tree than the recording's.
+    let err = replay().err().ok_or("replayed a pruned tree")?;
+    let err = err
+        .downcast_ref::<WinnowerError>()
+        .ok_or("not a winnower error")?;
+    assert!(matches!(err, WinnowerError::Replay { .. }), "{err}");
+    assert!(err.to_string().contains("src/lib.rs"), "{err}");
+
+    // A trial the recording lacks.
+    std::fs::write(root.join("src/lib.rs"), SRC)?;
+    let mut doc: serde_json::Value = serde_json::from_str(&std::fs::read_to_string(&recording)?)?;
+    doc["checks"].as_array_mut().ok_or("no checks")?.pop();
+    std::fs::write(&recording, doc.to_string())?;
+    let err = replay().err().ok_or("replayed a trial never recorded")?;
+    let err = err
+        .downcast_ref::<WinnowerError>()
+        .ok_or("not a winnower error")?;
+    assert!(
+        matches!(err, WinnowerError::Replay { detail, .. } if detail.contains("no recorded `cargo check")),
+        "{err}"
+    );
+    assert!(!tmp.path().join("checks.log").exists());
+    Ok(())
+}
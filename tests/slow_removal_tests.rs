@@ -0,0 +1,130 @@
+// tests/slow_removal_tests.rs
+//! Removals whose check took many times a check of the unmodified crate are flagged as
+//! possibly slowing builds down and, with `reject_slow_removals`, put back.
+#![cfg(feature = "discover")]
+
+mod common;
+
+use common::TestResult;
+use std::path::Path;
+use std::time::Duration;
+use trait_winnower::config::Config;
+use trait_winnower::dynamic_analysis::validate::ScriptedValidator;
+use trait_winnower::report::RemovalStatus;
+use trait_winnower::winnower::{PruneReport, Winnower};
+
+const LIB: &str = "\
+pub fn a<T: Clone>(t: T) -> T {
+    t
+}
+pub fn b<T: Clone>(t: T) -> T {
+    t
+}
+pub fn c<T: Clone>(t: T) -> T {
+    t
+}
+";
+
+const FAST: Duration = Duration::from_millis(20);
+
+/// Prune with `a` removed, `b` kept and `c` removed by a check twenty times slower than
+/// the unmodified crate's, then, when `a` is applied again after `c` is put back, `a`
+/// accepted once more.
+fn prune(root: &Path, reject: bool) -> Result<PruneReport, Box<dyn std::error::Error>> {
+    let validator = ScriptedValidator::new([true, false, true, true])
+        .with_delays([FAST, FAST, Duration::from_millis(400)])
+        .with_baseline(FAST);
+    Ok(Winnower::builder()
+        .target(root)
+        .config(Config::default())
+        .brute_force(true)
+        .validator(validator)
+        .reject_slow_removals(reject)
+        .build()?
+        .prune()?)
+}
+
+#[test]
+fn a_slow_success_is_flagged_and_kept() -> TestResult {
+    let tmp = tempfile::tempdir()?;
+    common::write_crate(tmp.path(), "slow", "", &[("src/lib.rs", LIB)])?;
+    let pruned = prune(tmp.path(), false)?;
+
+    let slow = pruned.report.slow_removals.expect("c checked slowly");
+    assert!(!slow.reverted);
+    assert_eq!(slow.factor, 5.0);
+    assert_eq!(slow.removals.len(), 1);
+    assert_eq!(slow.removals[0].finding.locator.name, "c");
+    assert!(
+        slow.removals[0].slowdown > 5.0,
+        "{}",
+        slow.removals[0].slowdown
+    );
+    let removals = &pruned.report.files[0].removals;
+    let flagged: Vec<&str> = removals
+        .iter()
+        .filter(|r| r.slowdown.is_some())
+        .map(|r| r.finding.locator.name.as_str())
+        .collect();
+    assert_eq!(flagged, ["c"]);
+    assert_eq!(
+        std::fs::read_to_string(tmp.path().join("src/lib.rs"))?,
+        LIB.replace("a<T: Clone>", "a<T>")
+            .replace("c<T: Clone>", "c<T>")
+    );
+    Ok(())
+}
+
+#[test]
+fn rejecting_slow_removals_puts_them_back() -> TestResult {
+    let tmp = tempfile::tempdir()?;
+    common::write_crate(tmp.path(), "slow", "", &[("src/lib.rs", LIB)])?;
+    let pruned = prune(tmp.path(), true)?;
+
+    assert!(
+        pruned
+            .report
+            .slow_removals
+            .expect("c checked slowly")
+            .reverted
+    );
+    let status = |name: &str| {
+        pruned.report.files[0]
+            .removals
+            .iter()
+            .find(|r| r.finding.locator.name == name)
+            .map(|r| r.status)
+    };
+    assert_eq!(status("a"), Some(RemovalStatus::Removed));
+    assert_eq!(status("b"), Some(RemovalStatus::Retained));
+    assert_eq!(status("c"), Some(RemovalStatus::Reverted));
+    assert_eq!(
+        std::fs::read_to_string(tmp.path().join("src/lib.rs"))?,
+        LIB.replace("a<T: Clone>", "a<T>")
+    );
+    Ok(())
+}
+
+#[test]
+fn a_lone_slow_trial_is_flagged_against_the_unmodified_crate() -> TestResult {
+    let tmp = tempfile::tempdir()?;
+    let lib = "pub fn c<T: Clone>(t: T) -> T {\n    t\n}\n";
+    common::write_crate(tmp.path(), "slow", "", &[("src/lib.rs", lib)])?;
+    let validator = ScriptedValidator::new([true])
+        .with_delay(Duration::from_millis(400))
+        .with_baseline(FAST);
+    let pruned = Winnower::builder()
+        .target(tmp.path())
+        .config(Config::default())
+        .brute_force(true)
+        .validator(validator)
+        .build()?
+        .prune()?;
+
+    // With no other trial to compare with, the baseline check is what shows it slow.
+    let slow = pruned.report.slow_removals.expect("c checked slowly");
+    assert_eq!(slow.baseline, FAST);
+    assert_eq!(slow.removals.len(), 1);
+    assert_eq!(slow.removals[0].finding.locator.name, "c");
+    Ok(())
+}
@@ -0,0 +1,193 @@
+// tests/strategy_tests.rs
+//! `prune --strategy` on the bench fixture, validated by its oracle: each preset makes the
+//! checks it stands for, and flags contradicting it are rejected.
+#![cfg(feature = "cli")]
+
+mod common;
+
+use assert_cmd::Command;
+use common::TestResult;
+use predicates::str::contains;
+use std::path::Path;
+use trait_winnower::bench::Oracle;
+use trait_winnower::config::{Config, Order};
+use trait_winnower::formats::ReportDoc;
+use trait_winnower::report::RemovalStatus;
+use trait_winnower::report::json::Json;
+use trait_winnower::strategy::{Strategy, StrategyName, StrategyOverrides};
+use trait_winnower::winnower::Winnower;
+
+const FIXTURE: &str = "tests/test_files/bench_fixture";
+
+fn copy_fixture(dst: &Path) -> std::io::Result<()> {
+    std::fs::create_dir_all(dst.join("src"))?;
+    for file in [
+        "Cargo.toml",
+        "src/lib.rs",
+        "src/convert.rs",
+        "src/shapes.rs",
+        "src/store.rs",
+        "src/util.rs",
+    ] {
+        std::fs::copy(Path::new(FIXTURE).join(file), dst.join(file))?;
+    }
+    Ok(())
+}
+
+/// Validator calls and removals of a prune of a fresh copy of the fixture with `name` and
+/// `config`, and the strategy its JSON report echoes.
+fn run(
+    name: StrategyName,
+    config: Option<&Config>,
+) -> Result<(usize, usize, Option<Strategy>), Box<dyn std::error::Error>> {
+    let tmp = tempfile::tempdir()?;
+    copy_fixture(tmp.path())?;
+    if let Some(config) = config {
+        std::fs::write(
+            tmp.path().join(".trait-winnower.toml"),
+            toml::to_string(config)?,
+        )?;
+    }
+    let oracle = Oracle::new(tmp.path())?;
+    let pruned = Winnower::builder()
+        .target(tmp.path())
+        .validator(oracle.clone())
+        .strategy(name)
+        .build()?
+        .prune()?;
+    let mut json = Vec::new();
+    Json::write(&pruned.report, "prune", &mut json)?;
+    let doc = ReportDoc::from_json(Path::new("report.json"), std::str::from_utf8(&json)?)?;
+    Ok((
+        oracle.calls(),
+        pruned.report.count_removals(RemovalStatus::Removed),
+        doc.strategy,
+    ))
+}
+
+// Regression guards, like `bench_tests`: change these only with a reason.
+
+#[test]
+fn thorough_checks_every_candidate_alone_then_retries_the_retained() -> TestResult {
+    let (checks, removed, echoed) = run(StrategyName::Thorough, None)?;
+    assert_eq!((checks, removed), (45, 32));
+    assert_eq!(echoed, Some(Strategy::preset(StrategyName::Thorough)));
+    Ok(())
+}
+
+#[test]
+fn fast_batches_and_screens_candidates() -> TestResult {
+    let (checks, removed, echoed) = run(StrategyName::Fast, None)?;
+    assert_eq!((checks, removed), (36, 32));
+    assert_eq!(echoed, Some(Strategy::preset(StrategyName::Fast)));
+    Ok(())
+}
+
+#[test]
+fn ci_keeps_source_order_and_skips_the_unlock_pass() -> TestResult {
+    let (checks, removed, echoed) = run(StrategyName::Ci, None)?;
+    assert_eq!((checks, removed), (42, 32));
+    let echoed = echoed.ok_or("no strategy in the report")?;
+    assert_eq!((echoed.order, echoed.unlocks), (Order::Source, false));
+    Ok(())
+}
+
+#[test]
+fn static_runs_no_trials() -> TestResult {
+    let (checks, removed, echoed) = run(StrategyName::Static, None)?;
+    assert_eq!((checks, removed), (0, 0));
+    assert_eq!(echoed.map(|s| s.trials), Some(false));
+    Ok(())
+}
+
+#[test]
+fn the_strategy_table_adjusts_a_preset() -> TestResult {
+    let mut config = Config::default();
+    config.strategy.fast = StrategyOverrides {
+        batch_size: Some(1),
+        unlocks: Some(false),
+        ..StrategyOverrides::default()
+    };
+    let (checks, removed, echoed) = run(StrategyName::Fast, Some(&config))?;
+    assert_eq!(removed, 32);
+    assert!(checks < 45, "{checks}");
+    let echoed = echoed.ok_or("no strategy in the report")?;
+    assert_eq!((echoed.batch_size, echoed.unlocks), (1, false));
+    assert!(echoed.prefilter);
+
+    let tmp = tempfile::tempdir()?;
+    copy_fixture(tmp.path())?;
+    std::fs::write(
+        tmp.path().join(".trait-winnower.toml"),
+        toml::to_string(&Config::default())? + "\n[strategy.fast]\nbatch = 4\n",
+    )?;
+    let err = Winnower::builder()
+        .target(tmp.path())
+        .strategy(StrategyName::Fast)
+        .build()
+        .err()
+        .ok_or("unknown key accepted")?;
+    assert!(err.to_string().contains("batch"), "{err}");
+    Ok(())
+}
+
+#[test]
+fn flags_contradicting_the_strategy_are_rejected() -> TestResult {
+    let tmp = tempfile::tempdir()?;
+    copy_fixture(tmp.path())?;
+    Command::cargo_bin("trait-winnower")?
+        .current_dir(tmp.path())
+        .args(["prune", "--strategy", "static", "--brute-force"])
+        .assert()
+        .failure()
+        .stderr(contains("--strategy static runs no trials"));
+    Command::cargo_bin("trait-winnower")?
+        .current_dir(tmp.path())
+        .args(["prune", "--strategy", "fast", "--batch-size", "2"])
+        .assert()
+        .failure()
+        .stderr(contains(
+            "--batch-size 2 contradicts --strategy fast, which uses 8",
+        ));
+    Command::cargo_bin("trait-winnower")?
+        .current_dir(tmp.path())
+        .args([
+            "prune",
+            "--strategy",
+            "thorough",
+            "--assume-consistent",
+            "true",
+        ])
+        .assert()
+        .failure()
+        .stderr(contains("under [strategy.thorough]"));
+    Ok(())
+}
+
+#[test]
+fn static_prune_removes_what_check_fix_would() -> TestResult {
+    let tmp = tempfile::tempdir()?;
+    std::fs::create_dir_all(tmp.path().join("src"))?;
+    std::fs::write(
+        tmp.path().join("Cargo.toml"),
+        "[package]\nname=\"static\"\nversion=\"0.1.0\"\nedition=\"2021\"\n\n[workspace]\n",
+    )?;
+    std::fs::write(
+        tmp.path().join("src/lib.rs"),
+        "pub fn dup<T: Clone + Clone>(t: &T) -> T {\n    t.clone()\n}\n",
+    )?;
+    Command::cargo_bin("trait-winnower")?
+        .current_dir(tmp.path())
+        .args(["prune", "--strategy", "static"])
+        .assert()
+        .success()
+        .stderr(contains("strategy: static (no trials)"))
+        .stderr(contains(
+            "removed 1 bound(s) in 1 file(s), without running cargo",
+        ));
+    assert_eq!(
+        std::fs::read_to_string(tmp.path().join("src/lib.rs"))?,
+        "pub fn dup<T: Clone>(t: &T) -> T {\n    t.clone()\n}\n"
+    );
+    Ok(())
+}
@@ -0,0 +1,99 @@
+// tests/suggestions_tests.rs
+//! `prune --format suggestions` prints GitHub suggestions for what it would remove and
+//! leaves the crate as it was.
+#![cfg(feature = "cli")]
+
+mod common;
+
+use assert_cmd::Command;
+use common::TestResult;
+use std::path::Path;
+
+fn copy_dir(from: &Path, to: &Path) -> std::io::Result<()> {
+    std::fs::create_dir_all(to)?;
+    for entry in std::fs::read_dir(from)? {
+        let entry = entry?;
+        let path = entry.path();
+        if path.is_dir() {
+            copy_dir(&path, &to.join(entry.file_name()))?;
+        } else {
+            std::fs::copy(&path, to.join(entry.file_name()))?;
+        }
+    }
+    Ok(())
+}
+
+fn suggestions(target: &Path) -> Result<String, Box<dyn std::error::Error>> {
+    let out = Command::cargo_bin("trait-winnower")?
+        .args(["prune", "-q", "-n", "all", "-b", "--format", "suggestions"])
+        .arg(target)
+        .output()?;
+    assert!(
+        out.status.success(),
+        "{}",
+        String::from_utf8_lossy(&out.stderr)
+    );
+    Ok(String::from_utf8(out.stdout)?)
+}
+
+#[test]
+fn sandbox_suggestions_match_the_golden_file() -> TestResult {
+    let fixture = Path::new("tests/test_files/trait_sandbox");
+    let tmp = tempfile::tempdir()?;
+    copy_dir(fixture, tmp.path())?;
+
+    let printed = suggestions(tmp.path())?;
+    let golden = std::fs::read_to_string("tests/expected/suggestions/trait_sandbox.json")?;
+    assert_eq!(printed, golden);
+    for file in ["a.rs", "b.rs", "c.rs", "traits.rs"] {
+        assert_eq!(
+            std::fs::read_to_string(tmp.path().join("src").join(file))?,
+            std::fs::read_to_string(fixture.join("src").join(file))?,
+            "{file} was left modified"
+        );
+    }
+    Ok(())
+}
+
+#[test]
+fn paths_are_relative_to_the_repository() -> TestResult {
+    let tmp = tempfile::tempdir()?;
+    let root = tmp.path().join("crates/x");
+    std::fs::create_dir_all(root.join("src"))?;
+    std::fs::write(
+        root.join("Cargo.toml"),
+        "[package]\nname=\"x\"\nversion=\"0.1.0\"\nedition=\"2021\"\n\n[workspace]\n",
+    )?;
+    let source = "pub fn f<T>(t: T) -> T\nwhere\n    T: Clone,\n{\n    t\n}\n";
+    std::fs::write(root.join("src/lib.rs"), source)?;
+    let init = std::process::Command::new("git")
+        .args(["init", "-q"])
+        .current_dir(tmp.path())
+        .status()?;
+    assert!(init.success());
+
+    let printed = suggestions(&root)?;
+    assert!(
+        printed.contains(r#""path": "crates/x/src/lib.rs""#),
+        "{printed}"
+    );
+    assert!(printed.contains(r#""start_line": 1"#), "{printed}");
+    assert!(printed.contains(r#""line": 3"#), "{printed}");
+    assert!(printed.contains(r#""body": "```suggestion\npub fn f<T>(t: T) -> T\n```""#));
+    assert_eq!(std::fs::read_to_string(root.join("src/lib.rs"))?, source);
+    Ok(())
+}
+
+#[test]
+fn check_has_no_suggestions() -> TestResult {
+    Command::cargo_bin("trait-winnower")?
+        .args([
+            "check",
+            "--format",
+            "suggestions",
+            "tests/test_files/trait_sandbox",
+        ])
+        .assert()
+        .failure();
+    Ok(())
+}
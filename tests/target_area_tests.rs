@@ -0,0 +1,126 @@
+// tests/target_area_tests.rs
+//! `analyze_tests`, `analyze_examples` and `analyze_benches` pick which of those areas
+//! discovery takes in, and pruning one validates with the flag that builds it.
+#![cfg(feature = "discover")]
+
+mod common;
+
+use common::TestResult;
+use std::path::{Path, PathBuf};
+use trait_winnower::config::{Config, TargetArea};
+use trait_winnower::discover::SkipReason;
+use trait_winnower::winnower::Winnower;
+
+const EXAMPLE: &str = "\
+fn show<T: Clone + std::fmt::Debug>(t: T) {
+    println!(\"{t:?}\");
+}
+fn dup<T: Clone>(t: &T) -> T {
+    t.clone()
+}
+fn main() {
+    show(dup(&1));
+}
+";
+
+const FILES: &[(&str, &str)] = &[
+    ("src/lib.rs", "pub fn lib() {}\n"),
+    ("examples/demo.rs", EXAMPLE),
+    (
+        "tests/it.rs",
+        "fn id<T: Copy + Clone>(t: T) -> T {\n    t\n}\n\n#[test]\nfn it() {\n    id(1);\n}\n",
+    ),
+];
+
+/// The analyzed files outside `src/`.
+fn analyzed(root: &Path, config: Config) -> Result<Vec<PathBuf>, Box<dyn std::error::Error>> {
+    let checked = Winnower::builder()
+        .target(root)
+        .config(config)
+        .build()?
+        .check()?;
+    let mut files: Vec<PathBuf> = checked
+        .report
+        .files
+        .iter()
+        .map(|f| f.path.clone())
+        .filter(|p| !p.starts_with("src"))
+        .collect();
+    files.sort();
+    Ok(files)
+}
+
+#[test]
+fn areas_are_analyzed_as_configured() -> TestResult {
+    let tmp = tempfile::tempdir()?;
+    common::write_crate(tmp.path(), "areas", "", FILES)?;
+    assert_eq!(
+        analyzed(tmp.path(), Config::default())?,
+        [PathBuf::from("examples/demo.rs")]
+    );
+    let config = Config {
+        analyze_tests: true,
+        analyze_examples: false,
+        ..Config::default()
+    };
+    assert_eq!(
+        analyzed(tmp.path(), config.clone())?,
+        [PathBuf::from("tests/it.rs")]
+    );
+    let checked = Winnower::builder()
+        .target(tmp.path())
+        .config(config)
+        .build()?
+        .check()?;
+    let demo = checked
+        .skipped
+        .iter()
+        .find(|s| s.path.ends_with("examples/demo.rs"))
+        .expect("the example is skipped");
+    assert_eq!(
+        demo.reason,
+        SkipReason::LeftOut {
+            area: TargetArea::Examples
+        }
+    );
+    assert_eq!(
+        demo.reason.to_string(),
+        "in `examples/`, which `analyze_examples` leaves out"
+    );
+    Ok(())
+}
+
+#[test]
+fn pruning_an_example_validates_it() -> TestResult {
+    let tmp = tempfile::tempdir()?;
+    common::write_crate(tmp.path(), "areas", "", FILES)?;
+    let mut config = Config::default();
+    // Without `--examples` added, `dup` would lose the `Clone` it needs.
+    config.cargo_check.args = vec!["--lib".into(), "--quiet".into()];
+    let pruned = Winnower::builder()
+        .target(tmp.path())
+        .config(config)
+        .brute_force(true)
+        .build()?
+        .prune()?;
+    let validation = pruned.report.validation.expect("cargo validated");
+    assert_eq!(validation.args, ["--lib", "--quiet", "--examples"]);
+    assert_eq!(
+        std::fs::read_to_string(tmp.path().join("examples/demo.rs"))?,
+        EXAMPLE.replace(
+            "show<T: Clone + std::fmt::Debug>",
+            "show<T: std::fmt::Debug>"
+        )
+    );
+
+    let out = std::process::Command::new(env!("CARGO"))
+        .args(["build", "--examples", "--quiet"])
+        .current_dir(tmp.path())
+        .output()?;
+    assert!(
+        out.status.success(),
+        "{}",
+        String::from_utf8_lossy(&out.stderr)
+    );
+    Ok(())
+}
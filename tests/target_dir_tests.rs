@@ -0,0 +1,77 @@
+// tests/target_dir_tests.rs
+//! Validation builds in a target directory of its own, leaving the user's `target/` alone.
+#![cfg(feature = "cli")]
+
+mod common;
+
+use common::TestResult;
+use trait_winnower::config::Config;
+use trait_winnower::winnower::{PruneReport, Winnower, WinnowerBuilder};
+
+const LIB: &str = "pub fn f<T: Clone>(t: T) -> T {\n    t\n}\n";
+
+fn prune(builder: WinnowerBuilder) -> Result<PruneReport, Box<dyn std::error::Error>> {
+    Ok(builder.brute_force(true).build()?.prune()?)
+}
+
+#[test]
+fn checks_build_outside_the_users_target_directory() -> TestResult {
+    let tmp = tempfile::tempdir()?;
+    common::write_crate(tmp.path(), "x", "", &[("src/lib.rs", LIB)])?;
+    let pruned = prune(Winnower::builder().target(tmp.path()))?;
+    let dir = pruned
+        .report
+        .validation
+        .and_then(|v| v.target_dir)
+        .expect("cargo ran");
+    assert_eq!(dir.path, tmp.path().join("target/trait-winnower-check"));
+    assert!(dir.kept);
+    assert!(dir.bytes > 0);
+    assert!(dir.path.join("debug").is_dir());
+    assert!(!tmp.path().join("target/debug").exists());
+    Ok(())
+}
+
+#[test]
+fn the_directory_is_configurable_and_can_be_removed() -> TestResult {
+    let tmp = tempfile::tempdir()?;
+    common::write_crate(tmp.path(), "x", "", &[("src/lib.rs", LIB)])?;
+    let mut config = Config::default();
+    config.cargo_check.target_dir = "build/check".into();
+    let pruned = prune(
+        Winnower::builder()
+            .target(tmp.path())
+            .config(config)
+            .keep_target_dir(false),
+    )?;
+    let dir = pruned
+        .report
+        .validation
+        .and_then(|v| v.target_dir)
+        .expect("cargo ran");
+    assert_eq!(dir.path, tmp.path().join("build/check"));
+    assert!(!dir.kept);
+    assert!(dir.bytes > 0, "measured before removal");
+    assert!(!dir.path.exists());
+    assert_eq!(pruned.modified.len(), 1);
+    Ok(())
+}
+
+#[test]
+fn the_cli_reports_the_directory_and_its_size() -> TestResult {
+    use assert_cmd::Command;
+    use predicates::str::contains;
+
+    let tmp = tempfile::tempdir()?;
+    common::write_crate(tmp.path(), "x", "", &[("src/lib.rs", LIB)])?;
+    Command::cargo_bin("trait-winnower")?
+        .args(["prune", "-b", "--keep-target-dir=false"])
+        .arg(tmp.path())
+        .assert()
+        .success()
+        .stderr(contains("checked in "))
+        .stderr(contains("trait-winnower-check ("))
+        .stderr(contains(" MiB, removed)"));
+    assert!(!tmp.path().join("target/trait-winnower-check").exists());
+    Ok(())
+}
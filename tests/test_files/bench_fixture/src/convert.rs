@@ -0,0 +1,19 @@
+use std::fmt::Display;
+
+/// bench: keep
+pub fn render<T: Display + Clone>(t: T) -> String {
+    format!("{}", t.clone())
+}
+
+pub fn passthrough<T: Into<u64> + Copy + Default>(t: T) -> T {
+    t
+}
+
+/// bench: keep
+pub fn widen<T: Into<u64> + Copy>(t: T) -> (u64, u64) {
+    (t.into(), t.into())
+}
+
+pub fn cloned<T: Clone + Sync + Send>(t: T) -> T {
+    t
+}
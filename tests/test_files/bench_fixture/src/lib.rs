@@ -0,0 +1,7 @@
+//! Standard fixture for `trait-winnower bench`: bounds of items documented `/// bench: keep`
+//! are needed, every other bound can go.
+
+pub mod convert;
+pub mod shapes;
+pub mod store;
+pub mod util;
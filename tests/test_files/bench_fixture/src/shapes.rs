@@ -0,0 +1,20 @@
+use std::fmt::Debug;
+use std::hash::Hash;
+
+pub struct Point<T: Copy + Default>(pub T, pub T);
+
+pub struct Tagged<T: Clone + Debug, U: Hash>(pub T, pub U);
+
+pub fn origin<T: Copy + Default + Debug>(t: T) -> T {
+    t
+}
+
+pub fn swap<A: Clone, B: Clone>(a: A, b: B) -> (B, A) {
+    (b, a)
+}
+
+pub fn tag<T: Debug + Send, U: Sync>(t: T, u: U) -> (T, U) {
+    (t, u)
+}
+
+pub fn unit<T: PartialOrd + Ord>(_: T) {}
@@ -0,0 +1,34 @@
+pub trait Store {
+    /// bench: keep
+    fn put<T: Clone + Default>(&self, t: T) -> T;
+    /// bench: keep
+    fn get<T: Default>(&self) -> T;
+    fn name<T: ToString>(&self, t: T) -> T;
+}
+
+pub struct Memory;
+
+impl Store for Memory {
+    /// bench: keep
+    fn put<T: Clone + Default>(&self, t: T) -> T {
+        let _ = T::default();
+        t.clone()
+    }
+
+    /// bench: keep
+    fn get<T: Default>(&self) -> T {
+        T::default()
+    }
+
+    fn name<T: ToString>(&self, t: T) -> T {
+        t
+    }
+}
+
+pub struct Wrapper<T>(pub T);
+
+impl<T: Clone + Send + Sync> Wrapper<T> {
+    pub fn inner(&self) -> &T {
+        &self.0
+    }
+}
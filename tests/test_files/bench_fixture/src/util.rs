@@ -0,0 +1,25 @@
+use std::fmt::Debug;
+use std::hash::Hash;
+
+pub fn first<T: Clone>(t: T) -> T {
+    t
+}
+
+/// bench: keep
+pub fn pair<T: Clone>(t: T) -> (T, T) {
+    (t.clone(), t)
+}
+
+pub fn show<T: Debug + Default>(t: T) -> T {
+    t
+}
+
+pub fn key<K: Hash + Eq + Ord>(k: K) -> K {
+    k
+}
+
+/// bench: keep
+pub fn same<T: PartialEq + Debug>(a: T, b: T) -> bool {
+    println!("{a:?} {b:?}");
+    a == b
+}
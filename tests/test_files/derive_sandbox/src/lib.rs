@@ -0,0 +1,14 @@
+use serde::Serialize;
+
+/// The derive infers `T: Serialize` on its own; the bound on the struct is not needed.
+#[derive(Serialize)]
+pub struct Inferred<T: Serialize> {
+    pub inner: T,
+}
+
+/// `bound = ""` turns inference off: the derived impl relies on the struct's bound.
+#[derive(Serialize)]
+#[serde(bound = "")]
+pub struct Explicit<T: Serialize> {
+    pub inner: T,
+}
@@ -0,0 +1,5 @@
+mod inner;
+
+pub fn a_fn<T: Copy>(t: T) -> T {
+    t
+}
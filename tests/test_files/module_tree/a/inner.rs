@@ -0,0 +1,3 @@
+pub fn inner_fn<T: Default>() -> T {
+    T::default()
+}
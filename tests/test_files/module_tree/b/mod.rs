@@ -0,0 +1,3 @@
+pub fn b_fn<T: std::fmt::Debug>(t: T) {
+    let _ = format!("{t:?}");
+}
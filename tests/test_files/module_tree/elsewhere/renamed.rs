@@ -0,0 +1,3 @@
+pub fn c_fn<T: Send>(t: T) -> T {
+    t
+}
@@ -0,0 +1,3 @@
+pub fn deep_fn<T: Sync>(t: T) -> T {
+    t
+}
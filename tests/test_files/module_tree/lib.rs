@@ -0,0 +1,13 @@
+mod a;
+mod b;
+#[path = "elsewhere/renamed.rs"]
+mod c;
+mod missing;
+
+mod inline {
+    mod deep;
+}
+
+pub fn root_fn<T: Clone>(t: T) -> T {
+    t.clone()
+}
@@ -0,0 +1,39 @@
+//! Many items in one file: each removal shifts the text after it, so the edits that follow
+//! must find their items again rather than trust offsets taken before.
+
+pub mod shifted;
+
+pub fn a<T: Clone + Copy + Send + Sync>(t: T) -> T {
+    t
+}
+
+pub fn b<T: Clone>(t: &T) -> T {
+    t.clone()
+}
+
+pub fn c<T: Default + Send>() -> T {
+    T::default()
+}
+
+pub struct Pair<A, B>(pub A, pub B);
+
+impl<A: Clone + Send, B: Clone + Sync> Pair<A, B> {
+    pub fn swap(&self) -> Pair<B, A> {
+        Pair(self.1.clone(), self.0.clone())
+    }
+
+    pub fn left<C: Send>(&self, _c: C) -> A {
+        self.0.clone()
+    }
+}
+
+pub fn d<T>(t: T) -> T
+where
+    T: Clone + Default,
+{
+    t
+}
+
+pub fn e<T: PartialEq + Eq>(x: &T, y: &T) -> bool {
+    x == y
+}
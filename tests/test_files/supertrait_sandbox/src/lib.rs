@@ -0,0 +1,69 @@
+//! Supertraits: bounds a supertrait already implies, and supertraits nothing needs.
+
+use core::fmt::Debug;
+
+pub trait Shape: Debug {
+    fn area(&self) -> u32;
+}
+
+pub trait Solid: Shape {
+    fn depth(&self) -> u32;
+}
+
+/// `Clone` is a supertrait nothing relies on.
+pub trait Named: Clone {
+    fn name(&self) -> &'static str;
+}
+
+#[derive(Clone, Debug)]
+pub struct Cube(pub u32);
+
+impl Shape for Cube {
+    fn area(&self) -> u32 {
+        self.0 * self.0
+    }
+}
+
+impl Solid for Cube {
+    fn depth(&self) -> u32 {
+        self.0
+    }
+}
+
+impl Named for Cube {
+    fn name(&self) -> &'static str {
+        "cube"
+    }
+}
+
+/// `Shape` and `Debug` are implied by `Solid`.
+pub fn volume<T: Solid + Shape + Debug>(t: &T) -> u32 {
+    t.area() * t.depth()
+}
+
+/// `Solid` is needed for `depth`; the `Debug` formatting comes through it.
+pub fn describe<T>(t: &T) -> String
+where
+    T: Solid,
+{
+    format!("{t:?} {}", t.depth())
+}
+
+/// `Named` is never used here.
+pub fn label<T: Shape + Named>(t: &T) -> u32 {
+    t.area()
+}
+
+#[cfg(test)]
+mod smoke {
+    use super::*;
+
+    #[test]
+    fn it_compiles_and_runs() {
+        let c = Cube(2);
+        assert_eq!(volume(&c), 8);
+        assert_eq!(describe(&c), "Cube(2) 2");
+        assert_eq!(label(&c), 4);
+        assert_eq!(c.name(), "cube");
+    }
+}
@@ -0,0 +1,170 @@
+// tests/test_only_tests.rs
+//! `prune --explain-test-only`: retained bounds that only `#[cfg(test)]` code needs are
+//! tried again without test targets and reported as such; the tree stays as pruned.
+#![cfg(feature = "cli")]
+
+mod common;
+
+use common::TestResult;
+use std::path::Path;
+use trait_winnower::config::{CargoCheckConfig, Config};
+use trait_winnower::formats::ReportDoc;
+use trait_winnower::report::RemovalStatus;
+use trait_winnower::report::json::Json;
+use trait_winnower::winnower::{PruneReport, Winnower};
+
+const LIB: &str = "\
+pub trait Shape
+where
+    Self: Clone,
+{
+    fn sides(&self) -> usize;
+}
+
+pub fn copy_all<T: Clone>(items: &[T]) -> Vec<T> {
+    items.to_vec()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::Shape;
+
+    fn twice<S: Shape>(s: &S) -> usize {
+        let copy: S = s.clone();
+        copy.sides() * 2
+    }
+
+    #[derive(Clone)]
+    struct Square;
+
+    impl Shape for Square {
+        fn sides(&self) -> usize {
+            4
+        }
+    }
+
+    #[test]
+    fn doubles() {
+        assert_eq!(twice(&Square), 8);
+    }
+}
+";
+
+fn write_crate(root: &Path, args: Option<&[&str]>) -> TestResult {
+    common::write_crate(root, "tested", "", &[("src/lib.rs", LIB)])?;
+    if let Some(args) = args {
+        let cfg = Config {
+            cargo_check: CargoCheckConfig {
+                args: args.iter().map(|a| a.to_string()).collect(),
+                ..CargoCheckConfig::default()
+            },
+            ..Config::default()
+        };
+        std::fs::write(root.join(".trait-winnower.toml"), toml::to_string(&cfg)?)?;
+    }
+    Ok(())
+}
+
+fn prune(root: &Path, explain: bool) -> Result<PruneReport, Box<dyn std::error::Error>> {
+    Ok(Winnower::builder()
+        .target(root)
+        .brute_force(true)
+        .explain_test_only(explain)
+        .build()?
+        .prune()?)
+}
+
+/// `(item, bound, status, test only)` of every removal, sorted.
+fn outcomes(pruned: &PruneReport) -> Vec<(String, String, RemovalStatus, bool)> {
+    let mut out: Vec<_> = pruned
+        .report
+        .files
+        .iter()
+        .flat_map(|f| &f.removals)
+        .map(|r| {
+            (
+                r.finding.item.clone(),
+                r.finding.bound.clone(),
+                r.status,
+                r.test_only,
+            )
+        })
+        .collect();
+    out.sort_by(|a, b| (&a.0, &a.1).cmp(&(&b.0, &b.1)));
+    out.dedup_by(|a, b| (&a.0, &a.1) == (&b.0, &b.1));
+    out
+}
+
+#[test]
+fn bounds_only_tests_need_are_explained() -> TestResult {
+    let tmp = tempfile::tempdir()?;
+    write_crate(tmp.path(), None)?;
+    let pruned = prune(tmp.path(), true)?;
+    let row = |item: &str, bound: &str, status, test_only| {
+        (item.to_owned(), bound.to_owned(), status, test_only)
+    };
+    assert_eq!(
+        outcomes(&pruned),
+        [
+            row("fn copy_all", "Clone", RemovalStatus::Retained, false),
+            // `mod tests` is not built without test targets; its bounds are not tried.
+            row("tests fn twice", "Shape", RemovalStatus::Retained, false),
+            row("trait Shape", "Clone", RemovalStatus::Retained, true),
+        ]
+    );
+    // The diagnosis moves nothing.
+    assert_eq!(std::fs::read_to_string(tmp.path().join("src/lib.rs"))?, LIB);
+
+    let mut json = Vec::new();
+    Json::write(&pruned.report, "prune", &mut json)?;
+    let doc = ReportDoc::from_json(Path::new("report.json"), std::str::from_utf8(&json)?)?;
+    let marked: Vec<&str> = doc
+        .files
+        .iter()
+        .flat_map(|f| &f.removals)
+        .filter(|r| r.test_only)
+        .map(|r| r.finding.item.as_str())
+        .collect();
+    assert_eq!(marked, ["trait Shape"]);
+    Ok(())
+}
+
+#[test]
+fn nothing_is_explained_without_the_flag_or_test_targets() -> TestResult {
+    let tmp = tempfile::tempdir()?;
+    write_crate(tmp.path(), None)?;
+    let pruned = prune(tmp.path(), false)?;
+    assert!(outcomes(&pruned).iter().all(|o| !o.3));
+
+    // Checks without test targets never needed the bound: it goes, nothing to explain.
+    let tmp = tempfile::tempdir()?;
+    write_crate(tmp.path(), Some(&["--workspace", "--quiet"]))?;
+    let pruned = prune(tmp.path(), true)?;
+    let shape = outcomes(&pruned)
+        .into_iter()
+        .find(|o| o.0 == "trait Shape")
+        .map(|o| (o.2, o.3));
+    assert_eq!(shape, Some((RemovalStatus::Removed, false)));
+    Ok(())
+}
+
+#[test]
+fn the_cli_prints_the_diagnosis() -> TestResult {
+    use assert_cmd::Command;
+    use predicates::prelude::PredicateBooleanExt;
+    use predicates::str::contains;
+
+    let tmp = tempfile::tempdir()?;
+    write_crate(tmp.path(), None)?;
+    Command::cargo_bin("trait-winnower")?
+        .args(["prune", "-b", "--explain-test-only"])
+        .arg(tmp.path())
+        .assert()
+        .success()
+        .stderr(contains(
+            "src/lib.rs:3 trait Shape: `Self: Clone`: bound required only by tests; consider \
+             restructuring the test or adding a test-only helper",
+        ))
+        .stderr(contains("fn twice").not());
+    Ok(())
+}
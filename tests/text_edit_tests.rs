@@ -0,0 +1,341 @@
+// tests/text_edit_tests.rs
+//! Dry-run text edits parse to the same tree the pruner's tree mutation writes.
+
+mod common;
+
+use common::TestResult;
+use syn::visit_mut::VisitMut;
+use trait_winnower::analysis::ItemBounds;
+use trait_winnower::dynamic_analysis::common::{BoundCandidate, BoundSite, OwnedBoundCandidate};
+use trait_winnower::dynamic_analysis::edit::{Anchor, BoundEditor};
+use trait_winnower::dynamic_analysis::text_edit::{TextEdit, apply_edits, propose_edits};
+
+const SRC: &str = "\
+fn a<T: Clone + Send + Sync, U: Copy>(t: T, u: U)
+where
+    T: std::fmt::Debug + Default,
+    U: PartialEq,
+    Vec<T>: Eq,
+{
+}
+
+struct S<X: Ord>(X) where X: Sync;
+
+impl<Y: Send + Unpin> S<Y> where Y: Ord {
+    fn m<V: Copy + Sized>(&self, v: V) where V: std::hash::Hash, {}
+}
+
+trait Tr<Z: Default> where Z: Clone {
+    fn n<W: PartialOrd>(w: W);
+}
+";
+
+/// One item's anchor and candidates, as the pruner sees them.
+struct Item {
+    anchor: Anchor,
+    candidates: Vec<BoundCandidate>,
+}
+
+fn items(file: &syn::File) -> Result<Vec<Item>, Box<dyn std::error::Error>> {
+    let bounds = ItemBounds::collect_items_in_file(file)?;
+    let mut out = Vec::new();
+    macro_rules! push {
+        ($list:expr, $collect:path) => {
+            for b in $list {
+                out.push(Item {
+                    anchor: Anchor::of(b.item_key()),
+                    candidates: $collect(b),
+                });
+            }
+        };
+    }
+    push!(bounds.fns(), BoundCandidate::collect_function_candidates);
+    push!(bounds.structs(), BoundCandidate::collect_struct_candidates);
+    push!(bounds.impls(), BoundCandidate::collect_impl_candidates);
+    push!(
+        bounds.impl_methods(),
+        BoundCandidate::collect_impl_method_candidates
+    );
+    push!(bounds.traits(), BoundCandidate::collect_trait_candidates);
+    push!(
+        bounds.trait_methods(),
+        BoundCandidate::collect_trait_method_candidates
+    );
+    Ok(out)
+}
+
+/// Remove `chosen` from one item through the pruner's `BoundEditor`, last index first so
+/// earlier coordinates stay valid.
+fn mutate(file: &mut syn::File, item: &Item, chosen: &[&BoundCandidate]) {
+    let mut chosen = chosen.to_vec();
+    chosen.sort_by_key(|c| match &c.site {
+        BoundSite::TypeParam {
+            param_index,
+            bound_index,
+            ..
+        } => (0, *param_index, *bound_index),
+        BoundSite::WhereClause {
+            pred_index,
+            bound_index,
+            ..
+        } => (1, *pred_index, *bound_index),
+        BoundSite::ImplTraitArg {
+            arg_index,
+            bound_index,
+            ..
+        } => (2, *arg_index, *bound_index),
+        // Types at different paths do not shift each other's indices. The return type goes
+        // first: its bounds are found by their text, which removals nested in them change.
+        BoundSite::TraitObject { bound_index, .. } => (3, 0, *bound_index),
+        BoundSite::ReturnImplTrait { bound_index, .. } => (4, 0, *bound_index),
+    });
+    for c in chosen.into_iter().rev() {
+        let mut editor = BoundEditor::<syn::ItemFn>::new(&item.anchor, c);
+        editor.visit_file_mut(file);
+        // The last trait bound of an `impl Trait` stays, and a trait object's principal.
+        let refused = matches!(
+            c.site,
+            BoundSite::ImplTraitArg { .. }
+                | BoundSite::ReturnImplTrait { .. }
+                | BoundSite::TraitObject { .. }
+        );
+        assert!(editor.modified() || refused, "{c:?}");
+    }
+}
+
+fn owned(chosen: &[&BoundCandidate]) -> Vec<OwnedBoundCandidate> {
+    chosen
+        .iter()
+        .map(|c| OwnedBoundCandidate::from(*c))
+        .collect()
+}
+
+/// Apply the proposed edits and check they match the tree mutation, as written.
+fn assert_same_tree(src: &str, mutated: &syn::File, chosen: &[OwnedBoundCandidate]) -> TestResult {
+    let edits = propose_edits(src, chosen)?;
+    assert!(
+        edits.windows(2).all(|w| w[0].end <= w[1].start),
+        "{edits:?}"
+    );
+    let edited = apply_edits(src, &edits);
+    let reparsed = syn::parse_file(&edited)?;
+    assert_eq!(
+        prettyplease::unparse(&reparsed),
+        prettyplease::unparse(mutated),
+        "edited source:\n{edited}"
+    );
+    Ok(())
+}
+
+#[test]
+fn each_single_removal_matches_the_tree_mutation() -> TestResult {
+    let file = syn::parse_file(SRC)?;
+    for item in items(&file)? {
+        for c in &item.candidates {
+            let mut mutated = file.clone();
+            mutate(&mut mutated, &item, &[c]);
+            assert_same_tree(SRC, &mutated, &owned(&[c]))?;
+        }
+    }
+    Ok(())
+}
+
+#[test]
+fn every_subset_of_one_item_matches_the_tree_mutation() -> TestResult {
+    let file = syn::parse_file(SRC)?;
+    let items = items(&file)?;
+    let item = &items[0];
+    let n = item.candidates.len();
+    assert_eq!(n, 8);
+    for mask in 0u32..(1 << n) {
+        let chosen: Vec<&BoundCandidate> = (0..n)
+            .filter(|i| mask & (1 << i) != 0)
+            .map(|i| &item.candidates[i])
+            .collect();
+        let mut mutated = file.clone();
+        mutate(&mut mutated, item, &chosen);
+        assert_same_tree(SRC, &mutated, &owned(&chosen))?;
+    }
+    Ok(())
+}
+
+#[test]
+fn removing_everything_matches_the_tree_mutation() -> TestResult {
+    let file = syn::parse_file(SRC)?;
+    let mut mutated = file.clone();
+    let mut all = Vec::new();
+    let items = items(&file)?;
+    for item in &items {
+        let chosen: Vec<&BoundCandidate> = item.candidates.iter().collect();
+        mutate(&mut mutated, item, &chosen);
+        all.extend(owned(&chosen));
+    }
+    assert_same_tree(SRC, &mutated, &all)?;
+    Ok(())
+}
+
+#[test]
+fn edits_keep_surrounding_formatting() -> TestResult {
+    let src = "\u{feff}// café\nfn f<T: Clone + Send>(t: T)   where T: Sync {}\n";
+    let file = syn::parse_file(src)?;
+    let items = items(&file)?;
+    let send = &items[0].candidates[1];
+    let sync = &items[0].candidates[2];
+
+    let edits = propose_edits(src, &owned(&[send]))?;
+    let start = src.find(" + Send").expect("bound");
+    assert_eq!(
+        edits,
+        [TextEdit {
+            start,
+            end: start + " + Send".len(),
+            replacement: String::new(),
+        }]
+    );
+    assert_eq!(
+        apply_edits(src, &propose_edits(src, &owned(&[send, sync]))?),
+        "\u{feff}// café\nfn f<T: Clone>(t: T) {}\n"
+    );
+    Ok(())
+}
+
+#[test]
+fn a_candidate_applies_to_every_item_declaring_it() -> TestResult {
+    let src = "fn f<T: Clone>(t: T) {}\nfn g<T: Clone>(t: T) {}\nfn h<T: Copy>(t: T) {}\n";
+    let file = syn::parse_file(src)?;
+    let items = items(&file)?;
+    let edits = propose_edits(src, &owned(&[&items[0].candidates[0]]))?;
+    assert_eq!(
+        apply_edits(src, &edits),
+        "fn f<T>(t: T) {}\nfn g<T>(t: T) {}\nfn h<T: Copy>(t: T) {}\n"
+    );
+    Ok(())
+}
+
+#[test]
+fn impl_trait_argument_removals_match_the_tree_mutation() -> TestResult {
+    let src = "\
+fn p<T: Send>(t: T, x: impl Clone + Send + 'static, y: impl Copy) {}
+
+struct S;
+
+impl S {
+    fn q(&self, v: impl Ord + Eq) {}
+}
+";
+    let file = syn::parse_file(src)?;
+    let items = items(&file)?;
+    let p = &items[0];
+    // `y: impl Copy` has one bound; it cannot go.
+    assert_eq!(p.candidates.len(), 4);
+    let n = p.candidates.len();
+    for mask in 0u32..(1 << n) {
+        let chosen: Vec<&BoundCandidate> = (0..n)
+            .filter(|i| mask & (1 << i) != 0)
+            .map(|i| &p.candidates[i])
+            .collect();
+        let mut mutated = file.clone();
+        mutate(&mut mutated, p, &chosen);
+        assert_same_tree(src, &mutated, &owned(&chosen))?;
+    }
+    let q = &items[1];
+    let all: Vec<&BoundCandidate> = q.candidates.iter().collect();
+    let edited = apply_edits(src, &propose_edits(src, &owned(&all))?);
+    assert!(edited.contains("fn q(&self, v: impl Ord) {}"), "{edited}");
+    let edited = apply_edits(
+        src,
+        &propose_edits(src, &owned(&p.candidates[1..2].iter().collect::<Vec<_>>()))?,
+    );
+    assert!(
+        edited.contains("fn p<T: Send>(t: T, x: impl Send + 'static, y: impl Copy) {}"),
+        "{edited}"
+    );
+    Ok(())
+}
+
+#[test]
+fn opaque_type_removals_match_the_tree_mutation() -> TestResult {
+    let src = "\
+fn r(e: &(dyn std::error::Error + Send + Sync)) -> impl Iterator<Item = Box<dyn Send + Sync>> + Send {
+    std::iter::empty()
+}
+
+struct E {
+    e: Box<dyn std::error::Error + Send + 'static>,
+}
+";
+    let file = syn::parse_file(src)?;
+    for item in items(&file)? {
+        let n = item.candidates.len();
+        for mask in 0u32..(1 << n) {
+            let chosen: Vec<&BoundCandidate> = (0..n)
+                .filter(|i| mask & (1 << i) != 0)
+                .map(|i| &item.candidates[i])
+                .collect();
+            let mut mutated = file.clone();
+            mutate(&mut mutated, &item, &chosen);
+            assert_same_tree(src, &mutated, &owned(&chosen))?;
+        }
+    }
+    let items = items(&file)?;
+    let all: Vec<OwnedBoundCandidate> = items
+        .iter()
+        .flat_map(|item| owned(&item.candidates.iter().collect::<Vec<_>>()))
+        .collect();
+    let edited = apply_edits(src, &propose_edits(src, &all)?);
+    assert!(
+        edited
+            .contains("fn r(e: &(dyn std::error::Error)) -> impl Iterator<Item = Box<dyn Send>> {"),
+        "{edited}"
+    );
+    assert!(
+        edited.contains("e: Box<dyn std::error::Error>,"),
+        "{edited}"
+    );
+    Ok(())
+}
+
+#[test]
+fn removals_leave_type_param_defaults_in_place() -> TestResult {
+    let src = "\
+struct D<A: Copy + Send = u8, B: Clone = String, C: Default + Sync = ()>(A, B, C);
+";
+    let file = syn::parse_file(src)?;
+    let items = items(&file)?;
+    let item = &items[0];
+    let n = item.candidates.len();
+    assert_eq!(n, 5);
+    for mask in 0u32..(1 << n) {
+        let chosen: Vec<&BoundCandidate> = (0..n)
+            .filter(|i| mask & (1 << i) != 0)
+            .map(|i| &item.candidates[i])
+            .collect();
+        let mut mutated = file.clone();
+        mutate(&mut mutated, item, &chosen);
+        assert_same_tree(src, &mutated, &owned(&chosen))?;
+    }
+    // The whole first, middle and last parameters' bounds, then part of two of them.
+    for (picked, want) in [
+        (
+            &[0, 1][..],
+            "struct D<A = u8, B: Clone = String, C: Default + Sync = ()>",
+        ),
+        (
+            &[2],
+            "struct D<A: Copy + Send = u8, B = String, C: Default + Sync = ()>",
+        ),
+        (
+            &[3, 4],
+            "struct D<A: Copy + Send = u8, B: Clone = String, C = ()>",
+        ),
+        (
+            &[1, 3],
+            "struct D<A: Copy = u8, B: Clone = String, C: Sync = ()>",
+        ),
+    ] {
+        let chosen: Vec<&BoundCandidate> = picked.iter().map(|&i| &item.candidates[i]).collect();
+        let edited = apply_edits(src, &propose_edits(src, &owned(&chosen))?);
+        assert!(edited.starts_with(want), "{edited}");
+    }
+    Ok(())
+}
@@ -0,0 +1,103 @@
+// tests/tidy_imports_tests.rs
+//! `--tidy-imports` drops the imports removed bounds were the last users of, and puts back
+//! one the crate stops building without.
+#![cfg(feature = "cli")]
+
+mod common;
+
+use assert_cmd::Command;
+use common::TestResult;
+use trait_winnower::config::Config;
+use trait_winnower::report::RemovalStatus;
+use trait_winnower::winnower::Winnower;
+
+const LIB: &str = "\
+use std::fmt::{Debug, Display};
+use std::io::Write;
+pub fn show<T: Debug>(t: T) -> T {
+    t
+}
+pub fn name<T: Display>(t: T) -> String {
+    t.to_string()
+}
+pub fn put<W: Write>(w: W) -> W {
+    w
+}
+pub fn fill(buf: &mut Vec<u8>) {
+    buf.write_all(b\"x\").unwrap();
+}
+";
+
+#[test]
+fn unused_imports_go_and_needed_ones_come_back() -> TestResult {
+    let tmp = tempfile::tempdir()?;
+    common::write_crate(tmp.path(), "tidy", "", &[("src/lib.rs", LIB)])?;
+    Command::cargo_bin("trait-winnower")?
+        .args(["prune", "--brute-force", "--tidy-imports"])
+        .arg(tmp.path())
+        .assert()
+        .success()
+        .stderr(predicates::str::contains(
+            "removed unused import `std::fmt::Debug` from src/lib.rs:1",
+        ))
+        .stderr(predicates::str::contains(
+            "kept import `std::io::Write` at src/lib.rs:2: the check failed without it",
+        ));
+    assert_eq!(
+        std::fs::read_to_string(tmp.path().join("src/lib.rs"))?,
+        LIB.replace("{Debug, Display}", "Display")
+            .replace("show<T: Debug>", "show<T>")
+            .replace("put<W: Write>", "put<W>")
+    );
+    Ok(())
+}
+
+#[test]
+fn a_tidied_import_is_no_longer_a_follow_up() -> TestResult {
+    let tmp = tempfile::tempdir()?;
+    common::write_crate(
+        tmp.path(),
+        "tidy",
+        "\n[lints.rust]\nunused_imports = \"deny\"\n",
+        &[("src/lib.rs", LIB)],
+    )?;
+    let mut config = Config::default();
+    config.cargo_check.tolerate_new_lints = vec!["unused_imports".into()];
+    let pruned = Winnower::builder()
+        .target(tmp.path())
+        .config(config)
+        .brute_force(true)
+        .tidy_imports(true)
+        .build()?
+        .prune()?;
+
+    let tidied: Vec<(&str, bool)> = pruned
+        .report
+        .tidied_imports
+        .iter()
+        .map(|t| (t.import.as_str(), t.removed))
+        .collect();
+    assert_eq!(
+        tidied,
+        [("std::fmt::Debug", true), ("std::io::Write", false)]
+    );
+    // Under the deny, `show` lost `Debug` only by tolerating its import, which is gone now;
+    // `fill` still uses `Write`, so `put` raised no lint.
+    let show = pruned.report.files[0]
+        .removals
+        .iter()
+        .find(|r| r.finding.bound == "Debug")
+        .ok_or("show's removal")?;
+    assert_eq!(show.status, RemovalStatus::Removed);
+    assert!(pruned.report.follow_ups().is_empty());
+    let out = std::process::Command::new(env!("CARGO"))
+        .args(["check", "--quiet"])
+        .current_dir(tmp.path())
+        .output()?;
+    assert!(
+        out.status.success(),
+        "{}",
+        String::from_utf8_lossy(&out.stderr)
+    );
+    Ok(())
+}
@@ -0,0 +1,114 @@
+// tests/tolerated_lint_tests.rs
+//! Under deny-warnings, a removal that only leaves an import unused is accepted when
+//! `tolerate_new_lints` names the lint, and the import is reported as a follow-up.
+#![cfg(feature = "discover")]
+
+mod common;
+
+use common::TestResult;
+use std::path::Path;
+use trait_winnower::config::Config;
+use trait_winnower::report::{RemovalStatus, Report};
+use trait_winnower::winnower::Winnower;
+
+const A: &str = "\
+use std::fmt::{Debug, Display};
+pub fn show<T: Debug>(t: T) -> T {
+    t
+}
+pub fn shown() -> impl Display + Send {
+    1
+}
+";
+
+/// A workspace denying warnings, of `a` and `b`, which needs `shown` to return a
+/// `Display`.
+fn write_workspace(root: &Path) -> std::io::Result<()> {
+    std::fs::write(
+        root.join("Cargo.toml"),
+        "[workspace]\nmembers = [\"a\", \"b\"]\nresolver = \"2\"\n\n\
+         [workspace.lints.rust]\nwarnings = \"deny\"\n",
+    )?;
+    for (name, deps, src) in [
+        ("a", "", A),
+        (
+            "b",
+            "a = { path = \"../a\" }\n",
+            "pub fn name() -> String {\n    a::shown().to_string()\n}\n",
+        ),
+    ] {
+        std::fs::create_dir_all(root.join(name).join("src"))?;
+        std::fs::write(
+            root.join(name).join("Cargo.toml"),
+            format!(
+                "[package]\nname = \"{name}\"\nversion = \"0.1.0\"\nedition = \"2021\"\n\n\
+                 [lints]\nworkspace = true\n\n[dependencies]\n{deps}"
+            ),
+        )?;
+        std::fs::write(root.join(name).join("src/lib.rs"), src)?;
+    }
+    Ok(())
+}
+
+fn prune(root: &Path, tolerate: &[&str]) -> Result<Report, Box<dyn std::error::Error>> {
+    let mut config = Config::default();
+    config.cargo_check.tolerate_new_lints = tolerate.iter().map(|&l| l.to_owned()).collect();
+    Ok(Winnower::builder()
+        .target(root)
+        .config(config)
+        .brute_force(true)
+        .include_opaque_types(true)
+        .build()?
+        .prune()?
+        .report)
+}
+
+fn status(report: &Report, bound: &str) -> Option<RemovalStatus> {
+    report
+        .files
+        .iter()
+        .flat_map(|f| &f.removals)
+        .find(|r| r.finding.bound == bound)
+        .map(|r| r.status)
+}
+
+#[test]
+fn a_tolerated_lint_accepts_the_removal_and_is_followed_up() -> TestResult {
+    let tmp = tempfile::tempdir()?;
+    write_workspace(tmp.path())?;
+    let report = prune(tmp.path(), &["unused_imports"])?;
+
+    assert_eq!(status(&report, "Debug"), Some(RemovalStatus::Removed));
+    // `a` builds without it too, once its import is forgiven; `b` does not.
+    assert_eq!(status(&report, "Display"), Some(RemovalStatus::Retained));
+    let follow_ups = report.follow_ups();
+    assert_eq!(follow_ups.len(), 1, "{follow_ups:?}");
+    let (follow_up, removal) = follow_ups[0];
+    assert_eq!(follow_up.lint, "unused_imports");
+    assert_eq!(follow_up.file, Path::new("a/src/lib.rs"));
+    assert_eq!(follow_up.line, 1);
+    assert!(follow_up.message.contains("Debug"), "{}", follow_up.message);
+    assert_eq!(removal.finding.item, "fn show");
+    assert_eq!(
+        std::fs::read_to_string(tmp.path().join("a/src/lib.rs"))?,
+        A.replace("show<T: Debug>", "show<T>")
+            .replace(" + Send", "")
+    );
+    Ok(())
+}
+
+#[test]
+fn without_tolerance_the_lint_keeps_the_bound() -> TestResult {
+    let tmp = tempfile::tempdir()?;
+    write_workspace(tmp.path())?;
+    let report = prune(tmp.path(), &[])?;
+
+    assert_eq!(status(&report, "Debug"), Some(RemovalStatus::Retained));
+    assert_eq!(status(&report, "Display"), Some(RemovalStatus::Retained));
+    assert!(report.follow_ups().is_empty());
+    assert_eq!(
+        std::fs::read_to_string(tmp.path().join("a/src/lib.rs"))?,
+        A.replace(" + Send", "")
+    );
+    Ok(())
+}
@@ -0,0 +1,115 @@
+// tests/toolchain_tests.rs
+//! A missing or wrong cargo, and a target directory cargo cannot write, fail upfront.
+#![cfg(feature = "discover")]
+
+mod common;
+
+use common::TestResult;
+use std::path::Path;
+use trait_winnower::config::Config;
+use trait_winnower::error::WinnowerError;
+use trait_winnower::winnower::{PruneReport, Winnower};
+
+const SRC: &str = "pub fn f<T: Clone>(t: T) -> T {\n    t\n}\n";
+
+/// The default configuration with `cargo_path`, as `.trait-winnower.toml` in `dir`.
+fn write_config(dir: &Path, cargo_path: &str) -> TestResult {
+    let mut config = Config::default();
+    config.cargo_check.cargo_path = Some(cargo_path.into());
+    std::fs::write(dir.join(".trait-winnower.toml"), toml::to_string(&config)?)?;
+    Ok(())
+}
+
+fn prune(dir: &Path) -> Result<PruneReport, WinnowerError> {
+    Winnower::builder()
+        .target(dir)
+        .brute_force(true)
+        .build()?
+        .prune()
+}
+
+#[test]
+fn a_missing_cargo_explains_how_to_install_one() -> TestResult {
+    let tmp = tempfile::tempdir()?;
+    common::write_crate(tmp.path(), "x", "", &[("src/lib.rs", SRC)])?;
+    write_config(tmp.path(), "tools/no-such-cargo")?;
+    let err = prune(tmp.path()).expect_err("cargo is missing");
+    let WinnowerError::Toolchain { program, detail } = &err else {
+        panic!("expected Toolchain, got {err:?}");
+    };
+    assert_eq!(program, &tmp.path().join("tools/no-such-cargo"));
+    assert!(detail.contains("rustup"), "{detail}");
+    assert!(detail.contains("cargo_path"), "{detail}");
+    assert_eq!(err.exit_code(), 2);
+    assert_eq!(std::fs::read_to_string(tmp.path().join("src/lib.rs"))?, SRC);
+    Ok(())
+}
+
+#[cfg(unix)]
+#[test]
+fn a_program_that_is_not_cargo_is_refused() -> TestResult {
+    use std::os::unix::fs::PermissionsExt;
+
+    let tmp = tempfile::tempdir()?;
+    common::write_crate(tmp.path(), "x", "", &[("src/lib.rs", SRC)])?;
+    let fake = tmp.path().join("not-cargo");
+    std::fs::write(&fake, "#!/bin/sh\necho 'busybox v1.36'\n")?;
+    std::fs::set_permissions(&fake, std::fs::Permissions::from_mode(0o755))?;
+    let mut config = Config::default();
+    config.cargo_check.cargo_path = Some(fake);
+    let err = Winnower::builder()
+        .target(tmp.path())
+        .config(config)
+        .brute_force(true)
+        .build()?
+        .prune()
+        .expect_err("not cargo");
+    assert!(err.to_string().contains("busybox v1.36"), "{err}");
+    assert!(err.to_string().contains("which is not cargo"), "{err}");
+    Ok(())
+}
+
+#[test]
+fn an_unwritable_target_directory_names_the_path() -> TestResult {
+    let tmp = tempfile::tempdir()?;
+    common::write_crate(tmp.path(), "x", "", &[("src/lib.rs", SRC)])?;
+    // A file where the directory should be fails like a read-only mount, even as root.
+    std::fs::write(tmp.path().join("target"), "")?;
+    let err = prune(tmp.path()).expect_err("target is not writable");
+    let WinnowerError::Io { action, path, .. } = &err else {
+        panic!("expected Io, got {err:?}");
+    };
+    assert!(action.contains("target directory"), "{action}");
+    assert_eq!(
+        path.as_deref(),
+        Some(tmp.path().join("target/trait-winnower-check").as_path())
+    );
+    assert_eq!(std::fs::read_to_string(tmp.path().join("src/lib.rs"))?, SRC);
+    Ok(())
+}
+
+#[test]
+fn the_toolchain_is_recorded_in_the_report() -> TestResult {
+    let tmp = tempfile::tempdir()?;
+    common::write_crate(tmp.path(), "x", "", &[("src/lib.rs", SRC)])?;
+    let pruned = prune(tmp.path())?;
+    let validation = pruned.report.validation.expect("cargo check validated");
+    let toolchain = validation.toolchain.expect("cargo ran");
+    assert!(toolchain.cargo.starts_with("cargo "), "{toolchain}");
+    assert!(
+        toolchain
+            .rustc
+            .as_deref()
+            .is_none_or(|r| r.starts_with("rustc "))
+    );
+    Ok(())
+}
+
+#[test]
+fn a_bare_cargo_path_is_looked_up_on_path() -> TestResult {
+    let tmp = tempfile::tempdir()?;
+    write_config(tmp.path(), "cargo-nightly")?;
+    let config = Config::load_or_default(tmp.path())?;
+    assert_eq!(config.cargo_check.cargo(), Path::new("cargo-nightly"));
+    Ok(())
+}
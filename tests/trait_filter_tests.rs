@@ -0,0 +1,87 @@
+// tests/trait_filter_tests.rs
+//! Bounds with associated type bindings prune like any other, and `only_traits` picks
+//! them by trait path whatever their arguments.
+#![cfg(feature = "discover")]
+
+mod common;
+
+use common::TestResult;
+use trait_winnower::report::RemovalStatus;
+use trait_winnower::winnower::Winnower;
+
+const SRC: &str = "\
+pub fn count<I>(items: Vec<I>) -> usize
+where
+    I: Iterator<Item = u32>,
+{
+    items.len()
+}
+
+pub fn sum<I>(it: I) -> u32
+where
+    I: Iterator<Item = u32>,
+{
+    it.sum()
+}
+
+pub fn keep<T: Clone>(t: T) -> T {
+    t
+}
+";
+
+#[test]
+fn only_traits_prunes_bounds_with_bindings_and_their_where_clause() -> TestResult {
+    let tmp = tempfile::tempdir()?;
+    common::write_crate(tmp.path(), "assoc", "", &[("src/lib.rs", SRC)])?;
+    let winnower = Winnower::builder()
+        .target(tmp.path())
+        .brute_force(true)
+        .only_traits(["Iterator"])
+        .build()?;
+
+    let checked = winnower.check()?;
+    let bounds: Vec<(&str, &str)> = checked
+        .report
+        .files
+        .iter()
+        .flat_map(|f| &f.findings)
+        .map(|f| (f.item.as_str(), f.bound.as_str()))
+        .collect();
+    assert_eq!(
+        bounds,
+        [
+            ("fn count", "Iterator < Item = u32 >"),
+            ("fn sum", "Iterator < Item = u32 >"),
+        ]
+    );
+
+    let pruned = winnower.prune()?;
+    let mut outcomes: Vec<(&str, RemovalStatus)> = pruned
+        .report
+        .files
+        .iter()
+        .flat_map(|f| &f.removals)
+        .filter(|r| r.status != RemovalStatus::Skipped)
+        .map(|r| (r.finding.item.as_str(), r.status))
+        .collect();
+    outcomes.dedup();
+    assert_eq!(
+        outcomes,
+        [
+            ("fn count", RemovalStatus::Removed),
+            ("fn sum", RemovalStatus::Retained),
+        ]
+    );
+
+    let src = std::fs::read_to_string(tmp.path().join("src/lib.rs"))?;
+    assert!(
+        src.contains("pub fn count<I>(items: Vec<I>) -> usize {"),
+        "{src}"
+    );
+    assert!(
+        src.contains("I: Iterator<Item = u32>,\n{\n    it.sum()"),
+        "{src}"
+    );
+    assert!(src.contains("pub fn keep<T: Clone>(t: T) -> T"), "{src}");
+    Ok(())
+}
@@ -1,113 +1,180 @@
-//! Integration tests for trait-winnower prune functionality.
-
-use std::fs;
-use std::path::Path;
-use std::process::Command;
-use tempfile::TempDir;
-use trait_winnower::analysis::ItemBounds;
-use trait_winnower::config::Config;
-use trait_winnower::discover::Discover;
-
-/// Helper function to copy directory recursively
-fn copy_dir_recursive(src: &Path, dst: &Path) -> std::io::Result<()> {
-    if src.is_dir() {
-        fs::create_dir_all(dst)?;
-        for entry in fs::read_dir(src)? {
-            let entry = entry?;
-            let src_path = entry.path();
-            let dst_path = dst.join(entry.file_name());
-
-            if src_path.is_dir() {
-                copy_dir_recursive(&src_path, &dst_path)?;
-            } else {
-                fs::copy(&src_path, &dst_path)?;
-            }
+// tests/trait_sandbox_tests.rs
+//! Fixture crates pruned end to end: each crate under `tests/test_files/<name>` is copied to a
+//! temporary directory, checked to build as it is, pruned with every candidate tried, and
+//! compared file by file with `tests/expected/<name>`.
+#![cfg(feature = "cli")]
+
+mod common;
+
+use assert_cmd::Command;
+use common::TestResult;
+use std::path::{Path, PathBuf};
+
+/// `tests/<dir>/<name>`, wherever the test runs from.
+fn fixture(dir: &str, name: &str) -> PathBuf {
+    Path::new(env!("CARGO_MANIFEST_DIR"))
+        .join("tests")
+        .join(dir)
+        .join(name)
+}
+
+fn copy_dir(from: &Path, to: &Path) -> std::io::Result<()> {
+    std::fs::create_dir_all(to)?;
+    for entry in std::fs::read_dir(from)? {
+        let entry = entry?;
+        let path = entry.path();
+        if path.is_dir() {
+            copy_dir(&path, &to.join(entry.file_name()))?;
+        } else {
+            std::fs::copy(&path, to.join(entry.file_name()))?;
         }
     }
     Ok(())
 }
 
-/// Helper function to collect all bounds from a directory
-fn collect_bounds_from_dir(dir_path: &Path) -> Result<Vec<String>, Box<dyn std::error::Error>> {
-    let cfg = Config::load_or_default(dir_path)?;
-    let files = Discover::discover_rs_files(dir_path, &cfg.include, &cfg.exclude)?;
-
-    let mut all_bounds = Vec::new();
-
-    for file in files {
-        let parsed_file = ItemBounds::parse_file(&file)?;
-        let items = ItemBounds::collect_items_in_file(&parsed_file)?;
+/// The files under `root`, relative to it, leaving out build output.
+fn files(root: &Path) -> std::io::Result<Vec<PathBuf>> {
+    fn walk(root: &Path, dir: &Path, out: &mut Vec<PathBuf>) -> std::io::Result<()> {
+        for entry in std::fs::read_dir(dir)? {
+            let path = entry?.path();
+            if path.is_dir() {
+                if path.file_name().is_some_and(|n| n != "target") {
+                    walk(root, &path, out)?;
+                }
+            } else if let Ok(rel) = path.strip_prefix(root) {
+                out.push(rel.to_path_buf());
+            }
+        }
+        Ok(())
+    }
+    let mut out = Vec::new();
+    walk(root, root, &mut out)?;
+    out.sort();
+    Ok(out)
+}
 
-        // Collect all items with their bounds
-        for item in items.iter_all_items() {
-            all_bounds.push(item.to_string());
+/// A line diff of `expected` against `actual`, `-` for lines only expected and `+` for lines
+/// only found, with the line numbers of each side.
+fn line_diff(expected: &str, actual: &str) -> String {
+    let (a, b): (Vec<_>, Vec<_>) = (expected.lines().collect(), actual.lines().collect());
+    // Longest common subsequence lengths of the suffixes.
+    let mut lcs = vec![vec![0usize; b.len() + 1]; a.len() + 1];
+    for i in (0..a.len()).rev() {
+        for j in (0..b.len()).rev() {
+            lcs[i][j] = if a[i] == b[j] {
+                lcs[i + 1][j + 1] + 1
+            } else {
+                lcs[i + 1][j].max(lcs[i][j + 1])
+            };
+        }
+    }
+    let (mut i, mut j, mut out) = (0, 0, String::new());
+    while i < a.len() || j < b.len() {
+        if i < a.len() && j < b.len() && a[i] == b[j] {
+            (i, j) = (i + 1, j + 1);
+        } else if j == b.len() || (i < a.len() && lcs[i + 1][j] >= lcs[i][j + 1]) {
+            out += &format!("  -{:>4} | {}\n", i + 1, a[i]);
+            i += 1;
+        } else {
+            out += &format!("  +{:>4} | {}\n", j + 1, b[j]);
+            j += 1;
         }
     }
+    out
+}
 
-    all_bounds.sort();
-    Ok(all_bounds)
+/// Asserts the tree at `actual` holds the same files as `expected`, with the same contents;
+/// the failure lists every file that differs, with its diff.
+fn assert_same_tree(expected: &Path, actual: &Path) -> TestResult {
+    let (want, got) = (files(expected)?, files(actual)?);
+    let mut problems = String::new();
+    for missing in want.iter().filter(|p| !got.contains(p)) {
+        problems += &format!("{}: missing\n", missing.display());
+    }
+    for extra in got.iter().filter(|p| !want.contains(p)) {
+        problems += &format!("{}: not expected\n", extra.display());
+    }
+    for path in want.iter().filter(|p| got.contains(p)) {
+        let exp = std::fs::read_to_string(expected.join(path))?;
+        let act = std::fs::read_to_string(actual.join(path))?;
+        if exp != act {
+            problems += &format!("{}: differs\n{}", path.display(), line_diff(&exp, &act));
+        }
+    }
+    assert!(
+        problems.is_empty(),
+        "pruned tree differs from {}:\n{problems}",
+        expected.display()
+    );
+    Ok(())
 }
 
-#[test]
-fn test_prune_trait_sandbox() -> Result<(), Box<dyn std::error::Error>> {
-    // Setup paths
-    let test_files_dir = Path::new("tests/test_files/trait_sandbox");
-    let expected_dir = Path::new("tests/expected/trait_sandbox");
-
-    assert!(test_files_dir.exists(), "Missing {:?}", test_files_dir);
-    assert!(expected_dir.exists(), "Missing {:?}", expected_dir);
-
-    // Temporary working directory
-    let temp_dir = TempDir::new()?;
-    let temp_path = temp_dir.path();
-    copy_dir_recursive(test_files_dir, temp_path)?;
-
-    // Ensure the binary exists
-    Command::new("cargo")
-        .args(&["build", "--bin", "trait-winnower"])
-        .status()
-        .expect("Failed to build trait-winnower binary before running test");
-
-    let binary_path = if cfg!(debug_assertions) {
-        "target/debug/trait-winnower"
-    } else {
-        "target/release/trait-winnower"
-    };
-
-    // Run the prune command
-    let output = Command::new(binary_path)
-        .args(&["prune", "-n", "all", "-t", "all", "--brute-force"])
-        .arg(temp_path)
+/// Prunes a copy of the fixture `name` and compares it with its expected tree.
+fn prune_fixture(name: &str) -> TestResult {
+    let (input, expected) = (fixture("test_files", name), fixture("expected", name));
+    assert!(input.exists(), "missing {}", input.display());
+    assert!(expected.exists(), "missing {}", expected.display());
+
+    let tmp = tempfile::tempdir()?;
+    copy_dir(&input, tmp.path())?;
+
+    // The fixture is a crate of its own and builds before anything is removed.
+    let check = std::process::Command::new(env!("CARGO"))
+        .args(["check", "--quiet", "--all-targets", "--all-features"])
+        .current_dir(tmp.path())
+        .env_remove("CARGO_TARGET_DIR")
         .output()?;
+    assert!(
+        check.status.success(),
+        "fixture {name} does not build:\n{}",
+        String::from_utf8_lossy(&check.stderr)
+    );
 
+    let out = Command::cargo_bin("trait-winnower")?
+        .args(["prune", "-n", "all", "-t", "all", "--brute-force"])
+        .arg(tmp.path())
+        .output()?;
     assert!(
-        output.status.success(),
+        out.status.success(),
         "trait-winnower prune failed\nstatus: {}\nstdout:\n{}\nstderr:\n{}",
-        output.status,
-        String::from_utf8_lossy(&output.stdout),
-        String::from_utf8_lossy(&output.stderr),
+        out.status,
+        String::from_utf8_lossy(&out.stdout),
+        String::from_utf8_lossy(&out.stderr),
     );
 
-    // Compare the collected bounds
-    let pruned_bounds = collect_bounds_from_dir(temp_path)?;
-    let expected_bounds = collect_bounds_from_dir(expected_dir)?;
+    assert_same_tree(&expected, tmp.path())
+}
 
-    assert_eq!(
-        pruned_bounds.len(),
-        expected_bounds.len(),
-        "Number of bounds differs. Pruned: {}, Expected: {}",
-        pruned_bounds.len(),
-        expected_bounds.len()
-    );
+#[test]
+fn test_prune_trait_sandbox() -> TestResult {
+    prune_fixture("trait_sandbox")
+}
 
-    for (i, (pruned, expected)) in pruned_bounds.iter().zip(expected_bounds.iter()).enumerate() {
-        assert_eq!(
-            pruned, expected,
-            "Bound at index {} differs.\nPruned:   {:?}\nExpected: {:?}",
-            i, pruned, expected
-        );
-    }
+#[test]
+fn prune_drops_bounds_a_supertrait_implies() -> TestResult {
+    prune_fixture("supertrait_sandbox")
+}
 
-    println!("[+] All bounds and file contents match expected results!");
-    Ok(())
+#[test]
+fn prune_handles_impl_and_method_bounds_together() -> TestResult {
+    prune_fixture("impl_methods_sandbox")
+}
+
+#[test]
+fn prune_validates_feature_gated_items_and_skips_unbuilt_ones() -> TestResult {
+    prune_fixture("cfg_sandbox")
+}
+
+#[test]
+fn prune_finds_items_again_after_earlier_edits_in_the_file() -> TestResult {
+    prune_fixture("stale_anchor_sandbox")
+}
+
+#[test]
+fn the_line_diff_shows_both_sides() {
+    assert_eq!(
+        line_diff("a\nb\nc\n", "a\nB\nc\nd\n"),
+        "  -   2 | b\n  +   2 | B\n  +   4 | d\n"
+    );
+    assert_eq!(line_diff("same\n", "same\n"), "");
 }
@@ -0,0 +1,139 @@
+// tests/trial_cache_tests.rs
+//! Trials validated in one run answer the same trials of later runs until anything their
+//! check compiles changes.
+#![cfg(feature = "cli")]
+
+mod common;
+
+use common::TestResult;
+use std::path::Path;
+use trait_winnower::config::{Config, TrialCacheConfig};
+use trait_winnower::report::RemovalStatus;
+use trait_winnower::winnower::{PruneReport, Winnower};
+
+/// `T: Clone` on `dup` can go until `b.rs` implements it with the bound.
+const A: &str = "pub trait Tr {\n    fn dup<T: Clone>(&self, t: T) -> (T, T);\n}\n";
+const B: &str = "pub fn g() {}\n";
+const B_NEEDS_CLONE: &str = "impl crate::a::Tr for () {\n    \
+                             fn dup<T: Clone>(&self, t: T) -> (T, T) {\n        \
+                             (t.clone(), t)\n    }\n}\n";
+
+const FILES: &[(&str, &str)] = &[
+    (
+        ".trait-winnower.toml",
+        "include = [\"src/a.rs\"]\nexclude = [\"target/**\"]\n\n[cargo_check]\n\n[trial_cache]\n",
+    ),
+    ("src/lib.rs", "pub mod a;\npub mod b;\n"),
+    ("src/a.rs", A),
+    ("src/b.rs", B),
+];
+
+fn prune(dir: &Path) -> Result<PruneReport, Box<dyn std::error::Error>> {
+    Ok(Winnower::builder()
+        .target(dir)
+        .brute_force(true)
+        .build()?
+        .prune()?)
+}
+
+fn status(pruned: &PruneReport) -> RemovalStatus {
+    pruned.report.files[0].removals[0].status
+}
+
+#[test]
+fn a_later_run_reuses_trials_until_another_file_of_the_package_changes() -> TestResult {
+    let tmp = tempfile::tempdir()?;
+    let root = tmp.path();
+    common::write_crate(root, "x", "", FILES)?;
+
+    let first = prune(root)?;
+    assert_eq!(status(&first), RemovalStatus::Removed);
+    assert_eq!(first.report.total_checks(), 1);
+    assert_eq!(first.report.total_trial_cache_hits(), 0);
+
+    // The same tree again, as the next CI job starting from the same commit sees it.
+    std::fs::write(root.join("src/a.rs"), A)?;
+    let second = prune(root)?;
+    assert_eq!(status(&second), RemovalStatus::Removed);
+    assert_eq!(second.report.total_checks(), 0);
+    assert_eq!(second.report.total_trial_cache_hits(), 1);
+    assert!(second.report.files[0].removals[0].cached);
+
+    // `a.rs` is unchanged, but `b.rs` now needs the bound: the stored answer must not
+    // stand.
+    std::fs::write(root.join("src/a.rs"), A)?;
+    std::fs::write(root.join("src/b.rs"), B_NEEDS_CLONE)?;
+    let third = prune(root)?;
+    assert_eq!(status(&third), RemovalStatus::Retained);
+    assert_eq!(third.report.total_checks(), 1);
+    assert_eq!(third.report.total_trial_cache_hits(), 0);
+    assert_eq!(std::fs::read_to_string(root.join("src/a.rs"))?, A);
+    Ok(())
+}
+
+#[test]
+fn without_the_table_nothing_is_cached() -> TestResult {
+    let tmp = tempfile::tempdir()?;
+    let root = tmp.path();
+    common::write_crate(root, "x", "", FILES)?;
+    let config = Config {
+        include: vec!["src/a.rs".into()],
+        ..Config::default()
+    };
+    for _ in 0..2 {
+        std::fs::write(root.join("src/a.rs"), A)?;
+        let pruned = Winnower::builder()
+            .target(root)
+            .config(config.clone())
+            .brute_force(true)
+            .build()?
+            .prune()?;
+        assert_eq!(pruned.report.total_checks(), 1);
+        assert_eq!(pruned.report.total_trial_cache_hits(), 0);
+    }
+    assert!(!TrialCacheConfig::default().dir(root).exists());
+    Ok(())
+}
+
+#[test]
+fn the_cli_shows_and_clears_the_cache() -> TestResult {
+    use assert_cmd::Command;
+    use predicates::str::contains;
+
+    let tmp = tempfile::tempdir()?;
+    let root = tmp.path();
+    common::write_crate(root, "x", "", FILES)?;
+    Command::cargo_bin("trait-winnower")?
+        .args(["prune", "-b"])
+        .arg(root)
+        .assert()
+        .success();
+    std::fs::write(root.join("src/a.rs"), A)?;
+    Command::cargo_bin("trait-winnower")?
+        .args(["prune", "-b"])
+        .arg(root)
+        .assert()
+        .success()
+        .stderr(contains("trial cache: 1 check(s) reused from earlier runs"));
+    // One trial: the check timing the unmodified crate already wrote `Cargo.lock`.
+    Command::cargo_bin("trait-winnower")?
+        .args(["cache", "stats"])
+        .arg(root)
+        .assert()
+        .success()
+        .stdout(contains(": 1 trial(s),"))
+        .stdout(contains("of 512 MB"));
+    Command::cargo_bin("trait-winnower")?
+        .args(["cache", "clear"])
+        .arg(root)
+        .assert()
+        .success()
+        .stdout(contains("removed 1 trial(s)"));
+    Command::cargo_bin("trait-winnower")?
+        .args(["cache", "stats"])
+        .arg(root)
+        .assert()
+        .success()
+        .stdout(contains(": 0 trial(s),"));
+    Ok(())
+}
@@ -0,0 +1,90 @@
+// tests/unlock_tests.rs
+//! A bound kept only because a bound pruned after it asked for it is reported as an unlock
+//! chain, and the next prune removes it.
+#![cfg(feature = "cli")]
+
+mod common;
+
+use assert_cmd::Command;
+use common::TestResult;
+use std::path::Path;
+use trait_winnower::formats::ReportDoc;
+use trait_winnower::report::RemovalStatus;
+use trait_winnower::winnower::Winnower;
+
+const SRC: &str = "pub fn a<T: Clone>() {\n    b::<T>()\n}\n\npub fn b<T: Clone>() {}\n";
+
+#[test]
+fn retained_bound_blamed_on_a_removed_one_is_unlocked() -> TestResult {
+    let tmp = tempfile::tempdir()?;
+    common::write_crate(tmp.path(), "u", "", &[("src/lib.rs", SRC)])?;
+    let prune = || {
+        Winnower::builder()
+            .target(tmp.path())
+            .brute_force(true)
+            .build()?
+            .prune()
+    };
+
+    let first = prune()?;
+    let removals: Vec<_> = first
+        .report
+        .files
+        .iter()
+        .flat_map(|f| &f.removals)
+        .collect();
+    let a = removals
+        .iter()
+        .find(|r| r.finding.item == "fn a")
+        .ok_or("no trial of `a`")?;
+    assert_eq!(a.status, RemovalStatus::Retained);
+    assert_eq!(a.blames.first().map(|b| b.item.as_str()), Some("b"));
+    // Suggested, not applied.
+    let pruned = std::fs::read_to_string(tmp.path().join("src/lib.rs"))?;
+    assert!(pruned.contains("pub fn a<T: Clone>()"), "{pruned}");
+    assert!(pruned.contains("pub fn b<T>()"), "{pruned}");
+    let [chain] = first.report.unlocks.as_slice() else {
+        panic!("{:?}", first.report.unlocks);
+    };
+    assert_eq!(chain.finding.item, "fn a");
+    assert_eq!(chain.unlocked_by.len(), 1);
+    assert_eq!(chain.unlocked_by[0].item, "fn b");
+    assert_eq!(chain.unlocked_by[0].line, 5);
+
+    let second = prune()?;
+    assert!(second.report.unlocks.is_empty());
+    let pruned = std::fs::read_to_string(tmp.path().join("src/lib.rs"))?;
+    assert!(pruned.contains("pub fn a<T>()"), "{pruned}");
+    Ok(())
+}
+
+#[test]
+fn unlock_chains_appear_in_the_json_report_and_on_stderr() -> TestResult {
+    let tmp = tempfile::tempdir()?;
+    common::write_crate(tmp.path(), "u", "", &[("src/lib.rs", SRC)])?;
+    let out = Command::cargo_bin("trait-winnower")?
+        .args(["--format", "json", "prune", "--brute-force"])
+        .arg(tmp.path())
+        .output()?;
+    assert!(
+        out.status.success(),
+        "{}",
+        String::from_utf8_lossy(&out.stderr)
+    );
+    let doc = ReportDoc::from_json(Path::new("stdout"), &String::from_utf8(out.stdout)?)?;
+    let [unlock] = doc.unlocks.as_slice() else {
+        panic!("{:?}", doc.unlocks);
+    };
+    assert_eq!(unlock.path, "src/lib.rs");
+    assert_eq!(unlock.finding.item, "fn a");
+    assert_eq!(unlock.unlocked_by[0].finding.item, "fn b");
+
+    let stderr = String::from_utf8(out.stderr)?;
+    assert!(
+        stderr.contains(
+            "unlocked: src/lib.rs:1 fn a: `T: Clone`\n  was kept for src/lib.rs:5 fn b: `T: Clone`\n"
+        ),
+        "{stderr}"
+    );
+    Ok(())
+}
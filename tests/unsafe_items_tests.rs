@@ -0,0 +1,143 @@
+// tests/unsafe_items_tests.rs
+//! The bounds of `unsafe impl`s may be all that keeps them sound: `check` flags them and
+//! `prune` leaves them alone unless `allow_unsafe_impl_pruning` is on. Generic `unsafe fn`s
+//! and `extern "C"` fns are pruned like any other fn.
+#![cfg(feature = "cli")]
+
+mod common;
+
+use assert_cmd::Command;
+use common::TestResult;
+use predicates::str::contains;
+use trait_winnower::dynamic_analysis::validate::AlwaysOk;
+use trait_winnower::report::{RemovalStatus, Report};
+use trait_winnower::winnower::Winnower;
+
+const SRC: &str = "\
+pub struct Wrapper<T>(pub *const T);
+
+unsafe impl<T: Send> Send for Wrapper<T> {}
+
+pub unsafe trait Plain {}
+
+unsafe impl<T: Copy> Plain for Wrapper<T> {}
+
+pub unsafe fn read<T: Copy + Clone>(p: *const T) -> T {
+    unsafe { *p }
+}
+
+pub extern \"C\" fn id<T: Copy + Clone>(t: T) -> T {
+    t
+}
+";
+
+fn outcomes(report: &Report) -> Vec<(String, String, RemovalStatus)> {
+    let mut outcomes: Vec<_> = report
+        .files
+        .iter()
+        .flat_map(|f| &f.removals)
+        .map(|r| (r.finding.item.clone(), r.finding.bound.clone(), r.status))
+        .collect();
+    // Later passes revisit the items; the first outcome of each bound is its trial.
+    outcomes.sort_by(|a, b| (&a.0, &a.1).cmp(&(&b.0, &b.1)));
+    outcomes.dedup_by(|a, b| (&a.0, &a.1) == (&b.0, &b.1));
+    outcomes
+}
+
+fn want(item: &str, bound: &str, status: RemovalStatus) -> (String, String, RemovalStatus) {
+    (item.to_owned(), bound.to_owned(), status)
+}
+
+#[test]
+fn unsafe_impl_bounds_are_skipped_but_unsafe_fns_are_pruned() -> TestResult {
+    let tmp = tempfile::tempdir()?;
+    common::write_crate(tmp.path(), "unsafes", "", &[("src/lib.rs", SRC)])?;
+    let pruned = Winnower::builder()
+        .target(tmp.path())
+        .brute_force(true)
+        .validator(AlwaysOk)
+        .build()?
+        .prune()?;
+
+    assert_eq!(
+        outcomes(&pruned.report),
+        [
+            want("fn id", "Clone", RemovalStatus::Removed),
+            want("fn id", "Copy", RemovalStatus::Removed),
+            want("fn read", "Clone", RemovalStatus::Removed),
+            want("fn read", "Copy", RemovalStatus::Removed),
+            want(
+                "impl Plain for Wrapper < T >",
+                "Copy",
+                RemovalStatus::Skipped
+            ),
+            want(
+                "impl Send for Wrapper < T >",
+                "Send",
+                RemovalStatus::Skipped
+            ),
+        ]
+    );
+    let src = std::fs::read_to_string(tmp.path().join("src/lib.rs"))?;
+    assert!(
+        src.contains("unsafe impl<T: Send> Send for Wrapper<T> {}"),
+        "{src}"
+    );
+    assert!(
+        src.contains("unsafe impl<T: Copy> Plain for Wrapper<T> {}"),
+        "{src}"
+    );
+    assert!(
+        src.contains("pub unsafe fn read<T>(p: *const T) -> T {"),
+        "{src}"
+    );
+    assert!(
+        src.contains("pub extern \"C\" fn id<T>(t: T) -> T {"),
+        "{src}"
+    );
+    Ok(())
+}
+
+#[test]
+fn unsafe_impl_bounds_are_pruned_when_allowed() -> TestResult {
+    let tmp = tempfile::tempdir()?;
+    common::write_crate(tmp.path(), "unsafes", "", &[("src/lib.rs", SRC)])?;
+    let pruned = Winnower::builder()
+        .target(tmp.path())
+        .brute_force(true)
+        .allow_unsafe_impl_pruning(true)
+        .validator(AlwaysOk)
+        .build()?
+        .prune()?;
+
+    let statuses: Vec<RemovalStatus> = outcomes(&pruned.report)
+        .into_iter()
+        .map(|(_, _, status)| status)
+        .collect();
+    assert_eq!(statuses, [RemovalStatus::Removed; 6]);
+    let src = std::fs::read_to_string(tmp.path().join("src/lib.rs"))?;
+    assert!(
+        src.contains("unsafe impl<T> Send for Wrapper<T> {}"),
+        "{src}"
+    );
+    Ok(())
+}
+
+#[test]
+fn check_notes_how_to_prune_unsafe_impl_bounds() -> TestResult {
+    let tmp = tempfile::tempdir()?;
+    common::write_crate(tmp.path(), "unsafes", "", &[("src/lib.rs", SRC)])?;
+    Command::cargo_bin("trait-winnower")?
+        .arg("check")
+        .arg(tmp.path())
+        .assert()
+        .success()
+        .stdout(contains(
+            "`unsafe impl Send for Wrapper < T >` vouches for its own soundness",
+        ))
+        .stdout(contains(
+            "`prune` keeps the bounds of an `unsafe impl` unless run with \
+             `--allow-unsafe-impl-pruning`",
+        ));
+    Ok(())
+}
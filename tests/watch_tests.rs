@@ -0,0 +1,90 @@
+// tests/watch_tests.rs
+//! `check --watch` re-checks files as they change, one line per event on a pipe, and stops
+//! cleanly on Ctrl-C.
+#![cfg(all(unix, feature = "cli"))]
+
+mod common;
+
+use common::TestResult;
+use std::io::{BufRead, BufReader};
+use std::path::Path;
+use std::process::{Command, Stdio};
+use std::sync::mpsc::{Receiver, channel};
+use std::time::Duration;
+
+/// The next line of `lines` starting with `prefix`, skipping others.
+fn expect(lines: &Receiver<String>, prefix: &str) -> Result<String, Box<dyn std::error::Error>> {
+    loop {
+        let line = lines.recv_timeout(Duration::from_secs(20))?;
+        if line.starts_with(prefix) {
+            return Ok(line);
+        }
+    }
+}
+
+fn write(root: &Path, path: &str, text: &str) -> std::io::Result<()> {
+    std::fs::write(root.join(path), text)
+}
+
+#[test]
+fn watch_follows_edits_new_files_and_deletions() -> TestResult {
+    let tmp = tempfile::tempdir()?;
+    let root = tmp.path();
+    std::fs::create_dir_all(root.join("src"))?;
+    write(
+        root,
+        "Cargo.toml",
+        "[package]\nname=\"w\"\nversion=\"0.1.0\"\nedition=\"2021\"\n",
+    )?;
+    write(root, "src/lib.rs", "pub fn f<T>(t: T) -> T { t }\n")?;
+
+    let mut child = Command::new(env!("CARGO_BIN_EXE_trait-winnower"))
+        .args(["check", "--watch"])
+        .arg(root)
+        .stdout(Stdio::piped())
+        .stderr(Stdio::null())
+        .spawn()?;
+    let stdout = child.stdout.take().ok_or("no stdout")?;
+    let (tx, lines) = channel();
+    std::thread::spawn(move || {
+        for line in BufReader::new(stdout).lines().map_while(Result::ok) {
+            if tx.send(line).is_err() {
+                break;
+            }
+        }
+    });
+
+    let watched = (|| -> TestResult {
+        assert_eq!(
+            expect(&lines, "watching")?,
+            "watching 0 finding(s) in 1 file(s)"
+        );
+        write(
+            root,
+            "src/lib.rs",
+            "pub fn f<T: Clone + Clone>(t: T) -> T { t.clone() }\n",
+        )?;
+        let checked = expect(&lines, "checked src/lib.rs")?;
+        assert!(checked.ends_with("in 1 file(s)"), "{checked}");
+        assert!(!checked.contains(": 0 finding(s)"), "{checked}");
+
+        write(root, "src/extra.rs", "pub fn g<U: Copy>(u: U) {}\n")?;
+        let added = expect(&lines, "checked src/extra.rs")?;
+        assert!(added.ends_with("in 2 file(s)"), "{added}");
+
+        std::fs::remove_file(root.join("src/extra.rs"))?;
+        let removed = expect(&lines, "removed src/extra.rs")?;
+        assert!(removed.ends_with("in 1 file(s)"), "{removed}");
+
+        write(root, "src/lib.rs", "pub fn f<T>(t: T) -> T {\n")?;
+        expect(&lines, "failed src/lib.rs")?;
+        Ok(())
+    })();
+
+    // SAFETY: signals the child spawned above.
+    unsafe { libc::kill(child.id() as libc::pid_t, libc::SIGINT) };
+    let status = child.wait()?;
+    watched?;
+    assert!(status.success(), "{status}");
+    Ok(())
+}
@@ -0,0 +1,127 @@
+// tests/weight_tests.rs
+//! `--sort-by weight` puts the bounds of the items the workspace refers to most first, and
+//! `--min-weight` leaves out those of items it hardly refers to, in `check` and `prune`.
+#![cfg(feature = "cli")]
+
+mod common;
+
+use assert_cmd::Command;
+use common::TestResult;
+use std::path::Path;
+use trait_winnower::dynamic_analysis::validate::AlwaysOk;
+use trait_winnower::references::{CACHE_FILE, SortBy};
+use trait_winnower::winnower::Winnower;
+
+const COLD: &str = "pub fn cold<T: Clone>(t: T) -> T {\n    t\n}\n";
+const HOT: &str = "\
+pub fn hot<T: Clone>(t: T) -> T {
+    t
+}
+
+pub fn callers() -> u8 {
+    hot(1) + hot(2) + crate::b::hot(3)
+}
+";
+
+const FILES: &[(&str, &str)] = &[
+    ("src/lib.rs", "pub mod a;\npub mod b;\n"),
+    ("src/a.rs", COLD),
+    ("src/b.rs", HOT),
+];
+
+fn check_json(root: &Path, args: &[&str]) -> Result<serde_json::Value, Box<dyn std::error::Error>> {
+    let out = Command::cargo_bin("trait-winnower")?
+        .args(["check", "--format", "json"])
+        .args(args)
+        .arg(root)
+        .output()?;
+    assert!(
+        out.status.success(),
+        "{}",
+        String::from_utf8_lossy(&out.stderr)
+    );
+    Ok(serde_json::from_slice(&out.stdout)?)
+}
+
+#[test]
+fn check_sorts_heaviest_first_and_caches_the_counts() -> TestResult {
+    let tmp = tempfile::tempdir()?;
+    common::write_crate(tmp.path(), "weights", "", FILES)?;
+    let json = check_json(tmp.path(), &["--sort-by", "weight"])?;
+    let files = json["files"].as_array().ok_or("no files")?;
+    let weights: Vec<(&str, u64)> = files
+        .iter()
+        .flat_map(|f| f["findings"].as_array().into_iter().flatten())
+        .map(|f| {
+            (
+                f["item"].as_str().unwrap_or(""),
+                f["weight"].as_u64().unwrap_or(0),
+            )
+        })
+        .collect();
+    assert_eq!(weights, [("fn hot", 3), ("fn cold", 0)]);
+    assert!(
+        tmp.path()
+            .join("target/trait-winnower")
+            .join(CACHE_FILE)
+            .is_file()
+    );
+
+    // Without weighing, findings keep location order and carry no weight.
+    let json = check_json(tmp.path(), &[])?;
+    assert_eq!(json["files"][0]["path"], "src/a.rs");
+    assert!(json["files"][0]["findings"][0].get("weight").is_none());
+    Ok(())
+}
+
+#[test]
+fn check_leaves_out_light_items() -> TestResult {
+    let tmp = tempfile::tempdir()?;
+    common::write_crate(tmp.path(), "weights", "", FILES)?;
+    let json = check_json(tmp.path(), &["--min-weight", "2"])?;
+    let items: Vec<&str> = json["files"]
+        .as_array()
+        .ok_or("no files")?
+        .iter()
+        .flat_map(|f| f["findings"].as_array().into_iter().flatten())
+        .filter_map(|f| f["item"].as_str())
+        .collect();
+    assert_eq!(items, ["fn hot"]);
+    Ok(())
+}
+
+#[test]
+fn prune_tries_only_heavy_enough_items_and_weighs_removals() -> TestResult {
+    let tmp = tempfile::tempdir()?;
+    common::write_crate(tmp.path(), "weights", "", FILES)?;
+    let pruned = Winnower::builder()
+        .target(tmp.path())
+        .brute_force(true)
+        .validator(AlwaysOk)
+        .sort_by(SortBy::Weight)
+        .min_weight(1)
+        .build()?
+        .prune()?;
+
+    let scheduled: Vec<_> = pruned
+        .report
+        .schedule
+        .as_ref()
+        .ok_or("no schedule")?
+        .files
+        .iter()
+        .map(|f| f.path.clone())
+        .collect();
+    assert_eq!(scheduled[0], Path::new("src/b.rs"), "{scheduled:?}");
+    let removals: Vec<_> = pruned
+        .report
+        .files
+        .iter()
+        .flat_map(|f| &f.removals)
+        .map(|r| (r.finding.item.as_str(), r.finding.weight))
+        .collect();
+    assert_eq!(removals[0], ("fn hot", Some(3)));
+    assert!(removals.iter().all(|(item, _)| *item == "fn hot"));
+    assert_eq!(std::fs::read_to_string(tmp.path().join("src/a.rs"))?, COLD);
+    Ok(())
+}
@@ -0,0 +1,96 @@
+// tests/worktree_tests.rs
+//! `prune` runs its trials in a copy of the workspace: the real files change only when a
+//! removal is kept.
+#![cfg(feature = "discover")]
+
+mod common;
+
+use common::TestResult;
+use std::path::Path;
+use std::time::{Duration, SystemTime};
+use trait_winnower::config::Config;
+use trait_winnower::dynamic_analysis::worktree::WORKTREE_DIR;
+use trait_winnower::report::RemovalStatus;
+use trait_winnower::winnower::{PruneReport, Winnower};
+
+/// Every bound in `needed.rs` is used; the one in `unused.rs` is not.
+const NEEDED: &str = "pub fn g<T: Clone>(t: T) -> (T, T) {\n    (t.clone(), t)\n}\n";
+const UNUSED: &str = "pub fn f<T: Clone>(t: T) -> T {\n    t\n}\n";
+
+fn write_crate(dir: &Path) -> std::io::Result<()> {
+    common::write_crate(
+        dir,
+        "x",
+        "",
+        &[
+            ("src/lib.rs", "pub mod needed;\npub mod unused;\n"),
+            ("src/needed.rs", NEEDED),
+            ("src/unused.rs", UNUSED),
+        ],
+    )?;
+    // An hour back, so any write shows.
+    let past = SystemTime::now() - Duration::from_secs(3600);
+    for file in ["src/needed.rs", "src/unused.rs"] {
+        std::fs::File::options()
+            .write(true)
+            .open(dir.join(file))?
+            .set_modified(past)?;
+    }
+    Ok(())
+}
+
+fn modified(path: &Path) -> std::io::Result<SystemTime> {
+    std::fs::metadata(path)?.modified()
+}
+
+fn prune(dir: &Path, worktree: bool) -> Result<PruneReport, Box<dyn std::error::Error>> {
+    Ok(Winnower::builder()
+        .target(dir)
+        .config(Config {
+            worktree,
+            // The bound in `needed.rs` has to reach a trial to be rejected.
+            prefilter: false,
+            ..Config::default()
+        })
+        .brute_force(true)
+        .build()?
+        .prune()?)
+}
+
+#[test]
+fn rejected_trials_never_touch_the_real_file() -> TestResult {
+    let tmp = tempfile::tempdir()?;
+    let root = tmp.path();
+    write_crate(root)?;
+    let needed = root.join("src/needed.rs");
+    let before = modified(&needed)?;
+
+    let pruned = prune(root, true)?;
+    assert_eq!(pruned.report.count_removals(RemovalStatus::Removed), 1);
+    assert_eq!(pruned.report.count_removals(RemovalStatus::Retained), 1);
+    assert_eq!(std::fs::read_to_string(&needed)?, NEEDED);
+    assert_eq!(
+        modified(&needed)?,
+        before,
+        "a rejected trial was written in place"
+    );
+    assert_ne!(std::fs::read_to_string(root.join("src/unused.rs"))?, UNUSED);
+    assert!(!root.join(WORKTREE_DIR).exists());
+    Ok(())
+}
+
+#[test]
+fn without_it_trials_are_written_in_place() -> TestResult {
+    let tmp = tempfile::tempdir()?;
+    let root = tmp.path();
+    write_crate(root)?;
+    let needed = root.join("src/needed.rs");
+    let before = modified(&needed)?;
+
+    let pruned = prune(root, false)?;
+    assert_eq!(pruned.report.count_removals(RemovalStatus::Removed), 1);
+    assert_eq!(std::fs::read_to_string(&needed)?, NEEDED);
+    assert_ne!(modified(&needed)?, before);
+    assert_ne!(std::fs::read_to_string(root.join("src/unused.rs"))?, UNUSED);
+    Ok(())
+}